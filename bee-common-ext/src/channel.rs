@@ -0,0 +1,257 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+//! Flume channels instrumented with a gauge of the number of messages waiting in them.
+//!
+//! Every channel created by this module registers its gauge in a process wide registry, listed by [`depths`], so that
+//! a worker falling behind can be spotted from its queue. Keeping a gauge up to date costs one atomic operation on send
+//! and one on receive; without the `channel-metrics` feature, it costs nothing and no gauge is registered.
+
+use futures::{
+    stream::Stream,
+    task::{Context, Poll},
+};
+use pin_project::pin_project;
+
+use std::{
+    collections::HashMap,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Weak,
+    },
+    time::{Duration, Instant},
+};
+
+pub use flume::{RecvError, SendError, TryRecvError};
+
+static REGISTRY: spin::Mutex<Vec<Weak<Gauge>>> = spin::Mutex::new(Vec::new());
+static GAUGE_ID: AtomicUsize = AtomicUsize::new(0);
+
+struct Gauge {
+    id: usize,
+    name: &'static str,
+    depth: AtomicUsize,
+    bound: Option<usize>,
+}
+
+impl Gauge {
+    fn register(name: &'static str, bound: Option<usize>) -> Arc<Self> {
+        let gauge = Arc::new(Self {
+            id: GAUGE_ID.fetch_add(1, Ordering::Relaxed),
+            name,
+            depth: AtomicUsize::new(0),
+            bound,
+        });
+
+        #[cfg(feature = "channel-metrics")]
+        {
+            let mut registry = REGISTRY.lock();
+            registry.retain(|gauge| gauge.strong_count() > 0);
+            registry.push(Arc::downgrade(&gauge));
+        }
+
+        gauge
+    }
+
+    #[inline]
+    fn inc(&self) {
+        #[cfg(feature = "channel-metrics")]
+        self.depth.fetch_add(1, Ordering::Relaxed);
+    }
+
+    #[inline]
+    fn dec(&self) {
+        #[cfg(feature = "channel-metrics")]
+        self.depth.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    fn depth(&self) -> ChannelDepth {
+        ChannelDepth {
+            id: self.id,
+            name: self.name,
+            depth: self.depth.load(Ordering::Relaxed),
+            bound: self.bound,
+        }
+    }
+}
+
+/// Number of messages waiting in a channel at some point in time.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ChannelDepth {
+    /// Identifies the channel; unlike its name, which several channels may share, e.g. one per peer.
+    pub id: usize,
+    pub name: &'static str,
+    pub depth: usize,
+    /// Capacity of the channel, `None` if it is unbounded.
+    pub bound: Option<usize>,
+}
+
+/// Returns the depth of every live instrumented channel.
+pub fn depths() -> Vec<ChannelDepth> {
+    REGISTRY
+        .lock()
+        .iter()
+        .filter_map(Weak::upgrade)
+        .map(|gauge| gauge.depth())
+        .collect()
+}
+
+/// Creates an unbounded channel whose depth is reported under `name`.
+pub fn unbounded<T>(name: &'static str) -> (Sender<T>, Receiver<T>) {
+    let (tx, rx) = flume::unbounded();
+
+    wrap(tx, rx, Gauge::register(name, None))
+}
+
+/// Creates a channel of capacity `bound` whose depth is reported under `name`.
+pub fn bounded<T>(name: &'static str, bound: usize) -> (Sender<T>, Receiver<T>) {
+    let (tx, rx) = flume::bounded(bound);
+
+    wrap(tx, rx, Gauge::register(name, Some(bound)))
+}
+
+fn wrap<T>(tx: flume::Sender<T>, rx: flume::Receiver<T>, gauge: Arc<Gauge>) -> (Sender<T>, Receiver<T>) {
+    (
+        Sender {
+            inner: tx,
+            gauge: gauge.clone(),
+        },
+        Receiver { inner: rx, gauge },
+    )
+}
+
+pub struct Sender<T> {
+    inner: flume::Sender<T>,
+    gauge: Arc<Gauge>,
+}
+
+impl<T> Sender<T> {
+    pub fn send(&self, msg: T) -> Result<(), SendError<T>> {
+        // Counted before sending so that the receiver never decrements a message that was not counted yet.
+        self.gauge.inc();
+        self.inner.send(msg).map_err(|e| {
+            self.gauge.dec();
+            e
+        })
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            gauge: self.gauge.clone(),
+        }
+    }
+}
+
+pub struct Receiver<T> {
+    inner: flume::Receiver<T>,
+    gauge: Arc<Gauge>,
+}
+
+impl<T> Receiver<T> {
+    pub fn recv(&self) -> Result<T, RecvError> {
+        self.inner.recv().map(|msg| self.received(msg))
+    }
+
+    pub fn try_recv(&self) -> Result<T, TryRecvError> {
+        self.inner.try_recv().map(|msg| self.received(msg))
+    }
+
+    pub async fn recv_async(&self) -> Result<T, RecvError> {
+        self.inner.recv_async().await.map(|msg| self.received(msg))
+    }
+
+    pub fn into_stream(self) -> RecvStream<T>
+    where
+        T: 'static,
+    {
+        RecvStream {
+            inner: self.inner.into_stream(),
+            gauge: self.gauge,
+        }
+    }
+
+    fn received(&self, msg: T) -> T {
+        self.gauge.dec();
+        msg
+    }
+}
+
+#[pin_project]
+pub struct RecvStream<T: 'static> {
+    #[pin]
+    inner: flume::r#async::RecvStream<'static, T>,
+    gauge: Arc<Gauge>,
+}
+
+impl<T: 'static> Stream for RecvStream<T> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+        let poll = this.inner.poll_next(cx);
+
+        if let Poll::Ready(Some(_)) = poll {
+            this.gauge.dec();
+        }
+
+        poll
+    }
+}
+
+/// Reports the channels staying above a fraction of their bound for too long.
+pub struct DepthMonitor {
+    fraction: f64,
+    duration: Duration,
+    // Bound assumed for unbounded channels.
+    soft_bound: usize,
+    // When each channel above the threshold went above it, and whether it was already reported since.
+    above_since: HashMap<usize, (Instant, bool)>,
+}
+
+impl DepthMonitor {
+    pub fn new(fraction: f64, duration: Duration, soft_bound: usize) -> Self {
+        Self {
+            fraction,
+            duration,
+            soft_bound,
+            above_since: HashMap::new(),
+        }
+    }
+
+    /// Updates the monitor with the `depths` observed at `now` and returns the channels that have been above the
+    /// threshold for longer than the configured duration. A channel is only returned once until it gets back below
+    /// the threshold.
+    pub fn check(&mut self, depths: &[ChannelDepth], now: Instant) -> Vec<ChannelDepth> {
+        let mut reported = Vec::new();
+
+        for depth in depths {
+            let threshold = depth.bound.unwrap_or(self.soft_bound) as f64 * self.fraction;
+
+            if (depth.depth as f64) < threshold {
+                self.above_since.remove(&depth.id);
+                continue;
+            }
+
+            let (since, warned) = self.above_since.entry(depth.id).or_insert((now, false));
+
+            if !*warned && now.duration_since(*since) > self.duration {
+                *warned = true;
+                reported.push(*depth);
+            }
+        }
+
+        reported
+    }
+}
@@ -29,6 +29,39 @@ impl<'a> Bus<'a> {
         }
     }
 
+    /// Registers `handler` to be called on every dispatched event of type `E`.
+    ///
+    /// The handler must outlive the bus, so the listeners of a `Bus<'static>` may only capture owned or shared state:
+    ///
+    /// ```
+    /// use bee_common_ext::event::Bus;
+    ///
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// struct Foo(u64);
+    ///
+    /// let bus: Bus<'static> = Bus::default();
+    /// let values = Arc::new(Mutex::new(Vec::<u64>::new()));
+    /// let captured = values.clone();
+    ///
+    /// bus.add_listener(move |foo: &Foo| captured.lock().unwrap().push(foo.0));
+    /// bus.dispatch(Foo(42));
+    ///
+    /// assert_eq!(*values.lock().unwrap(), vec![42]);
+    /// ```
+    ///
+    /// Borrowing local state is rejected:
+    ///
+    /// ```compile_fail
+    /// use bee_common_ext::event::Bus;
+    ///
+    /// struct Foo(u64);
+    ///
+    /// let bus: Bus<'static> = Bus::default();
+    /// let values = Vec::<u64>::new();
+    ///
+    /// bus.add_listener(|foo: &Foo| println!("{} {}", foo.0, values.len()));
+    /// ```
     pub fn add_listener<E: Any>(&self, handler: impl Fn(&E) + Send + Sync + 'a) {
         self.listeners
             .entry(TypeId::of::<E>())
@@ -46,6 +79,8 @@ mod tests {
 
     struct Foo;
 
+    struct Bar;
+
     #[test]
     fn basic() {
         let bus = Bus::default();
@@ -55,12 +90,107 @@ mod tests {
         bus.dispatch(Foo);
     }
 
+    #[test]
+    fn listeners_keyed_by_type() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let foos = AtomicUsize::new(0);
+        let bars = AtomicUsize::new(0);
+        let bus = Bus::default();
+
+        bus.add_listener(|_: &Foo| {
+            foos.fetch_add(1, Ordering::SeqCst);
+        });
+        bus.add_listener(|_: &Bar| {
+            bars.fetch_add(1, Ordering::SeqCst);
+        });
+
+        bus.dispatch(Foo);
+        bus.dispatch(Foo);
+        bus.dispatch(Bar);
+
+        assert_eq!(foos.load(Ordering::SeqCst), 2);
+        assert_eq!(bars.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn versions_of_an_event_keyed_separately() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        mod v0 {
+            pub struct Handshake;
+        }
+
+        mod v1 {
+            pub struct Handshake(pub u8);
+        }
+
+        let v0s = AtomicUsize::new(0);
+        let v1s = AtomicUsize::new(0);
+        let bus = Bus::default();
+
+        bus.add_listener(|_: &v0::Handshake| {
+            v0s.fetch_add(1, Ordering::SeqCst);
+        });
+        bus.add_listener(|event: &v1::Handshake| {
+            v1s.fetch_add(event.0 as usize, Ordering::SeqCst);
+        });
+
+        bus.dispatch(v1::Handshake(1));
+
+        assert_eq!(v0s.load(Ordering::SeqCst), 0);
+        assert_eq!(v1s.load(Ordering::SeqCst), 1);
+    }
+
     #[test]
     fn send_sync() {
         fn helper<T: Send + Sync>() {}
         helper::<Bus<'static>>();
     }
 
+    #[test]
+    fn concurrent_dispatches() {
+        use std::sync::{
+            atomic::{AtomicU64, Ordering},
+            Arc,
+        };
+
+        const THREADS: u64 = 8;
+        const DISPATCHES: u64 = 1000;
+
+        let mut runtime = tokio::runtime::Builder::new()
+            .threaded_scheduler()
+            .core_threads(THREADS as usize)
+            .build()
+            .unwrap();
+        let bus = Arc::new(Bus::<'static>::default());
+        let count = Arc::new(AtomicU64::new(0));
+        let listener_count = count.clone();
+
+        bus.add_listener(move |_: &Foo| {
+            listener_count.fetch_add(1, Ordering::SeqCst);
+        });
+
+        runtime.block_on(async {
+            let handles = (0..THREADS)
+                .map(|_| {
+                    let bus = bus.clone();
+                    tokio::spawn(async move {
+                        for _ in 0..DISPATCHES {
+                            bus.dispatch(Foo);
+                        }
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            for handle in handles {
+                handle.await.unwrap();
+            }
+        });
+
+        assert_eq!(count.load(Ordering::SeqCst), THREADS * DISPATCHES);
+    }
+
     // #[bench]
     // fn bench_add_two(b: &mut Bencher) {
     //     let bus = Bus::default();
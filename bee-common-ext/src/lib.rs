@@ -12,9 +12,12 @@
 //! A crate that provides common functionalities shared across multiple crates within the Bee framework, and for
 //! applications built on-top.
 
+pub mod channel;
 pub mod event;
+pub mod log_throttle;
 pub mod node;
 pub mod packable;
+pub mod shutdown_stream;
 pub mod shutdown_tokio;
 pub mod wait_priority_queue;
 pub mod worker;
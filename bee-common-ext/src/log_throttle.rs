@@ -0,0 +1,286 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+//! Throttling of log lines repeated by peers, so that a misbehaving peer can't flood the log.
+//!
+//! Log lines are grouped by target, peer and kind of error. The first line of a group is logged as is, the following
+//! ones are only counted and summarized by a single line once per interval. The counts still pending when a peer
+//! disconnects are summarized by [`LogThrottle::flush`].
+
+use log::{log, log_enabled, Level};
+
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    fmt,
+    hash::Hash,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// Identifies a group of log lines.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Key<P> {
+    /// Target of the log lines, usually `module_path!()`.
+    pub target: &'static str,
+    /// Peer causing the log lines.
+    pub peer: P,
+    /// Kind of error reported by the log lines.
+    pub kind: &'static str,
+}
+
+/// What to do with an occurrence of a log line.
+#[derive(Debug, Eq, PartialEq)]
+pub enum Occurrence {
+    /// The occurrence is the first one of its group in a while and is logged as is.
+    First,
+    /// The occurrence is only counted.
+    Suppressed,
+    /// The occurrence ends an interval during which `repeated` occurrences, itself included, have been suppressed.
+    Summary { repeated: usize, elapsed: Duration },
+}
+
+struct Group {
+    level: Level,
+    since: Instant,
+    repeated: usize,
+}
+
+/// Throttles log lines grouped by [`Key`], with peers identified by `P`.
+pub struct LogThrottle<P> {
+    interval: Duration,
+    groups: Mutex<HashMap<Key<P>, Group>>,
+}
+
+impl<P: Clone + fmt::Display + Eq + Hash> LogThrottle<P> {
+    /// Creates a throttle summarizing repeated log lines once per `interval`.
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            groups: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Records an occurrence of a log line of the group `key` at `now`.
+    pub fn record_at(&self, key: Key<P>, level: Level, now: Instant) -> Occurrence {
+        let mut groups = self.groups.lock().unwrap();
+
+        let group = match groups.entry(key) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                entry.insert(Group {
+                    level,
+                    since: now,
+                    repeated: 0,
+                });
+                return Occurrence::First;
+            }
+        };
+
+        let elapsed = now.saturating_duration_since(group.since);
+
+        if elapsed < self.interval {
+            group.repeated += 1;
+            return Occurrence::Suppressed;
+        }
+
+        let repeated = group.repeated;
+        group.since = now;
+        group.repeated = 0;
+
+        if repeated == 0 {
+            Occurrence::First
+        } else {
+            Occurrence::Summary {
+                repeated: repeated + 1,
+                elapsed,
+            }
+        }
+    }
+
+    /// Removes the groups of `peer` at `now`, returning the pending counts of suppressed log lines.
+    pub fn flush_at(&self, peer: &P, now: Instant) -> Vec<(Key<P>, Level, usize, Duration)> {
+        let mut flushed = Vec::new();
+
+        self.groups.lock().unwrap().retain(|key, group| {
+            if key.peer != *peer {
+                return true;
+            }
+            if group.repeated > 0 {
+                flushed.push((
+                    key.clone(),
+                    group.level,
+                    group.repeated,
+                    now.saturating_duration_since(group.since),
+                ));
+            }
+            false
+        });
+
+        flushed
+    }
+
+    /// Logs `args` at `level`, unless it is a repetition of the group `(target, peer, kind)`.
+    pub fn log(&self, level: Level, target: &'static str, peer: &P, kind: &'static str, args: fmt::Arguments) {
+        // Throttling costs a lock, which is not worth it for disabled log lines.
+        if !log_enabled!(target: target, level) {
+            return;
+        }
+
+        let key = Key {
+            target,
+            peer: peer.clone(),
+            kind,
+        };
+
+        match self.record_at(key, level, Instant::now()) {
+            Occurrence::First => log!(target: target, level, "{}", args),
+            Occurrence::Suppressed => {}
+            Occurrence::Summary { repeated, elapsed } => log_summary(level, target, peer, kind, repeated, elapsed),
+        }
+    }
+
+    /// Logs the summaries still pending for `peer` and forgets about it, e.g. when it disconnects.
+    pub fn flush(&self, peer: &P) {
+        for (key, level, repeated, elapsed) in self.flush_at(peer, Instant::now()) {
+            log_summary(level, key.target, &key.peer, key.kind, repeated, elapsed);
+        }
+    }
+}
+
+fn log_summary<P: fmt::Display>(
+    level: Level,
+    target: &'static str,
+    peer: &P,
+    kind: &'static str,
+    repeated: usize,
+    elapsed: Duration,
+) {
+    log!(
+        target: target,
+        level,
+        "[{}] {}: repeated {} times in the last {}s.",
+        peer,
+        kind,
+        repeated,
+        elapsed.as_secs()
+    );
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    const INTERVAL: Duration = Duration::from_secs(10);
+
+    fn key(peer: u8, kind: &'static str) -> Key<u8> {
+        Key {
+            target: "test",
+            peer,
+            kind,
+        }
+    }
+
+    fn secs(start: Instant, secs: u64) -> Instant {
+        start + Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn first_then_summarize() {
+        let throttle = LogThrottle::new(INTERVAL);
+        let start = Instant::now();
+
+        assert_eq!(throttle.record_at(key(0, "a"), Level::Warn, start), Occurrence::First);
+        for i in 1..10 {
+            assert_eq!(
+                throttle.record_at(key(0, "a"), Level::Warn, secs(start, i)),
+                Occurrence::Suppressed
+            );
+        }
+        assert_eq!(
+            throttle.record_at(key(0, "a"), Level::Warn, secs(start, 10)),
+            Occurrence::Summary {
+                repeated: 10,
+                elapsed: INTERVAL
+            }
+        );
+        assert_eq!(
+            throttle.record_at(key(0, "a"), Level::Warn, secs(start, 11)),
+            Occurrence::Suppressed
+        );
+    }
+
+    #[test]
+    fn quiet_interval_logs_again() {
+        let throttle = LogThrottle::new(INTERVAL);
+        let start = Instant::now();
+
+        assert_eq!(throttle.record_at(key(0, "a"), Level::Warn, start), Occurrence::First);
+        assert_eq!(
+            throttle.record_at(key(0, "a"), Level::Warn, secs(start, 30)),
+            Occurrence::First
+        );
+    }
+
+    #[test]
+    fn keys_are_independent() {
+        let throttle = LogThrottle::new(INTERVAL);
+        let start = Instant::now();
+
+        assert_eq!(throttle.record_at(key(0, "a"), Level::Warn, start), Occurrence::First);
+        assert_eq!(throttle.record_at(key(0, "b"), Level::Warn, start), Occurrence::First);
+        assert_eq!(throttle.record_at(key(1, "a"), Level::Warn, start), Occurrence::First);
+        assert_eq!(
+            throttle.record_at(
+                Key {
+                    target: "other",
+                    peer: 0,
+                    kind: "a"
+                },
+                Level::Warn,
+                start
+            ),
+            Occurrence::First
+        );
+        assert_eq!(
+            throttle.record_at(key(0, "a"), Level::Warn, start),
+            Occurrence::Suppressed
+        );
+    }
+
+    #[test]
+    fn flush_returns_pending_counts() {
+        let throttle = LogThrottle::new(INTERVAL);
+        let start = Instant::now();
+
+        throttle.record_at(key(0, "a"), Level::Warn, start);
+        throttle.record_at(key(0, "a"), Level::Warn, secs(start, 1));
+        throttle.record_at(key(0, "a"), Level::Warn, secs(start, 2));
+        throttle.record_at(key(0, "b"), Level::Trace, start);
+        throttle.record_at(key(1, "a"), Level::Warn, start);
+        throttle.record_at(key(1, "a"), Level::Warn, start);
+
+        assert_eq!(
+            throttle.flush_at(&0, secs(start, 5)),
+            vec![(key(0, "a"), Level::Warn, 2, Duration::from_secs(5))]
+        );
+        assert!(throttle.flush_at(&0, secs(start, 5)).is_empty());
+        // The flushed peer starts over, the others are untouched.
+        assert_eq!(
+            throttle.record_at(key(0, "a"), Level::Warn, secs(start, 6)),
+            Occurrence::First
+        );
+        assert_eq!(
+            throttle.flush_at(&1, secs(start, 6)),
+            vec![(key(1, "a"), Level::Warn, 1, Duration::from_secs(6))]
+        );
+    }
+}
@@ -17,14 +17,15 @@ use bee_storage::storage::Backend;
 use async_trait::async_trait;
 use futures::{channel::oneshot, future::Future};
 use log::warn;
+use thiserror::Error;
 
 use std::{
-    any::{type_name, Any},
-    collections::HashMap,
+    any::{type_name, Any, TypeId},
+    collections::{HashMap, HashSet},
     ops::Deref,
     panic::Location,
     sync::{
-        atomic::{AtomicUsize, Ordering},
+        atomic::{AtomicBool, AtomicUsize, Ordering},
         Arc, Mutex,
     },
 };
@@ -34,8 +35,9 @@ pub trait Node: Send + Sized + 'static {
     type Builder: NodeBuilder<Self>;
     type Backend: Backend;
 
+    /// Starts building a node, every node gets a `ShutdownFlag` resource.
     fn build() -> Self::Builder {
-        Self::Builder::default()
+        Self::Builder::default().with_resource(ShutdownFlag::default())
     }
 
     async fn stop(mut self) -> Result<(), shutdown::Error>
@@ -74,9 +76,112 @@ pub trait NodeBuilder<N: Node>: Default {
 
     fn with_worker_cfg<W: Worker<N> + 'static>(self, config: W::Config) -> Self;
 
+    /// Registers `res` on the node before any of its workers starts.
+    fn with_resource<R: Any + Send + Sync>(self, res: R) -> Self;
+
     async fn finish(self) -> N;
 }
 
+#[derive(Debug, Error, PartialEq)]
+pub enum DependencyError {
+    #[error("Cyclic dependency detected involving worker {0:?}.")]
+    Cycle(TypeId),
+    #[error("Worker {worker:?} depends on worker {dependency:?} which is not registered.")]
+    Missing { worker: TypeId, dependency: TypeId },
+}
+
+/// Validates the worker dependency graph, as given by `Worker::dependencies()` for every registered worker, and returns
+/// an order in which workers can be started so that each of them starts after its dependencies.
+pub fn validate_worker_order(
+    dependencies: &HashMap<TypeId, &'static [TypeId]>,
+) -> Result<Vec<TypeId>, DependencyError> {
+    fn visit(
+        id: TypeId,
+        dependencies: &HashMap<TypeId, &'static [TypeId]>,
+        being_visited: &mut HashSet<TypeId>,
+        order: &mut Vec<TypeId>,
+    ) -> Result<(), DependencyError> {
+        if order.contains(&id) {
+            return Ok(());
+        }
+
+        if !being_visited.insert(id) {
+            return Err(DependencyError::Cycle(id));
+        }
+
+        for &dependency in dependencies[&id] {
+            if !dependencies.contains_key(&dependency) {
+                return Err(DependencyError::Missing { worker: id, dependency });
+            }
+            visit(dependency, dependencies, being_visited, order)?;
+        }
+
+        being_visited.remove(&id);
+        order.push(id);
+
+        Ok(())
+    }
+
+    let mut being_visited = HashSet::new();
+    let mut order = Vec::with_capacity(dependencies.len());
+
+    for &id in dependencies.keys() {
+        visit(id, dependencies, &mut being_visited, &mut order)?;
+    }
+
+    Ok(order)
+}
+
+/// Extends the dependencies of every registered worker with its optional dependencies that are registered as well, so
+/// that the result can be given to `validate_worker_order`.
+pub fn resolve_optional_dependencies(
+    dependencies: &HashMap<TypeId, &'static [TypeId]>,
+    optional_dependencies: &HashMap<TypeId, &'static [TypeId]>,
+) -> HashMap<TypeId, &'static [TypeId]> {
+    dependencies
+        .iter()
+        .map(|(&id, &required)| {
+            let registered = optional_dependencies
+                .get(&id)
+                .map_or(&[][..], |optional| *optional)
+                .iter()
+                .filter(|dependency| dependencies.contains_key(dependency))
+                .copied()
+                .collect::<Vec<_>>();
+
+            if registered.is_empty() {
+                (id, required)
+            } else {
+                let all = required.iter().copied().chain(registered).collect::<Box<[TypeId]>>();
+                (id, &*Box::leak(all))
+            }
+        })
+        .collect()
+}
+
+/// Node resource set as the very first step of the node shutdown, before any worker is asked to stop. Long-running
+/// computations check it from time to time to stop early instead of holding the shutdown up until they complete.
+#[derive(Default)]
+pub struct ShutdownFlag(AtomicBool);
+
+impl ShutdownFlag {
+    pub fn set(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_set(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+impl Deref for ShutdownFlag {
+    type Target = AtomicBool;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
 static RES_ID: AtomicUsize = AtomicUsize::new(0);
 
 pub struct ResHandle<R> {
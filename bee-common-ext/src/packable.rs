@@ -27,6 +27,23 @@ pub enum Error {
     InvalidType,
     #[error("Invalid announced len.")]
     InvalidAnnouncedLen,
+    #[error("Invalid amount read.")]
+    InvalidAmount,
+}
+
+/// Reads `len` bytes from `buf`.
+///
+/// The buffer grows with the bytes actually read instead of being allocated upfront, so that a bogus announced length
+/// fails on the missing bytes instead of exhausting memory.
+pub fn read_bytes<R: Read>(buf: &mut R, len: usize) -> Result<Vec<u8>, Error> {
+    let mut bytes = Vec::new();
+    buf.by_ref().take(len as u64).read_to_end(&mut bytes)?;
+
+    if bytes.len() != len {
+        return Err(Error::Io(std::io::ErrorKind::UnexpectedEof.into()));
+    }
+
+    Ok(bytes)
 }
 
 pub trait Packable {
@@ -0,0 +1,104 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+//! A stream that ends when a shutdown signal is received.
+
+use futures::{
+    channel::oneshot,
+    future::FutureExt,
+    stream::{Fuse, FusedStream, Stream, StreamExt},
+    task::{Context, Poll},
+};
+use pin_project::pin_project;
+
+use std::pin::Pin;
+
+#[derive(Clone, Copy, PartialEq)]
+enum State {
+    Running,
+    Draining,
+    Terminated,
+}
+
+/// Wraps a stream and ends it when the shutdown signal is received.
+///
+/// An immediate stream, created with `new`, ends as soon as the signal is received and drops the items still pending in
+/// the inner stream. A graceful stream, created with `graceful`, keeps yielding the items that are ready in the inner
+/// stream and only ends once it is exhausted or would have to wait for new items, so that a worker can process what it
+/// was sent before stopping.
+#[pin_project]
+pub struct ShutdownStream<S> {
+    shutdown: oneshot::Receiver<()>,
+    #[pin]
+    stream: S,
+    graceful: bool,
+    state: State,
+}
+
+impl<S: Stream> ShutdownStream<Fuse<S>> {
+    /// Creates a stream ending immediately on shutdown.
+    pub fn new(shutdown: oneshot::Receiver<()>, stream: S) -> Self {
+        Self {
+            shutdown,
+            stream: stream.fuse(),
+            graceful: false,
+            state: State::Running,
+        }
+    }
+
+    /// Creates a stream draining the ready items of `stream` on shutdown before ending.
+    pub fn graceful(shutdown: oneshot::Receiver<()>, stream: S) -> Self {
+        Self {
+            graceful: true,
+            ..Self::new(shutdown, stream)
+        }
+    }
+}
+
+impl<S: Stream> Stream for ShutdownStream<S> {
+    type Item = S::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        if *this.state == State::Running {
+            // A dropped sender is treated as a shutdown signal as well.
+            if this.shutdown.poll_unpin(cx).is_ready() {
+                *this.state = if *this.graceful {
+                    State::Draining
+                } else {
+                    State::Terminated
+                };
+            } else {
+                let item = this.stream.as_mut().poll_next(cx);
+                if let Poll::Ready(None) = item {
+                    *this.state = State::Terminated;
+                }
+                return item;
+            }
+        }
+
+        if *this.state == State::Draining {
+            if let Poll::Ready(Some(item)) = this.stream.as_mut().poll_next(cx) {
+                return Poll::Ready(Some(item));
+            }
+            *this.state = State::Terminated;
+        }
+
+        Poll::Ready(None)
+    }
+}
+
+impl<S: Stream> FusedStream for ShutdownStream<S> {
+    fn is_terminated(&self) -> bool {
+        self.state == State::Terminated
+    }
+}
@@ -13,35 +13,94 @@ use futures::{
     future::FusedFuture,
     stream::{FusedStream, Stream},
 };
+use thiserror::Error;
+
 use std::{
     collections::{BinaryHeap, VecDeque},
     future::Future,
     pin::Pin,
-    sync::Mutex,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
     task::{Context, Poll, Waker},
 };
 
+/// Error returned by [`WaitPriorityQueue::try_insert`] when a bounded queue has no room left.
+#[derive(Debug, Error, PartialEq)]
+#[error("Queue is full.")]
+pub struct QueueFull;
+
+// Items, wakers of waiting consumers, wakers of producers waiting for room keyed by producer.
+type Inner<T> = (BinaryHeap<T>, VecDeque<Waker>, VecDeque<(usize, Waker)>);
+
 pub struct WaitPriorityQueue<T: Ord + Eq> {
-    inner: Mutex<(BinaryHeap<T>, VecDeque<Waker>)>,
+    inner: Mutex<Inner<T>>,
+    capacity: Option<usize>,
+    // Key of the next producer to wait for room.
+    next_producer: AtomicUsize,
 }
 
 impl<T: Ord + Eq> WaitPriorityQueue<T> {
+    /// Creates a queue that holds at most `capacity` items, producers going through [`insert`] wait for room once
+    /// it is full.
+    ///
+    /// [`insert`]: WaitPriorityQueue::insert
+    pub fn bounded(capacity: usize) -> Self {
+        Self {
+            inner: Mutex::new((BinaryHeap::with_capacity(capacity), VecDeque::new(), VecDeque::new())),
+            capacity: Some(capacity),
+            next_producer: AtomicUsize::new(0),
+        }
+    }
+
     pub fn is_empty(&self) -> bool {
         self.inner.lock().unwrap().0.is_empty()
     }
+
+    pub fn len(&self) -> usize {
+        self.inner.lock().unwrap().0.len()
+    }
+
+    pub fn capacity(&self) -> Option<usize> {
+        self.capacity
+    }
+
+    fn is_full(&self, len: usize) -> bool {
+        self.capacity.map_or(false, |capacity| len >= capacity)
+    }
 }
 
 impl<T: Ord + Eq> Default for WaitPriorityQueue<T> {
     fn default() -> Self {
         Self {
-            inner: Mutex::new((BinaryHeap::new(), VecDeque::new())),
+            inner: Mutex::new((BinaryHeap::new(), VecDeque::new(), VecDeque::new())),
+            capacity: None,
+            next_producer: AtomicUsize::new(0),
+        }
+    }
+}
+
+// Pops the item with the highest priority and lets a waiting producer know there is room again.
+fn pop_inner<T: Ord + Eq>(inner: &mut Inner<T>) -> Option<T> {
+    let entry = inner.0.pop();
+
+    if entry.is_some() {
+        if let Some((_, a)) = inner.2.pop_front() {
+            Waker::wake(a)
         }
     }
+
+    entry
 }
 
 impl<T: Ord + Eq> WaitPriorityQueue<T> {
     /// Pushes an item into the queue. It will be removed in an order consistent with the ordering
     /// of itself relative to other items in the queue at the time of removal.
+    /// This ignores the capacity of a bounded queue, use [`insert`] or [`try_insert`] to respect it.
+    ///
+    /// [`insert`]: WaitPriorityQueue::insert
+    /// [`try_insert`]: WaitPriorityQueue::try_insert
     pub fn push(&self, entry: T) {
         let mut inner = self.inner.lock().unwrap();
 
@@ -52,10 +111,37 @@ impl<T: Ord + Eq> WaitPriorityQueue<T> {
         }
     }
 
+    /// Inserts an item into the queue, waiting for room should the queue be bounded and full.
+    pub fn insert(&self, entry: T) -> impl Future<Output = ()> + FusedFuture + '_ {
+        InsertFut {
+            queue: self,
+            entry: Some(entry),
+            key: None,
+        }
+    }
+
+    /// Attempts to insert an item into the queue, returning [`QueueFull`] and dropping the item if a bounded queue
+    /// has no room left.
+    pub fn try_insert(&self, entry: T) -> Result<(), QueueFull> {
+        let mut inner = self.inner.lock().unwrap();
+
+        if self.is_full(inner.0.len()) {
+            return Err(QueueFull);
+        }
+
+        inner.0.push(entry);
+
+        if let Some(a) = inner.1.pop_front() {
+            Waker::wake(a)
+        }
+
+        Ok(())
+    }
+
     /// Attempts to remove the item with the highest priority from the queue, returning [`None`] if
     /// there are no available items.
     pub fn try_pop(&self) -> Option<T> {
-        self.inner.lock().unwrap().0.pop()
+        pop_inner(&mut self.inner.lock().unwrap())
     }
 
     /// Removes the item with the highest priority from the queue, waiting on an item should there
@@ -75,7 +161,94 @@ impl<T: Ord + Eq> WaitPriorityQueue<T> {
     /// Returns an iterator of pending items from this queue (i.e: those that are immediately
     /// available).
     pub fn pending(&self) -> impl Iterator<Item = T> + '_ {
-        std::iter::from_fn(move || self.inner.lock().unwrap().0.pop())
+        std::iter::from_fn(move || pop_inner(&mut self.inner.lock().unwrap()))
+    }
+}
+
+// A producer waiting for room is registered once under its key, polling it again only updates its waker.
+pub(crate) struct InsertFut<'a, T: Ord + Eq> {
+    queue: &'a WaitPriorityQueue<T>,
+    entry: Option<T>,
+    key: Option<usize>,
+}
+
+// The entry is only moved out, never pinned.
+impl<'a, T: Ord + Eq> Unpin for InsertFut<'a, T> {}
+
+impl<'a, T: Ord + Eq> Future for InsertFut<'a, T> {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let queue = self.queue;
+        let mut inner = queue.inner.lock().unwrap();
+
+        let entry = match self.entry.take() {
+            Some(entry) => entry,
+            None => return Poll::Pending,
+        };
+
+        if queue.is_full(inner.0.len()) {
+            let key = self.key;
+
+            match key.and_then(|key| inner.2.iter_mut().find(|(waiter, _)| *waiter == key)) {
+                Some((_, waker)) => {
+                    if !waker.will_wake(cx.waker()) {
+                        *waker = cx.waker().clone();
+                    }
+                }
+                // Either first time waiting, or woken up for room that another producer took.
+                None => {
+                    let key = key.unwrap_or_else(|| queue.next_producer.fetch_add(1, Ordering::Relaxed));
+                    inner.2.push_back((key, cx.waker().clone()));
+                    self.key = Some(key);
+                }
+            }
+
+            self.entry = Some(entry);
+
+            return Poll::Pending;
+        }
+
+        if let Some(key) = self.key.take() {
+            inner.2.retain(|(waiter, _)| *waiter != key);
+        }
+
+        inner.0.push(entry);
+
+        if let Some(a) = inner.1.pop_front() {
+            Waker::wake(a)
+        }
+
+        Poll::Ready(())
+    }
+}
+
+impl<'a, T: Ord + Eq> FusedFuture for InsertFut<'a, T> {
+    fn is_terminated(&self) -> bool {
+        self.entry.is_none()
+    }
+}
+
+impl<'a, T: Ord + Eq> Drop for InsertFut<'a, T> {
+    fn drop(&mut self) {
+        let key = match self.key {
+            Some(key) => key,
+            None => return,
+        };
+        let mut inner = match self.queue.inner.lock() {
+            Ok(inner) => inner,
+            Err(_) => return,
+        };
+        let waiting = inner.2.len();
+
+        inner.2.retain(|(waiter, _)| *waiter != key);
+
+        // A producer cancelled after being woken up passes the room it won't take on to the next one.
+        if inner.2.len() == waiting && !self.queue.is_full(inner.0.len()) {
+            if let Some((_, a)) = inner.2.pop_front() {
+                Waker::wake(a)
+            }
+        }
     }
 }
 
@@ -90,7 +263,7 @@ impl<'a, T: Ord + Eq> Future for WaitFut<'a, T> {
     fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
         let mut inner = self.queue.inner.lock().unwrap();
 
-        match inner.0.pop() {
+        match pop_inner(&mut inner) {
             _ if self.terminated => Poll::Pending,
             Some(entry) => {
                 self.terminated = true;
@@ -120,7 +293,7 @@ impl<'a, T: Ord + Eq> Stream for WaitIncoming<'a, T> {
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
         let mut inner = self.queue.inner.lock().unwrap();
 
-        match inner.0.pop() {
+        match pop_inner(&mut inner) {
             Some(entry) => Poll::Ready(Some(entry)),
             None => {
                 inner.1.push_back(cx.waker().clone());
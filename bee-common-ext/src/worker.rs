@@ -25,6 +25,11 @@ pub trait Worker<N: Node>: Any + Send + Sync + Sized {
         &[]
     }
 
+    /// Workers started before this one if they are registered, this one being able to run without them.
+    fn optional_dependencies() -> &'static [TypeId] {
+        &[]
+    }
+
     async fn start(node: &mut N, config: Self::Config) -> Result<Self, Self::Error>
     where
         Self: Sized;
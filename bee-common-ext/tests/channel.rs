@@ -0,0 +1,180 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use bee_common_ext::channel::{ChannelDepth, DepthMonitor};
+
+use std::time::{Duration, Instant};
+
+// The feature keeping the gauges up to date is enabled for the tests through the dev-dependency on this crate.
+mod gauge {
+
+    use bee_common_ext::channel;
+
+    use futures::{executor::block_on, StreamExt};
+
+    fn depth(name: &str) -> Option<usize> {
+        channel::depths()
+            .iter()
+            .find(|depth| depth.name == name)
+            .map(|depth| depth.depth)
+    }
+
+    #[test]
+    fn gauge_tracks_depth() {
+        let (tx, rx) = channel::bounded("gauge_tracks_depth", 10);
+
+        for i in 0..10 {
+            tx.send(i).unwrap();
+        }
+        assert_eq!(depth("gauge_tracks_depth"), Some(10));
+
+        for _ in 0..4 {
+            rx.recv().unwrap();
+        }
+        assert_eq!(depth("gauge_tracks_depth"), Some(6));
+
+        while rx.try_recv().is_ok() {}
+        assert_eq!(depth("gauge_tracks_depth"), Some(0));
+
+        drop(rx);
+        assert!(tx.send(0).is_err());
+        assert_eq!(depth("gauge_tracks_depth"), Some(0));
+
+        drop(tx);
+        assert_eq!(depth("gauge_tracks_depth"), None);
+    }
+
+    #[test]
+    fn gauge_tracks_stream_depth() {
+        let (tx, rx) = channel::unbounded("gauge_tracks_stream_depth");
+        let mut stream = rx.into_stream();
+
+        for i in 0..5 {
+            tx.send(i).unwrap();
+        }
+        assert_eq!(depth("gauge_tracks_stream_depth"), Some(5));
+
+        assert_eq!(block_on(stream.next()), Some(0));
+        assert_eq!(block_on(stream.next()), Some(1));
+        assert_eq!(depth("gauge_tracks_stream_depth"), Some(3));
+    }
+
+    #[test]
+    fn gauges_sharing_a_name() {
+        let (tx1, _rx1) = channel::unbounded("gauges_sharing_a_name");
+        let (tx2, _rx2) = channel::unbounded("gauges_sharing_a_name");
+
+        tx1.send(()).unwrap();
+        tx1.send(()).unwrap();
+        tx2.send(()).unwrap();
+
+        let depths = channel::depths()
+            .into_iter()
+            .filter(|depth| depth.name == "gauges_sharing_a_name")
+            .collect::<Vec<_>>();
+
+        assert_eq!(depths.len(), 2);
+        assert_ne!(depths[0].id, depths[1].id);
+        assert_eq!(depths.iter().map(|depth| depth.depth).sum::<usize>(), 3);
+    }
+}
+
+fn depths(depth: usize) -> [ChannelDepth; 2] {
+    [
+        ChannelDepth {
+            id: 0,
+            name: "bounded",
+            depth,
+            bound: Some(10),
+        },
+        ChannelDepth {
+            id: 1,
+            name: "unbounded",
+            depth,
+            bound: None,
+        },
+    ]
+}
+
+#[test]
+fn warning_after_duration() {
+    let start = Instant::now();
+    let mut monitor = DepthMonitor::new(0.8, Duration::from_secs(10), 100);
+
+    // Above the threshold of the bounded channel only.
+    assert!(monitor.check(&depths(8), start).is_empty());
+    assert!(monitor.check(&depths(9), start + Duration::from_secs(10)).is_empty());
+
+    let warnings = monitor.check(&depths(9), start + Duration::from_secs(11));
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].name, "bounded");
+
+    // Reported once per episode.
+    assert!(monitor.check(&depths(9), start + Duration::from_secs(12)).is_empty());
+}
+
+#[test]
+fn warning_resets_below_threshold() {
+    let start = Instant::now();
+    let mut monitor = DepthMonitor::new(0.8, Duration::from_secs(10), 100);
+
+    assert!(monitor.check(&depths(8), start).is_empty());
+    assert!(monitor.check(&depths(7), start + Duration::from_secs(5)).is_empty());
+    assert!(monitor.check(&depths(8), start + Duration::from_secs(6)).is_empty());
+    assert!(monitor.check(&depths(8), start + Duration::from_secs(16)).is_empty());
+    assert_eq!(monitor.check(&depths(8), start + Duration::from_secs(17)).len(), 1);
+
+    assert!(monitor.check(&depths(0), start + Duration::from_secs(18)).is_empty());
+    assert!(monitor.check(&depths(8), start + Duration::from_secs(19)).is_empty());
+    assert_eq!(monitor.check(&depths(8), start + Duration::from_secs(30)).len(), 1);
+}
+
+#[test]
+fn unbounded_channels_use_soft_bound() {
+    let start = Instant::now();
+    let mut monitor = DepthMonitor::new(0.5, Duration::from_secs(1), 100);
+
+    assert!(monitor.check(&depths(50), start).is_empty());
+
+    let warnings = monitor.check(&depths(50), start + Duration::from_secs(2));
+    assert_eq!(warnings.len(), 2);
+    assert!(warnings.iter().any(|warning| warning.name == "unbounded"));
+}
+
+#[test]
+fn channels_sharing_a_name_tracked_apart() {
+    let start = Instant::now();
+    let mut monitor = DepthMonitor::new(0.8, Duration::from_secs(10), 100);
+    let depths = |first, second| {
+        [
+            ChannelDepth {
+                id: 0,
+                name: "shared",
+                depth: first,
+                bound: Some(10),
+            },
+            ChannelDepth {
+                id: 1,
+                name: "shared",
+                depth: second,
+                bound: Some(10),
+            },
+        ]
+    };
+
+    // The second channel getting back below the threshold doesn't reset the first one.
+    assert!(monitor.check(&depths(9, 9), start).is_empty());
+    assert!(monitor.check(&depths(9, 0), start + Duration::from_secs(5)).is_empty());
+
+    let warnings = monitor.check(&depths(9, 0), start + Duration::from_secs(11));
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].id, 0);
+}
@@ -0,0 +1,100 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use bee_common_ext::node::{resolve_optional_dependencies, validate_worker_order, DependencyError};
+
+use std::{any::TypeId, collections::HashMap};
+
+struct A;
+struct B;
+struct C;
+struct D;
+
+fn deps(ids: Vec<TypeId>) -> &'static [TypeId] {
+    Box::leak(Box::from(ids))
+}
+
+#[test]
+fn dependencies_start_first() {
+    let mut graph = HashMap::new();
+    graph.insert(TypeId::of::<A>(), deps(vec![TypeId::of::<B>(), TypeId::of::<C>()]));
+    graph.insert(TypeId::of::<B>(), deps(vec![TypeId::of::<C>()]));
+    graph.insert(TypeId::of::<C>(), deps(vec![]));
+    graph.insert(TypeId::of::<D>(), deps(vec![TypeId::of::<A>()]));
+
+    let order = validate_worker_order(&graph).unwrap();
+    let position = |id| order.iter().position(|&other| other == id).unwrap();
+
+    assert_eq!(order.len(), 4);
+    for (id, dependencies) in graph.iter() {
+        for dependency in dependencies.iter() {
+            assert!(position(*dependency) < position(*id));
+        }
+    }
+}
+
+#[test]
+fn cycle() {
+    let mut graph = HashMap::new();
+    graph.insert(TypeId::of::<A>(), deps(vec![TypeId::of::<B>()]));
+    graph.insert(TypeId::of::<B>(), deps(vec![TypeId::of::<C>()]));
+    graph.insert(TypeId::of::<C>(), deps(vec![TypeId::of::<A>()]));
+
+    match validate_worker_order(&graph) {
+        Err(DependencyError::Cycle(id)) => assert!(graph.contains_key(&id)),
+        other => panic!("Expected a cycle, got {:?}.", other),
+    }
+}
+
+#[test]
+fn missing_dependency() {
+    let mut graph = HashMap::new();
+    graph.insert(TypeId::of::<A>(), deps(vec![TypeId::of::<B>()]));
+
+    assert_eq!(
+        validate_worker_order(&graph),
+        Err(DependencyError::Missing {
+            worker: TypeId::of::<A>(),
+            dependency: TypeId::of::<B>(),
+        })
+    );
+}
+
+#[test]
+fn registered_optional_dependency_starts_first() {
+    let mut graph = HashMap::new();
+    graph.insert(TypeId::of::<A>(), deps(vec![TypeId::of::<B>()]));
+    graph.insert(TypeId::of::<B>(), deps(vec![]));
+    graph.insert(TypeId::of::<C>(), deps(vec![]));
+    let mut optional = HashMap::new();
+    optional.insert(TypeId::of::<A>(), deps(vec![TypeId::of::<C>(), TypeId::of::<D>()]));
+
+    let graph = resolve_optional_dependencies(&graph, &optional);
+    let order = validate_worker_order(&graph).unwrap();
+    let position = |id| order.iter().position(|&other| other == id).unwrap();
+
+    assert_eq!(order.len(), 3);
+    assert!(position(TypeId::of::<B>()) < position(TypeId::of::<A>()));
+    assert!(position(TypeId::of::<C>()) < position(TypeId::of::<A>()));
+}
+
+#[test]
+fn unregistered_optional_dependency_ignored() {
+    let mut graph = HashMap::new();
+    graph.insert(TypeId::of::<A>(), deps(vec![]));
+    let mut optional = HashMap::new();
+    optional.insert(TypeId::of::<A>(), deps(vec![TypeId::of::<B>()]));
+
+    let graph = resolve_optional_dependencies(&graph, &optional);
+
+    assert!(graph[&TypeId::of::<A>()].is_empty());
+    assert_eq!(validate_worker_order(&graph), Ok(vec![TypeId::of::<A>()]));
+}
@@ -0,0 +1,67 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use bee_common_ext::shutdown_stream::ShutdownStream;
+
+use futures::{
+    channel::{mpsc, oneshot},
+    executor::block_on,
+    stream::{FusedStream, StreamExt},
+};
+
+#[test]
+fn immediate_drops_pending_items() {
+    let (shutdown_tx, shutdown_rx) = oneshot::channel();
+    let (tx, rx) = mpsc::unbounded();
+    let mut stream = ShutdownStream::new(shutdown_rx, rx);
+
+    tx.unbounded_send(0).unwrap();
+    assert_eq!(block_on(stream.next()), Some(0));
+
+    tx.unbounded_send(1).unwrap();
+    tx.unbounded_send(2).unwrap();
+    shutdown_tx.send(()).unwrap();
+
+    assert_eq!(block_on(stream.next()), None);
+    assert!(stream.is_terminated());
+}
+
+#[test]
+fn graceful_drains_pending_items() {
+    let (shutdown_tx, shutdown_rx) = oneshot::channel();
+    let (tx, rx) = mpsc::unbounded();
+    let mut stream = ShutdownStream::graceful(shutdown_rx, rx);
+
+    tx.unbounded_send(0).unwrap();
+    tx.unbounded_send(1).unwrap();
+    tx.unbounded_send(2).unwrap();
+    shutdown_tx.send(()).unwrap();
+
+    // The sender is still alive, the stream ends because nothing else is ready.
+    assert_eq!(block_on(stream.by_ref().collect::<Vec<_>>()), vec![0, 1, 2]);
+    assert!(stream.is_terminated());
+
+    tx.unbounded_send(3).unwrap();
+    assert_eq!(block_on(stream.next()), None);
+}
+
+#[test]
+fn ends_with_inner_stream() {
+    let (_shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+    let (tx, rx) = mpsc::unbounded();
+    let mut stream = ShutdownStream::graceful(shutdown_rx, rx);
+
+    tx.unbounded_send(0).unwrap();
+    drop(tx);
+
+    assert_eq!(block_on(stream.by_ref().collect::<Vec<_>>()), vec![0]);
+    assert!(stream.is_terminated());
+}
@@ -9,9 +9,21 @@
 // an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and limitations under the License.
 
-use bee_common_ext::wait_priority_queue::WaitPriorityQueue;
+use bee_common_ext::wait_priority_queue::{QueueFull, WaitPriorityQueue};
 
-use std::cmp::Ordering;
+use futures::{
+    pin_mut, poll,
+    task::{waker, ArcWake, Context, Poll, Waker},
+};
+
+use std::{
+    cmp::Ordering,
+    future::Future,
+    sync::{
+        atomic::{AtomicBool, Ordering as AtomicOrdering},
+        Arc,
+    },
+};
 
 #[derive(Eq, PartialEq, Debug)]
 pub(crate) struct TestMinHeapEntry(u64, char);
@@ -54,3 +66,143 @@ async fn min_heap() {
     assert_eq!(queue.pop().await, TestMinHeapEntry(8, 'I'));
     assert_eq!(queue.pop().await, TestMinHeapEntry(9, 'J'));
 }
+
+#[tokio::test]
+async fn bounded_try_insert_full() {
+    let queue = WaitPriorityQueue::bounded(3);
+
+    assert_eq!(queue.try_insert(TestMinHeapEntry(2, 'C')), Ok(()));
+    assert_eq!(queue.try_insert(TestMinHeapEntry(0, 'A')), Ok(()));
+    assert_eq!(queue.try_insert(TestMinHeapEntry(1, 'B')), Ok(()));
+    assert_eq!(queue.len(), 3);
+
+    assert_eq!(queue.try_insert(TestMinHeapEntry(3, 'D')), Err(QueueFull));
+    assert_eq!(queue.len(), 3);
+
+    assert_eq!(queue.pop().await, TestMinHeapEntry(0, 'A'));
+    assert_eq!(queue.try_insert(TestMinHeapEntry(3, 'D')), Ok(()));
+    assert_eq!(queue.try_insert(TestMinHeapEntry(4, 'E')), Err(QueueFull));
+}
+
+#[tokio::test]
+async fn bounded_insert_waits_for_room() {
+    let queue = WaitPriorityQueue::bounded(1);
+
+    queue.insert(TestMinHeapEntry(1, 'B')).await;
+
+    let insert = queue.insert(TestMinHeapEntry(0, 'A'));
+    pin_mut!(insert);
+
+    assert_eq!(poll!(insert.as_mut()), Poll::Pending);
+    assert_eq!(queue.pop().await, TestMinHeapEntry(1, 'B'));
+    assert_eq!(poll!(insert.as_mut()), Poll::Ready(()));
+    assert_eq!(queue.pop().await, TestMinHeapEntry(0, 'A'));
+}
+
+struct WakeFlag(AtomicBool);
+
+impl ArcWake for WakeFlag {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        arc_self.0.store(true, AtomicOrdering::SeqCst);
+    }
+}
+
+impl WakeFlag {
+    fn new() -> (Arc<Self>, Waker) {
+        let flag = Arc::new(Self(AtomicBool::new(false)));
+
+        (flag.clone(), waker(flag))
+    }
+
+    fn is_woken(&self) -> bool {
+        self.0.swap(false, AtomicOrdering::SeqCst)
+    }
+}
+
+#[tokio::test]
+async fn bounded_insert_wakes_every_producer() {
+    let queue = WaitPriorityQueue::bounded(1);
+    let (first_flag, first_waker) = WakeFlag::new();
+    let (second_flag, second_waker) = WakeFlag::new();
+
+    queue.insert(TestMinHeapEntry(0, 'A')).await;
+
+    let first = queue.insert(TestMinHeapEntry(1, 'B'));
+    let second = queue.insert(TestMinHeapEntry(2, 'C'));
+    pin_mut!(first);
+    pin_mut!(second);
+
+    // Polling a waiting producer again doesn't register it twice.
+    for _ in 0..3 {
+        assert_eq!(
+            first.as_mut().poll(&mut Context::from_waker(&first_waker)),
+            Poll::Pending
+        );
+    }
+    assert_eq!(
+        second.as_mut().poll(&mut Context::from_waker(&second_waker)),
+        Poll::Pending
+    );
+
+    assert_eq!(queue.pop().await, TestMinHeapEntry(0, 'A'));
+    assert!(first_flag.is_woken());
+    assert!(!second_flag.is_woken());
+    assert_eq!(
+        first.as_mut().poll(&mut Context::from_waker(&first_waker)),
+        Poll::Ready(())
+    );
+
+    assert_eq!(queue.pop().await, TestMinHeapEntry(1, 'B'));
+    assert!(second_flag.is_woken());
+    assert_eq!(
+        second.as_mut().poll(&mut Context::from_waker(&second_waker)),
+        Poll::Ready(())
+    );
+    assert_eq!(queue.pop().await, TestMinHeapEntry(2, 'C'));
+}
+
+#[tokio::test]
+async fn cancelled_producer_passes_wakeup_on() {
+    let queue = WaitPriorityQueue::bounded(1);
+    let (first_flag, first_waker) = WakeFlag::new();
+    let (second_flag, second_waker) = WakeFlag::new();
+
+    queue.insert(TestMinHeapEntry(0, 'A')).await;
+
+    let mut first = Box::pin(queue.insert(TestMinHeapEntry(1, 'B')));
+    let second = queue.insert(TestMinHeapEntry(2, 'C'));
+    pin_mut!(second);
+
+    assert_eq!(
+        first.as_mut().poll(&mut Context::from_waker(&first_waker)),
+        Poll::Pending
+    );
+    assert_eq!(
+        second.as_mut().poll(&mut Context::from_waker(&second_waker)),
+        Poll::Pending
+    );
+
+    assert_eq!(queue.pop().await, TestMinHeapEntry(0, 'A'));
+    assert!(first_flag.is_woken());
+
+    drop(first);
+
+    assert!(second_flag.is_woken());
+    assert_eq!(
+        second.as_mut().poll(&mut Context::from_waker(&second_waker)),
+        Poll::Ready(())
+    );
+    assert_eq!(queue.pop().await, TestMinHeapEntry(2, 'C'));
+}
+
+#[tokio::test]
+async fn unbounded_try_insert() {
+    let queue = WaitPriorityQueue::default();
+
+    for i in 0..100 {
+        assert_eq!(queue.try_insert(TestMinHeapEntry(i, 'X')), Ok(()));
+    }
+
+    assert_eq!(queue.len(), 100);
+    assert_eq!(queue.capacity(), None);
+}
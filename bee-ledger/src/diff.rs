@@ -9,11 +9,12 @@
 // an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and limitations under the License.
 
+use bee_storage::persistable::Persistable;
 use bee_transaction::bundled::Address;
 
 use std::collections::HashMap;
 
-#[derive(Default)]
+#[derive(Default, Persistable)]
 pub struct LedgerDiff(pub(crate) HashMap<Address, i64>);
 
 impl LedgerDiff {
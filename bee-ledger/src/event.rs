@@ -9,7 +9,9 @@
 // an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and limitations under the License.
 
-use bee_protocol::Milestone;
+use bee_crypto::ternary::Hash;
+use bee_protocol::{Milestone, MilestoneIndex};
+use bee_transaction::bundled::Address;
 
 pub struct MilestoneConfirmed {
     pub milestone: Milestone,
@@ -19,3 +21,12 @@ pub struct MilestoneConfirmed {
     pub tails_conflicting: usize,
     pub tails_included: usize,
 }
+
+/// The balance of an address was changed by a bundle confirmed by a milestone.
+pub struct AddressTouched {
+    pub address: Address,
+    /// Tail of the bundle that changed the balance.
+    pub transaction_hash: Hash,
+    pub milestone: MilestoneIndex,
+    pub delta: i64,
+}
@@ -11,11 +11,12 @@
 
 use crate::diff::LedgerDiff;
 
+use bee_storage::persistable::Persistable;
 use bee_transaction::bundled::Address;
 
 use std::{collections::HashMap, convert::From};
 
-#[derive(Default)]
+#[derive(Default, Persistable)]
 pub struct LedgerState(HashMap<Address, u64>);
 
 impl LedgerState {
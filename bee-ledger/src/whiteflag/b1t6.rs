@@ -10,6 +10,8 @@
 // See the License for the specific language governing permissions and limitations under the License.
 
 use bee_ternary::Trits;
+#[cfg(test)]
+use bee_ternary::{Btrit, T1B1Buf, TritBuf};
 
 use std::convert::TryFrom;
 
@@ -39,3 +41,42 @@ fn decode_group(t1: i8, t2: i8) -> Result<i8, ()> {
 
     i8::try_from(v).map_err(|_| ())
 }
+
+// Inverse of `decode`.
+#[cfg(test)]
+pub(crate) fn encode(src: &[u8]) -> TritBuf<T1B1Buf> {
+    let mut trits = TritBuf::zeros(src.len() * TRITS_PER_BYTE);
+
+    for (i, b) in src.iter().enumerate() {
+        let v = *b as i8 as isize;
+        // Balanced digits of `v` in base 27, the least significant first.
+        let t1 = (v + 13).rem_euclid(27) - 13;
+        let t2 = (v - t1) / 27;
+
+        for (j, mut t) in [t1, t2].iter().copied().enumerate() {
+            for k in 0..TRITS_PER_TRYTE {
+                let trit = (t + 1).rem_euclid(3) - 1;
+                t = (t - trit) / 3;
+                trits.set(
+                    i * TRITS_PER_BYTE + j * TRITS_PER_TRYTE + k,
+                    [Btrit::NegOne, Btrit::Zero, Btrit::PlusOne][(trit + 1) as usize],
+                );
+            }
+        }
+    }
+
+    trits
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn encode_decode_round_trip() {
+        let bytes = (0..=255).collect::<Vec<u8>>();
+
+        assert_eq!(decode(&encode(&bytes)), bytes);
+    }
+}
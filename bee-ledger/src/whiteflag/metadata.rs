@@ -13,25 +13,29 @@ use crate::diff::LedgerDiff;
 
 use bee_crypto::ternary::Hash;
 use bee_protocol::MilestoneIndex;
+use bee_transaction::bundled::Address;
 
 /// White flag metadata of a milestone confirmation.
 #[derive(Default)]
 pub(crate) struct WhiteFlagMetadata {
     /// Index of the confirming milestone.
     pub(crate) index: MilestoneIndex,
-    /// Timestamp of the confirming milestone.
-    #[allow(dead_code)]
+    /// Timestamp of the confirming milestone, in seconds.
     pub(crate) timestamp: u64,
     /// The ledger differences created by the confirming milestone.
     pub(crate) diff: LedgerDiff,
     /// The number of tails which were referenced by the confirming milestone.
     pub(crate) num_tails_referenced: usize,
+    /// The number of transactions of the bundles which were referenced by the confirming milestone.
+    pub(crate) num_transactions_referenced: usize,
     /// The number of tails which were excluded because they were part of a zero or spam value transfer.
     pub(crate) num_tails_zero_value: usize,
     /// The number of tails which were excluded as they were conflicting with the ledger state.
     pub(crate) num_tails_conflicting: usize,
     /// The tails of bundles which mutate the ledger in the order in which they were applied.
     pub(crate) tails_included: Vec<Hash>,
+    /// The non-zero balance changes of the included bundles, with their tail, in the order in which they were applied.
+    pub(crate) addresses_touched: Vec<(Address, Hash, i64)>,
 }
 
 impl WhiteFlagMetadata {
@@ -17,27 +17,36 @@ mod worker;
 
 use crate::state::LedgerState;
 
-use worker::LedgerWorker;
-pub use worker::LedgerWorkerEvent;
+pub use worker::{LedgerWorker, LedgerWorkerEvent};
 
 use bee_common_ext::{
     event::Bus,
     node::{Node, NodeBuilder},
 };
 use bee_protocol::{config::ProtocolCoordinatorConfig, event::LatestSolidMilestoneChanged, MilestoneIndex};
+use bee_transaction::bundled::Address;
 
 use log::warn;
 
-use std::sync::Arc;
+use std::{collections::HashSet, sync::Arc};
 
 pub fn init<N: Node>(
     index: u32,
     state: LedgerState,
+    spent_addresses: HashSet<Address>,
     coo_config: ProtocolCoordinatorConfig,
+    max_cone_size: usize,
     node_builder: N::Builder,
     bus: Arc<Bus<'static>>,
 ) -> N::Builder {
-    node_builder.with_worker_cfg::<LedgerWorker>((MilestoneIndex(index), state, coo_config, bus.clone()))
+    node_builder.with_worker_cfg::<LedgerWorker>((
+        MilestoneIndex::from(index),
+        state,
+        spent_addresses,
+        coo_config,
+        max_cone_size,
+        bus.clone(),
+    ))
 }
 
 pub fn events<N: Node>(node: &N, bus: Arc<Bus<'static>>) {
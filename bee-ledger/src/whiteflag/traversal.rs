@@ -14,13 +14,16 @@ use crate::{state::LedgerState, whiteflag::metadata::WhiteFlagMetadata};
 use bee_crypto::ternary::Hash;
 use bee_protocol::tangle::MsTangle;
 use bee_storage::storage::Backend;
-use bee_tangle::helper::load_bundle_builder;
+use bee_tangle::{helper::load_bundle_builder, traversal::CANCELLATION_CHECK_INTERVAL};
 use bee_transaction::{
     bundled::{Bundle, IncomingBundleBuilderError},
     Vertex,
 };
 
-use std::collections::HashSet;
+use std::{
+    collections::HashSet,
+    sync::atomic::{AtomicBool, Ordering},
+};
 
 const IOTA_SUPPLY: u64 = 2_779_530_283_277_761;
 
@@ -29,6 +32,8 @@ pub(crate) enum Error {
     MissingBundle,
     NotATail,
     InvalidBundle(IncomingBundleBuilderError),
+    ConeTooLarge(usize),
+    Cancelled,
 }
 
 #[inline]
@@ -59,6 +64,9 @@ fn on_bundle<B: Backend>(
         if !conflicting {
             // Second pass to mutate the state.
             for (address, diff) in mutations {
+                if diff != 0 {
+                    metadata.addresses_touched.push((address.clone(), *hash, diff));
+                }
                 state.apply_single_diff(address.clone(), diff);
                 metadata.diff.apply_single_diff(address, diff);
             }
@@ -68,11 +76,12 @@ fn on_bundle<B: Backend>(
     }
 
     metadata.num_tails_referenced += 1;
+    metadata.num_transactions_referenced += bundle.len();
 
     // TODO this only actually confirm tails
     tangle.update_metadata(&hash, |meta| {
         meta.flags_mut().set_conflicting(conflicting);
-        meta.confirm();
+        meta.confirm(metadata.timestamp * 1000);
         meta.set_milestone_index(metadata.index);
         // TODO Set OTRSI, ...
         // TODO increment metrics confirmed, zero, value and conflict.
@@ -84,11 +93,24 @@ pub(crate) fn visit_bundles_dfs<B: Backend>(
     state: &mut LedgerState,
     root: Hash,
     metadata: &mut WhiteFlagMetadata,
+    max_cone_size: Option<usize>,
+    cancel: &AtomicBool,
 ) -> Result<(), Error> {
     let mut hashes = vec![root];
     let mut visited = HashSet::new();
 
     while let Some(hash) = hashes.last() {
+        // Bundles are only ever applied as a whole, cancelling between two of them leaves their metadata consistent.
+        if visited.len() % CANCELLATION_CHECK_INTERVAL == 0 && cancel.load(Ordering::Relaxed) {
+            return Err(Error::Cancelled);
+        }
+
+        if let Some(max_cone_size) = max_cone_size {
+            if visited.len() > max_cone_size {
+                return Err(Error::ConeTooLarge(max_cone_size));
+            }
+        }
+
         let meta = match tangle.get_metadata(hash) {
             Some(meta) => meta,
             None => {
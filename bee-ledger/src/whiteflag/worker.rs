@@ -10,7 +10,7 @@
 // See the License for the specific language governing permissions and limitations under the License.
 
 use crate::{
-    event::MilestoneConfirmed,
+    event::{AddressTouched, MilestoneConfirmed},
     state::LedgerState,
     whiteflag::{
         b1t6::decode,
@@ -21,9 +21,17 @@ use crate::{
 };
 
 use bee_common::{shutdown_stream::ShutdownStream, worker::Error as WorkerError};
-use bee_common_ext::{event::Bus, node::Node, worker::Worker};
+use bee_common_ext::{
+    channel,
+    event::Bus,
+    node::{Node, ShutdownFlag},
+    worker::Worker,
+};
 use bee_crypto::ternary::{Hash, HASH_LENGTH};
-use bee_protocol::{config::ProtocolCoordinatorConfig, tangle::MsTangle, Milestone, MilestoneIndex, TangleWorker};
+use bee_protocol::{
+    config::ProtocolCoordinatorConfig, max_cone_size, tangle::MsTangle, Milestone, MilestoneIndex, Protocol,
+    TangleWorker,
+};
 use bee_storage::storage::Backend;
 use bee_tangle::helper::load_bundle_builder;
 use bee_transaction::bundled::{Address, BundledTransactionField};
@@ -33,7 +41,7 @@ use blake2::Blake2b;
 use futures::{channel::oneshot, stream::StreamExt};
 use log::{error, info, warn};
 
-use std::{any::TypeId, sync::Arc};
+use std::{any::TypeId, collections::HashSet, sync::Arc};
 
 const MERKLE_PROOF_LENGTH: usize = 384;
 
@@ -42,15 +50,18 @@ enum Error {
     MerkleProofMismatch,
     InvalidTailsCount,
     InvalidConfirmationSet(TraversalError),
+    Cancelled,
 }
 
 pub enum LedgerWorkerEvent {
     Confirm(Milestone),
     GetBalance(Address, oneshot::Sender<u64>),
+    /// Whether funds were moved out of an address, as of the snapshot or by a milestone confirmed since then.
+    IsSpent(Address, oneshot::Sender<bool>),
 }
 
-pub(crate) struct LedgerWorker {
-    pub(crate) tx: flume::Sender<LedgerWorkerEvent>,
+pub struct LedgerWorker {
+    pub tx: channel::Sender<LedgerWorkerEvent>,
 }
 
 fn milestone_info<B: Backend>(
@@ -68,14 +79,18 @@ fn milestone_info<B: Backend>(
     (proof, timestamp)
 }
 
+// Applies the milestone to the ledger state and returns the number of transactions it references.
 fn confirm<B: Backend>(
     tangle: &MsTangle<B>,
     milestone: Milestone,
     index: &mut MilestoneIndex,
     state: &mut LedgerState,
+    spent_addresses: &mut HashSet<Address>,
     coo_config: &ProtocolCoordinatorConfig,
+    max_cone_size_per_milestone: usize,
     bus: &Arc<Bus<'static>>,
-) -> Result<(), Error> {
+    shutdown_flag: &ShutdownFlag,
+) -> Result<usize, Error> {
     if milestone.index() != MilestoneIndex(index.0 + 1) {
         error!("Tried to confirm {} on top of {}.", milestone.index().0, index.0);
         return Err(Error::NonContiguousMilestone);
@@ -85,7 +100,16 @@ fn confirm<B: Backend>(
 
     let mut confirmation = WhiteFlagMetadata::new(milestone.index(), timestamp);
 
-    match visit_bundles_dfs(tangle, state, *milestone.hash(), &mut confirmation) {
+    let max_cone_size = max_cone_size(max_cone_size_per_milestone, tangle.get_latest_milestone_index(), *index);
+
+    match visit_bundles_dfs(
+        tangle,
+        state,
+        *milestone.hash(),
+        &mut confirmation,
+        max_cone_size,
+        shutdown_flag,
+    ) {
         Ok(_) => {
             if !merkle_proof.eq(&MerkleHasher::<Blake2b>::new().digest(&confirmation.tails_included)) {
                 error!(
@@ -122,6 +146,8 @@ fn confirm<B: Backend>(
                 confirmation.tails_included.len()
             );
 
+            let milestone_index = milestone.index();
+
             bus.dispatch(MilestoneConfirmed {
                 milestone,
                 timestamp,
@@ -131,8 +157,11 @@ fn confirm<B: Backend>(
                 tails_included: confirmation.tails_included.len(),
             });
 
-            Ok(())
+            touch_addresses(milestone_index, confirmation.addresses_touched, spent_addresses, bus);
+
+            Ok(confirmation.num_transactions_referenced)
         }
+        Err(TraversalError::Cancelled) => Err(Error::Cancelled),
         Err(e) => {
             error!(
                 "Error occured while traversing to confirm {}: {:?}.",
@@ -144,6 +173,32 @@ fn confirm<B: Backend>(
     }
 }
 
+fn touch_addresses(
+    milestone: MilestoneIndex,
+    addresses_touched: Vec<(Address, Hash, i64)>,
+    spent_addresses: &mut HashSet<Address>,
+    bus: &Bus<'static>,
+) {
+    for (address, transaction_hash, delta) in addresses_touched {
+        if delta < 0 {
+            spent_addresses.insert(address.clone());
+        }
+
+        bus.dispatch(AddressTouched {
+            address,
+            transaction_hash,
+            milestone,
+            delta,
+        });
+    }
+}
+
+fn is_spent(spent_addresses: &HashSet<Address>, address: Address, sender: oneshot::Sender<bool>) {
+    if let Err(e) = sender.send(spent_addresses.contains(&address)) {
+        warn!("Failed to send spent status: {:?}.", e);
+    }
+}
+
 fn get_balance(state: &LedgerState, address: Address, sender: oneshot::Sender<u64>) {
     if let Err(e) = sender.send(state.get_or_zero(&address)) {
         warn!("Failed to send balance: {:?}.", e);
@@ -155,7 +210,9 @@ impl<N: Node> Worker<N> for LedgerWorker {
     type Config = (
         MilestoneIndex,
         LedgerState,
+        HashSet<Address>,
         ProtocolCoordinatorConfig,
+        usize,
         Arc<Bus<'static>>,
     );
     type Error = WorkerError;
@@ -165,9 +222,10 @@ impl<N: Node> Worker<N> for LedgerWorker {
     }
 
     async fn start(node: &mut N, config: Self::Config) -> Result<Self, Self::Error> {
-        let (tx, rx) = flume::unbounded();
+        let (tx, rx) = channel::unbounded("LedgerWorker");
 
         let tangle = node.resource::<MsTangle<N::Backend>>();
+        let shutdown_flag = node.resource::<ShutdownFlag>();
 
         node.spawn::<Self, _, _>(|shutdown| async move {
             info!("Running.");
@@ -176,17 +234,48 @@ impl<N: Node> Worker<N> for LedgerWorker {
 
             let mut index = config.0;
             let mut state = config.1;
-            let coo_config = config.2;
-            let bus = config.3;
+            let mut spent_addresses = config.2;
+            let coo_config = config.3;
+            let max_cone_size = config.4;
+            let bus = config.5;
+            // Set once a milestone can't be confirmed without the ledger state being left behind for good.
+            let mut halted = false;
 
             while let Some(event) = receiver.next().await {
                 match event {
+                    LedgerWorkerEvent::Confirm(milestone) if halted => {
+                        warn!("Ledger halted, ignoring milestone {}.", *milestone.index());
+                    }
                     LedgerWorkerEvent::Confirm(milestone) => {
-                        if confirm(&tangle, milestone, &mut index, &mut state, &coo_config, &bus).is_err() {
-                            panic!("Error while confirming milestone, aborting.");
+                        let milestone_index = milestone.index();
+
+                        match confirm(
+                            &tangle,
+                            milestone,
+                            &mut index,
+                            &mut state,
+                            &mut spent_addresses,
+                            &coo_config,
+                            max_cone_size,
+                            &bus,
+                            &shutdown_flag,
+                        ) {
+                            Ok(referenced) => Protocol::transactions_confirmed(referenced as u64),
+                            // The node is shutting down and the ledger state, partially mutated by the interrupted
+                            // confirmation, is dropped.
+                            Err(Error::Cancelled) => {
+                                info!("Confirmation of milestone {} cancelled.", *milestone_index);
+                                break;
+                            }
+                            Err(Error::InvalidConfirmationSet(TraversalError::ConeTooLarge(max_cone_size))) => {
+                                Protocol::milestone_cone_too_large(milestone_index, max_cone_size);
+                                halted = true;
+                            }
+                            Err(_) => panic!("Error while confirming milestone, aborting."),
                         }
                     }
                     LedgerWorkerEvent::GetBalance(address, sender) => get_balance(&state, address, sender),
+                    LedgerWorkerEvent::IsSpent(address, sender) => is_spent(&spent_addresses, address, sender),
                 }
             }
 
@@ -197,6 +286,268 @@ impl<N: Node> Worker<N> for LedgerWorker {
     }
 }
 
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use crate::whiteflag::b1t6::encode;
+
+    use bee_common_ext::node::ResHandle;
+    use bee_protocol::{config::ProtocolConfig, tangle::TransactionMetadata};
+    use bee_ternary::{T1B1Buf, TritBuf, TryteBuf};
+    use bee_transaction::bundled::{
+        constants::PAYLOAD_TRIT_LEN, BundledTransaction, BundledTransactionBuilder, Index, Nonce, Payload, Tag,
+        Timestamp, Value,
+    };
+
+    use futures::executor::block_on;
+
+    use std::{collections::HashMap, error::Error as StdError, sync::Mutex};
+
+    struct NullBackend;
+
+    #[async_trait]
+    impl Backend for NullBackend {
+        type ConfigBuilder = ();
+        type Config = ();
+
+        async fn start(_config: Self::Config) -> Result<Self, Box<dyn StdError>> {
+            Ok(Self)
+        }
+
+        async fn shutdown(self) -> Result<(), Box<dyn StdError>> {
+            Ok(())
+        }
+    }
+
+    fn address(trytes: &str) -> Address {
+        Address::from_inner_unchecked(TryteBuf::try_from_str(trytes).unwrap().as_trits().encode::<T1B1Buf>())
+    }
+
+    fn hash(trytes: &str) -> Hash {
+        Hash::from_inner_unchecked(
+            TryteBuf::try_from_str(&format!("{:9<81}", trytes))
+                .unwrap()
+                .as_trits()
+                .encode::<T1B1Buf>(),
+        )
+    }
+
+    fn transaction(
+        index: usize,
+        last_index: usize,
+        address: Address,
+        value: i64,
+        payload: Payload,
+        trunk: Hash,
+        branch: Hash,
+    ) -> BundledTransaction {
+        BundledTransactionBuilder::new()
+            .with_payload(payload)
+            .with_address(address)
+            .with_value(Value::from_inner_unchecked(value))
+            .with_obsolete_tag(Tag::zeros())
+            .with_timestamp(Timestamp::from_inner_unchecked(0))
+            .with_index(Index::from_inner_unchecked(index))
+            .with_last_index(Index::from_inner_unchecked(last_index))
+            .with_tag(Tag::zeros())
+            .with_attachment_ts(Timestamp::from_inner_unchecked(0))
+            .with_bundle(Hash::zeros())
+            .with_trunk(trunk)
+            .with_branch(branch)
+            .with_attachment_lbts(Timestamp::from_inner_unchecked(0))
+            .with_attachment_ubts(Timestamp::from_inner_unchecked(0))
+            .with_nonce(Nonce::zeros())
+            .build_unchecked()
+            .unwrap()
+    }
+
+    // Tails are flagged valid so that the traversal doesn't check the signatures of the bundles.
+    fn insert(tangle: &MsTangle<NullBackend>, hash: Hash, transaction: BundledTransaction) {
+        let mut metadata = TransactionMetadata::arrived();
+        metadata.flags_mut().set_tail(transaction.index().to_inner() == &0);
+        metadata.flags_mut().set_valid(transaction.index().to_inner() == &0);
+
+        block_on(tangle.insert(transaction, hash, metadata));
+    }
+
+    // Adds to `tangle` a bundle moving all the funds of `sender` to `receiver`, and a milestone approving it. Returns the
+    // hash of the milestone tail.
+    fn value_milestone(
+        tangle: &MsTangle<NullBackend>,
+        coo_config: &ProtocolCoordinatorConfig,
+        sender: Address,
+        receiver: Address,
+    ) -> Hash {
+        let (value_tail, value_head) = (hash("VALUETAIL"), hash("VALUEHEAD"));
+        let milestone = [hash("MILESTONEA"), hash("MILESTONEB"), hash("MILESTONEC")];
+
+        tangle.add_solid_entry_point(Hash::zeros(), MilestoneIndex(0));
+
+        // Bundle moving all the funds of `sender` to `receiver`.
+        let sep = Hash::zeros();
+        insert(
+            tangle,
+            value_tail,
+            transaction(0, 1, sender, -100, Payload::zeros(), value_head, sep),
+        );
+        insert(
+            tangle,
+            value_head,
+            transaction(1, 1, receiver, 100, Payload::zeros(), sep, sep),
+        );
+
+        // Milestone bundle approving it, its last transaction carrying the merkle proof of the included tails.
+        let proof = encode(&MerkleHasher::<Blake2b>::new().digest(&[value_tail]));
+        let offset = coo_config.depth() as usize * HASH_LENGTH;
+        let mut payload = TritBuf::<T1B1Buf>::zeros(PAYLOAD_TRIT_LEN);
+        for (i, trit) in proof.iter().enumerate() {
+            payload.set(offset + i, trit);
+        }
+        let coo = address(&"C".repeat(81));
+        insert(
+            tangle,
+            milestone[0],
+            transaction(0, 2, coo.clone(), 0, Payload::zeros(), milestone[1], value_tail),
+        );
+        insert(
+            tangle,
+            milestone[1],
+            transaction(1, 2, coo.clone(), 0, Payload::zeros(), milestone[2], value_tail),
+        );
+        insert(
+            tangle,
+            milestone[2],
+            transaction(
+                2,
+                2,
+                coo,
+                0,
+                Payload::from_inner_unchecked(payload),
+                value_tail,
+                value_tail,
+            ),
+        );
+
+        milestone[0]
+    }
+
+    #[test]
+    fn confirmed_milestone_spends_address() {
+        let tangle = MsTangle::new(ResHandle::new(NullBackend));
+        let coo_config = ProtocolConfig::build().finish().coordinator().clone();
+        let sender = address(&"A".repeat(81));
+        let receiver = address(&"B".repeat(81));
+        let milestone = value_milestone(&tangle, &coo_config, sender.clone(), receiver.clone());
+
+        let mut index = MilestoneIndex(0);
+        let mut state = LedgerState::from(vec![(sender.clone(), 100)].into_iter().collect::<HashMap<_, _>>());
+        let mut spent_addresses = HashSet::new();
+
+        let referenced = confirm(
+            &tangle,
+            Milestone::new(milestone, MilestoneIndex(1)),
+            &mut index,
+            &mut state,
+            &mut spent_addresses,
+            &coo_config,
+            0,
+            &Arc::new(Bus::default()),
+            &ShutdownFlag::default(),
+        );
+
+        // The value bundle and the milestone bundle.
+        assert!(matches!(referenced, Ok(5)));
+        assert_eq!(index, MilestoneIndex(1));
+        assert_eq!(state.get_or_zero(&sender), 0);
+        assert_eq!(state.get_or_zero(&receiver), 100);
+
+        let (sender_spent, receiver_spent) = (oneshot::channel(), oneshot::channel());
+        is_spent(&spent_addresses, sender, sender_spent.0);
+        is_spent(&spent_addresses, receiver, receiver_spent.0);
+        assert_eq!(block_on(sender_spent.1), Ok(true));
+        assert_eq!(block_on(receiver_spent.1), Ok(false));
+    }
+
+    #[test]
+    fn cone_too_large_not_confirmed() {
+        let tangle = MsTangle::new(ResHandle::new(NullBackend));
+        let coo_config = ProtocolConfig::build().finish().coordinator().clone();
+        let sender = address(&"A".repeat(81));
+        let milestone = value_milestone(&tangle, &coo_config, sender.clone(), address(&"B".repeat(81)));
+        let confirmed = Arc::new(Mutex::new(Vec::new()));
+        let bus = Arc::new(Bus::default());
+
+        let confirmed_clone = confirmed.clone();
+        bus.add_listener(move |event: &MilestoneConfirmed| {
+            confirmed_clone.lock().unwrap().push(event.milestone.index())
+        });
+
+        let mut index = MilestoneIndex(0);
+        let mut state = LedgerState::from(vec![(sender, 100)].into_iter().collect::<HashMap<_, _>>());
+        let mut spent_addresses = HashSet::new();
+
+        // The cone holds the value bundle, the milestone bundle and the solid entry point.
+        let referenced = confirm(
+            &tangle,
+            Milestone::new(milestone, MilestoneIndex(1)),
+            &mut index,
+            &mut state,
+            &mut spent_addresses,
+            &coo_config,
+            1,
+            &bus,
+            &ShutdownFlag::default(),
+        );
+
+        assert!(matches!(
+            referenced,
+            Err(Error::InvalidConfirmationSet(TraversalError::ConeTooLarge(1)))
+        ));
+        assert_eq!(index, MilestoneIndex(0));
+        assert!(confirmed.lock().unwrap().is_empty());
+        assert!(spent_addresses.is_empty());
+    }
+
+    #[test]
+    fn touched_addresses_dispatched() {
+        let sender = address(&"A".repeat(81));
+        let receiver = address(&"B".repeat(81));
+        let tail = Hash::zeros();
+        let touched = Arc::new(Mutex::new(Vec::new()));
+        let mut spent_addresses = HashSet::new();
+        let bus = Bus::default();
+
+        let touched_clone = touched.clone();
+        bus.add_listener(move |event: &AddressTouched| {
+            touched_clone.lock().unwrap().push((
+                event.address.clone(),
+                event.transaction_hash,
+                event.milestone,
+                event.delta,
+            ));
+        });
+
+        touch_addresses(
+            MilestoneIndex(42),
+            vec![(sender.clone(), tail, -100), (receiver.clone(), tail, 100)],
+            &mut spent_addresses,
+            &bus,
+        );
+
+        assert_eq!(
+            *touched.lock().unwrap(),
+            vec![
+                (sender.clone(), tail, MilestoneIndex(42), -100),
+                (receiver.clone(), tail, MilestoneIndex(42), 100)
+            ]
+        );
+        assert!(spent_addresses.contains(&sender));
+        assert!(!spent_addresses.contains(&receiver));
+    }
+}
+
 // #[cfg(test)]
 // mod tests {
 //
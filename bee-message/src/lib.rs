@@ -24,6 +24,8 @@ pub use message::{Message, MessageBuilder};
 pub use message_id::{MessageId, MESSAGE_ID_LENGTH};
 pub use vertex::Vertex;
 
+use payload::transaction::SignatureType;
+
 use core::fmt;
 
 #[derive(Debug)]
@@ -37,12 +39,14 @@ pub enum Error {
     InvalidIndex,
     InvalidAddress,
     InvalidSignature,
+    InvalidPayloadKind,
     OrderError,
     HashError,
     PathError,
     MissingField(&'static str),
     SigningError(bee_signing_ext::binary::Error),
     SignatureError(bee_signing_ext::SignatureError),
+    SignatureTypeMismatch(SignatureType, SignatureType),
 }
 
 impl fmt::Display for Error {
@@ -56,12 +60,16 @@ impl fmt::Display for Error {
             Error::InvalidIndex => write!(f, "Invalid index provided."),
             Error::InvalidAddress => write!(f, "Invalid address provided."),
             Error::InvalidSignature => write!(f, "Invalid signature provided."),
+            Error::InvalidPayloadKind => write!(f, "Invalid payload kind provided."),
             Error::OrderError => write!(f, "The vector is not sorted by lexicographical order."),
             Error::HashError => write!(f, "The format of provided hash is not correct."),
             Error::PathError => write!(f, "The format of provided BIP32 path is not correct."),
             Error::MissingField(s) => write!(f, "Missing required field: {}.", s),
             Error::SigningError(e) => write!(f, "{}", e),
             Error::SignatureError(e) => write!(f, "{}", e),
+            Error::SignatureTypeMismatch(expected, found) => {
+                write!(f, "Expected a {} signature, found a {} one.", expected, found)
+            }
         }
     }
 }
@@ -18,7 +18,10 @@ use bee_common_ext::packable::{Error as PackableError, Packable, Read, Write};
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+use alloc::vec::Vec;
+use std::io::Cursor;
+
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
 pub struct Message {
     parent1: MessageId,
     parent2: MessageId,
@@ -46,6 +49,21 @@ impl Message {
     pub fn nonce(&self) -> u64 {
         self.nonce
     }
+
+    /// Packs the message into a freshly allocated buffer.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.packed_len());
+
+        // Writing to a `Vec` can't fail.
+        self.pack(&mut bytes).unwrap();
+
+        bytes
+    }
+
+    /// Unpacks a message from its packed bytes.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, PackableError> {
+        Self::unpack(&mut Cursor::new(bytes))
+    }
 }
 
 impl Packable for Message {
@@ -113,7 +131,7 @@ impl Vertex for Message {
     }
 }
 
-#[derive(Default)]
+#[derive(Clone, Default)]
 pub struct MessageBuilder {
     parent1: Option<MessageId>,
     parent2: Option<MessageId>,
@@ -141,10 +159,18 @@ impl MessageBuilder {
     }
 
     pub fn build(self) -> Result<Message, Error> {
+        let parent1 = self.parent1.ok_or(Error::MissingField("parent1"))?;
+        let parent2 = self.parent2.ok_or(Error::MissingField("parent2"))?;
+        let payload = self.payload.ok_or(Error::MissingField("payload"))?;
+
+        if !payload.packed_len_is_within_bounds() {
+            return Err(Error::CountError);
+        }
+
         Ok(Message {
-            parent1: self.parent1.ok_or(Error::MissingField("parent1"))?,
-            parent2: self.parent2.ok_or(Error::MissingField("parent2"))?,
-            payload: self.payload.ok_or(Error::MissingField("payload"))?,
+            parent1,
+            parent2,
+            payload,
             // TODO PoW
             nonce: 0,
         })
@@ -9,11 +9,11 @@
 // an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and limitations under the License.
 
-use bee_common_ext::packable::{Error as PackableError, Packable, Read, Write};
+use bee_common_ext::packable::{read_bytes, Error as PackableError, Packable, Read, Write};
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
 pub struct Indexation {
     index: String,
     data: Box<[u8]>,
@@ -45,12 +45,10 @@ impl Packable for Indexation {
         Self: Sized,
     {
         let index_len = u32::unpack(buf)? as usize;
-        let mut index_bytes = vec![0u8; index_len];
-        buf.read_exact(&mut index_bytes)?;
+        let index_bytes = read_bytes(buf, index_len)?;
 
         let data_len = u32::unpack(buf)? as usize;
-        let mut data_bytes = vec![0u8; data_len];
-        buf.read_exact(&mut data_bytes)?;
+        let data_bytes = read_bytes(buf, data_len)?;
 
         Ok(Self {
             index: String::from_utf8(index_bytes).map_err(|_| PackableError::InvalidUtf8String)?,
@@ -24,13 +24,23 @@ use serde::{Deserialize, Serialize};
 
 use alloc::boxed::Box;
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+/// Maximum packed length of a payload, type included.
+pub const MAX_PAYLOAD_LEN: u32 = 32768;
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub enum Payload {
     Transaction(Box<Transaction>),
     Milestone(Box<Milestone>),
     Indexation(Box<Indexation>),
 }
 
+impl Payload {
+    /// Whether the packed payload fits in `MAX_PAYLOAD_LEN` bytes.
+    pub fn packed_len_is_within_bounds(&self) -> bool {
+        self.packed_len() <= MAX_PAYLOAD_LEN as usize
+    }
+}
+
 impl Packable for Payload {
     fn packed_len(&self) -> usize {
         match self {
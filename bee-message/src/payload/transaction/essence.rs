@@ -11,8 +11,8 @@
 
 use crate::{
     payload::{
-        transaction::{input::Input, output::Output},
-        Payload,
+        transaction::{constants::INPUT_OUTPUT_COUNT_RANGE, input::Input, output::Output},
+        Indexation, Payload, MAX_PAYLOAD_LEN,
     },
     Error,
 };
@@ -24,7 +24,7 @@ use serde::{Deserialize, Serialize};
 use alloc::vec::Vec;
 
 // TODO remove pub(crate)
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
 pub struct TransactionEssence {
     pub(crate) inputs: Box<[Input]>,
     pub(crate) outputs: Box<[Output]>,
@@ -47,6 +47,11 @@ impl TransactionEssence {
     pub fn payload(&self) -> &Option<Payload> {
         &self.payload
     }
+
+    /// Whether the packed essence fits in `MAX_PAYLOAD_LEN` bytes, as the transaction payload carrying it must.
+    pub fn packed_len_is_within_bounds(&self) -> bool {
+        self.packed_len() <= MAX_PAYLOAD_LEN as usize
+    }
 }
 
 impl Packable for TransactionEssence {
@@ -106,7 +111,12 @@ impl Packable for TransactionEssence {
 
         let payload_len = u32::unpack(buf)? as usize;
         let payload = if payload_len > 0 {
-            let payload = Payload::unpack(buf)?;
+            // Only indexation payloads are allowed here. Checking the type before unpacking also keeps nested
+            // transactions from exhausting the stack.
+            let payload = match u32::unpack(buf)? {
+                2 => Payload::Indexation(Box::new(Indexation::unpack(buf)?)),
+                _ => return Err(PackableError::InvalidType),
+            };
             if payload_len != payload.packed_len() {
                 return Err(PackableError::InvalidAnnouncedLen);
             }
@@ -124,7 +134,7 @@ impl Packable for TransactionEssence {
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Clone, Debug, Default)]
 pub struct TransactionEssenceBuilder {
     inputs: Vec<Input>,
     outputs: Vec<Output>,
@@ -156,14 +166,35 @@ impl TransactionEssenceBuilder {
             return Err(Error::NoInput);
         }
 
+        // Inputs Count must be 0 < x <= 127
+        if !INPUT_OUTPUT_COUNT_RANGE.contains(&self.inputs.len()) {
+            return Err(Error::CountError);
+        }
+
         if self.outputs.is_empty() {
             return Err(Error::NoOutput);
         }
 
-        Ok(TransactionEssence {
+        // Outputs Count must be 0 < x <= 127
+        if !INPUT_OUTPUT_COUNT_RANGE.contains(&self.outputs.len()) {
+            return Err(Error::CountError);
+        }
+
+        // Only indexation payloads are allowed, as enforced when unpacking.
+        if !matches!(self.payload, None | Some(Payload::Indexation(_))) {
+            return Err(Error::InvalidPayloadKind);
+        }
+
+        let essence = TransactionEssence {
             inputs: self.inputs.into_boxed_slice(),
             outputs: self.outputs.into_boxed_slice(),
             payload: self.payload,
-        })
+        };
+
+        if !essence.packed_len_is_within_bounds() {
+            return Err(Error::CountError);
+        }
+
+        Ok(essence)
     }
 }
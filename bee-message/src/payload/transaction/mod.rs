@@ -24,7 +24,7 @@ pub use essence::TransactionEssence;
 pub use input::{Input, UTXOInput};
 pub use output::{Address, Ed25519Address, Output, SignatureLockedSingleOutput, WotsAddress};
 pub use transaction_id::TransactionId;
-pub use unlock::{Ed25519Signature, ReferenceUnlock, SignatureUnlock, UnlockBlock, WotsSignature};
+pub use unlock::{Ed25519Signature, ReferenceUnlock, SignatureType, SignatureUnlock, UnlockBlock, WotsSignature};
 
 use bee_common_ext::packable::{Error as PackableError, Packable, Read, Write};
 pub use bee_signing_ext::Seed;
@@ -38,7 +38,7 @@ use serde::{Deserialize, Serialize};
 use alloc::vec::Vec;
 use core::{cmp::Ordering, slice::Iter};
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
 pub struct Transaction {
     pub essence: TransactionEssence,
     // TODO Box
@@ -253,6 +253,37 @@ impl Transaction {
 
         Ok(())
     }
+
+    /// Checks that every input is unlocked by a signature of the type required by the address of the output it
+    /// consumes. The inputs only reference their outputs, so `consumed_addresses` holds these addresses, in the
+    /// order of the inputs.
+    pub fn validate_signature_types(&self, consumed_addresses: &[Address]) -> Result<(), Error> {
+        if consumed_addresses.len() != self.essence.inputs().len()
+            || self.unlock_blocks.len() != self.essence.inputs().len()
+        {
+            return Err(Error::CountError);
+        }
+
+        for (block, address) in self.unlock_blocks.iter().zip(consumed_addresses) {
+            // A reference unlock block unlocks with the signature it references.
+            let signature = match block {
+                UnlockBlock::Signature(signature) => signature,
+                UnlockBlock::Reference(reference) => match self.unlock_blocks.get(reference.index() as usize) {
+                    Some(UnlockBlock::Signature(signature)) => signature,
+                    _ => return Err(Error::InvalidIndex),
+                },
+            };
+
+            if signature.signature_type() != address.signature_type() {
+                return Err(Error::SignatureTypeMismatch(
+                    address.signature_type(),
+                    signature.signature_type(),
+                ));
+            }
+        }
+
+        Ok(())
+    }
 }
 
 #[allow(dead_code)]
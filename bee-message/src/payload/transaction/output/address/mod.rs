@@ -15,6 +15,8 @@ mod wots;
 pub use ed25519::Ed25519Address;
 pub use wots::WotsAddress;
 
+use crate::payload::transaction::SignatureType;
+
 use bee_common_ext::packable::{Error as PackableError, Packable, Read, Write};
 
 use serde::{Deserialize, Serialize};
@@ -46,6 +48,14 @@ impl Address {
             Address::Ed25519(address) => address.to_bech32(),
         }
     }
+
+    /// Returns the type of signature an unlock block needs to provide to spend from this address.
+    pub fn signature_type(&self) -> SignatureType {
+        match self {
+            Address::Wots(_) => SignatureType::Wots,
+            Address::Ed25519(_) => SignatureType::Ed25519,
+        }
+    }
 }
 
 impl Packable for Address {
@@ -17,7 +17,7 @@ use serde::{Deserialize, Serialize};
 
 use core::num::NonZeroU64;
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
 pub struct SignatureLockedSingleOutput {
     address: Address,
     amount: NonZeroU64,
@@ -54,12 +54,8 @@ impl Packable for SignatureLockedSingleOutput {
         Self: Sized,
     {
         let address = Address::unpack(buf)?;
-        let amount = u64::unpack(buf)?;
+        let amount = NonZeroU64::new(u64::unpack(buf)?).ok_or(PackableError::InvalidAmount)?;
 
-        // TODO unwrap
-        Ok(Self {
-            address,
-            amount: NonZeroU64::new(amount).unwrap(),
-        })
+        Ok(Self { address, amount })
     }
 }
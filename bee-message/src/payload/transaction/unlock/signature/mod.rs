@@ -19,6 +19,24 @@ use bee_common_ext::packable::{Error as PackableError, Packable, Read, Write};
 
 use serde::{Deserialize, Serialize};
 
+use core::fmt;
+
+/// The signature scheme an address is locked with, and which its unlock block must therefore use.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SignatureType {
+    Wots,
+    Ed25519,
+}
+
+impl fmt::Display for SignatureType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SignatureType::Wots => write!(f, "WOTS"),
+            SignatureType::Ed25519 => write!(f, "Ed25519"),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
 pub enum SignatureUnlock {
     Wots(WotsSignature),
@@ -37,6 +55,15 @@ impl From<Ed25519Signature> for SignatureUnlock {
     }
 }
 
+impl SignatureUnlock {
+    pub fn signature_type(&self) -> SignatureType {
+        match self {
+            Self::Wots(_) => SignatureType::Wots,
+            Self::Ed25519(_) => SignatureType::Ed25519,
+        }
+    }
+}
+
 impl Packable for SignatureUnlock {
     fn packed_len(&self) -> usize {
         match self {
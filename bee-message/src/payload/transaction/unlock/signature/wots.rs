@@ -11,7 +11,7 @@
 
 use crate::Error;
 
-use bee_common_ext::packable::{Error as PackableError, Packable, Read, Write};
+use bee_common_ext::packable::{read_bytes, Error as PackableError, Packable, Read, Write};
 use bee_ternary::{T5B1Buf, TritBuf};
 
 use bytemuck::cast_slice;
@@ -66,9 +66,7 @@ impl Packable for WotsSignature {
         Self: Sized,
     {
         let bytes_len = u32::unpack(buf)? as usize;
-        let mut bytes = vec![0u8; bytes_len];
-        buf.read_exact(&mut bytes)?;
 
-        Ok(Self(bytes))
+        Ok(Self(read_bytes(buf, bytes_len)?))
     }
 }
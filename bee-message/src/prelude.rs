@@ -13,10 +13,10 @@ pub use crate::{
     payload::{
         transaction::{
             Address, Ed25519Address, Ed25519Signature, Input, Output, ReferenceUnlock, Seed,
-            SignatureLockedSingleOutput, SignatureUnlock, TransactionBuilder, TransactionEssence, TransactionId,
-            UTXOInput, UnlockBlock, WotsAddress, WotsSignature,
+            SignatureLockedSingleOutput, SignatureType, SignatureUnlock, TransactionBuilder, TransactionEssence,
+            TransactionId, UTXOInput, UnlockBlock, WotsAddress, WotsSignature,
         },
-        Indexation, Milestone, Payload, Transaction,
+        Indexation, Milestone, Payload, Transaction, MAX_PAYLOAD_LEN,
     },
     Error, Message, MessageBuilder, MessageId, Vertex,
 };
@@ -0,0 +1,151 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use bee_common_ext::packable::{Error as PackableError, Packable};
+use bee_message::prelude::{
+    Address, Ed25519Address, Indexation, Input, Message, Output, Payload, SignatureLockedSingleOutput,
+    TransactionEssence, TransactionId, UTXOInput, UnlockBlock,
+};
+
+use rand::Rng;
+
+use std::num::NonZeroU64;
+
+const ITERATIONS: usize = 1000;
+
+fn essence_bytes() -> Vec<u8> {
+    let essence = TransactionEssence::builder()
+        .add_input(Input::UTXO(UTXOInput::new(TransactionId::new([0x11; 32]), 0).unwrap()))
+        .add_output(Output::SignatureLockedSingle(SignatureLockedSingleOutput::new(
+            Address::Ed25519(Ed25519Address::new([0x22; 32])),
+            NonZeroU64::new(1_000).unwrap(),
+        )))
+        .with_payload(Payload::Indexation(Box::new(Indexation::new(
+            "index".to_owned(),
+            Box::new([0x33; 16]),
+        ))))
+        .finish()
+        .unwrap();
+    let mut bytes = Vec::new();
+
+    essence.pack(&mut bytes).unwrap();
+
+    bytes
+}
+
+// Only checks that none of the unpackers panic, whatever they are fed with.
+fn unpack_all(bytes: &[u8]) {
+    let _ = TransactionEssence::unpack(&mut &bytes[..]);
+    let _ = Payload::unpack(&mut &bytes[..]);
+    let _ = UnlockBlock::unpack(&mut &bytes[..]);
+    let _ = Message::unpack(&mut &bytes[..]);
+}
+
+#[test]
+fn valid_essence_round_trip() {
+    let bytes = essence_bytes();
+    let essence = TransactionEssence::unpack(&mut bytes.as_slice()).unwrap();
+
+    assert_eq!(essence.packed_len(), bytes.len());
+}
+
+#[test]
+fn fuzz_random_bytes() {
+    let mut rng = rand::thread_rng();
+
+    for _ in 0..ITERATIONS {
+        let len = rng.gen_range(0, 1024);
+        let bytes: Vec<u8> = (0..len).map(|_| rng.gen()).collect();
+
+        unpack_all(&bytes);
+    }
+}
+
+#[test]
+fn fuzz_mutated_essence() {
+    let mut rng = rand::thread_rng();
+    let valid = essence_bytes();
+
+    for _ in 0..ITERATIONS {
+        let mut bytes = valid.clone();
+
+        for _ in 0..rng.gen_range(1, 8) {
+            let position = rng.gen_range(0, bytes.len());
+            bytes[position] = rng.gen();
+        }
+
+        if rng.gen_bool(0.3) {
+            bytes.truncate(rng.gen_range(0, bytes.len()));
+        }
+
+        unpack_all(&bytes);
+    }
+}
+
+#[test]
+fn fuzz_truncated_essence() {
+    let valid = essence_bytes();
+
+    for len in 0..valid.len() {
+        assert!(TransactionEssence::unpack(&mut &valid[..len]).is_err());
+    }
+}
+
+#[test]
+fn zero_amount_output() {
+    let mut bytes = vec![0u8, 1u8];
+    bytes.extend_from_slice(&[0x22; 32]);
+    bytes.extend_from_slice(&0u64.to_le_bytes());
+
+    match Output::unpack(&mut bytes.as_slice()) {
+        Err(PackableError::InvalidAmount) => (),
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn oversized_wots_signature_length() {
+    let mut bytes = vec![0u8, 0u8];
+    bytes.extend_from_slice(&u32::MAX.to_le_bytes());
+    bytes.extend_from_slice(&[0x44; 16]);
+
+    match UnlockBlock::unpack(&mut bytes.as_slice()) {
+        Err(PackableError::Io(_)) => (),
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn oversized_indexation_length() {
+    let mut bytes = 2u32.to_le_bytes().to_vec();
+    bytes.extend_from_slice(&u32::MAX.to_le_bytes());
+    bytes.extend_from_slice(b"index");
+
+    match Payload::unpack(&mut bytes.as_slice()) {
+        Err(PackableError::Io(_)) => (),
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn nested_transaction_payload_in_essence() {
+    let mut bytes = essence_bytes();
+    let payload_type = bytes.len()
+        - Payload::Indexation(Box::new(Indexation::new("index".to_owned(), Box::new([0x33; 16])))).packed_len();
+
+    // Turn the indexation payload of the essence into a transaction payload.
+    bytes[payload_type..payload_type + 4].copy_from_slice(&0u32.to_le_bytes());
+
+    match TransactionEssence::unpack(&mut bytes.as_slice()) {
+        Err(PackableError::InvalidType) => (),
+        _ => unreachable!(),
+    }
+}
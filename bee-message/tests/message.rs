@@ -0,0 +1,192 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use bee_common_ext::packable::Packable;
+use bee_message::prelude::{
+    Address, Ed25519Address, Ed25519Signature, Error, Indexation, Input, Message, MessageId, Milestone, Output,
+    Payload, SignatureLockedSingleOutput, SignatureUnlock, Transaction, TransactionEssence, TransactionId, UTXOInput,
+    UnlockBlock, MAX_PAYLOAD_LEN,
+};
+
+use std::num::NonZeroU64;
+
+fn indexation() -> Payload {
+    Payload::Indexation(Box::new(Indexation::new("index".to_owned(), Box::new([0x33; 16]))))
+}
+
+fn milestone() -> Payload {
+    Payload::Milestone(Box::new(Milestone::new(
+        42,
+        1_600_000_000,
+        Box::new([0x44; 64]),
+        vec![Box::new([0x55; 64]), Box::new([0x66; 64])],
+    )))
+}
+
+fn transaction() -> Payload {
+    let essence = TransactionEssence::builder()
+        .add_input(Input::UTXO(UTXOInput::new(TransactionId::new([0x11; 32]), 0).unwrap()))
+        .add_output(Output::SignatureLockedSingle(SignatureLockedSingleOutput::new(
+            Address::Ed25519(Ed25519Address::new([0x22; 32])),
+            NonZeroU64::new(1_000).unwrap(),
+        )))
+        .with_payload(indexation())
+        .finish()
+        .unwrap();
+
+    Payload::Transaction(Box::new(Transaction {
+        essence,
+        unlock_blocks: vec![UnlockBlock::Signature(SignatureUnlock::Ed25519(Ed25519Signature::new(
+            [0x77; 32],
+            Box::new([0x88; 64]),
+        )))],
+    }))
+}
+
+fn message(payload: Payload) -> Message {
+    Message::builder()
+        .parent1(MessageId::new([0x01; 32]))
+        .parent2(MessageId::new([0x02; 32]))
+        .payload(payload)
+        .build()
+        .unwrap()
+}
+
+fn round_trip(payload: Payload) {
+    let message = message(payload);
+    let bytes = message.to_bytes();
+    let unpacked = Message::from_bytes(&bytes).unwrap();
+
+    assert_eq!(message.parent1(), unpacked.parent1());
+    assert_eq!(message.parent2(), unpacked.parent2());
+    assert_eq!(message.nonce(), unpacked.nonce());
+    assert_eq!(unpacked.to_bytes(), bytes);
+}
+
+#[test]
+fn round_trip_indexation() {
+    round_trip(indexation());
+}
+
+#[test]
+fn round_trip_milestone() {
+    round_trip(milestone());
+}
+
+#[test]
+fn round_trip_transaction() {
+    round_trip(transaction());
+}
+
+#[test]
+fn from_bytes_truncated() {
+    let bytes = message(indexation()).to_bytes();
+
+    assert!(Message::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+}
+
+fn clone(payload: Payload) {
+    let message = message(payload);
+    let cloned = message.clone();
+
+    assert_eq!(message, cloned);
+    assert_eq!(message.to_bytes(), cloned.to_bytes());
+}
+
+#[test]
+fn clone_indexation() {
+    clone(indexation());
+}
+
+#[test]
+fn clone_milestone() {
+    clone(milestone());
+}
+
+#[test]
+fn clone_transaction() {
+    clone(transaction());
+}
+
+#[test]
+fn clone_transaction_is_independent() {
+    let original = match transaction() {
+        Payload::Transaction(transaction) => transaction,
+        _ => unreachable!(),
+    };
+    let mut cloned = original.clone();
+
+    cloned.unlock_blocks.clear();
+
+    assert_eq!(original.unlock_blocks.len(), 1);
+    assert_ne!(original, cloned);
+}
+
+#[test]
+fn clone_builder_is_independent() {
+    let builder = Message::builder()
+        .parent1(MessageId::new([0x01; 32]))
+        .parent2(MessageId::new([0x02; 32]));
+    let cloned = builder.clone().parent2(MessageId::new([0x03; 32])).payload(milestone());
+    let original = builder.payload(indexation()).build().unwrap();
+    let cloned = cloned.build().unwrap();
+
+    assert_eq!(original.parent2(), &MessageId::new([0x02; 32]));
+    assert_eq!(cloned.parent2(), &MessageId::new([0x03; 32]));
+    assert_eq!(original.payload(), &indexation());
+    assert_eq!(cloned.payload(), &milestone());
+}
+
+// An indexation payload whose packed length is `MAX_PAYLOAD_LEN` plus `delta` bytes.
+fn indexation_around_limit(delta: isize) -> Payload {
+    let overhead = Payload::Indexation(Box::new(Indexation::new("index".to_owned(), Box::new([])))).packed_len();
+    let data_len = (MAX_PAYLOAD_LEN as isize - overhead as isize + delta) as usize;
+    let payload = Payload::Indexation(Box::new(Indexation::new(
+        "index".to_owned(),
+        vec![0x33; data_len].into_boxed_slice(),
+    )));
+
+    assert_eq!(payload.packed_len() as isize, MAX_PAYLOAD_LEN as isize + delta);
+
+    payload
+}
+
+fn build(payload: Payload) -> Result<Message, Error> {
+    Message::builder()
+        .parent1(MessageId::new([0x01; 32]))
+        .parent2(MessageId::new([0x02; 32]))
+        .payload(payload)
+        .build()
+}
+
+#[test]
+fn payload_below_max_len() {
+    let payload = indexation_around_limit(-1);
+
+    assert!(payload.packed_len_is_within_bounds());
+    round_trip(payload);
+}
+
+#[test]
+fn payload_at_max_len() {
+    let payload = indexation_around_limit(0);
+
+    assert!(payload.packed_len_is_within_bounds());
+    round_trip(payload);
+}
+
+#[test]
+fn payload_above_max_len() {
+    let payload = indexation_around_limit(1);
+
+    assert!(!payload.packed_len_is_within_bounds());
+    assert!(matches!(build(payload), Err(Error::CountError)));
+}
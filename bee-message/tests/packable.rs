@@ -43,4 +43,26 @@ mod tests {
         // assert_eq!(msg.payload(), msg_unpacked.payload());
         // TODO check payload
     }
+
+    #[test]
+    fn packed_len_matches_packed_bytes() {
+        let payload = Payload::Indexation(Box::new(Indexation::new("index".to_owned(), vec![0u8; 1000].into())));
+        let msg = Message::builder()
+            .parent1(MessageId::new([0x11; 32]))
+            .parent2(MessageId::new([0x22; 32]))
+            .payload(payload.clone())
+            .build()
+            .unwrap();
+
+        let mut buf = vec![];
+        payload.pack(&mut buf).unwrap();
+        assert_eq!(payload.packed_len(), buf.len());
+        // Variant, index length prefix, index, data length prefix and data.
+        assert_eq!(payload.packed_len(), 4 + 4 + 5 + 4 + 1000);
+
+        let bytes = msg.to_bytes();
+        assert_eq!(bytes.len(), msg.packed_len());
+        // The buffer was allocated once with the exact size, without growing while packing.
+        assert_eq!(bytes.capacity(), msg.packed_len());
+    }
 }
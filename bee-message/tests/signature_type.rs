@@ -0,0 +1,96 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use bee_message::prelude::*;
+use bee_ternary::{T5B1Buf, TritBuf};
+
+use std::num::NonZeroU64;
+
+fn ed25519_address() -> Address {
+    Ed25519Address::new([1; 32]).into()
+}
+
+fn wots_address() -> Address {
+    WotsAddress::new(&TritBuf::<T5B1Buf>::zeros(243)).unwrap().into()
+}
+
+fn ed25519_unlock() -> UnlockBlock {
+    SignatureUnlock::from(Ed25519Signature::new([2; 32], Box::new([3; 64]))).into()
+}
+
+fn wots_unlock() -> UnlockBlock {
+    SignatureUnlock::from(WotsSignature::new(&TritBuf::<T5B1Buf>::zeros(6561)).unwrap()).into()
+}
+
+fn transaction(inputs: usize, unlock_blocks: Vec<UnlockBlock>) -> Transaction {
+    let mut essence = TransactionEssence::builder().add_output(Output::from(SignatureLockedSingleOutput::new(
+        ed25519_address(),
+        NonZeroU64::new(100).unwrap(),
+    )));
+
+    for index in 0..inputs {
+        essence = essence.add_input(UTXOInput::new(TransactionId::new([index as u8; 32]), 0).unwrap().into());
+    }
+
+    Transaction {
+        essence: essence.finish().unwrap(),
+        unlock_blocks,
+    }
+}
+
+#[test]
+fn address_signature_type() {
+    assert_eq!(ed25519_address().signature_type(), SignatureType::Ed25519);
+    assert_eq!(wots_address().signature_type(), SignatureType::Wots);
+}
+
+#[test]
+fn matching_signature_types() {
+    let transaction = transaction(2, vec![ed25519_unlock(), wots_unlock()]);
+
+    assert!(transaction
+        .validate_signature_types(&[ed25519_address(), wots_address()])
+        .is_ok());
+}
+
+#[test]
+fn mismatching_signature_types() {
+    let transaction = transaction(2, vec![ed25519_unlock(), wots_unlock()]);
+
+    match transaction.validate_signature_types(&[wots_address(), wots_address()]) {
+        Err(Error::SignatureTypeMismatch(SignatureType::Wots, SignatureType::Ed25519)) => {}
+        r => panic!("Unexpected result: {:?}.", r),
+    }
+}
+
+#[test]
+fn reference_unlocks_with_referenced_signature_type() {
+    let transaction = transaction(2, vec![ed25519_unlock(), ReferenceUnlock::new(0).unwrap().into()]);
+
+    assert!(transaction
+        .validate_signature_types(&[ed25519_address(), ed25519_address()])
+        .is_ok());
+
+    match transaction.validate_signature_types(&[ed25519_address(), wots_address()]) {
+        Err(Error::SignatureTypeMismatch(SignatureType::Wots, SignatureType::Ed25519)) => {}
+        r => panic!("Unexpected result: {:?}.", r),
+    }
+}
+
+#[test]
+fn consumed_addresses_count_mismatch() {
+    let transaction = transaction(2, vec![ed25519_unlock(), wots_unlock()]);
+
+    assert!(matches!(
+        transaction.validate_signature_types(&[ed25519_address()]),
+        Err(Error::CountError)
+    ));
+}
@@ -0,0 +1,148 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use bee_common_ext::packable::{Error as PackableError, Packable};
+use bee_message::prelude::*;
+
+use std::num::NonZeroU64;
+
+fn input(index: usize) -> Input {
+    UTXOInput::new(TransactionId::new([index as u8; 32]), 0).unwrap().into()
+}
+
+fn output() -> Output {
+    SignatureLockedSingleOutput::new(Ed25519Address::new([1; 32]).into(), NonZeroU64::new(100).unwrap()).into()
+}
+
+fn essence(inputs: usize, outputs: usize) -> Result<TransactionEssence, Error> {
+    let mut builder = TransactionEssence::builder();
+
+    for index in 0..inputs {
+        builder = builder.add_input(input(index));
+    }
+    for _ in 0..outputs {
+        builder = builder.add_output(output());
+    }
+
+    builder.finish()
+}
+
+#[test]
+fn max_inputs() {
+    assert_eq!(essence(127, 1).unwrap().inputs().len(), 127);
+}
+
+#[test]
+fn max_outputs() {
+    assert_eq!(essence(1, 127).unwrap().outputs().len(), 127);
+}
+
+#[test]
+fn max_inputs_and_outputs() {
+    assert!(essence(127, 127).is_ok());
+}
+
+#[test]
+fn too_many_inputs() {
+    assert!(matches!(essence(128, 1), Err(Error::CountError)));
+    assert!(matches!(essence(256, 1), Err(Error::CountError)));
+}
+
+#[test]
+fn too_many_outputs() {
+    assert!(matches!(essence(1, 128), Err(Error::CountError)));
+    assert!(matches!(essence(1, 256), Err(Error::CountError)));
+}
+
+#[test]
+fn no_input_or_output() {
+    assert!(matches!(essence(0, 1), Err(Error::NoInput)));
+    assert!(matches!(essence(1, 0), Err(Error::NoOutput)));
+}
+
+// An essence with a single input and output whose packed length is `MAX_PAYLOAD_LEN` plus `delta` bytes, padded by the
+// data of its indexation payload.
+fn essence_around_limit(delta: isize) -> Result<TransactionEssence, Error> {
+    let with_data = |data_len: usize| {
+        TransactionEssence::builder()
+            .add_input(input(0))
+            .add_output(output())
+            .with_payload(Payload::Indexation(Box::new(Indexation::new(
+                "index".to_owned(),
+                vec![0; data_len].into_boxed_slice(),
+            ))))
+            .finish()
+    };
+    let overhead = with_data(0).unwrap().packed_len();
+
+    with_data((MAX_PAYLOAD_LEN as isize - overhead as isize + delta) as usize)
+}
+
+#[test]
+fn essence_below_max_len() {
+    let essence = essence_around_limit(-1).unwrap();
+
+    assert!(essence.packed_len_is_within_bounds());
+    assert_eq!(essence.packed_len(), MAX_PAYLOAD_LEN as usize - 1);
+}
+
+#[test]
+fn essence_at_max_len() {
+    let essence = essence_around_limit(0).unwrap();
+
+    assert!(essence.packed_len_is_within_bounds());
+    assert_eq!(essence.packed_len(), MAX_PAYLOAD_LEN as usize);
+}
+
+#[test]
+fn essence_above_max_len() {
+    assert!(matches!(essence_around_limit(1), Err(Error::CountError)));
+}
+
+fn milestone_payload() -> Payload {
+    Payload::Milestone(Box::new(Milestone::new(
+        0,
+        0,
+        vec![0; 64].into_boxed_slice(),
+        vec![vec![0; 64].into_boxed_slice()],
+    )))
+}
+
+#[test]
+fn non_indexation_payload_rejected() {
+    let essence = TransactionEssence::builder()
+        .add_input(input(0))
+        .add_output(output())
+        .with_payload(milestone_payload())
+        .finish();
+
+    assert!(matches!(essence, Err(Error::InvalidPayloadKind)));
+}
+
+#[test]
+fn non_indexation_payload_rejected_on_unpack() {
+    let essence = essence(1, 1).unwrap();
+    let payload = milestone_payload();
+
+    let mut bytes = Vec::with_capacity(essence.packed_len() + payload.packed_len());
+    essence.pack(&mut bytes).unwrap();
+    assert_eq!(TransactionEssence::unpack(&mut bytes.as_slice()).unwrap(), essence);
+
+    // Replace the empty payload length with the milestone payload the builder refuses.
+    bytes.truncate(bytes.len() - 4);
+    (payload.packed_len() as u32).pack(&mut bytes).unwrap();
+    payload.pack(&mut bytes).unwrap();
+
+    assert!(matches!(
+        TransactionEssence::unpack(&mut bytes.as_slice()),
+        Err(PackableError::InvalidType)
+    ));
+}
@@ -0,0 +1,110 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+// Fans transactions out to the writer channels of the peers, as the broadcaster does, with the messages either copied
+// for every peer or shared by all of them.
+
+#[macro_use]
+extern crate criterion;
+
+use bytes::Bytes;
+use criterion::{BatchSize, Criterion};
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+const PEERS: usize = 10;
+// Transactions broadcast in a second at the target rate.
+const TRANSACTIONS: usize = 1000;
+// Size of a transaction message, header included.
+const MESSAGE_LEN: usize = 1607;
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn messages() -> Vec<Vec<u8>> {
+    (0..TRANSACTIONS).map(|i| vec![i as u8; MESSAGE_LEN]).collect()
+}
+
+fn fan_out<T: Clone>(messages: Vec<Vec<u8>>, convert: impl Fn(Vec<u8>) -> T) {
+    let channels = (0..PEERS).map(|_| flume::unbounded::<T>()).collect::<Vec<_>>();
+
+    for message in messages {
+        let message = convert(message);
+
+        for (sender, _) in &channels {
+            sender.send(message.clone()).unwrap();
+        }
+    }
+
+    for (_, receiver) in &channels {
+        while let Ok(message) = receiver.try_recv() {
+            criterion::black_box(message);
+        }
+    }
+}
+
+fn broadcast_copied(messages: Vec<Vec<u8>>) {
+    fan_out(messages, |message| message)
+}
+
+fn broadcast_shared(messages: Vec<Vec<u8>>) {
+    fan_out(messages, Bytes::from)
+}
+
+fn count_allocations(name: &str, broadcast: fn(Vec<Vec<u8>>)) {
+    let messages = messages();
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+
+    broadcast(messages);
+
+    println!(
+        "{}: {} allocations for {} transactions to {} peers.",
+        name,
+        ALLOCATIONS.load(Ordering::Relaxed) - before,
+        TRANSACTIONS,
+        PEERS
+    );
+}
+
+fn bench_broadcast(c: &mut Criterion) {
+    count_allocations("copied", broadcast_copied);
+    count_allocations("shared", broadcast_shared);
+
+    let mut group = c.benchmark_group("broadcast");
+    group.bench_function("copied", |b| {
+        b.iter_batched(messages, broadcast_copied, BatchSize::LargeInput)
+    });
+    group.bench_function("shared", |b| {
+        b.iter_batched(messages, broadcast_shared, BatchSize::LargeInput)
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_broadcast);
+criterion_main!(benches);
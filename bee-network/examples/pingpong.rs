@@ -147,7 +147,7 @@ async fn process_event(
             network
                 .send(SendMessage {
                     receiver_epid: epid,
-                    message: message.as_bytes(),
+                    message: message.as_bytes().into(),
                 })
                 .await
                 .expect("error sending message to peer");
@@ -230,7 +230,7 @@ fn spam_endpoint(mut network: Network, epid: EndpointId) {
             network
                 .send(SendMessage {
                     receiver_epid: epid,
-                    message: message.as_bytes(),
+                    message: message.as_bytes().into(),
                 })
                 .await
                 .expect("error sending number");
@@ -11,6 +11,8 @@
 
 use crate::endpoint::EndpointId;
 
+use bytes::Bytes;
+
 use std::fmt;
 
 pub type CommandSender = flume::Sender<Command>;
@@ -36,7 +38,8 @@ pub enum Command {
     },
     SendMessage {
         receiver_epid: EndpointId,
-        message: Vec<u8>,
+        // Reference counted, so that sending the same message to many endpoints doesn't copy it.
+        message: Bytes,
     },
     MarkDuplicate {
         duplicate_epid: EndpointId,
@@ -23,10 +23,15 @@ pub const DEFAULT_RECONNECT_INTERVAL: u64 = 60;
 
 /// Network configuration builder.
 #[derive(Default, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct NetworkConfigBuilder {
+    /// Port the node listens on for peer connections.
     binding_port: Option<u16>,
+    /// Address the node listens on for peer connections.
     binding_address: Option<IpAddr>,
+    /// Size in bytes of the buffer used to read from TCP connections.
     max_tcp_buffer_size: Option<usize>,
+    /// Interval in seconds between attempts to reconnect to disconnected peers.
     reconnect_interval: Option<u64>,
 }
 
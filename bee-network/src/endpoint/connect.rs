@@ -13,6 +13,8 @@ use super::{DataSender, EndpointId};
 
 use bee_common::worker::Error as WorkerError;
 
+use bytes::Bytes;
+
 use std::collections::{hash_map::Entry, HashMap};
 
 #[derive(Clone, Debug)]
@@ -74,7 +76,7 @@ impl ConnectedEndpointList {
         self.0.get(&epid).map_or(false, |v| v.duplicate_of.is_some())
     }
 
-    pub async fn send_message(&mut self, message: Vec<u8>, epid: EndpointId) -> Result<bool, WorkerError> {
+    pub async fn send_message(&mut self, message: Bytes, epid: EndpointId) -> Result<bool, WorkerError> {
         if let Some(connected_endpoint) = self.0.get_mut(&epid) {
             connected_endpoint
                 .data_sender
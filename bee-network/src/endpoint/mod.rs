@@ -19,6 +19,7 @@ pub use worker::*;
 
 use crate::util::TransportProtocol;
 
+use bytes::Bytes;
 use thiserror::Error;
 
 use std::{fmt, net::SocketAddr as SocketAddress};
@@ -58,8 +59,8 @@ impl fmt::Display for EndpointId {
     }
 }
 
-pub type DataSender = flume::Sender<Vec<u8>>;
-pub type DataReceiver = flume::Receiver<Vec<u8>>;
+pub type DataSender = flume::Sender<Bytes>;
+pub type DataReceiver = flume::Receiver<Bytes>;
 
 pub fn channel() -> (DataSender, DataReceiver) {
     flume::unbounded()
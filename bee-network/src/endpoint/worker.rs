@@ -24,6 +24,7 @@ use crate::{
 
 use bee_common::{shutdown::ShutdownListener, worker::Error as WorkerError};
 
+use bytes::Bytes;
 use futures::{select, FutureExt, StreamExt};
 use log::*;
 
@@ -376,7 +377,7 @@ async fn send_event_after_delay(event: Event, internal_event_sender: EventSender
 #[inline]
 async fn send_message(
     receiver_epid: EndpointId,
-    message: Vec<u8>,
+    message: Bytes,
     connected: &mut ConnectedEndpointList,
 ) -> Result<bool, WorkerError> {
     Ok(connected.send_message(message, receiver_epid).await?)
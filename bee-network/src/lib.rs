@@ -16,8 +16,9 @@ pub use config::{NetworkConfig, NetworkConfigBuilder};
 pub use endpoint::EndpointId;
 pub use event::{Event, EventReceiver};
 pub use tcp::Origin;
+pub use util::TransportProtocol;
 
-pub use network::Network;
+pub use network::{Error, Network};
 
 mod command;
 mod config;
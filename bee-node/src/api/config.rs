@@ -0,0 +1,75 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use serde::Deserialize;
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+const DEFAULT_BINDING_PORT: u16 = 14265;
+const DEFAULT_BINDING_ADDRESS: IpAddr = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
+const DEFAULT_MAX_BODY_SIZE: u64 = 64 * 1024;
+
+/// API configuration builder.
+#[derive(Default, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ApiConfigBuilder {
+    /// Port the API listens on.
+    binding_port: Option<u16>,
+    /// Address the API listens on.
+    binding_address: Option<IpAddr>,
+    /// Maximum size in bytes of a request body.
+    max_body_size: Option<u64>,
+}
+
+impl ApiConfigBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn binding_port(mut self, binding_port: u16) -> Self {
+        self.binding_port.replace(binding_port);
+        self
+    }
+
+    pub fn binding_address(mut self, binding_address: IpAddr) -> Self {
+        self.binding_address.replace(binding_address);
+        self
+    }
+
+    pub fn max_body_size(mut self, max_body_size: u64) -> Self {
+        self.max_body_size.replace(max_body_size);
+        self
+    }
+
+    /// Builds the API config.
+    pub fn finish(self) -> ApiConfig {
+        ApiConfig {
+            binding_address: SocketAddr::new(
+                self.binding_address.unwrap_or(DEFAULT_BINDING_ADDRESS),
+                self.binding_port.unwrap_or(DEFAULT_BINDING_PORT),
+            ),
+            max_body_size: self.max_body_size.unwrap_or(DEFAULT_MAX_BODY_SIZE),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ApiConfig {
+    pub(crate) binding_address: SocketAddr,
+    // In bytes.
+    pub(crate) max_body_size: u64,
+}
+
+impl ApiConfig {
+    pub fn build() -> ApiConfigBuilder {
+        ApiConfigBuilder::new()
+    }
+}
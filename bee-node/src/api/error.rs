@@ -0,0 +1,127 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use serde::Serialize;
+use warp::{
+    body::BodyDeserializeError,
+    http::StatusCode,
+    reject::{MethodNotAllowed, PayloadTooLarge, Reject},
+    Rejection, Reply,
+};
+
+use std::convert::Infallible;
+
+/// Errors returned by the API, each with a stable code that clients can match on.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum ApiError {
+    InvalidHash,
+    InvalidAddress,
+    InvalidMilestoneIndex,
+    InvalidTrytes,
+    InvalidTransaction,
+    InvalidBody,
+    PayloadTooLarge,
+    NotFound,
+    MethodNotAllowed,
+    Unavailable,
+}
+
+impl Reject for ApiError {}
+
+impl ApiError {
+    pub(crate) fn code(&self) -> &'static str {
+        match self {
+            Self::InvalidHash => "invalid_hash",
+            Self::InvalidAddress => "invalid_address",
+            Self::InvalidMilestoneIndex => "invalid_milestone_index",
+            Self::InvalidTrytes => "invalid_trytes",
+            Self::InvalidTransaction => "invalid_transaction",
+            Self::InvalidBody => "invalid_body",
+            Self::PayloadTooLarge => "payload_too_large",
+            Self::NotFound => "not_found",
+            Self::MethodNotAllowed => "method_not_allowed",
+            Self::Unavailable => "unavailable",
+        }
+    }
+
+    pub(crate) fn status(&self) -> StatusCode {
+        match self {
+            Self::InvalidHash
+            | Self::InvalidAddress
+            | Self::InvalidMilestoneIndex
+            | Self::InvalidTrytes
+            | Self::InvalidTransaction
+            | Self::InvalidBody => StatusCode::BAD_REQUEST,
+            Self::PayloadTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+            Self::NotFound => StatusCode::NOT_FOUND,
+            Self::MethodNotAllowed => StatusCode::METHOD_NOT_ALLOWED,
+            Self::Unavailable => StatusCode::SERVICE_UNAVAILABLE,
+        }
+    }
+
+    fn message(&self) -> &'static str {
+        match self {
+            Self::InvalidHash => "The hash is not made of 81 trytes.",
+            Self::InvalidAddress => "The address is not made of 81 trytes.",
+            Self::InvalidMilestoneIndex => "The milestone index is not a valid integer.",
+            Self::InvalidTrytes => "The transaction is not made of 2673 trytes.",
+            Self::InvalidTransaction => "The transaction trytes can't be decoded.",
+            Self::InvalidBody => "The request body is not valid JSON for this endpoint.",
+            Self::PayloadTooLarge => "The request body is too large.",
+            Self::NotFound => "The requested resource was not found.",
+            Self::MethodNotAllowed => "The method is not allowed for this resource.",
+            Self::Unavailable => "The node can't handle this request right now.",
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ErrorBody {
+    code: &'static str,
+    message: &'static str,
+}
+
+#[derive(Serialize)]
+struct ErrorResponse {
+    error: ErrorBody,
+}
+
+fn api_error(rejection: &Rejection) -> ApiError {
+    if rejection.is_not_found() {
+        ApiError::NotFound
+    } else if let Some(error) = rejection.find::<ApiError>() {
+        *error
+    } else if rejection.find::<PayloadTooLarge>().is_some() {
+        ApiError::PayloadTooLarge
+    } else if rejection.find::<BodyDeserializeError>().is_some() {
+        ApiError::InvalidBody
+    } else if rejection.find::<MethodNotAllowed>().is_some() {
+        ApiError::MethodNotAllowed
+    } else {
+        // Remaining rejections are about malformed requests, e.g. a missing header.
+        ApiError::InvalidBody
+    }
+}
+
+/// Turns every rejection into a JSON error body.
+pub(crate) async fn handle_rejection(rejection: Rejection) -> Result<impl Reply, Infallible> {
+    let error = api_error(&rejection);
+
+    Ok(warp::reply::with_status(
+        warp::reply::json(&ErrorResponse {
+            error: ErrorBody {
+                code: error.code(),
+                message: error.message(),
+            },
+        }),
+        error.status(),
+    ))
+}
@@ -0,0 +1,267 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use crate::{api::error::ApiError, constants::BEE_VERSION};
+
+use bee_common_ext::{channel, node::ResHandle};
+use bee_crypto::ternary::Hash;
+use bee_ledger::whiteflag::LedgerWorkerEvent;
+use bee_protocol::{tangle::MsTangle, MilestoneIndex, Protocol};
+use bee_storage::storage::Backend;
+use bee_ternary::{T1B1Buf, TritBuf, TryteBuf};
+use bee_transaction::bundled::{Address, BundledTransaction, BundledTransactionField, TRANSACTION_TRIT_LEN};
+
+use futures::channel::oneshot;
+use serde::{Deserialize, Serialize};
+use warp::{http::StatusCode, reject, Rejection, Reply};
+
+#[derive(Serialize)]
+struct InfoResponse {
+    name: &'static str,
+    version: &'static str,
+    is_synced: bool,
+    latest_milestone_index: u32,
+    latest_solid_milestone_index: u32,
+    snapshot_index: u32,
+    pruning_index: u32,
+}
+
+#[derive(Serialize)]
+struct MetadataResponse {
+    solid: bool,
+    tail: bool,
+    confirmed: bool,
+    conflicting: bool,
+    milestone_index: u32,
+}
+
+#[derive(Serialize)]
+struct TransactionResponse {
+    hash: String,
+    trytes: String,
+    metadata: Option<MetadataResponse>,
+}
+
+#[derive(Serialize)]
+struct MilestoneResponse {
+    index: u32,
+    hash: String,
+}
+
+#[derive(Serialize)]
+struct BalanceResponse {
+    address: String,
+    balance: u64,
+}
+
+#[derive(Serialize)]
+struct SpentResponse {
+    address: String,
+    spent: bool,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct SubmitTransactionRequest {
+    trytes: String,
+}
+
+pub(crate) fn parse_hash(trytes: &str) -> Result<Hash, ApiError> {
+    if trytes.len() != Hash::trit_len() / 3 {
+        return Err(ApiError::InvalidHash);
+    }
+
+    let trytes = TryteBuf::try_from_str(trytes).map_err(|_| ApiError::InvalidHash)?;
+
+    Ok(Hash::from_inner_unchecked(trytes.as_trits().encode::<T1B1Buf>()))
+}
+
+pub(crate) fn parse_address(trytes: &str) -> Result<Address, ApiError> {
+    if trytes.len() != Address::trit_len() / 3 {
+        return Err(ApiError::InvalidAddress);
+    }
+
+    let trytes = TryteBuf::try_from_str(trytes).map_err(|_| ApiError::InvalidAddress)?;
+
+    Ok(Address::from_inner_unchecked(trytes.as_trits().encode::<T1B1Buf>()))
+}
+
+pub(crate) fn parse_milestone_index(index: &str) -> Result<MilestoneIndex, ApiError> {
+    index
+        .parse::<u32>()
+        .map(MilestoneIndex)
+        .map_err(|_| ApiError::InvalidMilestoneIndex)
+}
+
+pub(crate) fn parse_transaction(trytes: &str) -> Result<BundledTransaction, ApiError> {
+    if trytes.len() != TRANSACTION_TRIT_LEN / 3 {
+        return Err(ApiError::InvalidTrytes);
+    }
+
+    let trytes = TryteBuf::try_from_str(trytes).map_err(|_| ApiError::InvalidTrytes)?;
+
+    BundledTransaction::from_trits(&trytes.as_trits().encode::<T1B1Buf>()).map_err(|_| ApiError::InvalidTransaction)
+}
+
+fn hash_trytes(hash: &Hash) -> String {
+    hash.iter_trytes().map(char::from).collect()
+}
+
+fn transaction_trytes(transaction: &BundledTransaction) -> String {
+    let mut trits = TritBuf::<T1B1Buf>::zeros(BundledTransaction::trit_len());
+
+    transaction.as_trits_allocated(&mut trits);
+
+    trits.iter_trytes().map(char::from).collect()
+}
+
+pub(crate) async fn info<B: Backend>(tangle: ResHandle<MsTangle<B>>) -> Result<impl Reply, Rejection> {
+    Ok(warp::reply::json(&InfoResponse {
+        name: "Bee",
+        version: BEE_VERSION,
+        is_synced: tangle.is_synced(),
+        latest_milestone_index: *tangle.get_latest_milestone_index(),
+        latest_solid_milestone_index: *tangle.get_latest_solid_milestone_index(),
+        snapshot_index: *tangle.get_snapshot_index(),
+        pruning_index: *tangle.get_pruning_index(),
+    }))
+}
+
+pub(crate) async fn transaction<B: Backend>(
+    hash: String,
+    tangle: ResHandle<MsTangle<B>>,
+) -> Result<impl Reply, Rejection> {
+    let hash = parse_hash(&hash).map_err(reject::custom)?;
+    let transaction = tangle
+        .get(&hash)
+        .await
+        .ok_or_else(|| reject::custom(ApiError::NotFound))?;
+    let metadata = tangle.get_metadata(&hash).map(|metadata| MetadataResponse {
+        solid: metadata.flags().is_solid(),
+        tail: metadata.flags().is_tail(),
+        confirmed: metadata.flags().is_confirmed(),
+        conflicting: metadata.flags().is_conflicting(),
+        milestone_index: *metadata.milestone_index(),
+    });
+
+    Ok(warp::reply::json(&TransactionResponse {
+        hash: hash_trytes(&hash),
+        trytes: transaction_trytes(&transaction),
+        metadata,
+    }))
+}
+
+pub(crate) async fn milestone<B: Backend>(
+    index: String,
+    tangle: ResHandle<MsTangle<B>>,
+) -> Result<impl Reply, Rejection> {
+    let index = parse_milestone_index(&index).map_err(reject::custom)?;
+    let hash = tangle
+        .get_milestone_hash(index)
+        .ok_or_else(|| reject::custom(ApiError::NotFound))?;
+
+    Ok(warp::reply::json(&MilestoneResponse {
+        index: *index,
+        hash: hash_trytes(&hash),
+    }))
+}
+
+// Sends a query to the ledger worker and waits for its answer.
+async fn query_ledger<T>(
+    ledger: &channel::Sender<LedgerWorkerEvent>,
+    event: impl FnOnce(oneshot::Sender<T>) -> LedgerWorkerEvent,
+) -> Result<T, Rejection> {
+    let (sender, receiver) = oneshot::channel();
+
+    ledger
+        .send(event(sender))
+        .map_err(|_| reject::custom(ApiError::Unavailable))?;
+
+    receiver.await.map_err(|_| reject::custom(ApiError::Unavailable))
+}
+
+pub(crate) async fn balance(
+    address: String,
+    ledger: channel::Sender<LedgerWorkerEvent>,
+) -> Result<impl Reply, Rejection> {
+    let parsed = parse_address(&address).map_err(reject::custom)?;
+    let balance = query_ledger(&ledger, |sender| LedgerWorkerEvent::GetBalance(parsed, sender)).await?;
+
+    Ok(warp::reply::json(&BalanceResponse { address, balance }))
+}
+
+pub(crate) async fn spent(
+    address: String,
+    ledger: channel::Sender<LedgerWorkerEvent>,
+) -> Result<impl Reply, Rejection> {
+    let parsed = parse_address(&address).map_err(reject::custom)?;
+    let spent = query_ledger(&ledger, |sender| LedgerWorkerEvent::IsSpent(parsed, sender)).await?;
+
+    Ok(warp::reply::json(&SpentResponse { address, spent }))
+}
+
+pub(crate) async fn submit_transaction(request: SubmitTransactionRequest) -> Result<impl Reply, Rejection> {
+    let transaction = parse_transaction(&request.trytes).map_err(reject::custom)?;
+
+    Protocol::submit_transaction(&transaction).map_err(|_| reject::custom(ApiError::Unavailable))?;
+
+    Ok(warp::reply::with_status(warp::reply(), StatusCode::ACCEPTED))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn parse_hash_valid() {
+        assert!(parse_hash(&"A".repeat(81)).is_ok());
+    }
+
+    #[test]
+    fn parse_hash_invalid() {
+        assert_eq!(parse_hash(&"A".repeat(80)), Err(ApiError::InvalidHash));
+        assert_eq!(parse_hash(&"a".repeat(81)), Err(ApiError::InvalidHash));
+    }
+
+    #[test]
+    fn parse_address_invalid() {
+        assert!(parse_address(&"A".repeat(81)).is_ok());
+        assert_eq!(parse_address(&"A".repeat(82)).err(), Some(ApiError::InvalidAddress));
+        assert_eq!(parse_address(&"a".repeat(81)).err(), Some(ApiError::InvalidAddress));
+    }
+
+    #[test]
+    fn parse_milestone_index_invalid() {
+        assert_eq!(parse_milestone_index("42").map(|index| *index), Ok(42));
+        assert_eq!(parse_milestone_index("-1"), Err(ApiError::InvalidMilestoneIndex));
+        assert_eq!(parse_milestone_index("latest"), Err(ApiError::InvalidMilestoneIndex));
+    }
+
+    #[test]
+    fn parse_transaction_round_trip() {
+        let trytes = "9".repeat(TRANSACTION_TRIT_LEN / 3);
+        let transaction = parse_transaction(&trytes).unwrap();
+
+        assert_eq!(transaction_trytes(&transaction), trytes);
+    }
+
+    #[test]
+    fn parse_transaction_invalid() {
+        assert_eq!(
+            parse_transaction(&"9".repeat(TRANSACTION_TRIT_LEN / 3 - 1)).err(),
+            Some(ApiError::InvalidTrytes)
+        );
+        assert_eq!(
+            parse_transaction(&"!".repeat(TRANSACTION_TRIT_LEN / 3)).err(),
+            Some(ApiError::InvalidTrytes)
+        );
+    }
+}
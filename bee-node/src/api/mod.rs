@@ -0,0 +1,347 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+//! Minimal HTTP API exposing core node queries.
+
+mod config;
+mod error;
+mod handlers;
+
+pub use config::{ApiConfig, ApiConfigBuilder};
+
+use bee_common::worker::Error as WorkerError;
+use bee_common_ext::{
+    channel,
+    node::{Node, ResHandle},
+    worker::Worker,
+};
+use bee_ledger::whiteflag::{LedgerWorker, LedgerWorkerEvent};
+use bee_protocol::{tangle::MsTangle, TangleWorker};
+use bee_storage::storage::Backend;
+
+use async_trait::async_trait;
+use log::{error, info};
+use warp::{Filter, Rejection, Reply};
+
+use std::{any::TypeId, convert::Infallible};
+
+fn address_routes(
+    ledger: channel::Sender<LedgerWorkerEvent>,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    let ledger = warp::any().map(move || ledger.clone());
+
+    let balance = warp::path!("addresses" / String / "balance")
+        .and(warp::get())
+        .and(ledger.clone())
+        .and_then(handlers::balance);
+    let spent = warp::path!("addresses" / String / "spent")
+        .and(warp::get())
+        .and(ledger)
+        .and_then(handlers::spent);
+
+    balance.or(spent)
+}
+
+fn routes<B: Backend>(
+    tangle: ResHandle<MsTangle<B>>,
+    ledger: channel::Sender<LedgerWorkerEvent>,
+    max_body_size: u64,
+) -> impl Filter<Extract = impl Reply, Error = Infallible> + Clone {
+    let tangle = warp::any().map(move || tangle.clone());
+
+    let info = warp::path!("info")
+        .and(warp::get())
+        .and(tangle.clone())
+        .and_then(handlers::info);
+    let transaction = warp::path!("transactions" / String)
+        .and(warp::get())
+        .and(tangle.clone())
+        .and_then(handlers::transaction);
+    let milestone = warp::path!("milestones" / String)
+        .and(warp::get())
+        .and(tangle)
+        .and_then(handlers::milestone);
+    let submit_transaction = warp::path!("transactions")
+        .and(warp::post())
+        .and(json_body(max_body_size))
+        .and_then(handlers::submit_transaction);
+
+    info.or(transaction)
+        .or(milestone)
+        .or(address_routes(ledger))
+        .or(submit_transaction)
+        .recover(error::handle_rejection)
+}
+
+fn json_body(
+    max_body_size: u64,
+) -> impl Filter<Extract = (handlers::SubmitTransactionRequest,), Error = Rejection> + Clone {
+    warp::body::content_length_limit(max_body_size).and(warp::body::json())
+}
+
+pub(crate) struct ApiWorker {}
+
+#[async_trait]
+impl<N: Node> Worker<N> for ApiWorker {
+    type Config = ApiConfig;
+    type Error = WorkerError;
+
+    fn dependencies() -> &'static [TypeId] {
+        Box::leak(Box::from(vec![
+            TypeId::of::<TangleWorker>(),
+            TypeId::of::<LedgerWorker>(),
+        ]))
+    }
+
+    async fn start(node: &mut N, config: Self::Config) -> Result<Self, Self::Error> {
+        let tangle = node.resource::<MsTangle<N::Backend>>();
+        let ledger = node.worker::<LedgerWorker>().unwrap().tx.clone();
+
+        node.spawn::<Self, _, _>(|shutdown| async move {
+            info!("Running.");
+
+            let server = warp::serve(routes(tangle, ledger, config.max_body_size)).try_bind_with_graceful_shutdown(
+                config.binding_address,
+                async {
+                    let _ = shutdown.await;
+                },
+            );
+
+            match server {
+                Ok((address, server)) => {
+                    info!("Listening on {}.", address);
+                    server.await;
+                }
+                Err(e) => error!("Binding to {} failed: {}.", config.binding_address, e),
+            }
+
+            info!("Stopped.");
+        });
+
+        Ok(Self {})
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use bee_ledger::state::LedgerState;
+    use bee_protocol::MilestoneIndex;
+    use bee_transaction::bundled::Address;
+
+    use warp::http::StatusCode;
+
+    use std::error::Error as StdError;
+
+    // Storage of a tangle that only lives in memory.
+    struct NullBackend;
+
+    #[async_trait]
+    impl Backend for NullBackend {
+        type ConfigBuilder = ();
+        type Config = ();
+
+        async fn start(_config: Self::Config) -> Result<Self, Box<dyn StdError>> {
+            Ok(Self)
+        }
+
+        async fn shutdown(self) -> Result<(), Box<dyn StdError>> {
+            Ok(())
+        }
+    }
+
+    fn tangle() -> ResHandle<MsTangle<NullBackend>> {
+        ResHandle::new(MsTangle::new(ResHandle::new(NullBackend)))
+    }
+
+    // The actual routes of the API, on top of an in-memory tangle and a ledger knowing no address.
+    fn api(
+        tangle: ResHandle<MsTangle<NullBackend>>,
+        max_body_size: u64,
+    ) -> impl Filter<Extract = impl Reply, Error = Infallible> + Clone {
+        routes(tangle, mock_ledger(LedgerState::new(), Vec::new()), max_body_size)
+    }
+
+    fn error_code(body: &[u8]) -> String {
+        let body: serde_json::Value = serde_json::from_slice(body).unwrap();
+
+        body["error"]["code"].as_str().unwrap().to_owned()
+    }
+
+    // Answers the ledger queries from `state` and `spent_addresses`, like the ledger worker does.
+    fn mock_ledger(state: LedgerState, spent_addresses: Vec<Address>) -> channel::Sender<LedgerWorkerEvent> {
+        let (tx, rx) = channel::unbounded("LedgerWorker");
+
+        tokio::spawn(async move {
+            while let Ok(event) = rx.recv_async().await {
+                match event {
+                    LedgerWorkerEvent::GetBalance(address, sender) => {
+                        let _ = sender.send(state.get_or_zero(&address));
+                    }
+                    LedgerWorkerEvent::IsSpent(address, sender) => {
+                        let _ = sender.send(spent_addresses.contains(&address));
+                    }
+                    LedgerWorkerEvent::Confirm(_) => (),
+                }
+            }
+        });
+
+        tx
+    }
+
+    #[tokio::test]
+    async fn address_balance_and_spent() {
+        let sender = "A".repeat(81);
+        let receiver = "B".repeat(81);
+        let mut state = LedgerState::new();
+        state.insert(handlers::parse_address(&sender).unwrap(), 100);
+        // The diff of a confirmed milestone moving 60 from the sender to the receiver.
+        state.apply_single_diff(handlers::parse_address(&sender).unwrap(), -60);
+        state.apply_single_diff(handlers::parse_address(&receiver).unwrap(), 60);
+
+        let routes = address_routes(mock_ledger(state, vec![handlers::parse_address(&sender).unwrap()]))
+            .recover(error::handle_rejection);
+
+        for (address, balance, spent) in &[(&sender, 40, true), (&receiver, 60, false)] {
+            let response = warp::test::request()
+                .path(&format!("/addresses/{}/balance", address))
+                .reply(&routes)
+                .await;
+            let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(body["balance"], *balance);
+
+            let response = warp::test::request()
+                .path(&format!("/addresses/{}/spent", address))
+                .reply(&routes)
+                .await;
+            let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(body["spent"], *spent);
+        }
+    }
+
+    #[tokio::test]
+    async fn address_invalid() {
+        let routes = address_routes(mock_ledger(LedgerState::new(), Vec::new())).recover(error::handle_rejection);
+        let response = warp::test::request()
+            .path("/addresses/ABC/balance")
+            .reply(&routes)
+            .await;
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(error_code(response.body()), "invalid_address");
+    }
+
+    #[tokio::test]
+    async fn info_and_milestone() {
+        let tangle = tangle();
+        let hash = handlers::parse_hash(&"A".repeat(81)).unwrap();
+
+        tangle.update_latest_milestone_index(MilestoneIndex(2));
+        tangle.update_latest_solid_milestone_index(MilestoneIndex(1));
+        tangle.add_milestone(MilestoneIndex(1), hash);
+
+        let api = api(tangle, 1024);
+
+        let response = warp::test::request().path("/info").reply(&api).await;
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(body["latest_milestone_index"], 2);
+        assert_eq!(body["latest_solid_milestone_index"], 1);
+
+        let response = warp::test::request().path("/milestones/1").reply(&api).await;
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(body["hash"], "A".repeat(81));
+
+        let response = warp::test::request().path("/milestones/3").reply(&api).await;
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(error_code(response.body()), "not_found");
+    }
+
+    #[tokio::test]
+    async fn unknown_transaction() {
+        let response = warp::test::request()
+            .path(&format!("/transactions/{}", "A".repeat(81)))
+            .reply(&api(tangle(), 1024))
+            .await;
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(error_code(response.body()), "not_found");
+    }
+
+    #[tokio::test]
+    async fn unknown_path() {
+        let response = warp::test::request().path("/unknown").reply(&api(tangle(), 1024)).await;
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(error_code(response.body()), "not_found");
+    }
+
+    #[tokio::test]
+    async fn body_too_large() {
+        let response = warp::test::request()
+            .method("POST")
+            .path("/transactions")
+            .body(format!("{{\"trytes\":\"{}\"}}", "9".repeat(64)))
+            .reply(&api(tangle(), 16))
+            .await;
+
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(error_code(response.body()), "payload_too_large");
+    }
+
+    #[tokio::test]
+    async fn invalid_body() {
+        let response = warp::test::request()
+            .method("POST")
+            .path("/transactions")
+            .body("{\"hash\":\"9\"}")
+            .reply(&api(tangle(), 1024))
+            .await;
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(error_code(response.body()), "invalid_body");
+    }
+
+    #[tokio::test]
+    async fn invalid_trytes() {
+        let response = warp::test::request()
+            .method("POST")
+            .path("/transactions")
+            .body("{\"trytes\":\"ABC\"}")
+            .reply(&api(tangle(), 1024))
+            .await;
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(error_code(response.body()), "invalid_trytes");
+    }
+
+    #[tokio::test]
+    async fn method_not_allowed() {
+        let response = warp::test::request()
+            .method("PUT")
+            .path("/transactions")
+            .reply(&api(tangle(), 1024))
+            .await;
+
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(error_code(response.body()), "method_not_allowed");
+    }
+}
@@ -9,11 +9,15 @@
 // an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and limitations under the License.
 
+#[cfg(feature = "api")]
+use crate::api::{ApiConfig, ApiConfigBuilder};
+
 use bee_common::logger::{LoggerConfig, LoggerConfigBuilder};
 use bee_network::{NetworkConfig, NetworkConfigBuilder};
-use bee_peering::{PeeringConfig, PeeringConfigBuilder};
-use bee_protocol::config::{ProtocolConfig, ProtocolConfigBuilder};
-use bee_snapshot::config::{SnapshotConfig, SnapshotConfigBuilder};
+use bee_peering::{PeeringConfig, PeeringConfigBuilder, PeeringConfigError};
+use bee_pow::{PowConfig, PowConfigBuilder, PowConfigError};
+use bee_protocol::config::{ProtocolConfig, ProtocolConfigBuilder, ProtocolConfigError};
+use bee_snapshot::config::{ConfigError as SnapshotConfigError, SnapshotConfig, SnapshotConfigBuilder};
 use bee_storage::storage::Backend;
 
 use serde::Deserialize;
@@ -23,11 +27,23 @@ use std::{fs, path::Path};
 
 #[derive(Debug, Error)]
 pub enum Error {
-    #[error("Reading the specified config file failed.")]
+    #[error("reading the specified config file failed")]
     ConfigFileReadFailure(#[from] std::io::Error),
 
-    #[error("Deserializing the node config builder failed.")]
+    #[error("deserializing the node config builder failed")]
     NodeConfigBuilderCreationFailure(#[from] toml::de::Error),
+
+    #[error("invalid protocol config")]
+    InvalidProtocolConfig(Vec<ProtocolConfigError>),
+
+    #[error("invalid peering config: {0}")]
+    InvalidPeeringConfig(#[from] PeeringConfigError),
+
+    #[error("invalid snapshot config: {0}")]
+    InvalidSnapshotConfig(#[from] SnapshotConfigError),
+
+    #[error("invalid pow config: {0}")]
+    InvalidPowConfig(#[from] PowConfigError),
 }
 
 #[derive(Default, Deserialize)]
@@ -37,7 +53,12 @@ pub struct NodeConfigBuilder<B: Backend> {
     pub(crate) peering: PeeringConfigBuilder,
     pub(crate) protocol: ProtocolConfigBuilder,
     pub(crate) snapshot: SnapshotConfigBuilder,
+    #[serde(default)]
+    pub(crate) pow: PowConfigBuilder,
     pub(crate) database: B::ConfigBuilder,
+    #[cfg(feature = "api")]
+    #[serde(default)]
+    pub(crate) api: ApiConfigBuilder,
 }
 
 impl<B: Backend> NodeConfigBuilder<B> {
@@ -49,15 +70,34 @@ impl<B: Backend> NodeConfigBuilder<B> {
         }
     }
 
-    pub fn finish(self) -> NodeConfig<B> {
-        NodeConfig {
+    /// Reads, validates and finishes a node config from a local config file in one step.
+    pub fn from_toml_file<P: AsRef<Path>>(path: P) -> Result<NodeConfig<B>, Error> {
+        Self::from_file(path)?.finish()
+    }
+
+    /// Validates and finishes a node config from a TOML string in one step.
+    pub fn from_toml_str(toml: &str) -> Result<NodeConfig<B>, Error> {
+        toml::from_str::<Self>(toml)?.finish()
+    }
+
+    pub fn finish(self) -> Result<NodeConfig<B>, Error> {
+        self.protocol.validate().map_err(Error::InvalidProtocolConfig)?;
+
+        let snapshot = self.snapshot.finish();
+        // Fails here rather than when the snapshot worker starts.
+        snapshot.pruning_delay()?;
+
+        Ok(NodeConfig {
             logger: self.logger.finish(),
             network: self.network.finish(),
-            peering: self.peering.finish(),
+            peering: self.peering.finish()?,
             protocol: self.protocol.finish(),
-            snapshot: self.snapshot.finish(),
+            snapshot,
+            pow: self.pow.finish()?,
             database: self.database.into(),
-        }
+            #[cfg(feature = "api")]
+            api: self.api.finish(),
+        })
     }
 }
 
@@ -68,5 +108,136 @@ pub struct NodeConfig<B: Backend> {
     pub peering: PeeringConfig,
     pub protocol: ProtocolConfig,
     pub snapshot: SnapshotConfig,
+    pub pow: PowConfig,
     pub database: B::Config,
+    #[cfg(feature = "api")]
+    pub api: ApiConfig,
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use bee_pow::NonceProviderKind;
+    use bee_snapshot::config::LoadType;
+    use bee_storage_rocksdb::storage::Storage;
+
+    use std::time::Duration;
+
+    const EXAMPLE_CONFIG: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/config.example.toml");
+
+    #[test]
+    fn example_config() {
+        let config = NodeConfigBuilder::<Storage>::from_toml_file(EXAMPLE_CONFIG).unwrap();
+
+        assert_eq!(config.network.socket_address(), "0.0.0.0:15600".parse().unwrap());
+
+        assert_eq!(config.peering.max_connected_peers(), 8);
+        assert_eq!(config.peering.manual.limit(), 5);
+        assert!(!config.peering.auto.enabled());
+
+        assert_eq!(config.protocol.coordinator().depth(), 24);
+        assert_eq!(config.protocol.workers().ms_max_cone_size(), 50000);
+
+        assert!(matches!(config.snapshot.load_type(), LoadType::Local));
+        assert_eq!(config.snapshot.local().path(), "./snapshots/mainnet/export.bin");
+        assert_eq!(
+            config.snapshot.local().download_urls(),
+            &vec![
+                "https://x-vps.com/export.bin".to_owned(),
+                "https://dbfiles.iota.org/mainnet/hornet/latest-export.bin".to_owned()
+            ]
+        );
+        assert_eq!(config.snapshot.local().depth(), 50);
+        assert_eq!(config.snapshot.local().interval_synced(), 50);
+        assert_eq!(config.snapshot.local().interval_unsynced(), 1000);
+        assert_eq!(config.snapshot.local().backups(), 1);
+        assert!(!config.snapshot.local().allow_coordinator_mismatch());
+        assert_eq!(config.snapshot.global().path(), "./snapshots/mainnet/snapshot.txt");
+        assert_eq!(*config.snapshot.global().index(), 1050000);
+        assert!(config.snapshot.pruning().enabled());
+        assert_eq!(config.snapshot.pruning().delay(), 60480);
+
+        assert_eq!(config.pow.provider_kind(), &NonceProviderKind::Local);
+        assert_eq!(config.pow.remote_url(), "http://localhost:14265");
+        assert_eq!(config.pow.timeout(), Duration::from_secs(60));
+        assert!(config.pow.fallback());
+
+        #[cfg(feature = "api")]
+        {
+            assert_eq!(config.api.binding_address, "127.0.0.1:14265".parse().unwrap());
+            assert_eq!(config.api.max_body_size, 65536);
+        }
+    }
+
+    #[test]
+    fn file_and_str_agree() {
+        let from_file = NodeConfigBuilder::<Storage>::from_toml_file(EXAMPLE_CONFIG).unwrap();
+        let from_str = NodeConfigBuilder::<Storage>::from_toml_str(include_str!("../config.example.toml")).unwrap();
+
+        assert_eq!(from_file.network.socket_address(), from_str.network.socket_address());
+        assert_eq!(from_file.snapshot.local().path(), from_str.snapshot.local().path());
+    }
+
+    #[test]
+    fn missing_file() {
+        assert!(matches!(
+            NodeConfigBuilder::<Storage>::from_toml_file("./does-not-exist.toml"),
+            Err(Error::ConfigFileReadFailure(_))
+        ));
+    }
+
+    #[test]
+    fn invalid_toml() {
+        assert!(matches!(
+            NodeConfigBuilder::<Storage>::from_toml_str("[protocol"),
+            Err(Error::NodeConfigBuilderCreationFailure(_))
+        ));
+    }
+
+    #[test]
+    fn invalid_protocol_config() {
+        let toml = include_str!("../config.example.toml").replace("mwm              = 14", "mwm              = 0");
+
+        assert!(matches!(
+            NodeConfigBuilder::<Storage>::from_toml_str(&toml),
+            Err(Error::InvalidProtocolConfig(_))
+        ));
+    }
+
+    #[test]
+    fn invalid_peering_config() {
+        let toml = include_str!("../config.example.toml").replace("max_connected_peers = 8", "max_connected_peers = 4");
+
+        assert!(matches!(
+            NodeConfigBuilder::<Storage>::from_toml_str(&toml),
+            Err(Error::InvalidPeeringConfig(
+                PeeringConfigError::MaxConnectedPeersTooSmall { given: 4, .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn invalid_snapshot_config() {
+        let toml = include_str!("../config.example.toml").replace("delay   = 60480", "delay   = 10");
+
+        assert!(matches!(
+            NodeConfigBuilder::<Storage>::from_toml_str(&toml),
+            Err(Error::InvalidSnapshotConfig(SnapshotConfigError::DelayTooSmall {
+                given: 10,
+                ..
+            }))
+        ));
+    }
+
+    #[test]
+    fn invalid_pow_config() {
+        let toml = include_str!("../config.example.toml").replace("provider   = \"local\"", "provider   = \"gpu\"");
+
+        assert!(matches!(
+            NodeConfigBuilder::<Storage>::from_toml_str(&toml),
+            Err(Error::InvalidPowConfig(PowConfigError::UnknownProvider(provider))) if provider == "gpu"
+        ));
+    }
 }
@@ -0,0 +1,31 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+//! Events dispatched by the node on its bus.
+
+use bee_network::EndpointId;
+
+use std::net::SocketAddr;
+
+/// An endpoint was added to the network.
+pub struct EndpointAdded(pub EndpointId);
+
+/// An endpoint was removed from the network.
+pub struct EndpointRemoved(pub EndpointId);
+
+/// A connection to an endpoint was established and the protocol registered for it.
+pub struct EndpointConnected(pub EndpointId, pub SocketAddr);
+
+/// A connection to an endpoint was closed.
+pub struct EndpointDisconnected(pub EndpointId);
+
+/// A connection to an endpoint was refused because the maximum number of connected endpoints was reached.
+pub struct EndpointRejected(pub EndpointId);
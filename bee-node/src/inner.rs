@@ -11,7 +11,7 @@
 
 use bee_common::shutdown;
 use bee_common_ext::{
-    node::{Node, NodeBuilder, ResHandle},
+    node::{resolve_optional_dependencies, validate_worker_order, Node, NodeBuilder, ResHandle, ShutdownFlag},
     worker::Worker,
 };
 use bee_storage::storage::Backend;
@@ -24,12 +24,13 @@ use tokio::spawn;
 
 use std::{
     any::{type_name, Any, TypeId},
-    collections::{HashMap, HashSet},
+    collections::HashMap,
     marker::PhantomData,
     pin::Pin,
 };
 
 type WorkerStart<N> = dyn for<'a> FnOnce(&'a mut N) -> Pin<Box<dyn Future<Output = ()> + 'a>>;
+type ResourceRegister<N> = dyn FnOnce(&mut N);
 type WorkerStop<N> = dyn for<'a> FnOnce(&'a mut N) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> + Send;
 
 #[allow(clippy::type_complexity)]
@@ -70,6 +71,9 @@ impl<B: Backend> Node for BeeNode<B> {
     where
         Self: Sized,
     {
+        // Interrupts long-running computations first, they would otherwise delay the shutdown signals of their workers.
+        self.resource::<ShutdownFlag>().set();
+
         for id in self.worker_order.clone().into_iter().rev() {
             for (shutdown, task_fut) in self.tasks.remove(&id).unwrap_or_default() {
                 let _ = shutdown.send(());
@@ -124,16 +128,20 @@ impl<B: Backend> Node for BeeNode<B> {
 
 pub struct BeeNodeBuilder<B: Backend> {
     deps: HashMap<TypeId, &'static [TypeId]>,
+    optional_deps: HashMap<TypeId, &'static [TypeId]>,
     worker_starts: HashMap<TypeId, Box<WorkerStart<BeeNode<B>>>>,
     worker_stops: HashMap<TypeId, Box<WorkerStop<BeeNode<B>>>>,
+    resource_registers: Vec<Box<ResourceRegister<BeeNode<B>>>>,
 }
 
 impl<B: Backend> Default for BeeNodeBuilder<B> {
     fn default() -> Self {
         Self {
             deps: HashMap::default(),
+            optional_deps: HashMap::default(),
             worker_starts: HashMap::default(),
             worker_stops: HashMap::default(),
+            resource_registers: Vec::default(),
         }
     }
 }
@@ -149,6 +157,7 @@ impl<B: Backend> NodeBuilder<BeeNode<B>> for BeeNodeBuilder<B> {
 
     fn with_worker_cfg<W: Worker<BeeNode<B>> + 'static>(mut self, config: W::Config) -> Self {
         self.deps.insert(TypeId::of::<W>(), W::dependencies());
+        self.optional_deps.insert(TypeId::of::<W>(), W::optional_dependencies());
         self.worker_starts.insert(
             TypeId::of::<W>(),
             Box::new(|node| {
@@ -176,16 +185,29 @@ impl<B: Backend> NodeBuilder<BeeNode<B>> for BeeNodeBuilder<B> {
         self
     }
 
+    fn with_resource<R: Any + Send + Sync>(mut self, res: R) -> Self {
+        self.resource_registers
+            .push(Box::new(|node: &mut BeeNode<B>| node.register_resource(res)));
+        self
+    }
+
     async fn finish(mut self) -> BeeNode<B> {
+        let worker_order = validate_worker_order(&resolve_optional_dependencies(&self.deps, &self.optional_deps))
+            .unwrap_or_else(|e| panic!("Invalid worker dependencies: {}", e));
+
         let mut node = BeeNode {
             workers: Map::new(),
             tasks: HashMap::new(),
             resources: Map::new(),
             phantom: PhantomData,
             worker_stops: self.worker_stops,
-            worker_order: TopologicalOrder::sort(self.deps),
+            worker_order,
         };
 
+        for register in self.resource_registers {
+            register(&mut node);
+        }
+
         for id in node.worker_order.clone() {
             self.worker_starts.remove(&id).unwrap()(&mut node).await;
         }
@@ -194,46 +216,20 @@ impl<B: Backend> NodeBuilder<BeeNode<B>> for BeeNodeBuilder<B> {
     }
 }
 
-struct TopologicalOrder {
-    graph: HashMap<TypeId, &'static [TypeId]>,
-    non_visited: HashSet<TypeId>,
-    being_visited: HashSet<TypeId>,
-    order: Vec<TypeId>,
-}
+#[cfg(test)]
+mod tests {
 
-impl TopologicalOrder {
-    fn visit(&mut self, id: TypeId) {
-        if !self.non_visited.contains(&id) {
-            return;
-        }
+    use super::*;
 
-        if !self.being_visited.insert(id) {
-            panic!("Cyclic dependency detected.");
-        }
-
-        for &id in self.graph[&id] {
-            self.visit(id);
-        }
-
-        self.being_visited.remove(&id);
-        self.non_visited.remove(&id);
-        self.order.push(id);
-    }
-
-    fn sort(graph: HashMap<TypeId, &'static [TypeId]>) -> Vec<TypeId> {
-        let non_visited = graph.keys().copied().collect();
+    use bee_storage_rocksdb::storage::Storage;
 
-        let mut this = Self {
-            graph,
-            non_visited,
-            being_visited: HashSet::new(),
-            order: vec![],
-        };
+    #[tokio::test]
+    async fn shutdown_flag_registered() {
+        let node = BeeNode::<Storage>::build().with_resource(0u32).finish().await;
 
-        while let Some(&id) = this.non_visited.iter().next() {
-            this.visit(id);
-        }
+        assert!(!node.resource::<ShutdownFlag>().is_set());
+        assert_eq!(*node.resource::<u32>(), 0);
 
-        this.order
+        node.stop().await.unwrap();
     }
 }
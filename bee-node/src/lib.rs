@@ -9,15 +9,23 @@
 // an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and limitations under the License.
 
+#[cfg(feature = "api")]
+mod api;
 mod banner;
 mod cli;
 mod config;
 mod constants;
+pub mod event;
 mod inner;
+mod network_handler;
 mod node;
 mod plugin;
+#[cfg(feature = "schema")]
+mod schema;
 
 pub use banner::print_banner_and_version;
 pub use cli::CliArgs;
-pub use config::NodeConfigBuilder;
+pub use config::{Error as ConfigError, NodeConfigBuilder};
 pub use node::{Error, Node};
+#[cfg(feature = "schema")]
+pub use schema::generate_schema;
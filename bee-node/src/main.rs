@@ -10,7 +10,7 @@
 // See the License for the specific language governing permissions and limitations under the License.
 
 use bee_common::logger::logger_init;
-use bee_node::{CliArgs, Node, NodeConfigBuilder};
+use bee_node::{CliArgs, ConfigError, Node, NodeConfigBuilder};
 
 const CONFIG_PATH: &str = "./config.toml";
 
@@ -19,7 +19,20 @@ async fn main() {
     match NodeConfigBuilder::from_file(CONFIG_PATH) {
         Ok(mut config_builder) => {
             CliArgs::default().apply_to_config(&mut config_builder);
-            let config = config_builder.finish();
+            let config = match config_builder.finish() {
+                Ok(config) => config,
+                Err(ConfigError::InvalidProtocolConfig(errors)) => {
+                    eprintln!("Program aborted. Invalid protocol config:");
+                    for error in errors {
+                        eprintln!("  - {}", error);
+                    }
+                    return;
+                }
+                Err(e) => {
+                    eprintln!("Program aborted. Error was: {}", e);
+                    return;
+                }
+            };
 
             logger_init(config.logger.clone()).unwrap();
 
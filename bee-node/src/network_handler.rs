@@ -0,0 +1,287 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use crate::event::{EndpointAdded, EndpointConnected, EndpointDisconnected, EndpointRejected, EndpointRemoved};
+
+use bee_common_ext::event::Bus;
+use bee_network::{
+    Command::{self, ConnectEndpoint, DisconnectEndpoint},
+    EndpointId, Error as NetworkError, Event, Origin,
+};
+
+use futures::channel::oneshot;
+use log::{info, warn};
+
+use std::{collections::HashMap, net::SocketAddr, sync::Arc};
+
+// TODO design proper type `PeerList`
+type PeerList = HashMap<EndpointId, (flume::Sender<Vec<u8>>, oneshot::Sender<()>)>;
+
+/// What the network event handler acts upon: the network and the protocol of the node.
+pub(crate) trait NetworkContext {
+    fn send_command(&self, command: Command) -> Result<(), NetworkError>;
+
+    fn register_peer(
+        &self,
+        epid: EndpointId,
+        address: SocketAddr,
+        origin: Origin,
+    ) -> (flume::Sender<Vec<u8>>, oneshot::Sender<()>);
+}
+
+/// Applies the node policies to the events of the network, registering connected endpoints to the protocol up to a
+/// maximum number of them.
+pub(crate) struct NetworkEventHandler {
+    peers: PeerList,
+    max_connected_endpoints: usize,
+    bus: Arc<Bus<'static>>,
+}
+
+impl NetworkEventHandler {
+    pub(crate) fn new(max_connected_endpoints: usize, bus: Arc<Bus<'static>>) -> Self {
+        Self {
+            peers: PeerList::new(),
+            max_connected_endpoints,
+            bus,
+        }
+    }
+
+    pub(crate) fn handle<C: NetworkContext>(&mut self, context: &C, event: Event) {
+        match event {
+            Event::EndpointAdded { epid, .. } => self.endpoint_added(context, epid),
+
+            Event::EndpointRemoved { epid, .. } => self.endpoint_removed(epid),
+
+            Event::EndpointConnected {
+                epid,
+                peer_address,
+                origin,
+            } => self.endpoint_connected(context, epid, peer_address, origin),
+
+            Event::EndpointDisconnected { epid, .. } => self.endpoint_disconnected(epid),
+
+            Event::MessageReceived { epid, message, .. } => self.message_received(epid, message),
+
+            _ => warn!("Unsupported event {}.", event),
+        }
+    }
+
+    /// Shuts down the protocol of every connected endpoint.
+    pub(crate) fn shutdown(self) {
+        for (epid, (_, shutdown)) in self.peers.into_iter() {
+            if shutdown.send(()).is_err() {
+                warn!("Sending shutdown to {} failed.", epid);
+            }
+        }
+    }
+
+    fn endpoint_added<C: NetworkContext>(&self, context: &C, epid: EndpointId) {
+        info!("Endpoint {} has been added.", epid);
+
+        if let Err(e) = context.send_command(ConnectEndpoint { epid }) {
+            warn!("Sending Command::Connect for {} failed: {}.", epid, e);
+        }
+
+        self.bus.dispatch(EndpointAdded(epid));
+    }
+
+    fn endpoint_removed(&self, epid: EndpointId) {
+        info!("Endpoint {} has been removed.", epid);
+
+        self.bus.dispatch(EndpointRemoved(epid));
+    }
+
+    fn endpoint_connected<C: NetworkContext>(
+        &mut self,
+        context: &C,
+        epid: EndpointId,
+        peer_address: SocketAddr,
+        origin: Origin,
+    ) {
+        if self.peers.len() >= self.max_connected_endpoints {
+            info!(
+                "Endpoint {} rejected, the maximum of {} connected endpoints is reached.",
+                epid, self.max_connected_endpoints
+            );
+
+            if let Err(e) = context.send_command(DisconnectEndpoint { epid }) {
+                warn!("Sending Command::Disconnect for {} failed: {}.", epid, e);
+            }
+
+            self.bus.dispatch(EndpointRejected(epid));
+            return;
+        }
+
+        let peer = context.register_peer(epid, peer_address, origin);
+        self.peers.insert(epid, peer);
+
+        self.bus.dispatch(EndpointConnected(epid, peer_address));
+    }
+
+    fn endpoint_disconnected(&mut self, epid: EndpointId) {
+        // TODO unregister ?
+        // Rejected endpoints were never registered.
+        if let Some((_, shutdown)) = self.peers.remove(&epid) {
+            if shutdown.send(()).is_err() {
+                warn!("Sending shutdown to {} failed.", epid);
+            }
+
+            self.bus.dispatch(EndpointDisconnected(epid));
+        }
+    }
+
+    fn message_received(&self, epid: EndpointId, message: Vec<u8>) {
+        if let Some(peer) = self.peers.get(&epid) {
+            if let Err(e) = peer.0.send(message) {
+                warn!("Sending PeerWorkerEvent::Message to {} failed: {}.", epid, e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use bee_network::TransportProtocol;
+
+    use std::{
+        cell::RefCell,
+        net::{IpAddr, Ipv4Addr},
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    #[derive(Default)]
+    struct MockContext {
+        commands: RefCell<Vec<Command>>,
+        registered: RefCell<Vec<EndpointId>>,
+        // Keeps the peer channels alive.
+        receivers: RefCell<Vec<(flume::Receiver<Vec<u8>>, oneshot::Receiver<()>)>>,
+    }
+
+    impl NetworkContext for MockContext {
+        fn send_command(&self, command: Command) -> Result<(), NetworkError> {
+            self.commands.borrow_mut().push(command);
+            Ok(())
+        }
+
+        fn register_peer(
+            &self,
+            epid: EndpointId,
+            _address: SocketAddr,
+            _origin: Origin,
+        ) -> (flume::Sender<Vec<u8>>, oneshot::Sender<()>) {
+            let (message_tx, message_rx) = flume::unbounded();
+            let (shutdown_tx, shutdown_rx) = oneshot::channel();
+
+            self.registered.borrow_mut().push(epid);
+            self.receivers.borrow_mut().push((message_rx, shutdown_rx));
+
+            (message_tx, shutdown_tx)
+        }
+    }
+
+    fn address(port: u16) -> SocketAddr {
+        SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port)
+    }
+
+    fn epid(port: u16) -> EndpointId {
+        EndpointId::new(TransportProtocol::Tcp, address(port))
+    }
+
+    fn connect(handler: &mut NetworkEventHandler, context: &MockContext, port: u16) {
+        handler.handle(context, Event::EndpointAdded { epid: epid(port) });
+        handler.handle(
+            context,
+            Event::EndpointConnected {
+                epid: epid(port),
+                peer_address: address(port),
+                origin: Origin::Outbound,
+            },
+        );
+    }
+
+    fn disconnected(context: &MockContext) -> Vec<EndpointId> {
+        context
+            .commands
+            .borrow()
+            .iter()
+            .filter_map(|command| match command {
+                DisconnectEndpoint { epid } => Some(*epid),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn endpoints_beyond_limit_are_rejected() {
+        let bus = Arc::new(Bus::default());
+        let rejected = Arc::new(AtomicUsize::new(0));
+        let rejected_clone = rejected.clone();
+        bus.add_listener(move |_: &EndpointRejected| {
+            rejected_clone.fetch_add(1, Ordering::SeqCst);
+        });
+
+        let context = MockContext::default();
+        let mut handler = NetworkEventHandler::new(2, bus);
+
+        for port in 15600..15604 {
+            connect(&mut handler, &context, port);
+        }
+
+        assert_eq!(*context.registered.borrow(), vec![epid(15600), epid(15601)]);
+        assert_eq!(disconnected(&context), vec![epid(15602), epid(15603)]);
+        assert_eq!(rejected.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn disconnection_frees_a_slot() {
+        let context = MockContext::default();
+        let mut handler = NetworkEventHandler::new(1, Arc::new(Bus::default()));
+
+        connect(&mut handler, &context, 15600);
+        connect(&mut handler, &context, 15601);
+        // The network reports the disconnection of the rejected endpoint, which must not free the slot.
+        handler.handle(&context, Event::EndpointDisconnected { epid: epid(15601) });
+        connect(&mut handler, &context, 15602);
+        handler.handle(&context, Event::EndpointDisconnected { epid: epid(15600) });
+        connect(&mut handler, &context, 15603);
+
+        assert_eq!(*context.registered.borrow(), vec![epid(15600), epid(15603)]);
+        assert_eq!(disconnected(&context), vec![epid(15601), epid(15602)]);
+    }
+
+    #[test]
+    fn messages_are_forwarded_to_registered_endpoints() {
+        let context = MockContext::default();
+        let mut handler = NetworkEventHandler::new(1, Arc::new(Bus::default()));
+
+        connect(&mut handler, &context, 15600);
+        connect(&mut handler, &context, 15601);
+
+        for port in &[15600, 15601] {
+            handler.handle(
+                &context,
+                Event::MessageReceived {
+                    epid: epid(*port),
+                    message: vec![*port as u8],
+                },
+            );
+        }
+
+        let receivers = context.receivers.borrow();
+
+        assert_eq!(receivers.len(), 1);
+        assert_eq!(receivers[0].0.try_recv().unwrap(), vec![15600u16 as u8]);
+        assert!(receivers[0].0.try_recv().is_err());
+    }
+}
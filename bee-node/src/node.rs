@@ -11,7 +11,13 @@
 
 #![warn(missing_docs)]
 
-use crate::{banner::print_banner_and_version, config::NodeConfig, inner::BeeNode, plugin};
+use crate::{
+    banner::print_banner_and_version,
+    config::NodeConfig,
+    inner::BeeNode,
+    network_handler::{NetworkContext, NetworkEventHandler},
+    plugin,
+};
 
 use bee_common::shutdown_stream::ShutdownStream;
 use bee_common_ext::{
@@ -19,35 +25,37 @@ use bee_common_ext::{
     node::{Node as _, NodeBuilder as _},
     shutdown_tokio::Shutdown,
 };
-use bee_network::{self, Command::ConnectEndpoint, EndpointId, Event, Network, Origin};
-use bee_peering::{ManualPeerManager, PeerManager};
-use bee_protocol::Protocol;
+use bee_network::{self, Command, EndpointId, Error as NetworkError, Event, Network, Origin};
+use bee_peering::{AutoPeeringWorker, ManualPeerManager, PeerManager};
+use bee_protocol::{
+    config::ProtocolConfig,
+    tangle::{RunningMarkerStorage, SolidEntryPointsStorage},
+    MetricsStorage, Protocol,
+};
 use bee_storage::storage::Backend;
 
 use futures::{
     channel::oneshot,
     stream::{Fuse, StreamExt},
+    Future,
 };
-use log::{error, info, trace, warn};
+use log::{info, trace};
 use thiserror::Error;
 use tokio::spawn;
 
-use std::{collections::HashMap, net::SocketAddr, sync::Arc};
+use std::{net::SocketAddr, sync::Arc};
 
 type NetworkEventStream = ShutdownStream<Fuse<flume::r#async::RecvStream<'static, Event>>>;
 
-// TODO design proper type `PeerList`
-type PeerList = HashMap<EndpointId, (flume::Sender<Vec<u8>>, oneshot::Sender<()>)>;
-
 /// All possible node errors.
 #[derive(Error, Debug)]
 pub enum Error {
-    /// Occurs, when there is an error while reading the snapshot file.
-    #[error("Reading snapshot file failed.")]
-    SnapshotError(bee_snapshot::Error),
+    /// Occurs, when there is an error while reading the snapshot file or validating the snapshot config.
+    #[error("loading snapshot failed: {0}")]
+    SnapshotError(#[source] bee_snapshot::Error),
 
     /// Occurs, when there is an error while shutting down the node.
-    #[error("Shutting down failed.")]
+    #[error("shutting down failed")]
     ShutdownError(#[from] bee_common::shutdown::Error),
 }
 
@@ -57,7 +65,10 @@ pub struct NodeBuilder<B: Backend> {
 
 impl<B: Backend> NodeBuilder<B> {
     /// Finishes the build process of a new node.
-    pub async fn finish(self) -> Result<Node<B>, Error> {
+    pub async fn finish(self) -> Result<Node<B>, Error>
+    where
+        B: SolidEntryPointsStorage + MetricsStorage + RunningMarkerStorage,
+    {
         print_banner_and_version();
 
         let node_builder = BeeNode::<B>::build();
@@ -67,10 +78,14 @@ impl<B: Backend> NodeBuilder<B> {
         let bus = Arc::new(Bus::default());
 
         // TODO temporary
-        let (mut node_builder, snapshot_state, snapshot_metadata) =
-            bee_snapshot::init::<BeeNode<B>>(&self.config.snapshot, node_builder)
-                .await
-                .map_err(Error::SnapshotError)?;
+        let (mut node_builder, snapshot_state, snapshot_spent_addresses, snapshot_metadata) =
+            bee_snapshot::init::<BeeNode<B>>(
+                &self.config.snapshot,
+                self.config.protocol.coordinator().public_key(),
+                node_builder,
+            )
+            .await
+            .map_err(Error::SnapshotError)?;
 
         info!("Initializing network...");
         let (network, events) = bee_network::init(self.config.network.clone(), &mut shutdown).await;
@@ -82,7 +97,9 @@ impl<B: Backend> NodeBuilder<B> {
         node_builder = bee_ledger::whiteflag::init::<BeeNode<B>>(
             snapshot_metadata.index(),
             snapshot_state.into(),
+            snapshot_spent_addresses,
             self.config.protocol.coordinator().clone(),
+            self.config.protocol.workers().ms_max_cone_size(),
             node_builder,
             bus.clone(),
         );
@@ -97,24 +114,39 @@ impl<B: Backend> NodeBuilder<B> {
             bus.clone(),
         );
 
+        if self.config.peering.auto.enabled() {
+            info!("Initializing auto-peering...");
+            node_builder = node_builder.with_worker_cfg::<AutoPeeringWorker>(self.config.peering.auto.clone());
+        }
+
+        #[cfg(feature = "api")]
+        {
+            info!("Initializing API...");
+            node_builder = node_builder.with_worker_cfg::<crate::api::ApiWorker>(self.config.api.clone());
+        }
+
         info!("Initializing plugins...");
         plugin::init(bus.clone());
 
-        let bee_node = node_builder.finish().await;
+        let mut bee_node = node_builder.finish().await;
+        // Workers creating transactions look the nonce provider up through this resource.
+        bee_node.register_resource(self.config.pow.clone());
 
         info!("Registering events...");
         bee_snapshot::events(&bee_node, bus.clone());
         bee_ledger::whiteflag::events(&bee_node, bus.clone());
         Protocol::events(&bee_node, self.config.protocol.clone(), bus.clone());
 
+        let network_handler = NetworkEventHandler::new(self.config.peering.max_connected_peers(), bus);
+
         info!("Initialized.");
         Ok(Node {
             config: self.config,
             tmp_node: bee_node,
             network,
-            network_events: ShutdownStream::new(ctrl_c_listener(), events.into_stream()),
+            network_events: ShutdownStream::new(shutdown_listener(), events.into_stream()),
             shutdown,
-            peers: HashMap::new(),
+            network_handler,
         })
     }
 }
@@ -127,9 +159,32 @@ pub struct Node<B: Backend> {
     network_events: NetworkEventStream,
     #[allow(dead_code)]
     shutdown: Shutdown,
-    peers: PeerList,
+    network_handler: NetworkEventHandler,
     config: NodeConfig<B>,
 }
+
+// Gives the network event handler access to the network and the protocol of the node.
+struct NodeNetworkContext<'a, B: Backend> {
+    node: &'a BeeNode<B>,
+    network: &'a Network,
+    config: &'a ProtocolConfig,
+}
+
+impl<'a, B: Backend> NetworkContext for NodeNetworkContext<'a, B> {
+    fn send_command(&self, command: Command) -> Result<(), NetworkError> {
+        self.network.unbounded_send(command)
+    }
+
+    fn register_peer(
+        &self,
+        epid: EndpointId,
+        address: SocketAddr,
+        origin: Origin,
+    ) -> (flume::Sender<Vec<u8>>, oneshot::Sender<()>) {
+        Protocol::register(self.node, self.config, epid, address, origin)
+    }
+}
+
 impl<B: Backend> Node<B> {
     #[allow(missing_docs)]
     pub async fn run(mut self) -> Result<(), Error> {
@@ -138,15 +193,18 @@ impl<B: Backend> Node<B> {
         while let Some(event) = self.network_events.next().await {
             trace!("Received event {}.", event);
 
-            self.process_event(event);
+            let context = NodeNetworkContext {
+                node: &self.tmp_node,
+                network: &self.network,
+                config: &self.config.protocol,
+            };
+
+            self.network_handler.handle(&context, event);
         }
 
         info!("Stopping...");
 
-        for (_, (_, shutdown)) in self.peers.into_iter() {
-            // TODO: Should we handle this error?
-            let _ = shutdown.send(());
-        }
+        self.network_handler.shutdown();
 
         self.tmp_node.stop().await.expect("Failed to properly stop node");
 
@@ -159,76 +217,49 @@ impl<B: Backend> Node<B> {
     pub fn builder(config: NodeConfig<B>) -> NodeBuilder<B> {
         NodeBuilder { config }
     }
+}
 
-    #[inline]
-    fn process_event(&mut self, event: Event) {
-        match event {
-            Event::EndpointAdded { epid, .. } => self.endpoint_added_handler(epid),
-
-            Event::EndpointRemoved { epid, .. } => self.endpoint_removed_handler(epid),
-
-            Event::EndpointConnected {
-                epid,
-                peer_address,
-                origin,
-            } => self.endpoint_connected_handler(epid, peer_address, origin),
-
-            Event::EndpointDisconnected { epid, .. } => self.endpoint_disconnected_handler(epid),
-
-            Event::MessageReceived { epid, message, .. } => self.endpoint_bytes_received_handler(epid, message),
-            _ => warn!("Unsupported event {}.", event),
-        }
-    }
-
-    #[inline]
-    fn endpoint_added_handler(&self, epid: EndpointId) {
-        info!("Endpoint {} has been added.", epid);
-
-        if let Err(e) = self.network.unbounded_send(ConnectEndpoint { epid }) {
-            warn!("Sending Command::Connect for {} failed: {}.", epid, e);
-        }
-    }
-
-    #[inline]
-    fn endpoint_removed_handler(&self, epid: EndpointId) {
-        info!("Endpoint {} has been removed.", epid);
-    }
-
-    #[inline]
-    fn endpoint_connected_handler(&mut self, epid: EndpointId, peer_address: SocketAddr, origin: Origin) {
-        let (receiver_tx, receiver_shutdown_tx) =
-            Protocol::register(&self.tmp_node, &self.config.protocol, epid, peer_address, origin);
-
-        self.peers.insert(epid, (receiver_tx, receiver_shutdown_tx));
-    }
-
-    #[inline]
-    fn endpoint_disconnected_handler(&mut self, epid: EndpointId) {
-        // TODO unregister ?
-        if let Some((_, shutdown)) = self.peers.remove(&epid) {
-            if let Err(e) = shutdown.send(()) {
-                warn!("Sending shutdown to {} failed: {:?}.", epid, e);
+#[cfg(unix)]
+fn shutdown_signal() -> impl Future<Output = ()> {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    // SIGTERM is intercepted from now on, not from the first poll.
+    let mut terminate = match signal(SignalKind::terminate()) {
+        Ok(terminate) => terminate,
+        Err(e) => panic!("Failed to intercept SIGTERM: {:?}.", e),
+    };
+
+    async move {
+        tokio::select! {
+            res = tokio::signal::ctrl_c() => {
+                if let Err(e) = res {
+                    panic!("Failed to intercept CTRL-C: {:?}.", e);
+                }
+                info!("Received CTRL-C.");
             }
+            _ = terminate.recv() => info!("Received SIGTERM."),
         }
     }
+}
 
-    #[inline]
-    fn endpoint_bytes_received_handler(&mut self, epid: EndpointId, bytes: Vec<u8>) {
-        if let Some(peer) = self.peers.get_mut(&epid) {
-            if let Err(e) = peer.0.send(bytes) {
-                warn!("Sending PeerWorkerEvent::Message to {} failed: {}.", epid, e);
-            }
+#[cfg(not(unix))]
+fn shutdown_signal() -> impl Future<Output = ()> {
+    async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            panic!("Failed to intercept CTRL-C: {:?}.", e);
         }
+        info!("Received CTRL-C.");
     }
 }
 
-fn ctrl_c_listener() -> oneshot::Receiver<()> {
+// Resolves on CTRL-C or SIGTERM, ending the event loop so that workers are stopped in reverse dependency order, the
+// storage last.
+fn shutdown_listener() -> oneshot::Receiver<()> {
     let (sender, receiver) = oneshot::channel();
+    let signal = shutdown_signal();
 
     tokio::spawn(async move {
-        if let Err(e) = tokio::signal::ctrl_c().await {
-            panic!("Failed to intercept CTRL-C: {:?}.", e);
-        }
+        signal.await;
 
         if let Err(e) = sender.send(()) {
             panic!("Failed to send the shutdown signal: {:?}.", e);
@@ -237,3 +268,30 @@ fn ctrl_c_listener() -> oneshot::Receiver<()> {
 
     receiver
 }
+
+#[cfg(all(test, unix))]
+mod tests {
+
+    use super::*;
+
+    use std::{
+        process::{self, Command},
+        time::Duration,
+    };
+
+    #[tokio::test]
+    async fn sigterm_ends_event_loop() {
+        let listener = shutdown_listener();
+
+        assert!(Command::new("kill")
+            .args(&["-TERM", &process::id().to_string()])
+            .status()
+            .unwrap()
+            .success());
+
+        assert!(tokio::time::timeout(Duration::from_secs(10), listener)
+            .await
+            .expect("the shutdown listener didn't resolve")
+            .is_ok());
+    }
+}
@@ -20,8 +20,19 @@ use std::{convert::Infallible, sync::Arc};
 
 fn tps(metrics: &TpsMetricsUpdated) {
     info!(
-        "incoming {} new {} known {} stale {} invalid {} outgoing {}",
-        metrics.incoming, metrics.new, metrics.known, metrics.stale, metrics.invalid, metrics.outgoing
+        "incoming {} new {} known {} stale {} invalid {} outgoing {} confirmed {} - itps {:.2} ntps {:.2} ctps {:.2} \
+         confirmation {:.2}%",
+        metrics.incoming,
+        metrics.new,
+        metrics.known,
+        metrics.stale,
+        metrics.invalid,
+        metrics.outgoing,
+        metrics.confirmed,
+        metrics.rates.itps,
+        metrics.rates.ntps,
+        metrics.rates.ctps,
+        metrics.rates.confirmation_ratio * 100.0
     );
 }
 
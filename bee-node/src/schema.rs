@@ -0,0 +1,126 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+//! JSON Schema of the node configuration file, for editors and config linters.
+
+#[cfg(feature = "api")]
+use crate::api::ApiConfigBuilder;
+
+use bee_network::NetworkConfigBuilder;
+use bee_peering::PeeringConfigBuilder;
+use bee_pow::PowConfigBuilder;
+use bee_protocol::config::ProtocolConfigBuilder;
+use bee_snapshot::config::SnapshotConfigBuilder;
+use bee_storage::storage::Backend;
+
+use schemars::{schema::RootSchema, schema_for, JsonSchema};
+
+// The logger config builder lives in `bee-common`, which doesn't implement `JsonSchema`; this mirrors its format.
+#[allow(dead_code)]
+#[derive(JsonSchema)]
+struct LoggerConfigBuilder {
+    /// Whether the log levels are colored.
+    color_enabled: Option<bool>,
+    /// Outputs the logs are written to.
+    outputs: Option<Vec<LoggerOutputConfigBuilder>>,
+}
+
+#[allow(dead_code)]
+#[derive(JsonSchema)]
+struct LoggerOutputConfigBuilder {
+    /// Name of the output, "stdout" or a file path.
+    name: Option<String>,
+    /// Maximum level of the logs written to the output, e.g. "info".
+    level: Option<String>,
+}
+
+// Mirrors `NodeConfigBuilder`, whose database section is only known once the backend is chosen.
+#[allow(dead_code)]
+#[derive(JsonSchema)]
+struct NodeConfigBuilder<D> {
+    /// Logging settings.
+    logger: LoggerConfigBuilder,
+    /// Networking settings.
+    network: NetworkConfigBuilder,
+    /// Peering settings.
+    peering: PeeringConfigBuilder,
+    /// Protocol settings.
+    protocol: ProtocolConfigBuilder,
+    /// Snapshot settings.
+    snapshot: SnapshotConfigBuilder,
+    /// Proof of work settings.
+    pow: Option<PowConfigBuilder>,
+    /// Storage backend settings.
+    database: D,
+    /// HTTP API settings.
+    #[cfg(feature = "api")]
+    api: Option<ApiConfigBuilder>,
+}
+
+/// Generates the JSON Schema of the configuration file of a node using the `B` storage backend.
+pub fn generate_schema<B: Backend>() -> RootSchema
+where
+    B::ConfigBuilder: JsonSchema,
+{
+    schema_for!(NodeConfigBuilder<B::ConfigBuilder>)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use bee_storage_rocksdb::storage::Storage;
+
+    use std::{env, fs, process};
+
+    fn example_config() -> serde_json::Value {
+        let toml = toml::from_str::<toml::Value>(include_str!("../config.example.toml")).unwrap();
+
+        serde_json::to_value(toml).unwrap()
+    }
+
+    #[test]
+    fn example_config_matches_schema() {
+        let schema = serde_json::to_value(generate_schema::<Storage>()).unwrap();
+        let config = example_config();
+
+        assert!(jsonschema::is_valid(&schema, &config));
+        // What the schema accepts, the actual builder does too.
+        assert!(serde_json::from_value::<crate::NodeConfigBuilder<Storage>>(config).is_ok());
+    }
+
+    #[test]
+    fn invalid_config_does_not_match_schema() {
+        let schema = serde_json::to_value(generate_schema::<Storage>()).unwrap();
+        let mut config = example_config();
+
+        config["protocol"]["mwm"] = serde_json::Value::from("fourteen");
+
+        assert!(!jsonschema::is_valid(&schema, &config));
+    }
+
+    // The schema written as `config.json` reads back as the generated one; written into a temporary directory so that
+    // running the tests leaves the source tree untouched.
+    #[test]
+    fn schema_file_round_trip() {
+        let dir = env::temp_dir().join(format!("bee_node_schema_{}", process::id()));
+        let path = dir.join("config.json");
+        let schema = serde_json::to_value(generate_schema::<Storage>()).unwrap();
+
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(&path, serde_json::to_string_pretty(&schema).unwrap() + "\n").unwrap();
+        let written = serde_json::from_str::<serde_json::Value>(&fs::read_to_string(&path).unwrap()).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(written, schema);
+    }
+}
@@ -0,0 +1,86 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use serde::Deserialize;
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+const DEFAULT_ENABLED: bool = false;
+const DEFAULT_BINDING_PORT: u16 = 14626;
+const DEFAULT_BINDING_ADDRESS: IpAddr = IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0));
+const DEFAULT_K: usize = 20;
+
+#[derive(Default, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct AutoPeeringConfigBuilder {
+    /// Whether the node discovers peers through auto-peering.
+    pub(crate) enabled: Option<bool>,
+    /// Port the node listens on for discovery packets.
+    pub(crate) binding_port: Option<u16>,
+    /// Address the node listens on for discovery packets.
+    pub(crate) binding_address: Option<IpAddr>,
+    /// Number of closest peers kept in the routing table.
+    pub(crate) k: Option<usize>,
+}
+
+impl AutoPeeringConfigBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled.replace(enabled);
+        self
+    }
+
+    pub fn binding_port(mut self, binding_port: u16) -> Self {
+        self.binding_port.replace(binding_port);
+        self
+    }
+
+    pub fn binding_address(mut self, binding_address: IpAddr) -> Self {
+        self.binding_address.replace(binding_address);
+        self
+    }
+
+    pub fn k(mut self, k: usize) -> Self {
+        self.k.replace(k);
+        self
+    }
+
+    pub fn finish(self) -> AutoPeeringConfig {
+        AutoPeeringConfig {
+            enabled: self.enabled.unwrap_or(DEFAULT_ENABLED),
+            binding_address: SocketAddr::new(
+                self.binding_address.unwrap_or(DEFAULT_BINDING_ADDRESS),
+                self.binding_port.unwrap_or(DEFAULT_BINDING_PORT),
+            ),
+            k: self.k.unwrap_or(DEFAULT_K),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct AutoPeeringConfig {
+    pub(crate) enabled: bool,
+    pub(crate) binding_address: SocketAddr,
+    pub(crate) k: usize,
+}
+
+impl AutoPeeringConfig {
+    pub fn build() -> AutoPeeringConfigBuilder {
+        AutoPeeringConfigBuilder::new()
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+}
@@ -0,0 +1,215 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use std::net::SocketAddr;
+
+pub const PEER_ID_LEN: usize = 32;
+
+/// Identifier of a node in the auto-peering overlay.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PeerId(pub [u8; PEER_ID_LEN]);
+
+impl PeerId {
+    /// Generates a random identifier.
+    pub fn random() -> Self {
+        Self(rand::random())
+    }
+}
+
+/// Computes the Kademlia XOR distance between two identifiers.
+///
+/// Distances compare lexicographically, so the first differing bit decides which of two peers is closer.
+pub fn distance(a: &PeerId, b: &PeerId) -> [u8; PEER_ID_LEN] {
+    let mut distance = [0u8; PEER_ID_LEN];
+
+    for (i, byte) in distance.iter_mut().enumerate() {
+        *byte = a.0[i] ^ b.0[i];
+    }
+
+    distance
+}
+
+/// Keeps the `k` known peers closest to the local node, ordered by increasing distance.
+///
+/// This is a single bucket rather than the full set of Kademlia buckets, which is enough for peer selection.
+pub struct RoutingTable {
+    local: PeerId,
+    k: usize,
+    peers: Vec<(PeerId, SocketAddr)>,
+}
+
+impl RoutingTable {
+    pub fn new(local: PeerId, k: usize) -> Self {
+        Self {
+            local,
+            k,
+            peers: Vec::with_capacity(k),
+        }
+    }
+
+    pub fn local(&self) -> &PeerId {
+        &self.local
+    }
+
+    pub fn len(&self) -> usize {
+        self.peers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.peers.is_empty()
+    }
+
+    /// Inserts a peer, evicting the furthest one if the table is full.
+    ///
+    /// Returns `false` if the peer is the local node, is further than every peer of a full table, or is already known;
+    /// in the latter case, its address is updated.
+    pub fn insert(&mut self, id: PeerId, address: SocketAddr) -> bool {
+        if id == self.local || self.k == 0 {
+            return false;
+        }
+
+        if let Some(peer) = self.peers.iter_mut().find(|(peer, _)| *peer == id) {
+            peer.1 = address;
+            return false;
+        }
+
+        let peer_distance = distance(&self.local, &id);
+        let position = self
+            .peers
+            .iter()
+            .position(|(peer, _)| distance(&self.local, peer) > peer_distance)
+            .unwrap_or_else(|| self.peers.len());
+
+        if position == self.k {
+            return false;
+        }
+
+        self.peers.insert(position, (id, address));
+        self.peers.truncate(self.k);
+
+        true
+    }
+
+    pub fn remove(&mut self, id: &PeerId) -> Option<SocketAddr> {
+        let position = self.peers.iter().position(|(peer, _)| peer == id)?;
+
+        Some(self.peers.remove(position).1)
+    }
+
+    /// Returns the peers of the table, closest first.
+    pub fn peers(&self) -> &[(PeerId, SocketAddr)] {
+        &self.peers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn id(last: u8) -> PeerId {
+        let mut id = [0u8; PEER_ID_LEN];
+        id[PEER_ID_LEN - 1] = last;
+
+        PeerId(id)
+    }
+
+    fn address(port: u16) -> SocketAddr {
+        SocketAddr::from(([127, 0, 0, 1], port))
+    }
+
+    #[test]
+    fn distance_to_self_is_zero() {
+        let a = PeerId::random();
+
+        assert_eq!(distance(&a, &a), [0u8; PEER_ID_LEN]);
+    }
+
+    #[test]
+    fn distance_is_symmetric() {
+        let a = PeerId::random();
+        let b = PeerId::random();
+
+        assert_eq!(distance(&a, &b), distance(&b, &a));
+    }
+
+    #[test]
+    fn distance_is_xor() {
+        assert_eq!(distance(&id(0b1010), &id(0b0110))[PEER_ID_LEN - 1], 0b1100);
+    }
+
+    #[test]
+    fn distance_orders_by_first_differing_bit() {
+        let mut far = [0u8; PEER_ID_LEN];
+        far[0] = 1;
+
+        assert!(distance(&id(0), &PeerId(far)) > distance(&id(0), &id(0xff)));
+    }
+
+    #[test]
+    fn insert_keeps_closest_first() {
+        let mut table = RoutingTable::new(id(0), 3);
+
+        assert!(table.insert(id(4), address(4)));
+        assert!(table.insert(id(1), address(1)));
+        assert!(table.insert(id(2), address(2)));
+
+        assert_eq!(
+            table.peers().iter().map(|(peer, _)| *peer).collect::<Vec<_>>(),
+            vec![id(1), id(2), id(4)]
+        );
+    }
+
+    #[test]
+    fn insert_evicts_furthest_when_full() {
+        let mut table = RoutingTable::new(id(0), 2);
+
+        assert!(table.insert(id(4), address(4)));
+        assert!(table.insert(id(2), address(2)));
+        assert!(table.insert(id(1), address(1)));
+        assert!(!table.insert(id(8), address(8)));
+
+        assert_eq!(table.len(), 2);
+        assert_eq!(
+            table.peers().iter().map(|(peer, _)| *peer).collect::<Vec<_>>(),
+            vec![id(1), id(2)]
+        );
+    }
+
+    #[test]
+    fn insert_known_peer_updates_address() {
+        let mut table = RoutingTable::new(id(0), 2);
+
+        assert!(table.insert(id(1), address(1)));
+        assert!(!table.insert(id(1), address(2)));
+
+        assert_eq!(table.peers(), &[(id(1), address(2))]);
+    }
+
+    #[test]
+    fn insert_local_peer() {
+        let mut table = RoutingTable::new(id(0), 2);
+
+        assert!(!table.insert(id(0), address(0)));
+        assert!(table.is_empty());
+    }
+
+    #[test]
+    fn remove_peer() {
+        let mut table = RoutingTable::new(id(0), 2);
+
+        table.insert(id(1), address(1));
+
+        assert_eq!(table.remove(&id(1)), Some(address(1)));
+        assert_eq!(table.remove(&id(1)), None);
+        assert!(table.is_empty());
+    }
+}
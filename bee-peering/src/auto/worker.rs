@@ -0,0 +1,91 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use crate::auto::{AutoPeeringConfig, PeerId, RoutingTable};
+
+use bee_common_ext::{node::Node, shutdown_stream::ShutdownStream, worker::Worker};
+use bee_protocol::StorageWorker;
+
+use async_trait::async_trait;
+use futures::stream::{self, StreamExt};
+use log::{info, trace, warn};
+use tokio::net::UdpSocket;
+
+use std::{any::TypeId, io, net::SocketAddr};
+
+// Large enough for any discovery packet while staying below common MTUs.
+const MAX_PACKET_SIZE: usize = 1280;
+
+const DISCOVERY_REQUEST: u8 = 0;
+const DISCOVERY_RESPONSE: u8 = 1;
+
+pub struct AutoPeeringWorker;
+
+// TODO parse the packet and add the advertised peers to the routing table.
+fn discover(_table: &mut RoutingTable, from: SocketAddr, packet: &[u8]) {
+    trace!("Received discovery response of {} bytes from {}.", packet.len(), from);
+}
+
+// TODO answer with the peers of the routing table closest to the requested identifier.
+fn respond(table: &RoutingTable, from: SocketAddr, packet: &[u8]) {
+    trace!(
+        "Received discovery request of {} bytes from {}, {} known peers.",
+        packet.len(),
+        from,
+        table.len()
+    );
+}
+
+#[async_trait]
+impl<N: Node> Worker<N> for AutoPeeringWorker {
+    type Config = AutoPeeringConfig;
+    type Error = io::Error;
+
+    fn dependencies() -> &'static [TypeId] {
+        Box::leak(Box::from(vec![TypeId::of::<StorageWorker>()]))
+    }
+
+    async fn start(node: &mut N, config: Self::Config) -> Result<Self, Self::Error> {
+        let socket = UdpSocket::bind(config.binding_address).await?;
+        let mut table = RoutingTable::new(PeerId::random(), config.k);
+
+        node.spawn::<Self, _, _>(|shutdown| async move {
+            info!("Running.");
+            trace!("Listening on {}.", config.binding_address);
+
+            let packets = Box::pin(stream::unfold(socket, |mut socket| async move {
+                let mut buffer = [0u8; MAX_PACKET_SIZE];
+                let packet = socket
+                    .recv_from(&mut buffer)
+                    .await
+                    .map(|(len, from)| (buffer[..len].to_vec(), from));
+
+                Some((packet, socket))
+            }));
+            let mut receiver = ShutdownStream::new(shutdown, packets);
+
+            while let Some(packet) = receiver.next().await {
+                match packet {
+                    Ok((packet, from)) => match packet.first() {
+                        Some(&DISCOVERY_REQUEST) => respond(&table, from, &packet),
+                        Some(&DISCOVERY_RESPONSE) => discover(&mut table, from, &packet),
+                        _ => trace!("Ignoring unknown packet from {}.", from),
+                    },
+                    Err(e) => warn!("Receiving discovery packet failed: {}.", e),
+                }
+            }
+
+            info!("Stopped.");
+        });
+
+        Ok(Self)
+    }
+}
@@ -9,13 +9,54 @@
 // an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and limitations under the License.
 
-use crate::manual::{ManualPeeringConfig, ManualPeeringConfigBuilder};
+use crate::{
+    auto::{AutoPeeringConfig, AutoPeeringConfigBuilder},
+    manual::{ManualPeeringConfig, ManualPeeringConfigBuilder, PeerUrlError},
+};
 
 use serde::Deserialize;
 
+use std::{error, fmt};
+
+const DEFAULT_MAX_CONNECTED_PEERS: usize = 8;
+
+#[derive(Debug, PartialEq)]
+pub enum PeeringConfigError {
+    InvalidPeer(PeerUrlError),
+    MaxConnectedPeersTooSmall { given: usize, manual_limit: u8 },
+}
+
+impl fmt::Display for PeeringConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidPeer(e) => write!(f, "{}", e),
+            Self::MaxConnectedPeersTooSmall { given, manual_limit } => write!(
+                f,
+                "peering.max_connected_peers is {} but must be at least 1 and at least peering.manual.limit ({})",
+                given, manual_limit
+            ),
+        }
+    }
+}
+
+impl error::Error for PeeringConfigError {}
+
+impl From<PeerUrlError> for PeeringConfigError {
+    fn from(e: PeerUrlError) -> Self {
+        Self::InvalidPeer(e)
+    }
+}
+
 #[derive(Default, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct PeeringConfigBuilder {
+    /// Maximum number of connected peers, manual and automatic ones alike.
+    max_connected_peers: Option<usize>,
+    /// Peers configured by the operator.
     manual: ManualPeeringConfigBuilder,
+    /// Peers discovered through auto-peering.
+    #[serde(default)]
+    auto: AutoPeeringConfigBuilder,
 }
 
 impl PeeringConfigBuilder {
@@ -23,20 +64,92 @@ impl PeeringConfigBuilder {
         Self::default()
     }
 
-    pub fn finish(self) -> PeeringConfig {
-        PeeringConfig {
-            manual: self.manual.finish(),
+    pub fn max_connected_peers(mut self, max_connected_peers: usize) -> Self {
+        self.max_connected_peers.replace(max_connected_peers);
+        self
+    }
+
+    pub fn manual(mut self, manual: ManualPeeringConfigBuilder) -> Self {
+        self.manual = manual;
+        self
+    }
+
+    pub fn finish(self) -> Result<PeeringConfig, PeeringConfigError> {
+        let manual = self.manual.finish()?;
+        let max_connected_peers = self.max_connected_peers.unwrap_or(DEFAULT_MAX_CONNECTED_PEERS);
+
+        // The manual peers must all fit, there is no point in accepting none.
+        if max_connected_peers == 0 || max_connected_peers < manual.limit() as usize {
+            return Err(PeeringConfigError::MaxConnectedPeersTooSmall {
+                given: max_connected_peers,
+                manual_limit: manual.limit(),
+            });
         }
+
+        Ok(PeeringConfig {
+            max_connected_peers,
+            manual,
+            auto: self.auto.finish(),
+        })
     }
 }
 
 #[derive(Clone)]
 pub struct PeeringConfig {
+    pub(crate) max_connected_peers: usize,
     pub manual: ManualPeeringConfig,
+    pub auto: AutoPeeringConfig,
 }
 
 impl PeeringConfig {
     pub fn build() -> PeeringConfigBuilder {
         PeeringConfigBuilder::new()
     }
+
+    pub fn max_connected_peers(&self) -> usize {
+        self.max_connected_peers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn default_max_connected_peers() {
+        let config = PeeringConfig::build().finish().unwrap();
+
+        assert_eq!(config.max_connected_peers(), DEFAULT_MAX_CONNECTED_PEERS);
+        assert!(config.max_connected_peers() >= config.manual.limit() as usize);
+    }
+
+    #[test]
+    fn max_connected_peers_below_manual_limit() {
+        let config = PeeringConfig::build()
+            .max_connected_peers(4)
+            .manual(ManualPeeringConfigBuilder::new().limit(5))
+            .finish();
+
+        assert_eq!(
+            config.err(),
+            Some(PeeringConfigError::MaxConnectedPeersTooSmall {
+                given: 4,
+                manual_limit: 5
+            })
+        );
+    }
+
+    #[test]
+    fn no_connected_peers() {
+        let config = PeeringConfig::build()
+            .max_connected_peers(0)
+            .manual(ManualPeeringConfigBuilder::new().limit(0))
+            .finish();
+
+        assert!(matches!(
+            config,
+            Err(PeeringConfigError::MaxConnectedPeersTooSmall { given: 0, .. })
+        ));
+    }
 }
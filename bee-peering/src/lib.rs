@@ -11,10 +11,12 @@
 
 #![allow(clippy::module_inception)]
 
+mod auto;
 mod config;
 mod manager;
 mod manual;
 
-pub use config::{PeeringConfig, PeeringConfigBuilder};
+pub use auto::{distance, AutoPeeringConfig, AutoPeeringConfigBuilder, AutoPeeringWorker, PeerId, RoutingTable};
+pub use config::{PeeringConfig, PeeringConfigBuilder, PeeringConfigError};
 pub use manager::PeerManager;
-pub use manual::ManualPeerManager;
+pub use manual::{ManualPeerManager, PeerUrlError};
@@ -10,15 +10,128 @@
 // See the License for the specific language governing permissions and limitations under the License.
 
 use serde::Deserialize;
+use url::{ParseError, Url};
+
+use std::{
+    error, fmt,
+    net::{Ipv4Addr, Ipv6Addr},
+};
 
 // TODO add acceptAnyConnection
 
 const DEFAULT_LIMIT: u8 = 5;
 const DEFAULT_PEERS: Vec<String> = Vec::new();
 
+#[derive(Debug, PartialEq)]
+pub enum PeerUrlError {
+    InvalidUrl(String),
+    UnsupportedScheme(String),
+    MissingPort(String),
+    InvalidPort(String),
+    InvalidMultiaddr(String),
+}
+
+const ACCEPTED_FORMATS: &str = "\"tcp://host:port\", \"udp://host:port\", \"host:port\" or \"/dns/host/tcp/port\"";
+
+impl fmt::Display for PeerUrlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidUrl(url) => write!(f, "peer {:?} is not a valid url, IPv6 addresses need brackets", url),
+            Self::UnsupportedScheme(url) => write!(f, "peer {:?} doesn't start with \"tcp://\" or \"udp://\"", url),
+            Self::MissingPort(url) => write!(f, "peer {:?} has no port", url),
+            Self::InvalidPort(url) => write!(f, "peer {:?} has an invalid port", url),
+            Self::InvalidMultiaddr(url) => write!(
+                f,
+                "peer {:?} is not of the form \"/{{ip4,ip6,dns,dns4,dns6}}/host/{{tcp,udp}}/port\"",
+                url
+            ),
+        }?;
+
+        write!(f, "; peering.manual.peers accepts {}", ACCEPTED_FORMATS)
+    }
+}
+
+impl error::Error for PeerUrlError {}
+
+// Validates `url`, errors reporting `peer`, the form the url was given in.
+fn check_peer_url(url: &str, peer: &str) -> Result<(), PeerUrlError> {
+    let parsed = Url::parse(url).map_err(|e| match e {
+        ParseError::InvalidPort => PeerUrlError::InvalidPort(peer.to_owned()),
+        _ => PeerUrlError::InvalidUrl(peer.to_owned()),
+    })?;
+
+    if !matches!(parsed.scheme(), "tcp" | "udp") {
+        return Err(PeerUrlError::UnsupportedScheme(peer.to_owned()));
+    }
+    if parsed.host().is_none() {
+        return Err(PeerUrlError::InvalidUrl(peer.to_owned()));
+    }
+    if parsed.port().is_none() {
+        return Err(PeerUrlError::MissingPort(peer.to_owned()));
+    }
+
+    Ok(())
+}
+
+/// Checks that a peer is a `tcp://` or `udp://` url with a host, either a domain name or an IP address, and a port.
+/// Domain names are only resolved when connecting.
+pub(crate) fn validate_peer_url(url: &str) -> Result<(), PeerUrlError> {
+    check_peer_url(url, url)
+}
+
+// Converts a multiaddr-like "/ip4/1.2.3.4/tcp/15600" into "tcp://1.2.3.4:15600".
+fn multiaddr_to_url(peer: &str) -> Result<String, PeerUrlError> {
+    let invalid = || PeerUrlError::InvalidMultiaddr(peer.to_owned());
+    let parts = peer[1..].split('/').collect::<Vec<_>>();
+
+    let (protocol, host, transport, port) = match parts.as_slice() {
+        [protocol, host, transport, port] => (*protocol, *host, *transport, *port),
+        _ => return Err(invalid()),
+    };
+
+    let host = match protocol {
+        "ip4" => host.parse::<Ipv4Addr>().map_err(|_| invalid())?.to_string(),
+        "ip6" => format!("[{}]", host.parse::<Ipv6Addr>().map_err(|_| invalid())?),
+        "dns" | "dns4" | "dns6" if !host.is_empty() => host.to_owned(),
+        _ => return Err(invalid()),
+    };
+
+    if !matches!(transport, "tcp" | "udp") {
+        return Err(invalid());
+    }
+    if port.parse::<u16>().is_err() {
+        return Err(PeerUrlError::InvalidPort(peer.to_owned()));
+    }
+
+    Ok(format!("{}://{}:{}", transport, host, port))
+}
+
+/// Validates a peer given as a url, as "host:port" or as a multiaddr, and returns it as a url.
+pub(crate) fn normalize_peer(peer: &str) -> Result<String, PeerUrlError> {
+    if peer.contains("://") {
+        validate_peer_url(peer)?;
+
+        return Ok(peer.to_owned());
+    }
+
+    let url = if peer.starts_with('/') {
+        multiaddr_to_url(peer)?
+    } else {
+        // Without a scheme, the host would be parsed as one.
+        format!("tcp://{}", peer)
+    };
+
+    check_peer_url(&url, peer)?;
+
+    Ok(url)
+}
+
 #[derive(Default, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ManualPeeringConfigBuilder {
+    /// Maximum number of connected peers.
     pub(crate) limit: Option<u8>,
+    /// Peers to connect to, e.g. "tcp://example.com:15600", "example.com:15600" or "/dns/example.com/tcp/15600".
     pub(crate) peers: Option<Vec<String>>,
 }
 
@@ -32,18 +145,23 @@ impl ManualPeeringConfigBuilder {
         self
     }
 
-    pub fn add_peer(mut self, peer: &str) {
-        if self.peers.is_none() {
-            self.peers.replace(Vec::new());
-        }
-        self.peers.unwrap().push(peer.to_owned());
+    pub fn add_peer(mut self, peer: &str) -> Self {
+        self.peers.get_or_insert_with(Vec::new).push(peer.to_owned());
+        self
     }
 
-    pub fn finish(self) -> ManualPeeringConfig {
-        ManualPeeringConfig {
+    pub fn finish(self) -> Result<ManualPeeringConfig, PeerUrlError> {
+        let peers = self
+            .peers
+            .unwrap_or(DEFAULT_PEERS)
+            .iter()
+            .map(|peer| normalize_peer(peer))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(ManualPeeringConfig {
             limit: self.limit.unwrap_or(DEFAULT_LIMIT),
-            peers: self.peers.unwrap_or(DEFAULT_PEERS),
-        }
+            peers,
+        })
     }
 }
 
@@ -57,4 +175,144 @@ impl ManualPeeringConfig {
     pub fn build() -> ManualPeeringConfigBuilder {
         ManualPeeringConfigBuilder::new()
     }
+
+    pub fn limit(&self) -> u8 {
+        self.limit
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn valid_ipv4() {
+        assert_eq!(validate_peer_url("tcp://127.0.0.1:15600"), Ok(()));
+    }
+
+    #[test]
+    fn valid_ipv6() {
+        assert_eq!(validate_peer_url("udp://[::1]:15600"), Ok(()));
+    }
+
+    #[test]
+    fn valid_domain_name() {
+        assert_eq!(validate_peer_url("tcp://localhost:15600"), Ok(()));
+    }
+
+    #[test]
+    fn ipv6_without_brackets() {
+        assert!(validate_peer_url("tcp://::1:15600").is_err());
+    }
+
+    #[test]
+    fn missing_port() {
+        assert_eq!(
+            validate_peer_url("tcp://127.0.0.1"),
+            Err(PeerUrlError::MissingPort("tcp://127.0.0.1".to_owned()))
+        );
+    }
+
+    #[test]
+    fn non_numeric_port() {
+        assert_eq!(
+            validate_peer_url("tcp://127.0.0.1:port"),
+            Err(PeerUrlError::InvalidPort("tcp://127.0.0.1:port".to_owned()))
+        );
+    }
+
+    #[test]
+    fn missing_scheme() {
+        assert!(validate_peer_url("127.0.0.1:15600").is_err());
+    }
+
+    #[test]
+    fn normalize_url() {
+        assert_eq!(normalize_peer("udp://[::1]:15600"), Ok("udp://[::1]:15600".to_owned()));
+    }
+
+    #[test]
+    fn normalize_host_and_port() {
+        assert_eq!(
+            normalize_peer("127.0.0.1:15600"),
+            Ok("tcp://127.0.0.1:15600".to_owned())
+        );
+        assert_eq!(normalize_peer("[::1]:15600"), Ok("tcp://[::1]:15600".to_owned()));
+        assert_eq!(
+            normalize_peer("example.com:15600"),
+            Ok("tcp://example.com:15600".to_owned())
+        );
+    }
+
+    #[test]
+    fn normalize_multiaddr() {
+        assert_eq!(
+            normalize_peer("/ip4/127.0.0.1/tcp/15600"),
+            Ok("tcp://127.0.0.1:15600".to_owned())
+        );
+        assert_eq!(normalize_peer("/ip6/::1/udp/15600"), Ok("udp://[::1]:15600".to_owned()));
+        assert_eq!(
+            normalize_peer("/dns/example.com/tcp/15600"),
+            Ok("tcp://example.com:15600".to_owned())
+        );
+    }
+
+    #[test]
+    fn normalize_rejects_invalid_forms() {
+        assert_eq!(
+            normalize_peer("127.0.0.1"),
+            Err(PeerUrlError::MissingPort("127.0.0.1".to_owned()))
+        );
+        assert_eq!(
+            normalize_peer("127.0.0.1:port"),
+            Err(PeerUrlError::InvalidPort("127.0.0.1:port".to_owned()))
+        );
+        assert_eq!(
+            normalize_peer("http://127.0.0.1:15600"),
+            Err(PeerUrlError::UnsupportedScheme("http://127.0.0.1:15600".to_owned()))
+        );
+        assert_eq!(
+            normalize_peer("/ip4/example.com/tcp/15600"),
+            Err(PeerUrlError::InvalidMultiaddr("/ip4/example.com/tcp/15600".to_owned()))
+        );
+        assert_eq!(
+            normalize_peer("/dns/example.com/sctp/15600"),
+            Err(PeerUrlError::InvalidMultiaddr("/dns/example.com/sctp/15600".to_owned()))
+        );
+        assert_eq!(
+            normalize_peer("/dns/example.com/tcp"),
+            Err(PeerUrlError::InvalidMultiaddr("/dns/example.com/tcp".to_owned()))
+        );
+        assert_eq!(
+            normalize_peer("/dns/example.com/tcp/70000"),
+            Err(PeerUrlError::InvalidPort("/dns/example.com/tcp/70000".to_owned()))
+        );
+    }
+
+    #[test]
+    fn finish_normalizes_peers() {
+        let config = ManualPeeringConfigBuilder::new()
+            .add_peer("127.0.0.1:15600")
+            .add_peer("/dns/example.com/tcp/15600")
+            .finish()
+            .unwrap();
+
+        assert_eq!(
+            config.peers,
+            vec!["tcp://127.0.0.1:15600".to_owned(), "tcp://example.com:15600".to_owned()]
+        );
+    }
+
+    #[test]
+    fn finish_rejects_invalid_peer() {
+        let builder = ManualPeeringConfigBuilder::new()
+            .add_peer("tcp://127.0.0.1:15600")
+            .add_peer("tcp://127.0.0.1");
+
+        match builder.finish() {
+            Err(PeerUrlError::MissingPort(_)) => (),
+            _ => unreachable!(),
+        }
+    }
 }
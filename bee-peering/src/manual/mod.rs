@@ -12,5 +12,5 @@
 mod config;
 mod manual;
 
-pub use config::{ManualPeeringConfig, ManualPeeringConfigBuilder};
+pub use config::{ManualPeeringConfig, ManualPeeringConfigBuilder, PeerUrlError};
 pub use manual::ManualPeerManager;
@@ -0,0 +1,166 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use crate::{
+    cores::Cores,
+    provider::{FallbackPoW, LocalPoW, NonceProvider, RemotePoW},
+};
+
+use serde::Deserialize;
+
+use std::{error, fmt, time::Duration};
+
+const DEFAULT_PROVIDER: &str = "local";
+const DEFAULT_REMOTE_URL: &str = "http://localhost:14265";
+const DEFAULT_TIMEOUT: u64 = 60;
+const DEFAULT_FALLBACK: bool = true;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum NonceProviderKind {
+    Local,
+    Remote,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum PowConfigError {
+    UnknownProvider(String),
+}
+
+impl fmt::Display for PowConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownProvider(provider) => {
+                write!(
+                    f,
+                    "pow.provider is \"{}\" but must be \"local\" or \"remote\"",
+                    provider
+                )
+            }
+        }
+    }
+}
+
+impl error::Error for PowConfigError {}
+
+#[derive(Default, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct PowConfigBuilder {
+    /// Nonce provider, "local" or "remote".
+    provider: Option<String>,
+    /// Number of cores used by the local nonce search, all of them by default.
+    cores: Option<usize>,
+    /// URL of the node the remote nonce search is delegated to.
+    remote_url: Option<String>,
+    /// Time in seconds after which a nonce search is given up.
+    timeout: Option<u64>,
+    /// Whether a failed remote nonce search falls back to a local one.
+    fallback: Option<bool>,
+}
+
+impl PowConfigBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn provider(mut self, provider: String) -> Self {
+        self.provider.replace(provider);
+        self
+    }
+
+    pub fn cores(mut self, cores: usize) -> Self {
+        self.cores.replace(cores);
+        self
+    }
+
+    pub fn remote_url(mut self, remote_url: String) -> Self {
+        self.remote_url.replace(remote_url);
+        self
+    }
+
+    pub fn timeout(mut self, timeout: u64) -> Self {
+        self.timeout.replace(timeout);
+        self
+    }
+
+    pub fn fallback(mut self, fallback: bool) -> Self {
+        self.fallback.replace(fallback);
+        self
+    }
+
+    pub fn finish(self) -> Result<PowConfig, PowConfigError> {
+        let provider = match self.provider.unwrap_or_else(|| DEFAULT_PROVIDER.to_owned()).as_str() {
+            "local" => NonceProviderKind::Local,
+            "remote" => NonceProviderKind::Remote,
+            provider => return Err(PowConfigError::UnknownProvider(provider.to_owned())),
+        };
+
+        Ok(PowConfig {
+            provider,
+            cores: self.cores.map(Cores::from).unwrap_or_else(Cores::max),
+            remote_url: self.remote_url.unwrap_or_else(|| DEFAULT_REMOTE_URL.to_owned()),
+            timeout: Duration::from_secs(self.timeout.unwrap_or(DEFAULT_TIMEOUT)),
+            fallback: self.fallback.unwrap_or(DEFAULT_FALLBACK),
+        })
+    }
+}
+
+#[derive(Clone)]
+pub struct PowConfig {
+    pub(crate) provider: NonceProviderKind,
+    pub(crate) cores: Cores,
+    pub(crate) remote_url: String,
+    pub(crate) timeout: Duration,
+    pub(crate) fallback: bool,
+}
+
+impl PowConfig {
+    pub fn build() -> PowConfigBuilder {
+        PowConfigBuilder::new()
+    }
+
+    pub fn provider_kind(&self) -> &NonceProviderKind {
+        &self.provider
+    }
+
+    pub fn cores(&self) -> &Cores {
+        &self.cores
+    }
+
+    pub fn remote_url(&self) -> &str {
+        &self.remote_url
+    }
+
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    pub fn fallback(&self) -> bool {
+        self.fallback
+    }
+
+    /// Creates the nonce provider selected by the configuration. A remote provider falls back to a local search, if
+    /// enabled.
+    pub fn provider(&self) -> Box<dyn NonceProvider> {
+        let local = LocalPoW::new(self.cores.clone()).with_timeout(self.timeout);
+
+        match self.provider {
+            NonceProviderKind::Local => Box::new(local),
+            NonceProviderKind::Remote => {
+                let remote = RemotePoW::new(self.remote_url.clone(), self.timeout);
+                if self.fallback {
+                    Box::new(FallbackPoW::new(remote, local))
+                } else {
+                    Box::new(remote)
+                }
+            }
+        }
+    }
+}
@@ -13,6 +13,10 @@ pub const TRANSACTION_TRIT_LEN: usize = 8019;
 pub const HASH_TRIT_LEN: usize = 243;
 pub const NONCE_TRIT_LEN: usize = 81;
 
+// Positions of the trunk and branch hashes in a transaction, needed by remote attach services.
+pub const TRUNK_TRIT_OFFSET: usize = 7290;
+pub const BRANCH_TRIT_OFFSET: usize = TRUNK_TRIT_OFFSET + HASH_TRIT_LEN;
+
 pub const MAINNET_DIFFICULTY: usize = 14;
 pub const COMNET_DIFFICULTY: usize = 10;
 pub const DEVNET_DIFFICULTY: usize = 9;
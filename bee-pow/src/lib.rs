@@ -9,6 +9,7 @@
 // an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and limitations under the License.
 
+mod config;
 mod constants;
 mod cores;
 mod difficulty;
@@ -16,9 +17,14 @@ mod input;
 mod nonce;
 mod pearldiver;
 mod powcurlstate;
+mod provider;
 mod trit;
 
+pub use config::{NonceProviderKind, PowConfig, PowConfigBuilder, PowConfigError};
 pub use cores::Cores;
 pub use difficulty::Difficulty;
+pub use input::InputTrits;
+pub use nonce::NonceTrits;
 pub use pearldiver::*;
+pub use provider::{Error, FallbackPoW, LocalPoW, NonceProvider, RemotePoW};
 use trit::Trit;
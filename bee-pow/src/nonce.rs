@@ -13,6 +13,8 @@ use crate::Trit;
 
 use crate::constants::NONCE_TRIT_LEN as NONCE_LEN;
 
+use bee_ternary::{T1B1Buf, TritBuf, Trits, T1B1};
+
 #[derive(Copy)]
 pub struct NonceTrits(pub(crate) [Trit; NONCE_LEN]);
 
@@ -24,6 +26,11 @@ impl NonceTrits {
     pub fn as_slice(&self) -> &[i8] {
         &self.0[..]
     }
+
+    pub fn to_trit_buf(&self) -> TritBuf<T1B1Buf> {
+        // Safe to unwrap since the nonce only contains valid trits.
+        Trits::<T1B1>::try_from_raw(&self.0, NONCE_LEN).unwrap().to_buf()
+    }
 }
 
 impl Default for NonceTrits {
@@ -32,6 +39,12 @@ impl Default for NonceTrits {
     }
 }
 
+impl std::fmt::Debug for NonceTrits {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("NonceTrits").field(&&self.0[..]).finish()
+    }
+}
+
 impl Eq for NonceTrits {}
 impl PartialEq for NonceTrits {
     fn eq(&self, other: &Self) -> bool {
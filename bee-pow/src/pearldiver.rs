@@ -45,11 +45,17 @@ impl PearlDiver {
     }
 
     pub fn search_sync(&mut self, input: &InputTrits) {
-        assert!(self.state() == PearlDiverState::Created);
-
         let mut prestate = make_prestate(input);
 
-        self.set_state(PearlDiverState::Searching);
+        {
+            // The search may have been cancelled while computing the prestate.
+            let mut state = self.state.write().unwrap();
+            match *state {
+                PearlDiverState::Created => *state = PearlDiverState::Searching,
+                PearlDiverState::Cancelled => return,
+                _ => panic!("A PearlDiver can only search once."),
+            }
+        }
 
         let num_cores = self.cores.clone();
 
@@ -64,12 +70,15 @@ impl PearlDiver {
                     let mut state_tmp = PowCurlState::new(BITS_1);
 
                     while *pdstate.read().unwrap() == PearlDiverState::Searching {
+                        // The state is hashed in place, so keep the candidate nonces around to extract the right one.
+                        let mut state_hash = state_thr.clone();
                         unsafe {
-                            transform(&mut state_thr, &mut state_tmp);
+                            transform(&mut state_hash, &mut state_tmp);
                         }
 
-                        if let Some(nonce) = find_nonce(&state_thr, &difficulty) {
-                            *pdstate.write().unwrap() = PearlDiverState::Completed(Some(nonce));
+                        if let Some(slot) = find_nonce_slot(&state_hash, &difficulty) {
+                            *pdstate.write().unwrap() =
+                                PearlDiverState::Completed(Some(extract_nonce(&state_thr, slot)));
                             break;
                         } else if inner_increment(&mut state_thr) {
                             break;
@@ -91,8 +100,9 @@ impl PearlDiver {
     }
 
     pub fn cancel(&mut self) {
-        if self.state() == PearlDiverState::Searching {
-            self.set_state(PearlDiverState::Cancelled);
+        let mut state = self.state.write().unwrap();
+        if let PearlDiverState::Created | PearlDiverState::Searching = *state {
+            *state = PearlDiverState::Cancelled;
         }
     }
 
@@ -136,7 +146,7 @@ fn make_prestate(input: &InputTrits) -> PowCurlState {
             match (*input)[offset] {
                 1 => prestate.set(i, BITS_1, BITS_0),
                 -1 => prestate.set(i, BITS_0, BITS_1),
-                _ => (),
+                _ => prestate.set(i, BITS_1, BITS_1),
             }
             offset += 1;
         }
@@ -150,7 +160,7 @@ fn make_prestate(input: &InputTrits) -> PowCurlState {
         match (*input)[offset] {
             1 => prestate.set(i, BITS_1, BITS_0),
             -1 => prestate.set(i, BITS_0, BITS_1),
-            _ => (),
+            _ => prestate.set(i, BITS_1, BITS_1),
         }
         offset += 1;
     }
@@ -213,7 +223,8 @@ unsafe fn transform(pre: &mut PowCurlState, tmp: &mut PowCurlState) {
     }
 }
 
-fn find_nonce(state: &PowCurlState, difficulty: &Difficulty) -> Option<NonceTrits> {
+/// Returns the slot of the first candidate nonce whose hash satisfies the difficulty, if any.
+fn find_nonce_slot(state: &PowCurlState, difficulty: &Difficulty) -> Option<usize> {
     let mut nonce_test = BITS_1;
 
     for i in (HASH_LEN - **difficulty)..HASH_LEN {
@@ -226,16 +237,10 @@ fn find_nonce(state: &PowCurlState, difficulty: &Difficulty) -> Option<NonceTrit
         }
     }
 
-    for slot in 0..BATCH_SIZE {
-        if (nonce_test >> slot) & 1 != 0 {
-            return Some(extract_nonce(&state, slot));
-        }
-    }
-
-    unreachable!()
+    (0..BATCH_SIZE).find(|&slot| (nonce_test >> slot) & 1 != 0)
 }
 
-/// Extracts the nonce from the final Curl state and the given slot index.
+/// Extracts the nonce from the Curl state before hashing and the given slot index.
 fn extract_nonce(state: &PowCurlState, slot: usize) -> NonceTrits {
     let mut nonce = [0; NONCE_LEN];
     let slotmask = 1 << slot;
@@ -243,9 +248,9 @@ fn extract_nonce(state: &PowCurlState, slot: usize) -> NonceTrits {
     for (offset, i) in (CHUNK_NONCE_START..HASH_LEN).enumerate() {
         let (hi, lo) = state.get(i);
 
-        match (hi & slotmask, lo & slotmask) {
-            (1, 0) => nonce[offset] = 1,
-            (0, 1) => nonce[offset] = -1,
+        match (hi & slotmask != 0, lo & slotmask != 0) {
+            (true, false) => nonce[offset] = 1,
+            (false, true) => nonce[offset] = -1,
             (_, _) => (),
         }
     }
@@ -0,0 +1,53 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use crate::{
+    nonce::NonceTrits,
+    provider::{Error, NonceProvider},
+};
+
+use bee_ternary::{Trits, T1B1};
+
+use async_trait::async_trait;
+use log::warn;
+
+/// Uses a fallback provider whenever the primary one fails, typically a local search when a remote service is
+/// unreachable or too slow.
+pub struct FallbackPoW<P, F> {
+    primary: P,
+    fallback: F,
+}
+
+impl<P, F> FallbackPoW<P, F> {
+    pub fn new(primary: P, fallback: F) -> Self {
+        Self { primary, fallback }
+    }
+}
+
+#[async_trait]
+impl<P: NonceProvider, F: NonceProvider> NonceProvider for FallbackPoW<P, F> {
+    fn provide(&self, trits: &Trits<T1B1>, mwm: u8) -> Result<NonceTrits, Error> {
+        self.primary.provide(trits, mwm).or_else(|e| {
+            warn!("Primary nonce provider failed: {}. Using the fallback one.", e);
+            self.fallback.provide(trits, mwm)
+        })
+    }
+
+    async fn provide_async(&self, trits: &Trits<T1B1>, mwm: u8) -> Result<NonceTrits, Error> {
+        match self.primary.provide_async(trits, mwm).await {
+            Ok(nonce) => Ok(nonce),
+            Err(e) => {
+                warn!("Primary nonce provider failed: {}. Using the fallback one.", e);
+                self.fallback.provide_async(trits, mwm).await
+            }
+        }
+    }
+}
@@ -0,0 +1,90 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use crate::{
+    cores::Cores,
+    difficulty::Difficulty,
+    nonce::NonceTrits,
+    pearldiver::{PearlDiver, PearlDiverState},
+    provider::{input_trits, Error, NonceProvider},
+};
+
+use bee_ternary::{T1B1Buf, Trits, T1B1};
+
+use async_trait::async_trait;
+
+use std::{
+    sync::mpsc::{channel, RecvTimeoutError},
+    thread,
+    time::Duration,
+};
+
+/// Multi-threaded CurlP nonce search on the local machine.
+#[derive(Clone)]
+pub struct LocalPoW {
+    cores: Cores,
+    timeout: Option<Duration>,
+}
+
+impl LocalPoW {
+    pub fn new(cores: Cores) -> Self {
+        Self { cores, timeout: None }
+    }
+
+    /// Cancels the search if it didn't complete within `timeout`.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout.replace(timeout);
+        self
+    }
+}
+
+impl Default for LocalPoW {
+    fn default() -> Self {
+        Self::new(Cores::max())
+    }
+}
+
+#[async_trait]
+impl NonceProvider for LocalPoW {
+    fn provide(&self, trits: &Trits<T1B1>, mwm: u8) -> Result<NonceTrits, Error> {
+        let input = input_trits(trits)?;
+        let mut pearl_diver = PearlDiver::new(self.cores.clone(), Difficulty::from(mwm as usize));
+        let (done_tx, done_rx) = channel::<()>();
+
+        if let Some(timeout) = self.timeout {
+            let mut canceller = pearl_diver.clone();
+            thread::spawn(move || {
+                // The sender is dropped as soon as the search is over, whatever its outcome.
+                if let Err(RecvTimeoutError::Timeout) = done_rx.recv_timeout(timeout) {
+                    canceller.cancel();
+                }
+            });
+        }
+
+        pearl_diver.search_sync(&input);
+        drop(done_tx);
+
+        match pearl_diver.state() {
+            PearlDiverState::Completed(Some(nonce)) => Ok(nonce),
+            PearlDiverState::Completed(None) => Err(Error::Exhausted),
+            _ => Err(Error::Cancelled),
+        }
+    }
+
+    async fn provide_async(&self, trits: &Trits<T1B1>, mwm: u8) -> Result<NonceTrits, Error> {
+        let provider = self.clone();
+        let trits = trits.to_buf::<T1B1Buf>();
+
+        tokio::task::spawn_blocking(move || provider.provide(&trits, mwm))
+            .await
+            .map_err(|_| Error::Cancelled)?
+    }
+}
@@ -0,0 +1,82 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+mod fallback;
+mod local;
+mod remote;
+
+pub use fallback::FallbackPoW;
+pub use local::LocalPoW;
+pub use remote::RemotePoW;
+
+use crate::{
+    constants::{NONCE_TRIT_LEN, TRANSACTION_TRIT_LEN},
+    input::InputTrits,
+    nonce::NonceTrits,
+};
+
+use bee_crypto::ternary::{
+    sponge::{CurlP81, Sponge},
+    Hash,
+};
+use bee_ternary::{T1B1Buf, Trits, T1B1};
+
+use async_trait::async_trait;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Invalid transaction length: {0} trits.")]
+    InvalidLength(usize),
+    #[error("No nonce satisfying the difficulty was found.")]
+    Exhausted,
+    #[error("Proof of work was cancelled.")]
+    Cancelled,
+    #[error("Remote proof of work failed: {0}.")]
+    Remote(String),
+    #[error("Invalid response from the remote proof of work service.")]
+    InvalidResponse,
+    #[error("The provided nonce doesn't satisfy the difficulty.")]
+    InvalidNonce,
+}
+
+/// A source of nonces making transactions satisfy a minimum weight magnitude.
+#[async_trait]
+pub trait NonceProvider: Send + Sync {
+    /// Finds a nonce for the transaction `trits`, of which the nonce part is ignored, such that the hash of the
+    /// transaction has at least `mwm` trailing zero trits.
+    fn provide(&self, trits: &Trits<T1B1>, mwm: u8) -> Result<NonceTrits, Error>;
+
+    /// Asynchronous version of `provide`, that doesn't block the executor.
+    async fn provide_async(&self, trits: &Trits<T1B1>, mwm: u8) -> Result<NonceTrits, Error>;
+}
+
+pub(crate) fn input_trits(trits: &Trits<T1B1>) -> Result<InputTrits, Error> {
+    if trits.len() != TRANSACTION_TRIT_LEN {
+        return Err(Error::InvalidLength(trits.len()));
+    }
+
+    let mut input = [0; TRANSACTION_TRIT_LEN];
+    input.copy_from_slice(trits.as_i8_slice());
+
+    Ok(InputTrits(input))
+}
+
+/// Returns whether setting `nonce` in the transaction `trits` makes it satisfy `mwm`.
+pub(crate) fn is_valid_nonce(trits: &Trits<T1B1>, nonce: &NonceTrits, mwm: u8) -> bool {
+    let mut trits = trits.to_buf::<T1B1Buf>();
+    trits[TRANSACTION_TRIT_LEN - NONCE_TRIT_LEN..].copy_from(&nonce.to_trit_buf());
+
+    match CurlP81::default().digest(&trits) {
+        Ok(hash) => Hash::from_inner_unchecked(hash).weight() >= mwm,
+        Err(_) => false,
+    }
+}
@@ -0,0 +1,149 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use crate::{
+    constants::{BRANCH_TRIT_OFFSET, HASH_TRIT_LEN, NONCE_TRIT_LEN, TRANSACTION_TRIT_LEN, TRUNK_TRIT_OFFSET},
+    nonce::NonceTrits,
+    provider::{input_trits, is_valid_nonce, Error, NonceProvider},
+};
+
+use bee_ternary::{T1B1Buf, T3B1Buf, Trits, TryteBuf, T1B1};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use std::time::Duration;
+
+const API_VERSION_HEADER: &str = "X-IOTA-API-Version";
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AttachToTangleRequest {
+    command: &'static str,
+    trunk_transaction: String,
+    branch_transaction: String,
+    min_weight_magnitude: u8,
+    trytes: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct AttachToTangleResponse {
+    trytes: Vec<String>,
+}
+
+/// Delegates the nonce search to a remote `attachToTangle` service.
+///
+/// The nonce is taken from the attached transaction returned by the service and checked against the local
+/// transaction, so services altering other fields than the nonce are rejected.
+#[derive(Clone)]
+pub struct RemotePoW {
+    url: String,
+    timeout: Duration,
+}
+
+impl RemotePoW {
+    pub fn new(url: String, timeout: Duration) -> Self {
+        Self { url, timeout }
+    }
+
+    fn request(&self, trits: &Trits<T1B1>, mwm: u8) -> Result<AttachToTangleRequest, Error> {
+        input_trits(trits)?;
+
+        Ok(AttachToTangleRequest {
+            command: "attachToTangle",
+            trunk_transaction: trytes(&trits[TRUNK_TRIT_OFFSET..][..HASH_TRIT_LEN]),
+            branch_transaction: trytes(&trits[BRANCH_TRIT_OFFSET..][..HASH_TRIT_LEN]),
+            min_weight_magnitude: mwm,
+            trytes: vec![trytes(trits)],
+        })
+    }
+
+    fn nonce(trits: &Trits<T1B1>, mwm: u8, response: AttachToTangleResponse) -> Result<NonceTrits, Error> {
+        let attached = response.trytes.first().ok_or(Error::InvalidResponse)?;
+        let attached = TryteBuf::try_from_str(attached)
+            .map_err(|_| Error::InvalidResponse)?
+            .as_trits()
+            .encode::<T1B1Buf>();
+
+        if attached.len() != TRANSACTION_TRIT_LEN {
+            return Err(Error::InvalidResponse);
+        }
+
+        let mut nonce = NonceTrits::default();
+        nonce
+            .0
+            .copy_from_slice(attached[TRANSACTION_TRIT_LEN - NONCE_TRIT_LEN..].as_i8_slice());
+
+        if is_valid_nonce(trits, &nonce, mwm) {
+            Ok(nonce)
+        } else {
+            Err(Error::InvalidNonce)
+        }
+    }
+}
+
+fn trytes(trits: &Trits<T1B1>) -> String {
+    trits
+        .encode::<T3B1Buf>()
+        .as_trytes()
+        .iter()
+        .map(|tryte| char::from(*tryte))
+        .collect()
+}
+
+fn map_error(e: reqwest::Error) -> Error {
+    if e.is_decode() {
+        Error::InvalidResponse
+    } else {
+        Error::Remote(e.to_string())
+    }
+}
+
+#[async_trait]
+impl NonceProvider for RemotePoW {
+    /// Must not be called from within an asynchronous runtime, `provide_async` has to be used instead.
+    fn provide(&self, trits: &Trits<T1B1>, mwm: u8) -> Result<NonceTrits, Error> {
+        let response = reqwest::blocking::Client::builder()
+            .timeout(self.timeout)
+            .build()
+            .map_err(map_error)?
+            .post(&self.url)
+            .header(API_VERSION_HEADER, "1")
+            .json(&self.request(trits, mwm)?)
+            .send()
+            .and_then(|response| response.error_for_status())
+            .map_err(map_error)?
+            .json()
+            .map_err(map_error)?;
+
+        Self::nonce(trits, mwm, response)
+    }
+
+    async fn provide_async(&self, trits: &Trits<T1B1>, mwm: u8) -> Result<NonceTrits, Error> {
+        let request = self.request(trits, mwm)?;
+        let response = reqwest::Client::builder()
+            .timeout(self.timeout)
+            .build()
+            .map_err(map_error)?
+            .post(&self.url)
+            .header(API_VERSION_HEADER, "1")
+            .json(&request)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(map_error)?
+            .json()
+            .await
+            .map_err(map_error)?;
+
+        Self::nonce(trits, mwm, response)
+    }
+}
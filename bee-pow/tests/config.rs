@@ -0,0 +1,34 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use bee_pow::{NonceProviderKind, PowConfigBuilder, PowConfigError};
+
+#[test]
+fn default_provider() {
+    let config = PowConfigBuilder::new().finish().unwrap();
+
+    assert_eq!(config.provider_kind(), &NonceProviderKind::Local);
+}
+
+#[test]
+fn remote_provider() {
+    let config = PowConfigBuilder::new().provider("remote".to_owned()).finish().unwrap();
+
+    assert_eq!(config.provider_kind(), &NonceProviderKind::Remote);
+}
+
+#[test]
+fn unknown_provider() {
+    assert_eq!(
+        PowConfigBuilder::new().provider("gpu".to_owned()).finish().err(),
+        Some(PowConfigError::UnknownProvider("gpu".to_owned()))
+    );
+}
@@ -0,0 +1,210 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use bee_crypto::ternary::{
+    sponge::{CurlP81, Sponge},
+    Hash,
+};
+use bee_pow::{Cores, Error, FallbackPoW, LocalPoW, NonceProvider, NonceTrits, RemotePoW};
+use bee_ternary::{T1B1Buf, T3B1Buf, TritBuf, Trits, T1B1};
+
+use rand::Rng;
+
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::TcpListener,
+    thread,
+    time::Duration,
+};
+
+const TRANSACTION_TRIT_LEN: usize = 8019;
+const NONCE_TRIT_LEN: usize = 81;
+const MWM: u8 = 5;
+
+fn rand_transaction() -> TritBuf<T1B1Buf> {
+    let mut rng = rand::thread_rng();
+    let raw = (0..TRANSACTION_TRIT_LEN)
+        .map(|_| rng.gen_range(-1i8, 2))
+        .collect::<Vec<i8>>();
+
+    Trits::<T1B1>::try_from_raw(&raw, TRANSACTION_TRIT_LEN)
+        .unwrap()
+        .to_buf()
+}
+
+fn attach(transaction: &Trits<T1B1>, nonce: &NonceTrits) -> TritBuf<T1B1Buf> {
+    let mut attached = transaction.to_buf::<T1B1Buf>();
+    attached[TRANSACTION_TRIT_LEN - NONCE_TRIT_LEN..].copy_from(&nonce.to_trit_buf());
+    attached
+}
+
+fn weight(transaction: &Trits<T1B1>) -> u8 {
+    Hash::from_inner_unchecked(CurlP81::default().digest(transaction).unwrap()).weight()
+}
+
+fn trytes(trits: &Trits<T1B1>) -> String {
+    trits
+        .encode::<T3B1Buf>()
+        .as_trytes()
+        .iter()
+        .map(|tryte| char::from(*tryte))
+        .collect()
+}
+
+// Serves a single `attachToTangle` request, answering with `attached`, and returns the URL to reach it.
+fn stub_server(attached: TritBuf<T1B1Buf>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+
+    thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream);
+        let mut content_length = 0;
+
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            let line = line.trim_end().to_lowercase();
+            if line.is_empty() {
+                break;
+            }
+            if let Some(length) = line.strip_prefix("content-length:") {
+                content_length = length.trim().parse().unwrap();
+            }
+        }
+
+        let mut request = vec![0; content_length];
+        reader.read_exact(&mut request).unwrap();
+        let request: serde_json::Value = serde_json::from_slice(&request).unwrap();
+        assert_eq!(request["command"], "attachToTangle");
+        assert_eq!(request["minWeightMagnitude"], MWM);
+
+        let body = serde_json::json!({ "trytes": [trytes(&attached)] }).to_string();
+        write!(
+            reader.get_mut(),
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        )
+        .unwrap();
+    });
+
+    url
+}
+
+fn unreachable_url() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    format!("http://{}", listener.local_addr().unwrap())
+}
+
+#[test]
+fn local_low_mwm() {
+    let transaction = rand_transaction();
+    let nonce = LocalPoW::default().provide(&transaction, MWM).unwrap();
+
+    assert!(weight(&attach(&transaction, &nonce)) >= MWM);
+}
+
+#[tokio::test]
+async fn local_low_mwm_async() {
+    let transaction = rand_transaction();
+    let nonce = LocalPoW::new(Cores::from(2))
+        .provide_async(&transaction, MWM)
+        .await
+        .unwrap();
+
+    assert!(weight(&attach(&transaction, &nonce)) >= MWM);
+}
+
+#[test]
+fn local_timeout() {
+    let transaction = rand_transaction();
+
+    match LocalPoW::new(Cores::from(1))
+        .with_timeout(Duration::from_millis(10))
+        .provide(&transaction, 60)
+    {
+        Err(Error::Cancelled) => (),
+        other => panic!("Expected the search to be cancelled, got {:?}.", other),
+    }
+}
+
+#[test]
+fn local_invalid_length() {
+    let transaction = TritBuf::<T1B1Buf>::zeros(TRANSACTION_TRIT_LEN - 1);
+
+    match LocalPoW::default().provide(&transaction, MWM) {
+        Err(Error::InvalidLength(length)) => assert_eq!(length, TRANSACTION_TRIT_LEN - 1),
+        other => panic!("Expected an invalid length, got {:?}.", other),
+    }
+}
+
+#[test]
+fn remote_stub() {
+    let transaction = rand_transaction();
+    let expected = LocalPoW::default().provide(&transaction, MWM).unwrap();
+    let url = stub_server(attach(&transaction, &expected));
+
+    let nonce = RemotePoW::new(url, Duration::from_secs(5))
+        .provide(&transaction, MWM)
+        .unwrap();
+
+    assert_eq!(nonce, expected);
+}
+
+#[test]
+fn remote_altered_transaction() {
+    let transaction = rand_transaction();
+    let other = rand_transaction();
+    let nonce = LocalPoW::default().provide(&other, MWM).unwrap();
+    let url = stub_server(attach(&other, &nonce));
+
+    match RemotePoW::new(url, Duration::from_secs(5)).provide(&transaction, MWM) {
+        Err(Error::InvalidNonce) => (),
+        other => panic!("Expected an invalid nonce, got {:?}.", other),
+    }
+}
+
+#[test]
+fn remote_unreachable() {
+    let transaction = rand_transaction();
+
+    match RemotePoW::new(unreachable_url(), Duration::from_secs(5)).provide(&transaction, MWM) {
+        Err(Error::Remote(_)) => (),
+        other => panic!("Expected a remote error, got {:?}.", other),
+    }
+}
+
+#[test]
+fn fallback_to_local() {
+    let transaction = rand_transaction();
+    let provider = FallbackPoW::new(
+        RemotePoW::new(unreachable_url(), Duration::from_secs(5)),
+        LocalPoW::default(),
+    );
+
+    let nonce = provider.provide(&transaction, MWM).unwrap();
+
+    assert!(weight(&attach(&transaction, &nonce)) >= MWM);
+}
+
+#[tokio::test]
+async fn fallback_to_local_async() {
+    let transaction = rand_transaction();
+    let provider = FallbackPoW::new(
+        RemotePoW::new(unreachable_url(), Duration::from_secs(5)),
+        LocalPoW::default(),
+    );
+
+    let nonce = provider.provide_async(&transaction, MWM).await.unwrap();
+
+    assert!(weight(&attach(&transaction, &nonce)) >= MWM);
+}
@@ -10,13 +10,20 @@
 // See the License for the specific language governing permissions and limitations under the License.
 
 use bee_crypto::ternary::sponge::SpongeKind;
-use bee_ternary::{T1B1Buf, T5B1Buf, TryteBuf};
-use bee_transaction::bundled::{Address, BundledTransactionField};
+use bee_ternary::{T1B1Buf, T5B1Buf, Trits, TryteBuf, T5B1};
+use bee_transaction::bundled::{
+    constants::{ADDRESS_CHECKSUM_TRIT_LEN, ADDRESS_TRIT_LEN, HASH_BYTE_LEN},
+    Address, BundledTransactionField,
+};
 
 use bytemuck::cast_slice;
 use serde::Deserialize;
 
+use std::{fmt, ops::RangeInclusive};
+
 const DEFAULT_MWM: u8 = 14;
+const DEFAULT_MWM_HISTORICAL: u8 = 0;
+const DEFAULT_ATTACHMENT_BOUNDS_VALIDATION: &str = "lenient";
 const DEFAULT_COO_DEPTH: u8 = 25;
 const DEFAULT_COO_PUBLIC_KEY: &str =
     "UDYXTZBE9GZGPM9SSQV9LTZNDLJIZMPUVVXYXFYVBLIEUHLSEWFTKZZLXYRHHWVQV9MNNX9KZC9D9UZWZ";
@@ -26,27 +33,256 @@ const DEFAULT_TRANSACTION_WORKER_CACHE: usize = 10000;
 const DEFAULT_STATUS_INTERVAL: u64 = 10;
 const DEFAULT_HANDSHAKE_WINDOW: u64 = 10;
 const DEFAULT_MS_SYNC_COUNT: u32 = 1;
+const DEFAULT_BROADCAST_BUDGET: u64 = 0;
+const DEFAULT_TPS_INTERVAL: u64 = 1000;
+const DEFAULT_TPS_WINDOW: u64 = 60;
+const DEFAULT_SOLIDIFICATION_ASSISTANCE: bool = false;
+const DEFAULT_TRANSACTION_REQUEST_MAX_RETRIES: u8 = 10;
+const DEFAULT_MAX_OUTSTANDING_REQUESTS: u64 = 1000;
+const DEFAULT_METRICS_PERSISTENCE: bool = true;
+const DEFAULT_METRICS_PERSISTENCE_INTERVAL: u64 = 60;
+const DEFAULT_MS_MAX_CONE_SIZE: usize = 50_000;
+const DEFAULT_MS_VALIDATION_CACHE: usize = 256;
+pub(crate) const DEFAULT_MS_HASH_CACHE: usize = 1000;
+const DEFAULT_BROADCASTER_ENABLED: bool = true;
+const DEFAULT_RESPONDERS_ENABLED: bool = true;
+const DEFAULT_TPS_ENABLED: bool = true;
+const DEFAULT_STATUS_ENABLED: bool = true;
+const DEFAULT_CHANNEL_WARN_FRACTION: f64 = 0.8;
+const DEFAULT_CHANNEL_WARN_DURATION: u64 = 10;
+const DEFAULT_CHANNEL_SOFT_BOUND: usize = 10_000;
+const DEFAULT_REVALIDATION_DEPTH: u32 = 50;
+
+const COO_PUBLIC_KEY_TRYTE_LEN: usize = ADDRESS_TRIT_LEN / 3;
+const COO_PUBLIC_KEY_CHECKSUM_TRYTE_LEN: usize = ADDRESS_CHECKSUM_TRIT_LEN / 3;
+const COO_PUBLIC_KEY_HEX_LEN: usize = 2 * HASH_BYTE_LEN;
+
+const MWM_RANGE: RangeInclusive<u8> = 1..=18;
+const COO_SECURITY_RANGE: RangeInclusive<u8> = 1..=3;
+
+/// A violation found while validating a protocol configuration.
+#[derive(Debug, PartialEq)]
+pub enum ProtocolConfigError {
+    InvalidMwm(u8),
+    /// The historical minimum weight magnitude is above the current one.
+    MwmHistoricalAboveMwm(u8, u8),
+    InvalidCooPublicKey(String),
+    InvalidCooPublicKeyChecksum(String),
+    InvalidCooSecurityLevel(u8),
+    InvalidCooSpongeType(String),
+    InvalidAttachmentBoundsValidation(String),
+    /// A worker setting that must be at least 1 is 0.
+    ZeroWorkerSetting(&'static str),
+    ZeroHandshakeWindow,
+    /// A worker setting that is enabled requires a worker that is disabled.
+    DisabledWorkerRequired(&'static str, &'static str),
+    InvalidChannelWarnFraction(f64),
+}
+
+impl fmt::Display for ProtocolConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidMwm(mwm) => write!(
+                f,
+                "mwm {} is out of range [{}, {}]",
+                mwm,
+                MWM_RANGE.start(),
+                MWM_RANGE.end()
+            ),
+            Self::MwmHistoricalAboveMwm(mwm_historical, mwm) => {
+                write!(f, "mwm_historical {} is above mwm {}", mwm_historical, mwm)
+            }
+            Self::InvalidCooPublicKey(key) => write!(
+                f,
+                "coordinator public_key {:?} is neither {} trytes, {} trytes ending with a checksum, nor {} hex \
+                characters",
+                key,
+                COO_PUBLIC_KEY_TRYTE_LEN,
+                COO_PUBLIC_KEY_TRYTE_LEN + COO_PUBLIC_KEY_CHECKSUM_TRYTE_LEN,
+                COO_PUBLIC_KEY_HEX_LEN
+            ),
+            Self::InvalidCooPublicKeyChecksum(key) => {
+                write!(f, "coordinator public_key {:?} does not match its checksum", key)
+            }
+            Self::InvalidCooSecurityLevel(level) => write!(
+                f,
+                "coordinator security level {} is out of range [{}, {}]",
+                level,
+                COO_SECURITY_RANGE.start(),
+                COO_SECURITY_RANGE.end()
+            ),
+            Self::InvalidCooSpongeType(sponge_type) => write!(
+                f,
+                "coordinator sponge type {:?} is not one of \"kerl\", \"curl27\" or \"curl81\"",
+                sponge_type
+            ),
+            Self::InvalidAttachmentBoundsValidation(validation) => write!(
+                f,
+                "attachment bounds validation {:?} is not one of \"strict\" or \"lenient\"",
+                validation
+            ),
+            Self::ZeroWorkerSetting(name) => write!(f, "workers {} must be at least 1", name),
+            Self::ZeroHandshakeWindow => write!(f, "handshake window must be at least 1s"),
+            Self::DisabledWorkerRequired(setting, worker) => {
+                write!(f, "workers {} requires the {} to be enabled", setting, worker)
+            }
+            Self::InvalidChannelWarnFraction(fraction) => {
+                write!(f, "workers channel_warn_fraction {} is out of range ]0, 1]", fraction)
+            }
+        }
+    }
+}
+
+fn sponge_kind(sponge_type: &str) -> Option<SpongeKind> {
+    match sponge_type {
+        "kerl" => Some(SpongeKind::Kerl),
+        "curl27" => Some(SpongeKind::CurlP27),
+        "curl81" => Some(SpongeKind::CurlP81),
+        _ => None,
+    }
+}
+
+/// How the attachment timestamp bounds of received transactions are validated.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AttachmentBoundsValidation {
+    /// Every transaction must carry bounds containing its attachment timestamp.
+    Strict,
+    /// Same as `Strict`, except that all-zero bounds, found in historical data, don't constrain the attachment
+    /// timestamp.
+    Lenient,
+}
+
+fn attachment_bounds_validation(validation: &str) -> Option<AttachmentBoundsValidation> {
+    match validation {
+        "strict" => Some(AttachmentBoundsValidation::Strict),
+        "lenient" => Some(AttachmentBoundsValidation::Lenient),
+        _ => None,
+    }
+}
+
+/// Parses a coordinator public key given as trytes, optionally followed by a checksum, or as hex encoded T5B1 bytes.
+fn public_key(public_key: &str) -> Result<Address, ProtocolConfigError> {
+    let invalid = || ProtocolConfigError::InvalidCooPublicKey(public_key.to_owned());
+
+    // Also makes slicing by byte index below safe.
+    if !public_key.is_ascii() {
+        return Err(invalid());
+    }
+
+    if public_key.len() == COO_PUBLIC_KEY_HEX_LEN {
+        let bytes = hex::decode(public_key).map_err(|_| invalid())?;
+        let trits = Trits::<T5B1>::try_from_raw(cast_slice(&bytes), ADDRESS_TRIT_LEN).map_err(|_| invalid())?;
+
+        return Address::try_from_inner(trits.encode::<T1B1Buf>()).map_err(|_| invalid());
+    }
+
+    let checksum = match public_key.len() {
+        COO_PUBLIC_KEY_TRYTE_LEN => None,
+        len if len == COO_PUBLIC_KEY_TRYTE_LEN + COO_PUBLIC_KEY_CHECKSUM_TRYTE_LEN => {
+            Some(&public_key[COO_PUBLIC_KEY_TRYTE_LEN..])
+        }
+        _ => return Err(invalid()),
+    };
+
+    let address = TryteBuf::try_from_str(&public_key[..COO_PUBLIC_KEY_TRYTE_LEN])
+        .ok()
+        .and_then(|trytes| Address::try_from_inner(trytes.as_trits().encode::<T1B1Buf>()).ok())
+        .ok_or_else(invalid)?;
+
+    if let Some(checksum) = checksum {
+        let checksum = TryteBuf::try_from_str(checksum).map_err(|_| invalid())?;
+
+        if checksum.as_trits().encode::<T1B1Buf>() != address.checksum() {
+            return Err(ProtocolConfigError::InvalidCooPublicKeyChecksum(public_key.to_owned()));
+        }
+    }
+
+    Ok(address)
+}
 
 #[derive(Default, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 struct ProtocolCoordinatorConfigBuilder {
+    /// Depth of the Merkle tree of the coordinator.
     depth: Option<u8>,
+    /// Address of the coordinator: 81 trytes, 90 trytes ending with a checksum, or 98 hex characters encoding its T5B1
+    /// bytes.
     public_key: Option<String>,
+    /// Security level of the signatures of the coordinator, between 1 and 3.
     security_level: Option<u8>,
+    /// Sponge used by the coordinator, one of "kerl", "curl27" or "curl81".
     sponge_type: Option<String>,
 }
 
 #[derive(Default, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 struct ProtocolWorkersConfigBuilder {
+    /// Number of recently received transactions remembered to skip duplicates before hashing.
     transaction_worker_cache: Option<usize>,
+    /// Interval in seconds between status logs.
     status_interval: Option<u64>,
+    /// Number of milestones requested ahead of the latest solid one while syncing.
     ms_sync_count: Option<u32>,
+    /// Outbound bytes per second allowed for broadcasting, 0 meaning unlimited.
+    broadcast_budget: Option<u64>,
+    /// Interval in milliseconds between transaction rate measurements.
+    tps_interval: Option<u64>,
+    /// Window in seconds the transaction rates are averaged over.
+    tps_window: Option<u64>,
+    /// Whether the parents of a transaction requested by a syncing peer are sent along with it.
+    solidification_assistance: Option<bool>,
+    /// Number of times a transaction is requested again before giving up on it.
+    transaction_request_max_retries: Option<u8>,
+    /// Number of unanswered transaction and milestone requests a peer can have before it is skipped for new ones, 0
+    /// meaning unlimited.
+    max_outstanding_requests: Option<u64>,
+    /// Whether lifetime metrics are persisted to the storage.
+    metrics_persistence: Option<bool>,
+    /// Interval in seconds between metrics persistences.
+    metrics_persistence_interval: Option<u64>,
+    /// Maximum number of transactions in the past cone of a milestone, per milestone the node is behind, 0 meaning
+    /// unlimited.
+    ms_max_cone_size: Option<usize>,
+    /// Number of recently validated milestones whose outcome is remembered to skip validating them again.
+    ms_validation_cache: Option<usize>,
+    /// Number of recent milestone hashes cached for fast lookups, 0 disabling the cache.
+    ms_hash_cache: Option<usize>,
+    /// Whether transactions are broadcast to the peers; they are still processed otherwise.
+    broadcaster_enabled: Option<bool>,
+    /// Whether the transaction and milestone requests of the peers are answered; they are ignored otherwise.
+    responders_enabled: Option<bool>,
+    /// Whether the transaction rates are measured.
+    tps_enabled: Option<bool>,
+    /// Whether the status of the node is logged.
+    status_enabled: Option<bool>,
+    /// Fraction of its bound above which a worker channel is considered backed up, in ]0, 1].
+    channel_warn_fraction: Option<f64>,
+    /// Number of seconds a worker channel has to stay backed up before a warning is logged.
+    channel_warn_duration: Option<u64>,
+    /// Bound assumed for the unbounded worker channels when checking whether they are backed up.
+    channel_soft_bound: Option<usize>,
+    /// Number of milestones up to the latest solid one that are checked after a run that did not shut down cleanly,
+    /// 0 meaning that they are not checked.
+    revalidation_depth: Option<u32>,
 }
 
 #[derive(Default, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ProtocolConfigBuilder {
+    /// Minimum weight magnitude of received transactions, between 1 and 18.
     mwm: Option<u8>,
+    /// Minimum weight magnitude of the transactions requested to solidify milestones, which may have been attached
+    /// with a lower one than the current `mwm`, at most `mwm` and 0 meaning that their weight is not checked.
+    mwm_historical: Option<u8>,
+    /// Validation of the attachment timestamp bounds of received transactions, one of "strict" or "lenient", the
+    /// latter accepting all-zero bounds.
+    attachment_bounds_validation: Option<String>,
+    /// Coordinator issuing the milestones.
     coordinator: ProtocolCoordinatorConfigBuilder,
+    /// Tuning of the protocol workers.
     workers: ProtocolWorkersConfigBuilder,
+    /// Maximum difference in seconds between the clocks of the node and of a handshaking peer.
+    /// A large window weakens the protection against replayed handshakes and should be avoided.
     handshake_window: Option<u64>,
 }
 
@@ -60,6 +296,17 @@ impl ProtocolConfigBuilder {
         self
     }
 
+    pub fn mwm_historical(mut self, mwm_historical: u8) -> Self {
+        self.mwm_historical.replace(mwm_historical);
+        self
+    }
+
+    pub fn attachment_bounds_validation(mut self, attachment_bounds_validation: &str) -> Self {
+        self.attachment_bounds_validation
+            .replace(attachment_bounds_validation.to_string());
+        self
+    }
+
     pub fn coo_depth(mut self, coo_depth: u8) -> Self {
         self.coordinator.depth.replace(coo_depth);
         self
@@ -90,54 +337,239 @@ impl ProtocolConfigBuilder {
         self
     }
 
+    pub fn broadcast_budget(mut self, broadcast_budget: u64) -> Self {
+        self.workers.broadcast_budget.replace(broadcast_budget);
+        self
+    }
+
     pub fn status_interval(mut self, status_interval: u64) -> Self {
         self.workers.status_interval.replace(status_interval);
         self
     }
 
+    pub fn tps_interval(mut self, tps_interval: u64) -> Self {
+        self.workers.tps_interval.replace(tps_interval);
+        self
+    }
+
+    pub fn tps_window(mut self, tps_window: u64) -> Self {
+        self.workers.tps_window.replace(tps_window);
+        self
+    }
+
+    pub fn solidification_assistance(mut self, solidification_assistance: bool) -> Self {
+        self.workers
+            .solidification_assistance
+            .replace(solidification_assistance);
+        self
+    }
+
+    pub fn transaction_request_max_retries(mut self, transaction_request_max_retries: u8) -> Self {
+        self.workers
+            .transaction_request_max_retries
+            .replace(transaction_request_max_retries);
+        self
+    }
+
+    pub fn max_outstanding_requests(mut self, max_outstanding_requests: u64) -> Self {
+        self.workers.max_outstanding_requests.replace(max_outstanding_requests);
+        self
+    }
+
+    pub fn metrics_persistence(mut self, metrics_persistence: bool) -> Self {
+        self.workers.metrics_persistence.replace(metrics_persistence);
+        self
+    }
+
+    pub fn metrics_persistence_interval(mut self, metrics_persistence_interval: u64) -> Self {
+        self.workers
+            .metrics_persistence_interval
+            .replace(metrics_persistence_interval);
+        self
+    }
+
+    pub fn ms_max_cone_size(mut self, ms_max_cone_size: usize) -> Self {
+        self.workers.ms_max_cone_size.replace(ms_max_cone_size);
+        self
+    }
+
+    pub fn ms_validation_cache(mut self, ms_validation_cache: usize) -> Self {
+        self.workers.ms_validation_cache.replace(ms_validation_cache);
+        self
+    }
+
+    pub fn ms_hash_cache(mut self, ms_hash_cache: usize) -> Self {
+        self.workers.ms_hash_cache.replace(ms_hash_cache);
+        self
+    }
+
+    pub fn broadcaster_enabled(mut self, broadcaster_enabled: bool) -> Self {
+        self.workers.broadcaster_enabled.replace(broadcaster_enabled);
+        self
+    }
+
+    pub fn responders_enabled(mut self, responders_enabled: bool) -> Self {
+        self.workers.responders_enabled.replace(responders_enabled);
+        self
+    }
+
+    pub fn tps_enabled(mut self, tps_enabled: bool) -> Self {
+        self.workers.tps_enabled.replace(tps_enabled);
+        self
+    }
+
+    pub fn status_enabled(mut self, status_enabled: bool) -> Self {
+        self.workers.status_enabled.replace(status_enabled);
+        self
+    }
+
+    pub fn channel_warn_fraction(mut self, channel_warn_fraction: f64) -> Self {
+        self.workers.channel_warn_fraction.replace(channel_warn_fraction);
+        self
+    }
+
+    pub fn channel_warn_duration(mut self, channel_warn_duration: u64) -> Self {
+        self.workers.channel_warn_duration.replace(channel_warn_duration);
+        self
+    }
+
+    pub fn channel_soft_bound(mut self, channel_soft_bound: usize) -> Self {
+        self.workers.channel_soft_bound.replace(channel_soft_bound);
+        self
+    }
+
+    pub fn revalidation_depth(mut self, revalidation_depth: u32) -> Self {
+        self.workers.revalidation_depth.replace(revalidation_depth);
+        self
+    }
+
     pub fn handshake_window(mut self, handshake_window: u64) -> Self {
         self.handshake_window.replace(handshake_window);
         self
     }
 
+    /// Returns every violation of the configured values, omitted values being replaced by valid defaults.
+    pub fn validate(&self) -> Result<(), Vec<ProtocolConfigError>> {
+        let mut errors = Vec::new();
+
+        if let Some(mwm) = self.mwm {
+            if !MWM_RANGE.contains(&mwm) {
+                errors.push(ProtocolConfigError::InvalidMwm(mwm));
+            }
+        }
+
+        if let Some(mwm_historical) = self.mwm_historical {
+            let mwm = self.mwm.unwrap_or(DEFAULT_MWM);
+
+            if mwm_historical > mwm {
+                errors.push(ProtocolConfigError::MwmHistoricalAboveMwm(mwm_historical, mwm));
+            }
+        }
+
+        if let Some(validation) = &self.attachment_bounds_validation {
+            if attachment_bounds_validation(validation).is_none() {
+                errors.push(ProtocolConfigError::InvalidAttachmentBoundsValidation(
+                    validation.clone(),
+                ));
+            }
+        }
+
+        if let Some(key) = &self.coordinator.public_key {
+            if let Err(e) = public_key(key) {
+                errors.push(e);
+            }
+        }
+
+        if let Some(level) = self.coordinator.security_level {
+            if !COO_SECURITY_RANGE.contains(&level) {
+                errors.push(ProtocolConfigError::InvalidCooSecurityLevel(level));
+            }
+        }
+
+        if let Some(sponge_type) = &self.coordinator.sponge_type {
+            if sponge_kind(sponge_type).is_none() {
+                errors.push(ProtocolConfigError::InvalidCooSpongeType(sponge_type.clone()));
+            }
+        }
+
+        let workers = [
+            (
+                "transaction_worker_cache",
+                self.workers.transaction_worker_cache.map(|v| v as u64),
+            ),
+            ("status_interval", self.workers.status_interval),
+            ("ms_sync_count", self.workers.ms_sync_count.map(u64::from)),
+            ("tps_interval", self.workers.tps_interval),
+            ("tps_window", self.workers.tps_window),
+            (
+                "metrics_persistence_interval",
+                self.workers.metrics_persistence_interval,
+            ),
+            ("channel_warn_duration", self.workers.channel_warn_duration),
+            ("channel_soft_bound", self.workers.channel_soft_bound.map(|v| v as u64)),
+            (
+                "ms_validation_cache",
+                self.workers.ms_validation_cache.map(|v| v as u64),
+            ),
+        ];
+
+        for (name, _) in workers.iter().filter(|(_, value)| *value == Some(0)) {
+            errors.push(ProtocolConfigError::ZeroWorkerSetting(*name));
+        }
+
+        // The parents sent to assist the solidification of a peer go through the broadcaster.
+        if self.workers.solidification_assistance == Some(true) && self.workers.broadcaster_enabled == Some(false) {
+            errors.push(ProtocolConfigError::DisabledWorkerRequired(
+                "solidification_assistance",
+                "broadcaster",
+            ));
+        }
+
+        if let Some(fraction) = self.workers.channel_warn_fraction {
+            // Written this way to reject NaN as well.
+            if !(fraction > 0.0 && fraction <= 1.0) {
+                errors.push(ProtocolConfigError::InvalidChannelWarnFraction(fraction));
+            }
+        }
+
+        if self.handshake_window == Some(0) {
+            errors.push(ProtocolConfigError::ZeroHandshakeWindow);
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Builds the configuration, invalid values being silently replaced by their defaults; see `validate`.
     pub fn finish(self) -> ProtocolConfig {
-        let coo_sponge_type = match self
+        let coo_sponge_type = self
             .coordinator
             .sponge_type
-            .unwrap_or_else(|| DEFAULT_COO_SPONGE_TYPE.to_owned())
-            .as_str()
-        {
-            "kerl" => SpongeKind::Kerl,
-            "curl27" => SpongeKind::CurlP27,
-            "curl81" => SpongeKind::CurlP81,
-            _ => SpongeKind::Kerl,
-        };
+            .as_deref()
+            .and_then(sponge_kind)
+            .unwrap_or_else(|| sponge_kind(DEFAULT_COO_SPONGE_TYPE).unwrap());
 
-        let coo_public_key_default = Address::from_inner_unchecked(
-            TryteBuf::try_from_str(DEFAULT_COO_PUBLIC_KEY)
-                .unwrap()
-                .as_trits()
-                .encode::<T1B1Buf>(),
-        );
-
-        let coo_public_key = match TryteBuf::try_from_str(
-            &self
-                .coordinator
-                .public_key
-                .unwrap_or_else(|| DEFAULT_COO_PUBLIC_KEY.to_owned()),
-        ) {
-            Ok(trytes) => match Address::try_from_inner(trytes.as_trits().encode::<T1B1Buf>()) {
-                Ok(coo_public_key) => coo_public_key,
-                Err(_) => coo_public_key_default,
-            },
-            Err(_) => coo_public_key_default,
-        };
+        let coo_public_key = self
+            .coordinator
+            .public_key
+            .as_deref()
+            .and_then(|key| public_key(key).ok())
+            .unwrap_or_else(|| public_key(DEFAULT_COO_PUBLIC_KEY).unwrap());
 
-        let mut public_key_bytes = [0u8; 49];
+        let mut public_key_bytes = [0u8; HASH_BYTE_LEN];
         public_key_bytes.copy_from_slice(cast_slice(coo_public_key.to_inner().encode::<T5B1Buf>().as_i8_slice()));
 
         ProtocolConfig {
             mwm: self.mwm.unwrap_or(DEFAULT_MWM),
+            mwm_historical: self.mwm_historical.unwrap_or(DEFAULT_MWM_HISTORICAL),
+            attachment_bounds_validation: self
+                .attachment_bounds_validation
+                .as_deref()
+                .and_then(attachment_bounds_validation)
+                .unwrap_or_else(|| attachment_bounds_validation(DEFAULT_ATTACHMENT_BOUNDS_VALIDATION).unwrap()),
             coordinator: ProtocolCoordinatorConfig {
                 depth: self.coordinator.depth.unwrap_or(DEFAULT_COO_DEPTH),
                 public_key: coo_public_key,
@@ -152,6 +584,43 @@ impl ProtocolConfigBuilder {
                     .unwrap_or(DEFAULT_TRANSACTION_WORKER_CACHE),
                 status_interval: self.workers.status_interval.unwrap_or(DEFAULT_STATUS_INTERVAL),
                 ms_sync_count: self.workers.ms_sync_count.unwrap_or(DEFAULT_MS_SYNC_COUNT),
+                broadcast_budget: self.workers.broadcast_budget.unwrap_or(DEFAULT_BROADCAST_BUDGET),
+                tps_interval: self.workers.tps_interval.unwrap_or(DEFAULT_TPS_INTERVAL),
+                tps_window: self.workers.tps_window.unwrap_or(DEFAULT_TPS_WINDOW),
+                solidification_assistance: self
+                    .workers
+                    .solidification_assistance
+                    .unwrap_or(DEFAULT_SOLIDIFICATION_ASSISTANCE),
+                transaction_request_max_retries: self
+                    .workers
+                    .transaction_request_max_retries
+                    .unwrap_or(DEFAULT_TRANSACTION_REQUEST_MAX_RETRIES),
+                max_outstanding_requests: self
+                    .workers
+                    .max_outstanding_requests
+                    .unwrap_or(DEFAULT_MAX_OUTSTANDING_REQUESTS),
+                metrics_persistence: self.workers.metrics_persistence.unwrap_or(DEFAULT_METRICS_PERSISTENCE),
+                metrics_persistence_interval: self
+                    .workers
+                    .metrics_persistence_interval
+                    .unwrap_or(DEFAULT_METRICS_PERSISTENCE_INTERVAL),
+                ms_max_cone_size: self.workers.ms_max_cone_size.unwrap_or(DEFAULT_MS_MAX_CONE_SIZE),
+                ms_validation_cache: self.workers.ms_validation_cache.unwrap_or(DEFAULT_MS_VALIDATION_CACHE),
+                ms_hash_cache: self.workers.ms_hash_cache.unwrap_or(DEFAULT_MS_HASH_CACHE),
+                broadcaster_enabled: self.workers.broadcaster_enabled.unwrap_or(DEFAULT_BROADCASTER_ENABLED),
+                responders_enabled: self.workers.responders_enabled.unwrap_or(DEFAULT_RESPONDERS_ENABLED),
+                tps_enabled: self.workers.tps_enabled.unwrap_or(DEFAULT_TPS_ENABLED),
+                status_enabled: self.workers.status_enabled.unwrap_or(DEFAULT_STATUS_ENABLED),
+                channel_warn_fraction: self
+                    .workers
+                    .channel_warn_fraction
+                    .unwrap_or(DEFAULT_CHANNEL_WARN_FRACTION),
+                channel_warn_duration: self
+                    .workers
+                    .channel_warn_duration
+                    .unwrap_or(DEFAULT_CHANNEL_WARN_DURATION),
+                channel_soft_bound: self.workers.channel_soft_bound.unwrap_or(DEFAULT_CHANNEL_SOFT_BOUND),
+                revalidation_depth: self.workers.revalidation_depth.unwrap_or(DEFAULT_REVALIDATION_DEPTH),
             },
             handshake_window: self.handshake_window.unwrap_or(DEFAULT_HANDSHAKE_WINDOW),
         }
@@ -162,7 +631,7 @@ impl ProtocolConfigBuilder {
 pub struct ProtocolCoordinatorConfig {
     pub(crate) depth: u8,
     pub(crate) public_key: Address,
-    pub(crate) public_key_bytes: [u8; 49],
+    pub(crate) public_key_bytes: [u8; HASH_BYTE_LEN],
     pub(crate) security_level: u8,
     pub(crate) sponge_type: SpongeKind,
 }
@@ -171,6 +640,10 @@ impl ProtocolCoordinatorConfig {
     pub fn depth(&self) -> u8 {
         self.depth
     }
+
+    pub fn public_key(&self) -> &Address {
+        &self.public_key
+    }
 }
 
 #[derive(Clone)]
@@ -178,11 +651,51 @@ pub struct ProtocolWorkersConfig {
     pub(crate) transaction_worker_cache: usize,
     pub(crate) status_interval: u64,
     pub(crate) ms_sync_count: u32,
+    pub(crate) broadcast_budget: u64,
+    // In milliseconds.
+    pub(crate) tps_interval: u64,
+    // In seconds.
+    pub(crate) tps_window: u64,
+    // Whether the parents of a transaction requested by a syncing peer are sent along with it.
+    pub(crate) solidification_assistance: bool,
+    // Number of times a transaction is requested again before giving up on it.
+    pub(crate) transaction_request_max_retries: u8,
+    // Per peer, 0 meaning unlimited.
+    pub(crate) max_outstanding_requests: u64,
+    // Whether lifetime metrics are persisted to the storage.
+    pub(crate) metrics_persistence: bool,
+    // In seconds.
+    pub(crate) metrics_persistence_interval: u64,
+    // Per milestone the node is behind, 0 meaning unlimited.
+    pub(crate) ms_max_cone_size: usize,
+    pub(crate) ms_validation_cache: usize,
+    // 0 meaning disabled.
+    pub(crate) ms_hash_cache: usize,
+    // Whether the optional workers are started.
+    pub(crate) broadcaster_enabled: bool,
+    pub(crate) responders_enabled: bool,
+    pub(crate) tps_enabled: bool,
+    pub(crate) status_enabled: bool,
+    pub(crate) channel_warn_fraction: f64,
+    // In seconds.
+    pub(crate) channel_warn_duration: u64,
+    pub(crate) channel_soft_bound: usize,
+    // 0 meaning that no milestone is checked after a crash.
+    pub(crate) revalidation_depth: u32,
+}
+
+impl ProtocolWorkersConfig {
+    pub fn ms_max_cone_size(&self) -> usize {
+        self.ms_max_cone_size
+    }
 }
 
 #[derive(Clone)]
 pub struct ProtocolConfig {
     pub(crate) mwm: u8,
+    // 0 meaning that the weight of requested transactions is not checked.
+    pub(crate) mwm_historical: u8,
+    pub(crate) attachment_bounds_validation: AttachmentBoundsValidation,
     pub(crate) coordinator: ProtocolCoordinatorConfig,
     pub(crate) workers: ProtocolWorkersConfig,
     pub(crate) handshake_window: u64,
@@ -196,4 +709,297 @@ impl ProtocolConfig {
     pub fn coordinator(&self) -> &ProtocolCoordinatorConfig {
         &self.coordinator
     }
+
+    pub fn workers(&self) -> &ProtocolWorkersConfig {
+        &self.workers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use bee_ternary::{T3B1Buf, T1B1};
+
+    fn errors(toml: &str) -> Vec<ProtocolConfigError> {
+        match toml::from_str::<ProtocolConfigBuilder>(toml).unwrap().validate() {
+            Ok(()) => Vec::new(),
+            Err(errors) => errors,
+        }
+    }
+
+    #[test]
+    fn defaults_when_omitted() {
+        let builder = toml::from_str::<ProtocolConfigBuilder>("").unwrap();
+
+        assert_eq!(builder.validate(), Ok(()));
+
+        let config = builder.finish();
+
+        assert_eq!(config.mwm, DEFAULT_MWM);
+        assert_eq!(config.mwm_historical, DEFAULT_MWM_HISTORICAL);
+        assert_eq!(config.attachment_bounds_validation, AttachmentBoundsValidation::Lenient);
+        assert_eq!(config.coordinator.depth, DEFAULT_COO_DEPTH);
+        assert_eq!(
+            config.coordinator.public_key,
+            public_key(DEFAULT_COO_PUBLIC_KEY).unwrap()
+        );
+        assert_eq!(config.coordinator.security_level, DEFAULT_COO_SECURITY);
+        assert_eq!(config.workers.status_interval, DEFAULT_STATUS_INTERVAL);
+        assert_eq!(config.workers.tps_window, DEFAULT_TPS_WINDOW);
+        assert_eq!(
+            config.workers.transaction_request_max_retries,
+            DEFAULT_TRANSACTION_REQUEST_MAX_RETRIES
+        );
+        assert_eq!(
+            config.workers.max_outstanding_requests,
+            DEFAULT_MAX_OUTSTANDING_REQUESTS
+        );
+        assert_eq!(config.workers.metrics_persistence, DEFAULT_METRICS_PERSISTENCE);
+        assert_eq!(
+            config.workers.metrics_persistence_interval,
+            DEFAULT_METRICS_PERSISTENCE_INTERVAL
+        );
+        assert_eq!(config.workers.ms_max_cone_size, DEFAULT_MS_MAX_CONE_SIZE);
+        assert_eq!(config.workers.ms_validation_cache, DEFAULT_MS_VALIDATION_CACHE);
+        assert_eq!(config.workers.ms_hash_cache, DEFAULT_MS_HASH_CACHE);
+        assert!(config.workers.broadcaster_enabled);
+        assert!(config.workers.responders_enabled);
+        assert!(config.workers.tps_enabled);
+        assert!(config.workers.status_enabled);
+        assert_eq!(config.workers.channel_warn_duration, DEFAULT_CHANNEL_WARN_DURATION);
+        assert_eq!(config.workers.channel_soft_bound, DEFAULT_CHANNEL_SOFT_BOUND);
+        assert_eq!(config.workers.revalidation_depth, DEFAULT_REVALIDATION_DEPTH);
+        assert_eq!(config.handshake_window, DEFAULT_HANDSHAKE_WINDOW);
+    }
+
+    #[test]
+    fn defaults_for_omitted_sub_config_fields() {
+        let config = toml::from_str::<ProtocolConfigBuilder>("mwm = 9\n[workers]\ntps_window = 30")
+            .unwrap()
+            .finish();
+
+        assert_eq!(config.mwm, 9);
+        assert_eq!(config.workers.tps_window, 30);
+        assert_eq!(config.workers.tps_interval, DEFAULT_TPS_INTERVAL);
+        assert_eq!(config.coordinator.depth, DEFAULT_COO_DEPTH);
+    }
+
+    #[test]
+    fn invalid_mwm() {
+        assert_eq!(errors("mwm = 0"), vec![ProtocolConfigError::InvalidMwm(0)]);
+        assert_eq!(errors("mwm = 140"), vec![ProtocolConfigError::InvalidMwm(140)]);
+        assert!(errors("mwm = 18").is_empty());
+    }
+
+    #[test]
+    fn mwm_historical_above_mwm() {
+        assert_eq!(
+            errors("mwm = 9\nmwm_historical = 10"),
+            vec![ProtocolConfigError::MwmHistoricalAboveMwm(10, 9)]
+        );
+        assert_eq!(
+            errors("mwm_historical = 15"),
+            vec![ProtocolConfigError::MwmHistoricalAboveMwm(15, DEFAULT_MWM)]
+        );
+        assert!(errors("mwm = 9\nmwm_historical = 9").is_empty());
+        assert!(errors("mwm_historical = 0").is_empty());
+    }
+
+    #[test]
+    fn attachment_bounds_validation_modes() {
+        let validation = |toml: &str| {
+            toml::from_str::<ProtocolConfigBuilder>(toml)
+                .unwrap()
+                .finish()
+                .attachment_bounds_validation
+        };
+
+        assert_eq!(
+            validation("attachment_bounds_validation = \"strict\""),
+            AttachmentBoundsValidation::Strict
+        );
+        assert_eq!(
+            validation("attachment_bounds_validation = \"lenient\""),
+            AttachmentBoundsValidation::Lenient
+        );
+        assert_eq!(
+            errors("attachment_bounds_validation = \"loose\""),
+            vec![ProtocolConfigError::InvalidAttachmentBoundsValidation(
+                "loose".to_owned()
+            )]
+        );
+    }
+
+    #[test]
+    fn invalid_coo_public_key() {
+        assert_eq!(
+            errors("[coordinator]\npublic_key = \"ABC\""),
+            vec![ProtocolConfigError::InvalidCooPublicKey("ABC".to_owned())]
+        );
+        assert_eq!(
+            errors("[coordinator]\npublic_key = \"abc\""),
+            vec![ProtocolConfigError::InvalidCooPublicKey("abc".to_owned())]
+        );
+    }
+
+    fn trytes(trits: &Trits<T1B1>) -> String {
+        trits
+            .encode::<T3B1Buf>()
+            .as_trytes()
+            .iter()
+            .map(|tryte| char::from(*tryte))
+            .collect()
+    }
+
+    fn coo_public_key(toml_key: &str) -> Address {
+        toml::from_str::<ProtocolConfigBuilder>(&format!("[coordinator]\npublic_key = \"{}\"", toml_key))
+            .unwrap()
+            .finish()
+            .coordinator
+            .public_key
+    }
+
+    #[test]
+    fn coo_public_key_with_checksum() {
+        // The default coordinator address followed by its checksum, as displayed by wallets.
+        let key = "UDYXTZBE9GZGPM9SSQV9LTZNDLJIZMPUVVXYXFYVBLIEUHLSEWFTKZZLXYRHHWVQV9MNNX9KZC9D9UZWZRGJMIGPDW";
+
+        assert_eq!(key.len(), 90);
+        assert!(errors(&format!("[coordinator]\npublic_key = \"{}\"", key)).is_empty());
+        assert_eq!(coo_public_key(key), public_key(DEFAULT_COO_PUBLIC_KEY).unwrap());
+    }
+
+    #[test]
+    fn coo_public_key_with_invalid_checksum() {
+        let address = public_key(DEFAULT_COO_PUBLIC_KEY).unwrap();
+        let checksum = trytes(&address.checksum());
+        // Replaces the first tryte of the checksum with a different one.
+        let tryte = if checksum.starts_with('A') { 'B' } else { 'A' };
+        let key = format!("{}{}{}", DEFAULT_COO_PUBLIC_KEY, tryte, &checksum[1..]);
+
+        assert_eq!(
+            errors(&format!("[coordinator]\npublic_key = \"{}\"", key)),
+            vec![ProtocolConfigError::InvalidCooPublicKeyChecksum(key)]
+        );
+    }
+
+    #[test]
+    fn coo_public_key_as_hex() {
+        let address = public_key(DEFAULT_COO_PUBLIC_KEY).unwrap();
+        let key = hex::encode(cast_slice::<i8, u8>(
+            address.to_inner().encode::<T5B1Buf>().as_i8_slice(),
+        ));
+
+        assert_eq!(key.len(), 98);
+        assert!(errors(&format!("[coordinator]\npublic_key = \"{}\"", key)).is_empty());
+        assert_eq!(coo_public_key(&key), address);
+    }
+
+    #[test]
+    fn invalid_coo_public_key_forms() {
+        let too_long = format!("{}9", DEFAULT_COO_PUBLIC_KEY);
+        let invalid_checksum_trytes = format!("{}abcdefghi", DEFAULT_COO_PUBLIC_KEY);
+        let invalid_hex = "z".repeat(98);
+        let non_ascii = "é".repeat(45);
+
+        for key in [too_long, invalid_checksum_trytes, invalid_hex, non_ascii].iter() {
+            assert_eq!(
+                errors(&format!("[coordinator]\npublic_key = \"{}\"", key)),
+                vec![ProtocolConfigError::InvalidCooPublicKey(key.clone())]
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_coo_security_level() {
+        assert_eq!(
+            errors("[coordinator]\nsecurity_level = 4"),
+            vec![ProtocolConfigError::InvalidCooSecurityLevel(4)]
+        );
+    }
+
+    #[test]
+    fn invalid_coo_sponge_type() {
+        assert_eq!(
+            errors("[coordinator]\nsponge_type = \"curl\""),
+            vec![ProtocolConfigError::InvalidCooSpongeType("curl".to_owned())]
+        );
+    }
+
+    #[test]
+    fn zero_worker_settings() {
+        let toml = "[workers]
+            transaction_worker_cache = 0
+            status_interval = 0
+            ms_sync_count = 0
+            tps_interval = 0
+            tps_window = 0
+            metrics_persistence_interval = 0
+            channel_warn_duration = 0
+            channel_soft_bound = 0
+            ms_validation_cache = 0";
+
+        assert_eq!(
+            errors(toml),
+            vec![
+                ProtocolConfigError::ZeroWorkerSetting("transaction_worker_cache"),
+                ProtocolConfigError::ZeroWorkerSetting("status_interval"),
+                ProtocolConfigError::ZeroWorkerSetting("ms_sync_count"),
+                ProtocolConfigError::ZeroWorkerSetting("tps_interval"),
+                ProtocolConfigError::ZeroWorkerSetting("tps_window"),
+                ProtocolConfigError::ZeroWorkerSetting("metrics_persistence_interval"),
+                ProtocolConfigError::ZeroWorkerSetting("channel_warn_duration"),
+                ProtocolConfigError::ZeroWorkerSetting("channel_soft_bound"),
+                ProtocolConfigError::ZeroWorkerSetting("ms_validation_cache"),
+            ]
+        );
+    }
+
+    #[test]
+    fn assistance_requires_broadcaster() {
+        assert_eq!(
+            errors("[workers]\nsolidification_assistance = true\nbroadcaster_enabled = false"),
+            vec![ProtocolConfigError::DisabledWorkerRequired(
+                "solidification_assistance",
+                "broadcaster"
+            )]
+        );
+        assert!(errors("[workers]\nbroadcaster_enabled = false").is_empty());
+        assert!(errors("[workers]\nsolidification_assistance = true").is_empty());
+    }
+
+    #[test]
+    fn invalid_channel_warn_fraction() {
+        assert_eq!(
+            errors("[workers]\nchannel_warn_fraction = 0.0"),
+            vec![ProtocolConfigError::InvalidChannelWarnFraction(0.0)]
+        );
+        assert_eq!(
+            errors("[workers]\nchannel_warn_fraction = 1.5"),
+            vec![ProtocolConfigError::InvalidChannelWarnFraction(1.5)]
+        );
+        assert!(errors("[workers]\nchannel_warn_fraction = 1.0").is_empty());
+    }
+
+    #[test]
+    fn zero_handshake_window() {
+        assert_eq!(
+            errors("handshake_window = 0"),
+            vec![ProtocolConfigError::ZeroHandshakeWindow]
+        );
+    }
+
+    #[test]
+    fn all_violations_reported() {
+        assert_eq!(
+            errors("mwm = 0\nhandshake_window = 0\n[coordinator]\nsponge_type = \"curl\""),
+            vec![
+                ProtocolConfigError::InvalidMwm(0),
+                ProtocolConfigError::InvalidCooSpongeType("curl".to_owned()),
+                ProtocolConfigError::ZeroHandshakeWindow,
+            ]
+        );
+    }
 }
@@ -9,7 +9,7 @@
 // an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and limitations under the License.
 
-use crate::Milestone;
+use crate::{Milestone, MilestoneApplicationError, MilestoneIndex};
 
 use bee_crypto::ternary::Hash;
 use bee_network::EndpointId;
@@ -18,12 +18,77 @@ use std::net::SocketAddr;
 
 pub struct HandshakeCompleted(pub EndpointId, pub SocketAddr);
 
-pub struct LatestMilestoneChanged(pub Milestone);
+pub struct PeerRemoved(pub EndpointId);
+
+/// The latest milestone index advanced.
+pub struct LatestMilestoneChanged {
+    pub old: MilestoneIndex,
+    pub new: MilestoneIndex,
+    pub hash: Hash,
+}
 
 pub struct LatestSolidMilestoneChanged(pub Milestone);
 
+/// A milestone was received but could not be applied.
+pub struct MilestoneApplicationFailed {
+    pub index: MilestoneIndex,
+    pub error: MilestoneApplicationError,
+}
+
 pub struct TransactionSolidified(pub Hash);
 
+/// The tangle caught up with the latest milestone, all milestones up to it being solid.
+pub struct TangleSyncedEvent {
+    pub latest_ms: MilestoneIndex,
+}
+
+/// The past cone of a milestone exceeded the maximum size. Its solidification is deferred or, if it was already solid,
+/// the ledger halts before confirming it.
+pub struct MilestoneConeTooLarge {
+    pub index: MilestoneIndex,
+    /// Maximum number of transactions the cone was allowed to contain.
+    pub max_cone_size: usize,
+}
+
+/// A requested transaction was not received after the maximum number of retries.
+pub struct TransactionUnreachable {
+    pub hash: Hash,
+}
+
+/// Progress of the hashing of a batch of transactions.
+pub struct BatchHashingProgress {
+    /// Number of transactions of the batch hashed so far.
+    pub completed: usize,
+    /// Number of transactions in the batch.
+    pub total: usize,
+}
+
+/// Transaction rates averaged over a sliding window.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TransactionRates {
+    /// Incoming transactions per second.
+    pub itps: f64,
+    /// New transactions per second.
+    pub ntps: f64,
+    /// Confirmed transactions per second.
+    pub ctps: f64,
+    /// Ratio of confirmed to new transactions, 0 if no new transaction arrived.
+    pub confirmation_ratio: f64,
+}
+
+/// Status of the node, dispatched every time the status worker logs it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NodeStatusEvent {
+    /// Incoming transactions per second since the previous status.
+    pub tps: f64,
+    pub solid_ms_index: u32,
+    pub latest_ms_index: u32,
+    pub connected_peers: u8,
+    pub synced_peers: u8,
+    /// Resident memory of the node process, 0 if it could not be read.
+    pub memory_bytes: u64,
+}
+
 pub struct TpsMetricsUpdated {
     pub incoming: u64,
     pub new: u64,
@@ -31,4 +96,21 @@ pub struct TpsMetricsUpdated {
     pub stale: u64,
     pub invalid: u64,
     pub outgoing: u64,
+    pub confirmed: u64,
+    pub rates: TransactionRates,
+}
+
+/// Outcome of the re-validation of the recent milestones that follows a run that did not shut down cleanly.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct RevalidationCompleted {
+    /// Number of milestones checked.
+    pub milestones: usize,
+    /// Number of milestones whose transaction was missing; they are requested again.
+    pub missing_milestones: usize,
+    /// Number of transactions missing from the past cones of the milestones.
+    pub missing_transactions: usize,
+    /// Number of transactions whose solid or confirmed flag was cleared.
+    pub repaired_transactions: usize,
+    /// Whether the pass was interrupted by a shutdown before it was done.
+    pub interrupted: bool,
 }
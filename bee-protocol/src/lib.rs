@@ -21,6 +21,12 @@ mod peer;
 mod protocol;
 mod worker;
 
-pub use milestone::{Milestone, MilestoneIndex};
-pub use protocol::{Protocol, ProtocolMetrics};
+#[cfg(test)]
+mod test_utils;
+
+pub use milestone::{
+    max_cone_size, validate_milestone, Milestone, MilestoneApplicationError, MilestoneBuilderError, MilestoneIndex,
+    MilestoneIndexError, MILESTONE_INDEX_KEY_LENGTH,
+};
+pub use protocol::{MetricsSnapshot, MetricsStorage, PersistedMetrics, Protocol, ProtocolMetrics, SubmitError};
 pub use worker::{StorageWorker, TangleWorker};
@@ -11,13 +11,33 @@
 
 //! Message trait.
 
-use std::ops::Range;
+use std::{fmt, ops::Range};
+
+/// Errors that can happen when deserializing a message.
+#[derive(Debug, PartialEq)]
+pub(crate) enum MessageError {
+    /// The buffer length is not within the size range of the message.
+    InvalidLength(usize),
+    /// The advertised length exceeds the largest body a message can have.
+    TooLarge(usize),
+}
+
+impl fmt::Display for MessageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidLength(length) => write!(f, "length {} is out of the size range of the message", length),
+            Self::TooLarge(length) => write!(f, "advertised length {} exceeds the largest message body", length),
+        }
+    }
+}
+
+impl std::error::Error for MessageError {}
 
 /// A trait describing the behavior of a message.
 ///
 /// This trait is protocol agnostic and only provides serialization and deserialization to and from byte buffers.
-/// It should not be used as is but rather be paired with a higher layer - like a type-length-value encoding - and as
-/// such does not provide any bounds check on inputs/outputs buffers.
+/// It should not be used as is but rather be paired with a higher layer - like a type-length-value encoding. Since
+/// input buffers come from peers, deserialization checks their bounds while serialization does not.
 pub(crate) trait Message {
     /// The unique identifier of the message within the protocol.
     const ID: u8;
@@ -31,11 +51,12 @@ pub(crate) trait Message {
     ///
     /// * `bytes`   -   The byte buffer to deserialize from.
     ///
-    /// # Panics
+    /// # Errors
     ///
-    /// Panics if the provided buffer has an invalid size.
-    /// The size of the buffer should be within the range returned by the `size_range` method.
-    fn from_bytes(bytes: &[u8]) -> Self;
+    /// Fails if the size of the buffer is not within the range returned by the `size_range` method.
+    fn from_bytes(bytes: &[u8]) -> Result<Self, MessageError>
+    where
+        Self: Sized;
 
     /// Returns the size of the message.
     fn size(&self) -> usize;
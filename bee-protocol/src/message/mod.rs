@@ -11,7 +11,6 @@
 
 // TODO document
 
-mod compression;
 mod message;
 mod tlv;
 mod v0;
@@ -19,9 +18,8 @@ mod v1;
 mod v2;
 mod version;
 
-pub(crate) use compression::{compress_transaction_bytes, uncompress_transaction_bytes};
-pub(crate) use message::Message;
-pub(crate) use tlv::{tlv_from_bytes, tlv_into_bytes, Header, HEADER_SIZE};
+pub(crate) use message::{Message, MessageError};
+pub(crate) use tlv::{tlv_check_length, tlv_from_bytes, tlv_into_bytes, Header, TlvError, HEADER_SIZE};
 pub(crate) use v0::Handshake;
 pub(crate) use v2::{Heartbeat, MilestoneRequest, Transaction, TransactionRequest};
-pub(crate) use version::{messages_supported_version, MESSAGES_VERSIONS};
+pub(crate) use version::{advertised_versions, has_capability, messages_supported_version, RESPONDER_CAPABILITY};
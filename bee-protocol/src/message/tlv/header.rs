@@ -11,6 +11,8 @@
 
 //! Header of the type-length-value encoding.
 
+use crate::message::tlv::TlvError;
+
 use std::convert::TryInto;
 
 const HEADER_TYPE_SIZE: usize = 1;
@@ -27,12 +29,19 @@ pub(crate) struct Header {
 }
 
 impl Header {
-    pub(crate) fn from_bytes(bytes: &[u8]) -> Self {
-        Self {
-            message_type: bytes[0],
-            // TODO propagate error
-            message_length: u16::from_be_bytes(bytes[HEADER_TYPE_SIZE..HEADER_SIZE].try_into().unwrap()),
+    pub(crate) fn from_bytes(bytes: &[u8]) -> Result<Self, TlvError> {
+        if bytes.len() != HEADER_SIZE {
+            return Err(TlvError::InvalidHeaderLength(bytes.len()));
         }
+
+        Ok(Self {
+            message_type: bytes[0],
+            message_length: u16::from_be_bytes(
+                bytes[HEADER_TYPE_SIZE..HEADER_SIZE]
+                    .try_into()
+                    .map_err(|_| TlvError::InvalidHeaderLength(bytes.len()))?,
+            ),
+        })
     }
 
     pub(crate) fn to_bytes(&self, bytes: &mut [u8]) {
@@ -40,3 +49,38 @@ impl Header {
         bytes[1..].copy_from_slice(&self.message_length.to_be_bytes());
     }
 }
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn from_bytes() {
+        assert_eq!(
+            Header::from_bytes(&[0x02, 0x01, 0xf4]).unwrap(),
+            Header {
+                message_type: 0x02,
+                message_length: 500,
+            }
+        );
+    }
+
+    #[test]
+    fn from_bytes_invalid_length() {
+        match Header::from_bytes(&[]) {
+            Err(TlvError::InvalidHeaderLength(0)) => (),
+            _ => unreachable!(),
+        }
+
+        match Header::from_bytes(&[0x02, 0x01]) {
+            Err(TlvError::InvalidHeaderLength(2)) => (),
+            _ => unreachable!(),
+        }
+
+        match Header::from_bytes(&[0x02, 0x01, 0xf4, 0x00]) {
+            Err(TlvError::InvalidHeaderLength(4)) => (),
+            _ => unreachable!(),
+        }
+    }
+}
@@ -15,4 +15,4 @@ mod header;
 mod tlv;
 
 pub(crate) use header::{Header, HEADER_SIZE};
-pub(crate) use tlv::{tlv_from_bytes, tlv_into_bytes};
+pub(crate) use tlv::{tlv_check_length, tlv_from_bytes, tlv_into_bytes, TlvError};
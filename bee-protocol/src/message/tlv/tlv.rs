@@ -11,7 +11,9 @@
 
 //! Type-length-value encoding/decoding.
 
-use crate::message::{Header, Message, HEADER_SIZE};
+use crate::message::{Header, Message, MessageError, HEADER_SIZE};
+
+use std::fmt;
 
 #[allow(clippy::enum_variant_names)]
 #[derive(Debug)]
@@ -19,6 +21,65 @@ pub(crate) enum TlvError {
     InvalidAdvertisedType(u8, u8),
     InvalidAdvertisedLength(usize, usize),
     InvalidLength(usize),
+    InvalidHeaderLength(usize),
+    InvalidMessage(MessageError),
+}
+
+impl fmt::Display for TlvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidAdvertisedType(advertised, expected) => {
+                write!(f, "advertised message type {} is not {}", advertised, expected)
+            }
+            Self::InvalidAdvertisedLength(advertised, actual) => {
+                write!(
+                    f,
+                    "advertised message length {} is not the actual {}",
+                    advertised, actual
+                )
+            }
+            Self::InvalidLength(length) => write!(f, "length {} is out of the size range of the message", length),
+            Self::InvalidHeaderLength(length) => write!(f, "header length {} is invalid", length),
+            Self::InvalidMessage(e) => write!(f, "invalid message: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for TlvError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::InvalidMessage(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<MessageError> for TlvError {
+    fn from(e: MessageError) -> Self {
+        Self::InvalidMessage(e)
+    }
+}
+
+/// Largest body a supported message can carry: a legacy gossip made of a full transaction and a requested hash.
+pub(crate) const MAX_MESSAGE_BODY_LEN: usize = 1653;
+
+/// Checks the length advertised by a TLV header before any buffer is allocated to receive the message.
+///
+/// # Arguments
+///
+/// * `header`  -   The TLV header to check.
+///
+/// # Errors
+///
+/// * The advertised message length exceeds `MAX_MESSAGE_BODY_LEN`.
+pub(crate) fn tlv_check_length(header: &Header) -> Result<(), TlvError> {
+    if header.message_length as usize > MAX_MESSAGE_BODY_LEN {
+        return Err(TlvError::InvalidMessage(MessageError::TooLarge(
+            header.message_length as usize,
+        )));
+    }
+
+    Ok(())
 }
 
 /// Deserializes a TLV header and a byte buffer into a message.
@@ -33,6 +94,7 @@ pub(crate) enum TlvError {
 /// * The advertised message type does not match the required message type.
 /// * The advertised message length does not match the buffer length.
 /// * The buffer length is not within the allowed size range of the required message type.
+/// * The message itself can not be deserialized from the buffer.
 pub(crate) fn tlv_from_bytes<M: Message>(header: &Header, bytes: &[u8]) -> Result<M, TlvError> {
     if header.message_type != M::ID {
         return Err(TlvError::InvalidAdvertisedType(header.message_type, M::ID));
@@ -49,7 +111,7 @@ pub(crate) fn tlv_from_bytes<M: Message>(header: &Header, bytes: &[u8]) -> Resul
         return Err(TlvError::InvalidLength(bytes.len()));
     }
 
-    Ok(M::from_bytes(bytes))
+    Ok(M::from_bytes(bytes)?)
 }
 
 /// Serializes a TLV header and a message into a byte buffer.
@@ -142,6 +204,63 @@ mod tests {
         }
     }
 
+    fn max_body_length<M: Message>() {
+        assert!(M::size_range().end - 1 <= MAX_MESSAGE_BODY_LEN);
+    }
+
+    #[test]
+    fn max_body_length_fits_messages() {
+        max_body_length::<Handshake>();
+        max_body_length::<LegacyGossip>();
+        max_body_length::<MilestoneRequest>();
+        max_body_length::<TransactionMessage>();
+        max_body_length::<TransactionRequest>();
+        max_body_length::<Heartbeat>();
+    }
+
+    #[test]
+    fn max_body_length_accepted() {
+        assert!(tlv_check_length(&Header {
+            message_type: TransactionMessage::ID,
+            message_length: MAX_MESSAGE_BODY_LEN as u16,
+        })
+        .is_ok());
+    }
+
+    #[test]
+    fn oversized_body_length_rejected() {
+        for length in &[MAX_MESSAGE_BODY_LEN + 1, u16::MAX as usize] {
+            match tlv_check_length(&Header {
+                message_type: TransactionMessage::ID,
+                message_length: *length as u16,
+            }) {
+                Err(TlvError::InvalidMessage(MessageError::TooLarge(advertised_length))) => {
+                    assert_eq!(advertised_length, *length)
+                }
+                _ => unreachable!(),
+            }
+        }
+    }
+
+    #[test]
+    fn display_and_source() {
+        let error = TlvError::InvalidMessage(MessageError::TooLarge(2000));
+
+        assert_eq!(
+            error.to_string(),
+            "invalid message: advertised length 2000 exceeds the largest message body"
+        );
+        assert_eq!(
+            TlvError::InvalidAdvertisedType(3, 2).to_string(),
+            "advertised message type 3 is not 2"
+        );
+        assert_eq!(
+            std::error::Error::source(&error).and_then(|source| source.downcast_ref::<MessageError>()),
+            Some(&MessageError::TooLarge(2000))
+        );
+        assert!(std::error::Error::source(&TlvError::InvalidLength(0)).is_none());
+    }
+
     fn fuzz<M: Message>() {
         let mut rng = rand::thread_rng();
 
@@ -164,8 +283,35 @@ mod tests {
         }
     }
 
+    // Feeds arbitrary headers and payloads, as a malicious peer could, and only checks that nothing panics.
+    fn fuzz_arbitrary<M: Message>() {
+        let mut rng = rand::thread_rng();
+
+        for _ in 0..1000 {
+            let header_bytes: Vec<u8> = (0..rng.gen_range(0, HEADER_SIZE + 2)).map(|_| rng.gen()).collect();
+            let header = match Header::from_bytes(&header_bytes) {
+                Ok(header) => header,
+                Err(_) => continue,
+            };
+            // Bias the header towards the message under test so that the payload actually gets parsed.
+            let header = Header {
+                message_type: if rng.gen_bool(0.8) { M::ID } else { header.message_type },
+                message_length: header.message_length % (M::size_range().end as u16 + 1),
+            };
+            let length = if rng.gen_bool(0.8) {
+                header.message_length as usize
+            } else {
+                rng.gen_range(0, M::size_range().end + 1)
+            };
+            let bytes: Vec<u8> = (0..length).map(|_| rng.gen()).collect();
+
+            let _ = tlv_from_bytes::<M>(&header, &bytes);
+            let _ = M::from_bytes(&bytes);
+        }
+    }
+
     macro_rules! implement_tlv_tests {
-        ($type:ty, $iat:tt, $ial:tt, $loor:tt, $fuzz:tt) => {
+        ($type:ty, $iat:tt, $ial:tt, $loor:tt, $fuzz:tt, $fuzz_arbitrary:tt) => {
             #[test]
             fn $iat() {
                 invalid_advertised_type::<$type>();
@@ -185,6 +331,11 @@ mod tests {
             fn $fuzz() {
                 fuzz::<$type>();
             }
+
+            #[test]
+            fn $fuzz_arbitrary() {
+                fuzz_arbitrary::<$type>();
+            }
         };
     }
 
@@ -193,7 +344,8 @@ mod tests {
         invalid_advertised_type_handshake,
         invalid_advertised_length_handshake,
         length_out_of_range_handshake,
-        fuzz_handshake
+        fuzz_handshake,
+        fuzz_arbitrary_handshake
     );
 
     implement_tlv_tests!(
@@ -201,7 +353,8 @@ mod tests {
         invalid_advertised_type_legacy_gossip,
         invalid_advertised_length_legacy_gossip,
         length_out_of_range_legacy_gossip,
-        fuzz_legacy_gossip
+        fuzz_legacy_gossip,
+        fuzz_arbitrary_legacy_gossip
     );
 
     implement_tlv_tests!(
@@ -209,7 +362,8 @@ mod tests {
         invalid_advertised_type_milestone_request,
         invalid_advertised_length_milestone_request,
         length_out_of_range_milestone_request,
-        fuzz_milestone_request
+        fuzz_milestone_request,
+        fuzz_arbitrary_milestone_request
     );
 
     implement_tlv_tests!(
@@ -217,7 +371,8 @@ mod tests {
         invalid_advertised_type_transaction,
         invalid_advertised_length_transaction,
         length_out_of_range_transaction,
-        fuzz_transaction
+        fuzz_transaction,
+        fuzz_arbitrary_transaction
     );
 
     implement_tlv_tests!(
@@ -225,7 +380,8 @@ mod tests {
         invalid_advertised_type_transaction_request,
         invalid_advertised_length_transaction_request,
         length_out_of_range_transaction_request,
-        fuzz_transaction_request
+        fuzz_transaction_request,
+        fuzz_arbitrary_transaction_request
     );
 
     implement_tlv_tests!(
@@ -233,6 +389,7 @@ mod tests {
         invalid_advertised_type_heartbeat,
         invalid_advertised_length_heartbeat,
         length_out_of_range_heartbeat,
-        fuzz_range_heartbeat
+        fuzz_range_heartbeat,
+        fuzz_arbitrary_heartbeat
     );
 }
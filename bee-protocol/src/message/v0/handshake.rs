@@ -11,7 +11,9 @@
 
 //! Handshake message of the protocol version 0
 
-use crate::message::Message;
+use crate::message::{Message, MessageError};
+
+use bee_transaction::bundled::constants::HASH_BYTE_LEN;
 
 use std::{
     convert::TryInto,
@@ -21,7 +23,7 @@ use std::{
 
 const PORT_SIZE: usize = 2;
 const TIMESTAMP_SIZE: usize = 8;
-const COORDINATOR_SIZE: usize = 49;
+const COORDINATOR_SIZE: usize = HASH_BYTE_LEN;
 const MINIMUM_WEIGHT_MAGNITUDE_SIZE: usize = 1;
 const CONSTANT_SIZE: usize = PORT_SIZE + TIMESTAMP_SIZE + COORDINATOR_SIZE + MINIMUM_WEIGHT_MAGNITUDE_SIZE;
 const VARIABLE_MIN_SIZE: usize = 1;
@@ -88,24 +90,31 @@ impl Message for Handshake {
         (CONSTANT_SIZE + VARIABLE_MIN_SIZE)..(CONSTANT_SIZE + VARIABLE_MAX_SIZE + 1)
     }
 
-    fn from_bytes(bytes: &[u8]) -> Self {
+    fn from_bytes(bytes: &[u8]) -> Result<Self, MessageError> {
+        let len = bytes.len();
+
+        if !Self::size_range().contains(&len) {
+            return Err(MessageError::InvalidLength(len));
+        }
+
         let mut message = Self::default();
 
         let (bytes, next) = bytes.split_at(PORT_SIZE);
-        message.port = u16::from_be_bytes(bytes.try_into().expect("Invalid buffer size"));
+        message.port = u16::from_be_bytes(bytes.try_into().map_err(|_| MessageError::InvalidLength(len))?);
 
         let (bytes, next) = next.split_at(TIMESTAMP_SIZE);
-        message.timestamp = u64::from_be_bytes(bytes.try_into().expect("Invalid buffer size"));
+        message.timestamp = u64::from_be_bytes(bytes.try_into().map_err(|_| MessageError::InvalidLength(len))?);
 
         let (bytes, next) = next.split_at(COORDINATOR_SIZE);
         message.coordinator.copy_from_slice(bytes);
 
         let (bytes, next) = next.split_at(MINIMUM_WEIGHT_MAGNITUDE_SIZE);
-        message.minimum_weight_magnitude = u8::from_be_bytes(bytes.try_into().expect("Invalid buffer size"));
+        message.minimum_weight_magnitude =
+            u8::from_be_bytes(bytes.try_into().map_err(|_| MessageError::InvalidLength(len))?);
 
         message.supported_versions = next.to_vec();
 
-        message
+        Ok(message)
     }
 
     fn size(&self) -> usize {
@@ -147,6 +156,19 @@ mod tests {
         assert_eq!(Handshake::ID, 1);
     }
 
+    #[test]
+    fn from_bytes_invalid_length() {
+        assert_eq!(Handshake::from_bytes(&[]).err(), Some(MessageError::InvalidLength(0)));
+        assert_eq!(
+            Handshake::from_bytes(&[0; CONSTANT_SIZE]).err(),
+            Some(MessageError::InvalidLength(CONSTANT_SIZE))
+        );
+        assert_eq!(
+            Handshake::from_bytes(&[0; CONSTANT_SIZE + VARIABLE_MAX_SIZE + 1]).err(),
+            Some(MessageError::InvalidLength(CONSTANT_SIZE + VARIABLE_MAX_SIZE + 1))
+        );
+    }
+
     #[test]
     fn size_range() {
         assert_eq!(Handshake::size_range().contains(&60), false);
@@ -170,7 +192,7 @@ mod tests {
         let message_from = Handshake::new(PORT, &COORDINATOR, MINIMUM_WEIGHT_MAGNITUDE, &SUPPORTED_VERSIONS);
         let mut bytes = vec![0u8; message_from.size()];
         message_from.into_bytes(&mut bytes);
-        let message_to = Handshake::from_bytes(&bytes);
+        let message_to = Handshake::from_bytes(&bytes).unwrap();
 
         // TODO test timestamp
         assert_eq!(message_to.port, PORT);
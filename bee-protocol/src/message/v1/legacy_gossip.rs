@@ -11,14 +11,16 @@
 
 //! LegacyGossip message of the protocol version 1
 
-use crate::message::Message;
+use crate::message::{Message, MessageError};
+
+use bee_transaction::bundled::constants::{HASH_BYTE_LEN, NON_PAYLOAD_BYTE_LEN, TRANSACTION_BYTE_LEN};
 
 use std::ops::Range;
 
-const HASH_SIZE: usize = 49;
+const HASH_SIZE: usize = HASH_BYTE_LEN;
 const CONSTANT_SIZE: usize = HASH_SIZE;
-const VARIABLE_MIN_SIZE: usize = 292;
-const VARIABLE_MAX_SIZE: usize = 1604;
+const VARIABLE_MIN_SIZE: usize = NON_PAYLOAD_BYTE_LEN;
+const VARIABLE_MAX_SIZE: usize = TRANSACTION_BYTE_LEN;
 
 /// A legacy message to send a transaction and request another one at the same time.
 pub(crate) struct LegacyGossip {
@@ -53,7 +55,11 @@ impl Message for LegacyGossip {
         (CONSTANT_SIZE + VARIABLE_MIN_SIZE)..(CONSTANT_SIZE + VARIABLE_MAX_SIZE + 1)
     }
 
-    fn from_bytes(bytes: &[u8]) -> Self {
+    fn from_bytes(bytes: &[u8]) -> Result<Self, MessageError> {
+        if !Self::size_range().contains(&bytes.len()) {
+            return Err(MessageError::InvalidLength(bytes.len()));
+        }
+
         let mut message = Self::default();
 
         let (bytes, next) = bytes.split_at(bytes.len() - HASH_SIZE);
@@ -61,7 +67,7 @@ impl Message for LegacyGossip {
 
         message.hash.copy_from_slice(next);
 
-        message
+        Ok(message)
     }
 
     fn size(&self) -> usize {
@@ -112,6 +118,18 @@ mod tests {
         assert_eq!(LegacyGossip::ID, 2);
     }
 
+    #[test]
+    fn from_bytes_invalid_length() {
+        assert_eq!(
+            LegacyGossip::from_bytes(&[]).err(),
+            Some(MessageError::InvalidLength(0))
+        );
+        assert_eq!(
+            LegacyGossip::from_bytes(&[0; HASH_SIZE - 1]).err(),
+            Some(MessageError::InvalidLength(HASH_SIZE - 1))
+        );
+    }
+
     #[test]
     fn size_range() {
         assert_eq!(LegacyGossip::size_range().contains(&340), false);
@@ -135,7 +153,7 @@ mod tests {
         let message_from = LegacyGossip::new(&TRANSACTION, REQUEST);
         let mut bytes = vec![0u8; message_from.size()];
         message_from.into_bytes(&mut bytes);
-        let message_to = LegacyGossip::from_bytes(&bytes);
+        let message_to = LegacyGossip::from_bytes(&bytes).unwrap();
 
         assert!(message_to.transaction.eq(&TRANSACTION));
         assert!(message_to.hash.eq(&REQUEST));
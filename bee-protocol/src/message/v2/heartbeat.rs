@@ -13,7 +13,7 @@
 
 // TODO comment/uncomment when Chrysalis Pt1 is released.
 
-use crate::message::Message;
+use crate::message::{Message, MessageError};
 
 use std::{convert::TryInto, ops::Range};
 
@@ -73,25 +73,33 @@ impl Message for Heartbeat {
         (CONSTANT_SIZE)..(CONSTANT_SIZE + 1)
     }
 
-    fn from_bytes(bytes: &[u8]) -> Self {
+    fn from_bytes(bytes: &[u8]) -> Result<Self, MessageError> {
+        let len = bytes.len();
+
+        if !Self::size_range().contains(&len) {
+            return Err(MessageError::InvalidLength(len));
+        }
+
         let mut message = Self::default();
 
         let (bytes, next) = bytes.split_at(LATEST_SOLID_MILESTONE_INDEX_SIZE);
-        message.latest_solid_milestone_index = u32::from_be_bytes(bytes.try_into().expect("Invalid buffer size"));
+        message.latest_solid_milestone_index =
+            u32::from_be_bytes(bytes.try_into().map_err(|_| MessageError::InvalidLength(len))?);
 
         let (bytes, next) = next.split_at(PRUNED_INDEX_SIZE);
-        message.pruned_index = u32::from_be_bytes(bytes.try_into().expect("Invalid buffer size"));
+        message.pruned_index = u32::from_be_bytes(bytes.try_into().map_err(|_| MessageError::InvalidLength(len))?);
 
         let (bytes, next) = next.split_at(LATEST_MILESTONE_INDEX_SIZE);
-        message.latest_milestone_index = u32::from_be_bytes(bytes.try_into().expect("Invalid buffer size"));
+        message.latest_milestone_index =
+            u32::from_be_bytes(bytes.try_into().map_err(|_| MessageError::InvalidLength(len))?);
 
         let (bytes, next) = next.split_at(CONNECTED_PEERS_SIZE);
-        message.connected_peers = u8::from_be_bytes(bytes.try_into().expect("Invalid buffer size"));
+        message.connected_peers = u8::from_be_bytes(bytes.try_into().map_err(|_| MessageError::InvalidLength(len))?);
 
         let (bytes, _) = next.split_at(SYNCED_PEERS_SIZE);
-        message.synced_peers = u8::from_be_bytes(bytes.try_into().expect("Invalid buffer size"));
+        message.synced_peers = u8::from_be_bytes(bytes.try_into().map_err(|_| MessageError::InvalidLength(len))?);
 
-        message
+        Ok(message)
     }
 
     fn size(&self) -> usize {
@@ -128,6 +136,15 @@ mod tests {
         assert_eq!(Heartbeat::ID, 6);
     }
 
+    #[test]
+    fn from_bytes_invalid_length() {
+        assert_eq!(Heartbeat::from_bytes(&[]).err(), Some(MessageError::InvalidLength(0)));
+        assert_eq!(
+            Heartbeat::from_bytes(&[0; CONSTANT_SIZE - 1]).err(),
+            Some(MessageError::InvalidLength(CONSTANT_SIZE - 1))
+        );
+    }
+
     #[test]
     fn size_range() {
         assert_eq!(Heartbeat::size_range().contains(&13), false);
@@ -159,7 +176,7 @@ mod tests {
         );
         let mut bytes = vec![0u8; message_from.size()];
         message_from.into_bytes(&mut bytes);
-        let message_to = Heartbeat::from_bytes(&bytes);
+        let message_to = Heartbeat::from_bytes(&bytes).unwrap();
 
         assert_eq!(message_to.latest_solid_milestone_index, LATEST_SOLID_MILESTONE_INDEX);
         assert_eq!(message_to.pruned_index, PRUNED_INDEX);
@@ -11,7 +11,7 @@
 
 //! MilestoneRequest message of the protocol version 2
 
-use crate::message::Message;
+use crate::message::{Message, MessageError};
 
 use std::{convert::TryInto, ops::Range};
 
@@ -38,12 +38,12 @@ impl Message for MilestoneRequest {
         (CONSTANT_SIZE)..(CONSTANT_SIZE + 1)
     }
 
-    fn from_bytes(bytes: &[u8]) -> Self {
+    fn from_bytes(bytes: &[u8]) -> Result<Self, MessageError> {
         let mut message = Self::default();
 
-        message.index = u32::from_be_bytes(bytes[0..INDEX_SIZE].try_into().expect("Invalid buffer size"));
+        message.index = u32::from_be_bytes(bytes.try_into().map_err(|_| MessageError::InvalidLength(bytes.len()))?);
 
-        message
+        Ok(message)
     }
 
     fn size(&self) -> usize {
@@ -67,6 +67,18 @@ mod tests {
         assert_eq!(MilestoneRequest::ID, 3);
     }
 
+    #[test]
+    fn from_bytes_invalid_length() {
+        assert_eq!(
+            MilestoneRequest::from_bytes(&[]).err(),
+            Some(MessageError::InvalidLength(0))
+        );
+        assert_eq!(
+            MilestoneRequest::from_bytes(&[0; CONSTANT_SIZE - 1]).err(),
+            Some(MessageError::InvalidLength(CONSTANT_SIZE - 1))
+        );
+    }
+
     #[test]
     fn size_range() {
         assert_eq!(MilestoneRequest::size_range().contains(&3), false);
@@ -86,7 +98,7 @@ mod tests {
         let message_from = MilestoneRequest::new(INDEX);
         let mut bytes = vec![0u8; message_from.size()];
         message_from.into_bytes(&mut bytes);
-        let message_to = MilestoneRequest::from_bytes(&bytes);
+        let message_to = MilestoneRequest::from_bytes(&bytes).unwrap();
 
         assert_eq!(message_to.index, INDEX);
     }
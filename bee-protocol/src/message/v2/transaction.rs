@@ -11,12 +11,17 @@
 
 //! Transaction message of the protocol version 2
 
-use crate::message::Message;
+use crate::message::{Message, MessageError};
+
+use bee_transaction::bundled::{
+    constants::{NON_PAYLOAD_BYTE_LEN, TRANSACTION_BYTE_LEN},
+    BundledTransaction,
+};
 
 use std::ops::Range;
 
-const VARIABLE_MIN_SIZE: usize = 292;
-const VARIABLE_MAX_SIZE: usize = 1604;
+const VARIABLE_MIN_SIZE: usize = NON_PAYLOAD_BYTE_LEN;
+const VARIABLE_MAX_SIZE: usize = TRANSACTION_BYTE_LEN;
 
 /// A message to send a transaction.
 #[derive(Default)]
@@ -33,6 +38,14 @@ impl Transaction {
     }
 }
 
+impl From<&BundledTransaction> for Transaction {
+    fn from(transaction: &BundledTransaction) -> Self {
+        Self {
+            bytes: transaction.to_gossip_bytes(),
+        }
+    }
+}
+
 impl Message for Transaction {
     const ID: u8 = 0x04;
 
@@ -40,12 +53,16 @@ impl Message for Transaction {
         (VARIABLE_MIN_SIZE)..(VARIABLE_MAX_SIZE + 1)
     }
 
-    fn from_bytes(bytes: &[u8]) -> Self {
+    fn from_bytes(bytes: &[u8]) -> Result<Self, MessageError> {
+        if !Self::size_range().contains(&bytes.len()) {
+            return Err(MessageError::InvalidLength(bytes.len()));
+        }
+
         let mut message = Self::default();
 
         message.bytes = bytes.to_vec();
 
-        message
+        Ok(message)
     }
 
     fn size(&self) -> usize {
@@ -91,6 +108,15 @@ mod tests {
         assert_eq!(Transaction::ID, 4);
     }
 
+    #[test]
+    fn from_bytes_invalid_length() {
+        assert_eq!(Transaction::from_bytes(&[]).err(), Some(MessageError::InvalidLength(0)));
+        assert_eq!(
+            Transaction::from_bytes(&[0; VARIABLE_MIN_SIZE - 1]).err(),
+            Some(MessageError::InvalidLength(VARIABLE_MIN_SIZE - 1))
+        );
+    }
+
     #[test]
     fn size_range() {
         assert_eq!(Transaction::size_range().contains(&291), false);
@@ -114,7 +140,7 @@ mod tests {
         let message_from = Transaction::new(&TRANSACTION);
         let mut bytes = vec![0u8; message_from.size()];
         message_from.into_bytes(&mut bytes);
-        let message_to = Transaction::from_bytes(&bytes);
+        let message_to = Transaction::from_bytes(&bytes).unwrap();
 
         assert!(message_to.bytes.eq(&TRANSACTION));
     }
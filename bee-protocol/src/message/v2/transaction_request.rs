@@ -11,11 +11,13 @@
 
 //! TransactionRequest message of the protocol version 2
 
-use crate::message::Message;
+use crate::message::{Message, MessageError};
+
+use bee_transaction::bundled::constants::HASH_BYTE_LEN;
 
 use std::ops::Range;
 
-const HASH_SIZE: usize = 49;
+const HASH_SIZE: usize = HASH_BYTE_LEN;
 const CONSTANT_SIZE: usize = HASH_SIZE;
 
 /// A message to request a transaction.
@@ -47,12 +49,16 @@ impl Message for TransactionRequest {
         (CONSTANT_SIZE)..(CONSTANT_SIZE + 1)
     }
 
-    fn from_bytes(bytes: &[u8]) -> Self {
+    fn from_bytes(bytes: &[u8]) -> Result<Self, MessageError> {
+        if !Self::size_range().contains(&bytes.len()) {
+            return Err(MessageError::InvalidLength(bytes.len()));
+        }
+
         let mut message = Self::default();
 
-        message.hash.copy_from_slice(&bytes[0..HASH_SIZE]);
+        message.hash.copy_from_slice(bytes);
 
-        message
+        Ok(message)
     }
 
     fn size(&self) -> usize {
@@ -79,6 +85,18 @@ mod tests {
         assert_eq!(TransactionRequest::ID, 5);
     }
 
+    #[test]
+    fn from_bytes_invalid_length() {
+        assert_eq!(
+            TransactionRequest::from_bytes(&[]).err(),
+            Some(MessageError::InvalidLength(0))
+        );
+        assert_eq!(
+            TransactionRequest::from_bytes(&[0; HASH_SIZE - 1]).err(),
+            Some(MessageError::InvalidLength(HASH_SIZE - 1))
+        );
+    }
+
     #[test]
     fn size_range() {
         assert_eq!(TransactionRequest::size_range().contains(&48), false);
@@ -98,7 +116,7 @@ mod tests {
         let message_from = TransactionRequest::new(&HASH);
         let mut bytes = vec![0u8; message_from.size()];
         message_from.into_bytes(&mut bytes);
-        let message_to = TransactionRequest::from_bytes(&bytes);
+        let message_to = TransactionRequest::from_bytes(&bytes).unwrap();
 
         assert!(message_to.hash.eq(&HASH));
     }
@@ -39,8 +39,10 @@ fn common_supported_version(own_supported_versions: &[u8], supported_versions: &
     }
 
     if highest_supported_version == 0 {
-        // TODO remove unwrap by checking size of versions
-        let last_versions_byte: &u8 = supported_versions.last().unwrap();
+        let last_versions_byte = match supported_versions.last() {
+            Some(byte) => byte,
+            None => return Err(0),
+        };
         let mut highest: u8 = 0;
 
         for j in 0..8 {
@@ -49,7 +51,10 @@ fn common_supported_version(own_supported_versions: &[u8], supported_versions: &
             }
         }
 
-        return Err(highest + ((supported_versions.len() - 1) as u8 * 8));
+        // Versions above 255 can't be represented, saturate rather than overflow on oversized peer input.
+        let highest = highest as usize + (supported_versions.len() - 1) * 8;
+
+        return Err(highest.min(u8::MAX as usize) as u8);
     }
 
     Ok(highest_supported_version)
@@ -59,6 +64,30 @@ pub(crate) fn messages_supported_version(supported_versions: &[u8]) -> Result<u8
     common_supported_version(&MESSAGES_VERSIONS, supported_versions)
 }
 
+// The capabilities of a node are advertised in the byte following its supported versions, which version negotiation
+// ignores since it only goes through our own versions.
+const CAPABILITIES_INDEX: usize = MESSAGES_VERSIONS.len();
+
+/// The node answers transaction and milestone requests.
+pub(crate) const RESPONDER_CAPABILITY: u8 = 1;
+
+/// Supported versions advertised in the handshake, followed by the capabilities of the node.
+pub(crate) fn advertised_versions(capabilities: u8) -> Vec<u8> {
+    let mut versions = MESSAGES_VERSIONS.to_vec();
+
+    versions.push(capabilities);
+
+    versions
+}
+
+/// Whether a peer advertised `capability` in its handshake. Peers that don't advertise any capability are assumed to
+/// have all of them.
+pub(crate) fn has_capability(supported_versions: &[u8], capability: u8) -> bool {
+    supported_versions
+        .get(CAPABILITIES_INDEX)
+        .map_or(true, |capabilities| capabilities & capability != 0)
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -84,4 +113,40 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn no_supported_versions() {
+        assert_eq!(messages_supported_version(&[]), Err(0));
+    }
+
+    #[test]
+    fn unsupported_versions_overflow() {
+        let mut versions = [0u8; 32];
+        versions[31] = 0b1000_0000;
+
+        assert_eq!(messages_supported_version(&versions), Err(u8::MAX));
+    }
+
+    #[test]
+    fn capabilities_ignored_by_negotiation() {
+        assert_eq!(
+            messages_supported_version(&advertised_versions(0)),
+            messages_supported_version(&MESSAGES_VERSIONS)
+        );
+        assert_eq!(
+            messages_supported_version(&advertised_versions(RESPONDER_CAPABILITY)),
+            messages_supported_version(&MESSAGES_VERSIONS)
+        );
+    }
+
+    #[test]
+    fn responder_capability() {
+        assert!(has_capability(
+            &advertised_versions(RESPONDER_CAPABILITY),
+            RESPONDER_CAPABILITY
+        ));
+        assert!(!has_capability(&advertised_versions(0), RESPONDER_CAPABILITY));
+        // Without any advertised capability.
+        assert!(has_capability(&MESSAGES_VERSIONS, RESPONDER_CAPABILITY));
+    }
 }
@@ -12,7 +12,7 @@
 use crate::milestone::{Milestone, MilestoneIndex};
 
 use bee_crypto::ternary::{
-    sponge::{Kerl, Sponge},
+    sponge::{CurlP27, CurlP81, Kerl, Sponge, SpongeKind},
     Hash,
 };
 use bee_signing::ternary::{
@@ -23,21 +23,100 @@ use bee_signing::ternary::{
 use bee_ternary::{convert::Error as ConvertError, TritBuf};
 use bee_transaction::{
     bundled::{
-        BundledTransaction as Transaction, BundledTransactionField, BundledTransactions as Transactions, Payload,
+        Address, Bundle, BundledTransaction as Transaction, BundledTransactionField,
+        BundledTransactions as Transactions, Payload,
     },
     Vertex,
 };
 
-use std::{convert::TryFrom, marker::PhantomData};
+use std::{convert::TryFrom, fmt, marker::PhantomData};
 
 #[derive(Debug)]
 pub enum MilestoneBuilderError {
     Empty,
+    /// A milestone bundle needs at least a signature transaction and the transaction carrying the Merkle siblings.
+    Incomplete(usize),
+    InvalidCoordinator,
+    InvalidPublicKey,
     InvalidSignature,
     SignatureError(MssError),
     InvalidIndex(ConvertError),
 }
 
+impl fmt::Display for MilestoneBuilderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "milestone bundle is empty"),
+            Self::Incomplete(len) => write!(f, "milestone bundle has {} transaction(s), expected at least 2", len),
+            Self::InvalidCoordinator => write!(f, "milestone bundle is not issued by the coordinator"),
+            Self::InvalidPublicKey => write!(f, "milestone public key is invalid"),
+            Self::InvalidSignature => write!(f, "milestone signature is invalid"),
+            Self::SignatureError(e) => write!(f, "verifying milestone signature failed: {:?}", e),
+            Self::InvalidIndex(e) => write!(f, "milestone index is invalid: {:?}", e),
+        }
+    }
+}
+
+impl std::error::Error for MilestoneBuilderError {}
+
+impl From<MssError> for MilestoneBuilderError {
+    fn from(e: MssError) -> Self {
+        Self::SignatureError(e)
+    }
+}
+
+impl From<ConvertError> for MilestoneBuilderError {
+    fn from(e: ConvertError) -> Self {
+        Self::InvalidIndex(e)
+    }
+}
+
+/// Reads the index a milestone bundle claims from the obsolete tag of its tail transaction.
+pub(crate) fn milestone_index(tail: &Transaction) -> Result<MilestoneIndex, ConvertError> {
+    Ok(MilestoneIndex(i64::try_from(tail.obsolete_tag().to_inner())? as u32))
+}
+
+/// Validates a milestone bundle issued by `coordinator` and returns the index it claims.
+///
+/// The structure of the bundle is expected to have been checked by `validate_bundle`; only the issuer and the Merkle
+/// signature, of depth `depth` and hashed with `sponge`, are checked here.
+pub fn validate_milestone(
+    bundle: &Bundle,
+    coordinator: &Address,
+    depth: u8,
+    sponge: SpongeKind,
+) -> Result<MilestoneIndex, MilestoneBuilderError> {
+    if bundle.is_empty() {
+        return Err(MilestoneBuilderError::Empty);
+    }
+
+    if bundle.tail().address() != coordinator {
+        return Err(MilestoneBuilderError::InvalidCoordinator);
+    }
+
+    match sponge {
+        SpongeKind::Kerl => validate_milestone_signature::<Kerl, WotsPublicKey<Kerl>>(bundle, depth),
+        SpongeKind::CurlP27 => validate_milestone_signature::<CurlP27, WotsPublicKey<CurlP27>>(bundle, depth),
+        SpongeKind::CurlP81 => validate_milestone_signature::<CurlP81, WotsPublicKey<CurlP81>>(bundle, depth),
+    }
+}
+
+fn validate_milestone_signature<M, P>(bundle: &Bundle, depth: u8) -> Result<MilestoneIndex, MilestoneBuilderError>
+where
+    M: Sponge + Default,
+    P: PublicKey,
+    <P as PublicKey>::Signature: RecoverableSignature,
+{
+    // The hash of the milestone is not part of its validation.
+    let mut builder = MilestoneBuilder::<Kerl, M, P>::new(Hash::zeros());
+
+    for transaction in bundle {
+        builder.push(transaction.clone());
+    }
+
+    Ok(builder.depth(depth).validate()?.build().index())
+}
+
 // TODO are stages really needed since it's internal ?
 pub trait MilestoneBuilderStage {}
 
@@ -97,16 +176,12 @@ where
         // Safe to unwrap `transactions.get(0)` since we're sure it's not empty
         // Safe to unwrap `self.depth` since we're sure it's not None
         let public_key: MssPublicKey<M, P> =
-            match MssPublicKey::<M, P>::from_trits(self.transactions.get(0).unwrap().address().to_inner().to_buf()) {
-                Ok(pk) => pk,
-                Err(_) => unreachable!(),
-            }
-            .with_depth(self.depth.unwrap());
-        let signature: MssSignature<M> = match MssSignature::<M>::from_trits(signature_buf) {
-            Ok(sig) => sig,
-            Err(_) => unreachable!(),
-        }
-        .with_index(*self.index as usize);
+            MssPublicKey::<M, P>::from_trits(self.transactions.get(0).unwrap().address().to_inner().to_buf())
+                .map_err(|_| MilestoneBuilderError::InvalidPublicKey)?
+                .with_depth(self.depth.unwrap());
+        let signature: MssSignature<M> = MssSignature::<M>::from_trits(signature_buf)
+            .map_err(|_| MilestoneBuilderError::InvalidSignature)?
+            .with_index(*self.index as usize);
         let hash = self
             .transactions
             .get(self.transactions.len() - 2)
@@ -123,7 +198,7 @@ where
                     Err(MilestoneBuilderError::InvalidSignature)
                 }
             }
-            Err(e) => Err(MilestoneBuilderError::SignatureError(e)),
+            Err(e) => Err(e.into()),
         }
     }
 
@@ -132,6 +207,10 @@ where
             return Err(MilestoneBuilderError::Empty);
         }
 
+        if self.transactions.len() < 2 {
+            return Err(MilestoneBuilderError::Incomplete(self.transactions.len()));
+        }
+
         // TODO check coo address
         // TODO check bundle structure
         // TODO check depth
@@ -140,10 +219,7 @@ where
         // TODO remove clone
         // TODO test invalid index
         // Safe to unwrap
-        self.index = MilestoneIndex(
-            i64::try_from(self.transactions.get(0).unwrap().obsolete_tag().to_inner())
-                .map_err(MilestoneBuilderError::InvalidIndex)? as u32,
-        );
+        self.index = milestone_index(self.transactions.get(0).unwrap())?;
 
         self.validate_signatures()?;
 
@@ -176,22 +252,32 @@ mod tests {
 
     use super::*;
 
-    use bee_crypto::ternary::sponge::CurlP27;
     use bee_ternary::{T1B1Buf, TryteBuf};
+    use bee_transaction::bundled::{validate_bundle, TRANSACTION_TRIT_LEN};
+
+    const MAINNET_COORDINATOR: &str =
+        "EQSAUZXULTTYZCLNJNTXQTQHOMOFZERHTCGTXOLTVAHKSA9OGAZDEKECURBRIXIJWNPFCQIOVFVVXJVD9";
+    const TESTNET_COORDINATOR: &str =
+        "EQQFCZBIHRHWPXKMTOLMYUYPCN9XLMJPYZVFJSAY9FQHCCLWTOLLUGKKMXYFDBOOYFBLBI9WUEILGECYM";
+
+    fn address(trytes: &str) -> Address {
+        Address::try_from_inner(TryteBuf::try_from_str(trytes).unwrap().as_trits().encode::<T1B1Buf>()).unwrap()
+    }
 
     fn generic_validate_milestone<S: Sponge + Default>(
         ms_hash: &str,
         ms_tx_array: Vec<&str>,
+        coordinator: &str,
         depth: u8,
+        sponge: SpongeKind,
         index: MilestoneIndex,
     ) {
         let ms_hash_trits =
             Hash::try_from_inner(TryteBuf::try_from_str(ms_hash).unwrap().as_trits().encode::<T1B1Buf>()).unwrap();
         let mut builder = MilestoneBuilder::<Kerl, S, WotsPublicKey<S>>::new(ms_hash_trits);
 
-        for ms_tx in ms_tx_array {
-            let ms_tx_trits = TryteBuf::try_from_str(ms_tx).unwrap().as_trits().encode::<T1B1Buf>();
-            builder.push(Transaction::from_trits(&ms_tx_trits).unwrap());
+        for ms_tx in transactions(&ms_tx_array) {
+            builder.push(ms_tx);
         }
 
         match builder.depth(depth).validate() {
@@ -203,6 +289,16 @@ mod tests {
             }
             Err(_) => unreachable!(),
         }
+
+        // The same milestone, validated without a node.
+        let bundle = validate_bundle(&transactions(&ms_tx_array), 0).unwrap();
+        let coordinator = address(coordinator);
+
+        assert_eq!(validate_milestone(&bundle, &coordinator, depth, sponge).unwrap(), index);
+        assert!(matches!(
+            validate_milestone(&bundle, &Address::zeros(), depth, sponge),
+            Err(MilestoneBuilderError::InvalidCoordinator)
+        ));
     }
 
     #[test]
@@ -215,7 +311,9 @@ mod tests {
         generic_validate_milestone::<Kerl>(
             MS_HASH,
             [MS_TX_0_TRYTES, MS_TX_1_TRYTES, MS_TX_2_TRYTES].to_vec(),
+            MAINNET_COORDINATOR,
             23,
+            SpongeKind::Kerl,
             MilestoneIndex(1_363_619),
         );
     }
@@ -230,7 +328,9 @@ mod tests {
         generic_validate_milestone::<Kerl>(
             MS_HASH,
             [MS_TX_0_TRYTES, MS_TX_1_TRYTES, MS_TX_2_TRYTES].to_vec(),
+            MAINNET_COORDINATOR,
             23,
+            SpongeKind::Kerl,
             MilestoneIndex(1_367_702),
         );
     }
@@ -245,7 +345,9 @@ mod tests {
         generic_validate_milestone::<Kerl>(
             MS_HASH,
             [MS_TX_0_TRYTES, MS_TX_1_TRYTES, MS_TX_2_TRYTES].to_vec(),
+            MAINNET_COORDINATOR,
             23,
+            SpongeKind::Kerl,
             MilestoneIndex(1_368_168),
         );
     }
@@ -259,7 +361,9 @@ mod tests {
         generic_validate_milestone::<CurlP27>(
             MS_HASH,
             [MS_TX_0_TRYTES, MS_TX_1_TRYTES].to_vec(),
+            TESTNET_COORDINATOR,
             22,
+            SpongeKind::CurlP27,
             MilestoneIndex(1_537_978),
         );
     }
@@ -273,7 +377,9 @@ mod tests {
         generic_validate_milestone::<CurlP27>(
             MS_HASH,
             [MS_TX_0_TRYTES, MS_TX_1_TRYTES].to_vec(),
+            TESTNET_COORDINATOR,
             22,
+            SpongeKind::CurlP27,
             MilestoneIndex(1_538_144),
         );
     }
@@ -287,8 +393,30 @@ mod tests {
         generic_validate_milestone::<CurlP27>(
             MS_HASH,
             [MS_TX_0_TRYTES, MS_TX_1_TRYTES].to_vec(),
+            TESTNET_COORDINATOR,
             22,
+            SpongeKind::CurlP27,
             MilestoneIndex(1_538_158),
         );
     }
+
+    #[test]
+    fn validate_milestone_single_transaction() {
+        let mut builder = MilestoneBuilder::<Kerl, Kerl, WotsPublicKey<Kerl>>::new(Hash::zeros());
+        builder.push(Transaction::from_trits(&TritBuf::<T1B1Buf>::zeros(TRANSACTION_TRIT_LEN)).unwrap());
+
+        assert!(matches!(
+            builder.depth(23).validate(),
+            Err(MilestoneBuilderError::Incomplete(1))
+        ));
+    }
+
+    fn transactions(ms_tx_array: &[&str]) -> Vec<Transaction> {
+        ms_tx_array
+            .iter()
+            .map(|ms_tx| {
+                Transaction::from_trits(&TryteBuf::try_from_str(ms_tx).unwrap().as_trits().encode::<T1B1Buf>()).unwrap()
+            })
+            .collect()
+    }
 }
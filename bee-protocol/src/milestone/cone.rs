@@ -0,0 +1,83 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use crate::{event::MilestoneConeTooLarge, milestone::MilestoneIndex, protocol::ProtocolMetrics};
+
+use bee_common_ext::event::Bus;
+
+use log::error;
+
+/// Maximum number of transactions the past cone of a milestone may contain, `None` meaning unlimited.
+///
+/// The cone of a milestone spans every milestone the node is missing, so the limit grows with the gap between the
+/// latest milestone and the latest solid one to let a node far behind catch up.
+pub fn max_cone_size(
+    max_per_milestone: usize,
+    latest_index: MilestoneIndex,
+    latest_solid_index: MilestoneIndex,
+) -> Option<usize> {
+    if max_per_milestone == 0 {
+        return None;
+    }
+
+    let gap = latest_index.saturating_sub(*latest_solid_index).max(1);
+
+    Some(max_per_milestone.saturating_mul(gap as usize))
+}
+
+pub(crate) fn cone_too_large(bus: &Bus, metrics: &ProtocolMetrics, index: MilestoneIndex, max_cone_size: usize) {
+    error!(
+        "Past cone of milestone {} exceeds {} transactions.",
+        *index, max_cone_size
+    );
+
+    metrics.oversized_milestones_inc();
+    bus.dispatch(MilestoneConeTooLarge { index, max_cone_size });
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn max_cone_size_scales_with_gap() {
+        assert_eq!(max_cone_size(100, MilestoneIndex(10), MilestoneIndex(10)), Some(100));
+        assert_eq!(max_cone_size(100, MilestoneIndex(11), MilestoneIndex(10)), Some(100));
+        assert_eq!(max_cone_size(100, MilestoneIndex(60), MilestoneIndex(10)), Some(5000));
+        // The latest milestone may not be known yet.
+        assert_eq!(max_cone_size(100, MilestoneIndex(0), MilestoneIndex(10)), Some(100));
+    }
+
+    #[test]
+    fn max_cone_size_disabled() {
+        assert_eq!(max_cone_size(0, MilestoneIndex(60), MilestoneIndex(10)), None);
+    }
+
+    #[test]
+    fn cone_too_large_reported() {
+        let bus = Bus::default();
+        let metrics = ProtocolMetrics::new();
+        let events = Arc::new(Mutex::new(Vec::new()));
+
+        let events_clone = events.clone();
+        bus.add_listener(move |event: &MilestoneConeTooLarge| {
+            events_clone.lock().unwrap().push((event.index, event.max_cone_size));
+        });
+
+        cone_too_large(&bus, &metrics, MilestoneIndex(42), 100);
+
+        assert_eq!(*events.lock().unwrap(), vec![(MilestoneIndex(42), 100)]);
+        assert_eq!(metrics.oversized_milestones(), 1);
+    }
+}
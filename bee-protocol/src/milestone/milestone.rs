@@ -10,13 +10,52 @@
 // See the License for the specific language governing permissions and limitations under the License.
 
 use bee_crypto::ternary::Hash;
+use bee_storage::persistable::{take, DecodeError, Persistable};
 
-use std::ops::{Add, Deref};
+use std::{
+    convert::TryFrom,
+    fmt,
+    ops::{Add, Deref},
+};
+
+/// Length of the key representation of a `MilestoneIndex`.
+pub const MILESTONE_INDEX_KEY_LENGTH: usize = 4;
+
+#[derive(Debug)]
+pub enum MilestoneIndexError {
+    InvalidLength(usize),
+}
+
+impl fmt::Display for MilestoneIndexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidLength(length) => write!(
+                f,
+                "milestone index key length {} is not {}",
+                length, MILESTONE_INDEX_KEY_LENGTH
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MilestoneIndexError {}
 
 /// A wrapper around a `u32` that represents a milestone index.
 #[derive(Debug, Clone, Copy, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct MilestoneIndex(pub u32);
 
+impl MilestoneIndex {
+    /// Returns the big-endian bytes of the index, so that keys are ordered like the indexes they represent.
+    pub fn to_key_bytes(&self) -> [u8; MILESTONE_INDEX_KEY_LENGTH] {
+        self.0.to_be_bytes()
+    }
+
+    /// Creates an index from its big-endian key bytes.
+    pub fn from_key_bytes(bytes: [u8; MILESTONE_INDEX_KEY_LENGTH]) -> Self {
+        Self(u32::from_be_bytes(bytes))
+    }
+}
+
 impl Deref for MilestoneIndex {
     type Target = u32;
 
@@ -31,6 +70,38 @@ impl From<u32> for MilestoneIndex {
     }
 }
 
+impl From<MilestoneIndex> for u32 {
+    fn from(index: MilestoneIndex) -> Self {
+        index.0
+    }
+}
+
+impl TryFrom<&[u8]> for MilestoneIndex {
+    type Error = MilestoneIndexError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        let mut key = [0u8; MILESTONE_INDEX_KEY_LENGTH];
+
+        if bytes.len() != key.len() {
+            return Err(MilestoneIndexError::InvalidLength(bytes.len()));
+        }
+        key.copy_from_slice(bytes);
+
+        Ok(Self::from_key_bytes(key))
+    }
+}
+
+impl Persistable for MilestoneIndex {
+    fn encode_persistable(&self, buffer: &mut Vec<u8>) {
+        buffer.extend(&self.to_key_bytes());
+    }
+    fn decode_persistable_prefix(slice: &[u8]) -> Result<(Self, usize), DecodeError> {
+        let bytes = take(slice, MILESTONE_INDEX_KEY_LENGTH)?;
+        // Can't fail, the length was checked.
+        Ok((Self::try_from(bytes).unwrap(), MILESTONE_INDEX_KEY_LENGTH))
+    }
+}
+
 impl Add for MilestoneIndex {
     type Output = Self;
 
@@ -39,7 +110,30 @@ impl Add for MilestoneIndex {
     }
 }
 
-#[derive(Clone)]
+/// Reasons a validated milestone could not be applied to the tangle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MilestoneApplicationError {
+    /// The milestone tail transaction is not in the tangle.
+    MissingTail,
+    /// Another milestone has already been applied at this index.
+    AlreadyApplied,
+    /// The signature of the milestone doesn't verify against the coordinator Merkle tree.
+    InvalidMerkleProof,
+}
+
+impl fmt::Display for MilestoneApplicationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingTail => write!(f, "milestone tail transaction not in the tangle"),
+            Self::AlreadyApplied => write!(f, "another milestone was already applied at this index"),
+            Self::InvalidMerkleProof => write!(f, "milestone signature does not verify against the coordinator"),
+        }
+    }
+}
+
+impl std::error::Error for MilestoneApplicationError {}
+
+#[derive(Clone, Persistable)]
 pub struct Milestone {
     pub(crate) hash: Hash,
     pub(crate) index: MilestoneIndex,
@@ -58,3 +152,58 @@ impl Milestone {
         self.index
     }
 }
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn key_bytes_round_trip() {
+        for index in [0, 1, 255, 256, 65_536, 1_363_619, u32::MAX].iter() {
+            let index = MilestoneIndex::from(*index);
+
+            assert_eq!(MilestoneIndex::from_key_bytes(index.to_key_bytes()), index);
+            assert_eq!(MilestoneIndex::try_from(&index.to_key_bytes()[..]).unwrap(), index);
+            assert_eq!(MilestoneIndex::from(u32::from(index)), index);
+        }
+    }
+
+    #[test]
+    fn key_bytes_order() {
+        let keys = (0..100_000u32)
+            .step_by(7)
+            .chain((u32::MAX - 1_000)..=u32::MAX)
+            .map(|index| MilestoneIndex::from(index).to_key_bytes())
+            .collect::<Vec<_>>();
+
+        let mut sorted = keys.clone();
+        sorted.sort();
+
+        assert_eq!(keys, sorted);
+    }
+
+    #[test]
+    fn try_from_invalid_length() {
+        match MilestoneIndex::try_from(&[0u8, 1, 2][..]) {
+            Err(MilestoneIndexError::InvalidLength(3)) => (),
+            _ => panic!("a 3 bytes slice should not be a valid milestone index"),
+        }
+        match MilestoneIndex::try_from(&[0u8, 1, 2, 3, 4][..]) {
+            Err(MilestoneIndexError::InvalidLength(5)) => (),
+            _ => panic!("a 5 bytes slice should not be a valid milestone index"),
+        }
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(
+            MilestoneIndexError::InvalidLength(3).to_string(),
+            "milestone index key length 3 is not 4"
+        );
+        assert_eq!(
+            MilestoneApplicationError::AlreadyApplied.to_string(),
+            "another milestone was already applied at this index"
+        );
+    }
+}
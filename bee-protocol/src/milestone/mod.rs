@@ -10,7 +10,13 @@
 // See the License for the specific language governing permissions and limitations under the License.
 
 mod builder;
+mod cone;
 mod milestone;
 
-pub(crate) use builder::{MilestoneBuilder, MilestoneBuilderError};
-pub use milestone::{Milestone, MilestoneIndex};
+pub(crate) use builder::milestone_index;
+pub use builder::{validate_milestone, MilestoneBuilderError};
+pub(crate) use cone::cone_too_large;
+pub use cone::max_cone_size;
+pub use milestone::{
+    Milestone, MilestoneApplicationError, MilestoneIndex, MilestoneIndexError, MILESTONE_INDEX_KEY_LENGTH,
+};
@@ -11,13 +11,48 @@
 
 use crate::{milestone::MilestoneIndex, peer::PeerMetrics};
 
+use bee_crypto::ternary::Hash;
 use bee_network::EndpointId;
 
+use spin::Mutex;
+
 use std::{
+    collections::{HashSet, VecDeque},
     net::SocketAddr,
-    sync::atomic::{AtomicU32, AtomicU8, Ordering},
+    sync::atomic::{AtomicBool, AtomicU32, AtomicU8, Ordering},
 };
 
+const SEEN_FILTER_CAPACITY: usize = 1000;
+
+// Remembers the latest transactions pushed to a peer, forgetting the oldest ones once full.
+#[derive(Default)]
+struct SeenFilter {
+    hashes: HashSet<Hash>,
+    order: VecDeque<Hash>,
+}
+
+impl SeenFilter {
+    fn insert(&mut self, hash: Hash) -> bool {
+        if !self.hashes.insert(hash) {
+            return false;
+        }
+
+        self.order.push_back(hash);
+
+        if self.order.len() > SEEN_FILTER_CAPACITY {
+            if let Some(oldest) = self.order.pop_front() {
+                self.hashes.remove(&oldest);
+            }
+        }
+
+        true
+    }
+
+    fn contains(&self, hash: &Hash) -> bool {
+        self.hashes.contains(hash)
+    }
+}
+
 pub struct HandshakedPeer {
     pub(crate) epid: EndpointId,
     pub(crate) address: SocketAddr,
@@ -27,6 +62,8 @@ pub struct HandshakedPeer {
     pub(crate) latest_milestone_index: AtomicU32,
     pub(crate) connected_peers: AtomicU8,
     pub(crate) synced_peers: AtomicU8,
+    pub(crate) serves_requests: AtomicBool,
+    seen: Mutex<SeenFilter>,
 }
 
 impl HandshakedPeer {
@@ -40,6 +77,8 @@ impl HandshakedPeer {
             latest_milestone_index: AtomicU32::new(0),
             connected_peers: AtomicU8::new(0),
             synced_peers: AtomicU8::new(0),
+            serves_requests: AtomicBool::new(true),
+            seen: Mutex::new(SeenFilter::default()),
         }
     }
 
@@ -85,6 +124,14 @@ impl HandshakedPeer {
         self.synced_peers.load(Ordering::Relaxed)
     }
 
+    pub(crate) fn set_serves_requests(&self, serves_requests: bool) {
+        self.serves_requests.store(serves_requests, Ordering::Relaxed);
+    }
+
+    pub(crate) fn serves_requests(&self) -> bool {
+        self.serves_requests.load(Ordering::Relaxed)
+    }
+
     pub(crate) fn has_data(&self, index: MilestoneIndex) -> bool {
         index > self.pruned_index() && index <= self.latest_solid_milestone_index()
     }
@@ -92,4 +139,55 @@ impl HandshakedPeer {
     pub(crate) fn maybe_has_data(&self, index: MilestoneIndex) -> bool {
         index > self.pruned_index() && index <= self.latest_milestone_index()
     }
+
+    /// Marks a transaction as pushed to this peer, returns `false` if it recently already was.
+    pub(crate) fn mark_seen(&self, hash: &Hash) -> bool {
+        self.seen.lock().insert(*hash)
+    }
+
+    /// Returns whether a transaction was recently pushed to this peer.
+    pub(crate) fn has_seen(&self, hash: &Hash) -> bool {
+        self.seen.lock().contains(hash)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use crate::test_utils::indexed_hash as hash;
+
+    #[test]
+    fn seen_filter_dedup() {
+        let mut filter = SeenFilter::default();
+
+        assert!(filter.insert(hash(0)));
+        assert!(!filter.insert(hash(0)));
+        assert!(filter.insert(hash(1)));
+    }
+
+    #[test]
+    fn seen_filter_forgets_oldest() {
+        let mut filter = SeenFilter::default();
+
+        for n in 0..=SEEN_FILTER_CAPACITY {
+            assert!(filter.insert(hash(n)));
+        }
+
+        assert_eq!(filter.order.len(), SEEN_FILTER_CAPACITY);
+        assert!(!filter.insert(hash(SEEN_FILTER_CAPACITY)));
+        assert!(filter.insert(hash(0)));
+    }
+
+    #[test]
+    fn mark_seen_per_peer() {
+        let address = "127.0.0.1:15600".parse().unwrap();
+        let peer = HandshakedPeer::new(EndpointId::new(bee_network::TransportProtocol::Tcp, address), address);
+
+        assert!(!peer.has_seen(&hash(42)));
+        assert!(peer.mark_seen(&hash(42)));
+        assert!(peer.has_seen(&hash(42)));
+        assert!(!peer.mark_seen(&hash(42)));
+    }
 }
@@ -39,27 +39,32 @@ impl PeerManager {
         self.peers.insert(peer.epid, peer);
     }
 
-    pub(crate) async fn handshake(&self, epid: &EndpointId, address: SocketAddr) {
+    pub(crate) async fn handshake(&self, epid: &EndpointId, address: SocketAddr, serves_requests: bool) {
         if self.peers.remove(epid).is_some() {
             // TODO check if not already added
 
-            let peer = Arc::new(HandshakedPeer::new(*epid, address));
+            let peer = HandshakedPeer::new(*epid, address);
+            peer.set_serves_requests(serves_requests);
+            let peer = Arc::new(peer);
 
             self.handshaked_peers.insert(*epid, peer.clone());
             self.handshaked_peers_keys.write().await.push(*epid);
         }
     }
 
-    pub(crate) async fn remove(&self, epid: &EndpointId) {
-        // TODO both ?
+    /// Removes a peer, handshaked or not, and returns it if it had completed its handshake.
+    pub(crate) async fn remove(&self, epid: &EndpointId) -> Option<Arc<HandshakedPeer>> {
         self.peers.remove(epid);
 
+        let removed = self.handshaked_peers.remove(epid).map(|(_, peer)| peer);
+
         self.handshaked_peers_keys.write().await.retain(|e| e != epid);
+
+        removed
     }
 
     pub(crate) fn connected_peers(&self) -> u8 {
-        // TODO impl
-        0
+        self.handshaked_peers.len().min(u8::MAX as usize) as u8
     }
 
     pub(crate) fn synced_peers(&self) -> u8 {
@@ -67,3 +72,59 @@ impl PeerManager {
         0
     }
 }
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use bee_network::{Origin, TransportProtocol};
+
+    fn peer(port: u16) -> Arc<Peer> {
+        let address: SocketAddr = format!("127.0.0.1:{}", port).parse().unwrap();
+
+        Arc::new(Peer::new(
+            EndpointId::new(TransportProtocol::Tcp, address),
+            address,
+            Origin::Outbound,
+        ))
+    }
+
+    #[tokio::test]
+    async fn add_remove_unhandshaked() {
+        let manager = PeerManager::new();
+        let peer = peer(15600);
+
+        manager.add(peer.clone());
+        assert_eq!(manager.peers.len(), 1);
+        assert_eq!(manager.connected_peers(), 0);
+
+        assert!(manager.remove(&peer.epid).await.is_none());
+        assert_eq!(manager.peers.len(), 0);
+        assert_eq!(manager.connected_peers(), 0);
+    }
+
+    #[tokio::test]
+    async fn add_handshake_remove() {
+        let manager = PeerManager::new();
+        let peers = (15600..15605).map(peer).collect::<Vec<_>>();
+
+        for peer in peers.iter() {
+            manager.add(peer.clone());
+            manager.handshake(&peer.epid, peer.address, true).await;
+        }
+
+        assert_eq!(manager.peers.len(), 0);
+        assert_eq!(manager.handshaked_peers.len(), 5);
+        assert_eq!(manager.handshaked_peers_keys.read().await.len(), 5);
+        assert_eq!(manager.connected_peers(), 5);
+
+        for (removed, peer) in peers.iter().enumerate() {
+            assert_eq!(manager.remove(&peer.epid).await.unwrap().epid, peer.epid);
+            assert!(manager.remove(&peer.epid).await.is_none());
+            assert_eq!(manager.handshaked_peers.len(), 4 - removed);
+            assert_eq!(manager.handshaked_peers_keys.read().await.len(), 4 - removed);
+            assert_eq!(manager.connected_peers() as usize, 4 - removed);
+        }
+    }
+}
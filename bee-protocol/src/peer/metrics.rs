@@ -36,6 +36,10 @@ pub struct PeerMetrics {
     transaction_requests_sent: AtomicU64,
     #[allow(dead_code)]
     heartbeats_sent: AtomicU64,
+
+    // Requests sent to the peer that are neither answered nor timed out yet.
+    outstanding_requests: AtomicU64,
+    completed_requests: AtomicU64,
 }
 
 impl PeerMetrics {
@@ -162,6 +166,31 @@ impl PeerMetrics {
     pub(crate) fn heartbeats_sent_inc(&self) -> u64 {
         self.heartbeats_sent.fetch_add(1, Ordering::SeqCst)
     }
+
+    pub fn outstanding_requests(&self) -> u64 {
+        self.outstanding_requests.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn outstanding_requests_inc(&self) -> u64 {
+        self.outstanding_requests.fetch_add(1, Ordering::SeqCst)
+    }
+
+    // Saturates at zero rather than wrapping around if a request is accounted for twice.
+    pub(crate) fn outstanding_requests_dec(&self) -> u64 {
+        self.outstanding_requests
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |outstanding| {
+                Some(outstanding.saturating_sub(1))
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn completed_requests(&self) -> u64 {
+        self.completed_requests.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn completed_requests_inc(&self) -> u64 {
+        self.completed_requests.fetch_add(1, Ordering::SeqCst)
+    }
 }
 
 #[cfg(test)]
@@ -231,4 +260,25 @@ mod tests {
         assert_eq!(metrics.transaction_requests_sent(), 1);
         assert_eq!(metrics.heartbeats_sent(), 1);
     }
+
+    #[test]
+    fn peer_metrics_requests() {
+        let metrics = PeerMetrics::default();
+
+        assert_eq!(metrics.outstanding_requests(), 0);
+        assert_eq!(metrics.completed_requests(), 0);
+
+        metrics.outstanding_requests_inc();
+        metrics.outstanding_requests_inc();
+        metrics.outstanding_requests_dec();
+        metrics.completed_requests_inc();
+
+        assert_eq!(metrics.outstanding_requests(), 1);
+        assert_eq!(metrics.completed_requests(), 1);
+
+        metrics.outstanding_requests_dec();
+        metrics.outstanding_requests_dec();
+
+        assert_eq!(metrics.outstanding_requests(), 0);
+    }
 }
@@ -13,19 +13,41 @@ use crate::{
     message::{
         tlv_into_bytes, Heartbeat, Message, MilestoneRequest, Transaction as TransactionMessage, TransactionRequest,
     },
-    milestone::MilestoneIndex,
+    milestone::{cone_too_large, MilestoneIndex},
     protocol::Protocol,
     tangle::MsTangle,
-    worker::{MilestoneRequesterWorkerEvent, TransactionRequesterWorkerEvent},
+    worker::{HasherWorkerEvent, MilestoneRequesterWorkerEvent, TransactionRequesterWorkerEvent},
 };
 
+use bee_common_ext::{channel, wait_priority_queue::WaitPriorityQueue};
 use bee_crypto::ternary::Hash;
 use bee_network::{Command::SendMessage, EndpointId};
 use bee_storage::storage::Backend;
+use bee_transaction::bundled::BundledTransaction;
 
-use log::warn;
+use dashmap::DashSet;
+use log::{debug, warn};
 
-use std::marker::PhantomData;
+use std::{fmt, marker::PhantomData};
+
+#[derive(Debug)]
+pub enum SubmitError {
+    /// The protocol workers are not running yet.
+    NotStarted,
+    /// The protocol workers are shutting down.
+    ShuttingDown,
+}
+
+impl fmt::Display for SubmitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotStarted => write!(f, "protocol workers are not running yet"),
+            Self::ShuttingDown => write!(f, "protocol workers are shutting down"),
+        }
+    }
+}
+
+impl std::error::Error for SubmitError {}
 
 pub(crate) struct Sender<M: Message> {
     marker: PhantomData<M>,
@@ -37,7 +59,7 @@ macro_rules! implement_sender_worker {
             pub(crate) fn send(epid: &EndpointId, message: $type) {
                 match Protocol::get().network.unbounded_send(SendMessage {
                     receiver_epid: *epid,
-                    message: tlv_into_bytes(message),
+                    message: tlv_into_bytes(message).into(),
                 }) {
                     Ok(_) => {
                         // self.peer.metrics.$incrementor();
@@ -57,6 +79,24 @@ implement_sender_worker!(TransactionMessage, transaction, transactions_sent_inc)
 implement_sender_worker!(TransactionRequest, transaction_request, transaction_requests_sent_inc);
 implement_sender_worker!(Heartbeat, heartbeat, heartbeats_sent_inc);
 
+// Queues a request for the milestone `index` unless one is already in flight. Requests for the latest milestone, at
+// index 0, are always queued since no milestone is ever received under that index.
+fn queue_milestone_request(
+    in_flight_milestones: &DashSet<MilestoneIndex>,
+    milestone_requester: &channel::Sender<MilestoneRequesterWorkerEvent>,
+    index: MilestoneIndex,
+    to: Option<EndpointId>,
+) {
+    if index.0 != 0 && !in_flight_milestones.insert(index) {
+        return;
+    }
+
+    if let Err(e) = milestone_requester.send(MilestoneRequesterWorkerEvent(index, to)) {
+        warn!("Requesting milestone failed: {}.", e);
+        in_flight_milestones.remove(&index);
+    }
+}
+
 impl Protocol {
     // TODO move some functions to workers
 
@@ -64,30 +104,28 @@ impl Protocol {
 
     pub(crate) fn request_milestone<B: Backend>(
         tangle: &MsTangle<B>,
-        transaction_requester: &flume::Sender<MilestoneRequesterWorkerEvent>,
+        transaction_requester: &channel::Sender<MilestoneRequesterWorkerEvent>,
         index: MilestoneIndex,
         to: Option<EndpointId>,
     ) {
         if !Protocol::get().requested_milestones.contains_key(&index) && !tangle.contains_milestone(index) {
-            if let Err(e) = transaction_requester.send(MilestoneRequesterWorkerEvent(index, to)) {
-                warn!("Requesting milestone failed: {}.", e);
-            }
+            queue_milestone_request(&Protocol::get().in_flight_milestones, transaction_requester, index, to);
         }
     }
 
     pub(crate) fn request_latest_milestone<B: Backend>(
         tangle: &MsTangle<B>,
-        transaction_requester: &flume::Sender<MilestoneRequesterWorkerEvent>,
+        transaction_requester: &channel::Sender<MilestoneRequesterWorkerEvent>,
         to: Option<EndpointId>,
     ) {
-        Protocol::request_milestone(tangle, transaction_requester, MilestoneIndex(0), to)
+        Protocol::request_milestone(tangle, transaction_requester, MilestoneIndex::from(0), to)
     }
 
     // TransactionRequest
 
     pub(crate) async fn request_transaction<B: Backend>(
         tangle: &MsTangle<B>,
-        transaction_requester: &flume::Sender<TransactionRequesterWorkerEvent>,
+        transaction_requester: &WaitPriorityQueue<TransactionRequesterWorkerEvent>,
         hash: Hash,
         index: MilestoneIndex,
     ) {
@@ -95,12 +133,42 @@ impl Protocol {
             && !tangle.is_solid_entry_point(&hash)
             && !Protocol::get().requested_transactions.contains_key(&hash)
         {
-            if let Err(e) = transaction_requester.send(TransactionRequesterWorkerEvent(hash, index)) {
-                warn!("Requesting transaction failed: {}.", e);
+            if let Err(e) = transaction_requester.try_insert(TransactionRequesterWorkerEvent(hash, index)) {
+                debug!("Dropped request for transaction {:?}: {}", hash, e);
+                Protocol::get().metrics.dropped_requests_inc();
             }
         }
     }
 
+    // Submission
+
+    /// Submits a locally issued transaction, processed and broadcast like the ones received from peers.
+    pub fn submit_transaction(transaction: &BundledTransaction) -> Result<(), SubmitError> {
+        let hasher = Protocol::get().hasher.r#try().ok_or(SubmitError::NotStarted)?;
+
+        hasher
+            .send(HasherWorkerEvent {
+                from: None,
+                transaction_message: TransactionMessage::from(transaction),
+            })
+            .map_err(|_| SubmitError::ShuttingDown)
+    }
+
+    // Confirmation
+
+    pub fn transactions_confirmed(count: u64) {
+        Protocol::get().metrics.confirmed_transactions_add(count);
+    }
+
+    // Milestone
+
+    /// Reports that the past cone of the milestone at `index` exceeds `max_cone_size` transactions.
+    pub fn milestone_cone_too_large(index: MilestoneIndex, max_cone_size: usize) {
+        let protocol = Protocol::get();
+
+        cone_too_large(&protocol.bus, &protocol.metrics, index, max_cone_size);
+    }
+
     // Heartbeat
 
     pub fn send_heartbeat(
@@ -112,9 +180,9 @@ impl Protocol {
         Sender::<Heartbeat>::send(
             &to,
             Heartbeat::new(
-                *latest_solid_milestone_index,
-                *pruning_milestone_index,
-                *latest_milestone_index,
+                latest_solid_milestone_index.into(),
+                pruning_milestone_index.into(),
+                latest_milestone_index.into(),
                 Protocol::get().peer_manager.connected_peers(),
                 Protocol::get().peer_manager.synced_peers(),
             ),
@@ -136,3 +204,60 @@ impl Protocol {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn queued(receiver: &channel::Receiver<MilestoneRequesterWorkerEvent>) -> Vec<MilestoneIndex> {
+        let mut indexes = Vec::new();
+
+        while let Ok(MilestoneRequesterWorkerEvent(index, _)) = receiver.try_recv() {
+            indexes.push(index);
+        }
+
+        indexes
+    }
+
+    #[test]
+    fn milestone_requests_deduplicated() {
+        let in_flight_milestones = DashSet::new();
+        let (sender, receiver) = channel::unbounded("MilestoneRequesterWorker");
+
+        for _ in 0..100 {
+            queue_milestone_request(&in_flight_milestones, &sender, MilestoneIndex(42), None);
+        }
+
+        assert_eq!(queued(&receiver), vec![MilestoneIndex(42)]);
+
+        // Once the milestone is received, it can be requested again.
+        in_flight_milestones.remove(&MilestoneIndex(42));
+        queue_milestone_request(&in_flight_milestones, &sender, MilestoneIndex(42), None);
+
+        assert_eq!(queued(&receiver), vec![MilestoneIndex(42)]);
+    }
+
+    #[test]
+    fn latest_milestone_requests_not_deduplicated() {
+        let in_flight_milestones = DashSet::new();
+        let (sender, receiver) = channel::unbounded("MilestoneRequesterWorker");
+
+        queue_milestone_request(&in_flight_milestones, &sender, MilestoneIndex(0), None);
+        queue_milestone_request(&in_flight_milestones, &sender, MilestoneIndex(0), None);
+
+        assert_eq!(queued(&receiver), vec![MilestoneIndex(0), MilestoneIndex(0)]);
+        assert!(in_flight_milestones.is_empty());
+    }
+
+    #[test]
+    fn failed_request_not_in_flight() {
+        let in_flight_milestones = DashSet::new();
+        let (sender, receiver) = channel::unbounded("MilestoneRequesterWorker");
+
+        drop(receiver);
+        queue_milestone_request(&in_flight_milestones, &sender, MilestoneIndex(42), None);
+
+        assert!(in_flight_milestones.is_empty());
+    }
+}
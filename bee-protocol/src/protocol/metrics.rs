@@ -9,15 +9,86 @@
 // an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and limitations under the License.
 
-use std::sync::atomic::{AtomicU64, Ordering};
+use bee_common_ext::channel::{self, ChannelDepth};
+use bee_storage::{
+    access::{Fetch, Insert},
+    persistable::Persistable,
+    storage::Backend,
+};
+
+use log::warn;
+
+use std::{
+    collections::HashMap,
+    ops::Add,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+/// The subset of the protocol counters that is persisted to provide lifetime statistics.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Persistable)]
+pub struct PersistedMetrics {
+    pub invalid_transactions: u64,
+    pub new_transactions: u64,
+    pub known_transactions: u64,
+    pub transactions_received: u64,
+    pub transactions_sent: u64,
+    pub confirmed_bundles: u64,
+    pub confirmed_transactions: u64,
+    pub conflicting_bundles: u64,
+}
+
+impl Add for PersistedMetrics {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            invalid_transactions: self.invalid_transactions.saturating_add(other.invalid_transactions),
+            new_transactions: self.new_transactions.saturating_add(other.new_transactions),
+            known_transactions: self.known_transactions.saturating_add(other.known_transactions),
+            transactions_received: self.transactions_received.saturating_add(other.transactions_received),
+            transactions_sent: self.transactions_sent.saturating_add(other.transactions_sent),
+            confirmed_bundles: self.confirmed_bundles.saturating_add(other.confirmed_bundles),
+            confirmed_transactions: self.confirmed_transactions.saturating_add(other.confirmed_transactions),
+            conflicting_bundles: self.conflicting_bundles.saturating_add(other.conflicting_bundles),
+        }
+    }
+}
+
+/// The persisted counters, both since the node started and since it was installed, and the depth of the worker
+/// channels.
+///
+/// Rates must be computed from `since_start`, the lifetime values include counts of previous runs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MetricsSnapshot {
+    pub since_start: PersistedMetrics,
+    pub lifetime: PersistedMetrics,
+    pub channels: Vec<ChannelDepth>,
+}
+
+/// Storage accesses required to persist the protocol metrics.
+///
+/// There is a single set of persisted metrics, it is stored under the `()` key.
+pub trait MetricsStorage: Backend + Insert<(), PersistedMetrics> + Fetch<(), PersistedMetrics> {}
+
+impl<B> MetricsStorage for B where B: Backend + Insert<(), PersistedMetrics> + Fetch<(), PersistedMetrics> {}
 
 #[derive(Default)]
 pub struct ProtocolMetrics {
+    // Counts of the previous runs, seeded from the storage at startup.
+    previous_runs: spin::Mutex<PersistedMetrics>,
+
     invalid_transactions: AtomicU64,
     stale_transactions: AtomicU64,
     new_transactions: AtomicU64,
     known_transactions: AtomicU64,
 
+    mwm_accepted_transactions: AtomicU64,
+    mwm_historical_accepted_transactions: AtomicU64,
+
+    zeroed_attachment_bounds: AtomicU64,
+    inverted_attachment_bounds: AtomicU64,
+    out_of_attachment_bounds: AtomicU64,
+
     invalid_messages: AtomicU64,
 
     milestone_requests_received: AtomicU64,
@@ -30,16 +101,89 @@ pub struct ProtocolMetrics {
     transaction_requests_sent: AtomicU64,
     heartbeats_sent: AtomicU64,
 
+    dropped_requests: AtomicU64,
+    assisted_transactions: AtomicU64,
+    responder_cache_hits: AtomicU64,
+    responder_merged_requests: AtomicU64,
+
     value_bundles: AtomicU64,
     non_value_bundles: AtomicU64,
     confirmed_bundles: AtomicU64,
+    confirmed_transactions: AtomicU64,
     conflicting_bundles: AtomicU64,
+
+    oversized_milestones: AtomicU64,
+    skipped_milestone_validations: AtomicU64,
+
+    peer_state_transitions: AtomicU64,
 }
 
 impl ProtocolMetrics {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Seeds the lifetime counters with the ones persisted in `storage`.
+    ///
+    /// Missing or unreadable values are treated as zero so that a damaged entry never prevents the node from starting.
+    pub(crate) async fn load<B: MetricsStorage>(&self, storage: &B) {
+        let persisted = match Fetch::<(), PersistedMetrics>::fetch(storage, &()).await {
+            Ok(persisted) => persisted.unwrap_or_default(),
+            Err(e) => {
+                warn!("Loading persisted metrics failed, starting from zero: {:?}.", e);
+                PersistedMetrics::default()
+            }
+        };
+
+        *self.previous_runs.lock() = persisted;
+    }
+
+    /// Writes the lifetime counters to `storage`.
+    pub(crate) async fn persist<B: MetricsStorage>(
+        &self,
+        storage: &B,
+    ) -> Result<(), <B as Insert<(), PersistedMetrics>>::Error> {
+        Insert::<(), PersistedMetrics>::insert(storage, &(), &self.lifetime()).await
+    }
+
+    /// Returns the persisted counters as counted since the node started.
+    pub fn since_start(&self) -> PersistedMetrics {
+        PersistedMetrics {
+            invalid_transactions: self.invalid_transactions(),
+            new_transactions: self.new_transactions(),
+            known_transactions: self.known_transactions(),
+            transactions_received: self.transactions_received(),
+            transactions_sent: self.transactions_sent(),
+            confirmed_bundles: self.confirmed_bundles(),
+            confirmed_transactions: self.confirmed_transactions(),
+            conflicting_bundles: self.conflicting_bundles(),
+        }
+    }
+
+    /// Returns the persisted counters as counted since the node was installed.
+    pub fn lifetime(&self) -> PersistedMetrics {
+        *self.previous_runs.lock() + self.since_start()
+    }
+
+    /// Returns the number of valid messages received since the node started, keyed by message type.
+    pub fn metrics_by_type(&self) -> HashMap<&'static str, u64> {
+        let mut metrics = HashMap::new();
+
+        metrics.insert("milestone_request", self.milestone_requests_received());
+        metrics.insert("transaction", self.transactions_received());
+        metrics.insert("transaction_request", self.transaction_requests_received());
+        metrics.insert("heartbeat", self.heartbeats_received());
+
+        metrics
+    }
+
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        MetricsSnapshot {
+            since_start: self.since_start(),
+            lifetime: self.lifetime(),
+            channels: channel::depths(),
+        }
+    }
 }
 
 impl ProtocolMetrics {
@@ -75,6 +219,46 @@ impl ProtocolMetrics {
         self.known_transactions.fetch_add(1, Ordering::SeqCst)
     }
 
+    pub fn mwm_accepted_transactions(&self) -> u64 {
+        self.mwm_accepted_transactions.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn mwm_accepted_transactions_inc(&self) -> u64 {
+        self.mwm_accepted_transactions.fetch_add(1, Ordering::SeqCst)
+    }
+
+    pub fn mwm_historical_accepted_transactions(&self) -> u64 {
+        self.mwm_historical_accepted_transactions.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn mwm_historical_accepted_transactions_inc(&self) -> u64 {
+        self.mwm_historical_accepted_transactions.fetch_add(1, Ordering::SeqCst)
+    }
+
+    pub fn zeroed_attachment_bounds(&self) -> u64 {
+        self.zeroed_attachment_bounds.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn zeroed_attachment_bounds_inc(&self) -> u64 {
+        self.zeroed_attachment_bounds.fetch_add(1, Ordering::SeqCst)
+    }
+
+    pub fn inverted_attachment_bounds(&self) -> u64 {
+        self.inverted_attachment_bounds.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn inverted_attachment_bounds_inc(&self) -> u64 {
+        self.inverted_attachment_bounds.fetch_add(1, Ordering::SeqCst)
+    }
+
+    pub fn out_of_attachment_bounds(&self) -> u64 {
+        self.out_of_attachment_bounds.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn out_of_attachment_bounds_inc(&self) -> u64 {
+        self.out_of_attachment_bounds.fetch_add(1, Ordering::SeqCst)
+    }
+
     pub fn invalid_messages(&self) -> u64 {
         self.invalid_messages.load(Ordering::Relaxed)
     }
@@ -141,6 +325,38 @@ impl ProtocolMetrics {
         self.transaction_requests_sent.fetch_add(1, Ordering::SeqCst)
     }
 
+    pub fn dropped_requests(&self) -> u64 {
+        self.dropped_requests.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn dropped_requests_inc(&self) -> u64 {
+        self.dropped_requests.fetch_add(1, Ordering::SeqCst)
+    }
+
+    pub fn assisted_transactions(&self) -> u64 {
+        self.assisted_transactions.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn assisted_transactions_inc(&self) -> u64 {
+        self.assisted_transactions.fetch_add(1, Ordering::SeqCst)
+    }
+
+    pub fn responder_cache_hits(&self) -> u64 {
+        self.responder_cache_hits.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn responder_cache_hits_inc(&self) -> u64 {
+        self.responder_cache_hits.fetch_add(1, Ordering::SeqCst)
+    }
+
+    pub fn responder_merged_requests(&self) -> u64 {
+        self.responder_merged_requests.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn responder_merged_requests_inc(&self) -> u64 {
+        self.responder_merged_requests.fetch_add(1, Ordering::SeqCst)
+    }
+
     pub fn heartbeats_sent(&self) -> u64 {
         self.heartbeats_sent.load(Ordering::Relaxed)
     }
@@ -177,6 +393,14 @@ impl ProtocolMetrics {
         self.confirmed_bundles.fetch_add(1, Ordering::SeqCst)
     }
 
+    pub fn confirmed_transactions(&self) -> u64 {
+        self.confirmed_transactions.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn confirmed_transactions_add(&self, value: u64) -> u64 {
+        self.confirmed_transactions.fetch_add(value, Ordering::SeqCst)
+    }
+
     pub fn conflicting_bundles(&self) -> u64 {
         self.conflicting_bundles.load(Ordering::Relaxed)
     }
@@ -185,6 +409,30 @@ impl ProtocolMetrics {
     pub(crate) fn conflicting_bundles_inc(&self) -> u64 {
         self.conflicting_bundles.fetch_add(1, Ordering::SeqCst)
     }
+
+    pub fn oversized_milestones(&self) -> u64 {
+        self.oversized_milestones.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn oversized_milestones_inc(&self) -> u64 {
+        self.oversized_milestones.fetch_add(1, Ordering::SeqCst)
+    }
+
+    pub fn skipped_milestone_validations(&self) -> u64 {
+        self.skipped_milestone_validations.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn skipped_milestone_validations_inc(&self) -> u64 {
+        self.skipped_milestone_validations.fetch_add(1, Ordering::SeqCst)
+    }
+
+    pub fn peer_state_transitions(&self) -> u64 {
+        self.peer_state_transitions.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn peer_state_transitions_inc(&self) -> u64 {
+        self.peer_state_transitions.fetch_add(1, Ordering::SeqCst)
+    }
 }
 
 #[cfg(test)]
@@ -192,6 +440,26 @@ mod tests {
 
     use super::*;
 
+    use crate::test_utils::MockStorage;
+
+    #[test]
+    fn persisted_metrics_add_saturates() {
+        let lifetime = PersistedMetrics {
+            new_transactions: u64::MAX - 1,
+            confirmed_bundles: 2,
+            ..Default::default()
+        };
+        let since_start = PersistedMetrics {
+            new_transactions: 2,
+            confirmed_bundles: 3,
+            ..Default::default()
+        };
+        let total = lifetime + since_start;
+
+        assert_eq!(total.new_transactions, u64::MAX);
+        assert_eq!(total.confirmed_bundles, 5);
+    }
+
     #[test]
     fn protocol_metrics_transactions() {
         let metrics = ProtocolMetrics::default();
@@ -255,6 +523,44 @@ mod tests {
         assert_eq!(metrics.heartbeats_sent(), 1);
     }
 
+    #[test]
+    fn protocol_metrics_dropped_requests() {
+        let metrics = ProtocolMetrics::default();
+
+        assert_eq!(metrics.dropped_requests(), 0);
+
+        metrics.dropped_requests_inc();
+        metrics.dropped_requests_inc();
+
+        assert_eq!(metrics.dropped_requests(), 2);
+    }
+
+    #[test]
+    fn protocol_metrics_assisted_transactions() {
+        let metrics = ProtocolMetrics::default();
+
+        assert_eq!(metrics.assisted_transactions(), 0);
+
+        metrics.assisted_transactions_inc();
+
+        assert_eq!(metrics.assisted_transactions(), 1);
+    }
+
+    #[test]
+    fn protocol_metrics_responder_cache() {
+        let metrics = ProtocolMetrics::default();
+
+        assert_eq!(metrics.responder_cache_hits(), 0);
+        assert_eq!(metrics.responder_merged_requests(), 0);
+
+        metrics.responder_cache_hits_inc();
+        metrics.responder_merged_requests_inc();
+        metrics.responder_merged_requests_inc();
+
+        assert_eq!(metrics.responder_cache_hits(), 1);
+        assert_eq!(metrics.responder_merged_requests(), 2);
+    }
+
     #[test]
     fn protocol_metrics_confirmation() {
         let metrics = ProtocolMetrics::default();
@@ -274,4 +580,74 @@ mod tests {
         assert_eq!(metrics.confirmed_bundles(), 1);
         assert_eq!(metrics.conflicting_bundles(), 1);
     }
+
+    #[tokio::test]
+    async fn lifetime_metrics_survive_restart() {
+        let storage = MockStorage::default();
+
+        let metrics = ProtocolMetrics::default();
+        metrics.load(&storage).await;
+        metrics.new_transactions_inc();
+        metrics.new_transactions_inc();
+        metrics.transactions_received_inc();
+        metrics.confirmed_transactions_add(5);
+        metrics.persist(&storage).await.unwrap();
+
+        // Restart.
+        let metrics = ProtocolMetrics::default();
+        metrics.load(&storage).await;
+        metrics.new_transactions_inc();
+
+        let snapshot = metrics.snapshot();
+
+        assert_eq!(snapshot.since_start.new_transactions, 1);
+        assert_eq!(snapshot.since_start.transactions_received, 0);
+        assert_eq!(snapshot.since_start.confirmed_transactions, 0);
+        assert_eq!(snapshot.lifetime.new_transactions, 3);
+        assert_eq!(snapshot.lifetime.transactions_received, 1);
+        assert_eq!(snapshot.lifetime.confirmed_transactions, 5);
+
+        metrics.persist(&storage).await.unwrap();
+
+        // Restart.
+        let metrics = ProtocolMetrics::default();
+        metrics.load(&storage).await;
+
+        assert_eq!(metrics.since_start(), PersistedMetrics::default());
+        assert_eq!(metrics.lifetime().new_transactions, 3);
+        assert_eq!(metrics.lifetime().transactions_received, 1);
+        assert_eq!(metrics.lifetime().confirmed_transactions, 5);
+    }
+
+    #[tokio::test]
+    async fn missing_persisted_metrics_start_from_zero() {
+        let metrics = ProtocolMetrics::default();
+        metrics.load(&MockStorage::default()).await;
+
+        assert_eq!(metrics.lifetime(), PersistedMetrics::default());
+    }
+
+    #[tokio::test]
+    async fn corrupted_persisted_metrics_start_from_zero() {
+        let mut storage = MockStorage::default();
+
+        Insert::<(), PersistedMetrics>::insert(
+            &storage,
+            &(),
+            &PersistedMetrics {
+                new_transactions: 10,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        storage.corrupted = true;
+
+        let metrics = ProtocolMetrics::default();
+        metrics.load(&storage).await;
+        metrics.new_transactions_inc();
+
+        assert_eq!(metrics.lifetime().new_transactions, 1);
+        assert_eq!(metrics.since_start().new_transactions, 1);
+    }
 }
@@ -14,5 +14,6 @@ mod metrics;
 mod protocol;
 
 pub(crate) use helper::Sender;
-pub use metrics::ProtocolMetrics;
+pub use helper::SubmitError;
+pub use metrics::{MetricsSnapshot, MetricsStorage, PersistedMetrics, ProtocolMetrics};
 pub use protocol::Protocol;
@@ -11,21 +11,24 @@
 
 use crate::{
     config::ProtocolConfig,
-    event::{LatestMilestoneChanged, LatestSolidMilestoneChanged},
+    event::{LatestMilestoneChanged, LatestSolidMilestoneChanged, TransactionUnreachable},
     milestone::MilestoneIndex,
     peer::{Peer, PeerManager},
-    protocol::ProtocolMetrics,
-    tangle::MsTangle,
+    protocol::{MetricsStorage, ProtocolMetrics},
+    tangle::{ExportFormat, MsTangle, RunningMarkerStorage, SolidEntryPointsStorage},
     worker::{
-        BroadcasterWorker, BundleValidatorWorker, HasherWorker, KickstartWorker, MilestoneRequesterWorker,
-        MilestoneResponderWorker, MilestoneSolidifierWorker, MilestoneSolidifierWorkerEvent, MilestoneValidatorWorker,
-        PeerHandshakerWorker, ProcessorWorker, SolidPropagatorWorker, StatusWorker, StorageWorker, TangleWorker,
-        TpsWorker, TransactionRequesterWorker, TransactionResponderWorker,
+        BroadcasterWorker, BundleValidatorWorker, ChannelMonitorWorker, HasherWorker, HasherWorkerEvent,
+        KickstartWorker, MetricsWorker, MilestoneRequesterWorker, MilestoneResponderWorker, MilestoneSolidifierWorker,
+        MilestoneSolidifierWorkerEvent, MilestoneValidatorWorker, PeerHandshakerWorker, ProcessorWorker,
+        RevalidatorWorker, SolidPropagatorWorker, StatusWorker, StorageWorker, TangleWorker, TpsWorker,
+        TransactionRequesterWorker, TransactionResponderWorker,
     },
 };
 
 use bee_common_ext::{
+    channel,
     event::Bus,
+    log_throttle::LogThrottle,
     node::{Node, NodeBuilder},
 };
 use bee_crypto::ternary::Hash;
@@ -33,12 +36,21 @@ use bee_network::{EndpointId, Network, Origin};
 use bee_snapshot::metadata::SnapshotMetadata;
 use bee_storage::storage::Backend;
 
-use dashmap::DashMap;
-use futures::channel::oneshot;
-use log::{debug, error, info};
+use dashmap::{DashMap, DashSet};
+use futures::{channel::oneshot, lock::Mutex};
+use log::{debug, error, info, log_enabled, Level};
 use tokio::spawn;
 
-use std::{net::SocketAddr, sync::Arc, time::Instant};
+use std::{
+    net::SocketAddr,
+    sync::{atomic::AtomicBool, Arc},
+    time::{Duration, Instant},
+};
+
+// Interval at which log lines repeated by a peer are summarized.
+const LOG_THROTTLE_INTERVAL_SECS: u64 = 10;
+// Depth of the future cone of an unreachable transaction dumped when debug logs are enabled.
+const UNREACHABLE_EXPORT_DEPTH: usize = 3;
 
 static PROTOCOL: spin::RwLock<Option<&'static Protocol>> = spin::RwLock::new(None);
 
@@ -49,8 +61,19 @@ pub struct Protocol {
     pub(crate) bus: Arc<Bus<'static>>,
     pub(crate) metrics: ProtocolMetrics,
     pub(crate) peer_manager: PeerManager,
-    pub(crate) requested_transactions: DashMap<Hash, (MilestoneIndex, Instant)>,
-    pub(crate) requested_milestones: DashMap<MilestoneIndex, Instant>,
+    pub(crate) requested_transactions: DashMap<Hash, (MilestoneIndex, Instant, u8, Option<EndpointId>)>,
+    // Requested milestones, with the time of the last request and the peer it was sent to, if any.
+    pub(crate) requested_milestones: DashMap<MilestoneIndex, (Instant, Option<EndpointId>)>,
+    // Milestones that were requested and not received yet, from the moment their request is queued.
+    pub(crate) in_flight_milestones: DashSet<MilestoneIndex>,
+    // Set once the node is built, to submit local transactions.
+    pub(crate) hasher: spin::Once<channel::Sender<HasherWorkerEvent>>,
+    pub(crate) log_throttle: LogThrottle<EndpointId>,
+    // Whether a `TangleSyncedEvent` was dispatched since the tangle last fell behind the latest milestone.
+    pub(crate) synced: AtomicBool,
+    // Held while the tangle is revalidated after an unclean shutdown and while a milestone is solidified, so that
+    // neither sees the flags the other is updating.
+    pub(crate) revalidation: Mutex<()>,
 }
 
 impl Protocol {
@@ -61,7 +84,10 @@ impl Protocol {
         snapshot_metadata: SnapshotMetadata,
         node_builder: N::Builder,
         bus: Arc<Bus<'static>>,
-    ) -> N::Builder {
+    ) -> N::Builder
+    where
+        N::Backend: SolidEntryPointsStorage + MetricsStorage + RunningMarkerStorage,
+    {
         let protocol = Protocol {
             network: network.clone(),
             snapshot_timestamp: snapshot_metadata.timestamp(),
@@ -70,50 +96,88 @@ impl Protocol {
             peer_manager: PeerManager::new(),
             requested_transactions: Default::default(),
             requested_milestones: Default::default(),
+            in_flight_milestones: Default::default(),
+            hasher: spin::Once::new(),
+            log_throttle: LogThrottle::new(Duration::from_secs(LOG_THROTTLE_INTERVAL_SECS)),
+            synced: AtomicBool::new(false),
+            revalidation: Mutex::new(()),
         };
 
         *PROTOCOL.write() = Some(Box::leak(Box::new(protocol)));
 
         let (ms_send, ms_recv) = oneshot::channel();
 
-        node_builder
+        let mut node_builder = node_builder
             .with_worker_cfg::<StorageWorker>(database_config)
-            .with_worker_cfg::<TangleWorker>(snapshot_metadata)
+            .with_worker_cfg::<TangleWorker>((snapshot_metadata, config.workers.ms_hash_cache))
             .with_worker_cfg::<HasherWorker>(config.workers.transaction_worker_cache)
             .with_worker_cfg::<ProcessorWorker>(config.clone())
-            .with_worker::<TransactionResponderWorker>()
-            .with_worker::<MilestoneResponderWorker>()
-            .with_worker::<TransactionRequesterWorker>()
-            .with_worker::<MilestoneRequesterWorker>()
+            .with_worker_cfg::<TransactionRequesterWorker>((
+                config.workers.transaction_request_max_retries,
+                config.workers.max_outstanding_requests,
+            ))
+            .with_worker_cfg::<MilestoneRequesterWorker>(config.workers.max_outstanding_requests)
             .with_worker_cfg::<MilestoneValidatorWorker>(config.clone())
-            .with_worker_cfg::<BroadcasterWorker>(network)
             .with_worker::<BundleValidatorWorker>()
             .with_worker::<SolidPropagatorWorker>()
-            .with_worker_cfg::<StatusWorker>(config.workers.status_interval)
-            .with_worker::<TpsWorker>()
+            .with_worker_cfg::<MetricsWorker>((
+                config.workers.metrics_persistence,
+                config.workers.metrics_persistence_interval,
+            ))
+            .with_worker_cfg::<ChannelMonitorWorker>((
+                config.workers.channel_warn_fraction,
+                config.workers.channel_warn_duration,
+                config.workers.channel_soft_bound,
+            ))
             .with_worker_cfg::<KickstartWorker>((ms_send, config.workers.ms_sync_count))
-            .with_worker_cfg::<MilestoneSolidifierWorker>(ms_recv)
+            .with_worker_cfg::<MilestoneSolidifierWorker>((ms_recv, config.workers.ms_max_cone_size))
+            .with_worker_cfg::<RevalidatorWorker>((config.workers.revalidation_depth, config.workers.ms_max_cone_size));
+
+        // The other workers don't depend on these ones and degrade gracefully when they are missing.
+
+        if config.workers.broadcaster_enabled {
+            node_builder =
+                node_builder.with_worker_cfg::<BroadcasterWorker>((network, config.workers.broadcast_budget));
+        } else {
+            info!("Broadcaster disabled.");
+        }
+
+        if config.workers.responders_enabled {
+            node_builder = node_builder
+                .with_worker_cfg::<TransactionResponderWorker>(config.workers.solidification_assistance)
+                .with_worker::<MilestoneResponderWorker>();
+        } else {
+            info!("Responders disabled.");
+        }
+
+        if config.workers.status_enabled {
+            node_builder = node_builder.with_worker_cfg::<StatusWorker>(config.workers.status_interval);
+        }
+
+        if config.workers.tps_enabled {
+            node_builder =
+                node_builder.with_worker_cfg::<TpsWorker>((config.workers.tps_interval, config.workers.tps_window));
+        }
+
+        node_builder
     }
 
     pub fn events<N: Node>(node: &N, config: ProtocolConfig, bus: Arc<Bus<'static>>) {
+        let hasher = node.worker::<HasherWorker>().unwrap().tx.clone();
+        Protocol::get().hasher.call_once(|| hasher);
+
         let tangle = node.resource::<MsTangle<N::Backend>>();
         bus.add_listener(move |latest_milestone: &LatestMilestoneChanged| {
             info!(
                 "New milestone {} {}.",
-                *latest_milestone.0.index,
-                latest_milestone
-                    .0
-                    .hash()
-                    .iter_trytes()
-                    .map(char::from)
-                    .collect::<String>()
+                *latest_milestone.new,
+                latest_milestone.hash.iter_trytes().map(char::from).collect::<String>()
             );
-            tangle.update_latest_milestone_index(latest_milestone.0.index);
 
             Protocol::broadcast_heartbeat(
                 tangle.get_latest_solid_milestone_index(),
                 tangle.get_pruning_index(),
-                latest_milestone.0.index,
+                latest_milestone.new,
             );
         });
 
@@ -131,11 +195,11 @@ impl Protocol {
 
         let tangle = node.resource::<MsTangle<N::Backend>>();
         bus.add_listener(move |latest_solid_milestone: &LatestSolidMilestoneChanged| {
+            // The latest solid milestone index was already updated by the dispatcher of the event.
             debug!("New solid milestone {}.", *latest_solid_milestone.0.index);
-            tangle.update_latest_solid_milestone_index(latest_solid_milestone.0.index);
 
             let ms_sync_count = config.workers.ms_sync_count;
-            let next_ms = latest_solid_milestone.0.index + MilestoneIndex(ms_sync_count);
+            let next_ms = latest_solid_milestone.0.index + MilestoneIndex::from(ms_sync_count);
 
             if tangle.contains_milestone(next_ms) {
                 if let Err(e) = milestone_solidifier.send(MilestoneSolidifierWorkerEvent(next_ms)) {
@@ -151,6 +215,27 @@ impl Protocol {
                 tangle.get_latest_milestone_index(),
             );
         });
+
+        // Solidification stalls on unreachable transactions, their approvers are dumped to help finding out why.
+        let tangle = node.resource::<MsTangle<N::Backend>>();
+        bus.add_listener(move |unreachable: &TransactionUnreachable| {
+            if !log_enabled!(Level::Debug) {
+                return;
+            }
+
+            let tangle = tangle.clone();
+            let hash = unreachable.hash;
+            spawn(async move {
+                let dot = tangle
+                    .export_subgraph(&hash, 0, UNREACHABLE_EXPORT_DEPTH, ExportFormat::Dot)
+                    .await;
+                debug!(
+                    "Neighborhood of unreachable transaction {}:\n{}",
+                    hash.iter_trytes().map(char::from).collect::<String>(),
+                    dot
+                );
+            });
+        });
     }
 
     pub(crate) fn get() -> &'static Protocol {
@@ -180,8 +265,10 @@ impl Protocol {
                 config.clone(),
                 peer,
                 node.worker::<HasherWorker>().unwrap().tx.clone(),
-                node.worker::<TransactionResponderWorker>().unwrap().tx.clone(),
-                node.worker::<MilestoneResponderWorker>().unwrap().tx.clone(),
+                node.worker::<TransactionResponderWorker>()
+                    .map(|responder| responder.tx.clone()),
+                node.worker::<MilestoneResponderWorker>()
+                    .map(|responder| responder.tx.clone()),
                 node.worker::<MilestoneRequesterWorker>().unwrap().tx.clone(),
             )
             .run(tangle, receiver_rx, receiver_shutdown_rx),
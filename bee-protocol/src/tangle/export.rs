@@ -0,0 +1,416 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use crate::tangle::{flags::Flags, MsTangle};
+
+use bee_crypto::ternary::Hash;
+use bee_storage::storage::Backend;
+
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt::Write,
+};
+
+// Maximum number of transactions exported, so that an export on a running node never walks a large part of the tangle.
+const MAX_EXPORTED_TRANSACTIONS: usize = 1_000;
+
+// Number of trytes of the hash used as the label of a DOT node.
+const DOT_LABEL_TRYTES: usize = 9;
+
+/// Formats a subgraph of the tangle can be exported to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// Graphviz DOT, nodes colored by their flags and branch edges dashed.
+    Dot,
+    /// Nodes with their flags and the hashes of their exported parents.
+    Json,
+}
+
+struct ExportedTransaction {
+    hash: Hash,
+    // `None` if the transaction is not in the tangle.
+    flags: Option<Flags>,
+    solid_entry_point: bool,
+    trunk: Option<Hash>,
+    branch: Option<Hash>,
+}
+
+struct Subgraph {
+    root: Hash,
+    transactions: Vec<ExportedTransaction>,
+    truncated: bool,
+}
+
+fn trytes(hash: &Hash) -> String {
+    hash.iter_trytes().map(char::from).collect::<String>()
+}
+
+impl<B: Backend> MsTangle<B> {
+    /// Exports the past cone of `root` up to `past_depth` and its future cone up to `future_depth`.
+    ///
+    /// Every transaction is looked up on its own so that no lock is held across the walk, and the export stops after
+    /// a bounded number of transactions; it is then flagged as truncated.
+    pub async fn export_subgraph(
+        &self,
+        root: &Hash,
+        past_depth: usize,
+        future_depth: usize,
+        format: ExportFormat,
+    ) -> String {
+        let subgraph = self.subgraph(root, past_depth, future_depth).await;
+
+        match format {
+            ExportFormat::Dot => subgraph.to_dot(),
+            ExportFormat::Json => subgraph.to_json(),
+        }
+    }
+
+    async fn subgraph(&self, root: &Hash, past_depth: usize, future_depth: usize) -> Subgraph {
+        let mut transactions = Vec::new();
+        let mut indexes = HashMap::new();
+        let mut truncated = false;
+
+        // Past cone first, then future cone, both breadth first so that truncation drops the farthest transactions.
+        for (past, max_depth) in [(true, past_depth), (false, future_depth)].iter() {
+            let mut queue = VecDeque::new();
+            queue.push_back((*root, 0));
+
+            while let Some((hash, depth)) = queue.pop_front() {
+                let (index, visited) = match indexes.get(&hash) {
+                    Some(index) => (*index, true),
+                    None => {
+                        if transactions.len() >= MAX_EXPORTED_TRANSACTIONS {
+                            truncated = true;
+                            break;
+                        }
+
+                        let (trunk, branch) = match self.get(&hash).await {
+                            Some(transaction) => (Some(*transaction.trunk()), Some(*transaction.branch())),
+                            None => (None, None),
+                        };
+                        transactions.push(ExportedTransaction {
+                            hash,
+                            flags: self.get_metadata(&hash).map(|metadata| *metadata.flags()),
+                            solid_entry_point: self.is_solid_entry_point(&hash),
+                            trunk,
+                            branch,
+                        });
+                        indexes.insert(hash, transactions.len() - 1);
+
+                        (transactions.len() - 1, false)
+                    }
+                };
+
+                // The root is expanded by both walks, any other transaction only when first visited.
+                if depth >= *max_depth || (depth > 0 && visited) {
+                    continue;
+                }
+
+                if *past {
+                    let transaction = &transactions[index];
+                    for parent in transaction.trunk.iter().chain(transaction.branch.iter()) {
+                        if !indexes.contains_key(parent) {
+                            queue.push_back((*parent, depth + 1));
+                        }
+                    }
+                } else {
+                    for child in self.get_children(&hash) {
+                        if !indexes.contains_key(&child) {
+                            queue.push_back((child, depth + 1));
+                        }
+                    }
+                }
+            }
+        }
+
+        // Only keep the links between exported transactions.
+        for transaction in transactions.iter_mut() {
+            transaction.trunk = transaction.trunk.filter(|trunk| indexes.contains_key(trunk));
+            transaction.branch = transaction.branch.filter(|branch| indexes.contains_key(branch));
+        }
+
+        Subgraph {
+            root: *root,
+            transactions,
+            truncated,
+        }
+    }
+}
+
+impl Subgraph {
+    fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph tangle {\n");
+
+        if self.truncated {
+            let _ = writeln!(
+                dot,
+                "    label=\"truncated to {} transactions\";",
+                MAX_EXPORTED_TRANSACTIONS
+            );
+        }
+
+        for transaction in &self.transactions {
+            let hash = trytes(&transaction.hash);
+            let shape = if transaction.hash == self.root {
+                "box"
+            } else {
+                "ellipse"
+            };
+            let (style, color) = match transaction.flags {
+                _ if transaction.solid_entry_point => ("filled", "gray"),
+                None => ("dashed", "white"),
+                Some(flags) if flags.is_milestone() => ("filled", "gold"),
+                Some(flags) if flags.is_confirmed() => ("filled", "palegreen"),
+                Some(flags) if flags.is_solid() => ("filled", "lightblue"),
+                Some(_) => ("filled", "white"),
+            };
+
+            let _ = writeln!(
+                dot,
+                "    \"{}\" [label=\"{}\", shape={}, style={}, fillcolor={}];",
+                hash,
+                &hash[..DOT_LABEL_TRYTES],
+                shape,
+                style,
+                color
+            );
+        }
+
+        for transaction in &self.transactions {
+            let hash = trytes(&transaction.hash);
+
+            if let Some(trunk) = &transaction.trunk {
+                let _ = writeln!(dot, "    \"{}\" -> \"{}\" [style=solid];", hash, trytes(trunk));
+            }
+            if let Some(branch) = &transaction.branch {
+                let _ = writeln!(dot, "    \"{}\" -> \"{}\" [style=dashed];", hash, trytes(branch));
+            }
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+
+    fn to_json(&self) -> String {
+        let parent = |hash: &Option<Hash>| match hash {
+            Some(hash) => format!("\"{}\"", trytes(hash)),
+            None => String::from("null"),
+        };
+
+        let nodes = self
+            .transactions
+            .iter()
+            .map(|transaction| {
+                let flags = transaction.flags.unwrap_or_default();
+
+                format!(
+                    "{{\"hash\":\"{}\",\"missing\":{},\"solid_entry_point\":{},\"solid\":{},\"confirmed\":{},\
+                     \"milestone\":{},\"trunk\":{},\"branch\":{}}}",
+                    trytes(&transaction.hash),
+                    transaction.flags.is_none(),
+                    transaction.solid_entry_point,
+                    flags.is_solid(),
+                    flags.is_confirmed(),
+                    flags.is_milestone(),
+                    parent(&transaction.trunk),
+                    parent(&transaction.branch)
+                )
+            })
+            .collect::<Vec<_>>();
+
+        format!(
+            "{{\"root\":\"{}\",\"truncated\":{},\"nodes\":[{}]}}",
+            trytes(&self.root),
+            self.truncated,
+            nodes.join(",")
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use crate::{
+        milestone::MilestoneIndex,
+        tangle::TransactionMetadata,
+        test_utils::{repeated_hash as hash, MockStorage},
+    };
+
+    use bee_common_ext::node::ResHandle;
+    use bee_transaction::bundled::{
+        Address, BundledTransaction as Tx, BundledTransactionBuilder, Index, Nonce, Payload, Tag, Timestamp, Value,
+    };
+
+    fn transaction(trunk: Hash, branch: Hash) -> Tx {
+        BundledTransactionBuilder::new()
+            .with_payload(Payload::zeros())
+            .with_address(Address::zeros())
+            .with_value(Value::from_inner_unchecked(0))
+            .with_obsolete_tag(Tag::zeros())
+            .with_timestamp(Timestamp::from_inner_unchecked(0))
+            .with_index(Index::from_inner_unchecked(0))
+            .with_last_index(Index::from_inner_unchecked(0))
+            .with_tag(Tag::zeros())
+            .with_attachment_ts(Timestamp::from_inner_unchecked(0))
+            .with_bundle(Hash::zeros())
+            .with_trunk(trunk)
+            .with_branch(branch)
+            .with_attachment_lbts(Timestamp::from_inner_unchecked(0))
+            .with_attachment_ubts(Timestamp::from_inner_unchecked(0))
+            .with_nonce(Nonce::zeros())
+            .build()
+            .unwrap()
+    }
+
+    fn metadata(flags: Flags) -> TransactionMetadata {
+        TransactionMetadata::new(flags, MilestoneIndex::from(0), 0, 0, 0)
+    }
+
+    fn node(tryte: char) -> String {
+        format!("\"{}\" [", tryte.to_string().repeat(81))
+    }
+
+    fn styled(tryte: char, shape: &str, style: &str, color: &str) -> String {
+        format!(
+            "{}label=\"{}\", shape={}, style={}, fillcolor={}];",
+            node(tryte),
+            tryte.to_string().repeat(DOT_LABEL_TRYTES),
+            shape,
+            style,
+            color
+        )
+    }
+
+    fn edge(from: char, to: char, style: &str) -> String {
+        format!(
+            "\"{}\" -> \"{}\" [style={}];",
+            from.to_string().repeat(81),
+            to.to_string().repeat(81),
+            style
+        )
+    }
+
+    // Solid entry point `S`, then a chain `A` <- `B` <- `C` <- `D` where each transaction approves the previous one
+    // through its trunk and `S` through its branch, `C` also being approved by `E`.
+    async fn tangle() -> MsTangle<MockStorage> {
+        let tangle = MsTangle::new(ResHandle::new(MockStorage::default()));
+
+        tangle.add_solid_entry_point(hash('S'), MilestoneIndex::from(1));
+
+        tangle
+            .insert(
+                transaction(hash('S'), hash('S')),
+                hash('A'),
+                metadata(Flags::SOLID | Flags::CONFIRMED),
+            )
+            .await;
+        tangle
+            .insert(
+                transaction(hash('A'), hash('S')),
+                hash('B'),
+                metadata(Flags::SOLID | Flags::MILESTONE),
+            )
+            .await;
+        tangle
+            .insert(transaction(hash('B'), hash('S')), hash('C'), metadata(Flags::SOLID))
+            .await;
+        tangle
+            .insert(transaction(hash('C'), hash('S')), hash('D'), metadata(Flags::empty()))
+            .await;
+        tangle
+            .insert(transaction(hash('C'), hash('X')), hash('E'), metadata(Flags::empty()))
+            .await;
+
+        tangle
+    }
+
+    #[tokio::test]
+    async fn dot_contains_nodes_and_edges() {
+        let tangle = tangle().await;
+
+        let dot = tangle.export_subgraph(&hash('C'), 1, 1, ExportFormat::Dot).await;
+
+        for tryte in &['B', 'C', 'D', 'E', 'S'] {
+            assert!(dot.contains(&node(*tryte)), "missing node {}", tryte);
+        }
+        assert!(dot.contains(&styled('C', "box", "filled", "lightblue")));
+        assert!(dot.contains(&styled('B', "ellipse", "filled", "gold")));
+        assert!(dot.contains(&styled('S', "ellipse", "filled", "gray")));
+        assert!(dot.contains(&styled('D', "ellipse", "filled", "white")));
+
+        assert!(dot.contains(&edge('C', 'B', "solid")));
+        assert!(dot.contains(&edge('C', 'S', "dashed")));
+        assert!(dot.contains(&edge('B', 'S', "dashed")));
+        assert!(dot.contains(&edge('D', 'C', "solid")));
+        assert!(dot.contains(&edge('E', 'C', "solid")));
+        // `B` and `E` are at the depth limit, their other parents are not exported.
+        assert!(!dot.contains(&node('A')));
+        assert!(!dot.contains(&node('X')));
+        assert!(!dot.contains("truncated"));
+    }
+
+    #[tokio::test]
+    async fn dot_missing_parent() {
+        let tangle = tangle().await;
+
+        let dot = tangle.export_subgraph(&hash('E'), 1, 0, ExportFormat::Dot).await;
+
+        assert!(dot.contains(&styled('X', "ellipse", "dashed", "white")));
+        assert!(dot.contains(&edge('E', 'X', "dashed")));
+    }
+
+    #[tokio::test]
+    async fn dot_respects_depth_limits() {
+        let tangle = tangle().await;
+
+        let dot = tangle.export_subgraph(&hash('B'), 1, 0, ExportFormat::Dot).await;
+
+        for tryte in &['A', 'B', 'S'] {
+            assert!(dot.contains(&node(*tryte)), "missing node {}", tryte);
+        }
+        for tryte in &['C', 'D', 'E'] {
+            assert!(!dot.contains(&node(*tryte)), "unexpected node {}", tryte);
+        }
+
+        let dot = tangle.export_subgraph(&hash('A'), 0, 2, ExportFormat::Dot).await;
+
+        for tryte in &['A', 'B', 'C'] {
+            assert!(dot.contains(&node(*tryte)), "missing node {}", tryte);
+        }
+        for tryte in &['D', 'E', 'S'] {
+            assert!(!dot.contains(&node(*tryte)), "unexpected node {}", tryte);
+        }
+        assert!(dot.contains(&edge('B', 'A', "solid")));
+        assert!(dot.contains(&edge('C', 'B', "solid")));
+    }
+
+    #[tokio::test]
+    async fn json_adjacency() {
+        let tangle = tangle().await;
+
+        let json = tangle.export_subgraph(&hash('A'), 1, 0, ExportFormat::Json).await;
+
+        assert_eq!(
+            json,
+            format!(
+                "{{\"root\":\"{a}\",\"truncated\":false,\"nodes\":[\
+                 {{\"hash\":\"{a}\",\"missing\":false,\"solid_entry_point\":false,\"solid\":true,\"confirmed\":true,\
+                 \"milestone\":false,\"trunk\":\"{s}\",\"branch\":\"{s}\"}},\
+                 {{\"hash\":\"{s}\",\"missing\":true,\"solid_entry_point\":true,\"solid\":false,\"confirmed\":false,\
+                 \"milestone\":false,\"trunk\":null,\"branch\":null}}]}}",
+                a = "A".repeat(81),
+                s = "S".repeat(81)
+            )
+        );
+    }
+}
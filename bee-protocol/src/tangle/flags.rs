@@ -9,6 +9,8 @@
 // an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and limitations under the License.
 
+use bee_storage::persistable::{DecodeError, Persistable};
+
 use bitflags::bitflags;
 
 bitflags! {
@@ -81,3 +83,13 @@ impl Flags {
         self.set(Flags::VALID, is_valid);
     }
 }
+
+impl Persistable for Flags {
+    fn encode_persistable(&self, buffer: &mut Vec<u8>) {
+        self.bits().encode_persistable(buffer)
+    }
+    fn decode_persistable_prefix(slice: &[u8]) -> Result<(Self, usize), DecodeError> {
+        let (bits, length) = u8::decode_persistable_prefix(slice)?;
+        Ok((Flags::from_bits(bits).ok_or(DecodeError::InvalidValue)?, length))
+    }
+}
@@ -11,13 +11,19 @@
 
 use crate::tangle::TransactionMetadata;
 
-use bee_tangle::{traversal, Hooks, Tangle, TransactionRef};
+use bee_tangle::{
+    traversal::{self, Cancelled},
+    Hooks, Tangle, TransactionRef,
+};
 
 use bee_crypto::ternary::Hash;
 
+use std::sync::atomic::AtomicBool;
+
 pub(crate) fn find_tail_of_bundle<H: Hooks<TransactionMetadata>>(
     tangle: &Tangle<TransactionMetadata, H>,
     root: Hash,
+    cancel: &AtomicBool,
 ) -> Option<Hash> {
     let mut tail = None;
     let mut bundle = None;
@@ -37,7 +43,9 @@ pub(crate) fn find_tail_of_bundle<H: Hooks<TransactionMetadata>>(
                 tail.replace(*hash);
             }
         },
-    );
+        cancel,
+    )
+    .ok()?;
 
     tail
 }
@@ -46,7 +54,8 @@ pub fn on_all_tails<Apply: FnMut(&Hash, &TransactionRef, &TransactionMetadata),
     tangle: &Tangle<TransactionMetadata, H>,
     root: Hash,
     apply: Apply,
-) {
+    cancel: &AtomicBool,
+) -> Result<(), Cancelled> {
     traversal::visit_parents_depth_first(
         tangle,
         root,
@@ -54,5 +63,6 @@ pub fn on_all_tails<Apply: FnMut(&Hash, &TransactionRef, &TransactionMetadata),
         |_, _, _| {},
         apply,
         |_| {},
-    );
+        cancel,
+    )
 }
@@ -11,13 +11,33 @@
 
 use crate::{milestone::MilestoneIndex, tangle::Flags};
 
-use std::time::{SystemTime, UNIX_EPOCH};
+use bee_storage::persistable::{DecodeError, Persistable};
+
+use std::{
+    mem::size_of,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Size in bytes of the metadata kept in memory for every vertex of the tangle.
+///
+/// The fields add up to 29 bytes, rounded up to the alignment of the timestamps. Growing the metadata costs that much
+/// for each of the millions of vertices held during synchronization, so this is checked at compile time.
+const TRANSACTION_METADATA_SIZE: usize = 32;
+
+const _: () = assert!(size_of::<TransactionMetadata>() == TRANSACTION_METADATA_SIZE);
+
+// Version of the persisted encoding, written as its first byte so that fields can be added later on.
+const TRANSACTION_METADATA_VERSION: u8 = 1;
 
 // TODO Should it really be copy ?
 #[derive(Copy, Clone, Default)]
 pub struct TransactionMetadata {
+    // All the boolean properties, packed in a single byte.
     flags: Flags,
+    // Index of the milestone confirming the transaction, or of the milestone itself for a milestone transaction; 0 when
+    // it is not known yet.
     milestone_index: MilestoneIndex,
+    // Timestamps are in milliseconds since the Unix epoch, 0 meaning that the event did not happen yet.
     arrival_timestamp: u64,
     solidification_timestamp: u64,
     confirmation_timestamp: u64,
@@ -94,11 +114,77 @@ impl TransactionMetadata {
             .as_millis() as u64;
     }
 
-    pub fn confirm(&mut self) {
+    /// Confirms the transaction with the timestamp, in milliseconds, of the confirming milestone.
+    pub fn confirm(&mut self, timestamp: u64) {
         self.flags.set_confirmed(true);
-        self.confirmation_timestamp = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .expect("Clock may have gone backwards")
-            .as_millis() as u64;
+        self.confirmation_timestamp = timestamp;
+    }
+}
+
+impl Persistable for TransactionMetadata {
+    fn encode_persistable(&self, buffer: &mut Vec<u8>) {
+        TRANSACTION_METADATA_VERSION.encode_persistable(buffer);
+        self.flags.encode_persistable(buffer);
+        self.milestone_index.encode_persistable(buffer);
+        self.arrival_timestamp.encode_persistable(buffer);
+        self.solidification_timestamp.encode_persistable(buffer);
+        self.confirmation_timestamp.encode_persistable(buffer);
+    }
+
+    fn decode_persistable_prefix(slice: &[u8]) -> Result<(Self, usize), DecodeError> {
+        let (version, mut position) = u8::decode_persistable_prefix(slice)?;
+
+        if version != TRANSACTION_METADATA_VERSION {
+            return Err(DecodeError::UnsupportedVersion(version));
+        }
+
+        let (flags, length) = Flags::decode_persistable_prefix(&slice[position..])?;
+        position += length;
+        let (milestone_index, length) = MilestoneIndex::decode_persistable_prefix(&slice[position..])?;
+        position += length;
+        let (arrival_timestamp, length) = u64::decode_persistable_prefix(&slice[position..])?;
+        position += length;
+        let (solidification_timestamp, length) = u64::decode_persistable_prefix(&slice[position..])?;
+        position += length;
+        let (confirmation_timestamp, length) = u64::decode_persistable_prefix(&slice[position..])?;
+        position += length;
+
+        Ok((
+            Self::new(
+                flags,
+                milestone_index,
+                arrival_timestamp,
+                solidification_timestamp,
+                confirmation_timestamp,
+            ),
+            position,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn flags_fit_in_a_byte() {
+        assert_eq!(size_of::<Flags>(), 1);
+        assert_eq!(size_of::<MilestoneIndex>(), 4);
+    }
+
+    #[test]
+    fn large_allocation() {
+        const COUNT: usize = 1_000_000;
+
+        let metadata = vec![TransactionMetadata::arrived(); COUNT];
+
+        assert_eq!(metadata.len(), COUNT);
+        // The allocation is exactly one slot per vertex, without any hidden per-element overhead.
+        assert_eq!(
+            metadata.capacity() * size_of::<TransactionMetadata>(),
+            COUNT * TRANSACTION_METADATA_SIZE
+        );
+        assert!(metadata.iter().all(|metadata| *metadata.milestone_index() == 0));
     }
 }
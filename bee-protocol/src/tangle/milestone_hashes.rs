@@ -0,0 +1,74 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+//! Cache of the hashes of the most recent milestones.
+
+use crate::milestone::MilestoneIndex;
+
+use bee_crypto::ternary::Hash;
+
+/// Ring buffer of the hashes of the most recent milestones.
+///
+/// Every index has a fixed slot, so that a milestone evicts the one `capacity` indexes below it. A capacity of 0 caches
+/// nothing.
+pub(crate) struct MilestoneHashRing {
+    slots: Vec<Option<(MilestoneIndex, Hash)>>,
+}
+
+impl MilestoneHashRing {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            slots: vec![None; capacity],
+        }
+    }
+
+    fn slot(&self, index: MilestoneIndex) -> Option<usize> {
+        if self.slots.is_empty() {
+            None
+        } else {
+            Some(*index as usize % self.slots.len())
+        }
+    }
+
+    pub(crate) fn get(&self, index: MilestoneIndex) -> Option<Hash> {
+        match self.slots[self.slot(index)?] {
+            Some((cached, hash)) if cached == index => Some(hash),
+            _ => None,
+        }
+    }
+
+    /// Caches the hash of the milestone at `index`, unless a more recent milestone holds its slot.
+    pub(crate) fn insert(&mut self, index: MilestoneIndex, hash: Hash) {
+        if let Some(slot) = self.slot(index) {
+            match self.slots[slot] {
+                Some((cached, _)) if cached > index => (),
+                _ => self.slots[slot] = Some((index, hash)),
+            }
+        }
+    }
+
+    pub(crate) fn remove(&mut self, index: MilestoneIndex) {
+        if let Some(slot) = self.slot(index) {
+            if matches!(self.slots[slot], Some((cached, _)) if cached == index) {
+                self.slots[slot] = None;
+            }
+        }
+    }
+
+    /// Drops the hashes of the milestones below `index`.
+    pub(crate) fn prune(&mut self, index: MilestoneIndex) {
+        for slot in self.slots.iter_mut() {
+            if matches!(slot, Some((cached, _)) if *cached < index) {
+                *slot = None;
+            }
+        }
+    }
+}
@@ -12,24 +12,41 @@
 pub mod flags;
 pub mod helper;
 
+mod export;
 mod metadata;
+mod milestone_hashes;
+mod revalidation;
+mod solid_entry_points;
 
+pub use export::ExportFormat;
 pub use metadata::TransactionMetadata;
+pub use revalidation::{revalidate, RunningMarker, RunningMarkerStorage};
+pub use solid_entry_points::{SolidEntryPoints, SolidEntryPointsStorage};
 
-use crate::{milestone::MilestoneIndex, tangle::flags::Flags};
+use crate::{
+    config::DEFAULT_MS_HASH_CACHE,
+    milestone::MilestoneIndex,
+    tangle::{flags::Flags, milestone_hashes::MilestoneHashRing},
+};
 
 use bee_common_ext::node::ResHandle;
 use bee_crypto::ternary::Hash;
-use bee_storage::storage::Backend;
-use bee_tangle::{Hooks, Tangle, TransactionRef as TxRef};
-use bee_transaction::bundled::BundledTransaction as Tx;
+use bee_storage::{
+    access::{Fetch, Insert},
+    storage::Backend,
+};
+use bee_tangle::{Hooks, InsertionOutcome, Tangle, TransactionRef as TxRef};
+use bee_transaction::bundled::{BundledTransaction as Tx, BundledTransactionField};
 
 use async_trait::async_trait;
 use dashmap::DashMap;
+use spin::RwLock;
 
 use std::{
+    collections::HashMap,
     ops::Deref,
-    sync::atomic::{AtomicU32, Ordering},
+    sync::atomic::{AtomicU32, AtomicU64, Ordering},
+    time::Duration,
 };
 
 pub struct StorageHooks<B> {
@@ -50,13 +67,23 @@ impl<B: Backend> Hooks<TransactionMetadata> for StorageHooks<B> {
         // println!("Attempted to insert {:?} into storage", hash);
         Ok(())
     }
+
+    async fn update(&self, _hash: &Hash, _metadata: TransactionMetadata) -> Result<(), Self::Error> {
+        // Transactions are not persisted yet, there is no stored metadata to update.
+        Ok(())
+    }
 }
 
 /// Milestone-based Tangle.
 pub struct MsTangle<B> {
     pub(crate) inner: Tangle<TransactionMetadata, StorageHooks<B>>,
     pub(crate) milestones: DashMap<MilestoneIndex, Hash>,
+    // Hashes of the most recent milestones, looked up before `milestones`.
+    recent_milestones: RwLock<MilestoneHashRing>,
+    // Number of milestone hash lookups that missed `recent_milestones`.
+    recent_milestones_misses: AtomicU64,
     pub(crate) solid_entry_points: DashMap<Hash, MilestoneIndex>,
+    storage: ResHandle<B>,
     latest_milestone_index: AtomicU32,
     latest_solid_milestone_index: AtomicU32,
     snapshot_index: AtomicU32,
@@ -74,10 +101,20 @@ impl<B> Deref for MsTangle<B> {
 
 impl<B: Backend> MsTangle<B> {
     pub fn new(storage: ResHandle<B>) -> Self {
+        Self::with_milestone_hash_cache(storage, DEFAULT_MS_HASH_CACHE)
+    }
+
+    /// Creates a tangle caching the hashes of the `capacity` most recent milestones, 0 disabling the cache.
+    pub fn with_milestone_hash_cache(storage: ResHandle<B>, capacity: usize) -> Self {
         Self {
-            inner: Tangle::new(StorageHooks { storage }),
+            inner: Tangle::new(StorageHooks {
+                storage: storage.clone(),
+            }),
             milestones: Default::default(),
+            recent_milestones: RwLock::new(MilestoneHashRing::new(capacity)),
+            recent_milestones_misses: AtomicU64::new(0),
             solid_entry_points: Default::default(),
+            storage,
             latest_milestone_index: Default::default(),
             latest_solid_milestone_index: Default::default(),
             snapshot_index: Default::default(),
@@ -90,7 +127,7 @@ impl<B: Backend> MsTangle<B> {
         // TODO: Write back changes by calling self.inner.shutdown().await
     }
 
-    pub async fn insert(&self, transaction: Tx, hash: Hash, metadata: TransactionMetadata) -> Option<TxRef> {
+    pub async fn insert(&self, transaction: Tx, hash: Hash, metadata: TransactionMetadata) -> InsertionOutcome {
         // TODO this has been temporarily moved to the processor.
         // Reason is that since the tangle is not a worker, it can't have access to the propagator tx.
         // When the tangle is made a worker, this should be put back on.
@@ -107,9 +144,26 @@ impl<B: Backend> MsTangle<B> {
         self.inner.insert(hash, transaction, metadata).await
     }
 
+    pub async fn insert_or_update<Update>(
+        &self,
+        transaction: Tx,
+        hash: Hash,
+        metadata: TransactionMetadata,
+        update: Update,
+    ) -> InsertionOutcome
+    where
+        Update: FnOnce(&mut TransactionMetadata) -> bool,
+    {
+        self.inner.insert_or_update(hash, transaction, metadata, update).await
+    }
+
     pub fn add_milestone(&self, index: MilestoneIndex, hash: Hash) {
         // TODO: only insert if vacant
+        // The ring lock is held over both updates so that concurrent additions and removals leave them in agreement.
+        let mut recent_milestones = self.recent_milestones.write();
         self.milestones.insert(index, hash);
+        recent_milestones.insert(index, hash);
+        drop(recent_milestones);
         self.inner.update_metadata(&hash, |metadata| {
             metadata.flags_mut().set_milestone(true);
             metadata.set_milestone_index(index);
@@ -117,7 +171,9 @@ impl<B: Backend> MsTangle<B> {
     }
 
     pub fn remove_milestone(&self, index: MilestoneIndex) {
+        let mut recent_milestones = self.recent_milestones.write();
         self.milestones.remove(&index);
+        recent_milestones.remove(index);
     }
 
     // TODO: use combinator instead of match
@@ -130,6 +186,12 @@ impl<B: Backend> MsTangle<B> {
 
     // TODO: use combinator instead of match
     pub fn get_milestone_hash(&self, index: MilestoneIndex) -> Option<Hash> {
+        if let Some(hash) = self.recent_milestones.read().get(index) {
+            return Some(hash);
+        }
+
+        self.recent_milestones_misses.fetch_add(1, Ordering::Relaxed);
+
         match self.milestones.get(&index) {
             None => None,
             Some(v) => Some(*v),
@@ -148,6 +210,22 @@ impl<B: Backend> MsTangle<B> {
         self.latest_milestone_index.store(*new_index, Ordering::Relaxed);
     }
 
+    /// Makes `index` the latest milestone index if it is above the current one and not below the snapshot index,
+    /// returning the previous latest milestone index if it did so.
+    pub fn advance_latest_milestone_index(&self, index: MilestoneIndex) -> Option<MilestoneIndex> {
+        if index < self.get_snapshot_index() {
+            return None;
+        }
+
+        let old = self.latest_milestone_index.fetch_max(*index, Ordering::SeqCst);
+
+        if old < *index {
+            Some(old.into())
+        } else {
+            None
+        }
+    }
+
     pub fn get_latest_solid_milestone_index(&self) -> MilestoneIndex {
         self.latest_solid_milestone_index.load(Ordering::Relaxed).into()
     }
@@ -170,6 +248,7 @@ impl<B: Backend> MsTangle<B> {
 
     pub fn update_pruning_index(&self, new_index: MilestoneIndex) {
         self.pruning_index.store(*new_index, Ordering::Relaxed);
+        self.recent_milestones.write().prune(new_index);
     }
 
     pub fn get_entry_point_index(&self) -> MilestoneIndex {
@@ -207,6 +286,99 @@ impl<B: Backend> MsTangle<B> {
         self.solid_entry_points.clear();
     }
 
+    /// Returns a snapshot of the current solid entry points and their milestone indexes.
+    pub fn solid_entry_points(&self) -> Vec<(Hash, MilestoneIndex)> {
+        self.solid_entry_points
+            .iter()
+            .map(|entry| (*entry.key(), *entry.value()))
+            .collect()
+    }
+
+    /// Replaces the solid entry points with `points` and sets the entry point index.
+    ///
+    /// The new set is persisted, together with the entry point index, before being applied in memory so that a crash
+    /// right after pruning doesn't bring the node back with the solid entry points of the snapshot file.
+    pub async fn replace_solid_entry_points(
+        &self,
+        points: HashMap<Hash, MilestoneIndex>,
+        entry_point_index: MilestoneIndex,
+    ) -> Result<(), <B as Insert<(), SolidEntryPoints>>::Error>
+    where
+        B: SolidEntryPointsStorage,
+    {
+        let solid_entry_points = SolidEntryPoints::new(entry_point_index, points);
+
+        Insert::<(), SolidEntryPoints>::insert(&*self.storage, &(), &solid_entry_points).await?;
+
+        self.clear_solid_entry_points();
+        for (hash, index) in solid_entry_points.into_points() {
+            self.add_solid_entry_point(hash, index);
+        }
+        self.update_entry_point_index(entry_point_index);
+        self.clean_orphan_links();
+
+        Ok(())
+    }
+
+    /// Drops the links of children to unknown parents that were made before the milestone at the entry point index
+    /// arrived. Such parents are below the solid entry points and will never be needed. Returns the number of parents
+    /// cleaned.
+    pub fn clean_orphan_links(&self) -> usize {
+        let horizon = match self
+            .get_milestone_hash(self.get_entry_point_index())
+            .and_then(|hash| self.inner.get_metadata(&hash))
+        {
+            Some(metadata) => metadata.arrival_timestamp(),
+            None => return 0,
+        };
+
+        self.inner
+            .clean_orphan_links(|parent, created| created < horizon && !self.is_solid_entry_point(parent))
+    }
+
+    /// Loads the persisted solid entry points if their entry point index is newer than the current one, in which case
+    /// they replace the solid entry points in memory. Returns whether the persisted set was used.
+    pub async fn load_solid_entry_points(&self) -> Result<bool, <B as Fetch<(), SolidEntryPoints>>::Error>
+    where
+        B: SolidEntryPointsStorage,
+    {
+        match Fetch::<(), SolidEntryPoints>::fetch(&*self.storage, &()).await? {
+            Some(solid_entry_points) if solid_entry_points.entry_point_index() > self.get_entry_point_index() => {
+                let entry_point_index = solid_entry_points.entry_point_index();
+
+                self.clear_solid_entry_points();
+                for (hash, index) in solid_entry_points.into_points() {
+                    self.add_solid_entry_point(hash, index);
+                }
+                self.update_entry_point_index(entry_point_index);
+
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    /// Returns the time between the attachment of the transaction associated with `hash` and the issuance of the
+    /// milestone that confirmed it.
+    pub async fn get_confirmation_time(&self, hash: &Hash) -> Option<Duration> {
+        let metadata = self.get_metadata(hash)?;
+
+        if !metadata.flags().is_confirmed() {
+            return None;
+        }
+
+        let attachment_timestamp = *self.get(hash).await?.attachment_ts().to_inner();
+
+        if attachment_timestamp == 0 {
+            return None;
+        }
+
+        metadata
+            .confirmation_timestamp()
+            .checked_sub(attachment_timestamp)
+            .map(Duration::from_millis)
+    }
+
     /// Returns whether the transaction associated with `hash` is a solid entry point.
     pub fn is_solid_entry_point(&self, hash: &Hash) -> bool {
         self.solid_entry_points.contains_key(hash)
@@ -225,6 +397,275 @@ impl<B: Backend> MsTangle<B> {
     }
 }
 
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use crate::test_utils::{hash, indexed_hash, MockStorage};
+
+    use bee_transaction::bundled::{Address, BundledTransactionBuilder, Index, Nonce, Payload, Tag, Timestamp, Value};
+
+    use std::{
+        sync::Arc,
+        thread,
+        time::{SystemTime, UNIX_EPOCH},
+    };
+
+    fn attached_transaction(attachment_timestamp: u64) -> Tx {
+        BundledTransactionBuilder::new()
+            .with_payload(Payload::zeros())
+            .with_address(Address::zeros())
+            .with_value(Value::from_inner_unchecked(0))
+            .with_obsolete_tag(Tag::zeros())
+            .with_timestamp(Timestamp::from_inner_unchecked(attachment_timestamp / 1000))
+            .with_index(Index::from_inner_unchecked(0))
+            .with_last_index(Index::from_inner_unchecked(0))
+            .with_tag(Tag::zeros())
+            .with_attachment_ts(Timestamp::from_inner_unchecked(attachment_timestamp))
+            .with_bundle(Hash::zeros())
+            .with_trunk(Hash::zeros())
+            .with_branch(Hash::zeros())
+            .with_attachment_lbts(Timestamp::from_inner_unchecked(0))
+            .with_attachment_ubts(Timestamp::from_inner_unchecked(0))
+            .with_nonce(Nonce::zeros())
+            .build()
+            .unwrap()
+    }
+
+    fn now_millis() -> u64 {
+        SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64
+    }
+
+    // Mimics the tangle worker: solid entry points of the snapshot file first, then the persisted ones if newer.
+    async fn start(storage: &MockStorage, snapshot_sep: Hash) -> MsTangle<MockStorage> {
+        let tangle = MsTangle::new(ResHandle::new(storage.clone()));
+        tangle.add_solid_entry_point(snapshot_sep, MilestoneIndex::from(1));
+        tangle.update_entry_point_index(MilestoneIndex::from(1));
+        tangle.load_solid_entry_points().await.unwrap();
+        tangle
+    }
+
+    #[tokio::test]
+    async fn solid_entry_points_recovered_after_crash() {
+        let storage = MockStorage::default();
+        let snapshot_sep = hash(&"A".repeat(81));
+        let pruned_sep_1 = hash(&"B".repeat(81));
+        let pruned_sep_2 = hash(&"C".repeat(81));
+
+        let tangle = start(&storage, snapshot_sep).await;
+        assert!(tangle.is_solid_entry_point(&snapshot_sep));
+
+        let mut points = HashMap::new();
+        points.insert(pruned_sep_1, MilestoneIndex::from(9));
+        points.insert(pruned_sep_2, MilestoneIndex::from(10));
+        tangle
+            .replace_solid_entry_points(points, MilestoneIndex::from(10))
+            .await
+            .unwrap();
+
+        // Crash right after pruning, without any shutdown.
+        drop(tangle);
+
+        let tangle = start(&storage, snapshot_sep).await;
+        let mut solid_entry_points = tangle.solid_entry_points();
+        solid_entry_points.sort_by_key(|(_, index)| *index);
+
+        assert!(!tangle.is_solid_entry_point(&snapshot_sep));
+        assert_eq!(
+            solid_entry_points,
+            vec![
+                (pruned_sep_1, MilestoneIndex::from(9)),
+                (pruned_sep_2, MilestoneIndex::from(10))
+            ]
+        );
+        assert_eq!(tangle.get_entry_point_index(), MilestoneIndex::from(10));
+    }
+
+    #[tokio::test]
+    async fn confirmation_time() {
+        let tangle = MsTangle::new(ResHandle::new(MockStorage::default()));
+        let tx_hash = hash(&"A".repeat(81));
+        let milestone_hash = hash(&"B".repeat(81));
+        let attachment_timestamp = now_millis() - 12_000;
+
+        tangle
+            .insert(
+                attached_transaction(attachment_timestamp),
+                tx_hash,
+                TransactionMetadata::arrived(),
+            )
+            .await;
+        assert_eq!(tangle.get_confirmation_time(&tx_hash).await, None);
+
+        // The milestone is issued 10 seconds after the attachment and confirms the transaction, like white flag does.
+        let milestone = attached_transaction(attachment_timestamp + 10_000);
+        let milestone_timestamp = milestone.get_timestamp();
+        tangle
+            .insert(milestone, milestone_hash, TransactionMetadata::arrived())
+            .await;
+        tangle.add_milestone(MilestoneIndex::from(1), milestone_hash);
+        tangle.update_metadata(&tx_hash, |metadata| {
+            metadata.confirm(milestone_timestamp * 1000);
+            metadata.set_milestone_index(MilestoneIndex::from(1));
+        });
+
+        let confirmation_time = tangle.get_confirmation_time(&tx_hash).await.unwrap();
+        assert!(confirmation_time >= Duration::from_secs(9));
+        assert!(confirmation_time <= Duration::from_secs(11));
+    }
+
+    #[tokio::test]
+    async fn confirmation_time_unknown_transaction() {
+        let tangle = MsTangle::new(ResHandle::new(MockStorage::default()));
+
+        assert_eq!(tangle.get_confirmation_time(&hash(&"A".repeat(81))).await, None);
+    }
+
+    #[tokio::test]
+    async fn older_persisted_solid_entry_points_ignored() {
+        let storage = MockStorage::default();
+        let persisted_sep = hash(&"B".repeat(81));
+        let snapshot_sep = hash(&"A".repeat(81));
+
+        Insert::<(), SolidEntryPoints>::insert(
+            &storage,
+            &(),
+            &SolidEntryPoints::new(
+                MilestoneIndex::from(0),
+                vec![(persisted_sep, MilestoneIndex::from(0))].into_iter().collect(),
+            ),
+        )
+        .await
+        .unwrap();
+
+        let tangle = start(&storage, snapshot_sep).await;
+
+        assert!(tangle.is_solid_entry_point(&snapshot_sep));
+        assert!(!tangle.is_solid_entry_point(&persisted_sep));
+        assert_eq!(tangle.get_entry_point_index(), MilestoneIndex::from(1));
+    }
+
+    fn milestone_hash(index: u32) -> Hash {
+        indexed_hash(index as usize)
+    }
+
+    fn misses(tangle: &MsTangle<MockStorage>) -> u64 {
+        tangle.recent_milestones_misses.load(Ordering::Relaxed)
+    }
+
+    #[test]
+    fn recent_milestone_hashes_avoid_map() {
+        let tangle = MsTangle::with_milestone_hash_cache(ResHandle::new(MockStorage::default()), 10);
+
+        for index in 1..=10 {
+            tangle.add_milestone(MilestoneIndex(index), milestone_hash(index));
+        }
+        for index in 1..=10 {
+            assert_eq!(
+                tangle.get_milestone_hash(MilestoneIndex(index)),
+                Some(milestone_hash(index))
+            );
+        }
+
+        assert_eq!(misses(&tangle), 0);
+    }
+
+    #[test]
+    fn old_milestone_hashes_fall_back_to_map() {
+        let tangle = MsTangle::with_milestone_hash_cache(ResHandle::new(MockStorage::default()), 10);
+
+        for index in 1..=25 {
+            tangle.add_milestone(MilestoneIndex(index), milestone_hash(index));
+        }
+
+        for index in 16..=25 {
+            assert_eq!(
+                tangle.get_milestone_hash(MilestoneIndex(index)),
+                Some(milestone_hash(index))
+            );
+        }
+        assert_eq!(misses(&tangle), 0);
+
+        for index in 1..=15 {
+            assert_eq!(
+                tangle.get_milestone_hash(MilestoneIndex(index)),
+                Some(milestone_hash(index))
+            );
+        }
+        assert_eq!(misses(&tangle), 15);
+
+        // Re-adding an evicted milestone doesn't evict a more recent one.
+        tangle.add_milestone(MilestoneIndex(5), milestone_hash(5));
+        assert_eq!(tangle.get_milestone_hash(MilestoneIndex(5)), Some(milestone_hash(5)));
+        assert_eq!(misses(&tangle), 16);
+        assert_eq!(tangle.get_milestone_hash(MilestoneIndex(15)), Some(milestone_hash(15)));
+        assert_eq!(misses(&tangle), 16);
+    }
+
+    #[test]
+    fn removed_and_pruned_milestone_hashes_not_cached() {
+        let tangle = MsTangle::with_milestone_hash_cache(ResHandle::new(MockStorage::default()), 10);
+
+        for index in 1..=10 {
+            tangle.add_milestone(MilestoneIndex(index), milestone_hash(index));
+        }
+
+        tangle.remove_milestone(MilestoneIndex(10));
+        assert_eq!(tangle.get_milestone_hash(MilestoneIndex(10)), None);
+
+        tangle.update_pruning_index(MilestoneIndex(5));
+        assert_eq!(misses(&tangle), 1);
+        assert_eq!(tangle.get_milestone_hash(MilestoneIndex(4)), Some(milestone_hash(4)));
+        assert_eq!(misses(&tangle), 2);
+        assert_eq!(tangle.get_milestone_hash(MilestoneIndex(5)), Some(milestone_hash(5)));
+        assert_eq!(misses(&tangle), 2);
+    }
+
+    #[test]
+    fn disabled_milestone_hash_cache() {
+        let tangle = MsTangle::with_milestone_hash_cache(ResHandle::new(MockStorage::default()), 0);
+
+        tangle.add_milestone(MilestoneIndex(1), milestone_hash(1));
+
+        assert_eq!(tangle.get_milestone_hash(MilestoneIndex(1)), Some(milestone_hash(1)));
+        assert_eq!(misses(&tangle), 1);
+    }
+
+    #[test]
+    fn concurrent_milestone_updates_agree() {
+        let tangle = Arc::new(MsTangle::with_milestone_hash_cache(
+            ResHandle::new(MockStorage::default()),
+            10,
+        ));
+
+        let threads = (0..4)
+            .map(|i| {
+                let tangle = tangle.clone();
+                thread::spawn(move || {
+                    for _ in 0..1000 {
+                        if i % 2 == 0 {
+                            tangle.add_milestone(MilestoneIndex(1), milestone_hash(1));
+                        } else {
+                            tangle.remove_milestone(MilestoneIndex(1));
+                        }
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        // The hash is found through the ring if and only if it is in the map.
+        assert_eq!(
+            tangle.recent_milestones.read().get(MilestoneIndex(1)).is_some(),
+            tangle.contains_milestone(MilestoneIndex(1))
+        );
+    }
+}
+
 // #[cfg(test)]
 // mod tests {
 //     use super::*;
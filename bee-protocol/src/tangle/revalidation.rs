@@ -0,0 +1,379 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use crate::{event::RevalidationCompleted, milestone::MilestoneIndex, tangle::MsTangle};
+
+use bee_crypto::ternary::Hash;
+use bee_storage::{
+    access::{Delete, Fetch, Insert},
+    persistable::Persistable,
+    storage::Backend,
+};
+use bee_transaction::Vertex;
+
+use tokio::task::yield_now;
+
+use std::{
+    collections::HashSet,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+// Number of vertices visited between two checks for interruption, during which the walk doesn't yield.
+const REVALIDATION_BATCH_SIZE: usize = 1000;
+
+/// Marker written when the node starts and removed when it shuts down cleanly.
+///
+/// Finding it at startup means that the previous run crashed and that the persisted state may be inconsistent.
+#[derive(Clone, Copy, Debug, PartialEq, Persistable)]
+pub struct RunningMarker {
+    // In seconds since the Unix epoch.
+    started_at: u64,
+}
+
+impl RunningMarker {
+    pub fn now() -> Self {
+        Self {
+            started_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .expect("Clock may have gone backwards")
+                .as_secs(),
+        }
+    }
+
+    /// Returns the time the run that wrote the marker started at, in seconds since the Unix epoch.
+    pub fn started_at(&self) -> u64 {
+        self.started_at
+    }
+}
+
+/// Storage accesses required to detect a run that did not shut down cleanly.
+///
+/// There is a single marker, it is stored under the `()` key.
+pub trait RunningMarkerStorage:
+    Backend + Insert<(), RunningMarker> + Fetch<(), RunningMarker> + Delete<(), RunningMarker>
+{
+}
+
+impl<B> RunningMarkerStorage for B where
+    B: Backend + Insert<(), RunningMarker> + Fetch<(), RunningMarker> + Delete<(), RunningMarker>
+{
+}
+
+/// Checks the past cones of the `depth` milestones up to the latest solid one and repairs what can be repaired.
+///
+/// A milestone whose transaction is missing is forgotten so that it gets requested again. Transactions whose ancestry
+/// is missing lose their solid and confirmed flags so that they go through solidification again, and so do confirmed
+/// transactions that are not solid. At most `max_cone_size` transactions are visited per milestone, 0 meaning
+/// unlimited.
+///
+/// `interrupted` is polled regularly; once it returns true the walk stops and only what was found so far is repaired.
+pub async fn revalidate<B, I>(
+    tangle: &MsTangle<B>,
+    depth: u32,
+    max_cone_size: usize,
+    mut interrupted: I,
+) -> RevalidationCompleted
+where
+    B: Backend,
+    I: FnMut() -> bool,
+{
+    let mut summary = RevalidationCompleted::default();
+    let latest = *tangle.get_latest_solid_milestone_index();
+    // Nothing below the entry point index is kept, the solid entry points stand for it.
+    let lowest = latest
+        .saturating_sub(depth.saturating_sub(1))
+        .max(*tangle.get_entry_point_index() + 1);
+    let mut visited = HashSet::new();
+    let mut missing = Vec::new();
+    let mut repaired = HashSet::new();
+
+    'milestones: for index in (lowest..=latest).rev() {
+        if interrupted() {
+            summary.interrupted = true;
+            break;
+        }
+
+        let index = MilestoneIndex::from(index);
+        let hash = match tangle.get_milestone_hash(index) {
+            Some(hash) => hash,
+            None => continue,
+        };
+
+        summary.milestones += 1;
+
+        if !tangle.contains(&hash).await {
+            tangle.remove_milestone(index);
+            summary.missing_milestones += 1;
+            continue;
+        }
+
+        let mut cone_size = 0;
+        let mut to_visit = vec![hash];
+
+        while let Some(hash) = to_visit.pop() {
+            if tangle.is_solid_entry_point(&hash) || !visited.insert(hash) {
+                continue;
+            }
+
+            if visited.len() % REVALIDATION_BATCH_SIZE == 0 {
+                yield_now().await;
+                if interrupted() {
+                    summary.interrupted = true;
+                    break 'milestones;
+                }
+            }
+
+            let transaction = match tangle.get(&hash).await {
+                Some(transaction) => transaction,
+                None => {
+                    missing.push(hash);
+                    continue;
+                }
+            };
+
+            let metadata = tangle.get_metadata(&hash).unwrap_or_default();
+            let flags = metadata.flags();
+
+            // Transactions confirmed by an older milestone belong to cones that are out of reach of this pass.
+            if flags.is_confirmed() && metadata.milestone_index() < MilestoneIndex::from(lowest) {
+                continue;
+            }
+
+            if flags.is_confirmed() && !flags.is_solid() {
+                tangle.update_metadata(&hash, |metadata| metadata.flags_mut().set_confirmed(false));
+                repaired.insert(hash);
+            }
+
+            cone_size += 1;
+            if max_cone_size != 0 && cone_size >= max_cone_size {
+                continue 'milestones;
+            }
+
+            to_visit.push(*transaction.trunk());
+            to_visit.push(*transaction.branch());
+        }
+    }
+
+    summary.missing_transactions = missing.len();
+
+    // Nothing that approves a missing transaction, directly or not, can be solid or confirmed.
+    let mut to_clear = missing
+        .iter()
+        .flat_map(|hash| tangle.get_children(hash))
+        .collect::<Vec<_>>();
+    let mut cleared = HashSet::new();
+
+    while let Some(hash) = to_clear.pop() {
+        if !cleared.insert(hash) {
+            continue;
+        }
+
+        let mut flagged = false;
+        tangle.update_metadata(&hash, |metadata| {
+            let flags = metadata.flags_mut();
+            flagged = flags.is_solid() || flags.is_confirmed();
+            flags.set_solid(false);
+            flags.set_confirmed(false);
+        });
+
+        if flagged {
+            repaired.insert(hash);
+            to_clear.extend(tangle.get_children(&hash));
+        }
+    }
+
+    summary.repaired_transactions = repaired.len();
+
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use crate::{
+        tangle::{flags::Flags, TransactionMetadata},
+        test_utils::{repeated_hash as hash, MockStorage},
+    };
+
+    use bee_common_ext::node::ResHandle;
+    use bee_transaction::bundled::{
+        Address, BundledTransaction as Tx, BundledTransactionBuilder, Index, Nonce, Payload, Tag, Timestamp, Value,
+    };
+
+    fn transaction(trunk: Hash, branch: Hash) -> Tx {
+        BundledTransactionBuilder::new()
+            .with_payload(Payload::zeros())
+            .with_address(Address::zeros())
+            .with_value(Value::from_inner_unchecked(0))
+            .with_obsolete_tag(Tag::zeros())
+            .with_timestamp(Timestamp::from_inner_unchecked(0))
+            .with_index(Index::from_inner_unchecked(0))
+            .with_last_index(Index::from_inner_unchecked(0))
+            .with_tag(Tag::zeros())
+            .with_attachment_ts(Timestamp::from_inner_unchecked(0))
+            .with_bundle(Hash::zeros())
+            .with_trunk(trunk)
+            .with_branch(branch)
+            .with_attachment_lbts(Timestamp::from_inner_unchecked(0))
+            .with_attachment_ubts(Timestamp::from_inner_unchecked(0))
+            .with_nonce(Nonce::zeros())
+            .build()
+            .unwrap()
+    }
+
+    fn confirmed(index: u32) -> TransactionMetadata {
+        TransactionMetadata::new(Flags::SOLID | Flags::CONFIRMED, MilestoneIndex::from(index), 0, 0, 0)
+    }
+
+    fn is_flagged(tangle: &MsTangle<MockStorage>, hash: &Hash) -> bool {
+        let metadata = tangle.get_metadata(hash).unwrap();
+        metadata.flags().is_solid() || metadata.flags().is_confirmed()
+    }
+
+    // Solid entry point `S` and milestones 2 (`B`, approving `A`) and 3 (`D`, approving `C` and `B`), `A` and `C`
+    // approving `S` and `X`, which is not part of the tangle unless `with_x` is set.
+    async fn tangle(with_x: bool) -> MsTangle<MockStorage> {
+        let tangle = MsTangle::new(ResHandle::new(MockStorage::default()));
+
+        tangle.add_solid_entry_point(hash('S'), MilestoneIndex::from(1));
+        tangle.update_entry_point_index(MilestoneIndex::from(1));
+        tangle.update_latest_solid_milestone_index(MilestoneIndex::from(3));
+
+        if with_x {
+            tangle
+                .insert(transaction(hash('S'), hash('S')), hash('X'), confirmed(2))
+                .await;
+        }
+        tangle
+            .insert(transaction(hash('S'), hash('X')), hash('A'), confirmed(2))
+            .await;
+        tangle
+            .insert(transaction(hash('A'), hash('S')), hash('B'), confirmed(2))
+            .await;
+        tangle
+            .insert(transaction(hash('S'), hash('S')), hash('C'), confirmed(3))
+            .await;
+        tangle
+            .insert(transaction(hash('C'), hash('B')), hash('D'), confirmed(3))
+            .await;
+        tangle.add_milestone(MilestoneIndex::from(2), hash('B'));
+        tangle.add_milestone(MilestoneIndex::from(3), hash('D'));
+
+        tangle
+    }
+
+    #[tokio::test]
+    async fn consistent_tangle_untouched() {
+        let tangle = tangle(true).await;
+
+        let summary = revalidate(&tangle, 10, 0, || false).await;
+
+        assert_eq!(
+            summary,
+            RevalidationCompleted {
+                milestones: 2,
+                ..Default::default()
+            }
+        );
+        for tryte in "XABCD".chars() {
+            assert!(is_flagged(&tangle, &hash(tryte)));
+        }
+    }
+
+    #[tokio::test]
+    async fn missing_parent_clears_flags() {
+        let tangle = tangle(false).await;
+
+        let summary = revalidate(&tangle, 10, 0, || false).await;
+
+        assert_eq!(
+            summary,
+            RevalidationCompleted {
+                milestones: 2,
+                missing_transactions: 1,
+                repaired_transactions: 3,
+                ..Default::default()
+            }
+        );
+        // `A` is missing a parent and `B` and `D` approve it.
+        for tryte in "ABD".chars() {
+            assert!(!is_flagged(&tangle, &hash(tryte)));
+        }
+        assert!(is_flagged(&tangle, &hash('C')));
+    }
+
+    #[tokio::test]
+    async fn confirmed_but_not_solid_unconfirmed() {
+        let tangle = tangle(true).await;
+        tangle.update_metadata(&hash('C'), |metadata| metadata.flags_mut().set_solid(false));
+
+        let summary = revalidate(&tangle, 10, 0, || false).await;
+
+        assert_eq!(summary.repaired_transactions, 1);
+        assert!(!tangle.get_metadata(&hash('C')).unwrap().flags().is_confirmed());
+    }
+
+    #[tokio::test]
+    async fn missing_milestone_forgotten() {
+        let tangle = tangle(true).await;
+        tangle.add_milestone(MilestoneIndex::from(4), hash('E'));
+        tangle.update_latest_solid_milestone_index(MilestoneIndex::from(4));
+
+        let summary = revalidate(&tangle, 10, 0, || false).await;
+
+        assert_eq!(summary.milestones, 3);
+        assert_eq!(summary.missing_milestones, 1);
+        assert!(!tangle.contains_milestone(MilestoneIndex::from(4)));
+        assert!(tangle.contains_milestone(MilestoneIndex::from(3)));
+    }
+
+    #[tokio::test]
+    async fn depth_bounds_milestones() {
+        let tangle = tangle(false).await;
+
+        // Only milestone 3 is checked, `A` is confirmed by milestone 2 and is out of reach.
+        let summary = revalidate(&tangle, 1, 0, || false).await;
+
+        assert_eq!(summary.milestones, 1);
+        assert_eq!(summary.missing_transactions, 0);
+        assert!(is_flagged(&tangle, &hash('A')));
+    }
+
+    #[tokio::test]
+    async fn interrupted() {
+        let tangle = tangle(false).await;
+
+        let summary = revalidate(&tangle, 10, 0, || true).await;
+
+        assert!(summary.interrupted);
+        assert_eq!(summary.milestones, 0);
+        assert!(is_flagged(&tangle, &hash('A')));
+    }
+
+    #[tokio::test]
+    async fn interrupted_after_first_milestone_repairs_findings() {
+        let tangle = tangle(false).await;
+        let mut checks = 0;
+
+        let summary = revalidate(&tangle, 10, 0, || {
+            checks += 1;
+            checks > 1
+        })
+        .await;
+
+        assert!(summary.interrupted);
+        assert_eq!(summary.milestones, 1);
+        assert_eq!(summary.missing_transactions, 1);
+        assert!(!is_flagged(&tangle, &hash('A')));
+    }
+}
@@ -0,0 +1,58 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use crate::milestone::MilestoneIndex;
+
+use bee_crypto::ternary::Hash;
+use bee_storage::{
+    access::{Fetch, Insert},
+    persistable::Persistable,
+    storage::Backend,
+};
+
+use std::collections::HashMap;
+
+/// The set of solid entry points as persisted by the storage, along with the entry point index it was computed for.
+///
+/// The whole set is written as a single value so that it can never be observed partially updated.
+#[derive(Clone, Debug, Default, Persistable)]
+pub struct SolidEntryPoints {
+    entry_point_index: MilestoneIndex,
+    points: HashMap<Hash, MilestoneIndex>,
+}
+
+impl SolidEntryPoints {
+    pub fn new(entry_point_index: MilestoneIndex, points: HashMap<Hash, MilestoneIndex>) -> Self {
+        Self {
+            entry_point_index,
+            points,
+        }
+    }
+
+    pub fn entry_point_index(&self) -> MilestoneIndex {
+        self.entry_point_index
+    }
+
+    pub fn points(&self) -> &HashMap<Hash, MilestoneIndex> {
+        &self.points
+    }
+
+    pub fn into_points(self) -> HashMap<Hash, MilestoneIndex> {
+        self.points
+    }
+}
+
+/// Storage accesses required to persist the solid entry points.
+///
+/// There is a single set of solid entry points, it is stored under the `()` key.
+pub trait SolidEntryPointsStorage: Backend + Insert<(), SolidEntryPoints> + Fetch<(), SolidEntryPoints> {}
+
+impl<B> SolidEntryPointsStorage for B where B: Backend + Insert<(), SolidEntryPoints> + Fetch<(), SolidEntryPoints> {}
@@ -0,0 +1,315 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+//! Helpers shared by the unit tests of the crate.
+
+use crate::{
+    config::ProtocolConfig,
+    protocol::{PersistedMetrics, Protocol},
+    tangle::{RunningMarker, SolidEntryPoints},
+};
+
+use bee_common::shutdown;
+use bee_common_ext::{
+    event::Bus,
+    node::{resolve_optional_dependencies, Node, NodeBuilder, ResHandle},
+    shutdown_tokio::Shutdown,
+    worker::Worker,
+};
+use bee_crypto::ternary::Hash;
+use bee_network::NetworkConfig;
+use bee_snapshot::local::LocalSnapshot;
+use bee_storage::{
+    access::{Delete, Fetch, Insert},
+    storage::Backend,
+};
+use bee_ternary::{T1B1Buf, TryteBuf};
+use bee_transaction::bundled::constants::HASH_BYTE_LEN;
+
+use async_trait::async_trait;
+use futures::{channel::oneshot, future::Future};
+
+use std::{
+    any::{type_name, Any, TypeId},
+    collections::HashMap,
+    error::Error,
+    sync::{Arc, Mutex},
+};
+
+/// In-memory backend whose content outlives the tangles and metrics using it, like a database does a node restart.
+#[derive(Clone, Default)]
+pub(crate) struct MockStorage {
+    solid_entry_points: Arc<Mutex<Option<SolidEntryPoints>>>,
+    metrics: Arc<Mutex<Option<PersistedMetrics>>>,
+    running_marker: Arc<Mutex<Option<RunningMarker>>>,
+    /// Makes every fetch fail, as if the stored values could not be decoded.
+    pub(crate) corrupted: bool,
+}
+
+impl MockStorage {
+    fn fetch<T: Clone>(&self, slot: &Mutex<Option<T>>) -> Result<Option<T>, ()> {
+        if self.corrupted {
+            Err(())
+        } else {
+            Ok(slot.lock().unwrap().clone())
+        }
+    }
+}
+
+#[async_trait]
+impl Backend for MockStorage {
+    type ConfigBuilder = ();
+    type Config = ();
+
+    async fn start(_config: Self::Config) -> Result<Self, Box<dyn Error>> {
+        Ok(Self::default())
+    }
+
+    async fn shutdown(self) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Insert<(), SolidEntryPoints> for MockStorage {
+    type Error = ();
+
+    async fn insert(&self, _key: &(), value: &SolidEntryPoints) -> Result<(), Self::Error> {
+        self.solid_entry_points.lock().unwrap().replace(value.clone());
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Fetch<(), SolidEntryPoints> for MockStorage {
+    type Error = ();
+
+    async fn fetch(&self, _key: &()) -> Result<Option<SolidEntryPoints>, Self::Error> {
+        MockStorage::fetch(self, &self.solid_entry_points)
+    }
+}
+
+#[async_trait]
+impl Insert<(), PersistedMetrics> for MockStorage {
+    type Error = ();
+
+    async fn insert(&self, _key: &(), value: &PersistedMetrics) -> Result<(), Self::Error> {
+        self.metrics.lock().unwrap().replace(*value);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Fetch<(), PersistedMetrics> for MockStorage {
+    type Error = ();
+
+    async fn fetch(&self, _key: &()) -> Result<Option<PersistedMetrics>, Self::Error> {
+        MockStorage::fetch(self, &self.metrics)
+    }
+}
+
+#[async_trait]
+impl Insert<(), RunningMarker> for MockStorage {
+    type Error = ();
+
+    async fn insert(&self, _key: &(), value: &RunningMarker) -> Result<(), Self::Error> {
+        self.running_marker.lock().unwrap().replace(*value);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Fetch<(), RunningMarker> for MockStorage {
+    type Error = ();
+
+    async fn fetch(&self, _key: &()) -> Result<Option<RunningMarker>, Self::Error> {
+        MockStorage::fetch(self, &self.running_marker)
+    }
+}
+
+#[async_trait]
+impl Delete<(), RunningMarker> for MockStorage {
+    type Error = ();
+
+    async fn delete(&self, _key: &()) -> Result<(), Self::Error> {
+        self.running_marker.lock().unwrap().take();
+        Ok(())
+    }
+}
+
+/// Hash made of `trytes`, padded with `9`s up to 81 trytes.
+pub(crate) fn hash(trytes: &str) -> Hash {
+    Hash::from_inner_unchecked(
+        TryteBuf::try_from_str(&format!("{:9<81}", trytes))
+            .unwrap()
+            .as_trits()
+            .encode::<T1B1Buf>(),
+    )
+}
+
+/// Hash made of `tryte` repeated 81 times.
+pub(crate) fn repeated_hash(tryte: char) -> Hash {
+    hash(&tryte.to_string().repeat(81))
+}
+
+/// Distinct hash for every `n`, its trytes being the base 26 digits of `n` written with the letters.
+pub(crate) fn indexed_hash(mut n: usize) -> Hash {
+    let mut trytes = String::new();
+
+    loop {
+        trytes.push((b'A' + (n % 26) as u8) as char);
+        n /= 26;
+        if n == 0 {
+            break;
+        }
+    }
+
+    hash(&trytes)
+}
+
+/// Node on which the workers are only registered, never started, so the node itself is never used.
+pub(crate) struct MockNode;
+
+/// Records the workers registered on it.
+#[derive(Default)]
+pub(crate) struct MockNodeBuilder(Graph);
+
+#[async_trait(?Send)]
+impl NodeBuilder<MockNode> for MockNodeBuilder {
+    fn with_worker<W: Worker<MockNode> + 'static>(self) -> Self
+    where
+        W::Config: Default,
+    {
+        Self(self.0.register::<W>())
+    }
+
+    fn with_worker_cfg<W: Worker<MockNode> + 'static>(self, _config: W::Config) -> Self {
+        Self(self.0.register::<W>())
+    }
+
+    fn with_resource<R: Any + Send + Sync>(self, _res: R) -> Self {
+        self
+    }
+
+    async fn finish(self) -> MockNode {
+        MockNode
+    }
+}
+
+#[async_trait]
+impl Node for MockNode {
+    type Builder = MockNodeBuilder;
+    type Backend = MockStorage;
+
+    async fn stop(self) -> Result<(), shutdown::Error> {
+        unreachable!()
+    }
+
+    fn spawn<W, G, F>(&mut self, _g: G)
+    where
+        W: Worker<Self>,
+        G: FnOnce(oneshot::Receiver<()>) -> F,
+        F: Future<Output = ()> + Send + 'static,
+    {
+        unreachable!()
+    }
+
+    fn worker<W>(&self) -> Option<&W>
+    where
+        W: Worker<Self> + Send + Sync,
+    {
+        unreachable!()
+    }
+
+    fn register_resource<R: Any + Send + Sync>(&mut self, _res: R) {
+        unreachable!()
+    }
+
+    fn remove_resource<R: Any + Send + Sync>(&mut self) -> Option<R> {
+        unreachable!()
+    }
+
+    fn resource<R: Any + Send + Sync>(&self) -> ResHandle<R> {
+        unreachable!()
+    }
+}
+
+/// Dependencies of the workers registered on a `MockNodeBuilder`.
+#[derive(Default)]
+pub(crate) struct Graph {
+    pub(crate) dependencies: HashMap<TypeId, &'static [TypeId]>,
+    pub(crate) optional_dependencies: HashMap<TypeId, &'static [TypeId]>,
+    pub(crate) names: HashMap<TypeId, &'static str>,
+}
+
+impl Graph {
+    fn register<W: Worker<MockNode>>(mut self) -> Self {
+        self.dependencies.insert(TypeId::of::<W>(), W::dependencies());
+        self.optional_dependencies
+            .insert(TypeId::of::<W>(), W::optional_dependencies());
+        self.names.insert(TypeId::of::<W>(), type_name::<W>());
+        self
+    }
+
+    /// Adds the optional dependencies that are registered to the dependencies.
+    pub(crate) fn resolved(self) -> Self {
+        Self {
+            dependencies: resolve_optional_dependencies(&self.dependencies, &self.optional_dependencies),
+            ..self
+        }
+    }
+}
+
+// A legacy local snapshot holding the whole supply on the null address.
+fn snapshot() -> LocalSnapshot {
+    const IOTA_SUPPLY: u64 = 2_779_530_283_277_761;
+
+    let mut bytes = vec![4u8];
+    bytes.extend_from_slice(&[0u8; HASH_BYTE_LEN]);
+    bytes.extend_from_slice(&0u32.to_le_bytes());
+    bytes.extend_from_slice(&0u64.to_le_bytes());
+    for count in &[0u32, 0, 1, 0] {
+        bytes.extend_from_slice(&count.to_le_bytes());
+    }
+    bytes.extend_from_slice(&[0u8; HASH_BYTE_LEN]);
+    bytes.extend_from_slice(&IOTA_SUPPLY.to_le_bytes());
+
+    LocalSnapshot::from_reader(&mut bytes.as_slice()).unwrap()
+}
+
+// `Protocol::init` replaces the global protocol, tests calling it run one at a time.
+static PROTOCOL_INIT: spin::Mutex<()> = spin::Mutex::new(());
+
+/// Initializes the global protocol with `config` and `bus` on a `MockNode`. The protocol stays the global one until the
+/// returned guard is dropped, along with the workers registered by `Protocol::init`.
+pub(crate) async fn init_protocol(
+    config: ProtocolConfig,
+    bus: Arc<Bus<'static>>,
+) -> (spin::MutexGuard<'static, ()>, Graph) {
+    let guard = PROTOCOL_INIT.lock();
+    let network_config = NetworkConfig::builder()
+        .binding_address("127.0.0.1")
+        .binding_port(0)
+        .finish();
+    let (network, _events) = bee_network::init(network_config, &mut Shutdown::new()).await;
+
+    let graph = Protocol::init::<MockNode>(
+        config,
+        (),
+        network,
+        snapshot().metadata().clone(),
+        MockNodeBuilder::default(),
+        bus,
+    )
+    .0;
+
+    (guard, graph)
+}
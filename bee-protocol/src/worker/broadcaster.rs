@@ -11,57 +11,196 @@
 
 use crate::{
     message::{tlv_into_bytes, Transaction as TransactionMessage},
+    milestone::MilestoneIndex,
     protocol::Protocol,
 };
 
 use bee_common::{shutdown_stream::ShutdownStream, worker::Error as WorkerError};
-use bee_common_ext::{node::Node, worker::Worker};
+use bee_common_ext::{channel, node::Node, worker::Worker};
 use bee_network::{Command::SendMessage, EndpointId, Network};
 
 use async_trait::async_trait;
-use futures::stream::StreamExt;
-use log::{info, warn};
+use bytes::Bytes;
+use futures::{future::FutureExt, stream::StreamExt};
+use log::{info, trace, warn};
+
+use std::time::{Duration, Instant};
 
 pub(crate) struct BroadcasterWorkerEvent {
     pub(crate) source: Option<EndpointId>,
+    // Only peer to send the transaction to, if not meant for every peer.
+    pub(crate) target: Option<EndpointId>,
     pub(crate) transaction: TransactionMessage,
+    // Index of the milestone that confirmed the transaction, or whose solidification requested it, if any.
+    pub(crate) milestone_index: Option<MilestoneIndex>,
 }
 
 pub(crate) struct BroadcasterWorker {
-    pub(crate) tx: flume::Sender<BroadcasterWorkerEvent>,
+    pub(crate) tx: channel::Sender<BroadcasterWorkerEvent>,
+}
+
+// Peers that are solid at or above the milestone index of a transaction already have it.
+fn peer_needs(milestone_index: Option<MilestoneIndex>, peer_solid_index: MilestoneIndex) -> bool {
+    match milestone_index {
+        Some(index) => index > peer_solid_index,
+        None => true,
+    }
+}
+
+// A broadcast never goes back to the peer the transaction came from, and a targeted one only goes to its target. In
+// both cases, peers that already have the transaction are skipped.
+pub(crate) fn is_recipient(
+    peer: EndpointId,
+    peer_solid_index: MilestoneIndex,
+    source: Option<EndpointId>,
+    target: Option<EndpointId>,
+    milestone_index: Option<MilestoneIndex>,
+) -> bool {
+    match (target, source) {
+        (Some(target), _) => target == peer && peer_needs(milestone_index, peer_solid_index),
+        (None, Some(source)) => source != peer && peer_needs(milestone_index, peer_solid_index),
+        (None, None) => true,
+    }
+}
+
+// Local transactions go first, then the fresh ones and finally the rebroadcasts of old ones, which are thus the first
+// to be dropped when the outbound budget is exhausted.
+fn priority(local: bool, milestone_index: Option<MilestoneIndex>) -> u8 {
+    match (local, milestone_index) {
+        (true, _) => 0,
+        (false, None) => 1,
+        (false, Some(_)) => 2,
+    }
+}
+
+// Transactions pushed to a single peer to assist its solidification are the least important ones.
+const TARGETED_PRIORITY: u8 = 3;
+
+// Outbound bytes per second allowed for broadcasting, 0 meaning unlimited.
+struct BroadcastBudget {
+    limit: u64,
+    spent: u64,
+    window: Instant,
+}
+
+impl BroadcastBudget {
+    fn new(limit: u64) -> Self {
+        Self {
+            limit,
+            spent: 0,
+            window: Instant::now(),
+        }
+    }
+
+    // Local broadcasts are always allowed but still count against the budget.
+    fn try_spend(&mut self, bytes: u64, local: bool, now: Instant) -> bool {
+        if now.duration_since(self.window) >= Duration::from_secs(1) {
+            self.spent = 0;
+            self.window = now;
+        }
+
+        if !local && self.limit != 0 && self.spent + bytes > self.limit {
+            return false;
+        }
+
+        self.spent += bytes;
+
+        true
+    }
+}
+
+// Recipients and encoding of the queued broadcasts, in the order they are to be sent. The broadcasts are sorted by
+// priority first so that the budget is spent on the most important ones, given the latest solid milestone index of
+// each peer.
+fn schedule(
+    mut events: Vec<BroadcasterWorkerEvent>,
+    peers: &[(EndpointId, MilestoneIndex)],
+    budget: &mut BroadcastBudget,
+    now: Instant,
+) -> Vec<(Vec<EndpointId>, Bytes)> {
+    events.sort_by_key(|event| match event.target {
+        Some(_) => TARGETED_PRIORITY,
+        None => priority(event.source.is_none(), event.milestone_index),
+    });
+
+    let mut scheduled = Vec::new();
+
+    for BroadcasterWorkerEvent {
+        source,
+        target,
+        transaction,
+        milestone_index,
+    } in events
+    {
+        let local = source.is_none() && target.is_none();
+        let recipients = peers
+            .iter()
+            .filter(|(peer, peer_solid_index)| is_recipient(*peer, *peer_solid_index, source, target, milestone_index))
+            .map(|(peer, _)| *peer)
+            .collect::<Vec<_>>();
+
+        if recipients.is_empty() {
+            continue;
+        }
+
+        // Encoded once and shared by all the recipients, cloning only bumps a reference count.
+        let bytes = Bytes::from(tlv_into_bytes(transaction));
+
+        if !budget.try_spend((bytes.len() * recipients.len()) as u64, local, now) {
+            trace!("Outbound budget exceeded, dropping broadcast.");
+            continue;
+        }
+
+        scheduled.push((recipients, bytes));
+    }
+
+    scheduled
 }
 
 #[async_trait]
 impl<N: Node> Worker<N> for BroadcasterWorker {
-    type Config = Network;
+    type Config = (Network, u64);
     type Error = WorkerError;
 
     async fn start(node: &mut N, config: Self::Config) -> Result<Self, Self::Error> {
-        let (tx, rx) = flume::unbounded();
+        let (tx, rx) = channel::unbounded("BroadcasterWorker");
+        let (network, budget) = config;
 
         node.spawn::<Self, _, _>(|shutdown| async move {
             info!("Running.");
 
             let mut receiver = ShutdownStream::new(shutdown, rx.into_stream());
+            let mut budget = BroadcastBudget::new(budget);
+
+            while let Some(event) = receiver.next().await {
+                let mut events = vec![event];
 
-            while let Some(BroadcasterWorkerEvent { source, transaction }) = receiver.next().await {
-                let bytes = tlv_into_bytes(transaction);
+                // Take everything that is already queued so that the budget is spent by priority.
+                while let Some(Some(event)) = receiver.next().now_or_never() {
+                    events.push(event);
+                }
 
-                for peer in Protocol::get().peer_manager.handshaked_peers.iter() {
-                    if match source {
-                        Some(source) => source != *peer.key(),
-                        None => true,
-                    } {
-                        match config.unbounded_send(SendMessage {
-                            receiver_epid: *peer.key(),
+                let peers = Protocol::get()
+                    .peer_manager
+                    .handshaked_peers
+                    .iter()
+                    .map(|peer| (*peer.key(), peer.value().latest_solid_milestone_index()))
+                    .collect::<Vec<_>>();
+
+                for (recipients, bytes) in schedule(events, &peers, &mut budget, Instant::now()) {
+                    for epid in recipients {
+                        match network.unbounded_send(SendMessage {
+                            receiver_epid: epid,
                             message: bytes.clone(),
                         }) {
                             Ok(_) => {
-                                (*peer.value()).metrics.transactions_sent_inc();
+                                if let Some(peer) = Protocol::get().peer_manager.handshaked_peers.get(&epid) {
+                                    peer.metrics.transactions_sent_inc();
+                                }
                                 Protocol::get().metrics.transactions_sent_inc();
                             }
                             Err(e) => {
-                                warn!("Broadcasting transaction to {:?} failed: {:?}.", *peer.key(), e);
+                                warn!("Broadcasting transaction to {:?} failed: {:?}.", epid, e);
                             }
                         };
                     }
@@ -74,3 +213,184 @@ impl<N: Node> Worker<N> for BroadcasterWorker {
         Ok(Self { tx })
     }
 }
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use bee_network::TransportProtocol;
+
+    fn peer(port: u16) -> EndpointId {
+        EndpointId::new(TransportProtocol::Tcp, ([127, 0, 0, 1], port).into())
+    }
+
+    #[test]
+    fn broadcast_skips_source() {
+        let (a, b, c) = (peer(15601), peer(15602), peer(15603));
+        let solid_index = MilestoneIndex(10);
+
+        let recipients = [a, b, c]
+            .iter()
+            .filter(|peer| is_recipient(**peer, solid_index, Some(a), None, None))
+            .copied()
+            .collect::<Vec<_>>();
+
+        assert_eq!(recipients, vec![b, c]);
+    }
+
+    #[test]
+    fn local_broadcast_reaches_all() {
+        let peers = [peer(15601), peer(15602), peer(15603)];
+
+        assert!(peers
+            .iter()
+            .all(|peer| is_recipient(*peer, MilestoneIndex(10), None, None, None)));
+    }
+
+    #[test]
+    fn targeted_broadcast_reaches_target_only() {
+        let (a, b, c) = (peer(15601), peer(15602), peer(15603));
+
+        let recipients = [a, b, c]
+            .iter()
+            .filter(|peer| is_recipient(**peer, MilestoneIndex(10), None, Some(c), None))
+            .copied()
+            .collect::<Vec<_>>();
+
+        assert_eq!(recipients, vec![c]);
+    }
+
+    #[test]
+    fn fanout_skips_peers_ahead() {
+        let ahead = MilestoneIndex(100);
+        let behind = MilestoneIndex(10);
+
+        assert!(!peer_needs(Some(MilestoneIndex(50)), ahead));
+        assert!(peer_needs(Some(MilestoneIndex(50)), behind));
+        assert!(!peer_needs(Some(MilestoneIndex(10)), behind));
+        assert!(peer_needs(None, ahead));
+        assert!(peer_needs(None, behind));
+    }
+
+    #[test]
+    fn budget_drops_old_before_new() {
+        let now = Instant::now();
+        let mut budget = BroadcastBudget::new(300);
+        // (local, milestone index) of the queued broadcasts, each costing 100 bytes.
+        let mut events = vec![
+            (false, Some(MilestoneIndex(1))),
+            (false, None),
+            (true, Some(MilestoneIndex(2))),
+            (false, Some(MilestoneIndex(3))),
+            (false, None),
+            (true, None),
+        ];
+
+        events.sort_by_key(|(local, index)| priority(*local, *index));
+
+        let sent = events
+            .into_iter()
+            .filter(|(local, _)| budget.try_spend(100, *local, now))
+            .collect::<Vec<_>>();
+
+        assert_eq!(sent, vec![(true, Some(MilestoneIndex(2))), (true, None), (false, None)]);
+    }
+
+    #[test]
+    fn budget_always_allows_local() {
+        let now = Instant::now();
+        let mut budget = BroadcastBudget::new(100);
+
+        assert!(budget.try_spend(100, false, now));
+        assert!(!budget.try_spend(1, false, now));
+        assert!(budget.try_spend(100, true, now));
+        assert!(budget.try_spend(1, false, now + Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn budget_unlimited() {
+        let now = Instant::now();
+        let mut budget = BroadcastBudget::new(0);
+
+        assert!(budget.try_spend(u32::MAX as u64, false, now));
+        assert!(budget.try_spend(u32::MAX as u64, false, now));
+    }
+
+    fn event(
+        source: Option<EndpointId>,
+        target: Option<EndpointId>,
+        content: u8,
+        milestone_index: Option<MilestoneIndex>,
+    ) -> BroadcasterWorkerEvent {
+        BroadcasterWorkerEvent {
+            source,
+            target,
+            transaction: TransactionMessage::new(&[content; 100]),
+            milestone_index,
+        }
+    }
+
+    fn bytes(content: u8) -> Bytes {
+        Bytes::from(tlv_into_bytes(TransactionMessage::new(&[content; 100])))
+    }
+
+    #[test]
+    fn fanout_to_peer_ahead_and_peer_behind() {
+        let (ahead, behind, source) = (peer(15601), peer(15602), peer(15603));
+        let peers = [(ahead, MilestoneIndex(100)), (behind, MilestoneIndex(10))];
+        let mut budget = BroadcastBudget::new(0);
+
+        let scheduled = schedule(
+            vec![
+                // Received while solidifying milestone 50, which only the peer behind is missing.
+                event(Some(source), None, 1, Some(MilestoneIndex(50))),
+                // Received through gossip, not confirmed yet.
+                event(Some(source), None, 2, None),
+                // Pushed to assist the solidification of peers that already have it or not.
+                event(None, Some(ahead), 3, Some(MilestoneIndex(50))),
+                event(None, Some(behind), 4, Some(MilestoneIndex(50))),
+                // Submitted locally.
+                event(None, None, 5, Some(MilestoneIndex(50))),
+            ],
+            &peers,
+            &mut budget,
+            Instant::now(),
+        );
+
+        assert_eq!(
+            scheduled,
+            vec![
+                (vec![ahead, behind], bytes(5)),
+                (vec![ahead, behind], bytes(2)),
+                (vec![behind], bytes(1)),
+                (vec![behind], bytes(4)),
+            ]
+        );
+    }
+
+    #[test]
+    fn budget_drops_old_before_new_with_two_peers() {
+        let (ahead, behind, source) = (peer(15601), peer(15602), peer(15603));
+        let peers = [(ahead, MilestoneIndex(100)), (behind, MilestoneIndex(10))];
+        let size = bytes(0).len() as u64;
+        // Enough for the local and the fresh broadcasts to both peers and nothing more.
+        let mut budget = BroadcastBudget::new(4 * size);
+
+        let scheduled = schedule(
+            vec![
+                event(Some(source), None, 1, Some(MilestoneIndex(50))),
+                event(Some(source), None, 2, None),
+                event(None, None, 3, None),
+            ],
+            &peers,
+            &mut budget,
+            Instant::now(),
+        );
+
+        assert_eq!(
+            scheduled,
+            vec![(vec![ahead, behind], bytes(3)), (vec![ahead, behind], bytes(2))]
+        );
+    }
+}
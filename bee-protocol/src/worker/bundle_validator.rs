@@ -12,9 +12,10 @@
 use crate::{tangle::MsTangle, worker::TangleWorker};
 
 use bee_common::{shutdown_stream::ShutdownStream, worker::Error as WorkerError};
-use bee_common_ext::{node::Node, worker::Worker};
+use bee_common_ext::{channel, node::Node, worker::Worker};
 use bee_crypto::ternary::Hash;
-use bee_tangle::helper::load_bundle_builder;
+use bee_tangle::helper::load_bundle_transactions;
+use bee_transaction::bundled::validate_bundle;
 
 use async_trait::async_trait;
 use futures::stream::StreamExt;
@@ -25,7 +26,7 @@ use std::any::TypeId;
 pub(crate) struct BundleValidatorWorkerEvent(pub(crate) Hash);
 
 pub(crate) struct BundleValidatorWorker {
-    pub(crate) tx: flume::Sender<BundleValidatorWorkerEvent>,
+    pub(crate) tx: channel::Sender<BundleValidatorWorkerEvent>,
 }
 
 #[async_trait]
@@ -38,7 +39,7 @@ impl<N: Node> Worker<N> for BundleValidatorWorker {
     }
 
     async fn start(node: &mut N, _config: Self::Config) -> Result<Self, Self::Error> {
-        let (tx, rx) = flume::unbounded();
+        let (tx, rx) = channel::unbounded("BundleValidatorWorker");
 
         let tangle = node.resource::<MsTangle<N::Backend>>();
 
@@ -48,9 +49,10 @@ impl<N: Node> Worker<N> for BundleValidatorWorker {
             let mut receiver = ShutdownStream::new(shutdown, rx.into_stream());
 
             while let Some(BundleValidatorWorkerEvent(hash)) = receiver.next().await {
-                match load_bundle_builder(&*tangle, &hash) {
-                    Some(builder) => {
-                        if builder.validate().is_ok() {
+                match load_bundle_transactions(&*tangle, &hash) {
+                    Some(transactions) => {
+                        // The weight of the transactions has already been checked by the processor.
+                        if validate_bundle(&transactions, 0).is_ok() {
                             tangle.update_metadata(&hash, |metadata| {
                                 metadata.flags_mut().set_valid(true);
                             })
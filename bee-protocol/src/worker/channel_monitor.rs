@@ -0,0 +1,63 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use bee_common::{shutdown_stream::ShutdownStream, worker::Error as WorkerError};
+use bee_common_ext::{
+    channel::{self, DepthMonitor},
+    node::Node,
+    worker::Worker,
+};
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use log::{info, warn};
+use tokio::time::interval;
+
+use std::time::{Duration, Instant};
+
+const CHECK_INTERVAL_SEC: u64 = 1;
+
+#[derive(Default)]
+pub(crate) struct ChannelMonitorWorker {}
+
+#[async_trait]
+impl<N: Node> Worker<N> for ChannelMonitorWorker {
+    // Fraction of the bound of a channel above which it is considered backed up, for how many seconds it has to stay
+    // above it before a warning is logged and the bound assumed for unbounded channels.
+    type Config = (f64, u64, usize);
+    type Error = WorkerError;
+
+    async fn start(node: &mut N, config: Self::Config) -> Result<Self, Self::Error> {
+        node.spawn::<Self, _, _>(|shutdown| async move {
+            info!("Running.");
+
+            let (fraction, duration_secs, soft_bound) = config;
+            let mut receiver = ShutdownStream::new(shutdown, interval(Duration::from_secs(CHECK_INTERVAL_SEC)));
+            let mut monitor = DepthMonitor::new(fraction, Duration::from_secs(duration_secs), soft_bound);
+
+            while receiver.next().await.is_some() {
+                for depth in monitor.check(&channel::depths(), Instant::now()) {
+                    warn!(
+                        "Channel {} has been backed up for more than {}s: {} messages waiting, bound {}.",
+                        depth.name,
+                        duration_secs,
+                        depth.depth,
+                        depth.bound.unwrap_or(soft_bound)
+                    );
+                }
+            }
+
+            info!("Stopped.");
+        });
+
+        Ok(Self::default())
+    }
+}
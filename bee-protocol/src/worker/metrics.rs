@@ -0,0 +1,82 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use crate::{
+    protocol::{MetricsStorage, Protocol},
+    worker::StorageWorker,
+};
+
+use bee_common::{shutdown_stream::ShutdownStream, worker::Error as WorkerError};
+use bee_common_ext::{node::Node, worker::Worker};
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use log::{info, warn};
+use tokio::time::interval;
+
+use std::{any::TypeId, time::Duration};
+
+#[derive(Default)]
+pub(crate) struct MetricsWorker {
+    enabled: bool,
+}
+
+#[async_trait]
+impl<N: Node> Worker<N> for MetricsWorker
+where
+    N::Backend: MetricsStorage,
+{
+    // Whether the metrics are persisted and the persistence interval in seconds.
+    type Config = (bool, u64);
+    type Error = WorkerError;
+
+    fn dependencies() -> &'static [TypeId] {
+        Box::leak(Box::from(vec![TypeId::of::<StorageWorker>()]))
+    }
+
+    async fn start(node: &mut N, config: Self::Config) -> Result<Self, Self::Error> {
+        let (enabled, interval_secs) = config;
+
+        if !enabled {
+            return Ok(Self::default());
+        }
+
+        let storage = node.storage();
+
+        Protocol::get().metrics.load(&*storage).await;
+
+        node.spawn::<Self, _, _>(|shutdown| async move {
+            info!("Running.");
+
+            let mut receiver = ShutdownStream::new(shutdown, interval(Duration::from_secs(interval_secs)));
+
+            while receiver.next().await.is_some() {
+                if let Err(e) = Protocol::get().metrics.persist(&*storage).await {
+                    warn!("Persisting metrics failed: {:?}.", e);
+                }
+            }
+
+            info!("Stopped.");
+        });
+
+        Ok(Self { enabled })
+    }
+
+    async fn stop(self, node: &mut N) -> Result<(), Self::Error> {
+        if self.enabled {
+            if let Err(e) = Protocol::get().metrics.persist(&*node.storage()).await {
+                warn!("Persisting metrics failed: {:?}.", e);
+            }
+        }
+
+        Ok(())
+    }
+}
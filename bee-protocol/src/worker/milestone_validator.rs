@@ -10,57 +10,155 @@
 // See the License for the specific language governing permissions and limitations under the License.
 
 use crate::{
-    config::ProtocolConfig,
-    event::{LatestMilestoneChanged, LatestSolidMilestoneChanged},
-    milestone::{Milestone, MilestoneBuilder, MilestoneBuilderError},
-    protocol::Protocol,
+    config::{ProtocolConfig, ProtocolCoordinatorConfig},
+    event::{LatestMilestoneChanged, MilestoneApplicationFailed},
+    milestone::{
+        milestone_index, validate_milestone, Milestone, MilestoneApplicationError, MilestoneBuilderError,
+        MilestoneIndex,
+    },
+    protocol::{Protocol, ProtocolMetrics},
     tangle::{helper::find_tail_of_bundle, MsTangle},
-    worker::{MilestoneSolidifierWorker, MilestoneSolidifierWorkerEvent, TangleWorker},
+    worker::{
+        request_completed, update_latest_solid_milestone, MilestoneSolidifierWorker, MilestoneSolidifierWorkerEvent,
+        TangleWorker,
+    },
 };
 
 use bee_common::{shutdown_stream::ShutdownStream, worker::Error as WorkerError};
-use bee_common_ext::{node::Node, worker::Worker};
-use bee_crypto::ternary::{
-    sponge::{CurlP27, CurlP81, Kerl, Sponge, SpongeKind},
-    Hash,
+use bee_common_ext::{
+    channel,
+    event::Bus,
+    node::{Node, ShutdownFlag},
+    worker::Worker,
 };
-use bee_signing::ternary::{wots::WotsPublicKey, PublicKey, RecoverableSignature};
+use bee_crypto::ternary::Hash;
 use bee_storage::storage::Backend;
-use bee_transaction::Vertex;
+use bee_transaction::{
+    bundled::{validate_bundle, BundledTransaction as Transaction, IncomingBundleBuilderError},
+    Vertex,
+};
 
 use async_trait::async_trait;
 use futures::stream::StreamExt;
 use log::{debug, error, info};
 
-use std::any::TypeId;
+use std::{
+    any::TypeId,
+    collections::{HashMap, VecDeque},
+    fmt,
+    future::Future,
+};
 
 #[derive(Debug)]
 pub(crate) enum MilestoneValidatorWorkerError {
     UnknownTail,
     NotATail,
     IncompleteBundle,
+    InvalidBundle(IncomingBundleBuilderError),
     InvalidMilestone(MilestoneBuilderError),
+    InvalidMerkleProof(MilestoneIndex),
+}
+
+impl fmt::Display for MilestoneValidatorWorkerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownTail => write!(f, "tail transaction is unknown"),
+            Self::NotATail => write!(f, "transaction is not a tail"),
+            Self::IncompleteBundle => write!(f, "milestone bundle is incomplete"),
+            Self::InvalidBundle(e) => write!(f, "invalid milestone bundle: {}", e),
+            Self::InvalidMilestone(e) => write!(f, "invalid milestone: {}", e),
+            Self::InvalidMerkleProof(index) => write!(f, "invalid Merkle proof for milestone {}", **index),
+        }
+    }
+}
+
+impl std::error::Error for MilestoneValidatorWorkerError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::InvalidBundle(e) => Some(e),
+            Self::InvalidMilestone(e) => Some(e),
+            _ => None,
+        }
+    }
 }
 
 pub(crate) struct MilestoneValidatorWorkerEvent(pub(crate) Hash, pub(crate) bool);
 
+// Outcomes of the recent milestone validations keyed by tail hash, the index of a valid milestone or `None` for an
+// invalid one. The least recently used outcomes are evicted first.
+struct ValidationCache {
+    outcomes: HashMap<Hash, Option<MilestoneIndex>>,
+    order: VecDeque<Hash>,
+    capacity: usize,
+}
+
+impl ValidationCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            outcomes: HashMap::new(),
+            order: VecDeque::new(),
+            capacity,
+        }
+    }
+
+    fn get(&mut self, tail_hash: &Hash) -> Option<Option<MilestoneIndex>> {
+        let outcome = *self.outcomes.get(tail_hash)?;
+
+        self.order.retain(|hash| hash != tail_hash);
+        self.order.push_back(*tail_hash);
+
+        Some(outcome)
+    }
+
+    fn insert(&mut self, tail_hash: Hash, outcome: Option<MilestoneIndex>) {
+        if self.outcomes.insert(tail_hash, outcome).is_some() {
+            self.order.retain(|hash| *hash != tail_hash);
+        }
+        self.order.push_back(tail_hash);
+
+        while self.order.len() > self.capacity {
+            if let Some(evicted) = self.order.pop_front() {
+                self.outcomes.remove(&evicted);
+            }
+        }
+    }
+}
+
 pub(crate) struct MilestoneValidatorWorker {
-    pub(crate) tx: flume::Sender<MilestoneValidatorWorkerEvent>,
+    pub(crate) tx: channel::Sender<MilestoneValidatorWorkerEvent>,
+}
+
+// Checks the structure of the milestone bundle made of `transactions`, ordered from tail to head, then its issuer and
+// its signature, and returns the index it claims.
+fn validate_milestone_transactions(
+    transactions: &[Transaction],
+    coordinator: &ProtocolCoordinatorConfig,
+) -> Result<MilestoneIndex, MilestoneValidatorWorkerError> {
+    let claimed_index = transactions.first().and_then(|tail| milestone_index(tail).ok());
+
+    // The weight of the transactions was already checked when they were received.
+    let bundle = validate_bundle(transactions, 0).map_err(MilestoneValidatorWorkerError::InvalidBundle)?;
+
+    validate_milestone(
+        &bundle,
+        &coordinator.public_key,
+        coordinator.depth,
+        coordinator.sponge_type,
+    )
+    .map_err(|e| match (e, claimed_index) {
+        (MilestoneBuilderError::InvalidSignature, Some(index))
+        | (MilestoneBuilderError::SignatureError(_), Some(index)) => {
+            MilestoneValidatorWorkerError::InvalidMerkleProof(index)
+        }
+        (e, _) => MilestoneValidatorWorkerError::InvalidMilestone(e),
+    })
 }
 
-async fn validate_milestone<N, M, P, B: Backend>(
+async fn load_and_validate_milestone<B: Backend>(
     tangle: &MsTangle<B>,
     config: &ProtocolConfig,
     tail_hash: Hash,
-) -> Result<Milestone, MilestoneValidatorWorkerError>
-where
-    N: Node,
-    M: Sponge + Default + Send + Sync + 'static,
-    P: PublicKey + Send + Sync + 'static,
-    <P as PublicKey>::Signature: RecoverableSignature,
-{
-    // TODO also do an IncomingBundleBuilder check ?
-    let mut builder = MilestoneBuilder::<Kerl, M, P>::new(tail_hash);
+) -> Result<Milestone, MilestoneValidatorWorkerError> {
     let mut transaction = tangle
         .get(&tail_hash)
         .await
@@ -71,23 +169,101 @@ where
         return Err(MilestoneValidatorWorkerError::NotATail);
     }
 
-    builder.push((*transaction).clone());
+    let mut transactions = vec![(*transaction).clone()];
 
     // TODO use walker
     for _ in 0..config.coordinator.security_level {
         transaction = tangle
-            .get((*transaction).trunk())
+            .get(transaction.trunk())
             .await
             .ok_or(MilestoneValidatorWorkerError::IncompleteBundle)?;
 
-        builder.push((*transaction).clone());
+        transactions.push((*transaction).clone());
+    }
+
+    let index = validate_milestone_transactions(&transactions, &config.coordinator)?;
+
+    Ok(Milestone::new(tail_hash, index))
+}
+
+// Validates the milestone of `tail_hash` with `validate`, unless the outcome of a previous validation is known or the
+// milestone replays an already applied index at or below the latest solid one. Returns `None` when nothing is left to
+// do with the milestone.
+async fn validate_once<B, V, F>(
+    tangle: &MsTangle<B>,
+    cache: &mut ValidationCache,
+    metrics: &ProtocolMetrics,
+    tail_hash: Hash,
+    validate: V,
+) -> Option<Result<Milestone, MilestoneValidatorWorkerError>>
+where
+    B: Backend,
+    V: FnOnce() -> F,
+    F: Future<Output = Result<Milestone, MilestoneValidatorWorkerError>>,
+{
+    if let Some(outcome) = cache.get(&tail_hash) {
+        metrics.skipped_milestone_validations_inc();
+        return outcome.map(|index| Ok(Milestone::new(tail_hash, index)));
+    }
+
+    if let Some(claimed_index) = tangle
+        .get(&tail_hash)
+        .await
+        .and_then(|transaction| milestone_index(&transaction).ok())
+    {
+        if claimed_index <= tangle.get_latest_solid_milestone_index() && tangle.contains_milestone(claimed_index) {
+            metrics.skipped_milestone_validations_inc();
+            return None;
+        }
+    }
+
+    let result = validate().await;
+
+    match &result {
+        Ok(milestone) => cache.insert(tail_hash, Some(milestone.index)),
+        // Missing transactions may still arrive, the validation is attempted again then.
+        Err(MilestoneValidatorWorkerError::UnknownTail) | Err(MilestoneValidatorWorkerError::IncompleteBundle) => {}
+        Err(_) => cache.insert(tail_hash, None),
     }
 
-    Ok(builder
-        .depth(config.coordinator.depth)
-        .validate()
-        .map_err(MilestoneValidatorWorkerError::InvalidMilestone)?
-        .build())
+    Some(result)
+}
+
+fn apply_milestone<B: Backend>(
+    tangle: &MsTangle<B>,
+    bus: &Bus,
+    milestone: &Milestone,
+) -> Result<(), MilestoneApplicationError> {
+    // Applying the same milestone again is a no-op, only a different milestone at the same index is a conflict.
+    let result = match tangle.get_milestone_hash(milestone.index) {
+        Some(hash) if hash == milestone.hash => Ok(()),
+        Some(_) => Err(MilestoneApplicationError::AlreadyApplied),
+        None if tangle.get_metadata(&milestone.hash).is_none() => Err(MilestoneApplicationError::MissingTail),
+        None => {
+            tangle.add_milestone(milestone.index, milestone.hash);
+            Ok(())
+        }
+    };
+
+    if let Err(error) = result {
+        bus.dispatch(MilestoneApplicationFailed {
+            index: milestone.index,
+            error,
+        });
+    }
+
+    result
+}
+
+// Milestones may be validated out of order while syncing, so the latest milestone index only ever advances.
+fn update_latest_milestone<B: Backend>(tangle: &MsTangle<B>, bus: &Bus, milestone: &Milestone) {
+    if let Some(old) = tangle.advance_latest_milestone_index(milestone.index) {
+        bus.dispatch(LatestMilestoneChanged {
+            old,
+            new: milestone.index,
+            hash: milestone.hash,
+        });
+    }
 }
 
 #[async_trait]
@@ -106,21 +282,24 @@ where
     }
 
     async fn start(node: &mut N, config: Self::Config) -> Result<Self, Self::Error> {
-        let (tx, rx) = flume::unbounded();
+        let (tx, rx) = channel::unbounded("MilestoneValidatorWorker");
         let milestone_solidifier = node.worker::<MilestoneSolidifierWorker>().unwrap().tx.clone();
 
         let tangle = node.resource::<MsTangle<N::Backend>>();
+        let shutdown_flag = node.resource::<ShutdownFlag>();
 
         node.spawn::<Self, _, _>(|shutdown| async move {
             info!("Running.");
 
+            let mut cache = ValidationCache::new(config.workers.ms_validation_cache);
+
             let mut receiver = ShutdownStream::new(shutdown, rx.into_stream());
 
             while let Some(MilestoneValidatorWorkerEvent(hash, is_tail)) = receiver.next().await {
                 let tail_hash = if is_tail {
                     Some(hash)
                 } else {
-                    find_tail_of_bundle(&tangle, hash)
+                    find_tail_of_bundle(&tangle, hash, &shutdown_flag)
                 };
 
                 if let Some(tail_hash) = tail_hash {
@@ -128,44 +307,46 @@ where
                         if meta.flags().is_milestone() {
                             continue;
                         }
-                        match match config.coordinator.sponge_type {
-                            SpongeKind::Kerl => {
-                                validate_milestone::<N, Kerl, WotsPublicKey<Kerl>, N::Backend>(
-                                    &tangle, &config, tail_hash,
-                                )
-                                .await
-                            }
-                            SpongeKind::CurlP27 => {
-                                validate_milestone::<N, CurlP27, WotsPublicKey<CurlP27>, N::Backend>(
-                                    &tangle, &config, tail_hash,
-                                )
-                                .await
-                            }
-                            SpongeKind::CurlP81 => {
-                                validate_milestone::<N, CurlP81, WotsPublicKey<CurlP81>, N::Backend>(
-                                    &tangle, &config, tail_hash,
-                                )
-                                .await
-                            }
-                        } {
+                        let validation =
+                            validate_once(&tangle, &mut cache, &Protocol::get().metrics, tail_hash, || {
+                                load_and_validate_milestone(&tangle, &config, tail_hash)
+                            });
+                        let result = match validation.await {
+                            Some(result) => result,
+                            None => continue,
+                        };
+
+                        match result {
                             Ok(milestone) => {
-                                tangle.add_milestone(milestone.index, milestone.hash);
+                                // Failures are dispatched as events.
+                                if apply_milestone(&tangle, &Protocol::get().bus, &milestone).is_err() {
+                                    continue;
+                                }
 
                                 // This is possibly not sufficient as there is no guarantee a milestone has been
                                 // solidified before being validated, we then also need
                                 // to check when a milestone gets solidified if it's
                                 // already vadidated.
                                 if meta.flags().is_solid() {
-                                    Protocol::get()
-                                        .bus
-                                        .dispatch(LatestSolidMilestoneChanged(milestone.clone()));
+                                    update_latest_solid_milestone(
+                                        &tangle,
+                                        &Protocol::get().bus,
+                                        &Protocol::get().synced,
+                                        milestone.clone(),
+                                    );
                                 }
 
-                                if milestone.index > tangle.get_latest_milestone_index() {
-                                    Protocol::get().bus.dispatch(LatestMilestoneChanged(milestone.clone()));
-                                }
+                                update_latest_milestone(&tangle, &Protocol::get().bus, &milestone);
+
+                                Protocol::get().in_flight_milestones.remove(&milestone.index);
+
+                                if let Some((_, (_, epid))) =
+                                    Protocol::get().requested_milestones.remove(&milestone.index)
+                                {
+                                    if let Some(epid) = epid {
+                                        request_completed(&Protocol::get().peer_manager.handshaked_peers, &epid);
+                                    }
 
-                                if Protocol::get().requested_milestones.remove(&milestone.index).is_some() {
                                     tangle
                                         .update_metadata(&milestone.hash, |meta| meta.flags_mut().set_requested(true));
 
@@ -178,7 +359,14 @@ where
                             }
                             Err(e) => match e {
                                 MilestoneValidatorWorkerError::IncompleteBundle => {}
-                                _ => debug!("Invalid milestone bundle: {:?}.", e),
+                                MilestoneValidatorWorkerError::InvalidMerkleProof(index) => {
+                                    debug!("Invalid Merkle proof for milestone {}.", *index);
+                                    Protocol::get().bus.dispatch(MilestoneApplicationFailed {
+                                        index,
+                                        error: MilestoneApplicationError::InvalidMerkleProof,
+                                    });
+                                }
+                                _ => debug!("Invalid milestone bundle: {}.", e),
                             },
                         }
                     }
@@ -191,3 +379,442 @@ where
         Ok(Self { tx })
     }
 }
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use crate::test_utils::{hash, MockStorage};
+
+    use bee_common_ext::node::ResHandle;
+    use bee_ternary::{T1B1Buf, TritBuf, TryteBuf};
+    use bee_transaction::bundled::{
+        Address, BundledTransaction as Transaction, BundledTransactionBuilder, BundledTransactionField, Index, Nonce,
+        Payload, Tag, Timestamp, Value, TRANSACTION_TRIT_LEN,
+    };
+
+    use std::{
+        error::Error,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc, Mutex,
+        },
+    };
+
+    fn failures(bus: &Bus<'static>) -> Arc<Mutex<Vec<(MilestoneIndex, MilestoneApplicationError)>>> {
+        let failures = Arc::new(Mutex::new(Vec::new()));
+        let failures_clone = failures.clone();
+
+        bus.add_listener(move |event: &MilestoneApplicationFailed| {
+            failures_clone.lock().unwrap().push((event.index, event.error))
+        });
+
+        failures
+    }
+
+    #[tokio::test]
+    async fn milestone_applied() {
+        let tangle = MsTangle::new(ResHandle::new(MockStorage::default()));
+        let bus = Bus::default();
+        let failures = failures(&bus);
+        let milestone = Milestone::new(hash(&"A".repeat(81)), MilestoneIndex(1));
+
+        tangle
+            .insert(
+                Transaction::from_trits(&TritBuf::<T1B1Buf>::zeros(TRANSACTION_TRIT_LEN)).unwrap(),
+                milestone.hash,
+                Default::default(),
+            )
+            .await;
+
+        assert!(apply_milestone(&tangle, &bus, &milestone).is_ok());
+        assert_eq!(tangle.get_milestone_hash(MilestoneIndex(1)), Some(milestone.hash));
+        assert!(tangle.get_metadata(&milestone.hash).unwrap().flags().is_milestone());
+        assert!(failures.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn missing_tail_reported() {
+        let tangle = MsTangle::new(ResHandle::new(MockStorage::default()));
+        let bus = Bus::default();
+        let failures = failures(&bus);
+        let milestone = Milestone::new(hash(&"A".repeat(81)), MilestoneIndex(1));
+
+        assert_eq!(
+            apply_milestone(&tangle, &bus, &milestone),
+            Err(MilestoneApplicationError::MissingTail)
+        );
+        assert!(!tangle.contains_milestone(MilestoneIndex(1)));
+        assert_eq!(
+            *failures.lock().unwrap(),
+            vec![(MilestoneIndex(1), MilestoneApplicationError::MissingTail)]
+        );
+    }
+
+    #[tokio::test]
+    async fn missing_tail_between_applied_milestones() {
+        let tangle = MsTangle::new(ResHandle::new(MockStorage::default()));
+        let bus = Bus::default();
+        let failures = failures(&bus);
+        let milestones = vec![
+            Milestone::new(hash(&"A".repeat(81)), MilestoneIndex(1)),
+            Milestone::new(hash(&"B".repeat(81)), MilestoneIndex(2)),
+            Milestone::new(hash(&"C".repeat(81)), MilestoneIndex(3)),
+        ];
+        let insert = |milestone: &Milestone| {
+            tangle.insert(
+                Transaction::from_trits(&TritBuf::<T1B1Buf>::zeros(TRANSACTION_TRIT_LEN)).unwrap(),
+                milestone.hash,
+                Default::default(),
+            )
+        };
+
+        // The tail of the second milestone is missing.
+        insert(&milestones[0]).await;
+        insert(&milestones[2]).await;
+
+        assert!(apply_milestone(&tangle, &bus, &milestones[0]).is_ok());
+        assert_eq!(
+            apply_milestone(&tangle, &bus, &milestones[1]),
+            Err(MilestoneApplicationError::MissingTail)
+        );
+        assert!(apply_milestone(&tangle, &bus, &milestones[2]).is_ok());
+
+        assert!(tangle.contains_milestone(MilestoneIndex(1)));
+        assert!(!tangle.contains_milestone(MilestoneIndex(2)));
+        assert!(tangle.contains_milestone(MilestoneIndex(3)));
+        assert_eq!(
+            *failures.lock().unwrap(),
+            vec![(MilestoneIndex(2), MilestoneApplicationError::MissingTail)]
+        );
+
+        // Once its tail arrives, the milestone is applied.
+        insert(&milestones[1]).await;
+
+        assert!(apply_milestone(&tangle, &bus, &milestones[1]).is_ok());
+        assert_eq!(tangle.get_milestone_hash(MilestoneIndex(2)), Some(milestones[1].hash));
+        assert_eq!(failures.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn index_already_applied() {
+        let tangle = MsTangle::new(ResHandle::new(MockStorage::default()));
+        let bus = Bus::default();
+        let failures = failures(&bus);
+        let applied = hash(&"A".repeat(81));
+        let milestone = Milestone::new(hash(&"B".repeat(81)), MilestoneIndex(1));
+
+        tangle.add_milestone(MilestoneIndex(1), applied);
+
+        assert_eq!(
+            apply_milestone(&tangle, &bus, &milestone),
+            Err(MilestoneApplicationError::AlreadyApplied)
+        );
+        assert_eq!(tangle.get_milestone_hash(MilestoneIndex(1)), Some(applied));
+        assert_eq!(
+            *failures.lock().unwrap(),
+            vec![(MilestoneIndex(1), MilestoneApplicationError::AlreadyApplied)]
+        );
+    }
+
+    #[tokio::test]
+    async fn same_milestone_applied_again() {
+        let tangle = MsTangle::new(ResHandle::new(MockStorage::default()));
+        let bus = Bus::default();
+        let failures = failures(&bus);
+        let milestone = Milestone::new(hash(&"A".repeat(81)), MilestoneIndex(1));
+
+        tangle.add_milestone(milestone.index, milestone.hash);
+
+        assert!(apply_milestone(&tangle, &bus, &milestone).is_ok());
+        assert_eq!(tangle.get_milestone_hash(MilestoneIndex(1)), Some(milestone.hash));
+        assert!(failures.lock().unwrap().is_empty());
+    }
+
+    fn latest_milestone_changes(bus: &Bus<'static>) -> Arc<Mutex<Vec<(MilestoneIndex, MilestoneIndex, Hash)>>> {
+        let changes = Arc::new(Mutex::new(Vec::new()));
+        let changes_clone = changes.clone();
+
+        bus.add_listener(move |event: &LatestMilestoneChanged| {
+            changes_clone.lock().unwrap().push((event.old, event.new, event.hash))
+        });
+
+        changes
+    }
+
+    #[test]
+    fn latest_milestone_only_advances() {
+        let tangle = MsTangle::new(ResHandle::new(MockStorage::default()));
+        let bus = Bus::default();
+        let changes = latest_milestone_changes(&bus);
+        let (a, b, c) = (hash(&"A".repeat(81)), hash(&"B".repeat(81)), hash(&"C".repeat(81)));
+
+        tangle.update_snapshot_index(MilestoneIndex(1000));
+        tangle.update_latest_milestone_index(MilestoneIndex(1000));
+
+        let mut observed = Vec::new();
+        for milestone in &[
+            Milestone::new(a, MilestoneIndex(1002)),
+            Milestone::new(b, MilestoneIndex(1001)),
+            Milestone::new(c, MilestoneIndex(1003)),
+        ] {
+            update_latest_milestone(&tangle, &bus, milestone);
+            observed.push(tangle.get_latest_milestone_index());
+        }
+
+        assert_eq!(
+            observed,
+            vec![MilestoneIndex(1002), MilestoneIndex(1002), MilestoneIndex(1003)]
+        );
+        assert_eq!(
+            *changes.lock().unwrap(),
+            vec![
+                (MilestoneIndex(1000), MilestoneIndex(1002), a),
+                (MilestoneIndex(1002), MilestoneIndex(1003), c)
+            ]
+        );
+    }
+
+    #[test]
+    fn milestone_below_snapshot_not_latest() {
+        let tangle = MsTangle::new(ResHandle::new(MockStorage::default()));
+        let bus = Bus::default();
+        let changes = latest_milestone_changes(&bus);
+
+        tangle.update_snapshot_index(MilestoneIndex(1000));
+
+        update_latest_milestone(
+            &tangle,
+            &bus,
+            &Milestone::new(hash(&"A".repeat(81)), MilestoneIndex(999)),
+        );
+
+        assert_eq!(tangle.get_latest_milestone_index(), MilestoneIndex(0));
+        assert!(changes.lock().unwrap().is_empty());
+    }
+
+    // Tail of a milestone claiming `index` through its obsolete tag.
+    fn milestone_tail(index: &str) -> Transaction {
+        BundledTransactionBuilder::new()
+            .with_payload(Payload::zeros())
+            .with_address(Address::zeros())
+            .with_value(Value::from_inner_unchecked(0))
+            .with_obsolete_tag(Tag::from_inner_unchecked(
+                TryteBuf::try_from_str(&format!("{:9<27}", index))
+                    .unwrap()
+                    .as_trits()
+                    .encode::<T1B1Buf>(),
+            ))
+            .with_timestamp(Timestamp::from_inner_unchecked(0))
+            .with_index(Index::from_inner_unchecked(0))
+            .with_last_index(Index::from_inner_unchecked(0))
+            .with_tag(Tag::zeros())
+            .with_attachment_ts(Timestamp::from_inner_unchecked(0))
+            .with_bundle(Hash::zeros())
+            .with_trunk(Hash::zeros())
+            .with_branch(Hash::zeros())
+            .with_attachment_lbts(Timestamp::from_inner_unchecked(0))
+            .with_attachment_ubts(Timestamp::from_inner_unchecked(0))
+            .with_nonce(Nonce::zeros())
+            .build()
+            .unwrap()
+    }
+
+    // Validates `tail_hash` with a mock validation returning `outcome` and counting its calls in `validations`.
+    async fn validate(
+        tangle: &MsTangle<MockStorage>,
+        cache: &mut ValidationCache,
+        metrics: &ProtocolMetrics,
+        tail_hash: Hash,
+        outcome: Result<MilestoneIndex, MilestoneValidatorWorkerError>,
+        validations: &AtomicUsize,
+    ) -> Option<Result<Milestone, MilestoneValidatorWorkerError>> {
+        validate_once(tangle, cache, metrics, tail_hash, || async move {
+            validations.fetch_add(1, Ordering::SeqCst);
+            outcome.map(|index| Milestone::new(tail_hash, index))
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn same_milestone_validated_once() {
+        let tangle = MsTangle::new(ResHandle::new(MockStorage::default()));
+        let mut cache = ValidationCache::new(8);
+        let metrics = ProtocolMetrics::new();
+        let validations = AtomicUsize::new(0);
+        let tail_hash = hash(&"A".repeat(81));
+
+        for _ in 0..2 {
+            let milestone = validate(
+                &tangle,
+                &mut cache,
+                &metrics,
+                tail_hash,
+                Ok(MilestoneIndex(1)),
+                &validations,
+            )
+            .await
+            .unwrap()
+            .unwrap();
+
+            assert_eq!(milestone.hash, tail_hash);
+            assert_eq!(milestone.index, MilestoneIndex(1));
+        }
+
+        assert_eq!(validations.load(Ordering::SeqCst), 1);
+        assert_eq!(metrics.skipped_milestone_validations(), 1);
+    }
+
+    #[tokio::test]
+    async fn invalid_milestone_validated_once() {
+        let tangle = MsTangle::new(ResHandle::new(MockStorage::default()));
+        let mut cache = ValidationCache::new(8);
+        let metrics = ProtocolMetrics::new();
+        let validations = AtomicUsize::new(0);
+        let tail_hash = hash(&"A".repeat(81));
+
+        match validate(
+            &tangle,
+            &mut cache,
+            &metrics,
+            tail_hash,
+            Err(MilestoneValidatorWorkerError::InvalidMerkleProof(MilestoneIndex(1))),
+            &validations,
+        )
+        .await
+        {
+            Some(Err(MilestoneValidatorWorkerError::InvalidMerkleProof(MilestoneIndex(1)))) => (),
+            _ => panic!("the first validation should fail"),
+        }
+        assert!(validate(
+            &tangle,
+            &mut cache,
+            &metrics,
+            tail_hash,
+            Ok(MilestoneIndex(1)),
+            &validations
+        )
+        .await
+        .is_none());
+
+        assert_eq!(validations.load(Ordering::SeqCst), 1);
+        assert_eq!(metrics.skipped_milestone_validations(), 1);
+    }
+
+    #[tokio::test]
+    async fn incomplete_bundle_validated_again() {
+        let tangle = MsTangle::new(ResHandle::new(MockStorage::default()));
+        let mut cache = ValidationCache::new(8);
+        let metrics = ProtocolMetrics::new();
+        let validations = AtomicUsize::new(0);
+        let tail_hash = hash(&"A".repeat(81));
+
+        for _ in 0..2 {
+            assert!(matches!(
+                validate(
+                    &tangle,
+                    &mut cache,
+                    &metrics,
+                    tail_hash,
+                    Err(MilestoneValidatorWorkerError::IncompleteBundle),
+                    &validations,
+                )
+                .await,
+                Some(Err(MilestoneValidatorWorkerError::IncompleteBundle))
+            ));
+        }
+
+        assert_eq!(validations.load(Ordering::SeqCst), 2);
+        assert_eq!(metrics.skipped_milestone_validations(), 0);
+    }
+
+    #[tokio::test]
+    async fn replayed_milestone_not_validated() {
+        let tangle = MsTangle::new(ResHandle::new(MockStorage::default()));
+        let mut cache = ValidationCache::new(8);
+        let metrics = ProtocolMetrics::new();
+        let validations = AtomicUsize::new(0);
+        let (applied, replayed, missing) = (hash(&"A".repeat(81)), hash(&"B".repeat(81)), hash(&"C".repeat(81)));
+
+        // Milestone 5 is applied and solid, milestone 4 is missing, as if it was requested again after a crash.
+        tangle.insert(milestone_tail("E"), replayed, Default::default()).await;
+        tangle.insert(milestone_tail("D"), missing, Default::default()).await;
+        tangle.add_milestone(MilestoneIndex(5), applied);
+        tangle.update_latest_solid_milestone_index(MilestoneIndex(10));
+
+        assert!(validate(
+            &tangle,
+            &mut cache,
+            &metrics,
+            replayed,
+            Ok(MilestoneIndex(5)),
+            &validations
+        )
+        .await
+        .is_none());
+        assert_eq!(validations.load(Ordering::SeqCst), 0);
+        assert_eq!(metrics.skipped_milestone_validations(), 1);
+
+        assert!(validate(
+            &tangle,
+            &mut cache,
+            &metrics,
+            missing,
+            Ok(MilestoneIndex(4)),
+            &validations
+        )
+        .await
+        .unwrap()
+        .is_ok());
+        assert_eq!(validations.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn malformed_milestone_bundle_rejected() {
+        let config = ProtocolConfig::build().finish();
+
+        // The bundle hash of the tail doesn't match its essence.
+        match validate_milestone_transactions(&[milestone_tail("A")], &config.coordinator) {
+            Err(MilestoneValidatorWorkerError::InvalidBundle(IncomingBundleBuilderError::InvalidBundleHash)) => (),
+            _ => panic!("the structure of the bundle should be checked first"),
+        }
+    }
+
+    #[test]
+    fn validation_cache_evicts_least_recently_used() {
+        let mut cache = ValidationCache::new(2);
+        let (a, b, c) = (hash(&"A".repeat(81)), hash(&"B".repeat(81)), hash(&"C".repeat(81)));
+
+        cache.insert(a, Some(MilestoneIndex(1)));
+        cache.insert(b, None);
+        assert_eq!(cache.get(&a), Some(Some(MilestoneIndex(1))));
+        cache.insert(c, Some(MilestoneIndex(3)));
+
+        assert_eq!(cache.get(&a), Some(Some(MilestoneIndex(1))));
+        assert_eq!(cache.get(&b), None);
+        assert_eq!(cache.get(&c), Some(Some(MilestoneIndex(3))));
+    }
+
+    #[test]
+    fn error_display_and_source() {
+        let error = MilestoneValidatorWorkerError::InvalidMilestone(MilestoneBuilderError::InvalidCoordinator);
+
+        assert_eq!(
+            error.to_string(),
+            "invalid milestone: milestone bundle is not issued by the coordinator"
+        );
+        assert_eq!(
+            MilestoneValidatorWorkerError::InvalidMerkleProof(MilestoneIndex(7)).to_string(),
+            "invalid Merkle proof for milestone 7"
+        );
+        match error
+            .source()
+            .and_then(|source| source.downcast_ref::<MilestoneBuilderError>())
+        {
+            Some(MilestoneBuilderError::InvalidCoordinator) => (),
+            _ => panic!("the source should be the milestone builder error"),
+        }
+        assert!(MilestoneValidatorWorkerError::UnknownTail.source().is_none());
+    }
+}
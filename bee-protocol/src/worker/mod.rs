@@ -13,10 +13,13 @@
 
 mod broadcaster;
 mod bundle_validator;
+mod channel_monitor;
+mod metrics;
 mod milestone_validator;
 mod peer;
 mod requester;
 mod responder;
+mod revalidator;
 mod solidifier;
 mod status;
 mod storage;
@@ -26,22 +29,125 @@ mod transaction;
 
 pub(crate) use broadcaster::{BroadcasterWorker, BroadcasterWorkerEvent};
 pub(crate) use bundle_validator::{BundleValidatorWorker, BundleValidatorWorkerEvent};
+pub(crate) use channel_monitor::ChannelMonitorWorker;
+pub(crate) use metrics::MetricsWorker;
 pub(crate) use milestone_validator::{MilestoneValidatorWorker, MilestoneValidatorWorkerEvent};
 pub(crate) use peer::{PeerHandshakerWorker, PeerWorker};
 pub(crate) use requester::{
-    MilestoneRequesterWorker, MilestoneRequesterWorkerEvent, TransactionRequesterWorker,
+    request_completed, MilestoneRequesterWorker, MilestoneRequesterWorkerEvent, TransactionRequesterWorker,
     TransactionRequesterWorkerEvent,
 };
 pub(crate) use responder::{
     MilestoneResponderWorker, MilestoneResponderWorkerEvent, TransactionResponderWorker,
     TransactionResponderWorkerEvent,
 };
+pub(crate) use revalidator::RevalidatorWorker;
 pub(crate) use solidifier::{
-    KickstartWorker, MilestoneSolidifierWorker, MilestoneSolidifierWorkerEvent, SolidPropagatorWorker,
-    SolidPropagatorWorkerEvent,
+    update_latest_solid_milestone, KickstartWorker, MilestoneSolidifierWorker, MilestoneSolidifierWorkerEvent,
+    SolidPropagatorWorker, SolidPropagatorWorkerEvent,
 };
 pub(crate) use status::StatusWorker;
 pub use storage::StorageWorker;
 pub use tangle::TangleWorker;
 pub(crate) use tps::TpsWorker;
-pub(crate) use transaction::{HasherWorker, HasherWorkerEvent, ProcessorWorker};
+pub(crate) use transaction::{store_transaction, HasherWorker, HasherWorkerEvent, ProcessorWorker};
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use crate::{
+        config::ProtocolConfig,
+        test_utils::{init_protocol, Graph},
+    };
+
+    use bee_common_ext::{event::Bus, node::validate_worker_order};
+
+    use std::{any::TypeId, sync::Arc};
+
+    // The workers registered by `Protocol::init` with `config`.
+    async fn registered_workers(config: ProtocolConfig) -> Graph {
+        init_protocol(config, Arc::new(Bus::default())).await.1.resolved()
+    }
+
+    async fn protocol_workers() -> Graph {
+        registered_workers(ProtocolConfig::build().finish()).await
+    }
+
+    async fn required_protocol_workers() -> Graph {
+        registered_workers(
+            ProtocolConfig::build()
+                .broadcaster_enabled(false)
+                .responders_enabled(false)
+                .status_enabled(false)
+                .tps_enabled(false)
+                .finish(),
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn workers_start_after_their_dependencies() {
+        let graph = protocol_workers().await;
+        let order = validate_worker_order(&graph.dependencies).unwrap();
+        let position = |id: &TypeId| order.iter().position(|other| other == id).unwrap();
+
+        assert_eq!(order.len(), graph.dependencies.len());
+
+        for (id, dependencies) in graph.dependencies.iter() {
+            for dependency in dependencies.iter() {
+                assert!(
+                    position(dependency) < position(id),
+                    "{} starts before its dependency {}.",
+                    graph.names[id],
+                    graph.names[dependency]
+                );
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn core_workers_start_order() {
+        let graph = protocol_workers().await;
+        let order = validate_worker_order(&graph.dependencies).unwrap();
+        let position = |id: TypeId| order.iter().position(|other| *other == id).unwrap();
+
+        assert!(position(TypeId::of::<StorageWorker>()) < position(TypeId::of::<TangleWorker>()));
+        assert!(position(TypeId::of::<StorageWorker>()) < position(TypeId::of::<MetricsWorker>()));
+        assert!(position(TypeId::of::<TangleWorker>()) < position(TypeId::of::<ProcessorWorker>()));
+        assert!(position(TypeId::of::<TangleWorker>()) < position(TypeId::of::<HasherWorker>()));
+        // Hashed transactions are sent to the processor, which must then already be running.
+        assert!(position(TypeId::of::<ProcessorWorker>()) < position(TypeId::of::<HasherWorker>()));
+        assert!(
+            position(TypeId::of::<MilestoneSolidifierWorker>()) < position(TypeId::of::<MilestoneValidatorWorker>())
+        );
+        assert!(position(TypeId::of::<MilestoneRequesterWorker>()) < position(TypeId::of::<KickstartWorker>()));
+    }
+
+    #[tokio::test]
+    async fn broadcaster_starts_before_its_optional_dependents() {
+        let graph = protocol_workers().await;
+        let order = validate_worker_order(&graph.dependencies).unwrap();
+        let position = |id: TypeId| order.iter().position(|other| *other == id).unwrap();
+
+        assert!(position(TypeId::of::<BroadcasterWorker>()) < position(TypeId::of::<ProcessorWorker>()));
+        assert!(position(TypeId::of::<BroadcasterWorker>()) < position(TypeId::of::<TransactionResponderWorker>()));
+    }
+
+    #[tokio::test]
+    async fn optional_workers_can_be_disabled() {
+        let graph = required_protocol_workers().await;
+        let order = validate_worker_order(&graph.dependencies).unwrap();
+
+        assert_eq!(order.len(), graph.dependencies.len());
+        assert!(!order.contains(&TypeId::of::<BroadcasterWorker>()));
+    }
+
+    #[tokio::test]
+    async fn broadcaster_alone_can_be_disabled() {
+        let graph = registered_workers(ProtocolConfig::build().broadcaster_enabled(false).finish()).await;
+
+        assert!(validate_worker_order(&graph.dependencies).is_ok());
+    }
+}
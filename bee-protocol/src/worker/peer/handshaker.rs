@@ -13,26 +13,32 @@ use crate::{
     config::ProtocolConfig,
     event::HandshakeCompleted,
     message::{
-        messages_supported_version, tlv_from_bytes, tlv_into_bytes, Handshake, Header, Message, MESSAGES_VERSIONS,
+        advertised_versions, has_capability, messages_supported_version, tlv_into_bytes, Handshake, Header,
+        RESPONDER_CAPABILITY,
     },
     peer::Peer,
     protocol::Protocol,
     tangle::MsTangle,
     worker::{
-        peer::message_handler::MessageHandler, HasherWorkerEvent, MilestoneRequesterWorkerEvent,
-        MilestoneResponderWorkerEvent, PeerWorker, TransactionResponderWorkerEvent,
+        peer::{
+            message_handler::MessageHandler,
+            state::{PeerAction, PeerEvent, PeerState, PeerStateMachine, Rejection},
+        },
+        HasherWorkerEvent, MilestoneRequesterWorkerEvent, MilestoneResponderWorkerEvent, PeerWorker,
+        TransactionResponderWorkerEvent,
     },
 };
 
-use bee_common_ext::node::ResHandle;
+use bee_common_ext::{channel, node::ResHandle};
 use bee_network::{Command::SendMessage, Network, Origin};
 use bee_storage::storage::Backend;
 
 use futures::{channel::oneshot, future::FutureExt};
-use log::{error, info, trace, warn};
+use log::{error, info, warn, Level};
 use tokio::spawn;
 
 use std::{
+    fmt,
     net::SocketAddr,
     sync::Arc,
     time::{SystemTime, UNIX_EPOCH},
@@ -48,24 +54,47 @@ pub(crate) enum HandshakeError {
     AlreadyHandshaked,
 }
 
+impl fmt::Display for HandshakeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidTimestampDiff(diff) => write!(f, "timestamps are {}ms apart", diff),
+            Self::CoordinatorMismatch => write!(f, "coordinator does not match"),
+            Self::MwmMismatch(own, peer) => write!(f, "mwm {} does not match the mwm {} of the peer", own, peer),
+            Self::UnsupportedVersion(version) => write!(f, "version {} is not supported", version),
+            Self::PortMismatch(own, peer) => write!(f, "port {} does not match the advertised port {}", own, peer),
+            Self::AlreadyHandshaked => write!(f, "peer is already handshaked"),
+        }
+    }
+}
+
+impl std::error::Error for HandshakeError {}
+
 #[derive(Debug)]
 pub(crate) enum PeerHandshakerWorkerError {}
 
-enum HandshakeStatus {
-    Awaiting,
-    Done,
-    Duplicate,
+/// Checks that the timestamps, in milliseconds, of the node and of a peer are at most `window` seconds apart, whichever
+/// clock is ahead.
+fn validate_timestamp(timestamp: u64, peer_timestamp: u64, window: u64) -> Result<(), HandshakeError> {
+    let diff = (timestamp as i64)
+        .saturating_sub(peer_timestamp as i64)
+        .saturating_abs();
+
+    if diff as u64 > window.saturating_mul(1000) {
+        return Err(HandshakeError::InvalidTimestampDiff(diff));
+    }
+
+    Ok(())
 }
 
 pub struct PeerHandshakerWorker {
     network: Network,
     config: ProtocolConfig,
     peer: Arc<Peer>,
-    status: HandshakeStatus,
-    hasher: flume::Sender<HasherWorkerEvent>,
-    transaction_responder: flume::Sender<TransactionResponderWorkerEvent>,
-    milestone_responder: flume::Sender<MilestoneResponderWorkerEvent>,
-    milestone_requester: flume::Sender<MilestoneRequesterWorkerEvent>,
+    state: PeerStateMachine,
+    hasher: channel::Sender<HasherWorkerEvent>,
+    transaction_responder: Option<channel::Sender<TransactionResponderWorkerEvent>>,
+    milestone_responder: Option<channel::Sender<MilestoneResponderWorkerEvent>>,
+    milestone_requester: channel::Sender<MilestoneRequesterWorkerEvent>,
 }
 
 impl PeerHandshakerWorker {
@@ -73,16 +102,16 @@ impl PeerHandshakerWorker {
         network: Network,
         config: ProtocolConfig,
         peer: Arc<Peer>,
-        hasher: flume::Sender<HasherWorkerEvent>,
-        transaction_responder: flume::Sender<TransactionResponderWorkerEvent>,
-        milestone_responder: flume::Sender<MilestoneResponderWorkerEvent>,
-        milestone_requester: flume::Sender<MilestoneRequesterWorkerEvent>,
+        hasher: channel::Sender<HasherWorkerEvent>,
+        transaction_responder: Option<channel::Sender<TransactionResponderWorkerEvent>>,
+        milestone_responder: Option<channel::Sender<MilestoneResponderWorkerEvent>>,
+        milestone_requester: channel::Sender<MilestoneRequesterWorkerEvent>,
     ) -> Self {
         Self {
             network,
             config,
+            state: PeerStateMachine::new(peer.address),
             peer,
-            status: HandshakeStatus::Awaiting,
             hasher,
             transaction_responder,
             milestone_responder,
@@ -103,6 +132,12 @@ impl PeerHandshakerWorker {
         let receiver_fused = receiver.into_stream();
         let shutdown_fused = shutdown.fuse();
 
+        let capabilities = if self.config.workers.responders_enabled {
+            RESPONDER_CAPABILITY
+        } else {
+            0
+        };
+
         // This is the only message not using a Sender because they are not running yet (awaiting handshake)
         if let Err(e) = self.network.unbounded_send(SendMessage {
             receiver_epid: self.peer.epid,
@@ -110,43 +145,47 @@ impl PeerHandshakerWorker {
                 self.network.config().binding_port,
                 &self.config.coordinator.public_key_bytes,
                 self.config.mwm,
-                &MESSAGES_VERSIONS,
-            )),
+                &advertised_versions(capabilities),
+            ))
+            .into(),
         }) {
             // TODO then what ?
             warn!("[{}] Failed to send handshake: {:?}.", self.peer.address, e);
         }
 
+        self.state.handle(PeerEvent::HandshakeSent, &Protocol::get().metrics);
+
         let mut message_handler = MessageHandler::new(receiver_fused, shutdown_fused, self.peer.address);
 
         while let Some((header, bytes)) = message_handler.fetch_message().await {
             if let Err(e) = self.process_message(&tangle, &header, bytes).await {
                 error!("[{}] Processing message failed: {:?}.", self.peer.address, e);
             }
-            if let HandshakeStatus::Done | HandshakeStatus::Duplicate = self.status {
+            if let PeerState::Ready | PeerState::Duplicate = self.state.state() {
                 break;
             }
         }
 
-        match self.status {
-            HandshakeStatus::Done => {
-                spawn(
-                    PeerWorker::new(
-                        Protocol::get()
-                            .peer_manager
-                            .handshaked_peers
-                            .get(&self.peer.epid)
-                            .unwrap()
-                            .value()
-                            .clone(),
-                        self.hasher,
-                        self.transaction_responder,
-                        self.milestone_responder,
-                    )
-                    .run(tangle.clone(), message_handler),
-                );
-            }
-            HandshakeStatus::Duplicate => {
+        if let PeerState::Ready = self.state.state() {
+            // Handshaked peers are removed by their peer worker when it stops.
+            spawn(
+                PeerWorker::new(
+                    Protocol::get()
+                        .peer_manager
+                        .handshaked_peers
+                        .get(&self.peer.epid)
+                        .unwrap()
+                        .value()
+                        .clone(),
+                    self.state,
+                    self.hasher,
+                    self.transaction_responder,
+                    self.milestone_responder,
+                )
+                .run(tangle.clone(), message_handler),
+            );
+        } else {
+            if let PeerState::Duplicate = self.state.state() {
                 info!("[{}] Closing duplicate connection.", self.peer.epid);
 
                 // TODO: uncomment the following block once we have the epid for which this connection is a duplicate
@@ -165,24 +204,26 @@ impl PeerHandshakerWorker {
                 // if let Err(e) = self.network.unbounded_send(DisconnectEndpoint { epid: self.peer.epid }) {
                 //     warn!("[{}] Disconnecting peer failed: {}.", self.peer.epid, e);
                 // }
+            } else {
+                self.state.handle(PeerEvent::ReadingStopped, &Protocol::get().metrics);
             }
-            _ => (),
+
+            Protocol::get().log_throttle.flush(&self.peer.epid);
+            Protocol::get().peer_manager.remove(&self.peer.epid).await;
+
+            self.state.handle(PeerEvent::Removed, &Protocol::get().metrics);
         }
 
         info!("[{}] Stopped.", self.peer.address);
     }
 
-    pub(crate) fn validate_handshake(&mut self, handshake: Handshake) -> Result<SocketAddr, HandshakeError> {
+    pub(crate) fn validate_handshake(&self, handshake: Handshake) -> Result<SocketAddr, HandshakeError> {
         let timestamp = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .expect("Clock may have gone backwards")
             .as_millis() as u64;
 
-        if ((timestamp - handshake.timestamp) as i64).abs() as u64 > self.config.handshake_window * 1000 {
-            return Err(HandshakeError::InvalidTimestampDiff(
-                ((timestamp - handshake.timestamp) as i64).abs(),
-            ));
-        }
+        validate_timestamp(timestamp, handshake.timestamp, self.config.handshake_window)?;
 
         if !self.config.coordinator.public_key_bytes.eq(&handshake.coordinator) {
             return Err(HandshakeError::CoordinatorMismatch);
@@ -216,7 +257,6 @@ impl PeerHandshakerWorker {
 
         for peer in Protocol::get().peer_manager.handshaked_peers.iter() {
             if peer.address == address {
-                self.status = HandshakeStatus::Duplicate;
                 return Err(HandshakeError::AlreadyHandshaked);
             }
         }
@@ -224,20 +264,31 @@ impl PeerHandshakerWorker {
         Ok(address)
     }
 
+    // Repeated warnings are summarized, a misbehaving peer could otherwise flood the log.
+    fn warn(&self, kind: &'static str, args: std::fmt::Arguments) {
+        Protocol::get()
+            .log_throttle
+            .log(Level::Warn, module_path!(), &self.peer.epid, kind, args);
+    }
+
     async fn process_message<B: Backend>(
         &mut self,
         tangle: &MsTangle<B>,
         header: &Header,
         bytes: &[u8],
     ) -> Result<(), PeerHandshakerWorkerError> {
-        if let Handshake::ID = header.message_type {
-            trace!("[{}] Reading Handshake...", self.peer.address);
-            match tlv_from_bytes::<Handshake>(&header, bytes) {
-                Ok(handshake) => match self.validate_handshake(handshake) {
+        match self.state.on_message(header, bytes) {
+            PeerAction::ValidateHandshake(handshake) => {
+                let serves_requests = has_capability(&handshake.supported_versions, RESPONDER_CAPABILITY);
+
+                match self.validate_handshake(handshake) {
                     Ok(address) => {
                         info!("[{}] Handshake completed.", self.peer.address);
 
-                        Protocol::get().peer_manager.handshake(&self.peer.epid, address).await;
+                        Protocol::get()
+                            .peer_manager
+                            .handshake(&self.peer.epid, address, serves_requests)
+                            .await;
 
                         Protocol::get()
                             .bus
@@ -252,24 +303,84 @@ impl PeerHandshakerWorker {
 
                         Protocol::request_latest_milestone(tangle, &self.milestone_requester, Some(self.peer.epid));
 
-                        self.status = HandshakeStatus::Done;
+                        self.state
+                            .handle(PeerEvent::HandshakeCompleted, &Protocol::get().metrics);
                     }
                     Err(e) => {
-                        warn!("[{}] Handshaking failed: {:?}.", self.peer.address, e);
-                    }
-                },
-                Err(e) => {
-                    warn!("[{}] Reading Handshake failed: {:?}.", self.peer.address, e);
+                        self.warn(
+                            "failed handshake",
+                            format_args!("[{}] Handshaking failed: {}.", self.peer.address, e),
+                        );
 
-                    Protocol::get().metrics.invalid_messages_inc();
+                        if let HandshakeError::AlreadyHandshaked = e {
+                            self.state
+                                .handle(PeerEvent::DuplicateDetected, &Protocol::get().metrics);
+                        }
+                    }
                 }
             }
-        } else {
-            warn!("[{}] Ignoring messages until fully handshaked.", self.peer.address);
-
-            Protocol::get().metrics.invalid_messages_inc();
+            PeerAction::Reject(rejection) => self.reject(rejection),
+            // Not returned before the handshake is completed.
+            PeerAction::ForwardMilestoneRequest(_)
+            | PeerAction::ForwardTransaction(_)
+            | PeerAction::ForwardTransactionRequest(_)
+            | PeerAction::ApplyHeartbeat(_)
+            | PeerAction::Drop
+            | PeerAction::Disconnect => {}
         }
 
         Ok(())
     }
+
+    fn reject(&self, rejection: Rejection) {
+        self.warn(rejection.kind(), format_args!("[{}] {}", self.peer.address, rejection));
+
+        Protocol::get().metrics.invalid_messages_inc();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    const NOW: u64 = 1_600_000_000_000;
+    const WINDOW: u64 = 10;
+
+    #[test]
+    fn timestamp_within_window() {
+        assert!(validate_timestamp(NOW, NOW, WINDOW).is_ok());
+        assert!(validate_timestamp(NOW, NOW - 5_000, WINDOW).is_ok());
+        assert!(validate_timestamp(NOW, NOW + 5_000, WINDOW).is_ok());
+    }
+
+    #[test]
+    fn timestamp_on_window_edge() {
+        assert!(validate_timestamp(NOW, NOW - 10_000, WINDOW).is_ok());
+        assert!(validate_timestamp(NOW, NOW + 10_000, WINDOW).is_ok());
+    }
+
+    #[test]
+    fn timestamp_outside_window() {
+        assert!(matches!(
+            validate_timestamp(NOW, NOW - 11_000, WINDOW),
+            Err(HandshakeError::InvalidTimestampDiff(11_000))
+        ));
+        assert!(matches!(
+            validate_timestamp(NOW, NOW + 11_000, WINDOW),
+            Err(HandshakeError::InvalidTimestampDiff(11_000))
+        ));
+    }
+
+    #[test]
+    fn error_display() {
+        assert_eq!(
+            HandshakeError::InvalidTimestampDiff(11_000).to_string(),
+            "timestamps are 11000ms apart"
+        );
+        assert_eq!(
+            HandshakeError::MwmMismatch(14, 9).to_string(),
+            "mwm 14 does not match the mwm 9 of the peer"
+        );
+    }
 }
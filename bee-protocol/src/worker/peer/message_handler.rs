@@ -8,7 +8,10 @@
 // Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
 // an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and limitations under the License.
-use crate::message::{Header, HEADER_SIZE};
+use crate::{
+    message::{tlv_check_length, Header, HEADER_SIZE},
+    protocol::Protocol,
+};
 
 use futures::{
     channel::oneshot,
@@ -17,7 +20,7 @@ use futures::{
     stream::StreamExt,
 };
 
-use log::trace;
+use log::{trace, warn};
 
 use std::net::SocketAddr;
 
@@ -62,7 +65,7 @@ impl MessageHandler {
     }
     /// Fetch the header and payload of a message.
     ///
-    /// This method only returns `None` if a shutdown signal is received.
+    /// This method only returns `None` if a shutdown signal is received or if a header can't be read.
     pub(super) async fn fetch_message(&mut self) -> Option<(Header, &[u8])> {
         // loop until we can return the header and payload
         loop {
@@ -75,7 +78,19 @@ impl MessageHandler {
                         .fetch_bytes_or_shutdown(&mut self.shutdown, HEADER_SIZE)
                         .await?;
                     trace!("[{}] Reading Header...", self.address);
-                    let header = Header::from_bytes(bytes);
+                    let header = match Header::from_bytes(bytes) {
+                        Ok(header) => header,
+                        Err(e) => {
+                            warn!("[{}] Reading Header failed: {:?}.", self.address, e);
+                            return None;
+                        }
+                    };
+                    // A malicious peer could advertise a huge length to make us allocate the buffer.
+                    if let Err(e) = tlv_check_length(&header) {
+                        warn!("[{}] Reading Header failed: {:?}.", self.address, e);
+                        Protocol::get().metrics.invalid_messages_inc();
+                        return None;
+                    }
                     // Now we are ready to read a payload.
                     self.state = ReadState::Payload(header);
                 }
@@ -12,6 +12,7 @@
 mod handshaker;
 mod message_handler;
 mod peer;
+mod state;
 
 pub(crate) use handshaker::PeerHandshakerWorker;
 pub(crate) use peer::PeerWorker;
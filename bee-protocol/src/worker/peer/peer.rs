@@ -10,48 +10,63 @@
 // See the License for the specific language governing permissions and limitations under the License.
 
 use crate::{
-    message::{
-        tlv_from_bytes, Header, Heartbeat, Message, MilestoneRequest, Transaction as TransactionMessage,
-        TransactionRequest,
-    },
+    event::PeerRemoved,
+    message::Header,
     milestone::MilestoneIndex,
     peer::HandshakedPeer,
     protocol::Protocol,
     tangle::MsTangle,
     worker::{
-        peer::message_handler::MessageHandler, HasherWorkerEvent, MilestoneResponderWorkerEvent,
-        TransactionResponderWorkerEvent,
+        peer::{
+            message_handler::MessageHandler,
+            state::{PeerAction, PeerEvent, PeerStateMachine},
+        },
+        HasherWorkerEvent, MilestoneResponderWorkerEvent, TransactionResponderWorkerEvent,
     },
 };
 
-use bee_common_ext::node::ResHandle;
+use bee_common_ext::{channel, node::ResHandle};
 use bee_storage::storage::Backend;
 
-use log::{error, info, trace, warn};
+use log::{error, info, Level};
 
-use std::sync::Arc;
+use std::{fmt, sync::Arc};
 
 #[derive(Debug)]
 pub(crate) enum PeerWorkerError {
     FailedSend,
 }
 
+impl fmt::Display for PeerWorkerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::FailedSend => write!(f, "forwarding message to a worker failed"),
+        }
+    }
+}
+
+impl std::error::Error for PeerWorkerError {}
+
 pub struct PeerWorker {
     peer: Arc<HandshakedPeer>,
-    hasher: flume::Sender<HasherWorkerEvent>,
-    transaction_responder: flume::Sender<TransactionResponderWorkerEvent>,
-    milestone_responder: flume::Sender<MilestoneResponderWorkerEvent>,
+    state: PeerStateMachine,
+    hasher: channel::Sender<HasherWorkerEvent>,
+    // Requests are dropped if the responders are disabled.
+    transaction_responder: Option<channel::Sender<TransactionResponderWorkerEvent>>,
+    milestone_responder: Option<channel::Sender<MilestoneResponderWorkerEvent>>,
 }
 
 impl PeerWorker {
     pub(crate) fn new(
         peer: Arc<HandshakedPeer>,
-        hasher: flume::Sender<HasherWorkerEvent>,
-        transaction_responder: flume::Sender<TransactionResponderWorkerEvent>,
-        milestone_responder: flume::Sender<MilestoneResponderWorkerEvent>,
+        state: PeerStateMachine,
+        hasher: channel::Sender<HasherWorkerEvent>,
+        transaction_responder: Option<channel::Sender<TransactionResponderWorkerEvent>>,
+        milestone_responder: Option<channel::Sender<MilestoneResponderWorkerEvent>>,
     ) -> Self {
         Self {
             peer,
+            state,
             hasher,
             transaction_responder,
             milestone_responder,
@@ -63,13 +78,28 @@ impl PeerWorker {
 
         while let Some((header, bytes)) = message_handler.fetch_message().await {
             if let Err(e) = self.process_message(&tangle, &header, bytes) {
-                error!("[{}] Processing message failed: {:?}.", self.peer.address, e);
+                error!("[{}] Processing message failed: {}.", self.peer.address, e);
             }
         }
 
+        self.state.handle(PeerEvent::ReadingStopped, &Protocol::get().metrics);
+
         info!("[{}] Stopped.", self.peer.address);
 
-        Protocol::get().peer_manager.remove(&self.peer.epid).await;
+        Protocol::get().log_throttle.flush(&self.peer.epid);
+
+        if Protocol::get().peer_manager.remove(&self.peer.epid).await.is_some() {
+            Protocol::get().bus.dispatch(PeerRemoved(self.peer.epid));
+        }
+
+        self.state.handle(PeerEvent::Removed, &Protocol::get().metrics);
+    }
+
+    // Repeated warnings are summarized, a misbehaving peer could otherwise flood the log.
+    fn warn(&self, kind: &'static str, args: std::fmt::Arguments) {
+        Protocol::get()
+            .log_throttle
+            .log(Level::Warn, module_path!(), &self.peer.epid, kind, args);
     }
 
     fn process_message<B: Backend>(
@@ -78,116 +108,77 @@ impl PeerWorker {
         header: &Header,
         bytes: &[u8],
     ) -> Result<(), PeerWorkerError> {
-        match header.message_type {
-            MilestoneRequest::ID => {
-                trace!("[{}] Reading MilestoneRequest...", self.peer.address);
-                match tlv_from_bytes::<MilestoneRequest>(&header, bytes) {
-                    Ok(message) => {
-                        self.milestone_responder
-                            .send(MilestoneResponderWorkerEvent {
-                                epid: self.peer.epid,
-                                request: message,
-                            })
-                            .map_err(|_| PeerWorkerError::FailedSend)?;
-
-                        self.peer.metrics.milestone_requests_received_inc();
-                        Protocol::get().metrics.milestone_requests_received_inc();
-                    }
-                    Err(e) => {
-                        warn!("[{}] Reading MilestoneRequest failed: {:?}.", self.peer.address, e);
-
-                        self.peer.metrics.invalid_messages_inc();
-                        Protocol::get().metrics.invalid_messages_inc();
-                    }
+        let action = self.state.on_message(header, bytes);
+
+        action.record(&Protocol::get().metrics);
+
+        match action {
+            PeerAction::ForwardMilestoneRequest(message) => {
+                if let Some(milestone_responder) = &self.milestone_responder {
+                    milestone_responder
+                        .send(MilestoneResponderWorkerEvent {
+                            epid: self.peer.epid,
+                            request: message,
+                        })
+                        .map_err(|_| PeerWorkerError::FailedSend)?;
                 }
+
+                self.peer.metrics.milestone_requests_received_inc();
             }
-            TransactionMessage::ID => {
-                trace!("[{}] Reading TransactionMessage...", self.peer.address);
-                match tlv_from_bytes::<TransactionMessage>(&header, bytes) {
-                    Ok(message) => {
-                        self.hasher
-                            .send(HasherWorkerEvent {
-                                from: self.peer.epid,
-                                transaction_message: message,
-                            })
-                            .map_err(|_| PeerWorkerError::FailedSend)?;
-
-                        self.peer.metrics.transactions_received_inc();
-                        Protocol::get().metrics.transactions_received_inc();
-                    }
-                    Err(e) => {
-                        warn!("[{}] Reading TransactionMessage failed: {:?}.", self.peer.address, e);
-
-                        self.peer.metrics.invalid_messages_inc();
-                        Protocol::get().metrics.invalid_messages_inc();
-                    }
-                }
+            PeerAction::ForwardTransaction(message) => {
+                self.hasher
+                    .send(HasherWorkerEvent {
+                        from: Some(self.peer.epid),
+                        transaction_message: message,
+                    })
+                    .map_err(|_| PeerWorkerError::FailedSend)?;
+
+                self.peer.metrics.transactions_received_inc();
             }
-            TransactionRequest::ID => {
-                trace!("[{}] Reading TransactionRequest...", self.peer.address);
-                match tlv_from_bytes::<TransactionRequest>(&header, bytes) {
-                    Ok(message) => {
-                        self.transaction_responder
-                            .send(TransactionResponderWorkerEvent {
-                                epid: self.peer.epid,
-                                request: message,
-                            })
-                            .map_err(|_| PeerWorkerError::FailedSend)?;
-
-                        self.peer.metrics.transaction_requests_received_inc();
-                        Protocol::get().metrics.transaction_requests_received_inc();
-                    }
-                    Err(e) => {
-                        warn!("[{}] Reading TransactionRequest failed: {:?}.", self.peer.address, e);
-
-                        self.peer.metrics.invalid_messages_inc();
-                        Protocol::get().metrics.invalid_messages_inc();
-                    }
+            PeerAction::ForwardTransactionRequest(message) => {
+                if let Some(transaction_responder) = &self.transaction_responder {
+                    transaction_responder
+                        .send(TransactionResponderWorkerEvent {
+                            epid: self.peer.epid,
+                            request: message,
+                        })
+                        .map_err(|_| PeerWorkerError::FailedSend)?;
                 }
+
+                self.peer.metrics.transaction_requests_received_inc();
             }
-            Heartbeat::ID => {
-                trace!("[{}] Reading Heartbeat...", self.peer.address);
-                match tlv_from_bytes::<Heartbeat>(&header, bytes) {
-                    Ok(message) => {
-                        self.peer
-                            .set_latest_solid_milestone_index(message.latest_solid_milestone_index.into());
-                        self.peer.set_pruned_index(message.pruned_index.into());
-                        self.peer
-                            .set_latest_milestone_index(message.latest_milestone_index.into());
-                        self.peer.set_connected_peers(message.connected_peers);
-                        self.peer.set_synced_peers(message.synced_peers);
-
-                        if !tangle.is_synced_threshold(2)
-                            && !self
-                                .peer
-                                .has_data(MilestoneIndex(*tangle.get_latest_solid_milestone_index() + 1))
-                        {
-                            warn!("The peer {} can't help syncing.", self.peer.address);
-                            // TODO drop if autopeered.
-                        }
-
-                        // Also drop connection if autopeered and we can't help it sync
-
-                        self.peer.metrics.heartbeats_received_inc();
-                        Protocol::get().metrics.heartbeats_received_inc();
-                    }
-                    Err(e) => {
-                        warn!("[{}] Reading Heartbeat failed: {:?}.", self.peer.address, e);
-
-                        self.peer.metrics.invalid_messages_inc();
-                        Protocol::get().metrics.invalid_messages_inc();
-                    }
+            PeerAction::ApplyHeartbeat(message) => {
+                self.peer
+                    .set_latest_solid_milestone_index(message.latest_solid_milestone_index.into());
+                self.peer.set_pruned_index(message.pruned_index.into());
+                self.peer
+                    .set_latest_milestone_index(message.latest_milestone_index.into());
+                self.peer.set_connected_peers(message.connected_peers);
+                self.peer.set_synced_peers(message.synced_peers);
+
+                if !tangle.is_synced_threshold(2)
+                    && !self
+                        .peer
+                        .has_data(tangle.get_latest_solid_milestone_index() + MilestoneIndex::from(1))
+                {
+                    self.warn(
+                        "unhelpful for syncing",
+                        format_args!("The peer {} can't help syncing.", self.peer.address),
+                    );
+                    // TODO drop if autopeered.
                 }
+
+                // Also drop connection if autopeered and we can't help it sync
+
+                self.peer.metrics.heartbeats_received_inc();
             }
-            _ => {
-                warn!(
-                    "[{}] Ignoring unsupported message type: {}.",
-                    self.peer.address, header.message_type
-                );
+            PeerAction::Reject(rejection) => {
+                self.warn(rejection.kind(), format_args!("[{}] {}", self.peer.address, rejection));
 
                 self.peer.metrics.invalid_messages_inc();
-                Protocol::get().metrics.invalid_messages_inc();
             }
+            // Not returned once the handshake is completed.
+            PeerAction::ValidateHandshake(_) | PeerAction::Drop | PeerAction::Disconnect => {}
         };
 
         Ok(())
@@ -0,0 +1,484 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+//! State machine of the connection to a peer, driven by the peer handshaker and peer workers.
+
+use crate::{
+    message::{
+        tlv_from_bytes, Handshake, Header, Heartbeat, Message, MilestoneRequest, TlvError,
+        Transaction as TransactionMessage, TransactionRequest,
+    },
+    protocol::ProtocolMetrics,
+};
+
+use log::{debug, warn};
+
+use std::{fmt, net::SocketAddr};
+
+/// State of the connection to a peer.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum PeerState {
+    /// The connection is established, our handshake is not sent yet.
+    Connected,
+    /// Our handshake is sent, the one of the peer is awaited.
+    Handshaking,
+    /// The handshake is completed, the messages of the peer are processed.
+    Ready,
+    /// No more messages are read from the peer, which is being removed.
+    Draining,
+    /// The peer is removed.
+    Closed,
+    /// The peer is already handshaked through another connection.
+    Duplicate,
+}
+
+/// Event reported by the workers driving the state machine.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum PeerEvent {
+    /// Our handshake was sent to the peer.
+    HandshakeSent,
+    /// The handshake of the peer was validated.
+    HandshakeCompleted,
+    /// The handshake of the peer was validated but the peer is already handshaked.
+    DuplicateDetected,
+    /// No more messages are read from the peer, either because of a shutdown or of an unreadable header.
+    ReadingStopped,
+    /// The peer was removed from the peer manager.
+    Removed,
+}
+
+/// Reason for dropping a message of the peer as invalid.
+pub(crate) enum Rejection {
+    /// A message other than a handshake was received before the handshake completed.
+    BeforeHandshake,
+    /// The message type is not supported once handshaked.
+    UnsupportedType(u8),
+    /// The message, of the given type, could not be read.
+    Invalid(u8, TlvError),
+}
+
+impl Rejection {
+    /// Kind under which repeated rejections are summarized in the log.
+    pub(crate) fn kind(&self) -> &'static str {
+        match self {
+            Rejection::BeforeHandshake => "message before handshake",
+            Rejection::UnsupportedType(_) => "unsupported message type",
+            Rejection::Invalid(Handshake::ID, _) => "invalid handshake",
+            Rejection::Invalid(MilestoneRequest::ID, _) => "invalid milestone request",
+            Rejection::Invalid(TransactionMessage::ID, _) => "invalid transaction message",
+            Rejection::Invalid(TransactionRequest::ID, _) => "invalid transaction request",
+            Rejection::Invalid(Heartbeat::ID, _) => "invalid heartbeat",
+            Rejection::Invalid(_, _) => "invalid message",
+        }
+    }
+}
+
+impl fmt::Display for Rejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = |message_type: u8| match message_type {
+            Handshake::ID => "Handshake",
+            MilestoneRequest::ID => "MilestoneRequest",
+            TransactionMessage::ID => "TransactionMessage",
+            TransactionRequest::ID => "TransactionRequest",
+            Heartbeat::ID => "Heartbeat",
+            _ => "message",
+        };
+
+        match self {
+            Rejection::BeforeHandshake => write!(f, "Ignoring messages until fully handshaked."),
+            Rejection::UnsupportedType(message_type) => {
+                write!(f, "Ignoring unsupported message type: {}.", message_type)
+            }
+            Rejection::Invalid(message_type, e) => write!(f, "Reading {} failed: {}.", name(*message_type), e),
+        }
+    }
+}
+
+/// What the driving worker has to do with a message of the peer.
+pub(crate) enum PeerAction {
+    /// Validate the handshake and report the outcome as a `PeerEvent`.
+    ValidateHandshake(Handshake),
+    /// Forward the milestone request to the milestone responder.
+    ForwardMilestoneRequest(MilestoneRequest),
+    /// Forward the transaction to the hasher.
+    ForwardTransaction(TransactionMessage),
+    /// Forward the transaction request to the transaction responder.
+    ForwardTransactionRequest(TransactionRequest),
+    /// Update the view of the peer with its heartbeat.
+    ApplyHeartbeat(Heartbeat),
+    /// Drop the message and count it as invalid.
+    Reject(Rejection),
+    /// Drop the message, the peer is being removed.
+    Drop,
+    /// Drop the message and stop reading from the peer.
+    Disconnect,
+}
+
+impl PeerAction {
+    /// Counts the message that led to this action in `metrics`.
+    pub(crate) fn record(&self, metrics: &ProtocolMetrics) {
+        match self {
+            PeerAction::ForwardMilestoneRequest(_) => {
+                metrics.milestone_requests_received_inc();
+            }
+            PeerAction::ForwardTransaction(_) => {
+                metrics.transactions_received_inc();
+            }
+            PeerAction::ForwardTransactionRequest(_) => {
+                metrics.transaction_requests_received_inc();
+            }
+            PeerAction::ApplyHeartbeat(_) => {
+                metrics.heartbeats_received_inc();
+            }
+            PeerAction::Reject(_) => {
+                metrics.invalid_messages_inc();
+            }
+            PeerAction::ValidateHandshake(_) | PeerAction::Drop | PeerAction::Disconnect => {}
+        }
+    }
+}
+
+/// Returns the state reached from `state` on `event`, `None` if `event` is unexpected in `state`.
+fn next_state(state: PeerState, event: PeerEvent) -> Option<PeerState> {
+    match (state, event) {
+        (PeerState::Connected, PeerEvent::HandshakeSent) => Some(PeerState::Handshaking),
+        (PeerState::Handshaking, PeerEvent::HandshakeCompleted) => Some(PeerState::Ready),
+        (PeerState::Handshaking, PeerEvent::DuplicateDetected) => Some(PeerState::Duplicate),
+        (PeerState::Connected, PeerEvent::ReadingStopped)
+        | (PeerState::Handshaking, PeerEvent::ReadingStopped)
+        | (PeerState::Ready, PeerEvent::ReadingStopped) => Some(PeerState::Draining),
+        (PeerState::Draining, PeerEvent::Removed) | (PeerState::Duplicate, PeerEvent::Removed) => {
+            Some(PeerState::Closed)
+        }
+        _ => None,
+    }
+}
+
+fn read<M: Message>(header: &Header, bytes: &[u8], action: fn(M) -> PeerAction) -> PeerAction {
+    match tlv_from_bytes::<M>(header, bytes) {
+        Ok(message) => action(message),
+        Err(e) => PeerAction::Reject(Rejection::Invalid(M::ID, e)),
+    }
+}
+
+/// State machine of the connection to a peer.
+///
+/// Messages are given to `on_message` which tells what to do with them, the other events are reported with `handle`.
+pub(crate) struct PeerStateMachine {
+    address: SocketAddr,
+    state: PeerState,
+}
+
+impl PeerStateMachine {
+    pub(crate) fn new(address: SocketAddr) -> Self {
+        Self {
+            address,
+            state: PeerState::Connected,
+        }
+    }
+
+    pub(crate) fn state(&self) -> PeerState {
+        self.state
+    }
+
+    /// Moves to the state reached on `event`. Unexpected events are logged and leave the state unchanged.
+    pub(crate) fn handle(&mut self, event: PeerEvent, metrics: &ProtocolMetrics) {
+        match next_state(self.state, event) {
+            Some(state) => {
+                debug!("[{}] {:?} -> {:?} on {:?}.", self.address, self.state, state, event);
+                self.state = state;
+                metrics.peer_state_transitions_inc();
+            }
+            None => warn!("[{}] Unexpected {:?} in state {:?}.", self.address, event, self.state),
+        }
+    }
+
+    /// Reads the message made of `header` and `bytes` and returns what to do with it in the current state.
+    pub(crate) fn on_message(&self, header: &Header, bytes: &[u8]) -> PeerAction {
+        match self.state {
+            PeerState::Connected | PeerState::Handshaking => match header.message_type {
+                Handshake::ID => read(header, bytes, PeerAction::ValidateHandshake),
+                _ => PeerAction::Reject(Rejection::BeforeHandshake),
+            },
+            PeerState::Ready => match header.message_type {
+                MilestoneRequest::ID => read(header, bytes, PeerAction::ForwardMilestoneRequest),
+                TransactionMessage::ID => read(header, bytes, PeerAction::ForwardTransaction),
+                TransactionRequest::ID => read(header, bytes, PeerAction::ForwardTransactionRequest),
+                Heartbeat::ID => read(header, bytes, PeerAction::ApplyHeartbeat),
+                message_type => PeerAction::Reject(Rejection::UnsupportedType(message_type)),
+            },
+            PeerState::Draining => PeerAction::Drop,
+            PeerState::Closed | PeerState::Duplicate => PeerAction::Disconnect,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{PeerEvent::*, PeerState::*, *};
+
+    use crate::message::{tlv_into_bytes, HEADER_SIZE};
+
+    use bee_transaction::bundled::constants::{HASH_BYTE_LEN, NON_PAYLOAD_BYTE_LEN};
+
+    const STATES: [PeerState; 6] = [Connected, Handshaking, Ready, Draining, Closed, Duplicate];
+    const EVENTS: [PeerEvent; 5] = [
+        HandshakeSent,
+        HandshakeCompleted,
+        DuplicateDetected,
+        ReadingStopped,
+        Removed,
+    ];
+
+    fn machine(state: PeerState) -> PeerStateMachine {
+        PeerStateMachine {
+            address: "127.0.0.1:15600".parse().unwrap(),
+            state,
+        }
+    }
+
+    fn message<M: Message>(message: M) -> (Header, Vec<u8>) {
+        let bytes = tlv_into_bytes(message);
+
+        (
+            Header::from_bytes(&bytes[..HEADER_SIZE]).unwrap(),
+            bytes[HEADER_SIZE..].to_vec(),
+        )
+    }
+
+    fn handshake() -> (Header, Vec<u8>) {
+        message(Handshake::new(15600, &[0; HASH_BYTE_LEN], 14, &[1]))
+    }
+
+    fn transaction() -> (Header, Vec<u8>) {
+        message(TransactionMessage::new(&[0; NON_PAYLOAD_BYTE_LEN]))
+    }
+
+    fn heartbeat() -> (Header, Vec<u8>) {
+        message(Heartbeat::default())
+    }
+
+    fn unknown() -> (Header, Vec<u8>) {
+        (
+            Header {
+                message_type: 0xff,
+                message_length: 0,
+            },
+            Vec::new(),
+        )
+    }
+
+    fn action(machine: &PeerStateMachine, (header, bytes): (Header, Vec<u8>)) -> &'static str {
+        match machine.on_message(&header, &bytes) {
+            PeerAction::ValidateHandshake(_) => "validate handshake",
+            PeerAction::ForwardMilestoneRequest(_) => "forward milestone request",
+            PeerAction::ForwardTransaction(_) => "forward transaction",
+            PeerAction::ForwardTransactionRequest(_) => "forward transaction request",
+            PeerAction::ApplyHeartbeat(_) => "apply heartbeat",
+            PeerAction::Reject(rejection) => rejection.kind(),
+            PeerAction::Drop => "drop",
+            PeerAction::Disconnect => "disconnect",
+        }
+    }
+
+    #[test]
+    fn every_transition() {
+        let transitions = [
+            (Connected, HandshakeSent, Handshaking),
+            (Connected, ReadingStopped, Draining),
+            (Handshaking, HandshakeCompleted, Ready),
+            (Handshaking, DuplicateDetected, Duplicate),
+            (Handshaking, ReadingStopped, Draining),
+            (Ready, ReadingStopped, Draining),
+            (Draining, Removed, Closed),
+            (Duplicate, Removed, Closed),
+        ];
+
+        for &from in STATES.iter() {
+            for &event in EVENTS.iter() {
+                let metrics = ProtocolMetrics::new();
+                let mut machine = machine(from);
+                let expected = transitions
+                    .iter()
+                    .find(|(state, on, _)| *state == from && *on == event)
+                    .map(|(_, _, to)| *to);
+
+                machine.handle(event, &metrics);
+
+                match expected {
+                    Some(to) => {
+                        assert_eq!(machine.state(), to, "{:?} on {:?}", from, event);
+                        assert_eq!(metrics.peer_state_transitions(), 1);
+                    }
+                    // Unexpected events leave the state unchanged.
+                    None => {
+                        assert_eq!(machine.state(), from, "{:?} on {:?}", from, event);
+                        assert_eq!(metrics.peer_state_transitions(), 0);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn every_message_in_every_state() {
+        let before_handshake = [
+            "validate handshake",
+            "message before handshake",
+            "message before handshake",
+            "message before handshake",
+            "message before handshake",
+            "message before handshake",
+        ];
+        let table = [
+            (Connected, before_handshake),
+            (Handshaking, before_handshake),
+            (
+                Ready,
+                [
+                    "unsupported message type",
+                    "forward milestone request",
+                    "forward transaction",
+                    "forward transaction request",
+                    "apply heartbeat",
+                    "unsupported message type",
+                ],
+            ),
+            (Draining, ["drop"; 6]),
+            (Closed, ["disconnect"; 6]),
+            (Duplicate, ["disconnect"; 6]),
+        ];
+
+        for (state, expected) in table.iter() {
+            let machine = machine(*state);
+            let actions = [
+                action(&machine, handshake()),
+                action(&machine, message(MilestoneRequest::default())),
+                action(&machine, transaction()),
+                action(&machine, message(TransactionRequest::default())),
+                action(&machine, heartbeat()),
+                action(&machine, unknown()),
+            ];
+
+            assert_eq!(&actions, expected, "{:?}", state);
+        }
+    }
+
+    #[test]
+    fn invalid_messages_rejected() {
+        let (mut header, bytes) = transaction();
+        header.message_length -= 1;
+
+        assert_eq!(
+            action(&machine(Ready), (header, bytes[1..].to_vec())),
+            "invalid transaction message"
+        );
+
+        let (mut header, bytes) = heartbeat();
+        header.message_length += 1;
+
+        assert_eq!(action(&machine(Ready), (header, bytes)), "invalid heartbeat");
+
+        let (mut header, bytes) = handshake();
+        header.message_length += 1;
+
+        assert_eq!(action(&machine(Handshaking), (header, bytes)), "invalid handshake");
+    }
+
+    #[test]
+    fn rejections_logged_as_before() {
+        assert_eq!(
+            Rejection::BeforeHandshake.to_string(),
+            "Ignoring messages until fully handshaked."
+        );
+        assert_eq!(
+            Rejection::UnsupportedType(0xff).to_string(),
+            "Ignoring unsupported message type: 255."
+        );
+        assert_eq!(
+            Rejection::Invalid(Heartbeat::ID, TlvError::InvalidLength(0)).to_string(),
+            "Reading Heartbeat failed: length 0 is out of the size range of the message."
+        );
+    }
+
+    // The flow of a peer as driven by the peer handshaker and then the peer worker.
+    #[test]
+    fn handshake_then_ready() {
+        let metrics = ProtocolMetrics::new();
+        let mut machine = machine(Connected);
+
+        machine.handle(HandshakeSent, &metrics);
+        assert_eq!(action(&machine, transaction()), "message before handshake");
+        assert_eq!(action(&machine, handshake()), "validate handshake");
+
+        machine.handle(HandshakeCompleted, &metrics);
+        assert_eq!(machine.state(), Ready);
+        assert_eq!(action(&machine, transaction()), "forward transaction");
+        assert_eq!(action(&machine, heartbeat()), "apply heartbeat");
+        assert_eq!(action(&machine, handshake()), "unsupported message type");
+
+        machine.handle(ReadingStopped, &metrics);
+        machine.handle(Removed, &metrics);
+        assert_eq!(machine.state(), Closed);
+        assert_eq!(metrics.peer_state_transitions(), 4);
+    }
+
+    #[test]
+    fn duplicate_connection() {
+        let metrics = ProtocolMetrics::new();
+        let mut machine = machine(Connected);
+
+        machine.handle(HandshakeSent, &metrics);
+        assert_eq!(action(&machine, handshake()), "validate handshake");
+
+        machine.handle(DuplicateDetected, &metrics);
+        assert_eq!(machine.state(), Duplicate);
+        assert_eq!(action(&machine, transaction()), "disconnect");
+
+        // The handshaker stops reading without draining, the messages of a duplicate are not processed.
+        machine.handle(ReadingStopped, &metrics);
+        assert_eq!(machine.state(), Duplicate);
+
+        machine.handle(Removed, &metrics);
+        assert_eq!(machine.state(), Closed);
+        assert_eq!(metrics.peer_state_transitions(), 3);
+    }
+
+    #[test]
+    fn metrics_by_type() {
+        let metrics = ProtocolMetrics::new();
+        let machine = machine(Ready);
+
+        assert!(metrics.metrics_by_type().values().all(|count| *count == 0));
+
+        for (header, bytes) in vec![
+            heartbeat(),
+            transaction(),
+            message(TransactionRequest::default()),
+            transaction(),
+            heartbeat(),
+            transaction(),
+            unknown(),
+        ] {
+            machine.on_message(&header, &bytes).record(&metrics);
+        }
+
+        let by_type = metrics.metrics_by_type();
+
+        assert_eq!(by_type.len(), 4);
+        assert_eq!(by_type["milestone_request"], 0);
+        assert_eq!(by_type["transaction"], 3);
+        assert_eq!(by_type["transaction_request"], 1);
+        assert_eq!(by_type["heartbeat"], 2);
+        assert_eq!(metrics.invalid_messages(), 1);
+    }
+}
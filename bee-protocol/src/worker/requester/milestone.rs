@@ -12,22 +12,27 @@
 use crate::{
     message::MilestoneRequest,
     milestone::MilestoneIndex,
+    peer::HandshakedPeer,
     protocol::{Protocol, Sender},
     tangle::MsTangle,
-    worker::TangleWorker,
+    worker::{
+        requester::selection::{self, handshaked_peers, request_sent, request_timed_out, round_robin},
+        TangleWorker,
+    },
 };
 
 use bee_common::{shutdown_stream::ShutdownStream, worker::Error as WorkerError};
-use bee_common_ext::{node::Node, worker::Worker};
+use bee_common_ext::{channel, node::Node, worker::Worker};
 use bee_network::EndpointId;
 
 use async_trait::async_trait;
 use futures::{select, StreamExt};
-use log::{debug, info};
+use log::{debug, info, trace};
 use tokio::time::interval;
 
 use std::{
     any::TypeId,
+    sync::Arc,
     time::{Duration, Instant},
 };
 
@@ -36,63 +41,103 @@ const RETRY_INTERVAL_SECS: u64 = 5;
 pub(crate) struct MilestoneRequesterWorkerEvent(pub(crate) MilestoneIndex, pub(crate) Option<EndpointId>);
 
 pub(crate) struct MilestoneRequesterWorker {
-    pub(crate) tx: flume::Sender<MilestoneRequesterWorkerEvent>,
+    pub(crate) tx: channel::Sender<MilestoneRequesterWorkerEvent>,
 }
 
-async fn process_request(index: MilestoneIndex, epid: Option<EndpointId>, counter: &mut usize) {
+/// Chooses the peer to request the milestone `index` from, `None` meaning that no peer can provide it yet or that all
+/// the ones that can are busy.
+///
+/// Since any peer knows its latest milestone, a request for index 0 goes round-robin to all of them. Other requests
+/// follow the shared selection, preferring the peers that have the milestone as solid.
+fn select_peer(
+    index: MilestoneIndex,
+    peers: &[Arc<HandshakedPeer>],
+    counter: &mut usize,
+    max_outstanding: u64,
+) -> Option<EndpointId> {
+    let peer = if index.0 == 0 {
+        round_robin(peers, counter, max_outstanding, |_| true)
+    } else {
+        selection::select_peer(index, peers, counter, max_outstanding)
+    };
+
+    peer.map(|peer| peer.epid)
+}
+
+async fn process_request(index: MilestoneIndex, epid: Option<EndpointId>, counter: &mut usize, max_outstanding: u64) {
     if Protocol::get().requested_milestones.contains_key(&index) {
         return;
     }
 
-    process_request_unchecked(index, epid, counter).await;
+    let epid = process_request_unchecked(index, epid, counter, max_outstanding).await;
 
     if index.0 != 0 {
-        Protocol::get().requested_milestones.insert(index, Instant::now());
+        Protocol::get()
+            .requested_milestones
+            .insert(index, (Instant::now(), epid));
     }
 }
 
-/// Return `true` if the milestone was requested
-async fn process_request_unchecked(index: MilestoneIndex, epid: Option<EndpointId>, counter: &mut usize) -> bool {
-    if Protocol::get().peer_manager.handshaked_peers.is_empty() {
-        return false;
-    }
+/// Returns the peer the milestone was requested from, if any.
+async fn process_request_unchecked(
+    index: MilestoneIndex,
+    epid: Option<EndpointId>,
+    counter: &mut usize,
+    max_outstanding: u64,
+) -> Option<EndpointId> {
+    let peers = handshaked_peers().await;
+
+    // A peer explicitly asked for is not subject to the cap on outstanding requests.
+    let epid = match epid {
+        Some(epid) => peers.iter().find(|peer| peer.epid == epid).map(|peer| peer.epid),
+        None => select_peer(index, &peers, counter, max_outstanding),
+    };
 
     match epid {
         Some(epid) => {
-            Sender::<MilestoneRequest>::send(&epid, MilestoneRequest::new(*index));
-            true
-        }
-        None => {
-            let guard = Protocol::get().peer_manager.handshaked_peers_keys.read().await;
+            Sender::<MilestoneRequest>::send(&epid, MilestoneRequest::new(index.into()));
 
-            for _ in 0..guard.len() {
-                let epid = &guard[*counter % guard.len()];
-
-                *counter += 1;
-
-                if let Some(peer) = Protocol::get().peer_manager.handshaked_peers.get(epid) {
-                    if peer.maybe_has_data(index) {
-                        Sender::<MilestoneRequest>::send(&epid, MilestoneRequest::new(*index));
-                        return true;
-                    }
+            // Requests for the latest milestone are not tracked, they don't count as outstanding.
+            if index.0 != 0 {
+                if let Some(peer) = peers.iter().find(|peer| peer.epid == epid) {
+                    request_sent(peer);
                 }
             }
 
-            false
+            Some(epid)
+        }
+        None => {
+            // The request stays in `requested_milestones` and is retried once the heartbeats are more recent or the
+            // peers less busy.
+            trace!(
+                "No peer can take the request for milestone {} yet, delaying it.",
+                *index
+            );
+            None
         }
     }
 }
 
-async fn retry_requests(counter: &mut usize) {
+async fn retry_requests(counter: &mut usize, max_outstanding: u64) {
     let mut retry_counts: usize = 0;
 
     for mut milestone in Protocol::get().requested_milestones.iter_mut() {
-        let (index, instant) = milestone.pair_mut();
+        let (index, (instant, epid)) = milestone.pair_mut();
         let now = Instant::now();
-        if (now - *instant).as_secs() > RETRY_INTERVAL_SECS && process_request_unchecked(*index, None, counter).await {
+
+        if (now - *instant).as_secs() <= RETRY_INTERVAL_SECS {
+            continue;
+        }
+
+        if let Some(epid) = epid.take() {
+            request_timed_out(&Protocol::get().peer_manager.handshaked_peers, &epid);
+        }
+
+        if let Some(to) = process_request_unchecked(*index, None, counter, max_outstanding).await {
             *instant = now;
+            epid.replace(to);
             retry_counts += 1;
-        };
+        }
     }
 
     if retry_counts > 0 {
@@ -102,15 +147,16 @@ async fn retry_requests(counter: &mut usize) {
 
 #[async_trait]
 impl<N: Node> Worker<N> for MilestoneRequesterWorker {
-    type Config = ();
+    type Config = u64;
     type Error = WorkerError;
 
     fn dependencies() -> &'static [TypeId] {
         Box::leak(Box::from(vec![TypeId::of::<TangleWorker>()]))
     }
 
-    async fn start(node: &mut N, _config: Self::Config) -> Result<Self, Self::Error> {
-        let (tx, rx) = flume::unbounded();
+    async fn start(node: &mut N, config: Self::Config) -> Result<Self, Self::Error> {
+        let max_outstanding = config;
+        let (tx, rx) = channel::unbounded("MilestoneRequesterWorker");
 
         let tangle = node.resource::<MsTangle<N::Backend>>();
 
@@ -124,11 +170,11 @@ impl<N: Node> Worker<N> for MilestoneRequesterWorker {
 
             loop {
                 select! {
-                    _ = timeouts.next() => retry_requests(&mut counter).await,
+                    _ = timeouts.next() => retry_requests(&mut counter, max_outstanding).await,
                     entry = receiver.next() => match entry {
                         Some(MilestoneRequesterWorkerEvent(index, epid)) => {
                             if !tangle.contains_milestone(index.into()) {
-                                process_request(index, epid, &mut counter).await;
+                                process_request(index, epid, &mut counter, max_outstanding).await;
                             }
                         },
                         None => break,
@@ -142,3 +188,123 @@ impl<N: Node> Worker<N> for MilestoneRequesterWorker {
         Ok(Self { tx })
     }
 }
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use bee_network::TransportProtocol;
+
+    use std::net::SocketAddr;
+
+    fn peer(port: u16, latest_solid_milestone_index: u32, latest_milestone_index: u32) -> Arc<HandshakedPeer> {
+        let address: SocketAddr = format!("127.0.0.1:{}", port).parse().unwrap();
+        let peer = HandshakedPeer::new(EndpointId::new(TransportProtocol::Tcp, address), address);
+
+        peer.set_latest_solid_milestone_index(latest_solid_milestone_index.into());
+        peer.set_latest_milestone_index(latest_milestone_index.into());
+
+        Arc::new(peer)
+    }
+
+    #[test]
+    fn synced_peer_chosen() {
+        let unsynced = peer(1, 2, 10);
+        let synced = peer(2, 10, 10);
+        let peers = vec![unsynced, synced.clone()];
+        let mut counter = 0;
+
+        for _ in 0..peers.len() {
+            assert_eq!(
+                select_peer(MilestoneIndex(5), &peers, &mut counter, 0),
+                Some(synced.epid)
+            );
+        }
+    }
+
+    #[test]
+    fn synced_peers_round_robin() {
+        let synced = peer(1, 6, 10);
+        let more_synced = peer(2, 9, 10);
+        let peers = vec![synced.clone(), more_synced.clone()];
+        let mut counter = 0;
+
+        assert_eq!(
+            select_peer(MilestoneIndex(5), &peers, &mut counter, 0),
+            Some(synced.epid)
+        );
+        assert_eq!(
+            select_peer(MilestoneIndex(5), &peers, &mut counter, 0),
+            Some(more_synced.epid)
+        );
+    }
+
+    #[test]
+    fn unsolid_peer_as_fallback() {
+        let behind = peer(1, 2, 3);
+        let syncing = peer(2, 2, 10);
+        let peers = vec![behind, syncing.clone()];
+        let mut counter = 0;
+
+        assert_eq!(
+            select_peer(MilestoneIndex(5), &peers, &mut counter, 0),
+            Some(syncing.epid)
+        );
+    }
+
+    #[test]
+    fn no_capable_peer_delays() {
+        let peers = vec![peer(1, 2, 3), peer(2, 0, 0)];
+        let mut counter = 0;
+
+        assert_eq!(select_peer(MilestoneIndex(5), &peers, &mut counter, 0), None);
+        assert_eq!(select_peer(MilestoneIndex(5), &[], &mut counter, 0), None);
+    }
+
+    #[test]
+    fn ties_round_robin() {
+        let a = peer(1, 10, 10);
+        let b = peer(2, 10, 10);
+        let peers = vec![a.clone(), peer(3, 2, 10), b.clone()];
+        let mut counter = 0;
+
+        assert_eq!(select_peer(MilestoneIndex(5), &peers, &mut counter, 0), Some(a.epid));
+        assert_eq!(select_peer(MilestoneIndex(5), &peers, &mut counter, 0), Some(b.epid));
+        assert_eq!(select_peer(MilestoneIndex(5), &peers, &mut counter, 0), Some(a.epid));
+    }
+
+    #[test]
+    fn latest_milestone_round_robin() {
+        let a = peer(1, 0, 0);
+        let b = peer(2, 0, 0);
+        let peers = vec![a.clone(), b.clone()];
+        let mut counter = 0;
+
+        assert_eq!(select_peer(MilestoneIndex(0), &peers, &mut counter, 0), Some(a.epid));
+        assert_eq!(select_peer(MilestoneIndex(0), &peers, &mut counter, 0), Some(b.epid));
+    }
+
+    #[test]
+    fn busy_peer_skipped() {
+        let busy = peer(1, 10, 10);
+        let other = peer(2, 10, 10);
+        let peers = vec![busy.clone(), other.clone()];
+        let mut counter = 0;
+
+        request_sent(&busy);
+
+        assert_eq!(
+            select_peer(MilestoneIndex(5), &peers, &mut counter, 1),
+            Some(other.epid)
+        );
+        assert_eq!(
+            select_peer(MilestoneIndex(5), &peers, &mut counter, 1),
+            Some(other.epid)
+        );
+        assert_eq!(
+            select_peer(MilestoneIndex(0), &peers, &mut counter, 1),
+            Some(other.epid)
+        );
+    }
+}
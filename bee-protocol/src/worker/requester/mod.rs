@@ -10,7 +10,9 @@
 // See the License for the specific language governing permissions and limitations under the License.
 
 mod milestone;
+mod selection;
 mod transaction;
 
 pub(crate) use milestone::{MilestoneRequesterWorker, MilestoneRequesterWorkerEvent};
+pub(crate) use selection::request_completed;
 pub(crate) use transaction::{TransactionRequesterWorker, TransactionRequesterWorkerEvent};
@@ -0,0 +1,223 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use crate::{milestone::MilestoneIndex, peer::HandshakedPeer, protocol::Protocol};
+
+use bee_network::EndpointId;
+
+use dashmap::DashMap;
+
+use std::sync::Arc;
+
+/// Returns the handshaked peers, in the order they were handshaked.
+pub(crate) async fn handshaked_peers() -> Vec<Arc<HandshakedPeer>> {
+    Protocol::get()
+        .peer_manager
+        .handshaked_peers_keys
+        .read()
+        .await
+        .iter()
+        .filter_map(|epid| Protocol::get().peer_manager.handshaked_peers.get(epid))
+        .map(|peer| peer.value().clone())
+        .collect()
+}
+
+// Whether a peer has fewer than `max_outstanding` unanswered requests, 0 meaning unlimited.
+fn has_capacity(peer: &HandshakedPeer, max_outstanding: u64) -> bool {
+    max_outstanding == 0 || peer.metrics.outstanding_requests() < max_outstanding
+}
+
+/// Chooses round-robin the next peer that serves requests, is `eligible` and has fewer than `max_outstanding`
+/// unanswered requests, 0 meaning unlimited.
+pub(crate) fn round_robin<'a>(
+    peers: &'a [Arc<HandshakedPeer>],
+    counter: &mut usize,
+    max_outstanding: u64,
+    eligible: impl Fn(&HandshakedPeer) -> bool,
+) -> Option<&'a Arc<HandshakedPeer>> {
+    for _ in 0..peers.len() {
+        let peer = &peers[*counter % peers.len()];
+        *counter += 1;
+
+        if peer.serves_requests() && eligible(peer) && has_capacity(peer, max_outstanding) {
+            return Some(peer);
+        }
+    }
+
+    None
+}
+
+/// Chooses the peer to request data of the milestone `index` from, `None` meaning that no peer can provide it yet or
+/// that all the ones that can are busy.
+///
+/// The peers whose latest heartbeat shows the milestone as solid are tried round-robin first, then the ones that
+/// announced a latest milestone at or above `index`. Peers with `max_outstanding` unanswered requests are skipped.
+pub(crate) fn select_peer<'a>(
+    index: MilestoneIndex,
+    peers: &'a [Arc<HandshakedPeer>],
+    counter: &mut usize,
+    max_outstanding: u64,
+) -> Option<&'a Arc<HandshakedPeer>> {
+    round_robin(peers, counter, max_outstanding, |peer| peer.has_data(index))
+        .or_else(|| round_robin(peers, counter, max_outstanding, |peer| peer.maybe_has_data(index)))
+}
+
+/// Accounts for a request sent to `peer` until it is answered or times out.
+pub(crate) fn request_sent(peer: &HandshakedPeer) {
+    peer.metrics.outstanding_requests_inc();
+}
+
+/// Accounts for the answer to a request sent to the peer `epid`, if it is still handshaked.
+pub(crate) fn request_completed(peers: &DashMap<EndpointId, Arc<HandshakedPeer>>, epid: &EndpointId) {
+    if let Some(peer) = peers.get(epid) {
+        peer.metrics.outstanding_requests_dec();
+        peer.metrics.completed_requests_inc();
+    }
+}
+
+/// Accounts for a request sent to the peer `epid` that timed out, if it is still handshaked.
+pub(crate) fn request_timed_out(peers: &DashMap<EndpointId, Arc<HandshakedPeer>>, epid: &EndpointId) {
+    if let Some(peer) = peers.get(epid) {
+        peer.metrics.outstanding_requests_dec();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use bee_network::TransportProtocol;
+
+    use std::net::SocketAddr;
+
+    const REQUESTS: usize = 30;
+
+    fn peer(port: u16, latest_solid_milestone_index: u32) -> Arc<HandshakedPeer> {
+        let address: SocketAddr = format!("127.0.0.1:{}", port).parse().unwrap();
+        let peer = HandshakedPeer::new(EndpointId::new(TransportProtocol::Tcp, address), address);
+
+        peer.set_latest_solid_milestone_index(latest_solid_milestone_index.into());
+        peer.set_latest_milestone_index(latest_solid_milestone_index.into());
+
+        Arc::new(peer)
+    }
+
+    fn peers() -> (Vec<Arc<HandshakedPeer>>, DashMap<EndpointId, Arc<HandshakedPeer>>) {
+        let peers = (1..=3).map(|port| peer(port, 10)).collect::<Vec<_>>();
+        let map = peers.iter().map(|peer| (peer.epid, peer.clone())).collect();
+
+        (peers, map)
+    }
+
+    #[test]
+    fn balanced_distribution() {
+        let (peers, map) = peers();
+        let mut counter = 0;
+
+        for _ in 0..REQUESTS {
+            let peer = select_peer(MilestoneIndex(5), &peers, &mut counter, 2).unwrap();
+            request_sent(peer);
+            request_completed(&map, &peer.epid);
+        }
+
+        for peer in peers.iter() {
+            assert_eq!(peer.metrics.completed_requests(), (REQUESTS / peers.len()) as u64);
+            assert_eq!(peer.metrics.outstanding_requests(), 0);
+        }
+    }
+
+    #[test]
+    fn cap_enforced_on_unresponsive_peer() {
+        let (peers, map) = peers();
+        let unresponsive = peers[1].clone();
+        let max_outstanding = 3;
+        let mut counter = 0;
+
+        for _ in 0..REQUESTS {
+            let peer = select_peer(MilestoneIndex(5), &peers, &mut counter, max_outstanding).unwrap();
+            request_sent(peer);
+
+            if peer.epid != unresponsive.epid {
+                request_completed(&map, &peer.epid);
+            }
+        }
+
+        assert_eq!(unresponsive.metrics.outstanding_requests(), max_outstanding);
+        assert_eq!(unresponsive.metrics.completed_requests(), 0);
+        // The other peers took over the requests the unresponsive one could not.
+        assert_eq!(
+            peers[0].metrics.completed_requests() + peers[2].metrics.completed_requests(),
+            (REQUESTS as u64) - max_outstanding
+        );
+        assert!(
+            (peers[0].metrics.completed_requests() as i64 - peers[2].metrics.completed_requests() as i64).abs() <= 1
+        );
+
+        // Once its requests time out, the peer is eligible again.
+        for _ in 0..max_outstanding {
+            request_timed_out(&map, &unresponsive.epid);
+        }
+        assert_eq!(unresponsive.metrics.outstanding_requests(), 0);
+        assert!((0..peers.len())
+            .filter_map(|_| select_peer(MilestoneIndex(5), &peers, &mut counter, max_outstanding))
+            .any(|peer| peer.epid == unresponsive.epid));
+    }
+
+    #[test]
+    fn all_peers_busy() {
+        let (peers, _) = peers();
+        let mut counter = 0;
+
+        for peer in peers.iter() {
+            request_sent(peer);
+        }
+
+        assert!(select_peer(MilestoneIndex(5), &peers, &mut counter, 1).is_none());
+        assert!(select_peer(MilestoneIndex(5), &peers, &mut counter, 0).is_some());
+    }
+
+    #[test]
+    fn peers_with_data_preferred() {
+        let behind = peer(1, 2);
+        let synced = peer(2, 10);
+        let peers = vec![behind, synced.clone()];
+        let mut counter = 0;
+
+        for _ in 0..peers.len() {
+            assert_eq!(
+                select_peer(MilestoneIndex(5), &peers, &mut counter, 0).unwrap().epid,
+                synced.epid
+            );
+        }
+    }
+
+    #[test]
+    fn peers_not_serving_requests_skipped() {
+        let (peers, _) = peers();
+        let mut counter = 0;
+
+        peers[1].set_serves_requests(false);
+
+        for _ in 0..REQUESTS {
+            assert_ne!(
+                select_peer(MilestoneIndex(5), &peers, &mut counter, 0).unwrap().epid,
+                peers[1].epid
+            );
+        }
+
+        for peer in peers.iter() {
+            peer.set_serves_requests(false);
+        }
+
+        assert!(select_peer(MilestoneIndex(5), &peers, &mut counter, 0).is_none());
+    }
+}
@@ -10,91 +10,160 @@
 // See the License for the specific language governing permissions and limitations under the License.
 
 use crate::{
+    event::TransactionUnreachable,
     message::TransactionRequest,
     milestone::MilestoneIndex,
+    peer::HandshakedPeer,
     protocol::{Protocol, Sender},
+    worker::requester::selection::{handshaked_peers, request_sent, request_timed_out, select_peer},
 };
 
 use bee_common::{shutdown_stream::ShutdownStream, worker::Error as WorkerError};
-use bee_common_ext::{node::Node, worker::Worker};
+use bee_common_ext::{event::Bus, node::Node, wait_priority_queue::WaitPriorityQueue, worker::Worker};
 use bee_crypto::ternary::Hash;
+use bee_network::EndpointId;
 use bee_ternary::T5B1Buf;
 
 use async_trait::async_trait;
 use bytemuck::cast_slice;
+use dashmap::DashMap;
 use futures::{select, StreamExt};
-use log::{debug, info};
+use log::{debug, info, warn};
 use tokio::time::interval;
 
-use std::time::{Duration, Instant};
+use std::{
+    cmp::Ordering,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 const RETRY_INTERVAL_SECS: u64 = 5;
+const REQUEST_QUEUE_CAPACITY: usize = 100_000;
+
+// Requested transactions, with the index of the milestone they are requested for, the time of the last request, the
+// number of retries so far and the peer the last request was sent to, if any.
+type RequestedTransactions = DashMap<Hash, (MilestoneIndex, Instant, u8, Option<EndpointId>)>;
 
 pub(crate) struct TransactionRequesterWorkerEvent(pub(crate) Hash, pub(crate) MilestoneIndex);
 
+// Requests are ordered by milestone index only, the lowest index having the highest priority.
+
+impl Eq for TransactionRequesterWorkerEvent {}
+
+impl PartialEq for TransactionRequesterWorkerEvent {
+    fn eq(&self, other: &Self) -> bool {
+        self.1 == other.1
+    }
+}
+
+impl PartialOrd for TransactionRequesterWorkerEvent {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for TransactionRequesterWorkerEvent {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.1.cmp(&self.1)
+    }
+}
+
 pub(crate) struct TransactionRequesterWorker {
-    pub(crate) tx: flume::Sender<TransactionRequesterWorkerEvent>,
+    pub(crate) queue: Arc<WaitPriorityQueue<TransactionRequesterWorkerEvent>>,
 }
 
-async fn process_request(hash: Hash, index: MilestoneIndex, counter: &mut usize) {
+async fn process_request(hash: Hash, index: MilestoneIndex, counter: &mut usize, max_outstanding: u64) {
     if Protocol::get().requested_transactions.contains_key(&hash) {
         return;
     }
 
-    if process_request_unchecked(hash, index, counter).await {
-        Protocol::get()
-            .requested_transactions
-            .insert(hash, (index, Instant::now()));
-    }
+    // When no peer can take the request yet, it is still recorded so that it is retried later.
+    let epid = process_request_unchecked(hash, index, counter, max_outstanding).await;
+
+    Protocol::get()
+        .requested_transactions
+        .insert(hash, (index, Instant::now(), 0, epid));
 }
 
-/// Return `true` if the transaction was requested.
-async fn process_request_unchecked(hash: Hash, index: MilestoneIndex, counter: &mut usize) -> bool {
-    if Protocol::get().peer_manager.handshaked_peers.is_empty() {
-        return false;
-    }
+/// Returns the peer the transaction was requested from, if any.
+async fn process_request_unchecked(
+    hash: Hash,
+    index: MilestoneIndex,
+    counter: &mut usize,
+    max_outstanding: u64,
+) -> Option<EndpointId> {
+    let peers = handshaked_peers().await;
+    let peer = select_peer(index, &peers, counter, max_outstanding)?;
+    let hash = hash.as_trits().encode::<T5B1Buf>();
 
-    let guard = Protocol::get().peer_manager.handshaked_peers_keys.read().await;
+    Sender::<TransactionRequest>::send(&peer.epid, TransactionRequest::new(cast_slice(hash.as_i8_slice())));
+    request_sent(peer);
 
-    for _ in 0..guard.len() {
-        let epid = &guard[*counter % guard.len()];
+    Some(peer.epid)
+}
 
-        *counter += 1;
+// Gives up on the requests that timed out after `max_retries` retries and returns the other timed out ones, releasing
+// the peers they were sent to.
+fn timed_out_requests(
+    requests: &RequestedTransactions,
+    max_retries: u8,
+    peers: &DashMap<EndpointId, Arc<HandshakedPeer>>,
+    bus: &Bus<'static>,
+) -> Vec<(Hash, MilestoneIndex)> {
+    let mut timed_out = Vec::new();
+    let mut unreachable = Vec::new();
 
-        if let Some(peer) = Protocol::get().peer_manager.handshaked_peers.get(epid) {
-            if peer.has_data(index) {
-                let hash = hash.as_trits().encode::<T5B1Buf>();
-                Sender::<TransactionRequest>::send(epid, TransactionRequest::new(cast_slice(hash.as_i8_slice())));
-                return true;
-            }
+    requests.retain(|hash, (index, instant, retries, epid)| {
+        if instant.elapsed().as_secs() <= RETRY_INTERVAL_SECS {
+            return true;
         }
-    }
-
-    for _ in 0..guard.len() {
-        let epid = &guard[*counter % guard.len()];
 
-        *counter += 1;
+        if let Some(epid) = epid.take() {
+            request_timed_out(peers, &epid);
+        }
 
-        if let Some(peer) = Protocol::get().peer_manager.handshaked_peers.get(epid) {
-            if peer.maybe_has_data(index) {
-                let hash = hash.as_trits().encode::<T5B1Buf>();
-                Sender::<TransactionRequest>::send(epid, TransactionRequest::new(cast_slice(hash.as_i8_slice())));
-                return true;
-            }
+        if *retries >= max_retries {
+            unreachable.push(*hash);
+            return false;
         }
+
+        timed_out.push((*hash, *index));
+        true
+    });
+
+    // Dispatching outside of `retain` as listeners may access the requests.
+    for hash in unreachable {
+        warn!(
+            "Transaction {} still not received after {} retries, giving up.",
+            hash.iter_trytes().map(char::from).collect::<String>(),
+            max_retries
+        );
+        bus.dispatch(TransactionUnreachable { hash });
     }
 
-    false
+    timed_out
 }
 
-async fn retry_requests(counter: &mut usize) {
+fn mark_retried(requests: &RequestedTransactions, hash: &Hash, to: EndpointId) {
+    if let Some(mut request) = requests.get_mut(hash) {
+        let (_, instant, retries, epid) = request.value_mut();
+        *instant = Instant::now();
+        *retries = retries.saturating_add(1);
+        epid.replace(to);
+    }
+}
+
+async fn retry_requests(max_retries: u8, counter: &mut usize, max_outstanding: u64) {
     let mut retry_counts: usize = 0;
 
-    for mut transaction in Protocol::get().requested_transactions.iter_mut() {
-        let (hash, (index, instant)) = transaction.pair_mut();
-        let now = Instant::now();
-        if (now - *instant).as_secs() > RETRY_INTERVAL_SECS && process_request_unchecked(*hash, *index, counter).await {
-            *instant = now;
+    for (hash, index) in timed_out_requests(
+        &Protocol::get().requested_transactions,
+        max_retries,
+        &Protocol::get().peer_manager.handshaked_peers,
+        &Protocol::get().bus,
+    ) {
+        if let Some(epid) = process_request_unchecked(hash, index, counter, max_outstanding).await {
+            mark_retried(&Protocol::get().requested_transactions, &hash, epid);
             retry_counts += 1;
         }
     }
@@ -106,32 +175,149 @@ async fn retry_requests(counter: &mut usize) {
 
 #[async_trait]
 impl<N: Node> Worker<N> for TransactionRequesterWorker {
-    type Config = ();
+    type Config = (u8, u64);
     type Error = WorkerError;
 
-    async fn start(node: &mut N, _config: Self::Config) -> Result<Self, Self::Error> {
-        let (tx, rx) = flume::unbounded();
+    async fn start(node: &mut N, config: Self::Config) -> Result<Self, Self::Error> {
+        let (max_retries, max_outstanding) = config;
+        let queue = Arc::new(WaitPriorityQueue::bounded(REQUEST_QUEUE_CAPACITY));
+        let requests = queue.clone();
 
         node.spawn::<Self, _, _>(|shutdown| async move {
             info!("Running.");
 
-            let mut receiver = ShutdownStream::new(shutdown, rx.into_stream());
+            let mut receiver = ShutdownStream::new(shutdown, requests.incoming());
 
             let mut counter: usize = 0;
             let mut timeouts = interval(Duration::from_secs(RETRY_INTERVAL_SECS)).fuse();
 
             loop {
                 select! {
-                    _ = timeouts.next() => retry_requests(&mut counter).await,
+                    _ = timeouts.next() => retry_requests(max_retries, &mut counter, max_outstanding).await,
                     entry = receiver.next() => match entry {
-                        Some(TransactionRequesterWorkerEvent(hash, index)) => process_request(hash, index, &mut counter).await,
+                        Some(TransactionRequesterWorkerEvent(hash, index)) => {
+                            process_request(hash, index, &mut counter, max_outstanding).await
+                        }
                         None => break,
                     },
                 }
             }
 
-            info!("Stopped."); });
+            info!("Stopped.");
+        });
+
+        Ok(Self { queue })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use crate::test_utils::hash;
+
+    use bee_network::TransportProtocol;
+
+    use std::{
+        net::SocketAddr,
+        sync::atomic::{AtomicUsize, Ordering as AtomicOrdering},
+    };
+
+    const MAX_RETRIES: u8 = 10;
+
+    fn peer(port: u16) -> Arc<HandshakedPeer> {
+        let address: SocketAddr = format!("127.0.0.1:{}", port).parse().unwrap();
+
+        Arc::new(HandshakedPeer::new(
+            EndpointId::new(TransportProtocol::Tcp, address),
+            address,
+        ))
+    }
+
+    // Makes the request look like its last attempt timed out without any response.
+    fn time_out(requests: &RequestedTransactions, hash: &Hash) {
+        requests.get_mut(hash).unwrap().value_mut().1 = Instant::now()
+            .checked_sub(Duration::from_secs(RETRY_INTERVAL_SECS + 1))
+            .unwrap();
+    }
+
+    #[test]
+    fn recent_request_not_retried() {
+        let bus = Bus::default();
+        let peers = DashMap::new();
+        let requests = RequestedTransactions::new();
+        let requested = hash(&"A".repeat(81));
+
+        requests.insert(requested, (MilestoneIndex(1), Instant::now(), 0, None));
+
+        assert!(timed_out_requests(&requests, MAX_RETRIES, &peers, &bus).is_empty());
+        assert!(requests.contains_key(&requested));
+    }
+
+    #[test]
+    fn unreachable_after_max_retries() {
+        let bus = Bus::default();
+        let peer = peer(1);
+        let peers = DashMap::new();
+        let unreachable = Arc::new(AtomicUsize::new(0));
+        let requests = RequestedTransactions::new();
+        let requested = hash(&"A".repeat(81));
+        let other = hash(&"B".repeat(81));
+
+        let counter = unreachable.clone();
+        bus.add_listener(move |event: &TransactionUnreachable| {
+            assert_eq!(event.hash, hash(&"A".repeat(81)));
+            counter.fetch_add(1, AtomicOrdering::SeqCst);
+        });
+
+        peers.insert(peer.epid, peer.clone());
+        requests.insert(requested, (MilestoneIndex(1), Instant::now(), 0, None));
+        requests.insert(other, (MilestoneIndex(1), Instant::now(), 0, None));
+
+        // The initial request and its retries all fail.
+        for _ in 0..MAX_RETRIES {
+            time_out(&requests, &requested);
+            assert_eq!(
+                timed_out_requests(&requests, MAX_RETRIES, &peers, &bus),
+                vec![(requested, MilestoneIndex(1))]
+            );
+            request_sent(&peer);
+            mark_retried(&requests, &requested, peer.epid);
+        }
+        assert_eq!(unreachable.load(AtomicOrdering::SeqCst), 0);
+
+        // The last retry fails as well.
+        time_out(&requests, &requested);
+        assert!(timed_out_requests(&requests, MAX_RETRIES, &peers, &bus).is_empty());
+
+        assert_eq!(unreachable.load(AtomicOrdering::SeqCst), 1);
+        assert_eq!(peer.metrics.outstanding_requests(), 0);
+        assert!(!requests.contains_key(&requested));
+        assert!(requests.contains_key(&other));
+    }
+
+    #[test]
+    fn timed_out_request_releases_peer() {
+        let bus = Bus::default();
+        let peer = peer(1);
+        let peers = DashMap::new();
+        let requests = RequestedTransactions::new();
+        let requested = hash(&"A".repeat(81));
+
+        peers.insert(peer.epid, peer.clone());
+        request_sent(&peer);
+        requests.insert(requested, (MilestoneIndex(1), Instant::now(), 0, Some(peer.epid)));
+
+        time_out(&requests, &requested);
+        assert_eq!(
+            timed_out_requests(&requests, MAX_RETRIES, &peers, &bus),
+            vec![(requested, MilestoneIndex(1))]
+        );
+        assert_eq!(peer.metrics.outstanding_requests(), 0);
+        assert_eq!(peer.metrics.completed_requests(), 0);
 
-        Ok(Self { tx })
+        // Until it is sent again, the request is not accounted to any peer.
+        assert_eq!(requests.get(&requested).unwrap().3, None);
     }
 }
@@ -0,0 +1,336 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use bee_crypto::ternary::Hash;
+
+use futures::channel::oneshot;
+use spin::Mutex;
+
+use std::{
+    collections::{HashMap, VecDeque},
+    future::Future,
+    time::{Duration, Instant},
+};
+
+const RESPONSE_CACHE_CAPACITY: usize = 256;
+const RESPONSE_CACHE_TTL: Duration = Duration::from_secs(5);
+
+/// A transaction as served to requesting peers, along with its parents.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct CachedResponse {
+    /// Compressed wire bytes of the transaction.
+    pub(crate) bytes: Vec<u8>,
+    pub(crate) trunk: Hash,
+    pub(crate) branch: Hash,
+}
+
+/// How a response was obtained from the cache.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum CacheOutcome {
+    /// Served from a recent entry.
+    Hit,
+    /// Served by waiting for a fetch of the same hash that was already in flight.
+    Merged,
+    /// Fetched by this request.
+    Fetched,
+}
+
+type Waiters = Vec<oneshot::Sender<Option<CachedResponse>>>;
+
+#[derive(Default)]
+struct CacheState {
+    entries: HashMap<Hash, (Instant, CachedResponse)>,
+    order: VecDeque<Hash>,
+    in_flight: HashMap<Hash, Waiters>,
+}
+
+impl CacheState {
+    fn get(&mut self, hash: &Hash, ttl: Duration) -> Option<CachedResponse> {
+        match self.entries.get(hash) {
+            Some((inserted, response)) if inserted.elapsed() < ttl => {
+                let response = response.clone();
+                // Most recently used entries go to the back so that the oldest ones are evicted first.
+                self.order.retain(|h| h != hash);
+                self.order.push_back(*hash);
+                Some(response)
+            }
+            Some(_) => {
+                self.entries.remove(hash);
+                self.order.retain(|h| h != hash);
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn insert(&mut self, hash: Hash, response: CachedResponse, capacity: usize) {
+        if self.entries.insert(hash, (Instant::now(), response)).is_some() {
+            self.order.retain(|h| *h != hash);
+        }
+        self.order.push_back(hash);
+
+        while self.order.len() > capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.entries.remove(&oldest);
+            }
+        }
+    }
+}
+
+// Clears the in flight entry of a fetch that did not complete, because its request was cancelled or it panicked, so
+// that the next request for the hash fetches it again instead of waiting forever. The requests waiting for that fetch
+// serve nothing.
+struct InFlightGuard<'a> {
+    state: &'a Mutex<CacheState>,
+    hash: Hash,
+    armed: bool,
+}
+
+impl Drop for InFlightGuard<'_> {
+    fn drop(&mut self) {
+        if self.armed {
+            self.state.lock().in_flight.remove(&self.hash);
+        }
+    }
+}
+
+/// Least recently used cache of the responses to transaction requests, with a short time to live.
+///
+/// Concurrent requests for a hash that is not cached trigger a single fetch, the other requests awaiting its result.
+pub(crate) struct ResponseCache {
+    capacity: usize,
+    ttl: Duration,
+    state: Mutex<CacheState>,
+}
+
+impl Default for ResponseCache {
+    fn default() -> Self {
+        Self::new(RESPONSE_CACHE_CAPACITY, RESPONSE_CACHE_TTL)
+    }
+}
+
+impl ResponseCache {
+    pub(crate) fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            capacity,
+            ttl,
+            state: Mutex::new(CacheState::default()),
+        }
+    }
+
+    /// Returns the cached response for `hash`, fetching it with `fetch` if it is neither cached nor being fetched.
+    pub(crate) async fn get_or_fetch<F, Fut>(&self, hash: Hash, fetch: F) -> (Option<CachedResponse>, CacheOutcome)
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Option<CachedResponse>>,
+    {
+        let receiver = {
+            let mut state = self.state.lock();
+
+            if let Some(response) = state.get(&hash, self.ttl) {
+                return (Some(response), CacheOutcome::Hit);
+            }
+
+            match state.in_flight.get_mut(&hash) {
+                Some(waiters) => {
+                    let (sender, receiver) = oneshot::channel();
+                    waiters.push(sender);
+                    Some(receiver)
+                }
+                None => {
+                    state.in_flight.insert(hash, Vec::new());
+                    None
+                }
+            }
+        };
+
+        if let Some(receiver) = receiver {
+            // The fetching request may have been dropped, in which case there is nothing to serve.
+            return (receiver.await.unwrap_or(None), CacheOutcome::Merged);
+        }
+
+        let mut guard = InFlightGuard {
+            state: &self.state,
+            hash,
+            armed: true,
+        };
+
+        let response = fetch().await;
+
+        let waiters = {
+            let mut state = self.state.lock();
+
+            guard.armed = false;
+
+            if let Some(response) = response.as_ref() {
+                state.insert(hash, response.clone(), self.capacity);
+            }
+
+            state.in_flight.remove(&hash).unwrap_or_default()
+        };
+
+        for waiter in waiters {
+            // The waiting request may have been dropped.
+            let _ = waiter.send(response.clone());
+        }
+
+        (response, CacheOutcome::Fetched)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use crate::test_utils::hash;
+
+    use futures::future::{join_all, pending};
+    use tokio::time::{delay_for, timeout};
+
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn response(byte: u8) -> CachedResponse {
+        CachedResponse {
+            bytes: vec![byte; 10],
+            trunk: hash(&"T".repeat(81)),
+            branch: hash(&"B".repeat(81)),
+        }
+    }
+
+    // Backend taking some time to answer, counting how many times it is queried.
+    async fn slow_fetch(fetches: &AtomicUsize, response: Option<CachedResponse>) -> Option<CachedResponse> {
+        fetches.fetch_add(1, Ordering::SeqCst);
+        delay_for(Duration::from_millis(50)).await;
+        response
+    }
+
+    #[tokio::test]
+    async fn concurrent_requests_fetch_once() {
+        let cache = ResponseCache::default();
+        let fetches = AtomicUsize::new(0);
+        let requested = hash(&"A".repeat(81));
+
+        let results =
+            join_all((0..10).map(|_| cache.get_or_fetch(requested, || slow_fetch(&fetches, Some(response(1)))))).await;
+
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+        assert!(results.iter().all(|(served, _)| *served == Some(response(1))));
+        assert_eq!(
+            results
+                .iter()
+                .filter(|(_, outcome)| *outcome == CacheOutcome::Fetched)
+                .count(),
+            1
+        );
+        assert_eq!(
+            results
+                .iter()
+                .filter(|(_, outcome)| *outcome == CacheOutcome::Merged)
+                .count(),
+            9
+        );
+    }
+
+    #[tokio::test]
+    async fn repeated_request_hits() {
+        let cache = ResponseCache::default();
+        let fetches = AtomicUsize::new(0);
+        let requested = hash(&"A".repeat(81));
+
+        let (_, outcome) = cache
+            .get_or_fetch(requested, || slow_fetch(&fetches, Some(response(1))))
+            .await;
+        assert_eq!(outcome, CacheOutcome::Fetched);
+
+        let (served, outcome) = cache
+            .get_or_fetch(requested, || slow_fetch(&fetches, Some(response(2))))
+            .await;
+        assert_eq!(outcome, CacheOutcome::Hit);
+        assert_eq!(served, Some(response(1)));
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn expired_entry_fetched_again() {
+        let cache = ResponseCache::new(RESPONSE_CACHE_CAPACITY, Duration::from_millis(10));
+        let fetches = AtomicUsize::new(0);
+        let requested = hash(&"A".repeat(81));
+
+        cache
+            .get_or_fetch(requested, || slow_fetch(&fetches, Some(response(1))))
+            .await;
+        delay_for(Duration::from_millis(20)).await;
+        let (served, outcome) = cache
+            .get_or_fetch(requested, || slow_fetch(&fetches, Some(response(2))))
+            .await;
+
+        assert_eq!(outcome, CacheOutcome::Fetched);
+        assert_eq!(served, Some(response(2)));
+        assert_eq!(fetches.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn least_recently_used_evicted() {
+        let cache = ResponseCache::new(2, RESPONSE_CACHE_TTL);
+        let fetches = AtomicUsize::new(0);
+        let (a, b, c) = (hash(&"A".repeat(81)), hash(&"B".repeat(81)), hash(&"C".repeat(81)));
+
+        for requested in &[a, b, a, c] {
+            cache
+                .get_or_fetch(*requested, || slow_fetch(&fetches, Some(response(1))))
+                .await;
+        }
+
+        assert_eq!(fetches.load(Ordering::SeqCst), 3);
+        assert_eq!(
+            cache.get_or_fetch(a, || slow_fetch(&fetches, None)).await.1,
+            CacheOutcome::Hit
+        );
+        assert_eq!(
+            cache.get_or_fetch(b, || slow_fetch(&fetches, None)).await.1,
+            CacheOutcome::Fetched
+        );
+    }
+
+    #[tokio::test]
+    async fn unknown_transaction_not_cached() {
+        let cache = ResponseCache::default();
+        let fetches = AtomicUsize::new(0);
+        let requested = hash(&"A".repeat(81));
+
+        for _ in 0..2 {
+            let (served, outcome) = cache.get_or_fetch(requested, || slow_fetch(&fetches, None)).await;
+            assert_eq!(served, None);
+            assert_eq!(outcome, CacheOutcome::Fetched);
+        }
+        assert_eq!(fetches.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn cancelled_fetch_not_left_in_flight() {
+        let cache = ResponseCache::default();
+        let fetches = AtomicUsize::new(0);
+        let requested = hash(&"A".repeat(81));
+
+        let cancelled = timeout(Duration::from_millis(10), cache.get_or_fetch(requested, pending)).await;
+        assert!(cancelled.is_err());
+
+        let (served, outcome) = timeout(
+            Duration::from_secs(1),
+            cache.get_or_fetch(requested, || slow_fetch(&fetches, Some(response(1)))),
+        )
+        .await
+        .unwrap();
+        assert_eq!(outcome, CacheOutcome::Fetched);
+        assert_eq!(served, Some(response(1)));
+    }
+}
@@ -10,21 +10,18 @@
 // See the License for the specific language governing permissions and limitations under the License.
 
 use crate::{
-    message::{compress_transaction_bytes, MilestoneRequest, Transaction as TransactionMessage},
+    message::{MilestoneRequest, Transaction as TransactionMessage},
     protocol::Sender,
     tangle::MsTangle,
     worker::TangleWorker,
 };
 
 use bee_common::{shutdown_stream::ShutdownStream, worker::Error as WorkerError};
-use bee_common_ext::{node::Node, worker::Worker};
+use bee_common_ext::{channel, node::Node, worker::Worker};
 use bee_network::EndpointId;
 use bee_tangle::helper::load_bundle_builder;
-use bee_ternary::{T1B1Buf, T5B1Buf, TritBuf};
-use bee_transaction::bundled::BundledTransaction as Transaction;
 
 use async_trait::async_trait;
-use bytemuck::cast_slice;
 use futures::stream::StreamExt;
 use log::info;
 
@@ -36,7 +33,7 @@ pub(crate) struct MilestoneResponderWorkerEvent {
 }
 
 pub(crate) struct MilestoneResponderWorker {
-    pub(crate) tx: flume::Sender<MilestoneResponderWorkerEvent>,
+    pub(crate) tx: channel::Sender<MilestoneResponderWorkerEvent>,
 }
 
 #[async_trait]
@@ -49,7 +46,7 @@ impl<N: Node> Worker<N> for MilestoneResponderWorker {
     }
 
     async fn start(node: &mut N, _config: Self::Config) -> Result<Self, Self::Error> {
-        let (tx, rx) = flume::unbounded();
+        let (tx, rx) = channel::unbounded("MilestoneResponderWorker");
 
         let tangle = node.resource::<MsTangle<N::Backend>>();
 
@@ -68,16 +65,9 @@ impl<N: Node> Worker<N> for MilestoneResponderWorker {
                     if let Some(builder) = load_bundle_builder(&tangle, &hash) {
                         // This is safe because the bundle has already been validated.
                         let bundle = unsafe { builder.build() };
-                        let mut trits = TritBuf::<T1B1Buf>::zeros(Transaction::trit_len());
 
                         for transaction in bundle {
-                            transaction.as_trits_allocated(&mut trits);
-                            Sender::<TransactionMessage>::send(
-                                &epid,
-                                TransactionMessage::new(&compress_transaction_bytes(cast_slice(
-                                    trits.encode::<T5B1Buf>().as_i8_slice(),
-                                ))),
-                            );
+                            Sender::<TransactionMessage>::send(&epid, TransactionMessage::from(&transaction));
                         }
                     }
                 }
@@ -10,25 +10,31 @@
 // See the License for the specific language governing permissions and limitations under the License.
 
 use crate::{
-    message::{compress_transaction_bytes, Transaction as TransactionMessage, TransactionRequest},
-    protocol::Sender,
+    message::{Transaction as TransactionMessage, TransactionRequest},
+    milestone::MilestoneIndex,
+    peer::HandshakedPeer,
+    protocol::{Protocol, ProtocolMetrics, Sender},
     tangle::MsTangle,
-    worker::TangleWorker,
+    worker::{
+        responder::cache::{CacheOutcome, CachedResponse, ResponseCache},
+        BroadcasterWorker, BroadcasterWorkerEvent, TangleWorker,
+    },
 };
 
 use bee_common::{shutdown_stream::ShutdownStream, worker::Error as WorkerError};
-use bee_common_ext::{node::Node, worker::Worker};
+use bee_common_ext::{channel, node::Node, worker::Worker};
 use bee_crypto::ternary::Hash;
 use bee_network::EndpointId;
-use bee_ternary::{T1B1Buf, T5B1Buf, TritBuf, Trits, T5B1};
-use bee_transaction::bundled::{BundledTransaction as Transaction, BundledTransactionField};
+use bee_storage::storage::Backend;
+use bee_ternary::{Trits, T5B1};
 
 use async_trait::async_trait;
 use bytemuck::cast_slice;
 use futures::stream::StreamExt;
-use log::info;
+use log::{info, warn};
+use tokio::{spawn, sync::Semaphore};
 
-use std::any::TypeId;
+use std::{any::TypeId, sync::Arc};
 
 pub(crate) struct TransactionResponderWorkerEvent {
     pub(crate) epid: EndpointId,
@@ -36,42 +42,183 @@ pub(crate) struct TransactionResponderWorkerEvent {
 }
 
 pub(crate) struct TransactionResponderWorker {
-    pub(crate) tx: flume::Sender<TransactionResponderWorkerEvent>,
+    pub(crate) tx: channel::Sender<TransactionResponderWorkerEvent>,
+}
+
+// Maximum number of requests answered at the same time, further requests waiting in the queue of the worker.
+const MAX_CONCURRENT_RESPONSES: usize = 64;
+
+// Number of milestones a peer has to be behind our solid milestone to be considered syncing.
+const ASSISTANCE_THRESHOLD: u32 = 15;
+
+// Direct parents of a requested transaction that are worth pushing to the requesting peer: only if it is syncing and
+// they were not recently pushed to it already.
+fn assisted_parents(peer: &HandshakedPeer, solid_index: MilestoneIndex, trunk: &Hash, branch: &Hash) -> Vec<Hash> {
+    let mut parents = Vec::new();
+
+    if peer.latest_solid_milestone_index().saturating_add(ASSISTANCE_THRESHOLD) > *solid_index {
+        return parents;
+    }
+
+    for parent in [trunk, branch].iter() {
+        if !parents.contains(*parent) && !peer.has_seen(parent) {
+            parents.push(**parent);
+        }
+    }
+
+    parents
+}
+
+// Pushes the parents of a transaction requested by a syncing peer to it. A parent is only marked as seen by the peer,
+// and counted as assisted, once the broadcaster accepted it, so that a failed push doesn't prevent a later one.
+async fn assist<B: Backend>(
+    tangle: &MsTangle<B>,
+    peer: &HandshakedPeer,
+    broadcaster: &channel::Sender<BroadcasterWorkerEvent>,
+    metrics: &ProtocolMetrics,
+    trunk: &Hash,
+    branch: &Hash,
+) {
+    for parent in assisted_parents(peer, tangle.get_latest_solid_milestone_index(), trunk, branch) {
+        let transaction = match tangle.get(&parent).await {
+            Some(transaction) => transaction,
+            None => continue,
+        };
+        // A confirmed parent is tagged with its milestone so that it is not pushed if the peer got solid in the meantime.
+        let milestone_index = tangle
+            .get_metadata(&parent)
+            .filter(|metadata| metadata.flags().is_confirmed())
+            .map(|metadata| metadata.milestone_index());
+
+        match broadcaster.send(BroadcasterWorkerEvent {
+            source: None,
+            target: Some(peer.epid),
+            transaction: TransactionMessage::from(&*transaction),
+            milestone_index,
+        }) {
+            Ok(_) => {
+                peer.mark_seen(&parent);
+                metrics.assisted_transactions_inc();
+            }
+            Err(e) => {
+                warn!("Assisting solidification of {} failed: {}.", peer.epid, e);
+            }
+        }
+    }
+}
+
+fn requested_hash(request: &TransactionRequest) -> Option<Hash> {
+    let hash = Trits::<T5B1>::try_from_raw(cast_slice(&request.hash), Hash::trit_len()).ok()?;
+
+    Some(Hash::from_inner_unchecked(hash.encode()))
+}
+
+// Looks the requested transaction up, wherever it came from, since locally submitted transactions are stored like the
+// received ones.
+async fn fetch_response<B: Backend>(tangle: &MsTangle<B>, hash: &Hash) -> Option<CachedResponse> {
+    tangle.get(hash).await.map(|transaction| CachedResponse {
+        bytes: TransactionMessage::from(&*transaction).bytes,
+        trunk: *transaction.trunk(),
+        branch: *transaction.branch(),
+    })
+}
+
+// The parents of the transaction are pushed through `assistance`, if any, to assist the solidification of the peer.
+async fn respond<B: Backend>(
+    tangle: &MsTangle<B>,
+    cache: &ResponseCache,
+    assistance: Option<&channel::Sender<BroadcasterWorkerEvent>>,
+    TransactionResponderWorkerEvent { epid, request }: TransactionResponderWorkerEvent,
+) {
+    let hash = match requested_hash(&request) {
+        Some(hash) => hash,
+        None => return,
+    };
+
+    let (response, outcome) = cache.get_or_fetch(hash, || fetch_response(tangle, &hash)).await;
+
+    match outcome {
+        CacheOutcome::Hit => {
+            Protocol::get().metrics.responder_cache_hits_inc();
+        }
+        CacheOutcome::Merged => {
+            Protocol::get().metrics.responder_merged_requests_inc();
+        }
+        CacheOutcome::Fetched => (),
+    }
+
+    let response = match response {
+        Some(response) => response,
+        None => return,
+    };
+
+    Sender::<TransactionMessage>::send(&epid, TransactionMessage::new(&response.bytes));
+
+    let broadcaster = match assistance {
+        Some(broadcaster) => broadcaster,
+        None => return,
+    };
+
+    let peer = match Protocol::get().peer_manager.handshaked_peers.get(&epid) {
+        Some(peer) => peer.value().clone(),
+        None => return,
+    };
+
+    assist(
+        tangle,
+        &peer,
+        broadcaster,
+        &Protocol::get().metrics,
+        &response.trunk,
+        &response.branch,
+    )
+    .await;
 }
 
 #[async_trait]
 impl<N: Node> Worker<N> for TransactionResponderWorker {
-    type Config = ();
+    type Config = bool;
     type Error = WorkerError;
 
     fn dependencies() -> &'static [TypeId] {
         Box::leak(Box::from(vec![TypeId::of::<TangleWorker>()]))
     }
 
-    async fn start(node: &mut N, _config: Self::Config) -> Result<Self, Self::Error> {
-        let (tx, rx) = flume::unbounded();
+    // Only needed for the solidification assistance, which can't be enabled without the broadcaster.
+    fn optional_dependencies() -> &'static [TypeId] {
+        Box::leak(Box::from(vec![TypeId::of::<BroadcasterWorker>()]))
+    }
+
+    async fn start(node: &mut N, config: Self::Config) -> Result<Self, Self::Error> {
+        let (tx, rx) = channel::unbounded("TransactionResponderWorker");
 
         let tangle = node.resource::<MsTangle<N::Backend>>();
+        let assistance = if config {
+            node.worker::<BroadcasterWorker>()
+                .map(|broadcaster| broadcaster.tx.clone())
+        } else {
+            None
+        };
 
         node.spawn::<Self, _, _>(|shutdown| async move {
             info!("Running.");
 
             let mut receiver = ShutdownStream::new(shutdown, rx.into_stream());
 
-            while let Some(TransactionResponderWorkerEvent { epid, request }) = receiver.next().await {
-                if let Ok(hash) = Trits::<T5B1>::try_from_raw(cast_slice(&request.hash), Hash::trit_len()) {
-                    if let Some(transaction) = tangle.get(&Hash::from_inner_unchecked(hash.encode())).await {
-                        let mut trits = TritBuf::<T1B1Buf>::zeros(Transaction::trit_len());
-
-                        transaction.as_trits_allocated(&mut trits);
-                        Sender::<TransactionMessage>::send(
-                            &epid,
-                            TransactionMessage::new(&compress_transaction_bytes(cast_slice(
-                                trits.encode::<T5B1Buf>().as_i8_slice(),
-                            ))),
-                        );
-                    }
-                }
+            let cache = Arc::new(ResponseCache::default());
+            let permits = Arc::new(Semaphore::new(MAX_CONCURRENT_RESPONSES));
+
+            // Requests are answered concurrently so that the ones for the same transaction can share a single fetch.
+            while let Some(event) = receiver.next().await {
+                let permit = permits.clone().acquire_owned().await;
+                let tangle = tangle.clone();
+                let cache = cache.clone();
+                let assistance = assistance.clone();
+
+                spawn(async move {
+                    respond(&tangle, &cache, assistance.as_ref(), event).await;
+                    drop(permit);
+                });
             }
 
             info!("Stopped.");
@@ -80,3 +227,174 @@ impl<N: Node> Worker<N> for TransactionResponderWorker {
         Ok(Self { tx })
     }
 }
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use crate::{
+        test_utils::{hash, MockStorage},
+        worker::store_transaction,
+    };
+
+    use bee_common_ext::node::ResHandle;
+    use bee_network::TransportProtocol;
+    use bee_ternary::{T1B1Buf, T5B1Buf, TritBuf};
+    use bee_transaction::bundled::{BundledTransaction as Transaction, TRANSACTION_BYTE_LEN, TRANSACTION_TRIT_LEN};
+
+    fn peer(solid_index: u32) -> HandshakedPeer {
+        let address = "127.0.0.1:15600".parse().unwrap();
+        let peer = HandshakedPeer::new(EndpointId::new(TransportProtocol::Tcp, address), address);
+
+        peer.set_latest_solid_milestone_index(MilestoneIndex(solid_index));
+
+        peer
+    }
+
+    fn transaction() -> Transaction {
+        Transaction::from_trits(&TritBuf::<T1B1Buf>::zeros(TRANSACTION_TRIT_LEN)).unwrap()
+    }
+
+    const TRUNK: &str = "TRUNK9999999999999999999999999999999999999999999999999999999999999999999999999999";
+    const BRANCH: &str = "BRANCH999999999999999999999999999999999999999999999999999999999999999999999999999";
+
+    #[test]
+    fn syncing_peer_gets_both_parents() {
+        let peer = peer(10);
+        let (trunk, branch) = (hash(TRUNK), hash(BRANCH));
+
+        assert_eq!(
+            assisted_parents(&peer, MilestoneIndex(100), &trunk, &branch),
+            vec![trunk, branch]
+        );
+    }
+
+    #[test]
+    fn synced_peer_gets_nothing() {
+        let peer = peer(95);
+
+        assert!(assisted_parents(&peer, MilestoneIndex(100), &hash(TRUNK), &hash(BRANCH)).is_empty());
+    }
+
+    #[test]
+    fn parents_are_not_pushed_twice() {
+        let peer = peer(10);
+        let (trunk, branch) = (hash(TRUNK), hash(BRANCH));
+
+        assert_eq!(
+            assisted_parents(&peer, MilestoneIndex(100), &trunk, &trunk),
+            vec![trunk]
+        );
+        peer.mark_seen(&trunk);
+        assert_eq!(
+            assisted_parents(&peer, MilestoneIndex(100), &trunk, &branch),
+            vec![branch]
+        );
+        peer.mark_seen(&branch);
+        assert!(assisted_parents(&peer, MilestoneIndex(100), &trunk, &branch).is_empty());
+    }
+
+    fn syncing_tangle() -> MsTangle<MockStorage> {
+        let tangle = MsTangle::new(ResHandle::new(MockStorage::default()));
+        tangle.update_latest_solid_milestone_index(MilestoneIndex(100));
+        tangle
+    }
+
+    #[tokio::test]
+    async fn mid_cone_request_pushes_both_parents() {
+        let tangle = syncing_tangle();
+        let metrics = ProtocolMetrics::new();
+        let peer = peer(10);
+        let (broadcaster, pushed) = channel::unbounded("BroadcasterWorker");
+        let (trunk, branch) = (hash(TRUNK), hash(BRANCH));
+
+        store_transaction(&tangle, trunk, transaction(), false).await;
+        store_transaction(&tangle, branch, transaction(), false).await;
+
+        assist(&tangle, &peer, &broadcaster, &metrics, &trunk, &branch).await;
+
+        for _ in 0..2 {
+            let event = pushed.try_recv().unwrap();
+            assert_eq!(event.source, None);
+            assert_eq!(event.target, Some(peer.epid));
+            assert_eq!(event.transaction.bytes, TransactionMessage::from(&transaction()).bytes);
+        }
+        assert!(pushed.try_recv().is_err());
+        assert_eq!(metrics.assisted_transactions(), 2);
+        assert!(peer.has_seen(&trunk) && peer.has_seen(&branch));
+
+        // Already pushed, nothing is queued again.
+        assist(&tangle, &peer, &broadcaster, &metrics, &trunk, &branch).await;
+
+        assert!(pushed.try_recv().is_err());
+        assert_eq!(metrics.assisted_transactions(), 2);
+    }
+
+    #[tokio::test]
+    async fn failed_push_retried() {
+        let tangle = syncing_tangle();
+        let metrics = ProtocolMetrics::new();
+        let peer = peer(10);
+        let (trunk, branch) = (hash(TRUNK), hash(BRANCH));
+
+        store_transaction(&tangle, trunk, transaction(), false).await;
+
+        // The broadcaster is gone.
+        let (broadcaster, pushed) = channel::unbounded("BroadcasterWorker");
+        drop(pushed);
+        assist(&tangle, &peer, &broadcaster, &metrics, &trunk, &branch).await;
+
+        assert_eq!(metrics.assisted_transactions(), 0);
+        assert!(!peer.has_seen(&trunk));
+
+        // The branch is not known yet.
+        let (broadcaster, pushed) = channel::unbounded("BroadcasterWorker");
+        assist(&tangle, &peer, &broadcaster, &metrics, &trunk, &branch).await;
+
+        assert!(pushed.try_recv().is_ok());
+        assert!(pushed.try_recv().is_err());
+        assert_eq!(metrics.assisted_transactions(), 1);
+        assert!(!peer.has_seen(&branch));
+
+        store_transaction(&tangle, branch, transaction(), false).await;
+        assist(&tangle, &peer, &broadcaster, &metrics, &trunk, &branch).await;
+
+        assert!(pushed.try_recv().is_ok());
+        assert_eq!(metrics.assisted_transactions(), 2);
+        assert!(peer.has_seen(&branch));
+    }
+
+    #[tokio::test]
+    async fn local_transaction_served() {
+        let tangle = MsTangle::new(ResHandle::new(MockStorage::default()));
+        let transaction = transaction();
+        let expected = TransactionMessage::from(&transaction).bytes;
+        let hash = hash(&format!("{}9", "LOCAL".repeat(16)));
+
+        // Submitted locally and never broadcast, so the only way peers learn about it is through a request.
+        assert!(store_transaction(&tangle, hash, transaction, false)
+            .await
+            .inserted()
+            .is_some());
+
+        let request = TransactionRequest::new(cast_slice(hash.as_trits().encode::<T5B1Buf>().as_i8_slice()));
+        let served = fetch_response(&tangle, &requested_hash(&request).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(served.bytes, expected);
+        // The empty payload is trimmed.
+        assert!(served.bytes.len() < TRANSACTION_BYTE_LEN);
+    }
+
+    #[tokio::test]
+    async fn unknown_transaction_not_served() {
+        let tangle = MsTangle::new(ResHandle::new(MockStorage::default()));
+        let request = TransactionRequest::new(cast_slice(hash(TRUNK).as_trits().encode::<T5B1Buf>().as_i8_slice()));
+
+        assert!(fetch_response(&tangle, &requested_hash(&request).unwrap())
+            .await
+            .is_none());
+    }
+}
@@ -0,0 +1,192 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use crate::{
+    event::TangleSyncedEvent,
+    protocol::Protocol,
+    tangle::{revalidate, MsTangle, RunningMarker, RunningMarkerStorage},
+    worker::TangleWorker,
+};
+
+use bee_common::worker::Error as WorkerError;
+use bee_common_ext::{node::Node, worker::Worker};
+use bee_storage::access::{Delete, Fetch, Insert};
+
+use async_trait::async_trait;
+use futures::{channel::oneshot, select};
+use log::{info, warn};
+
+use std::{any::TypeId, sync::Mutex};
+
+pub(crate) struct RevalidatorWorker;
+
+// Replaces the running marker of the previous run with one for this run. Returns the previous marker if the previous
+// run did not shut down cleanly and there are milestones to check, `depth` not being 0.
+async fn mark_running<B: RunningMarkerStorage>(storage: &B, depth: u32) -> Option<RunningMarker> {
+    let previous = match Fetch::<(), RunningMarker>::fetch(storage, &()).await {
+        Ok(previous) => previous,
+        Err(e) => {
+            warn!("Fetching running marker failed: {:?}.", e);
+            None
+        }
+    };
+
+    if let Err(e) = Insert::<(), RunningMarker>::insert(storage, &(), &RunningMarker::now()).await {
+        warn!("Inserting running marker failed: {:?}.", e);
+    }
+
+    previous.filter(|_| depth != 0)
+}
+
+// Removes the running marker of this run, which then shut down cleanly.
+async fn mark_stopped<B: RunningMarkerStorage>(storage: &B) {
+    if let Err(e) = Delete::<(), RunningMarker>::delete(storage, &()).await {
+        warn!("Deleting running marker failed: {:?}.", e);
+    }
+}
+
+#[async_trait]
+impl<N: Node> Worker<N> for RevalidatorWorker
+where
+    N::Backend: RunningMarkerStorage,
+{
+    // Number of milestones checked after a crash and maximum number of transactions visited per milestone.
+    type Config = (u32, usize);
+    type Error = WorkerError;
+
+    fn dependencies() -> &'static [TypeId] {
+        Box::leak(Box::from(vec![TypeId::of::<TangleWorker>()]))
+    }
+
+    async fn start(node: &mut N, config: Self::Config) -> Result<Self, Self::Error> {
+        let (depth, max_cone_size) = config;
+        let previous = match mark_running(&*node.storage(), depth).await {
+            Some(previous) => previous,
+            None => return Ok(Self),
+        };
+
+        warn!(
+            "The run started at {} did not shut down cleanly, checking the last {} milestones.",
+            previous.started_at(),
+            depth
+        );
+
+        let tangle = node.resource::<MsTangle<N::Backend>>();
+
+        // The latest solid milestone index only gets back to where the previous run left it once the tangle is synced,
+        // revalidating earlier would check nothing but the snapshot.
+        let (synced_tx, synced_rx) = oneshot::channel();
+        let synced_tx = Mutex::new(Some(synced_tx));
+        Protocol::get().bus.add_listener(move |_: &TangleSyncedEvent| {
+            if let Some(synced_tx) = synced_tx.lock().unwrap().take() {
+                let _ = synced_tx.send(());
+            }
+        });
+
+        node.spawn::<Self, _, _>(|mut shutdown| async move {
+            info!("Running.");
+
+            select! {
+                _ = &mut shutdown => {
+                    info!("Stopped.");
+                    return;
+                },
+                _ = synced_rx => {},
+            }
+
+            // Waits for the milestone being solidified, if any, and holds the next ones back while flags are repaired.
+            let revalidation = Protocol::get().revalidation.lock().await;
+            // Stops at the next check once the shutdown signal was received or its sender dropped.
+            let summary = revalidate(&tangle, depth, max_cone_size, || {
+                !matches!(shutdown.try_recv(), Ok(None))
+            })
+            .await;
+            drop(revalidation);
+
+            info!(
+                "Checked {} milestones: {} missing milestones, {} missing transactions, {} repaired transactions.",
+                summary.milestones,
+                summary.missing_milestones,
+                summary.missing_transactions,
+                summary.repaired_transactions
+            );
+
+            Protocol::get().bus.dispatch(summary);
+
+            info!("Stopped.");
+        });
+
+        Ok(Self)
+    }
+
+    async fn stop(self, node: &mut N) -> Result<(), Self::Error> {
+        mark_stopped(&*node.storage()).await;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use crate::test_utils::MockStorage;
+
+    #[tokio::test]
+    async fn absent_marker_skips_revalidation() {
+        let storage = MockStorage::default();
+
+        assert_eq!(mark_running(&storage, 10).await, None);
+        // The marker of this run is written.
+        assert!(Fetch::<(), RunningMarker>::fetch(&storage, &())
+            .await
+            .unwrap()
+            .is_some());
+    }
+
+    #[tokio::test]
+    async fn present_marker_triggers_revalidation() {
+        let storage = MockStorage::default();
+        let crashed = RunningMarker::now();
+
+        Insert::<(), RunningMarker>::insert(&storage, &(), &crashed)
+            .await
+            .unwrap();
+        assert_eq!(mark_running(&storage, 10).await, Some(crashed));
+
+        // Not cleared by the check itself, a crash during the revalidation triggers it again.
+        assert!(mark_running(&storage, 10).await.is_some());
+        // Nothing to check.
+        assert_eq!(mark_running(&storage, 0).await, None);
+    }
+
+    #[tokio::test]
+    async fn clean_stop_deletes_marker() {
+        let storage = MockStorage::default();
+
+        mark_running(&storage, 10).await;
+        mark_stopped(&storage).await;
+
+        assert_eq!(Fetch::<(), RunningMarker>::fetch(&storage, &()).await.unwrap(), None);
+        assert_eq!(mark_running(&storage, 10).await, None);
+    }
+
+    #[tokio::test]
+    async fn unreadable_marker_skips_revalidation() {
+        let storage = MockStorage {
+            corrupted: true,
+            ..MockStorage::default()
+        };
+
+        assert_eq!(mark_running(&storage, 10).await, None);
+    }
+}
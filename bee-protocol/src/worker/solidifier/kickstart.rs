@@ -10,21 +10,57 @@
 // See the License for the specific language governing permissions and limitations under the License.
 
 use crate::{
-    milestone::MilestoneIndex,
+    event::{LatestSolidMilestoneChanged, TangleSyncedEvent},
+    milestone::{Milestone, MilestoneIndex},
     protocol::Protocol,
     tangle::MsTangle,
     worker::{MilestoneRequesterWorker, TangleWorker},
 };
 
 use bee_common::{shutdown_stream::ShutdownStream, worker::Error as WorkerError};
-use bee_common_ext::{node::Node, worker::Worker};
+use bee_common_ext::{event::Bus, node::Node, worker::Worker};
+use bee_storage::storage::Backend;
 
 use async_trait::async_trait;
 use futures::{channel::oneshot, StreamExt};
 use log::{error, info};
 use tokio::time::interval;
 
-use std::{any::TypeId, time::Duration};
+use std::{
+    any::TypeId,
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+// Returns `true` if all milestones up to the latest one are solid, dispatching a `TangleSyncedEvent` unless `synced`
+// tells that it was already dispatched since the tangle last fell behind. The tangle is not considered synced until a
+// milestone above the snapshot index is known.
+fn dispatch_if_synced<B: Backend>(tangle: &MsTangle<B>, bus: &Bus<'static>, synced: &AtomicBool) -> bool {
+    let latest_ms = tangle.get_latest_milestone_index();
+
+    if latest_ms <= tangle.get_snapshot_index() || !tangle.is_synced() {
+        synced.store(false, Ordering::Relaxed);
+        return false;
+    }
+
+    if !synced.swap(true, Ordering::Relaxed) {
+        bus.dispatch(TangleSyncedEvent { latest_ms });
+    }
+
+    true
+}
+
+/// Makes `milestone` the latest solid milestone, dispatching a `TangleSyncedEvent` if it caught up with the latest one.
+pub(crate) fn update_latest_solid_milestone<B: Backend>(
+    tangle: &MsTangle<B>,
+    bus: &Bus<'static>,
+    synced: &AtomicBool,
+    milestone: Milestone,
+) {
+    tangle.update_latest_solid_milestone_index(milestone.index);
+    bus.dispatch(LatestSolidMilestoneChanged(milestone));
+    dispatch_if_synced(tangle, bus, synced);
+}
 
 #[derive(Default)]
 pub(crate) struct KickstartWorker {}
@@ -52,17 +88,26 @@ impl<N: Node> Worker<N> for KickstartWorker {
             let mut receiver = ShutdownStream::new(shutdown, interval(Duration::from_secs(1)));
 
             while receiver.next().await.is_some() {
+                if dispatch_if_synced(&tangle, &Protocol::get().bus, &Protocol::get().synced) {
+                    // Nothing to kickstart, the solidifier carries on from the next milestone.
+                    let next_ms = *tangle.get_latest_solid_milestone_index() + 1;
+                    if config.0.send(MilestoneIndex::from(next_ms)).is_err() {
+                        error!("Could not set first non-solid milestone");
+                    }
+                    break;
+                }
+
                 let next_ms = *tangle.get_latest_solid_milestone_index() + 1;
                 let latest_ms = *tangle.get_latest_milestone_index();
 
                 if !Protocol::get().peer_manager.handshaked_peers.is_empty() && next_ms + config.1 < latest_ms {
-                    Protocol::request_milestone(&tangle, &milestone_requester, MilestoneIndex(next_ms), None);
-                    if config.0.send(MilestoneIndex(next_ms)).is_err() {
+                    Protocol::request_milestone(&tangle, &milestone_requester, MilestoneIndex::from(next_ms), None);
+                    if config.0.send(MilestoneIndex::from(next_ms)).is_err() {
                         error!("Could not set first non-solid milestone");
                     }
 
                     for index in next_ms..(next_ms + config.1) {
-                        Protocol::request_milestone(&tangle, &milestone_requester, MilestoneIndex(index), None);
+                        Protocol::request_milestone(&tangle, &milestone_requester, MilestoneIndex::from(index), None);
                     }
                     break;
                 }
@@ -74,3 +119,88 @@ impl<N: Node> Worker<N> for KickstartWorker {
         Ok(Self::default())
     }
 }
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use crate::test_utils::{indexed_hash, MockStorage};
+
+    use bee_common_ext::node::ResHandle;
+
+    use std::sync::{Arc, Mutex};
+
+    fn tangle(snapshot: u32, solid: u32, latest: u32) -> MsTangle<MockStorage> {
+        let tangle = MsTangle::new(ResHandle::new(MockStorage::default()));
+
+        tangle.update_snapshot_index(MilestoneIndex(snapshot));
+        tangle.update_latest_solid_milestone_index(MilestoneIndex(solid));
+        tangle.update_latest_milestone_index(MilestoneIndex(latest));
+
+        tangle
+    }
+
+    fn synced_events(bus: &Bus<'static>) -> Arc<Mutex<Vec<MilestoneIndex>>> {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let events_clone = events.clone();
+
+        bus.add_listener(move |event: &TangleSyncedEvent| events_clone.lock().unwrap().push(event.latest_ms));
+
+        events
+    }
+
+    #[test]
+    fn synced_at_latest_milestone() {
+        let bus = Bus::default();
+        let events = synced_events(&bus);
+
+        assert!(dispatch_if_synced(&tangle(10, 42, 42), &bus, &AtomicBool::new(false)));
+        assert_eq!(*events.lock().unwrap(), vec![MilestoneIndex(42)]);
+    }
+
+    #[test]
+    fn not_synced_behind_latest_milestone() {
+        let bus = Bus::default();
+        let events = synced_events(&bus);
+
+        assert!(!dispatch_if_synced(&tangle(10, 41, 42), &bus, &AtomicBool::new(false)));
+        assert!(events.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn not_synced_without_milestone_above_snapshot() {
+        let bus = Bus::default();
+        let events = synced_events(&bus);
+
+        assert!(!dispatch_if_synced(&tangle(10, 10, 10), &bus, &AtomicBool::new(false)));
+        assert!(events.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn synced_once_solid_milestone_reaches_latest() {
+        let bus = Bus::default();
+        let events = synced_events(&bus);
+        let tangle = tangle(10, 40, 42);
+        let synced = AtomicBool::new(false);
+        let milestone = |index: u32| Milestone::new(indexed_hash(index as usize), MilestoneIndex(index));
+
+        update_latest_solid_milestone(&tangle, &bus, &synced, milestone(41));
+        assert!(events.lock().unwrap().is_empty());
+
+        update_latest_solid_milestone(&tangle, &bus, &synced, milestone(42));
+        assert_eq!(tangle.get_latest_solid_milestone_index(), MilestoneIndex(42));
+        assert_eq!(*events.lock().unwrap(), vec![MilestoneIndex(42)]);
+
+        // Following the next milestones doesn't make the tangle synced again.
+        tangle.update_latest_milestone_index(MilestoneIndex(43));
+        update_latest_solid_milestone(&tangle, &bus, &synced, milestone(43));
+        assert_eq!(*events.lock().unwrap(), vec![MilestoneIndex(42)]);
+
+        // Catching up again after falling behind does.
+        tangle.update_latest_milestone_index(MilestoneIndex(45));
+        update_latest_solid_milestone(&tangle, &bus, &synced, milestone(44));
+        update_latest_solid_milestone(&tangle, &bus, &synced, milestone(45));
+        assert_eq!(*events.lock().unwrap(), vec![MilestoneIndex(42), MilestoneIndex(45)]);
+    }
+}
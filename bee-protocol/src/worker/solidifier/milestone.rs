@@ -10,62 +10,149 @@
 // See the License for the specific language governing permissions and limitations under the License.
 
 use crate::{
-    milestone::MilestoneIndex,
+    milestone::{max_cone_size, MilestoneIndex},
     protocol::Protocol,
-    tangle::MsTangle,
+    tangle::{MsTangle, TransactionMetadata},
     worker::{TangleWorker, TransactionRequesterWorker, TransactionRequesterWorkerEvent},
 };
 
 use bee_common::{shutdown_stream::ShutdownStream, worker::Error as WorkerError};
-use bee_common_ext::{node::Node, worker::Worker};
+use bee_common_ext::{
+    channel,
+    node::{Node, ShutdownFlag},
+    wait_priority_queue::WaitPriorityQueue,
+    worker::Worker,
+};
+use bee_crypto::ternary::Hash;
 use bee_storage::storage::Backend;
-use bee_tangle::traversal;
+use bee_tangle::traversal::{self, Cancelled};
 
 use async_trait::async_trait;
-use futures::{channel::oneshot, StreamExt};
+use futures::{channel::oneshot, lock::Mutex, StreamExt};
 use log::{debug, info};
 
-use std::any::TypeId;
+use std::{any::TypeId, cell::Cell, sync::atomic::AtomicBool};
 
 pub(crate) struct MilestoneSolidifierWorkerEvent(pub MilestoneIndex);
 
 pub(crate) struct MilestoneSolidifierWorker {
-    pub(crate) tx: flume::Sender<MilestoneSolidifierWorkerEvent>,
+    pub(crate) tx: channel::Sender<MilestoneSolidifierWorkerEvent>,
 }
 
+// Walks the past cone of `target_hash` through the transactions matching `walk`, collecting the missing ones. The walk
+// is cut short once more than `max_cone_size` transactions are walked through, in which case only the transactions
+// missing from the walked part are returned, along with `false`.
+fn missing_in_cone<B: Backend, Walk>(
+    tangle: &MsTangle<B>,
+    target_hash: Hash,
+    walk: Walk,
+    max_cone_size: Option<usize>,
+    cancel: &AtomicBool,
+) -> Result<(Vec<Hash>, bool), Cancelled>
+where
+    Walk: Fn(&Hash, &TransactionMetadata) -> bool,
+{
+    // TODO: This wouldn't be necessary if the traversal code wasn't closure-driven
+    let mut missing = Vec::new();
+    let cone_size = Cell::new(0);
+    let too_large = Cell::new(false);
+
+    traversal::visit_parents_depth_first(
+        &**tangle,
+        target_hash,
+        |hash, _, metadata| {
+            if !walk(hash, metadata) {
+                return false;
+            }
+
+            cone_size.set(cone_size.get() + 1);
+
+            // Not going any further bounds the walk to the transactions that are already queued.
+            if max_cone_size.map_or(false, |max| cone_size.get() > max) {
+                too_large.set(true);
+                return false;
+            }
+
+            true
+        },
+        |_, _, _| {},
+        |_, _, _| {},
+        |missing_hash| missing.push(*missing_hash),
+        cancel,
+    )?;
+
+    Ok((missing, !too_large.get()))
+}
+
+// Requests the transactions missing from the past cone of the milestone at `target_index`, then moves on to the next
+// milestone. A cone too large to be walked at once only gets its walked part requested and the milestone stays the
+// next one to solidify, `true` being returned so that its cone is walked again on the next trigger. The same goes for
+// a milestone triggered while the tangle is revalidated, the `revalidation` lock being held for the whole step.
 async fn trigger_solidification_unchecked<B: Backend>(
     tangle: &MsTangle<B>,
-    transaction_requester: &flume::Sender<TransactionRequesterWorkerEvent>,
+    transaction_requester: &WaitPriorityQueue<TransactionRequesterWorkerEvent>,
     target_index: MilestoneIndex,
     next_ms_index: &mut MilestoneIndex,
-) {
+    max_cone_size_per_milestone: usize,
+    shutdown_flag: &ShutdownFlag,
+    revalidation: &Mutex<()>,
+) -> bool {
+    let _revalidation = match revalidation.try_lock() {
+        Some(guard) => guard,
+        None => {
+            debug!(
+                "Solidification of milestone {} deferred until revalidation completes.",
+                *target_index
+            );
+            return true;
+        }
+    };
+
     if let Some(target_hash) = tangle.get_milestone_hash(target_index) {
         if !tangle.is_solid_transaction(&target_hash) {
             debug!("Triggered solidification for milestone {}.", *target_index);
 
-            // TODO: This wouldn't be necessary if the traversal code wasn't closure-driven
-            let mut missing = Vec::new();
-
-            traversal::visit_parents_depth_first(
-                &**tangle,
+            let max_cone_size = max_cone_size(
+                max_cone_size_per_milestone,
+                tangle.get_latest_milestone_index(),
+                tangle.get_latest_solid_milestone_index(),
+            );
+            let missing = missing_in_cone(
+                tangle,
                 target_hash,
-                |hash, _, metadata| {
+                |hash, metadata| {
                     (!metadata.flags().is_requested() || *hash == target_hash)
                         && !metadata.flags().is_solid()
                         && !Protocol::get().requested_transactions.contains_key(&hash)
                 },
-                |_, _, _| {},
-                |_, _, _| {},
-                |missing_hash| missing.push(*missing_hash),
+                max_cone_size,
+                shutdown_flag,
             );
 
+            let (missing, complete) = match missing {
+                Ok(walk) => walk,
+                // The node is shutting down, the milestone is left for the next run.
+                Err(Cancelled) => {
+                    debug!("Solidification of milestone {} cancelled.", *target_index);
+                    return false;
+                }
+            };
+
             for missing_hash in missing {
                 Protocol::request_transaction(tangle, transaction_requester, missing_hash, target_index).await;
             }
 
-            *next_ms_index = target_index + MilestoneIndex(1);
+            match max_cone_size {
+                Some(max_cone_size) if !complete => {
+                    Protocol::milestone_cone_too_large(target_index, max_cone_size);
+                    return true;
+                }
+                _ => *next_ms_index = target_index + MilestoneIndex::from(1),
+            }
         }
     }
+
+    false
 }
 
 fn save_index(target_index: MilestoneIndex, queue: &mut Vec<MilestoneIndex>) {
@@ -77,7 +164,7 @@ fn save_index(target_index: MilestoneIndex, queue: &mut Vec<MilestoneIndex>) {
 
 #[async_trait]
 impl<N: Node> Worker<N> for MilestoneSolidifierWorker {
-    type Config = oneshot::Receiver<MilestoneIndex>;
+    type Config = (oneshot::Receiver<MilestoneIndex>, usize);
     type Error = WorkerError;
 
     fn dependencies() -> &'static [TypeId] {
@@ -88,10 +175,11 @@ impl<N: Node> Worker<N> for MilestoneSolidifierWorker {
     }
 
     async fn start(node: &mut N, config: Self::Config) -> Result<Self, Self::Error> {
-        let (tx, rx) = flume::unbounded();
-        let transaction_requester = node.worker::<TransactionRequesterWorker>().unwrap().tx.clone();
+        let (tx, rx) = channel::unbounded("MilestoneSolidifierWorker");
+        let transaction_requester = node.worker::<TransactionRequesterWorker>().unwrap().queue.clone();
 
         let tangle = node.resource::<MsTangle<N::Backend>>();
+        let shutdown_flag = node.resource::<ShutdownFlag>();
 
         node.spawn::<Self, _, _>(|shutdown| async move {
             info!("Running.");
@@ -99,14 +187,29 @@ impl<N: Node> Worker<N> for MilestoneSolidifierWorker {
             let mut receiver = ShutdownStream::new(shutdown, rx.into_stream());
 
             let mut queue = vec![];
-            let mut next_ms_index = config.await.unwrap();
+            let (next_ms_index, max_cone_size) = config;
+            let mut next_ms_index = next_ms_index.await.unwrap();
 
             while let Some(MilestoneSolidifierWorkerEvent(index)) = receiver.next().await {
                 save_index(index, &mut queue);
                 while let Some(index) = queue.pop() {
                     if index == next_ms_index {
-                        trigger_solidification_unchecked(&tangle, &transaction_requester, index, &mut next_ms_index)
-                            .await;
+                        let deferred = trigger_solidification_unchecked(
+                            &tangle,
+                            &transaction_requester,
+                            index,
+                            &mut next_ms_index,
+                            max_cone_size,
+                            &shutdown_flag,
+                            &Protocol::get().revalidation,
+                        )
+                        .await;
+
+                        // The milestone is triggered again with the next event.
+                        if deferred {
+                            save_index(index, &mut queue);
+                            break;
+                        }
                     } else {
                         queue.push(index);
                         break;
@@ -120,3 +223,199 @@ impl<N: Node> Worker<N> for MilestoneSolidifierWorker {
         Ok(Self { tx })
     }
 }
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use crate::{
+        config::ProtocolConfig,
+        event::MilestoneConeTooLarge,
+        test_utils::{indexed_hash as hash, init_protocol, MockStorage},
+    };
+
+    use bee_common_ext::{event::Bus, node::ResHandle};
+    use bee_transaction::bundled::{
+        Address, BundledTransactionBuilder, BundledTransactionField, Index, Nonce, Payload, Tag, Timestamp, Value,
+    };
+
+    use std::sync::Arc;
+
+    // Builds a chain of `len` transactions on top of the missing `hash(0)` and returns the hash of its head.
+    async fn chain(tangle: &MsTangle<MockStorage>, len: usize) -> Hash {
+        for n in 1..=len {
+            let transaction = BundledTransactionBuilder::new()
+                .with_payload(Payload::zeros())
+                .with_address(Address::zeros())
+                .with_value(Value::from_inner_unchecked(0))
+                .with_obsolete_tag(Tag::zeros())
+                .with_timestamp(Timestamp::from_inner_unchecked(0))
+                .with_index(Index::from_inner_unchecked(0))
+                .with_last_index(Index::from_inner_unchecked(0))
+                .with_tag(Tag::zeros())
+                .with_attachment_ts(Timestamp::from_inner_unchecked(0))
+                .with_bundle(hash(0))
+                .with_trunk(hash(n - 1))
+                .with_branch(hash(n - 1))
+                .with_attachment_lbts(Timestamp::from_inner_unchecked(0))
+                .with_attachment_ubts(Timestamp::from_inner_unchecked(0))
+                .with_nonce(Nonce::zeros())
+                .build_unchecked()
+                .unwrap();
+
+            tangle
+                .insert(transaction, hash(n), TransactionMetadata::default())
+                .await;
+        }
+
+        hash(len)
+    }
+
+    #[tokio::test]
+    async fn cone_within_cap() {
+        let tangle = MsTangle::new(ResHandle::new(MockStorage::default()));
+        let head = chain(&tangle, 5).await;
+
+        assert_eq!(
+            missing_in_cone(&tangle, head, |_, _| true, Some(5), &AtomicBool::new(false)),
+            Ok((vec![hash(0)], true))
+        );
+        assert_eq!(
+            missing_in_cone(&tangle, head, |_, _| true, None, &AtomicBool::new(false)),
+            Ok((vec![hash(0)], true))
+        );
+    }
+
+    #[tokio::test]
+    async fn cone_over_cap() {
+        let tangle = MsTangle::new(ResHandle::new(MockStorage::default()));
+        let head = chain(&tangle, 5).await;
+
+        // The walk stops before reaching the missing transaction at the bottom of the chain.
+        assert_eq!(
+            missing_in_cone(&tangle, head, |_, _| true, Some(4), &AtomicBool::new(false)),
+            Ok((vec![], false))
+        );
+    }
+
+    #[tokio::test]
+    async fn unwalked_transactions_not_counted() {
+        let tangle = MsTangle::new(ResHandle::new(MockStorage::default()));
+        let head = chain(&tangle, 5).await;
+        let stop = hash(2);
+
+        // The walk stops at the second transaction of the chain, which is as if it was already solid.
+        assert_eq!(
+            missing_in_cone(&tangle, head, |hash, _| *hash != stop, Some(3), &AtomicBool::new(false)),
+            Ok((vec![], true))
+        );
+    }
+
+    #[tokio::test]
+    async fn walk_cancelled() {
+        let tangle = MsTangle::new(ResHandle::new(MockStorage::default()));
+        let head = chain(&tangle, traversal::CANCELLATION_CHECK_INTERVAL).await;
+
+        assert_eq!(
+            missing_in_cone(&tangle, head, |_, _| true, None, &AtomicBool::new(true)),
+            Err(Cancelled)
+        );
+    }
+
+    #[tokio::test]
+    async fn solidification_deferred_while_revalidating() {
+        let tangle = MsTangle::new(ResHandle::new(MockStorage::default()));
+        let transaction_requester = WaitPriorityQueue::default();
+        let shutdown_flag = ShutdownFlag::default();
+        let revalidation = Mutex::new(());
+        let mut next_ms_index = MilestoneIndex(1);
+
+        let guard = revalidation.lock().await;
+        assert!(
+            trigger_solidification_unchecked(
+                &tangle,
+                &transaction_requester,
+                MilestoneIndex(1),
+                &mut next_ms_index,
+                0,
+                &shutdown_flag,
+                &revalidation,
+            )
+            .await
+        );
+        drop(guard);
+
+        // The milestone is unknown, there is nothing to solidify yet.
+        assert!(
+            !trigger_solidification_unchecked(
+                &tangle,
+                &transaction_requester,
+                MilestoneIndex(1),
+                &mut next_ms_index,
+                0,
+                &shutdown_flag,
+                &revalidation,
+            )
+            .await
+        );
+        assert_eq!(next_ms_index, MilestoneIndex(1));
+        // The step releases the lock for the revalidation once done.
+        assert!(revalidation.try_lock().is_some());
+    }
+
+    #[tokio::test]
+    async fn oversized_cone_deferred() {
+        let bus = Arc::new(Bus::default());
+        let events = Arc::new(spin::Mutex::new(Vec::new()));
+        let events_clone = events.clone();
+
+        bus.add_listener(move |event: &MilestoneConeTooLarge| {
+            events_clone.lock().push((event.index, event.max_cone_size))
+        });
+
+        let _protocol = init_protocol(ProtocolConfig::build().finish(), bus).await;
+        let tangle = MsTangle::new(ResHandle::new(MockStorage::default()));
+        let transaction_requester = WaitPriorityQueue::default();
+        let shutdown_flag = ShutdownFlag::default();
+        let revalidation = Mutex::new(());
+        let mut next_ms_index = MilestoneIndex(1);
+
+        tangle.add_milestone(MilestoneIndex(1), chain(&tangle, 5).await);
+
+        // The walk is cut short before reaching the missing transaction at the bottom of the chain.
+        assert!(
+            trigger_solidification_unchecked(
+                &tangle,
+                &transaction_requester,
+                MilestoneIndex(1),
+                &mut next_ms_index,
+                3,
+                &shutdown_flag,
+                &revalidation,
+            )
+            .await
+        );
+        assert_eq!(next_ms_index, MilestoneIndex(1));
+        assert!(transaction_requester.is_empty());
+        assert_eq!(*events.lock(), vec![(MilestoneIndex(1), 3)]);
+        assert_eq!(Protocol::get().metrics.oversized_milestones(), 1);
+
+        // Within the cap, the missing transaction is requested and the next milestone becomes the one to solidify.
+        assert!(
+            !trigger_solidification_unchecked(
+                &tangle,
+                &transaction_requester,
+                MilestoneIndex(1),
+                &mut next_ms_index,
+                10,
+                &shutdown_flag,
+                &revalidation,
+            )
+            .await
+        );
+        assert_eq!(next_ms_index, MilestoneIndex(2));
+        assert_eq!(transaction_requester.len(), 1);
+        assert_eq!(events.lock().len(), 1);
+    }
+}
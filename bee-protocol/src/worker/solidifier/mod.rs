@@ -13,6 +13,6 @@ mod kickstart;
 mod milestone;
 mod propagator;
 
-pub(crate) use kickstart::KickstartWorker;
+pub(crate) use kickstart::{update_latest_solid_milestone, KickstartWorker};
 pub(crate) use milestone::{MilestoneSolidifierWorker, MilestoneSolidifierWorkerEvent};
 pub(crate) use propagator::{SolidPropagatorWorker, SolidPropagatorWorkerEvent};
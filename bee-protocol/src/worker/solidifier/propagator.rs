@@ -10,15 +10,15 @@
 // See the License for the specific language governing permissions and limitations under the License.
 
 use crate::{
-    event::{LatestSolidMilestoneChanged, TransactionSolidified},
+    event::TransactionSolidified,
     milestone::Milestone,
     protocol::Protocol,
     tangle::MsTangle,
-    worker::{BundleValidatorWorker, BundleValidatorWorkerEvent, TangleWorker},
+    worker::{update_latest_solid_milestone, BundleValidatorWorker, BundleValidatorWorkerEvent, TangleWorker},
 };
 
 use bee_common::{shutdown_stream::ShutdownStream, worker::Error as WorkerError};
-use bee_common_ext::{node::Node, worker::Worker};
+use bee_common_ext::{channel, node::Node, worker::Worker};
 use bee_crypto::ternary::Hash;
 use bee_transaction::Vertex;
 
@@ -31,7 +31,7 @@ use std::any::TypeId;
 pub(crate) struct SolidPropagatorWorkerEvent(pub(crate) Hash);
 
 pub(crate) struct SolidPropagatorWorker {
-    pub(crate) tx: flume::Sender<SolidPropagatorWorkerEvent>,
+    pub(crate) tx: channel::Sender<SolidPropagatorWorkerEvent>,
 }
 
 #[async_trait]
@@ -47,7 +47,7 @@ impl<N: Node> Worker<N> for SolidPropagatorWorker {
     }
 
     async fn start(node: &mut N, _config: Self::Config) -> Result<Self, Self::Error> {
-        let (tx, rx) = flume::unbounded();
+        let (tx, rx) = channel::unbounded("SolidPropagatorWorker");
         let bundle_validator = node.worker::<BundleValidatorWorker>().unwrap().tx.clone();
 
         let tangle = node.resource::<MsTangle<N::Backend>>();
@@ -95,9 +95,12 @@ impl<N: Node> Worker<N> for SolidPropagatorWorker {
                         }
 
                         if let Some(index) = index {
-                            Protocol::get()
-                                .bus
-                                .dispatch(LatestSolidMilestoneChanged(Milestone { hash: *hash, index }));
+                            update_latest_solid_milestone(
+                                &tangle,
+                                &Protocol::get().bus,
+                                &Protocol::get().synced,
+                                Milestone { hash: *hash, index },
+                            );
                         }
                     }
                 }
@@ -9,18 +9,124 @@
 // an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and limitations under the License.
 
-use crate::{protocol::Protocol, tangle::MsTangle, worker::TangleWorker};
+use crate::{
+    event::{NodeStatusEvent, TangleSyncedEvent},
+    peer::PeerManager,
+    protocol::{Protocol, ProtocolMetrics},
+    tangle::MsTangle,
+    worker::TangleWorker,
+};
 
 use bee_common::{shutdown_stream::ShutdownStream, worker::Error as WorkerError};
-use bee_common_ext::{node::Node, worker::Worker};
+use bee_common_ext::{event::Bus, node::Node, worker::Worker};
+use bee_storage::storage::Backend;
 
 use async_trait::async_trait;
 use futures::StreamExt;
 use log::info;
+use sysinfo::{ProcessExt, System, SystemExt};
 use tokio::time::interval;
 
 use std::{any::TypeId, time::Duration};
 
+// Resident memory of the node process in bytes, 0 if it can't be read.
+fn memory_bytes(system: &mut System) -> u64 {
+    let pid = match sysinfo::get_current_pid() {
+        Ok(pid) => pid,
+        Err(_) => return 0,
+    };
+
+    if !system.refresh_process(pid) {
+        return 0;
+    }
+
+    // Reported in KiB.
+    system.get_process(pid).map_or(0, |process| process.memory() * 1024)
+}
+
+// Percentage of the milestones since the snapshot that are solid.
+fn sync_progress(snapshot_index: u32, latest_solid_milestone_index: u32, latest_milestone_index: u32) -> u8 {
+    let solid = latest_solid_milestone_index.saturating_sub(snapshot_index);
+    let total = latest_milestone_index.saturating_sub(snapshot_index);
+
+    if total == 0 {
+        return 100;
+    }
+
+    (solid.min(total) as f32 * 100.0 / total as f32) as u8
+}
+
+// Status of the node, `received` transactions having been received in total and `previously_received` at the previous
+// status, `interval` seconds ago.
+#[allow(clippy::too_many_arguments)]
+fn node_status(
+    received: u64,
+    previously_received: u64,
+    interval: u64,
+    solid_ms_index: u32,
+    latest_ms_index: u32,
+    connected_peers: u8,
+    synced_peers: u8,
+    memory_bytes: u64,
+) -> NodeStatusEvent {
+    NodeStatusEvent {
+        tps: received.saturating_sub(previously_received) as f64 / interval.max(1) as f64,
+        solid_ms_index,
+        latest_ms_index,
+        connected_peers,
+        synced_peers,
+        memory_bytes,
+    }
+}
+
+// Logs the status of the node and dispatches it on `bus`, `previously_received` transactions having been received at
+// the previous status, `interval` seconds ago. Returns the number of transactions received so far.
+#[allow(clippy::too_many_arguments)]
+fn report_status<B: Backend>(
+    tangle: &MsTangle<B>,
+    bus: &Bus,
+    metrics: &ProtocolMetrics,
+    peer_manager: &PeerManager,
+    requested_transactions: usize,
+    system: &mut System,
+    previously_received: u64,
+    interval: u64,
+) -> u64 {
+    let snapshot_index = *tangle.get_snapshot_index();
+    let latest_solid_milestone_index = *tangle.get_latest_solid_milestone_index();
+    let latest_milestone_index = *tangle.get_latest_milestone_index();
+
+    // TODO Threshold
+    // TODO use tangle synced method
+    if latest_solid_milestone_index == latest_milestone_index {
+        info!("Synchronized at {}.", latest_milestone_index);
+    } else {
+        info!(
+            "Synchronizing {}..{}..{} ({}%) - Requested {}.",
+            snapshot_index,
+            latest_solid_milestone_index,
+            latest_milestone_index,
+            sync_progress(snapshot_index, latest_solid_milestone_index, latest_milestone_index),
+            requested_transactions
+        );
+    };
+
+    let received = metrics.transactions_received();
+
+    bus.dispatch(node_status(
+        received,
+        previously_received,
+        interval,
+        latest_solid_milestone_index,
+        latest_milestone_index,
+        peer_manager.connected_peers(),
+        peer_manager.synced_peers(),
+        memory_bytes(system),
+    ));
+
+    received
+}
+
 #[derive(Default)]
 pub(crate) struct StatusWorker;
 
@@ -36,32 +142,28 @@ impl<N: Node> Worker<N> for StatusWorker {
     async fn start(node: &mut N, config: Self::Config) -> Result<Self, Self::Error> {
         let tangle = node.resource::<MsTangle<N::Backend>>();
 
+        Protocol::get()
+            .bus
+            .add_listener(|_: &TangleSyncedEvent| info!("Node is synced!"));
+
         node.spawn::<Self, _, _>(|shutdown| async move {
             info!("Running.");
 
             let mut receiver = ShutdownStream::new(shutdown, interval(Duration::from_secs(config)));
+            let mut system = System::new();
+            let mut total_received = Protocol::get().metrics.transactions_received();
 
             while receiver.next().await.is_some() {
-                let snapshot_index = *tangle.get_snapshot_index();
-                let latest_solid_milestone_index = *tangle.get_latest_solid_milestone_index();
-                let latest_milestone_index = *tangle.get_latest_milestone_index();
-
-                // TODO Threshold
-                // TODO use tangle synced method
-                if latest_solid_milestone_index == latest_milestone_index {
-                    info!("Synchronized at {}.", latest_milestone_index);
-                } else {
-                    let progress = ((latest_solid_milestone_index - snapshot_index) as f32 * 100.0
-                        / (latest_milestone_index - snapshot_index) as f32) as u8;
-                    info!(
-                        "Synchronizing {}..{}..{} ({}%) - Requested {}.",
-                        snapshot_index,
-                        latest_solid_milestone_index,
-                        latest_milestone_index,
-                        progress,
-                        Protocol::get().requested_transactions.len()
-                    );
-                };
+                total_received = report_status(
+                    &tangle,
+                    &Protocol::get().bus,
+                    &Protocol::get().metrics,
+                    &Protocol::get().peer_manager,
+                    Protocol::get().requested_transactions.len(),
+                    &mut system,
+                    total_received,
+                    config,
+                );
             }
 
             info!("Stopped.");
@@ -70,3 +172,83 @@ impl<N: Node> Worker<N> for StatusWorker {
         Ok(Self::default())
     }
 }
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use crate::test_utils::MockStorage;
+
+    use bee_common_ext::node::ResHandle;
+
+    use std::sync::Mutex;
+
+    #[test]
+    fn memory_bytes_of_current_process() {
+        assert!(memory_bytes(&mut System::new()) > 0);
+    }
+
+    #[test]
+    fn status_tps() {
+        let status = node_status(1_250, 1_000, 10, 41, 42, 4, 3, 1_024);
+
+        assert!((status.tps - 25.0).abs() < f64::EPSILON);
+        assert_eq!(status.solid_ms_index, 41);
+        assert_eq!(status.latest_ms_index, 42);
+        assert_eq!(status.connected_peers, 4);
+        assert_eq!(status.synced_peers, 3);
+        assert_eq!(status.memory_bytes, 1_024);
+    }
+
+    #[test]
+    fn status_tps_after_counter_reset() {
+        assert!(node_status(10, 1_000, 10, 41, 42, 4, 3, 0).tps.abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn status_sync_progress() {
+        assert_eq!(sync_progress(100, 150, 200), 50);
+        assert_eq!(sync_progress(100, 200, 200), 100);
+        assert_eq!(sync_progress(100, 100, 100), 100);
+        // The solid milestone may lag behind the snapshot index right after a restart.
+        assert_eq!(sync_progress(100, 90, 200), 0);
+    }
+
+    #[test]
+    fn status_dispatched_on_bus() {
+        let tangle = MsTangle::new(ResHandle::new(MockStorage::default()));
+        let metrics = ProtocolMetrics::new();
+        let peer_manager = PeerManager::new();
+        let mut system = System::new();
+        let statuses = Mutex::new(Vec::new());
+        let bus = Bus::default();
+
+        bus.add_listener(|status: &NodeStatusEvent| statuses.lock().unwrap().push(*status));
+
+        tangle.update_latest_solid_milestone_index(41.into());
+        tangle.update_latest_milestone_index(42.into());
+
+        let received = report_status(&tangle, &bus, &metrics, &peer_manager, 0, &mut system, 0, 10);
+        for _ in 0..50 {
+            metrics.transactions_received_inc();
+        }
+        assert_eq!(
+            report_status(&tangle, &bus, &metrics, &peer_manager, 0, &mut system, received, 10),
+            50
+        );
+
+        drop(bus);
+        let statuses = statuses.into_inner().unwrap();
+
+        assert_eq!(statuses.len(), 2);
+        assert!(statuses[0].tps.abs() < f64::EPSILON);
+        assert!((statuses[1].tps - 5.0).abs() < f64::EPSILON);
+        for status in statuses.iter() {
+            assert_eq!(status.solid_ms_index, 41);
+            assert_eq!(status.latest_ms_index, 42);
+            assert_eq!(status.connected_peers, 0);
+            assert!(status.memory_bytes > 0);
+        }
+    }
+}
@@ -9,14 +9,18 @@
 // an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and limitations under the License.
 
-use crate::{tangle::MsTangle, worker::storage::StorageWorker, MilestoneIndex};
+use crate::{
+    tangle::{MsTangle, SolidEntryPointsStorage},
+    worker::storage::StorageWorker,
+    MilestoneIndex,
+};
 
 use bee_common::shutdown_stream::ShutdownStream;
 use bee_common_ext::{node::Node, worker::Worker};
 use bee_snapshot::metadata::SnapshotMetadata;
 
 use async_trait::async_trait;
-use log::{error, warn};
+use log::{debug, error, info, warn};
 use tokio::time::interval;
 
 use std::{
@@ -25,11 +29,17 @@ use std::{
     time::{Duration, Instant},
 };
 
+// Children linked to parents below the solid entry points are only a few, cleaning their links can wait.
+const ORPHAN_LINKS_CLEANING_INTERVAL_SECS: u64 = 60;
+
 pub struct TangleWorker;
 
 #[async_trait]
-impl<N: Node> Worker<N> for TangleWorker {
-    type Config = SnapshotMetadata;
+impl<N: Node> Worker<N> for TangleWorker
+where
+    N::Backend: SolidEntryPointsStorage,
+{
+    type Config = (SnapshotMetadata, usize);
     type Error = Infallible;
 
     fn dependencies() -> &'static [TypeId] {
@@ -37,8 +47,9 @@ impl<N: Node> Worker<N> for TangleWorker {
     }
 
     async fn start(node: &mut N, config: Self::Config) -> Result<Self, Self::Error> {
+        let (config, ms_hash_cache) = config;
         let storage = node.storage();
-        let tangle = MsTangle::<N::Backend>::new(storage);
+        let tangle = MsTangle::<N::Backend>::with_milestone_hash_cache(storage, ms_hash_cache);
 
         node.register_resource(tangle);
 
@@ -48,9 +59,19 @@ impl<N: Node> Worker<N> for TangleWorker {
         tangle.update_latest_milestone_index(config.index().into());
         tangle.update_snapshot_index(config.index().into());
         tangle.update_pruning_index(config.index().into());
+        tangle.update_entry_point_index(config.index().into());
 
         for (hash, index) in config.solid_entry_points() {
-            tangle.add_solid_entry_point(*hash, MilestoneIndex(*index));
+            tangle.add_solid_entry_point(*hash, MilestoneIndex::from(*index));
+        }
+        // Pruning may have moved the solid entry points past the ones of the snapshot file.
+        match tangle.load_solid_entry_points().await {
+            Ok(true) => info!(
+                "Loaded solid entry points of entry point index {} from storage.",
+                *tangle.get_entry_point_index()
+            ),
+            Ok(false) => (),
+            Err(e) => error!("Loading solid entry points from storage failed: {:?}.", e),
         }
         for _seen_milestone in config.seen_milestones() {
             // TODO request ?
@@ -58,13 +79,21 @@ impl<N: Node> Worker<N> for TangleWorker {
 
         node.spawn::<Self, _, _>(|shutdown| async move {
             use futures::StreamExt;
-            use std::time::Duration;
-            use tokio::time::interval;
 
-            let mut receiver = ShutdownStream::new(shutdown, interval(Duration::from_secs(1)));
+            let mut receiver = ShutdownStream::new(
+                shutdown,
+                interval(Duration::from_secs(ORPHAN_LINKS_CLEANING_INTERVAL_SECS)),
+            );
 
             while receiver.next().await.is_some() {
-                // println!("Tangle len = {}", tangle.len());
+                let cleaned = tangle.clean_orphan_links();
+
+                if cleaned != 0 {
+                    debug!(
+                        "Cleaned the links to {} unknown parents below the solid entry points.",
+                        cleaned
+                    );
+                }
             }
         });
 
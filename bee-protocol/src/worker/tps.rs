@@ -9,7 +9,10 @@
 // an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and limitations under the License.
 
-use crate::{event::TpsMetricsUpdated, protocol::Protocol};
+use crate::{
+    event::{TpsMetricsUpdated, TransactionRates},
+    protocol::Protocol,
+};
 
 use bee_common::{shutdown_stream::ShutdownStream, worker::Error as WorkerError};
 use bee_common_ext::{node::Node, worker::Worker};
@@ -19,21 +22,99 @@ use futures::StreamExt;
 use log::info;
 use tokio::time::interval;
 
-use std::time::Duration;
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+#[derive(Default)]
+struct Sample {
+    elapsed: Duration,
+    incoming: u64,
+    new: u64,
+    confirmed: u64,
+}
+
+/// Estimates transaction rates over a sliding window of per-interval counts.
+pub(crate) struct RateEstimator {
+    window: Duration,
+    samples: VecDeque<Sample>,
+    // Sum of the samples currently in the window.
+    total: Sample,
+}
+
+impl RateEstimator {
+    pub(crate) fn new(window: Duration) -> Self {
+        Self {
+            window,
+            samples: VecDeque::new(),
+            total: Sample::default(),
+        }
+    }
+
+    /// Records the counts observed during the last `elapsed` period and evicts samples that left the window.
+    pub(crate) fn record(&mut self, elapsed: Duration, incoming: u64, new: u64, confirmed: u64) {
+        self.total.elapsed += elapsed;
+        self.total.incoming += incoming;
+        self.total.new += new;
+        self.total.confirmed += confirmed;
+
+        self.samples.push_back(Sample {
+            elapsed,
+            incoming,
+            new,
+            confirmed,
+        });
+
+        // Always keep the latest sample, even if it alone spans more than the window.
+        while self.total.elapsed > self.window && self.samples.len() > 1 {
+            if let Some(sample) = self.samples.pop_front() {
+                self.total.elapsed -= sample.elapsed;
+                self.total.incoming -= sample.incoming;
+                self.total.new -= sample.new;
+                self.total.confirmed -= sample.confirmed;
+            }
+        }
+    }
+
+    pub(crate) fn rates(&self) -> TransactionRates {
+        let millis = self.total.elapsed.as_millis();
+
+        if millis == 0 {
+            return TransactionRates::default();
+        }
+
+        let per_second = |count: u64| count as f64 * 1000.0 / millis as f64;
+
+        TransactionRates {
+            itps: per_second(self.total.incoming),
+            ntps: per_second(self.total.new),
+            ctps: per_second(self.total.confirmed),
+            confirmation_ratio: if self.total.new == 0 {
+                0.0
+            } else {
+                self.total.confirmed as f64 / self.total.new as f64
+            },
+        }
+    }
+}
 
 #[derive(Default)]
 pub(crate) struct TpsWorker {}
 
 #[async_trait]
 impl<N: Node> Worker<N> for TpsWorker {
-    type Config = ();
+    // Sampling interval in milliseconds and window size in seconds.
+    type Config = (u64, u64);
     type Error = WorkerError;
 
-    async fn start(node: &mut N, _config: Self::Config) -> Result<Self, Self::Error> {
+    async fn start(node: &mut N, config: Self::Config) -> Result<Self, Self::Error> {
         node.spawn::<Self, _, _>(|shutdown| async move {
             info!("Running.");
 
-            let mut receiver = ShutdownStream::new(shutdown, interval(Duration::from_secs(1)));
+            let (interval_ms, window_secs) = config;
+            let mut receiver = ShutdownStream::new(shutdown, interval(Duration::from_millis(interval_ms)));
+            let mut estimator = RateEstimator::new(Duration::from_secs(window_secs));
 
             let mut total_incoming = 0u64;
             let mut total_new = 0u64;
@@ -41,6 +122,8 @@ impl<N: Node> Worker<N> for TpsWorker {
             let mut total_stale = 0u64;
             let mut total_invalid = 0u64;
             let mut total_outgoing = 0u64;
+            let mut total_confirmed = 0u64;
+            let mut last = Instant::now();
 
             while receiver.next().await.is_some() {
                 let incoming = Protocol::get().metrics.transactions_received();
@@ -49,6 +132,16 @@ impl<N: Node> Worker<N> for TpsWorker {
                 let stale = Protocol::get().metrics.stale_transactions();
                 let invalid = Protocol::get().metrics.invalid_transactions();
                 let outgoing = Protocol::get().metrics.transactions_sent();
+                let confirmed = Protocol::get().metrics.confirmed_transactions();
+
+                let now = Instant::now();
+                estimator.record(
+                    now - last,
+                    incoming - total_incoming,
+                    new - total_new,
+                    confirmed - total_confirmed,
+                );
+                last = now;
 
                 Protocol::get().bus.dispatch(TpsMetricsUpdated {
                     incoming: incoming - total_incoming,
@@ -57,6 +150,8 @@ impl<N: Node> Worker<N> for TpsWorker {
                     stale: stale - total_stale,
                     invalid: invalid - total_invalid,
                     outgoing: outgoing - total_outgoing,
+                    confirmed: confirmed - total_confirmed,
+                    rates: estimator.rates(),
                 });
 
                 total_incoming = incoming;
@@ -65,6 +160,7 @@ impl<N: Node> Worker<N> for TpsWorker {
                 total_stale = stale;
                 total_invalid = invalid;
                 total_outgoing = outgoing;
+                total_confirmed = confirmed;
             }
 
             info!("Stopped.");
@@ -73,3 +169,90 @@ impl<N: Node> Worker<N> for TpsWorker {
         Ok(Self::default())
     }
 }
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn assert_rate(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < f64::EPSILON, "{} != {}", actual, expected);
+    }
+
+    #[test]
+    fn rates_over_window() {
+        let mut estimator = RateEstimator::new(Duration::from_secs(10));
+
+        for _ in 0..10 {
+            estimator.record(Duration::from_secs(1), 20, 10, 5);
+        }
+
+        let rates = estimator.rates();
+
+        assert_rate(rates.itps, 20.0);
+        assert_rate(rates.ntps, 10.0);
+        assert_rate(rates.ctps, 5.0);
+        assert_rate(rates.confirmation_ratio, 0.5);
+    }
+
+    #[test]
+    fn rates_slide_out_of_window() {
+        let mut estimator = RateEstimator::new(Duration::from_secs(4));
+
+        for _ in 0..4 {
+            estimator.record(Duration::from_secs(1), 100, 100, 100);
+        }
+        for _ in 0..4 {
+            estimator.record(Duration::from_secs(1), 8, 4, 2);
+        }
+
+        let rates = estimator.rates();
+
+        assert_rate(rates.itps, 8.0);
+        assert_rate(rates.ntps, 4.0);
+        assert_rate(rates.ctps, 2.0);
+        assert_rate(rates.confirmation_ratio, 0.5);
+    }
+
+    #[test]
+    fn rates_with_uneven_intervals() {
+        let mut estimator = RateEstimator::new(Duration::from_secs(60));
+
+        estimator.record(Duration::from_millis(500), 3, 3, 0);
+        estimator.record(Duration::from_millis(1500), 5, 1, 2);
+
+        let rates = estimator.rates();
+
+        assert_rate(rates.itps, 4.0);
+        assert_rate(rates.ntps, 2.0);
+        assert_rate(rates.ctps, 1.0);
+        assert_rate(rates.confirmation_ratio, 0.5);
+    }
+
+    #[test]
+    fn rates_without_transactions() {
+        let mut estimator = RateEstimator::new(Duration::from_secs(60));
+
+        assert_eq!(estimator.rates(), TransactionRates::default());
+
+        estimator.record(Duration::from_secs(1), 0, 0, 0);
+
+        let rates = estimator.rates();
+
+        assert_rate(rates.itps, 0.0);
+        assert_rate(rates.ctps, 0.0);
+        assert_rate(rates.confirmation_ratio, 0.0);
+    }
+
+    #[test]
+    fn confirmations_without_new_transactions() {
+        let mut estimator = RateEstimator::new(Duration::from_secs(60));
+
+        estimator.record(Duration::from_secs(2), 0, 0, 4);
+
+        let rates = estimator.rates();
+
+        assert_rate(rates.ctps, 2.0);
+        assert_rate(rates.confirmation_ratio, 0.0);
+    }
+}
@@ -12,28 +12,28 @@
 #![allow(clippy::assertions_on_constants)]
 
 use crate::{
-    message::{uncompress_transaction_bytes, Transaction as TransactionMessage},
+    event::BatchHashingProgress,
+    message::Transaction as TransactionMessage,
     protocol::Protocol,
     worker::transaction::{HashCache, ProcessorWorker, ProcessorWorkerEvent},
 };
 
-use bee_common::{shutdown_stream::ShutdownStream, worker::Error as WorkerError};
-use bee_common_ext::{node::Node, worker::Worker};
+use bee_common::worker::Error as WorkerError;
+use bee_common_ext::{channel, node::Node, shutdown_stream::ShutdownStream, worker::Worker};
 use bee_crypto::ternary::{
     sponge::{BatchHasher, CurlPRounds, BATCH_SIZE},
     Hash,
 };
 use bee_network::EndpointId;
-use bee_ternary::{T5B1Buf, TritBuf, Trits, T5B1};
-use bee_transaction::bundled::{BundledTransactionField, TRANSACTION_TRIT_LEN};
+use bee_ternary::{T5B1Buf, TritBuf};
+use bee_transaction::bundled::{gossip_bytes_to_trits, BundledTransactionField, TRANSACTION_TRIT_LEN};
 
 use async_trait::async_trait;
-use bytemuck::cast_slice;
 use futures::{
     stream::{Fuse, Stream, StreamExt},
     task::{Context, Poll},
 };
-use log::{info, trace, warn};
+use log::{info, trace, warn, Level};
 use pin_project::pin_project;
 
 use std::{any::TypeId, pin::Pin};
@@ -43,33 +43,50 @@ use std::{any::TypeId, pin::Pin};
 const BATCH_SIZE_THRESHOLD: usize = 3;
 
 pub(crate) struct HasherWorkerEvent {
-    pub(crate) from: EndpointId,
+    // Peer the transaction was received from, `None` if it was submitted locally.
+    pub(crate) from: Option<EndpointId>,
     pub(crate) transaction_message: TransactionMessage,
 }
 
 pub(crate) struct HasherWorker {
-    pub(crate) tx: flume::Sender<HasherWorkerEvent>,
+    pub(crate) tx: channel::Sender<HasherWorkerEvent>,
 }
 
 fn trigger_hashing(
     batch_size: usize,
     receiver: &mut BatchStream,
-    processor_worker: &mut flume::Sender<ProcessorWorkerEvent>,
+    processor_worker: &mut channel::Sender<ProcessorWorkerEvent>,
 ) {
     if batch_size < BATCH_SIZE_THRESHOLD {
         let hashes = receiver.hasher.hash_unbatched();
         send_hashes(hashes, &mut receiver.events, processor_worker);
     } else {
-        let hashes = receiver.hasher.hash_batched();
+        let hashes = hash_batched_with_progress(&mut receiver.hasher, |completed, total| {
+            Protocol::get().bus.dispatch(BatchHashingProgress { completed, total })
+        });
         send_hashes(hashes, &mut receiver.events, processor_worker);
     }
     // FIXME: we could store the fraction of times we use the batched hasher
 }
 
+// `BatchHasher::hash_batched` computes the whole batch at once; `callback(completed, total)` is called as each hash of
+// the batch is yielded.
+fn hash_batched_with_progress<'a, F: Fn(usize, usize) + 'a>(
+    hasher: &'a mut BatchHasher<T5B1Buf>,
+    callback: F,
+) -> impl Iterator<Item = TritBuf> + 'a {
+    let total = hasher.len();
+
+    hasher.hash_batched().enumerate().map(move |(index, hash)| {
+        callback(index + 1, total);
+        hash
+    })
+}
+
 fn send_hashes(
     hashes: impl Iterator<Item = TritBuf>,
     events: &mut Vec<HasherWorkerEvent>,
-    processor_worker: &mut flume::Sender<ProcessorWorkerEvent>,
+    processor_worker: &mut channel::Sender<ProcessorWorkerEvent>,
 ) {
     for (
         HasherWorkerEvent {
@@ -99,11 +116,11 @@ impl<N: Node> Worker<N> for HasherWorker {
     }
 
     async fn start(node: &mut N, config: Self::Config) -> Result<Self, Self::Error> {
-        let (tx, rx) = flume::unbounded();
+        let (tx, rx) = channel::unbounded("HasherWorker");
         let mut processor_worker = node.worker::<ProcessorWorker>().unwrap().tx.clone();
 
         node.spawn::<Self, _, _>(|shutdown| async move {
-            let mut receiver = BatchStream::new(config, ShutdownStream::new(shutdown, rx.into_stream()));
+            let mut receiver = BatchStream::new(config, ShutdownStream::graceful(shutdown, rx.into_stream()));
 
             info!("Running.");
 
@@ -121,7 +138,7 @@ impl<N: Node> Worker<N> for HasherWorker {
 #[pin_project(project = BatchStreamProj)]
 pub(crate) struct BatchStream {
     #[pin]
-    receiver: ShutdownStream<Fuse<flume::r#async::RecvStream<'static, HasherWorkerEvent>>>,
+    receiver: ShutdownStream<Fuse<channel::RecvStream<HasherWorkerEvent>>>,
     cache: HashCache,
     hasher: BatchHasher<T5B1Buf>,
     events: Vec<HasherWorkerEvent>,
@@ -130,7 +147,7 @@ pub(crate) struct BatchStream {
 impl BatchStream {
     pub(crate) fn new(
         cache_size: usize,
-        receiver: ShutdownStream<Fuse<flume::r#async::RecvStream<'static, HasherWorkerEvent>>>,
+        receiver: ShutdownStream<Fuse<channel::RecvStream<HasherWorkerEvent>>>,
     ) -> Self {
         assert!(BATCH_SIZE_THRESHOLD <= BATCH_SIZE);
         Self {
@@ -180,17 +197,30 @@ impl Stream for BatchStream {
                 Poll::Ready(Some(event)) => {
                     // If the transaction was already received, we skip it and poll again.
                     if !cache.insert(&event.transaction_message.bytes) {
-                        trace!("Transaction already received.");
+                        match &event.from {
+                            // Every neighbour sends most transactions, this would otherwise be logged per transaction.
+                            Some(from) => Protocol::get().log_throttle.log(
+                                Level::Trace,
+                                module_path!(),
+                                from,
+                                "known transaction",
+                                format_args!("[{}] Transaction already received.", from),
+                            ),
+                            None => trace!("Transaction already received."),
+                        }
                         Protocol::get().metrics.known_transactions_inc();
                         continue;
                     }
                     // Given that the current batch has less than `BATCH_SIZE` transactions. We can
                     // add the transaction in the current event to the batch.
-                    let transaction_bytes = uncompress_transaction_bytes(&event.transaction_message.bytes);
-
-                    let trits = Trits::<T5B1>::try_from_raw(cast_slice(&transaction_bytes), TRANSACTION_TRIT_LEN)
-                        .unwrap()
-                        .to_buf::<T5B1Buf>();
+                    let trits = match gossip_bytes_to_trits(&event.transaction_message.bytes) {
+                        Ok(trits) => trits,
+                        Err(e) => {
+                            trace!("Invalid transaction: {:?}.", e);
+                            Protocol::get().metrics.invalid_transactions_inc();
+                            continue;
+                        }
+                    };
 
                     hasher.add(trits);
                     events.push(event);
@@ -201,12 +231,47 @@ impl Stream for BatchStream {
                     }
                 }
                 Poll::Ready(None) => {
-                    // If the `receiver` stream ended, it means that either we should shutdown or
-                    // the other side of the channel disconnected. In either case, we end this
-                    // stream too without hashing the pending batch we have.
-                    return Poll::Ready(None);
+                    // If the `receiver` stream ended, it means that either we should shutdown, after
+                    // the transactions already received have been drained, or the other side of the
+                    // channel disconnected. In either case, we hash the pending batch before ending
+                    // this stream too.
+                    return if batch_size == 0 {
+                        Poll::Ready(None)
+                    } else {
+                        Poll::Ready(Some(batch_size))
+                    };
                 }
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use std::cell::RefCell;
+
+    #[test]
+    fn batch_hashing_progress() {
+        let mut hasher = BatchHasher::new(TRANSACTION_TRIT_LEN, CurlPRounds::Rounds81);
+        let progress = RefCell::new(Vec::new());
+
+        for _ in 0..64 {
+            hasher.add(TritBuf::<T5B1Buf>::zeros(TRANSACTION_TRIT_LEN));
+        }
+
+        let hashes = hash_batched_with_progress(&mut hasher, |completed, total| {
+            progress.borrow_mut().push((completed, total))
+        })
+        .count();
+        let progress = progress.into_inner();
+
+        assert_eq!(hashes, 64);
+        assert_eq!(progress.len(), 64);
+        assert!(progress.windows(2).all(|pair| pair[0].0 < pair[1].0));
+        assert!(progress.iter().all(|(_, total)| *total == 64));
+        assert_eq!(progress.last(), Some(&(64, 64)));
+    }
+}
@@ -15,7 +15,7 @@ mod processor;
 
 pub(crate) use hash_cache::HashCache;
 pub(crate) use hasher::{HasherWorker, HasherWorkerEvent};
-pub(crate) use processor::{ProcessorWorker, ProcessorWorkerEvent};
+pub(crate) use processor::{store_transaction, ProcessorWorker, ProcessorWorkerEvent};
 
 // #[cfg(test)]
 // mod tests {
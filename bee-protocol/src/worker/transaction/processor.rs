@@ -10,28 +10,30 @@
 // See the License for the specific language governing permissions and limitations under the License.
 
 use crate::{
-    config::ProtocolConfig,
-    message::{uncompress_transaction_bytes, Transaction as TransactionMessage},
-    protocol::Protocol,
+    config::{AttachmentBoundsValidation, ProtocolConfig},
+    message::Transaction as TransactionMessage,
+    milestone::MilestoneIndex,
+    protocol::{Protocol, ProtocolMetrics},
     tangle::{MsTangle, TransactionMetadata},
     worker::{
-        BroadcasterWorker, BroadcasterWorkerEvent, MilestoneValidatorWorker, MilestoneValidatorWorkerEvent,
-        SolidPropagatorWorker, SolidPropagatorWorkerEvent, TangleWorker, TransactionRequesterWorker,
+        request_completed, BroadcasterWorker, BroadcasterWorkerEvent, MilestoneValidatorWorker,
+        MilestoneValidatorWorkerEvent, SolidPropagatorWorker, SolidPropagatorWorkerEvent, TangleWorker,
+        TransactionRequesterWorker,
     },
 };
 
 use bee_common::{shutdown_stream::ShutdownStream, worker::Error as WorkerError};
-use bee_common_ext::{node::Node, worker::Worker};
+use bee_common_ext::{channel, node::Node, worker::Worker};
 use bee_crypto::ternary::Hash;
 use bee_network::EndpointId;
-use bee_ternary::{T1B1Buf, T5B1Buf, Trits, T5B1};
+use bee_storage::storage::Backend;
+use bee_tangle::{InsertionOutcome, TransactionRef as TxRef};
 use bee_transaction::{
-    bundled::{BundledTransaction as Transaction, TRANSACTION_TRIT_LEN},
+    bundled::{BundledTransaction as Transaction, BundledTransactionField},
     Vertex,
 };
 
 use async_trait::async_trait;
-use bytemuck::cast_slice;
 use futures::stream::StreamExt;
 use log::{error, info, trace, warn};
 
@@ -42,18 +44,18 @@ use std::{
 
 pub(crate) struct ProcessorWorkerEvent {
     pub(crate) hash: Hash,
-    pub(crate) from: EndpointId,
+    pub(crate) from: Option<EndpointId>,
     pub(crate) transaction_message: TransactionMessage,
 }
 
 pub(crate) struct ProcessorWorker {
-    pub(crate) tx: flume::Sender<ProcessorWorkerEvent>,
+    pub(crate) tx: channel::Sender<ProcessorWorkerEvent>,
 }
 
 /// Timeframe to allow past or future transactions, 10 minutes in seconds.
 const ALLOWED_TIMESTAMP_WINDOW_SECS: u64 = 10 * 60;
 
-fn validate_timestamp(transaction: &Transaction) -> (bool, bool) {
+fn validate_timestamp(transaction: &Transaction, snapshot_timestamp: u64) -> (bool, bool) {
     let timestamp = transaction.get_timestamp();
     let now = SystemTime::now()
         .duration_since(UNIX_EPOCH)
@@ -63,10 +65,203 @@ fn validate_timestamp(transaction: &Transaction) -> (bool, bool) {
     let future = now + ALLOWED_TIMESTAMP_WINDOW_SECS;
 
     // (is_timestamp_valid, should_broadcast)
-    (
-        timestamp >= Protocol::get().snapshot_timestamp && timestamp < future,
-        timestamp >= past,
-    )
+    (timestamp >= snapshot_timestamp && timestamp < future, timestamp >= past)
+}
+
+// Checks the weight of a transaction against the minimum weight magnitude. A transaction we requested is checked
+// against the historical one instead, since it may have been attached when a lower one was in force; 0 skips the
+// check. Acceptances are counted by path.
+fn validate_weight(weight: u8, requested: bool, mwm: u8, mwm_historical: u8, metrics: &ProtocolMetrics) -> bool {
+    if requested {
+        if weight < mwm_historical {
+            return false;
+        }
+        metrics.mwm_historical_accepted_transactions_inc();
+    } else {
+        if weight < mwm {
+            return false;
+        }
+        metrics.mwm_accepted_transactions_inc();
+    }
+
+    true
+}
+
+/// Reason why the attachment timestamp bounds of a transaction are rejected.
+#[derive(Debug, PartialEq)]
+enum AttachmentBoundsViolation {
+    /// Both bounds are zero, which only the lenient validation accepts.
+    Zeroed,
+    /// The upper bound is below the lower bound.
+    Inverted,
+    /// The attachment timestamp, in milliseconds like the bounds, is outside of them.
+    OutOfBounds,
+}
+
+fn check_attachment_bounds(
+    attachment_ts: u64,
+    attachment_lbts: u64,
+    attachment_ubts: u64,
+    validation: AttachmentBoundsValidation,
+) -> Result<(), AttachmentBoundsViolation> {
+    if attachment_lbts == 0 && attachment_ubts == 0 {
+        return match validation {
+            AttachmentBoundsValidation::Strict => Err(AttachmentBoundsViolation::Zeroed),
+            AttachmentBoundsValidation::Lenient => Ok(()),
+        };
+    }
+
+    if attachment_ubts < attachment_lbts {
+        Err(AttachmentBoundsViolation::Inverted)
+    } else if attachment_ts < attachment_lbts || attachment_ts > attachment_ubts {
+        Err(AttachmentBoundsViolation::OutOfBounds)
+    } else {
+        Ok(())
+    }
+}
+
+// Checks the attachment timestamp bounds of a transaction, counting the rejections by reason.
+fn validate_attachment_bounds(
+    transaction: &Transaction,
+    validation: AttachmentBoundsValidation,
+    metrics: &ProtocolMetrics,
+) -> bool {
+    match check_attachment_bounds(
+        *transaction.attachment_ts().to_inner(),
+        *transaction.attachment_lbts().to_inner(),
+        *transaction.attachment_ubts().to_inner(),
+        validation,
+    ) {
+        Ok(()) => true,
+        Err(violation) => {
+            trace!("Invalid attachment timestamp bounds: {:?}.", violation);
+            match violation {
+                AttachmentBoundsViolation::Zeroed => metrics.zeroed_attachment_bounds_inc(),
+                AttachmentBoundsViolation::Inverted => metrics.inverted_attachment_bounds_inc(),
+                AttachmentBoundsViolation::OutOfBounds => metrics.out_of_attachment_bounds_inc(),
+            };
+            false
+        }
+    }
+}
+
+// Stores a transaction in the tangle, and through its hooks in the storage, whether it was received from a peer or
+// submitted locally. If the transaction was already known, it is only marked as requested if it now is, the flag then
+// being written to the storage as well.
+pub(crate) async fn store_transaction<B: Backend>(
+    tangle: &MsTangle<B>,
+    hash: Hash,
+    transaction: Transaction,
+    requested: bool,
+) -> InsertionOutcome {
+    let mut metadata = TransactionMetadata::arrived();
+
+    metadata.flags_mut().set_tail(transaction.is_tail());
+    metadata.flags_mut().set_requested(requested);
+
+    tangle
+        .insert_or_update(transaction, hash, metadata, |metadata| {
+            if requested && !metadata.flags().is_requested() {
+                metadata.flags_mut().set_requested(true);
+                true
+            } else {
+                false
+            }
+        })
+        .await
+}
+
+// Validates a received transaction and stores it in the tangle. A new transaction is returned together with the event
+// broadcasting it, unless it is too old to be gossiped. A requested transaction belongs to the cone of the milestone
+// being solidified, `requested_index`, and its broadcast is tagged with it so that peers already solid at that index
+// are skipped. A transaction received through gossip is not tagged: no milestone has confirmed it yet, even if its
+// parents are confirmed.
+#[allow(clippy::too_many_arguments)]
+async fn process_transaction<B: Backend>(
+    tangle: &MsTangle<B>,
+    config: &ProtocolConfig,
+    metrics: &ProtocolMetrics,
+    snapshot_timestamp: u64,
+    hash: Hash,
+    from: Option<EndpointId>,
+    transaction_message: TransactionMessage,
+    requested_index: Option<MilestoneIndex>,
+) -> Option<(TxRef, Option<BroadcasterWorkerEvent>)> {
+    let requested = requested_index.is_some();
+
+    let transaction = match Transaction::from_gossip_bytes(&transaction_message.bytes) {
+        Ok(transaction) => transaction,
+        Err(e) => {
+            trace!("Invalid transaction: {:?}.", e);
+            metrics.invalid_transactions_inc();
+            return None;
+        }
+    };
+
+    if !validate_weight(hash.weight(), requested, config.mwm, config.mwm_historical, metrics) {
+        trace!("Insufficient weight magnitude: {}.", hash.weight());
+        metrics.invalid_transactions_inc();
+        return None;
+    }
+
+    if !requested && !validate_attachment_bounds(&transaction, config.attachment_bounds_validation, metrics) {
+        metrics.invalid_transactions_inc();
+        return None;
+    }
+
+    let (is_timestamp_valid, should_broadcast) = validate_timestamp(&transaction, snapshot_timestamp);
+
+    if !requested && !is_timestamp_valid {
+        trace!("Stale transaction, invalid timestamp.");
+        metrics.stale_transactions_inc();
+        return None;
+    }
+
+    match store_transaction(tangle, hash, transaction, requested).await {
+        InsertionOutcome::Inserted(transaction) => {
+            metrics.new_transactions_inc();
+
+            let broadcast = if should_broadcast {
+                Some(BroadcasterWorkerEvent {
+                    source: from,
+                    target: None,
+                    transaction: transaction_message,
+                    milestone_index: requested_index,
+                })
+            } else {
+                None
+            };
+
+            Some((transaction, broadcast))
+        }
+        InsertionOutcome::AlreadyPresent => {
+            metrics.known_transactions_inc();
+            None
+        }
+    }
+}
+
+// Forwards a processed transaction to the broadcaster, if enabled, and to the milestone validator if it was issued by
+// the coordinator.
+fn forward(
+    hash: Hash,
+    transaction: &TxRef,
+    broadcast: Option<BroadcasterWorkerEvent>,
+    broadcaster: Option<&channel::Sender<BroadcasterWorkerEvent>>,
+    milestone_validator: &channel::Sender<MilestoneValidatorWorkerEvent>,
+    config: &ProtocolConfig,
+) {
+    if let (Some(broadcast), Some(broadcaster)) = (broadcast, broadcaster) {
+        if let Err(e) = broadcaster.send(broadcast) {
+            warn!("Broadcasting transaction failed: {}.", e);
+        }
+    }
+
+    if transaction.address().eq(&config.coordinator.public_key) {
+        if let Err(e) = milestone_validator.send(MilestoneValidatorWorkerEvent(hash, transaction.is_tail())) {
+            error!("Sending tail to milestone validation failed: {:?}.", e);
+        }
+    }
 }
 
 #[async_trait]
@@ -79,17 +274,23 @@ impl<N: Node> Worker<N> for ProcessorWorker {
             TypeId::of::<TangleWorker>(),
             TypeId::of::<MilestoneValidatorWorker>(),
             TypeId::of::<SolidPropagatorWorker>(),
-            TypeId::of::<BroadcasterWorker>(),
             TypeId::of::<TransactionRequesterWorker>(),
         ]))
     }
 
+    // Transactions are still processed when the broadcaster is disabled, they are just not broadcast.
+    fn optional_dependencies() -> &'static [TypeId] {
+        Box::leak(Box::from(vec![TypeId::of::<BroadcasterWorker>()]))
+    }
+
     async fn start(node: &mut N, config: Self::Config) -> Result<Self, Self::Error> {
-        let (tx, rx) = flume::unbounded();
+        let (tx, rx) = channel::unbounded("ProcessorWorker");
         let milestone_validator = node.worker::<MilestoneValidatorWorker>().unwrap().tx.clone();
         let solid_propagator = node.worker::<SolidPropagatorWorker>().unwrap().tx.clone();
-        let broadcaster = node.worker::<BroadcasterWorker>().unwrap().tx.clone();
-        let transaction_requester = node.worker::<TransactionRequesterWorker>().unwrap().tx.clone();
+        let broadcaster = node
+            .worker::<BroadcasterWorker>()
+            .map(|broadcaster| broadcaster.tx.clone());
+        let transaction_requester = node.worker::<TransactionRequesterWorker>().unwrap().queue.clone();
 
         let tangle = node.resource::<MsTangle<N::Backend>>();
 
@@ -106,50 +307,23 @@ impl<N: Node> Worker<N> for ProcessorWorker {
             {
                 trace!("Processing received transaction...");
 
-                let transaction_bytes = uncompress_transaction_bytes(&transaction_message.bytes);
-                let transaction =
-                    match Trits::<T5B1>::try_from_raw(cast_slice(&transaction_bytes), TRANSACTION_TRIT_LEN) {
-                        Ok(transaction_trits) => {
-                            let transaction_buf = transaction_trits.to_buf::<T5B1Buf>().encode::<T1B1Buf>();
-                            match Transaction::from_trits(&transaction_buf) {
-                                Ok(transaction) => transaction,
-                                Err(e) => {
-                                    trace!("Invalid transaction: {:?}.", e);
-                                    Protocol::get().metrics.invalid_transactions_inc();
-                                    return;
-                                }
-                            }
-                        }
-                        Err(e) => {
-                            trace!("Invalid transaction: {:?}.", e);
-                            Protocol::get().metrics.invalid_transactions_inc();
-                            return;
-                        }
-                    };
-
-                let requested = Protocol::get().requested_transactions.contains_key(&hash);
-
-                if !requested && hash.weight() < config.mwm {
-                    trace!("Insufficient weight magnitude: {}.", hash.weight());
-                    Protocol::get().metrics.invalid_transactions_inc();
-                    return;
-                }
-
-                let (is_timestamp_valid, should_broadcast) = validate_timestamp(&transaction);
-
-                if !requested && !is_timestamp_valid {
-                    trace!("Stale transaction, invalid timestamp.");
-                    Protocol::get().metrics.stale_transactions_inc();
-                    return;
-                }
-
-                let mut metadata = TransactionMetadata::arrived();
+                let requested_index = Protocol::get()
+                    .requested_transactions
+                    .get(&hash)
+                    .map(|entry| entry.value().0);
 
-                metadata.flags_mut().set_tail(transaction.is_tail());
-                metadata.flags_mut().set_requested(requested);
-
-                // store transaction
-                if let Some(transaction) = tangle.insert(transaction, hash, metadata).await {
+                if let Some((transaction, broadcast)) = process_transaction(
+                    &tangle,
+                    &config,
+                    &Protocol::get().metrics,
+                    Protocol::get().snapshot_timestamp,
+                    hash,
+                    from,
+                    transaction_message,
+                    requested_index,
+                )
+                .await
+                {
                     // TODO this was temporarily moved from the tangle.
                     // Reason is that since the tangle is not a worker, it can't have access to the propagator tx.
                     // When the tangle is made a worker, this should be put back on.
@@ -157,40 +331,29 @@ impl<N: Node> Worker<N> for ProcessorWorker {
                         error!("Failed to send hash to solid propagator: {:?}.", e);
                     }
 
-                    Protocol::get().metrics.new_transactions_inc();
+                    if let Some((_, (index, _, _, epid))) = Protocol::get().requested_transactions.remove(&hash) {
+                        if let Some(epid) = epid {
+                            request_completed(&Protocol::get().peer_manager.handshaked_peers, &epid);
+                        }
 
-                    match Protocol::get().requested_transactions.remove(&hash) {
-                        Some((_, (index, _))) => {
-                            let trunk = transaction.trunk();
-                            let branch = transaction.branch();
+                        let trunk = transaction.trunk();
+                        let branch = transaction.branch();
 
-                            Protocol::request_transaction(&tangle, &transaction_requester, *trunk, index).await;
+                        Protocol::request_transaction(&tangle, &transaction_requester, *trunk, index).await;
 
-                            if trunk != branch {
-                                Protocol::request_transaction(&tangle, &transaction_requester, *branch, index).await;
-                            }
-                        }
-                        None => {
-                            if should_broadcast {
-                                if let Err(e) = broadcaster.send(BroadcasterWorkerEvent {
-                                    source: Some(from),
-                                    transaction: transaction_message,
-                                }) {
-                                    warn!("Broadcasting transaction failed: {}.", e);
-                                }
-                            }
-                        }
-                    };
-
-                    if transaction.address().eq(&config.coordinator.public_key) {
-                        if let Err(e) =
-                            milestone_validator.send(MilestoneValidatorWorkerEvent(hash, transaction.is_tail()))
-                        {
-                            error!("Sending tail to milestone validation failed: {:?}.", e);
+                        if trunk != branch {
+                            Protocol::request_transaction(&tangle, &transaction_requester, *branch, index).await;
                         }
                     }
-                } else {
-                    Protocol::get().metrics.known_transactions_inc();
+
+                    forward(
+                        hash,
+                        &transaction,
+                        broadcast,
+                        broadcaster.as_ref(),
+                        &milestone_validator,
+                        &config,
+                    );
                 }
             }
 
@@ -200,3 +363,373 @@ impl<N: Node> Worker<N> for ProcessorWorker {
         Ok(Self { tx })
     }
 }
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use crate::{
+        tangle::flags::Flags,
+        test_utils::{hash, MockStorage},
+        worker::broadcaster::is_recipient,
+    };
+
+    use bee_common_ext::node::ResHandle;
+    use bee_network::TransportProtocol;
+    use bee_ternary::{T1B1Buf, TritBuf};
+    use bee_transaction::bundled::{
+        constants::{ATTACHMENT_TS_LOWER_BOUND, ATTACHMENT_TS_UPPER_BOUND},
+        Address, BundledTransactionBuilder, Index, Nonce, Payload, Tag, Timestamp, Value, TRANSACTION_TRIT_LEN,
+    };
+
+    fn transaction() -> Transaction {
+        Transaction::from_trits(&TritBuf::<T1B1Buf>::zeros(TRANSACTION_TRIT_LEN)).unwrap()
+    }
+
+    fn is_requested(tangle: &MsTangle<MockStorage>, hash: &Hash) -> bool {
+        tangle.get_metadata(hash).unwrap().flags().is_requested()
+    }
+
+    // A hash whose last 3 trytes are zeros, preceded by an `A`.
+    fn low_weight_hash() -> Hash {
+        hash(&format!("{}A", "B".repeat(77)))
+    }
+
+    #[test]
+    fn low_weight_requested_and_gossiped() {
+        let metrics = ProtocolMetrics::new();
+        let weight = low_weight_hash().weight();
+        let mwm = weight + 1;
+
+        // Gossiped, it has to meet the current minimum weight magnitude.
+        assert!(!validate_weight(weight, false, mwm, weight, &metrics));
+        // Requested, the historical one is enough.
+        assert!(validate_weight(weight, true, mwm, weight, &metrics));
+        assert!(!validate_weight(weight, true, mwm, weight + 1, &metrics));
+        // A historical minimum of 0 doesn't check requested transactions.
+        assert!(validate_weight(weight, true, mwm, 0, &metrics));
+
+        assert_eq!(metrics.mwm_accepted_transactions(), 0);
+        assert_eq!(metrics.mwm_historical_accepted_transactions(), 2);
+
+        assert!(validate_weight(weight, false, weight, 0, &metrics));
+        assert_eq!(metrics.mwm_accepted_transactions(), 1);
+    }
+
+    #[tokio::test]
+    async fn low_weight_processed() {
+        let tangle = MsTangle::new(ResHandle::new(MockStorage::default()));
+        let metrics = ProtocolMetrics::new();
+        let weight = low_weight_hash().weight();
+        let config = ProtocolConfig::build().mwm(weight + 1).mwm_historical(weight).finish();
+        let bytes = fresh_transaction(hash("TRUNK"), hash("BRANCH")).to_gossip_bytes();
+
+        // Gossiped, it is rejected.
+        assert!(process_transaction(
+            &tangle,
+            &config,
+            &metrics,
+            0,
+            low_weight_hash(),
+            None,
+            TransactionMessage::new(&bytes),
+            None,
+        )
+        .await
+        .is_none());
+        assert!(!tangle.contains(&low_weight_hash()).await);
+        assert_eq!(metrics.invalid_transactions(), 1);
+        assert_eq!(metrics.mwm_accepted_transactions(), 0);
+        assert_eq!(metrics.mwm_historical_accepted_transactions(), 0);
+
+        // Requested, it is accepted.
+        assert!(process_transaction(
+            &tangle,
+            &config,
+            &metrics,
+            0,
+            low_weight_hash(),
+            None,
+            TransactionMessage::new(&bytes),
+            Some(MilestoneIndex(50)),
+        )
+        .await
+        .is_some());
+        assert!(is_requested(&tangle, &low_weight_hash()));
+        assert_eq!(metrics.new_transactions(), 1);
+        assert_eq!(metrics.invalid_transactions(), 1);
+        assert_eq!(metrics.mwm_accepted_transactions(), 0);
+        assert_eq!(metrics.mwm_historical_accepted_transactions(), 1);
+    }
+
+    #[test]
+    fn zeroed_attachment_bounds() {
+        assert_eq!(
+            check_attachment_bounds(0, 0, 0, AttachmentBoundsValidation::Lenient),
+            Ok(())
+        );
+        assert_eq!(
+            check_attachment_bounds(1_600_000_000_000, 0, 0, AttachmentBoundsValidation::Lenient),
+            Ok(())
+        );
+        assert_eq!(
+            check_attachment_bounds(0, 0, 0, AttachmentBoundsValidation::Strict),
+            Err(AttachmentBoundsViolation::Zeroed)
+        );
+        assert_eq!(
+            check_attachment_bounds(1_600_000_000_000, 0, 0, AttachmentBoundsValidation::Strict),
+            Err(AttachmentBoundsViolation::Zeroed)
+        );
+    }
+
+    #[test]
+    fn violated_attachment_bounds() {
+        for validation in [AttachmentBoundsValidation::Strict, AttachmentBoundsValidation::Lenient].iter() {
+            assert_eq!(
+                check_attachment_bounds(15, 20, 10, *validation),
+                Err(AttachmentBoundsViolation::Inverted)
+            );
+            assert_eq!(
+                check_attachment_bounds(5, 10, 20, *validation),
+                Err(AttachmentBoundsViolation::OutOfBounds)
+            );
+            assert_eq!(
+                check_attachment_bounds(25, 10, 20, *validation),
+                Err(AttachmentBoundsViolation::OutOfBounds)
+            );
+        }
+    }
+
+    #[test]
+    fn valid_attachment_bounds() {
+        for validation in [AttachmentBoundsValidation::Strict, AttachmentBoundsValidation::Lenient].iter() {
+            assert_eq!(check_attachment_bounds(10, 10, 20, *validation), Ok(()));
+            assert_eq!(check_attachment_bounds(15, 10, 20, *validation), Ok(()));
+            assert_eq!(check_attachment_bounds(20, 10, 20, *validation), Ok(()));
+            assert_eq!(
+                check_attachment_bounds(
+                    1_600_000_000_000,
+                    ATTACHMENT_TS_LOWER_BOUND,
+                    ATTACHMENT_TS_UPPER_BOUND,
+                    *validation
+                ),
+                Ok(())
+            );
+        }
+    }
+
+    #[test]
+    fn attachment_bounds_rejections_counted_by_reason() {
+        let metrics = ProtocolMetrics::new();
+
+        // The transaction made of zero trits has all-zero bounds.
+        assert!(validate_attachment_bounds(
+            &transaction(),
+            AttachmentBoundsValidation::Lenient,
+            &metrics
+        ));
+        assert_eq!(metrics.zeroed_attachment_bounds(), 0);
+
+        assert!(!validate_attachment_bounds(
+            &transaction(),
+            AttachmentBoundsValidation::Strict,
+            &metrics
+        ));
+        assert_eq!(metrics.zeroed_attachment_bounds(), 1);
+        assert_eq!(metrics.inverted_attachment_bounds(), 0);
+        assert_eq!(metrics.out_of_attachment_bounds(), 0);
+    }
+
+    #[tokio::test]
+    async fn store_transaction_outcome() {
+        let tangle = MsTangle::new(ResHandle::new(MockStorage::default()));
+        let hash = Hash::zeros();
+
+        assert!(matches!(
+            store_transaction(&tangle, hash, transaction(), false).await,
+            InsertionOutcome::Inserted(_)
+        ));
+        assert!(matches!(
+            store_transaction(&tangle, hash, transaction(), false).await,
+            InsertionOutcome::AlreadyPresent
+        ));
+    }
+
+    #[tokio::test]
+    async fn store_transaction_merges_requested_flag() {
+        let tangle = MsTangle::new(ResHandle::new(MockStorage::default()));
+        let hash = Hash::zeros();
+
+        store_transaction(&tangle, hash, transaction(), false).await;
+        assert!(!is_requested(&tangle, &hash));
+
+        // A duplicate that was requested in the meantime upgrades the flag.
+        store_transaction(&tangle, hash, transaction(), true).await;
+        assert!(is_requested(&tangle, &hash));
+
+        // A later unsolicited duplicate doesn't downgrade it.
+        store_transaction(&tangle, hash, transaction(), false).await;
+        assert!(is_requested(&tangle, &hash));
+    }
+
+    // Transaction issued now, approving `trunk` and `branch`.
+    fn fresh_transaction(trunk: Hash, branch: Hash) -> Transaction {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+
+        BundledTransactionBuilder::new()
+            .with_payload(Payload::zeros())
+            .with_address(Address::zeros())
+            .with_value(Value::from_inner_unchecked(0))
+            .with_obsolete_tag(Tag::zeros())
+            .with_timestamp(Timestamp::from_inner_unchecked(now.as_secs()))
+            .with_index(Index::from_inner_unchecked(0))
+            .with_last_index(Index::from_inner_unchecked(0))
+            .with_tag(Tag::zeros())
+            .with_attachment_ts(Timestamp::from_inner_unchecked(now.as_millis() as u64))
+            .with_bundle(Hash::zeros())
+            .with_trunk(trunk)
+            .with_branch(branch)
+            .with_attachment_lbts(Timestamp::from_inner_unchecked(0))
+            .with_attachment_ubts(Timestamp::from_inner_unchecked(0))
+            .with_nonce(Nonce::zeros())
+            .build()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn fresh_transaction_reaches_synced_peer() {
+        let tangle = MsTangle::new(ResHandle::new(MockStorage::default()));
+        let config = ProtocolConfig::build().mwm(0).finish();
+        let metrics = ProtocolMetrics::new();
+        let (trunk, branch) = (hash("TRUNK"), hash("BRANCH"));
+        let confirmed = TransactionMetadata::new(Flags::SOLID | Flags::CONFIRMED, MilestoneIndex(10), 0, 0, 0);
+
+        tangle.insert(transaction(), trunk, confirmed).await;
+        tangle.insert(transaction(), branch, confirmed).await;
+
+        let (source, synced) = (
+            EndpointId::new(TransportProtocol::Tcp, "127.0.0.1:15601".parse().unwrap()),
+            EndpointId::new(TransportProtocol::Tcp, "127.0.0.1:15602".parse().unwrap()),
+        );
+        let message = TransactionMessage::new(&fresh_transaction(trunk, branch).to_gossip_bytes());
+
+        let (_, broadcast) = process_transaction(
+            &tangle,
+            &config,
+            &metrics,
+            0,
+            hash("FRESH"),
+            Some(source),
+            message,
+            None,
+        )
+        .await
+        .unwrap();
+        let broadcast = broadcast.unwrap();
+
+        assert_eq!(metrics.new_transactions(), 1);
+        assert_eq!(broadcast.milestone_index, None);
+        // The peer is solid past the milestone that confirmed the parents, it still lacks the new transaction.
+        assert!(is_recipient(
+            synced,
+            MilestoneIndex(20),
+            broadcast.source,
+            broadcast.target,
+            broadcast.milestone_index
+        ));
+        assert!(!is_recipient(
+            source,
+            MilestoneIndex(20),
+            broadcast.source,
+            broadcast.target,
+            broadcast.milestone_index
+        ));
+    }
+
+    #[tokio::test]
+    async fn requested_transaction_skips_peer_ahead() {
+        let tangle = MsTangle::new(ResHandle::new(MockStorage::default()));
+        let config = ProtocolConfig::build().mwm(0).finish();
+        let metrics = ProtocolMetrics::new();
+        let (source, ahead, behind) = (
+            EndpointId::new(TransportProtocol::Tcp, "127.0.0.1:15601".parse().unwrap()),
+            EndpointId::new(TransportProtocol::Tcp, "127.0.0.1:15602".parse().unwrap()),
+            EndpointId::new(TransportProtocol::Tcp, "127.0.0.1:15603".parse().unwrap()),
+        );
+        let message = TransactionMessage::new(&fresh_transaction(hash("TRUNK"), hash("BRANCH")).to_gossip_bytes());
+
+        // Requested while solidifying milestone 50.
+        let (_, broadcast) = process_transaction(
+            &tangle,
+            &config,
+            &metrics,
+            0,
+            hash("REQUESTED"),
+            Some(source),
+            message,
+            Some(MilestoneIndex(50)),
+        )
+        .await
+        .unwrap();
+        let broadcast = broadcast.unwrap();
+
+        assert_eq!(broadcast.milestone_index, Some(MilestoneIndex(50)));
+        assert!(!is_recipient(
+            ahead,
+            MilestoneIndex(60),
+            broadcast.source,
+            broadcast.target,
+            broadcast.milestone_index
+        ));
+        assert!(is_recipient(
+            behind,
+            MilestoneIndex(40),
+            broadcast.source,
+            broadcast.target,
+            broadcast.milestone_index
+        ));
+    }
+
+    #[tokio::test]
+    async fn processed_without_broadcaster() {
+        let tangle = MsTangle::new(ResHandle::new(MockStorage::default()));
+        let config = ProtocolConfig::build().mwm(0).finish();
+        let metrics = ProtocolMetrics::new();
+        let (milestone_validator, milestone_validator_rx) = channel::unbounded("MilestoneValidatorWorker");
+        let (broadcaster, broadcaster_rx) = channel::unbounded("BroadcasterWorker");
+        let bytes = fresh_transaction(hash("TRUNK"), hash("BRANCH")).to_gossip_bytes();
+
+        for (name, broadcaster) in [("DISABLED", None), ("ENABLED", Some(&broadcaster))].iter() {
+            let (transaction, broadcast) = process_transaction(
+                &tangle,
+                &config,
+                &metrics,
+                0,
+                hash(name),
+                None,
+                TransactionMessage::new(&bytes),
+                None,
+            )
+            .await
+            .unwrap();
+
+            forward(
+                hash(name),
+                &transaction,
+                broadcast,
+                *broadcaster,
+                &milestone_validator,
+                &config,
+            );
+
+            assert!(tangle.contains(&hash(name)).await);
+            assert_eq!(broadcaster_rx.try_recv().is_ok(), broadcaster.is_some());
+        }
+
+        assert_eq!(metrics.new_transactions(), 2);
+        assert_eq!(metrics.invalid_transactions(), 0);
+        // Not issued by the coordinator.
+        assert!(milestone_validator_rx.try_recv().is_err());
+    }
+}
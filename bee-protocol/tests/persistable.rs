@@ -0,0 +1,140 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use bee_crypto::ternary::Hash;
+use bee_protocol::{
+    tangle::{flags::Flags, TransactionMetadata},
+    Milestone, MilestoneIndex,
+};
+use bee_storage::persistable::{DecodeError, Persistable};
+
+// These are the canonical encodings shared by all the backends, changing them breaks existing databases.
+// Milestone indexes are big-endian so that keys are ordered like indexes, the other fields are little-endian.
+
+const MILESTONE_INDEX_BYTES: [u8; 4] = [1, 2, 3, 4];
+
+// Transaction metadata starts with the version of its encoding.
+const TRANSACTION_METADATA_BYTES: [u8; 30] = [
+    1, 3, 1, 2, 3, 4, 1, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 3, 0, 0, 0, 0, 0, 0, 0,
+];
+
+#[test]
+fn milestone_index_golden_bytes() {
+    let mut buffer = Vec::new();
+    MilestoneIndex(0x0102_0304).encode_persistable(&mut buffer);
+
+    assert_eq!(buffer, MILESTONE_INDEX_BYTES);
+    assert_eq!(
+        MilestoneIndex::decode_persistable(&MILESTONE_INDEX_BYTES).unwrap(),
+        MilestoneIndex(0x0102_0304)
+    );
+}
+
+#[test]
+fn flags_golden_bytes() {
+    let mut buffer = Vec::new();
+    (Flags::SOLID | Flags::TAIL).encode_persistable(&mut buffer);
+
+    assert_eq!(buffer, [3]);
+    assert_eq!(Flags::decode_persistable(&[3]).unwrap(), Flags::SOLID | Flags::TAIL);
+}
+
+#[test]
+fn milestone_golden_bytes() {
+    let mut bytes = [0u8; 53];
+    bytes[49..].copy_from_slice(&MILESTONE_INDEX_BYTES);
+
+    let mut buffer = Vec::new();
+    Milestone::new(Hash::zeros(), MilestoneIndex(0x0102_0304)).encode_persistable(&mut buffer);
+
+    assert_eq!(&buffer[..], &bytes[..]);
+
+    let milestone = Milestone::decode_persistable(&bytes).unwrap();
+
+    assert_eq!(*milestone.hash(), Hash::zeros());
+    assert_eq!(milestone.index(), MilestoneIndex(0x0102_0304));
+}
+
+#[test]
+fn transaction_metadata_golden_bytes() {
+    let mut buffer = Vec::new();
+    TransactionMetadata::new(Flags::SOLID | Flags::TAIL, MilestoneIndex(0x0102_0304), 1, 2, 3)
+        .encode_persistable(&mut buffer);
+
+    assert_eq!(buffer, TRANSACTION_METADATA_BYTES);
+
+    let metadata = TransactionMetadata::decode_persistable(&TRANSACTION_METADATA_BYTES).unwrap();
+
+    assert_eq!(*metadata.flags(), Flags::SOLID | Flags::TAIL);
+    assert_eq!(metadata.milestone_index(), MilestoneIndex(0x0102_0304));
+    assert_eq!(metadata.arrival_timestamp(), 1);
+    assert_eq!(metadata.solidification_timestamp(), 2);
+    assert_eq!(metadata.confirmation_timestamp(), 3);
+}
+
+#[test]
+fn transaction_metadata_truncated() {
+    match TransactionMetadata::decode_persistable(&TRANSACTION_METADATA_BYTES[..29]) {
+        Err(DecodeError::UnexpectedEnd { .. }) => (),
+        _ => panic!("decoding truncated metadata should fail"),
+    }
+}
+
+#[test]
+fn transaction_metadata_invalid_flags() {
+    let mut bytes = TRANSACTION_METADATA_BYTES;
+    bytes[1] = 0b1000_0000;
+
+    match TransactionMetadata::decode_persistable(&bytes) {
+        Err(DecodeError::InvalidValue) => (),
+        _ => panic!("decoding unknown flags should fail"),
+    }
+}
+
+#[test]
+fn transaction_metadata_unsupported_version() {
+    let mut bytes = TRANSACTION_METADATA_BYTES;
+    bytes[0] = 2;
+
+    match TransactionMetadata::decode_persistable(&bytes) {
+        Err(DecodeError::UnsupportedVersion(2)) => (),
+        _ => panic!("decoding an unknown version should fail"),
+    }
+}
+
+#[test]
+fn transaction_metadata_round_trip() {
+    let flags = [
+        Flags::empty(),
+        Flags::SOLID | Flags::TAIL,
+        Flags::REQUESTED | Flags::VALID,
+        Flags::MILESTONE | Flags::CONFIRMED,
+        Flags::CONFLICTING,
+        Flags::all(),
+    ];
+    let indexes = [0, 1, 0x0102_0304, u32::MAX];
+
+    for flags in flags.iter() {
+        for index in indexes.iter() {
+            let metadata = TransactionMetadata::new(*flags, MilestoneIndex(*index), u64::MAX, 0, 1_602_000_000_000);
+            let mut buffer = Vec::new();
+            metadata.encode_persistable(&mut buffer);
+
+            let decoded = TransactionMetadata::decode_persistable(&buffer).unwrap();
+
+            assert_eq!(decoded.flags(), metadata.flags());
+            assert_eq!(decoded.milestone_index(), metadata.milestone_index());
+            assert_eq!(decoded.arrival_timestamp(), u64::MAX);
+            assert_eq!(decoded.solidification_timestamp(), 0);
+            assert_eq!(decoded.confirmation_timestamp(), 1_602_000_000_000);
+        }
+    }
+}
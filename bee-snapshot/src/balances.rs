@@ -0,0 +1,280 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+//! Verification of the balances of a snapshot.
+
+use crate::constants::IOTA_SUPPLY;
+
+use bee_transaction::bundled::Address;
+
+use rayon::prelude::*;
+
+use std::{
+    borrow::Borrow,
+    collections::{hash_map::DefaultHasher, HashSet},
+    hash::{Hash, Hasher},
+    sync::Mutex,
+};
+
+// Number of balance entries checked by a single task.
+const CHUNK_SIZE: usize = 10_000;
+// Number of groups the addresses are split into to look for duplicates in parallel.
+const SHARDS: usize = 64;
+
+/// An inconsistency of the balances of a snapshot.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Violation {
+    /// The address has a balance of 0 and should not be part of the snapshot.
+    NullBalance(Address),
+    /// The address appears more than once, it is reported for each additional occurrence.
+    DuplicateAddress(Address),
+    /// The balances add up to the first value instead of the total supply, the second one.
+    InvalidSupply(u128, u64),
+}
+
+// Outcome of the verification of a chunk of entries.
+#[derive(Default)]
+struct Partial<'a> {
+    supply: u128,
+    violations: Vec<Violation>,
+    // Addresses of the chunk grouped by shard.
+    shards: Vec<Vec<&'a Address>>,
+}
+
+fn shard(address: &Address) -> usize {
+    let mut hasher = DefaultHasher::new();
+    address.hash(&mut hasher);
+    (hasher.finish() % SHARDS as u64) as usize
+}
+
+/// Checks that the balances hold no null balance nor duplicate address and add up to the total supply.
+///
+/// Balances are unsigned and summed with 128 bits, so no set of entries can wrap around to the expected supply.
+pub fn verify_balances<A>(entries: &[(A, u64)]) -> Result<(), Vec<Violation>>
+where
+    A: Borrow<Address> + Sync,
+{
+    verify_balances_with_progress(entries, |_| ())
+}
+
+/// Same as `verify_balances`, calling `progress` with the percentage of the verification completed so far.
+///
+/// The entries are checked in parallel on the rayon thread pool; `progress` is called from its threads, with increasing
+/// percentages, the last one being 100.
+pub fn verify_balances_with_progress<A, P>(entries: &[(A, u64)], progress: P) -> Result<(), Vec<Violation>>
+where
+    A: Borrow<Address> + Sync,
+    P: Fn(u8) + Sync,
+{
+    let steps = (entries.len() + CHUNK_SIZE - 1) / CHUNK_SIZE + SHARDS;
+    let done = Mutex::new(0);
+    let step = || {
+        let mut done = done.lock().unwrap();
+        *done += 1;
+        progress((*done * 100 / steps) as u8);
+    };
+
+    let partials = entries
+        .par_chunks(CHUNK_SIZE)
+        .map(|chunk| {
+            let mut partial = Partial {
+                shards: vec![Vec::new(); SHARDS],
+                ..Default::default()
+            };
+
+            for (address, balance) in chunk {
+                let address = address.borrow();
+
+                if *balance == 0 {
+                    partial.violations.push(Violation::NullBalance(address.clone()));
+                }
+                partial.supply += *balance as u128;
+                partial.shards[shard(address)].push(address);
+            }
+
+            step();
+
+            partial
+        })
+        .collect::<Vec<_>>();
+
+    let duplicates = (0..SHARDS)
+        .into_par_iter()
+        .flat_map(|shard| {
+            let mut addresses = HashSet::new();
+            let duplicates = partials
+                .iter()
+                .flat_map(|partial| partial.shards[shard].iter())
+                .filter(|address| !addresses.insert(**address))
+                .map(|address| Violation::DuplicateAddress(Address::clone(address)))
+                .collect::<Vec<_>>();
+
+            step();
+
+            duplicates
+        })
+        .collect::<Vec<_>>();
+
+    let supply = partials.iter().map(|partial| partial.supply).sum::<u128>();
+    let mut violations = partials
+        .into_iter()
+        .flat_map(|partial| partial.violations)
+        .chain(duplicates)
+        .collect::<Vec<_>>();
+
+    if supply != IOTA_SUPPLY as u128 {
+        violations.push(Violation::InvalidSupply(supply, IOTA_SUPPLY));
+    }
+
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(violations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use bee_ternary::{T1B1Buf, Trits, T1B1};
+    use bee_transaction::bundled::BundledTransactionField;
+
+    // Enough entries to be split over many chunks.
+    const ENTRIES: u64 = 100_000;
+
+    // Returns a distinct address for every `n`.
+    fn address(mut n: u64) -> Address {
+        let mut raw = [0i8; 243];
+
+        for trit in raw.iter_mut() {
+            *trit = (n % 3) as i8 - 1;
+            n /= 3;
+        }
+
+        Address::from_inner_unchecked(
+            Trits::<T1B1>::try_from_raw(&raw, raw.len())
+                .unwrap()
+                .to_buf::<T1B1Buf>(),
+        )
+    }
+
+    // Spreads the total supply over `ENTRIES` distinct addresses.
+    fn balances() -> Vec<(Address, u64)> {
+        let balance = IOTA_SUPPLY / ENTRIES;
+
+        (0..ENTRIES)
+            .map(|n| {
+                if n == 0 {
+                    (address(n), balance + IOTA_SUPPLY % ENTRIES)
+                } else {
+                    (address(n), balance)
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn valid() {
+        assert_eq!(verify_balances(&balances()), Ok(()));
+    }
+
+    #[test]
+    fn borrowed_addresses() {
+        let balances = balances();
+        let borrowed = balances
+            .iter()
+            .map(|(address, balance)| (address, *balance))
+            .collect::<Vec<_>>();
+
+        assert_eq!(verify_balances(&borrowed), Ok(()));
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(
+            verify_balances::<Address>(&[]),
+            Err(vec![Violation::InvalidSupply(0, IOTA_SUPPLY)])
+        );
+    }
+
+    #[test]
+    fn null_balance() {
+        let mut balances = balances();
+        balances.push((address(ENTRIES), 0));
+
+        assert_eq!(
+            verify_balances(&balances),
+            Err(vec![Violation::NullBalance(address(ENTRIES))])
+        );
+    }
+
+    #[test]
+    fn duplicate_address() {
+        let mut balances = balances();
+        // Moves a balance to a duplicate of an address of another chunk, keeping the supply right.
+        balances[CHUNK_SIZE * 3].1 -= 1;
+        balances.push((address(7), 1));
+
+        assert_eq!(
+            verify_balances(&balances),
+            Err(vec![Violation::DuplicateAddress(address(7))])
+        );
+    }
+
+    #[test]
+    fn overflowing_supply() {
+        let mut balances = balances();
+        // Would add up to the total supply if the sum wrapped around on 64 bits.
+        balances.push((address(ENTRIES), u64::MAX));
+        balances.push((address(ENTRIES + 1), 1));
+
+        assert_eq!(
+            verify_balances(&balances),
+            Err(vec![Violation::InvalidSupply(
+                IOTA_SUPPLY as u128 + u64::MAX as u128 + 1,
+                IOTA_SUPPLY
+            )])
+        );
+    }
+
+    #[test]
+    fn all_violations_reported() {
+        let mut balances = balances();
+        balances.push((address(1), 0));
+        balances.push((address(ENTRIES), 1));
+
+        assert_eq!(
+            verify_balances(&balances),
+            Err(vec![
+                Violation::NullBalance(address(1)),
+                Violation::DuplicateAddress(address(1)),
+                Violation::InvalidSupply(IOTA_SUPPLY as u128 + 1, IOTA_SUPPLY),
+            ])
+        );
+    }
+
+    #[test]
+    fn progress() {
+        let reported = Mutex::new(Vec::new());
+
+        verify_balances_with_progress(&balances(), |percent| reported.lock().unwrap().push(percent)).unwrap();
+
+        let reported = reported.into_inner().unwrap();
+        assert_eq!(
+            reported.len(),
+            (ENTRIES as usize + CHUNK_SIZE - 1) / CHUNK_SIZE + SHARDS
+        );
+        assert!(reported.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert_eq!(reported.last(), Some(&100));
+    }
+}
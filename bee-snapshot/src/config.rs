@@ -10,6 +10,9 @@
 // See the License for the specific language governing permissions and limitations under the License.
 
 use crate::{
+    constants::{
+        ADDITIONAL_PRUNING_THRESHOLD, SOLID_ENTRY_POINT_CHECK_THRESHOLD_FUTURE, SOLID_ENTRY_POINT_CHECK_THRESHOLD_PAST,
+    },
     global::{GlobalSnapshotConfig, GlobalSnapshotConfigBuilder},
     local::{LocalSnapshotConfig, LocalSnapshotConfigBuilder},
     pruning::{PruningConfig, PruningConfigBuilder},
@@ -17,8 +20,31 @@ use crate::{
 
 use serde::Deserialize;
 
+use std::fmt;
+
 const DEFAULT_LOAD_TYPE: &str = "local";
 
+#[derive(Debug, PartialEq)]
+pub enum ConfigError {
+    DelayTooSmall { given: u32, minimum: u32 },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DelayTooSmall { given, minimum } => {
+                write!(
+                    f,
+                    "pruning delay {} is too small, it must be at least {}",
+                    given, minimum
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
 #[derive(Clone)]
 pub enum LoadType {
     Local,
@@ -26,10 +52,15 @@ pub enum LoadType {
 }
 
 #[derive(Default, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct SnapshotConfigBuilder {
+    /// Snapshot loaded at startup, "local" or "global".
     load_type: Option<String>,
+    /// Local snapshot settings.
     local: LocalSnapshotConfigBuilder,
+    /// Global snapshot settings.
     global: GlobalSnapshotConfigBuilder,
+    /// Pruning settings.
     pruning: PruningConfigBuilder,
 }
 
@@ -48,6 +79,21 @@ impl SnapshotConfigBuilder {
         self
     }
 
+    pub fn local_depth(mut self, depth: u32) -> Self {
+        self.local = self.local.depth(depth);
+        self
+    }
+
+    pub fn pruning_enabled(mut self, enabled: bool) -> Self {
+        self.pruning = self.pruning.enabled(enabled);
+        self
+    }
+
+    pub fn pruning_delay(mut self, delay: u32) -> Self {
+        self.pruning = self.pruning.delay(delay);
+        self
+    }
+
     pub fn finish(self) -> SnapshotConfig {
         let load_type = match self.load_type.unwrap_or_else(|| DEFAULT_LOAD_TYPE.to_owned()).as_str() {
             "local" => LoadType::Local,
@@ -92,4 +138,72 @@ impl SnapshotConfig {
     pub fn pruning(&self) -> &PruningConfig {
         &self.pruning
     }
+
+    /// Depth of the local snapshots, raised to the minimum needed to compute solid entry points.
+    pub fn depth(&self) -> u32 {
+        self.local.depth().max(SOLID_ENTRY_POINT_CHECK_THRESHOLD_FUTURE)
+    }
+
+    /// Returns the pruning delay, failing if it would prune milestones still needed by the local snapshots.
+    pub fn pruning_delay(&self) -> Result<u32, ConfigError> {
+        let minimum = self.depth() + SOLID_ENTRY_POINT_CHECK_THRESHOLD_PAST + ADDITIONAL_PRUNING_THRESHOLD + 1;
+        let given = self.pruning.delay();
+
+        if self.pruning.enabled() && given < minimum {
+            return Err(ConfigError::DelayTooSmall { given, minimum });
+        }
+
+        Ok(given)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn default_pruning_delay() {
+        assert!(SnapshotConfig::build().finish().pruning_delay().is_ok());
+    }
+
+    #[test]
+    fn pruning_delay_too_small() {
+        let config = SnapshotConfig::build().local_depth(100).pruning_delay(200).finish();
+
+        assert_eq!(
+            config.pruning_delay(),
+            Err(ConfigError::DelayTooSmall {
+                given: 200,
+                minimum: 201
+            })
+        );
+    }
+
+    #[test]
+    fn pruning_delay_minimum() {
+        let config = SnapshotConfig::build().local_depth(100).pruning_delay(201).finish();
+
+        assert_eq!(config.pruning_delay(), Ok(201));
+    }
+
+    #[test]
+    fn pruning_delay_uses_raised_depth() {
+        let config = SnapshotConfig::build().local_depth(0).pruning_delay(150).finish();
+
+        assert_eq!(
+            config.pruning_delay(),
+            Err(ConfigError::DelayTooSmall {
+                given: 150,
+                minimum: 151
+            })
+        );
+    }
+
+    #[test]
+    fn pruning_delay_ignored_when_disabled() {
+        let config = SnapshotConfig::build().pruning_enabled(false).pruning_delay(0).finish();
+
+        assert_eq!(config.pruning_delay(), Ok(0));
+    }
 }
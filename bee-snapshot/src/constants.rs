@@ -10,9 +10,6 @@
 // See the License for the specific language governing permissions and limitations under the License.
 
 pub(crate) const IOTA_SUPPLY: u64 = 2_779_530_283_277_761;
-#[allow(dead_code)] // TODO: When pruning is enabled
 pub(crate) const SOLID_ENTRY_POINT_CHECK_THRESHOLD_PAST: u32 = 50;
-#[allow(dead_code)] // TODO: When pruning is enabled
 pub(crate) const SOLID_ENTRY_POINT_CHECK_THRESHOLD_FUTURE: u32 = 50;
-#[allow(dead_code)] // TODO: When pruning is enabled
 pub(crate) const ADDITIONAL_PRUNING_THRESHOLD: u32 = 50;
@@ -15,8 +15,11 @@ const DEFAULT_PATH: &str = "./snapshots/mainnet/snapshot.txt";
 const DEFAULT_INDEX: u32 = 1050000;
 
 #[derive(Default, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct GlobalSnapshotConfigBuilder {
+    /// Path of the global snapshot file.
     path: Option<String>,
+    /// Index of the milestone the global snapshot was taken at.
     index: Option<u32>,
 }
 
@@ -16,6 +16,7 @@ use bee_transaction::bundled::{Address, BundledTransactionField};
 
 use std::{
     collections::HashMap,
+    fmt,
     fs::File,
     io::{prelude::*, BufReader},
 };
@@ -35,6 +36,26 @@ pub enum Error {
     DifferentNewline,
 }
 
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::FileNotFound => write!(f, "global snapshot file not found"),
+            Self::FailedIO => write!(f, "reading global snapshot file failed"),
+            Self::MissingSemicolon => write!(f, "missing semicolon between address and balance"),
+            Self::ExtraneousSemicolon => write!(f, "extraneous semicolon after balance"),
+            Self::InvalidAddressTryte => write!(f, "address contains an invalid tryte"),
+            Self::InvalidAddressLength => write!(f, "address has an invalid length"),
+            Self::DuplicateAddress => write!(f, "address appears more than once"),
+            Self::InvalidBalance => write!(f, "balance is not a valid number"),
+            Self::NullBalance => write!(f, "balance is null"),
+            Self::InvalidSupply => write!(f, "balances don't add up to the total supply"),
+            Self::DifferentNewline => write!(f, "lines don't all end with the same newline"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
 impl GlobalSnapshot {
     pub fn from_file(path: &str, index: u32) -> Result<Self, Error> {
         let file = File::open(path).map_err(|_| Error::FileNotFound)?;
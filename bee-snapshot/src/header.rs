@@ -10,9 +10,12 @@
 // See the License for the specific language governing permissions and limitations under the License.
 
 use bee_crypto::ternary::Hash;
+use bee_storage::persistable::Persistable;
+use bee_transaction::bundled::Address;
 
+#[derive(Clone, Persistable)]
 pub struct SnapshotHeader {
-    pub(crate) coordinator: Hash,
+    pub(crate) coordinator: Address,
     pub(crate) hash: Hash,
     pub(crate) snapshot_index: u32,
     pub(crate) entry_point_index: u32,
@@ -21,7 +24,7 @@ pub struct SnapshotHeader {
 }
 
 impl SnapshotHeader {
-    pub fn coordinator(&self) -> &Hash {
+    pub fn coordinator(&self) -> &Address {
         &self.coordinator
     }
 
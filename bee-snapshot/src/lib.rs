@@ -13,6 +13,7 @@ pub(crate) mod constants;
 pub(crate) mod pruning;
 // pub(crate) mod worker;
 
+pub mod balances;
 pub mod config;
 pub mod event;
 pub mod global;
@@ -31,15 +32,102 @@ use bee_transaction::bundled::Address;
 // use bee_protocol::{event::LatestSolidMilestoneChanged, MilestoneIndex};
 
 use chrono::{offset::TimeZone, Utc};
-use log::info;
+use log::{info, warn};
 
-use std::{collections::HashMap, path::Path, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    path::Path,
+    sync::Arc,
+};
 
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum Error {
+    Config(config::ConfigError),
     Global(global::FileError),
     Local(local::FileError),
     Download(local::DownloadError),
+    CoordinatorMismatch(Address, Address),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Config(e) => write!(f, "invalid snapshot config: {}", e),
+            Self::Global(e) => write!(f, "loading global snapshot file failed: {}", e),
+            Self::Local(e) => write!(f, "loading local snapshot file failed: {}", e),
+            Self::Download(e) => write!(f, "downloading local snapshot file failed: {}", e),
+            Self::CoordinatorMismatch(_, _) => {
+                write!(
+                    f,
+                    "snapshot was created for another coordinator than the configured one"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Config(e) => Some(e),
+            Self::Global(e) => Some(e),
+            Self::Local(e) => Some(e),
+            Self::Download(e) => Some(e),
+            Self::CoordinatorMismatch(_, _) => None,
+        }
+    }
+}
+
+impl From<config::ConfigError> for Error {
+    fn from(e: config::ConfigError) -> Self {
+        Self::Config(e)
+    }
+}
+
+impl From<global::FileError> for Error {
+    fn from(e: global::FileError) -> Self {
+        Self::Global(e)
+    }
+}
+
+impl From<local::FileError> for Error {
+    fn from(e: local::FileError) -> Self {
+        Self::Local(e)
+    }
+}
+
+impl From<local::DownloadError> for Error {
+    fn from(e: local::DownloadError) -> Self {
+        Self::Download(e)
+    }
+}
+
+/// Checks that a snapshot was created for the configured `coordinator`, unless `allow_mismatch` is set.
+///
+/// Snapshots that don't record their coordinator, like global snapshots or local snapshots of older versions, always
+/// pass the check.
+pub fn check_coordinator(
+    metadata: &SnapshotMetadata,
+    coordinator: &Address,
+    allow_mismatch: bool,
+) -> Result<(), Error> {
+    let snapshot_coordinator = metadata.coordinator();
+
+    if *snapshot_coordinator == Address::zeros() {
+        warn!("Snapshot doesn't record its coordinator, assuming it is the configured one.");
+    } else if snapshot_coordinator != coordinator {
+        if !allow_mismatch {
+            return Err(Error::CoordinatorMismatch(
+                snapshot_coordinator.clone(),
+                coordinator.clone(),
+            ));
+        }
+        warn!("Snapshot was created for another coordinator than the configured one, loading it anyway.");
+    }
+
+    Ok(())
 }
 
 // TODO change return type
@@ -47,14 +135,16 @@ pub enum Error {
 pub async fn init<N: Node>(
     // tangle: &MsTangle<B>,
     config: &config::SnapshotConfig,
+    coordinator: &Address,
     node_builder: N::Builder,
-) -> Result<(N::Builder, HashMap<Address, u64>, SnapshotMetadata), Error> {
-    let (state, mut metadata) = match config.load_type() {
+) -> Result<(N::Builder, HashMap<Address, u64>, HashSet<Address>, SnapshotMetadata), Error> {
+    config.pruning_delay()?;
+
+    let (state, spent_addresses, mut metadata) = match config.load_type() {
         config::LoadType::Global => {
             info!("Loading global snapshot file {}...", config.global().path());
 
-            let snapshot = global::GlobalSnapshot::from_file(config.global().path(), *config.global().index())
-                .map_err(Error::Global)?;
+            let snapshot = global::GlobalSnapshot::from_file(config.global().path(), *config.global().index())?;
 
             info!(
                 "Loaded global snapshot file from with index {} and {} balances.",
@@ -66,7 +156,7 @@ pub async fn init<N: Node>(
 
             let metadata = SnapshotMetadata {
                 header: SnapshotHeader {
-                    coordinator: Hash::zeros(),
+                    coordinator: coordinator.clone(),
                     hash: Hash::zeros(),
                     snapshot_index: index,
                     entry_point_index: index,
@@ -78,31 +168,44 @@ pub async fn init<N: Node>(
                 seen_milestones: HashMap::new(),
             };
 
-            (state, metadata)
+            // Global snapshots don't list spent addresses.
+            (state, HashSet::new(), metadata)
         }
         config::LoadType::Local => {
-            if !Path::new(config.local().path()).exists() {
-                local::download_local_snapshot(config.local())
-                    .await
-                    .map_err(Error::Download)?;
+            let path = config.local().path();
+            let backups = config.local().backups();
+
+            if !(0..=backups).any(|n| Path::new(&local::backup_path(path, n)).exists()) {
+                local::download_local_snapshot(config.local()).await?;
             }
-            info!("Loading local snapshot file {}...", config.local().path());
+            info!("Loading local snapshot file {}...", path);
 
-            let snapshot = local::LocalSnapshot::from_file(config.local().path()).map_err(Error::Local)?;
+            let snapshot = local::LocalSnapshot::from_file_or_backups(path, backups)?;
 
             info!(
-                "Loaded local snapshot file from {} with index {}, {} solid entry points, {} seen milestones and \
-                {} balances.",
+                "Loaded local snapshot file from {} with index {}, {} solid entry points, {} seen milestones, {} \
+                balances and {} spent addresses.",
                 Utc.timestamp(snapshot.metadata().timestamp() as i64, 0).to_rfc2822(),
                 snapshot.metadata().index(),
                 snapshot.metadata().solid_entry_points().len(),
                 snapshot.metadata().seen_milestones().len(),
-                snapshot.state.len()
+                snapshot.state.len(),
+                snapshot.spent_addresses.len()
             );
 
-            let LocalSnapshot { metadata, state } = snapshot;
+            check_coordinator(
+                snapshot.metadata(),
+                coordinator,
+                config.local().allow_coordinator_mismatch(),
+            )?;
+
+            let LocalSnapshot {
+                metadata,
+                state,
+                spent_addresses,
+            } = snapshot;
 
-            (state, metadata)
+            (state, spent_addresses, metadata)
         }
     };
 
@@ -112,7 +215,7 @@ pub async fn init<N: Node>(
 
     // node_builder = node_builder.with_worker_cfg::<worker::SnapshotWorker>(config.clone());
 
-    Ok((node_builder, state, metadata))
+    Ok((node_builder, state, spent_addresses, metadata))
 }
 
 pub fn events<N: Node>(_node: &N, _bus: Arc<Bus<'static>>) {
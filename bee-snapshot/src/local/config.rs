@@ -16,14 +16,26 @@ const DEFAULT_DOWNLOAD_URLS: Vec<String> = Vec::new();
 const DEFAULT_DEPTH: u32 = 50;
 const DEFAULT_INTERVAL_SYNCED: u32 = 50;
 const DEFAULT_INTERVAL_UNSYNCED: u32 = 1000;
+const DEFAULT_BACKUPS: usize = 1;
+const DEFAULT_ALLOW_COORDINATOR_MISMATCH: bool = false;
 
 #[derive(Default, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct LocalSnapshotConfigBuilder {
+    /// Path of the local snapshot file.
     path: Option<String>,
+    /// URLs the local snapshot file is downloaded from if it is missing.
     download_urls: Option<Vec<String>>,
+    /// Number of milestones kept below a new local snapshot.
     depth: Option<u32>,
+    /// Number of milestones between local snapshots while synced.
     interval_synced: Option<u32>,
+    /// Number of milestones between local snapshots while not synced.
     interval_unsynced: Option<u32>,
+    /// Number of previous local snapshot files kept as backups, loaded if the newest one is corrupted.
+    backups: Option<usize>,
+    /// Whether a local snapshot created for another coordinator than the configured one is loaded anyway.
+    allow_coordinator_mismatch: Option<bool>,
 }
 
 impl LocalSnapshotConfigBuilder {
@@ -56,6 +68,16 @@ impl LocalSnapshotConfigBuilder {
         self
     }
 
+    pub fn backups(mut self, backups: usize) -> Self {
+        self.backups.replace(backups);
+        self
+    }
+
+    pub fn allow_coordinator_mismatch(mut self, allow_coordinator_mismatch: bool) -> Self {
+        self.allow_coordinator_mismatch.replace(allow_coordinator_mismatch);
+        self
+    }
+
     pub fn finish(self) -> LocalSnapshotConfig {
         LocalSnapshotConfig {
             path: self.path.unwrap_or_else(|| DEFAULT_PATH.to_string()),
@@ -63,6 +85,10 @@ impl LocalSnapshotConfigBuilder {
             depth: self.depth.unwrap_or(DEFAULT_DEPTH),
             interval_synced: self.interval_synced.unwrap_or(DEFAULT_INTERVAL_SYNCED),
             interval_unsynced: self.interval_unsynced.unwrap_or(DEFAULT_INTERVAL_UNSYNCED),
+            backups: self.backups.unwrap_or(DEFAULT_BACKUPS),
+            allow_coordinator_mismatch: self
+                .allow_coordinator_mismatch
+                .unwrap_or(DEFAULT_ALLOW_COORDINATOR_MISMATCH),
         }
     }
 }
@@ -74,6 +100,8 @@ pub struct LocalSnapshotConfig {
     depth: u32,
     interval_synced: u32,
     interval_unsynced: u32,
+    backups: usize,
+    allow_coordinator_mismatch: bool,
 }
 
 impl LocalSnapshotConfig {
@@ -100,4 +128,12 @@ impl LocalSnapshotConfig {
     pub fn interval_unsynced(&self) -> u32 {
         self.interval_unsynced
     }
+
+    pub fn backups(&self) -> usize {
+        self.backups
+    }
+
+    pub fn allow_coordinator_mismatch(&self) -> bool {
+        self.allow_coordinator_mismatch
+    }
 }
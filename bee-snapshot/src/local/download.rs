@@ -13,13 +13,23 @@ use crate::local::LocalSnapshotConfig;
 
 use log::{error, info, warn};
 
-use std::{fs::File, io::copy, path::Path};
+use std::{fmt, fs::File, io::copy, path::Path};
 
 #[derive(Debug)]
 pub enum Error {
     NoWorkingDownloadSource,
 }
 
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoWorkingDownloadSource => write!(f, "no working download source available"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
 // TODO copy is not really streaming ?
 // TODO temporary file until fully downloaded ?
 pub async fn download_local_snapshot(config: &LocalSnapshotConfig) -> Result<(), Error> {
@@ -9,22 +9,38 @@
 // an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and limitations under the License.
 
-use crate::{constants::IOTA_SUPPLY, header::SnapshotHeader, local::LocalSnapshot, metadata::SnapshotMetadata};
+use crate::{
+    balances::{verify_balances_with_progress, Violation},
+    header::SnapshotHeader,
+    local::LocalSnapshot,
+    metadata::SnapshotMetadata,
+};
 
 use bee_crypto::ternary::{Hash, HASH_LENGTH};
 use bee_ternary::{T1B1Buf, T5B1Buf, Trits, T5B1};
-use bee_transaction::bundled::{Address, BundledTransactionField};
+use bee_transaction::bundled::{
+    constants::{ADDRESS_BYTE_LEN, HASH_BYTE_LEN},
+    Address, BundledTransactionField,
+};
 
 use bytemuck::cast_slice;
-use log::debug;
+use log::{debug, error, warn};
 
 use std::{
-    collections::HashMap,
-    fs::OpenOptions,
-    io::{BufReader, BufWriter, Read, Write},
+    borrow::Borrow,
+    collections::{HashMap, HashSet},
+    fmt,
+    fs::{rename, File, OpenOptions},
+    io::{BufReader, BufWriter, ErrorKind, Read, Write},
+    path::Path,
+    sync::atomic::{AtomicU8, Ordering},
 };
 
-const VERSION: u8 = 4;
+const VERSION: u8 = 5;
+// Files of this version don't contain the coordinator address.
+const LEGACY_VERSION: u8 = 4;
+// Upper bound of entries preallocated from the counts announced in the file header.
+const MAX_PREALLOCATED_ENTRIES: u32 = 100_000;
 
 // TODO detail errors
 #[derive(Debug)]
@@ -32,15 +48,144 @@ pub enum Error {
     IOError(std::io::Error),
     InvalidVersion(u8, u8),
     InvalidMilestoneHash,
+    InvalidCoordinatorAddress,
     InvalidSolidEntryPointHash,
     InvalidSeenMilestoneHash,
     InvalidAddress,
-    InvalidSupply(u64, u64),
+    InvalidSpentAddress,
+    InvalidBalances(Vec<Violation>),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::IOError(e) => write!(f, "I/O error: {}", e),
+            Self::InvalidVersion(version, expected) => {
+                write!(f, "invalid snapshot file version {}, expected {}", version, expected)
+            }
+            Self::InvalidMilestoneHash => write!(f, "invalid milestone hash"),
+            Self::InvalidCoordinatorAddress => write!(f, "invalid coordinator address"),
+            Self::InvalidSolidEntryPointHash => write!(f, "invalid solid entry point hash"),
+            Self::InvalidSeenMilestoneHash => write!(f, "invalid seen milestone hash"),
+            Self::InvalidAddress => write!(f, "invalid address"),
+            Self::InvalidSpentAddress => write!(f, "invalid spent address"),
+            Self::InvalidBalances(violations) => write!(f, "balances have {} violations", violations.len()),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::IOError(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Self::IOError(e)
+    }
+}
+
+// Logs the progress of the verification of balances, which can take a while for large snapshots.
+fn verify_balances<A>(balances: &[(A, u64)]) -> Result<(), Error>
+where
+    A: Borrow<Address> + Sync,
+{
+    // Progress is reported in steps of 10%.
+    let logged = AtomicU8::new(0);
+
+    verify_balances_with_progress(balances, |percent| {
+        if percent >= logged.load(Ordering::Relaxed) + 10 {
+            logged.store(percent - percent % 10, Ordering::Relaxed);
+            debug!("Verified {}% of the balances.", percent - percent % 10);
+        }
+    })
+    .map_err(Error::InvalidBalances)
+}
+
+/// Returns the path of the `n`-th most recent backup of a local snapshot file, `n == 0` being the file itself.
+pub(crate) fn backup_path(path: &str, n: usize) -> String {
+    match n {
+        0 => path.to_owned(),
+        1 => format!("{}.bak", path),
+        n => format!("{}.bak.{}", path, n),
+    }
 }
+
+// Shifts the existing backups by one, dropping the oldest one, and turns the current file into the most recent backup.
+fn rotate_backups(path: &str, backups: usize) -> Result<(), std::io::Error> {
+    if backups == 0 {
+        return Ok(());
+    }
+
+    for n in (1..backups).rev() {
+        let from = backup_path(path, n);
+        if Path::new(&from).exists() {
+            rename(&from, backup_path(path, n + 1))?;
+        }
+    }
+
+    if Path::new(path).exists() {
+        rename(path, backup_path(path, 1))?;
+    }
+
+    Ok(())
+}
+
+// Makes the renames within the directory of `path` durable.
+#[cfg(unix)]
+fn sync_parent_dir(path: &Path) -> Result<(), std::io::Error> {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => File::open(parent)?.sync_all(),
+        _ => File::open(".")?.sync_all(),
+    }
+}
+
+#[cfg(not(unix))]
+fn sync_parent_dir(_path: &Path) -> Result<(), std::io::Error> {
+    Ok(())
+}
+
 impl LocalSnapshot {
     pub fn from_file(path: &str) -> Result<LocalSnapshot, Error> {
-        let mut reader = BufReader::new(OpenOptions::new().read(true).open(path).map_err(Error::IOError)?);
+        let mut reader = BufReader::new(OpenOptions::new().read(true).open(path)?);
 
+        LocalSnapshot::from_reader(&mut reader)
+    }
+
+    /// Loads the local snapshot file at `path` or, if it is missing or invalid, the most recent valid of its `backups`
+    /// backups. The error of the first existing file is returned if none of them can be loaded.
+    pub fn from_file_or_backups(path: &str, backups: usize) -> Result<LocalSnapshot, Error> {
+        let mut first_error = None;
+
+        for n in 0..=backups {
+            let candidate = backup_path(path, n);
+
+            if !Path::new(&candidate).exists() {
+                continue;
+            }
+
+            match LocalSnapshot::from_file(&candidate) {
+                Ok(snapshot) => {
+                    if n != 0 {
+                        warn!("Loaded backup local snapshot file {} instead of {}.", candidate, path);
+                    }
+                    return Ok(snapshot);
+                }
+                Err(e) => {
+                    error!("Failed to load local snapshot file {}: {}", candidate, e);
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+
+        Err(first_error.unwrap_or_else(|| Error::IOError(ErrorKind::NotFound.into())))
+    }
+
+    pub fn from_reader<R: Read>(reader: &mut R) -> Result<LocalSnapshot, Error> {
         // Version byte
 
         let mut buf = [0u8];
@@ -49,7 +194,7 @@ impl LocalSnapshot {
             Err(e) => return Err(Error::IOError(e)),
         };
 
-        if version != VERSION {
+        if version != VERSION && version != LEGACY_VERSION {
             return Err(Error::InvalidVersion(version, VERSION));
         }
 
@@ -57,7 +202,7 @@ impl LocalSnapshot {
 
         // Milestone hash
 
-        let mut buf = [0u8; 49];
+        let mut buf = [0u8; HASH_BYTE_LEN];
         let hash = match reader.read_exact(&mut buf) {
             Ok(_) => match Trits::<T5B1>::try_from_raw(cast_slice(&buf), HASH_LENGTH) {
                 Ok(trits) => Hash::try_from_inner(trits.encode::<T1B1Buf>()).map_err(|_| Error::InvalidMilestoneHash),
@@ -88,6 +233,28 @@ impl LocalSnapshot {
 
         debug!("Timestamp: {}.", timestamp);
 
+        // Coordinator address
+
+        let coordinator = if version == LEGACY_VERSION {
+            Address::zeros()
+        } else {
+            let mut buf = [0u8; ADDRESS_BYTE_LEN];
+            match reader.read_exact(&mut buf) {
+                Ok(_) => match Trits::<T5B1>::try_from_raw(cast_slice(&buf), HASH_LENGTH) {
+                    Ok(trits) => {
+                        Address::try_from_inner(trits.encode::<T1B1Buf>()).map_err(|_| Error::InvalidCoordinatorAddress)
+                    }
+                    Err(_) => Err(Error::InvalidCoordinatorAddress),
+                },
+                Err(e) => Err(Error::IOError(e)),
+            }?
+        };
+
+        debug!(
+            "Coordinator: {}.",
+            coordinator.to_inner().iter_trytes().map(char::from).collect::<String>()
+        );
+
         // Number of solid entry points
 
         let mut buf = [0u8; std::mem::size_of::<u32>()];
@@ -130,9 +297,10 @@ impl LocalSnapshot {
 
         // Solid entry points
 
-        let mut buf_hash = [0u8; 49];
+        let mut buf_hash = [0u8; HASH_BYTE_LEN];
         let mut buf_index = [0u8; std::mem::size_of::<u32>()];
-        let mut solid_entry_points = HashMap::with_capacity(solid_entry_points_num as usize);
+        let mut solid_entry_points =
+            HashMap::with_capacity(solid_entry_points_num.min(MAX_PREALLOCATED_ENTRIES) as usize);
         for _ in 0..solid_entry_points_num {
             let hash = match reader.read_exact(&mut buf_hash) {
                 Ok(_) => match Trits::<T5B1>::try_from_raw(cast_slice(&buf_hash), HASH_LENGTH) {
@@ -152,9 +320,9 @@ impl LocalSnapshot {
 
         // Seen milestones
 
-        let mut buf_hash = [0u8; 49];
+        let mut buf_hash = [0u8; HASH_BYTE_LEN];
         let mut buf_index = [0u8; std::mem::size_of::<u32>()];
-        let mut seen_milestones = HashMap::with_capacity(seen_milestones_num as usize);
+        let mut seen_milestones = HashMap::with_capacity(seen_milestones_num.min(MAX_PREALLOCATED_ENTRIES) as usize);
         for _ in 0..seen_milestones_num {
             let seen_milestone = match reader.read_exact(&mut buf_hash) {
                 Ok(_) => match Trits::<T5B1>::try_from_raw(cast_slice(&buf_hash), HASH_LENGTH) {
@@ -174,10 +342,9 @@ impl LocalSnapshot {
 
         // Balances
 
-        let mut buf_address = [0u8; 49];
+        let mut buf_address = [0u8; ADDRESS_BYTE_LEN];
         let mut buf_value = [0u8; std::mem::size_of::<u64>()];
-        let mut state = HashMap::with_capacity(balances_num as usize);
-        let mut supply: u64 = 0;
+        let mut balances = Vec::with_capacity(balances_num.min(MAX_PREALLOCATED_ENTRIES) as usize);
         for i in 0..balances_num {
             let address = match reader.read_exact(&mut buf_address) {
                 Ok(_) => match Trits::<T5B1>::try_from_raw(cast_slice(&buf_address), HASH_LENGTH) {
@@ -196,16 +363,32 @@ impl LocalSnapshot {
                     "Read {}/{} ({:.0}%) balances.",
                     i,
                     balances_num,
-                    ((i * 100) as f64) / (balances_num as f64)
+                    (i as f64 * 100.0) / (balances_num as f64)
                 );
             }
 
-            state.insert(address, value);
-            supply += value;
+            balances.push((address, value));
         }
 
-        if supply != IOTA_SUPPLY {
-            return Err(Error::InvalidSupply(supply, IOTA_SUPPLY));
+        verify_balances(&balances)?;
+
+        let state = balances.into_iter().collect();
+
+        // Spent addresses
+
+        let mut buf_address = [0u8; ADDRESS_BYTE_LEN];
+        let mut spent_addresses = HashSet::with_capacity(spent_addresses_num.min(MAX_PREALLOCATED_ENTRIES) as usize);
+        for _ in 0..spent_addresses_num {
+            let address = match reader.read_exact(&mut buf_address) {
+                Ok(_) => match Trits::<T5B1>::try_from_raw(cast_slice(&buf_address), HASH_LENGTH) {
+                    Ok(trits) => {
+                        Address::try_from_inner(trits.encode::<T1B1Buf>()).map_err(|_| Error::InvalidSpentAddress)
+                    }
+                    Err(_) => Err(Error::InvalidSpentAddress),
+                },
+                Err(e) => Err(Error::IOError(e)),
+            }?;
+            spent_addresses.insert(address);
         }
 
         // TODO hash ?
@@ -213,7 +396,7 @@ impl LocalSnapshot {
         Ok(LocalSnapshot {
             metadata: SnapshotMetadata {
                 header: SnapshotHeader {
-                    coordinator: Hash::zeros(),
+                    coordinator,
                     hash,
                     snapshot_index: index,
                     entry_point_index: index,
@@ -224,19 +407,54 @@ impl LocalSnapshot {
                 seen_milestones,
             },
             state,
+            spent_addresses,
         })
     }
 
-    pub fn to_file(&self, path: &str) -> Result<(), Error> {
+    /// Atomically replaces the local snapshot file at `path`, keeping up to `backups` previous files as backups.
+    ///
+    /// The snapshot is first fully written and synced to a temporary file of the same directory which is then renamed
+    /// over `path`, so that a crash never leaves a truncated snapshot behind.
+    pub fn to_file(&self, path: &str, backups: usize) -> Result<(), Error> {
+        self.to_file_with_hook(path, backups, |_| Ok(()))
+    }
+
+    // The hook is called on the synced temporary file, right before it replaces the previous snapshot file.
+    fn to_file_with_hook<F>(&self, path: &str, backups: usize, before_rename: F) -> Result<(), Error>
+    where
+        F: FnOnce(&Path) -> Result<(), std::io::Error>,
+    {
+        let tmp_path = format!("{}.tmp", path);
+
         let mut writer = BufWriter::new(
             OpenOptions::new()
                 .write(true)
                 .truncate(true)
                 .create(true)
-                .open(path)
-                .map_err(Error::IOError)?,
+                .open(&tmp_path)?,
         );
 
+        self.to_writer(&mut writer)?;
+        writer.flush()?;
+        writer.get_ref().sync_all()?;
+        drop(writer);
+
+        before_rename(Path::new(&tmp_path))?;
+
+        rotate_backups(path, backups)?;
+        rename(&tmp_path, path)?;
+        sync_parent_dir(Path::new(path)).map_err(Error::IOError)
+    }
+
+    pub fn to_writer<W: Write>(&self, writer: &mut W) -> Result<(), Error> {
+        verify_balances(
+            &self
+                .state
+                .iter()
+                .map(|(address, balance)| (address, *balance))
+                .collect::<Vec<_>>(),
+        )?;
+
         // Version byte
 
         if let Err(e) = writer.write_all(&[VERSION]) {
@@ -263,6 +481,19 @@ impl LocalSnapshot {
             return Err(Error::IOError(e));
         }
 
+        // Coordinator address
+
+        if let Err(e) = writer.write_all(&cast_slice(
+            self.metadata
+                .header
+                .coordinator
+                .to_inner()
+                .encode::<T5B1Buf>()
+                .as_i8_slice(),
+        )) {
+            return Err(Error::IOError(e));
+        }
+
         // Number of solid entry points
 
         if let Err(e) = writer.write_all(&(self.metadata.solid_entry_points.len() as u32).to_le_bytes()) {
@@ -283,7 +514,7 @@ impl LocalSnapshot {
 
         // Number of spent addresses
 
-        if let Err(e) = writer.write_all(&0u32.to_le_bytes()) {
+        if let Err(e) = writer.write_all(&(self.spent_addresses.len() as u32).to_le_bytes()) {
             return Err(Error::IOError(e));
         }
 
@@ -320,8 +551,246 @@ impl LocalSnapshot {
             }
         }
 
+        // Spent addresses
+
+        for address in self.spent_addresses.iter() {
+            if let Err(e) = writer.write_all(&cast_slice(address.to_inner().encode::<T5B1Buf>().as_i8_slice())) {
+                return Err(Error::IOError(e));
+            }
+        }
+
         // TODO hash ?
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use crate::constants::IOTA_SUPPLY;
+
+    use bee_ternary::TryteBuf;
+
+    use std::{fs, path::PathBuf};
+
+    fn snapshot(index: u32) -> LocalSnapshot {
+        let mut state = HashMap::new();
+        state.insert(Address::zeros(), IOTA_SUPPLY);
+
+        LocalSnapshot {
+            metadata: SnapshotMetadata {
+                header: SnapshotHeader {
+                    coordinator: Address::zeros(),
+                    hash: Hash::zeros(),
+                    snapshot_index: index,
+                    entry_point_index: index,
+                    pruning_index: index,
+                    timestamp: 0,
+                },
+                solid_entry_points: HashMap::new(),
+                seen_milestones: HashMap::new(),
+            },
+            state,
+            spent_addresses: HashSet::new(),
+        }
+    }
+
+    // Returns the path of a snapshot file in a fresh directory unique to the test.
+    fn snapshot_path(test: &str) -> (PathBuf, String) {
+        let dir = std::env::temp_dir().join(format!("bee_snapshot_{}_{}", test, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("export.bin").to_str().unwrap().to_owned();
+
+        (dir, path)
+    }
+
+    fn truncate(path: &Path) -> Result<(), std::io::Error> {
+        OpenOptions::new().write(true).open(path)?.set_len(10)
+    }
+
+    #[test]
+    fn coordinator_round_trip() {
+        let coordinator = Address::try_from_inner(
+            TryteBuf::try_from_str("EQSAUZXULTTYZCLNJNTXQTQHOMOFZERHTCGTXOLTVAHKSA9OGAZDEKECURBRIXIJWNPFCQIOVFVVXJVD9")
+                .unwrap()
+                .as_trits()
+                .encode::<T1B1Buf>(),
+        )
+        .unwrap();
+        let mut ls = snapshot(1);
+        ls.metadata.header.coordinator = coordinator.clone();
+
+        let mut bytes = Vec::new();
+        ls.to_writer(&mut bytes).unwrap();
+
+        assert_eq!(
+            LocalSnapshot::from_reader(&mut bytes.as_slice())
+                .unwrap()
+                .metadata()
+                .coordinator(),
+            &coordinator
+        );
+    }
+
+    #[test]
+    fn spent_addresses_round_trip() {
+        let spent = Address::try_from_inner(
+            TryteBuf::try_from_str("EQSAUZXULTTYZCLNJNTXQTQHOMOFZERHTCGTXOLTVAHKSA9OGAZDEKECURBRIXIJWNPFCQIOVFVVXJVD9")
+                .unwrap()
+                .as_trits()
+                .encode::<T1B1Buf>(),
+        )
+        .unwrap();
+        let mut ls = snapshot(1);
+        ls.spent_addresses.insert(spent.clone());
+
+        let mut bytes = Vec::new();
+        ls.to_writer(&mut bytes).unwrap();
+
+        let read = LocalSnapshot::from_reader(&mut bytes.as_slice()).unwrap();
+        assert_eq!(read.spent_addresses().len(), 1);
+        assert!(read.spent_addresses().contains(&spent));
+    }
+
+    #[test]
+    fn invalid_balances_not_written() {
+        let mut ls = snapshot(1);
+        ls.state.insert(Address::zeros(), IOTA_SUPPLY - 1);
+
+        let mut bytes = Vec::new();
+
+        match ls.to_writer(&mut bytes) {
+            Err(Error::InvalidBalances(violations)) => assert_eq!(
+                violations,
+                vec![Violation::InvalidSupply(IOTA_SUPPLY as u128 - 1, IOTA_SUPPLY)]
+            ),
+            _ => unreachable!(),
+        }
+        assert!(bytes.is_empty());
+    }
+
+    fn index(path: &str) -> u32 {
+        LocalSnapshot::from_file(path).unwrap().metadata().index()
+    }
+
+    #[test]
+    fn write_keeps_backup() {
+        let (dir, path) = snapshot_path("write_keeps_backup");
+
+        snapshot(1).to_file(&path, 1).unwrap();
+        snapshot(2).to_file(&path, 1).unwrap();
+
+        assert_eq!(index(&path), 2);
+        assert_eq!(index(&backup_path(&path, 1)), 1);
+        assert!(!Path::new(&format!("{}.tmp", path)).exists());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn write_rotates_backups() {
+        let (dir, path) = snapshot_path("write_rotates_backups");
+
+        for i in 1..=4 {
+            snapshot(i).to_file(&path, 2).unwrap();
+        }
+
+        assert_eq!(index(&path), 4);
+        assert_eq!(index(&backup_path(&path, 1)), 3);
+        assert_eq!(index(&backup_path(&path, 2)), 2);
+        assert!(!Path::new(&backup_path(&path, 3)).exists());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn write_without_backups() {
+        let (dir, path) = snapshot_path("write_without_backups");
+
+        snapshot(1).to_file(&path, 0).unwrap();
+        snapshot(2).to_file(&path, 0).unwrap();
+
+        assert_eq!(index(&path), 2);
+        assert!(!Path::new(&backup_path(&path, 1)).exists());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn interrupted_write_keeps_previous_snapshot() {
+        let (dir, path) = snapshot_path("interrupted_write_keeps_previous_snapshot");
+
+        snapshot(1).to_file(&path, 1).unwrap();
+
+        let result = snapshot(2).to_file_with_hook(&path, 1, |tmp| {
+            truncate(tmp)?;
+            Err(std::io::Error::new(ErrorKind::Other, "crash"))
+        });
+
+        assert!(matches!(result, Err(Error::IOError(_))));
+        assert_eq!(index(&path), 1);
+        assert_eq!(
+            LocalSnapshot::from_file_or_backups(&path, 1)
+                .unwrap()
+                .metadata()
+                .index(),
+            1
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn truncated_snapshot_falls_back_to_backup() {
+        let (dir, path) = snapshot_path("truncated_snapshot_falls_back_to_backup");
+
+        snapshot(1).to_file(&path, 1).unwrap();
+        snapshot(2).to_file_with_hook(&path, 1, truncate).unwrap();
+
+        assert!(matches!(LocalSnapshot::from_file(&path), Err(Error::IOError(_))));
+        assert_eq!(
+            LocalSnapshot::from_file_or_backups(&path, 1)
+                .unwrap()
+                .metadata()
+                .index(),
+            1
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn missing_snapshot_falls_back_to_backup() {
+        let (dir, path) = snapshot_path("missing_snapshot_falls_back_to_backup");
+
+        snapshot(1).to_file(&path, 1).unwrap();
+        snapshot(2).to_file(&path, 1).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            LocalSnapshot::from_file_or_backups(&path, 1)
+                .unwrap()
+                .metadata()
+                .index(),
+            1
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn no_snapshot_nor_backup() {
+        let (dir, path) = snapshot_path("no_snapshot_nor_backup");
+
+        assert!(matches!(
+            LocalSnapshot::from_file_or_backups(&path, 1),
+            Err(Error::IOError(e)) if e.kind() == ErrorKind::NotFound
+        ));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -14,6 +14,7 @@ mod download;
 mod file;
 
 pub(crate) use download::{download_local_snapshot, Error as DownloadError};
+pub(crate) use file::backup_path;
 
 pub use config::{LocalSnapshotConfig, LocalSnapshotConfigBuilder};
 pub use file::Error as FileError;
@@ -25,11 +26,12 @@ use bee_transaction::bundled::Address;
 
 use log::{error, info};
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 pub struct LocalSnapshot {
     pub(crate) metadata: SnapshotMetadata,
     pub(crate) state: HashMap<Address, u64>,
+    pub(crate) spent_addresses: HashSet<Address>,
 }
 
 impl LocalSnapshot {
@@ -40,19 +42,23 @@ impl LocalSnapshot {
     pub fn state(&self) -> &HashMap<Address, u64> {
         &self.state
     }
+
+    pub fn spent_addresses(&self) -> &HashSet<Address> {
+        &self.spent_addresses
+    }
 }
 
 #[derive(Debug)]
 pub(crate) enum Error {}
 
 #[allow(dead_code)] // TODO: When pruning is enabled
-pub(crate) fn snapshot(path: &str, index: u32) -> Result<(), Error> {
+pub(crate) fn snapshot(config: &LocalSnapshotConfig, coordinator: &Address, index: u32) -> Result<(), Error> {
     info!("Creating local snapshot at index {}...", index);
 
     let ls = LocalSnapshot {
         metadata: SnapshotMetadata {
             header: SnapshotHeader {
-                coordinator: Hash::zeros(),
+                coordinator: coordinator.clone(),
                 hash: Hash::zeros(),
                 snapshot_index: index,
                 entry_point_index: index,
@@ -63,12 +69,11 @@ pub(crate) fn snapshot(path: &str, index: u32) -> Result<(), Error> {
             seen_milestones: HashMap::new(),
         },
         state: HashMap::new(),
+        spent_addresses: HashSet::new(),
     };
 
-    let file = path.to_string() + "_tmp";
-
-    if let Err(e) = ls.to_file(&file) {
-        error!("Failed to write local snapshot to file {}: {:?}.", file, e);
+    if let Err(e) = ls.to_file(config.path(), config.backups()) {
+        error!("Failed to write local snapshot to file {}: {}", config.path(), e);
     }
 
     info!("Created local snapshot at index {}.", index);
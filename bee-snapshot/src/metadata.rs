@@ -12,9 +12,12 @@
 use crate::header::SnapshotHeader;
 
 use bee_crypto::ternary::Hash;
+use bee_storage::persistable::Persistable;
+use bee_transaction::bundled::Address;
 
 use std::collections::HashMap;
 
+#[derive(Clone, Persistable)]
 pub struct SnapshotMetadata {
     pub(crate) header: SnapshotHeader,
     pub(crate) solid_entry_points: HashMap<Hash, u32>,
@@ -34,6 +37,11 @@ impl SnapshotMetadata {
         self.header.timestamp
     }
 
+    /// Address of the coordinator the snapshot was created for, all zeros if unknown.
+    pub fn coordinator(&self) -> &Address {
+        &self.header.coordinator
+    }
+
     pub fn solid_entry_points(&self) -> &HashMap<Hash, u32> {
         &self.solid_entry_points
     }
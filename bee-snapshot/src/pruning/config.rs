@@ -15,8 +15,11 @@ const DEFAULT_ENABLED: bool = true;
 const DEFAULT_DELAY: u32 = 60480;
 
 #[derive(Default, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct PruningConfigBuilder {
+    /// Whether old milestones are pruned.
     enabled: Option<bool>,
+    /// Number of milestones kept before pruning.
     delay: Option<u32>,
 }
 
@@ -31,6 +31,7 @@ pub use config::{PruningConfig, PruningConfigBuilder};
 // pub enum Error {
 //     MilestoneNotFoundInTangle(u32),
 //     MetadataNotFound(Box<Hash>),
+//     StorageError(String),
 // }
 
 // /// Checks whether any direct approver of the given transaction was confirmed by a
@@ -121,7 +122,10 @@ pub use config::{PruningConfig, PruningConfigBuilder};
 // }
 
 // // NOTE we don't prune cache, but only prune the database.
-// pub fn prune_database<B: Backend>(tangle: &MsTangle<B>, mut target_index: MilestoneIndex) -> Result<(), Error> {
+// pub async fn prune_database<B: SolidEntryPointsStorage>(
+//     tangle: &MsTangle<B>,
+//     mut target_index: MilestoneIndex,
+// ) -> Result<(), Error> {
 //     let target_index_max = MilestoneIndex(
 //         *tangle.get_snapshot_index() - SOLID_ENTRY_POINT_CHECK_THRESHOLD_PAST - ADDITIONAL_PRUNING_THRESHOLD - 1,
 //     );
@@ -131,17 +135,12 @@ pub use config::{PruningConfig, PruningConfigBuilder};
 //     // Update the solid entry points in the static MsTangle.
 //     let new_solid_entry_points = get_new_solid_entry_points(tangle, target_index)?;
 
-//     // Clear the solid_entry_points in the static MsTangle.
-//     tangle.clear_solid_entry_points();
-
-//     // TODO update the whole solid_entry_points in the static MsTangle w/o looping.
-//     for (hash, milestone_index) in new_solid_entry_points.into_iter() {
-//         tangle.add_solid_entry_point(hash, milestone_index);
-//     }
-
-//     // We have to set the new solid entry point index.
+//     // Persist the new solid entry points along with the new solid entry point index, then apply them in memory.
 //     // This way we can cleanly prune even if the pruning was aborted last time.
-//     tangle.update_entry_point_index(target_index);
+//     tangle
+//         .replace_solid_entry_points(new_solid_entry_points.into_iter().collect(), target_index)
+//         .await
+//         .map_err(|e| Error::StorageError(format!("{:?}", e)))?;
 
 //     prune_unconfirmed_transactions(&tangle.get_pruning_index());
 
@@ -133,7 +133,7 @@ impl<N: Node> Worker<N> for SnapshotWorker {
 
             while let Some(SnapshotWorkerEvent(milestone)) = receiver.next().await {
                 if should_snapshot(&tangle, milestone.index(), &config, depth) {
-                    if let Err(e) = snapshot(config.local().path(), *milestone.index() - depth) {
+                    if let Err(e) = snapshot(config.local(), *milestone.index() - depth) {
                         error!("Failed to create snapshot: {:?}.", e);
                     }
                 }
@@ -0,0 +1,199 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use bee_snapshot::{
+    balances::Violation,
+    check_coordinator,
+    local::{FileError as Error, LocalSnapshot},
+    Error as SnapshotError,
+};
+use bee_ternary::{T1B1Buf, T5B1Buf, TryteBuf};
+use bee_transaction::bundled::{constants::HASH_BYTE_LEN, Address, BundledTransactionField};
+
+use bytemuck::cast_slice;
+use rand::Rng;
+
+use std::error::Error as _;
+
+const VERSION: u8 = 5;
+const LEGACY_VERSION: u8 = 4;
+const HASH_SIZE: usize = HASH_BYTE_LEN;
+const IOTA_SUPPLY: u64 = 2_779_530_283_277_761;
+const MAINNET_COORDINATOR: &str = "EQSAUZXULTTYZCLNJNTXQTQHOMOFZERHTCGTXOLTVAHKSA9OGAZDEKECURBRIXIJWNPFCQIOVFVVXJVD9";
+const DEVNET_COORDINATOR: &str = "GYISMBVRKSCEXXTUPBWTIHRCZIKIRPDYAHAYKOEH9XFNXZNCXGTEYJFSMYIKHSSYUOFIRUM9KGOPKKUE9";
+
+fn header(solid_entry_points: u32, seen_milestones: u32, balances: u32, spent_addresses: u32) -> Vec<u8> {
+    let mut bytes = vec![VERSION];
+    bytes.extend_from_slice(&[0u8; HASH_SIZE]);
+    bytes.extend_from_slice(&42u32.to_le_bytes());
+    bytes.extend_from_slice(&0u64.to_le_bytes());
+    bytes.extend_from_slice(&[0u8; HASH_SIZE]);
+    bytes.extend_from_slice(&solid_entry_points.to_le_bytes());
+    bytes.extend_from_slice(&seen_milestones.to_le_bytes());
+    bytes.extend_from_slice(&balances.to_le_bytes());
+    bytes.extend_from_slice(&spent_addresses.to_le_bytes());
+    bytes
+}
+
+fn address(trytes: &str) -> Address {
+    Address::try_from_inner(TryteBuf::try_from_str(trytes).unwrap().as_trits().encode::<T1B1Buf>()).unwrap()
+}
+
+// A valid snapshot holding the whole supply on a single address, created for `coordinator` if not legacy.
+fn fixture(version: u8, coordinator: &Address) -> Vec<u8> {
+    let mut bytes = vec![version];
+    bytes.extend_from_slice(&[0u8; HASH_SIZE]);
+    bytes.extend_from_slice(&42u32.to_le_bytes());
+    bytes.extend_from_slice(&0u64.to_le_bytes());
+    if version != LEGACY_VERSION {
+        bytes.extend_from_slice(cast_slice(coordinator.to_inner().encode::<T5B1Buf>().as_i8_slice()));
+    }
+    for count in &[0u32, 0, 1, 0] {
+        bytes.extend_from_slice(&count.to_le_bytes());
+    }
+    bytes.extend_from_slice(&[0u8; HASH_SIZE]);
+    bytes.extend_from_slice(&IOTA_SUPPLY.to_le_bytes());
+    bytes
+}
+
+#[test]
+fn invalid_version() {
+    match LocalSnapshot::from_reader(&mut [VERSION + 1].as_ref()) {
+        Err(Error::InvalidVersion(read, expected)) => {
+            assert_eq!(read, VERSION + 1);
+            assert_eq!(expected, VERSION);
+        }
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn truncated_header() {
+    let bytes = header(0, 0, 0, 0);
+
+    for len in 0..bytes.len() {
+        match LocalSnapshot::from_reader(&mut &bytes[..len]) {
+            Err(Error::IOError(_)) => (),
+            _ => unreachable!(),
+        }
+    }
+}
+
+#[test]
+fn oversized_announced_counts() {
+    let bytes = header(u32::MAX, u32::MAX, u32::MAX, u32::MAX);
+
+    match LocalSnapshot::from_reader(&mut bytes.as_slice()) {
+        Err(Error::IOError(_)) => (),
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn overflowing_supply() {
+    let mut bytes = header(0, 0, 2, 0);
+
+    for _ in 0..2 {
+        bytes.extend_from_slice(&[0u8; HASH_SIZE]);
+        bytes.extend_from_slice(&u64::MAX.to_le_bytes());
+    }
+
+    match LocalSnapshot::from_reader(&mut bytes.as_slice()) {
+        Err(Error::InvalidBalances(violations)) => {
+            assert!(violations.contains(&Violation::InvalidSupply(2 * u64::MAX as u128, IOTA_SUPPLY)))
+        }
+        _ => unreachable!(),
+    }
+}
+
+// Only checks that reading arbitrary content never panics.
+#[test]
+fn fuzz_random_bytes() {
+    let mut rng = rand::thread_rng();
+
+    for _ in 0..1000 {
+        let mut bytes = if rng.gen_bool(0.5) {
+            header(rng.gen_range(0, 4), rng.gen_range(0, 4), rng.gen_range(0, 4), rng.gen())
+        } else {
+            vec![VERSION]
+        };
+        let len = rng.gen_range(0, 512);
+        bytes.extend((0..len).map(|_| rng.gen::<u8>()));
+
+        let _ = LocalSnapshot::from_reader(&mut bytes.as_slice());
+    }
+}
+
+#[test]
+fn legacy_version() {
+    let bytes = fixture(LEGACY_VERSION, &address(MAINNET_COORDINATOR));
+    let snapshot = LocalSnapshot::from_reader(&mut bytes.as_slice()).unwrap();
+
+    assert_eq!(snapshot.metadata().index(), 42);
+    assert_eq!(snapshot.metadata().coordinator(), &Address::zeros());
+    assert!(check_coordinator(snapshot.metadata(), &address(MAINNET_COORDINATOR), false).is_ok());
+}
+
+#[test]
+fn matching_coordinator() {
+    let bytes = fixture(VERSION, &address(MAINNET_COORDINATOR));
+    let snapshot = LocalSnapshot::from_reader(&mut bytes.as_slice()).unwrap();
+
+    assert_eq!(snapshot.metadata().coordinator(), &address(MAINNET_COORDINATOR));
+    assert!(check_coordinator(snapshot.metadata(), &address(MAINNET_COORDINATOR), false).is_ok());
+}
+
+#[test]
+fn mismatching_coordinator() {
+    let bytes = fixture(VERSION, &address(DEVNET_COORDINATOR));
+    let snapshot = LocalSnapshot::from_reader(&mut bytes.as_slice()).unwrap();
+
+    match check_coordinator(snapshot.metadata(), &address(MAINNET_COORDINATOR), false) {
+        Err(SnapshotError::CoordinatorMismatch(snapshot_coordinator, coordinator)) => {
+            assert_eq!(snapshot_coordinator, address(DEVNET_COORDINATOR));
+            assert_eq!(coordinator, address(MAINNET_COORDINATOR));
+        }
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn mismatching_coordinator_allowed() {
+    let bytes = fixture(VERSION, &address(DEVNET_COORDINATOR));
+    let snapshot = LocalSnapshot::from_reader(&mut bytes.as_slice()).unwrap();
+
+    assert!(check_coordinator(snapshot.metadata(), &address(MAINNET_COORDINATOR), true).is_ok());
+}
+
+#[test]
+fn error_display_and_source() {
+    let error = SnapshotError::from(LocalSnapshot::from_reader(&mut [VERSION + 1].as_ref()).err().unwrap());
+
+    assert_eq!(
+        error.to_string(),
+        "loading local snapshot file failed: invalid snapshot file version 6, expected 5"
+    );
+    match error.source().and_then(|source| source.downcast_ref::<Error>()) {
+        Some(Error::InvalidVersion(6, 5)) => (),
+        _ => unreachable!(),
+    }
+
+    let error = SnapshotError::from(LocalSnapshot::from_reader(&mut [0u8; 0].as_ref()).err().unwrap());
+    let io_error = error
+        .source()
+        .and_then(|source| source.source())
+        .and_then(|source| source.downcast_ref::<std::io::Error>());
+
+    assert_eq!(
+        io_error.map(std::io::Error::kind),
+        Some(std::io::ErrorKind::UnexpectedEof)
+    );
+}
@@ -0,0 +1,473 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+//! Derive macros for the `bee-storage` traits.
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, parse_quote, spanned::Spanned, Attribute, Data, DataEnum, DeriveInput, Error, Field, Fields,
+    Index, Meta, NestedMeta, Type, WherePredicate,
+};
+
+/// Derives `Persistable` for types whose fields are all `Persistable`.
+///
+/// Struct fields are encoded one after the other in declaration order, without any framing, so a struct is
+/// byte-compatible with the concatenation of its fields. Fixed-size arrays are encoded element by element.
+/// Fields marked with `#[persistable(skip)]` are not encoded and are set to their `Default` value when decoding.
+///
+/// Enums may only have unit and new-type variants. They are encoded as a discriminant byte, the index of the variant,
+/// followed by the encoding of the variant field, if any.
+#[proc_macro_derive(Persistable, attributes(persistable))]
+pub fn derive_persistable(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match persistable(input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn persistable(input: DeriveInput) -> Result<TokenStream2, Error> {
+    let name = &input.ident;
+    let mut predicates = Vec::new();
+
+    let (encode, decode) = match &input.data {
+        Data::Struct(data) => persistable_struct(&data.fields, &mut predicates)?,
+        Data::Enum(data) => persistable_enum(data, &mut predicates)?,
+        Data::Union(data) => {
+            return Err(Error::new(
+                data.union_token.span(),
+                "`Persistable` can't be derived for unions",
+            ))
+        }
+    };
+
+    let mut generics = input.generics.clone();
+    generics.make_where_clause().predicates.extend(predicates);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::bee_storage::persistable::Persistable for #name #ty_generics #where_clause {
+            #[allow(unused_variables)]
+            fn encode_persistable(&self, buffer: &mut Vec<u8>) {
+                #encode
+            }
+
+            #[allow(unused_mut, unused_variables)]
+            fn decode_persistable_prefix(
+                slice: &[u8],
+            ) -> Result<(Self, usize), ::bee_storage::persistable::DecodeError> {
+                #decode
+            }
+        }
+    })
+}
+
+fn persistable_struct(
+    fields: &Fields,
+    predicates: &mut Vec<WherePredicate>,
+) -> Result<(TokenStream2, TokenStream2), Error> {
+    let mut encode = Vec::new();
+    let mut decode = Vec::new();
+    let mut bindings = Vec::new();
+
+    for (i, field) in fields.iter().enumerate() {
+        let (accessor, binding) = match &field.ident {
+            Some(ident) => (quote!(#ident), quote!(#ident)),
+            None => {
+                let index = Index::from(i);
+                let binding = format_ident!("field_{}", i);
+                (quote!(#index), quote!(#binding))
+            }
+        };
+        let ty = &field.ty;
+
+        if is_skipped(field)? {
+            predicates.push(parse_quote!(#ty: Default));
+            decode.push(quote!(let #binding = Default::default();));
+        } else {
+            let (field_encode, field_decode) = persistable_field(ty, predicates)?;
+            encode.push(quote! {
+                let field = &self.#accessor;
+                #field_encode
+            });
+            decode.push(quote! {
+                let #binding = {
+                    let slice = &slice[position..];
+                    #field_decode
+                };
+            });
+        }
+
+        bindings.push(binding);
+    }
+
+    let construct = match fields {
+        Fields::Named(_) => quote!(Self { #(#bindings),* }),
+        Fields::Unnamed(_) => quote!(Self(#(#bindings),*)),
+        Fields::Unit => quote!(Self),
+    };
+
+    Ok((
+        quote!(#(#encode)*),
+        quote! {
+            let mut position = 0;
+            #(#decode)*
+            Ok((#construct, position))
+        },
+    ))
+}
+
+fn persistable_enum(
+    data: &DataEnum,
+    predicates: &mut Vec<WherePredicate>,
+) -> Result<(TokenStream2, TokenStream2), Error> {
+    if data.variants.len() > 256 {
+        return Err(Error::new(
+            data.enum_token.span(),
+            "`Persistable` can't be derived for enums with more than 256 variants",
+        ));
+    }
+
+    let mut encode = Vec::new();
+    let mut decode = Vec::new();
+
+    for (i, variant) in data.variants.iter().enumerate() {
+        let ident = &variant.ident;
+        let discriminant = i as u8;
+
+        match &variant.fields {
+            Fields::Unit => {
+                encode.push(quote!(Self::#ident => buffer.push(#discriminant),));
+                decode.push(quote!(#discriminant => Ok((Self::#ident, 1)),));
+            }
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                let field = &fields.unnamed[0];
+                if is_skipped(field)? {
+                    return Err(Error::new(field.span(), "enum variant fields can't be skipped"));
+                }
+                let (field_encode, field_decode) = persistable_field(&field.ty, predicates)?;
+                encode.push(quote! {
+                    Self::#ident(field) => {
+                        buffer.push(#discriminant);
+                        #field_encode
+                    }
+                });
+                decode.push(quote! {
+                    #discriminant => {
+                        let mut position = 1;
+                        let field = {
+                            let slice = &slice[position..];
+                            #field_decode
+                        };
+                        Ok((Self::#ident(field), position))
+                    }
+                });
+            }
+            _ => {
+                return Err(Error::new(
+                    variant.span(),
+                    "`Persistable` can only be derived for enums with unit or new-type variants",
+                ))
+            }
+        }
+    }
+
+    Ok((
+        quote! {
+            match self {
+                #(#encode)*
+            }
+        },
+        quote! {
+            let discriminant = ::bee_storage::persistable::take(slice, 1)?[0];
+            match discriminant {
+                #(#decode)*
+                discriminant => Err(::bee_storage::persistable::DecodeError::InvalidDiscriminant(discriminant)),
+            }
+        },
+    ))
+}
+
+// Generates the code encoding the variable `field` into `buffer` and the code decoding a value from the start of
+// `slice`, which adds the number of bytes it read to `position`.
+fn persistable_field(ty: &Type, predicates: &mut Vec<WherePredicate>) -> Result<(TokenStream2, TokenStream2), Error> {
+    match ty {
+        Type::Path(_) => {
+            predicates.push(parse_quote!(#ty: ::bee_storage::persistable::Persistable));
+            Ok((
+                quote! {
+                    <#ty as ::bee_storage::persistable::Persistable>::encode_persistable(field, buffer);
+                },
+                quote! {
+                    let (field, length) =
+                        <#ty as ::bee_storage::persistable::Persistable>::decode_persistable_prefix(slice)?;
+                    position += length;
+                    field
+                },
+            ))
+        }
+        Type::Array(array) => {
+            let elem = &array.elem;
+            let len = &array.len;
+            if let Type::Path(_) = **elem {
+                predicates.push(parse_quote!(#elem: ::bee_storage::persistable::Persistable + Copy + Default));
+                Ok((
+                    quote! {
+                        for element in field.iter() {
+                            <#elem as ::bee_storage::persistable::Persistable>::encode_persistable(
+                                element,
+                                buffer,
+                            );
+                        }
+                    },
+                    quote! {
+                        let mut array = [<#elem as Default>::default(); #len];
+                        let mut slice = slice;
+                        for element in array.iter_mut() {
+                            let (value, length) =
+                                <#elem as ::bee_storage::persistable::Persistable>::decode_persistable_prefix(
+                                    slice,
+                                )?;
+                            *element = value;
+                            slice = &slice[length..];
+                            position += length;
+                        }
+                        array
+                    },
+                ))
+            } else {
+                Err(Error::new(
+                    elem.span(),
+                    "unsupported array element type for `Persistable`",
+                ))
+            }
+        }
+        Type::Paren(paren) => persistable_field(&paren.elem, predicates),
+        Type::Group(group) => persistable_field(&group.elem, predicates),
+        _ => Err(Error::new(ty.span(), "unsupported field type for `Persistable`")),
+    }
+}
+
+fn is_skipped(field: &Field) -> Result<bool, Error> {
+    let mut skipped = false;
+
+    for attr in field.attrs.iter().filter(|attr| attr.path.is_ident("persistable")) {
+        skipped |= parse_attribute(attr)?;
+    }
+
+    Ok(skipped)
+}
+
+// Only `#[persistable(skip)]` is supported for now.
+fn parse_attribute(attr: &Attribute) -> Result<bool, Error> {
+    if let Meta::List(list) = attr.parse_meta()? {
+        if list.nested.len() == 1 {
+            if let NestedMeta::Meta(Meta::Path(path)) = &list.nested[0] {
+                if path.is_ident("skip") {
+                    return Ok(true);
+                }
+            }
+        }
+    }
+
+    Err(Error::new(attr.span(), "expected `#[persistable(skip)]`"))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn expand(input: TokenStream2) -> String {
+        persistable(syn::parse2(input).unwrap()).unwrap().to_string()
+    }
+
+    #[test]
+    fn expand_tuple_struct() {
+        let input = quote! {
+            struct Index(u32);
+        };
+        let expected = quote! {
+            impl ::bee_storage::persistable::Persistable for Index
+            where
+                u32: ::bee_storage::persistable::Persistable
+            {
+                #[allow(unused_variables)]
+                fn encode_persistable(&self, buffer: &mut Vec<u8>) {
+                    let field = &self.0;
+                    <u32 as ::bee_storage::persistable::Persistable>::encode_persistable(field, buffer);
+                }
+
+                #[allow(unused_mut, unused_variables)]
+                fn decode_persistable_prefix(
+                    slice: &[u8],
+                ) -> Result<(Self, usize), ::bee_storage::persistable::DecodeError> {
+                    let mut position = 0;
+                    let field_0 = {
+                        let slice = &slice[position..];
+                        let (field, length) =
+                            <u32 as ::bee_storage::persistable::Persistable>::decode_persistable_prefix(slice)?;
+                        position += length;
+                        field
+                    };
+                    Ok((Self(field_0), position))
+                }
+            }
+        };
+
+        assert_eq!(expand(input), expected.to_string());
+    }
+
+    #[test]
+    fn expand_unit_struct() {
+        let input = quote! {
+            struct Unit;
+        };
+        let expected = quote! {
+            impl ::bee_storage::persistable::Persistable for Unit {
+                #[allow(unused_variables)]
+                fn encode_persistable(&self, buffer: &mut Vec<u8>) {}
+
+                #[allow(unused_mut, unused_variables)]
+                fn decode_persistable_prefix(
+                    slice: &[u8],
+                ) -> Result<(Self, usize), ::bee_storage::persistable::DecodeError> {
+                    let mut position = 0;
+                    Ok((Self, position))
+                }
+            }
+        };
+
+        assert_eq!(expand(input), expected.to_string());
+    }
+
+    #[test]
+    fn expand_named_struct() {
+        let input = quote! {
+            struct Header {
+                indexes: [Index; 2],
+                #[persistable(skip)]
+                cached: Option<u32>,
+                timestamp: u64,
+            }
+        };
+        let expected = quote! {
+            impl ::bee_storage::persistable::Persistable for Header
+            where
+                Index: ::bee_storage::persistable::Persistable + Copy + Default,
+                Option<u32>: Default,
+                u64: ::bee_storage::persistable::Persistable
+            {
+                #[allow(unused_variables)]
+                fn encode_persistable(&self, buffer: &mut Vec<u8>) {
+                    let field = &self.indexes;
+                    for element in field.iter() {
+                        <Index as ::bee_storage::persistable::Persistable>::encode_persistable(
+                            element,
+                            buffer,
+                        );
+                    }
+                    let field = &self.timestamp;
+                    <u64 as ::bee_storage::persistable::Persistable>::encode_persistable(field, buffer);
+                }
+
+                #[allow(unused_mut, unused_variables)]
+                fn decode_persistable_prefix(
+                    slice: &[u8],
+                ) -> Result<(Self, usize), ::bee_storage::persistable::DecodeError> {
+                    let mut position = 0;
+                    let indexes = {
+                        let slice = &slice[position..];
+                        let mut array = [<Index as Default>::default(); 2];
+                        let mut slice = slice;
+                        for element in array.iter_mut() {
+                            let (value, length) =
+                                <Index as ::bee_storage::persistable::Persistable>::decode_persistable_prefix(
+                                    slice,
+                                )?;
+                            *element = value;
+                            slice = &slice[length..];
+                            position += length;
+                        }
+                        array
+                    };
+                    let cached = Default::default();
+                    let timestamp = {
+                        let slice = &slice[position..];
+                        let (field, length) =
+                            <u64 as ::bee_storage::persistable::Persistable>::decode_persistable_prefix(slice)?;
+                        position += length;
+                        field
+                    };
+                    Ok((Self { indexes, cached, timestamp }, position))
+                }
+            }
+        };
+
+        assert_eq!(expand(input), expected.to_string());
+    }
+
+    #[test]
+    fn expand_enum() {
+        let input = quote! {
+            enum Kind {
+                Unknown,
+                Milestone(Index),
+            }
+        };
+        let expected = quote! {
+            impl ::bee_storage::persistable::Persistable for Kind
+            where
+                Index: ::bee_storage::persistable::Persistable
+            {
+                #[allow(unused_variables)]
+                fn encode_persistable(&self, buffer: &mut Vec<u8>) {
+                    match self {
+                        Self::Unknown => buffer.push(0u8),
+                        Self::Milestone(field) => {
+                            buffer.push(1u8);
+                            <Index as ::bee_storage::persistable::Persistable>::encode_persistable(field, buffer);
+                        }
+                    }
+                }
+
+                #[allow(unused_mut, unused_variables)]
+                fn decode_persistable_prefix(
+                    slice: &[u8],
+                ) -> Result<(Self, usize), ::bee_storage::persistable::DecodeError> {
+                    let discriminant = ::bee_storage::persistable::take(slice, 1)?[0];
+                    match discriminant {
+                        0u8 => Ok((Self::Unknown, 1)),
+                        1u8 => {
+                            let mut position = 1;
+                            let field = {
+                                let slice = &slice[position..];
+                                let (field, length) =
+                                    <Index as ::bee_storage::persistable::Persistable>::decode_persistable_prefix(slice)?;
+                                position += length;
+                                field
+                            };
+                            Ok((Self::Milestone(field), position))
+                        }
+                        discriminant => Err(::bee_storage::persistable::DecodeError::InvalidDiscriminant(discriminant)),
+                    }
+                }
+            }
+        };
+
+        assert_eq!(expand(input), expected.to_string());
+    }
+}
@@ -0,0 +1,51 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+use bee_storage::persistable::Persistable;
+
+#[derive(Debug, PartialEq, Persistable)]
+struct Wrapper<T>(T);
+
+#[derive(Debug, PartialEq, Persistable)]
+struct Cached<T, C> {
+    value: T,
+    #[persistable(skip)]
+    cache: C,
+}
+
+#[derive(Debug, PartialEq, Persistable)]
+enum Either<L, R> {
+    Left(L),
+    Right(R),
+    Neither,
+}
+
+fn round_trip<P: Persistable + std::fmt::Debug + PartialEq>(value: P, bytes: &[u8]) {
+    let mut buffer = Vec::new();
+    value.encode_persistable(&mut buffer);
+
+    assert_eq!(buffer, bytes);
+    assert_eq!(P::decode_persistable(bytes).unwrap(), value);
+}
+
+fn main() {
+    round_trip(Wrapper(0x0102_0304u32), &[4, 3, 2, 1]);
+    round_trip(Wrapper(Wrapper(7u8)), &[7]);
+    round_trip(
+        Cached {
+            value: 1u16,
+            cache: 0u64,
+        },
+        &[1, 0],
+    );
+    round_trip(Either::<u8, u16>::Left(1), &[0, 1]);
+    round_trip(Either::<u8, u16>::Right(2), &[1, 2, 0]);
+    round_trip(Either::<u8, u16>::Neither, &[2]);
+}
@@ -0,0 +1,54 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+use bee_storage::persistable::Persistable;
+
+#[derive(Debug, PartialEq, Persistable)]
+struct Unit;
+
+#[derive(Debug, PartialEq, Persistable)]
+struct Empty {}
+
+#[derive(Debug, PartialEq, Persistable)]
+struct Tuple(u8, u16);
+
+// Array elements have to be `Copy` and `Default` to be decoded in place.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Persistable)]
+struct Index(u32);
+
+#[derive(Debug, PartialEq, Persistable)]
+#[allow(unused_parens)]
+struct Fields {
+    parenthesized: (u8),
+    indexes: [Index; 2],
+    bytes: [u8; 3],
+}
+
+fn round_trip<P: Persistable + std::fmt::Debug + PartialEq>(value: P, bytes: &[u8]) {
+    let mut buffer = Vec::new();
+    value.encode_persistable(&mut buffer);
+
+    assert_eq!(buffer, bytes);
+    assert_eq!(P::decode_persistable(bytes).unwrap(), value);
+}
+
+fn main() {
+    round_trip(Unit, &[]);
+    round_trip(Empty {}, &[]);
+    round_trip(Tuple(1, 2), &[1, 2, 0]);
+    round_trip(
+        Fields {
+            parenthesized: 1,
+            indexes: [Index(2), Index(3)],
+            bytes: [4, 5, 6],
+        },
+        &[1, 2, 0, 0, 0, 3, 0, 0, 0, 4, 5, 6],
+    );
+}
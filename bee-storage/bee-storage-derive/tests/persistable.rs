@@ -0,0 +1,146 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use bee_storage::persistable::{DecodeError, Persistable};
+
+use rand::Rng;
+
+#[derive(Debug, PartialEq, Persistable)]
+struct Index(u32);
+
+#[derive(Debug, PartialEq, Persistable)]
+struct Header {
+    index: Index,
+    timestamp: u64,
+}
+
+#[derive(Debug, PartialEq, Persistable)]
+enum Kind {
+    Unknown,
+    Milestone(Index),
+    Value(u64),
+}
+
+#[derive(Debug, PartialEq, Persistable)]
+struct Metadata {
+    header: Header,
+    kind: Kind,
+    hash: [u8; 49],
+    #[persistable(skip)]
+    cached: Option<u32>,
+    pruning_index: u32,
+}
+
+fn encode<P: Persistable>(value: &P) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    value.encode_persistable(&mut buffer);
+    buffer
+}
+
+fn decode<P: Persistable>(slice: &[u8]) -> Result<P, DecodeError> {
+    P::decode_persistable(slice)
+}
+
+fn random_metadata<R: Rng>(rng: &mut R) -> Metadata {
+    let mut hash = [0u8; 49];
+    rng.fill(&mut hash[..]);
+
+    Metadata {
+        header: Header {
+            index: Index(rng.gen()),
+            timestamp: rng.gen(),
+        },
+        kind: match rng.gen_range(0, 3) {
+            0 => Kind::Unknown,
+            1 => Kind::Milestone(Index(rng.gen())),
+            _ => Kind::Value(rng.gen()),
+        },
+        hash,
+        cached: None,
+        pruning_index: rng.gen(),
+    }
+}
+
+#[test]
+fn newtype_is_transparent() {
+    assert_eq!(encode(&Index(0x0403_0201)), vec![1, 2, 3, 4]);
+}
+
+#[test]
+fn fields_are_encoded_in_order() {
+    let header = Header {
+        index: Index(1),
+        timestamp: 2,
+    };
+
+    assert_eq!(encode(&header), vec![1, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0]);
+}
+
+#[test]
+fn variants_are_prefixed_by_discriminant() {
+    assert_eq!(encode(&Kind::Unknown), vec![0]);
+    assert_eq!(encode(&Kind::Milestone(Index(1))), vec![1, 1, 0, 0, 0]);
+    assert_eq!(encode(&Kind::Value(2)), vec![2, 2, 0, 0, 0, 0, 0, 0, 0]);
+}
+
+#[test]
+fn skipped_field_is_not_encoded() {
+    let mut rng = rand::thread_rng();
+    let mut metadata = random_metadata(&mut rng);
+    let bytes = encode(&metadata);
+
+    metadata.cached = Some(rng.gen());
+
+    assert_eq!(encode(&metadata), bytes);
+    assert_eq!(decode::<Metadata>(&bytes).unwrap().cached, None);
+}
+
+#[test]
+fn round_trip() {
+    let mut rng = rand::thread_rng();
+
+    for _ in 0..1000 {
+        let metadata = random_metadata(&mut rng);
+
+        assert_eq!(decode::<Metadata>(&encode(&metadata)).unwrap(), metadata);
+    }
+}
+
+#[test]
+fn truncated_slice() {
+    let bytes = encode(&random_metadata(&mut rand::thread_rng()));
+
+    for length in 0..bytes.len() {
+        match decode::<Metadata>(&bytes[..length]) {
+            Err(DecodeError::UnexpectedEnd { .. }) => (),
+            _ => panic!("decoding a truncated slice should fail"),
+        }
+    }
+}
+
+#[test]
+fn trailing_bytes() {
+    let mut bytes = encode(&Index(1));
+    bytes.push(0);
+
+    match decode::<Index>(&bytes) {
+        Err(DecodeError::TrailingBytes(1)) => (),
+        _ => panic!("decoding a slice with trailing bytes should fail"),
+    }
+}
+
+#[test]
+fn invalid_discriminant() {
+    match decode::<Kind>(&[3]) {
+        Err(DecodeError::InvalidDiscriminant(3)) => (),
+        _ => panic!("decoding an unknown discriminant should fail"),
+    }
+}
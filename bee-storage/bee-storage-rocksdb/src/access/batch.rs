@@ -61,8 +61,8 @@ impl<'a> BatchBuilder<'a, Storage, Hash, TransactionMetadata> for StorageBatch<'
         let hash_to_metadata = self.storage.inner.cf_handle(TRANSACTION_HASH_TO_METADATA).unwrap();
         self.key_buf.clear();
         self.value_buf.clear();
-        hash.encode_persistable::<Self>(&mut self.key_buf);
-        transaction_metadata.encode_persistable::<Self>(&mut self.value_buf);
+        hash.encode_persistable(&mut self.key_buf);
+        transaction_metadata.encode_persistable(&mut self.value_buf);
         self.batch
             .put_cf(&hash_to_metadata, self.key_buf.as_slice(), self.value_buf.as_slice());
         Ok(self)
@@ -70,7 +70,7 @@ impl<'a> BatchBuilder<'a, Storage, Hash, TransactionMetadata> for StorageBatch<'
     fn try_delete(mut self, hash: &Hash) -> Result<Self, (Self, Self::Error)> {
         let hash_to_metadata = self.storage.inner.cf_handle(TRANSACTION_HASH_TO_METADATA).unwrap();
         self.key_buf.clear();
-        hash.encode_persistable::<Self>(&mut self.key_buf);
+        hash.encode_persistable(&mut self.key_buf);
         self.batch.delete_cf(&hash_to_metadata, self.key_buf.as_slice());
         Ok(self)
     }
@@ -82,8 +82,8 @@ impl<'a> BatchBuilder<'a, Storage, MilestoneIndex, LedgerDiff> for StorageBatch<
         let ms_index_to_ledger_diff = self.storage.inner.cf_handle(MILESTONE_INDEX_TO_LEDGER_DIFF).unwrap();
         self.key_buf.clear();
         self.value_buf.clear();
-        ms_index.encode_persistable::<Self>(&mut self.key_buf);
-        ledger_diff.encode_persistable::<Self>(&mut self.value_buf);
+        ms_index.encode_persistable(&mut self.key_buf);
+        ledger_diff.encode_persistable(&mut self.value_buf);
         self.batch.put_cf(
             &ms_index_to_ledger_diff,
             self.key_buf.as_slice(),
@@ -95,7 +95,7 @@ impl<'a> BatchBuilder<'a, Storage, MilestoneIndex, LedgerDiff> for StorageBatch<
     fn try_delete(mut self, ms_index: &MilestoneIndex) -> Result<Self, (Self, Self::Error)> {
         let ms_index_to_ledger_diff = self.storage.inner.cf_handle(MILESTONE_INDEX_TO_LEDGER_DIFF).unwrap();
         self.key_buf.clear();
-        ms_index.encode_persistable::<Self>(&mut self.key_buf);
+        ms_index.encode_persistable(&mut self.key_buf);
         self.batch.delete_cf(&ms_index_to_ledger_diff, self.key_buf.as_slice());
         Ok(self)
     }
@@ -111,8 +111,8 @@ impl<'a> BatchBuilder<'a, Storage, MilestoneIndex, LedgerState> for StorageBatch
         let ms_index_to_ledger_state = self.storage.inner.cf_handle(MILESTONE_INDEX_TO_LEDGER_STATE).unwrap();
         self.key_buf.clear();
         self.value_buf.clear();
-        ms_index.encode_persistable::<Self>(&mut self.key_buf);
-        ledger_state.encode_persistable::<Self>(&mut self.value_buf);
+        ms_index.encode_persistable(&mut self.key_buf);
+        ledger_state.encode_persistable(&mut self.value_buf);
         self.batch.put_cf(
             &ms_index_to_ledger_state,
             self.key_buf.as_slice(),
@@ -124,7 +124,7 @@ impl<'a> BatchBuilder<'a, Storage, MilestoneIndex, LedgerState> for StorageBatch
     fn try_delete(mut self, ms_index: &MilestoneIndex) -> Result<Self, (Self, Self::Error)> {
         let ms_index_to_ledger_state = self.storage.inner.cf_handle(MILESTONE_INDEX_TO_LEDGER_STATE).unwrap();
         self.key_buf.clear();
-        ms_index.encode_persistable::<Self>(&mut self.key_buf);
+        ms_index.encode_persistable(&mut self.key_buf);
         self.batch.delete_cf(&ms_index_to_ledger_state, self.key_buf.as_slice());
         Ok(self)
     }
@@ -140,8 +140,8 @@ impl<'a> BatchBuilder<'a, Storage, Hash, BundledTransaction> for StorageBatch<'a
         let hash_to_tx = self.storage.inner.cf_handle(TRANSACTION_HASH_TO_TRANSACTION).unwrap();
         self.key_buf.clear();
         self.value_buf.clear();
-        hash.encode_persistable::<Self>(&mut self.key_buf);
-        bundled_transaction.encode_persistable::<Self>(&mut self.value_buf);
+        hash.encode_persistable(&mut self.key_buf);
+        bundled_transaction.encode_persistable(&mut self.value_buf);
         self.batch
             .put_cf(&hash_to_tx, self.key_buf.as_slice(), self.value_buf.as_slice());
         Ok(self)
@@ -150,7 +150,7 @@ impl<'a> BatchBuilder<'a, Storage, Hash, BundledTransaction> for StorageBatch<'a
     fn try_delete(mut self, hash: &Hash) -> Result<Self, (Self, Self::Error)> {
         let hash_to_tx = self.storage.inner.cf_handle(TRANSACTION_HASH_TO_TRANSACTION).unwrap();
         self.key_buf.clear();
-        hash.encode_persistable::<Self>(&mut self.key_buf);
+        hash.encode_persistable(&mut self.key_buf);
         self.batch.delete_cf(&hash_to_tx, self.key_buf.as_slice());
         Ok(self)
     }
@@ -162,8 +162,8 @@ impl<'a> BatchBuilder<'a, Storage, Hash, MilestoneIndex> for StorageBatch<'a> {
         let ms_hash_to_ms_index = self.storage.inner.cf_handle(MILESTONE_HASH_TO_INDEX).unwrap();
         self.key_buf.clear();
         self.value_buf.clear();
-        hash.encode_persistable::<Self>(&mut self.key_buf);
-        milestone_index.encode_persistable::<Self>(&mut self.value_buf);
+        hash.encode_persistable(&mut self.key_buf);
+        milestone_index.encode_persistable(&mut self.value_buf);
         self.batch
             .put_cf(&ms_hash_to_ms_index, self.key_buf.as_slice(), self.value_buf.as_slice());
         Ok(self)
@@ -172,7 +172,7 @@ impl<'a> BatchBuilder<'a, Storage, Hash, MilestoneIndex> for StorageBatch<'a> {
     fn try_delete(mut self, hash: &Hash) -> Result<Self, (Self, Self::Error)> {
         let ms_hash_to_ms_index = self.storage.inner.cf_handle(MILESTONE_HASH_TO_INDEX).unwrap();
         self.key_buf.clear();
-        hash.encode_persistable::<Self>(&mut self.key_buf);
+        hash.encode_persistable(&mut self.key_buf);
         self.batch.delete_cf(&ms_hash_to_ms_index, self.key_buf.as_slice());
         Ok(self)
     }
@@ -11,7 +11,10 @@
 
 use bee_crypto::ternary::Hash;
 use bee_ledger::{diff::LedgerDiff, state::LedgerState};
-use bee_protocol::{tangle::TransactionMetadata, MilestoneIndex};
+use bee_protocol::{
+    tangle::{RunningMarker, SolidEntryPoints, TransactionMetadata},
+    MilestoneIndex, PersistedMetrics,
+};
 use bee_storage::{access::Fetch, persistable::Persistable};
 use bee_transaction::bundled::BundledTransaction;
 
@@ -26,10 +29,9 @@ impl Fetch<Hash, TransactionMetadata> for Storage {
     {
         let hash_to_metadata = self.inner.cf_handle(TRANSACTION_HASH_TO_METADATA).unwrap();
         let mut hash_buf: Vec<u8> = Vec::new();
-        hash.encode_persistable::<Self>(&mut hash_buf);
+        hash.encode_persistable(&mut hash_buf);
         if let Some(res) = self.inner.get_cf(&hash_to_metadata, hash_buf.as_slice())? {
-            let transaction_metadata: TransactionMetadata =
-                TransactionMetadata::decode_persistable::<Self>(res.as_slice());
+            let transaction_metadata: TransactionMetadata = TransactionMetadata::decode_persistable(res.as_slice())?;
             Ok(Some(transaction_metadata))
         } else {
             Ok(None)
@@ -46,13 +48,13 @@ impl Fetch<MilestoneIndex, LedgerDiff> for Storage {
     {
         let ms_index_to_ledger_diff = self.inner.cf_handle(MILESTONE_INDEX_TO_LEDGER_DIFF).unwrap();
         let mut index_buf: Vec<u8> = Vec::new();
-        milestone_index.encode_persistable::<Self>(&mut index_buf);
+        milestone_index.encode_persistable(&mut index_buf);
         if let Some(res) = self
             .inner
             .get_cf(&ms_index_to_ledger_diff, index_buf.as_slice())
             .unwrap()
         {
-            let ledger_diff: LedgerDiff = LedgerDiff::decode_persistable::<Self>(res.as_slice());
+            let ledger_diff: LedgerDiff = LedgerDiff::decode_persistable(res.as_slice())?;
             Ok(Some(ledger_diff))
         } else {
             Ok(None)
@@ -69,13 +71,13 @@ impl Fetch<MilestoneIndex, LedgerState> for Storage {
     {
         let ms_index_to_ledger_state = self.inner.cf_handle(MILESTONE_INDEX_TO_LEDGER_STATE).unwrap();
         let mut index_buf: Vec<u8> = Vec::new();
-        milestone_index.encode_persistable::<Self>(&mut index_buf);
+        milestone_index.encode_persistable(&mut index_buf);
         if let Some(res) = self
             .inner
             .get_cf(&ms_index_to_ledger_state, index_buf.as_slice())
             .unwrap()
         {
-            let ledger_state: LedgerState = LedgerState::decode_persistable::<Self>(res.as_slice());
+            let ledger_state: LedgerState = LedgerState::decode_persistable(res.as_slice())?;
             Ok(Some(ledger_state))
         } else {
             Ok(None)
@@ -92,9 +94,9 @@ impl Fetch<Hash, BundledTransaction> for Storage {
     {
         let hash_to_tx = self.inner.cf_handle(TRANSACTION_HASH_TO_TRANSACTION).unwrap();
         let mut hash_buf: Vec<u8> = Vec::new();
-        hash.encode_persistable::<Storage>(&mut hash_buf);
+        hash.encode_persistable(&mut hash_buf);
         if let Some(res) = self.inner.get_cf(&hash_to_tx, hash_buf.as_slice())? {
-            let transaction: BundledTransaction = BundledTransaction::decode_persistable::<Storage>(res.as_slice());
+            let transaction: BundledTransaction = BundledTransaction::decode_persistable(res.as_slice())?;
             Ok(Some(transaction))
         } else {
             Ok(None)
@@ -110,12 +112,65 @@ impl Fetch<Hash, MilestoneIndex> for Storage {
     {
         let ms_hash_to_ms_index = self.inner.cf_handle(MILESTONE_HASH_TO_INDEX).unwrap();
         let mut hash_buf: Vec<u8> = Vec::new();
-        hash.encode_persistable::<Storage>(&mut hash_buf);
+        hash.encode_persistable(&mut hash_buf);
         if let Some(res) = self.inner.get_cf(&ms_hash_to_ms_index, hash_buf.as_slice())? {
-            let ms_index: MilestoneIndex = MilestoneIndex::decode_persistable::<Storage>(res.as_slice());
+            let ms_index: MilestoneIndex = MilestoneIndex::decode_persistable(res.as_slice())?;
             Ok(Some(ms_index))
         } else {
             Ok(None)
         }
     }
 }
+
+#[async_trait::async_trait]
+impl Fetch<(), SolidEntryPoints> for Storage {
+    type Error = OpError;
+    async fn fetch(&self, key: &()) -> Result<Option<SolidEntryPoints>, OpError>
+    where
+        Self: Sized,
+    {
+        let solid_entry_points_cf = self.inner.cf_handle(SOLID_ENTRY_POINTS).unwrap();
+        let mut key_buf: Vec<u8> = Vec::new();
+        key.encode_persistable(&mut key_buf);
+        if let Some(res) = self.inner.get_cf(&solid_entry_points_cf, key_buf.as_slice())? {
+            let solid_entry_points: SolidEntryPoints = SolidEntryPoints::decode_persistable(res.as_slice())?;
+            Ok(Some(solid_entry_points))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Fetch<(), PersistedMetrics> for Storage {
+    type Error = OpError;
+    async fn fetch(&self, _key: &()) -> Result<Option<PersistedMetrics>, OpError>
+    where
+        Self: Sized,
+    {
+        let system = self.inner.cf_handle(SYSTEM).unwrap();
+        if let Some(res) = self.inner.get_cf(&system, SYSTEM_PROTOCOL_METRICS_KEY)? {
+            let metrics: PersistedMetrics = PersistedMetrics::decode_persistable(res.as_slice())?;
+            Ok(Some(metrics))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Fetch<(), RunningMarker> for Storage {
+    type Error = OpError;
+    async fn fetch(&self, _key: &()) -> Result<Option<RunningMarker>, OpError>
+    where
+        Self: Sized,
+    {
+        let system = self.inner.cf_handle(SYSTEM).unwrap();
+        if let Some(res) = self.inner.get_cf(&system, SYSTEM_RUNNING_MARKER_KEY)? {
+            let marker: RunningMarker = RunningMarker::decode_persistable(res.as_slice())?;
+            Ok(Some(marker))
+        } else {
+            Ok(None)
+        }
+    }
+}
@@ -11,7 +11,10 @@
 
 use bee_crypto::ternary::Hash;
 use bee_ledger::{diff::LedgerDiff, state::LedgerState};
-use bee_protocol::{tangle::TransactionMetadata, MilestoneIndex};
+use bee_protocol::{
+    tangle::{RunningMarker, SolidEntryPoints, TransactionMetadata},
+    MilestoneIndex, PersistedMetrics,
+};
 use bee_storage::{access::Insert, persistable::Persistable};
 use bee_transaction::bundled::BundledTransaction;
 
@@ -23,9 +26,9 @@ impl Insert<Hash, TransactionMetadata> for Storage {
     async fn insert(&self, hash: &Hash, tx_metadata: &TransactionMetadata) -> Result<(), Self::Error> {
         let hash_to_metadata = self.inner.cf_handle(TRANSACTION_HASH_TO_METADATA).unwrap();
         let mut hash_buf = Vec::new();
-        hash.encode_persistable::<Self>(&mut hash_buf);
+        hash.encode_persistable(&mut hash_buf);
         let mut metadata_buf = Vec::new();
-        tx_metadata.encode_persistable::<Self>(&mut metadata_buf);
+        tx_metadata.encode_persistable(&mut metadata_buf);
         self.inner
             .put_cf(&hash_to_metadata, hash_buf.as_slice(), metadata_buf.as_slice())?;
         Ok(())
@@ -38,9 +41,9 @@ impl Insert<MilestoneIndex, LedgerDiff> for Storage {
     async fn insert(&self, milestone_index: &MilestoneIndex, ledger_diff: &LedgerDiff) -> Result<(), Self::Error> {
         let ms_index_to_ledger_diff = self.inner.cf_handle(MILESTONE_INDEX_TO_LEDGER_DIFF).unwrap();
         let mut index_buf = Vec::new();
-        milestone_index.encode_persistable::<Self>(&mut index_buf);
+        milestone_index.encode_persistable(&mut index_buf);
         let mut ledger_diff_buf = Vec::new();
-        ledger_diff.encode_persistable::<Self>(&mut ledger_diff_buf);
+        ledger_diff.encode_persistable(&mut ledger_diff_buf);
         self.inner.put_cf(
             &ms_index_to_ledger_diff,
             index_buf.as_slice(),
@@ -56,9 +59,9 @@ impl Insert<MilestoneIndex, LedgerState> for Storage {
     async fn insert(&self, milestone_index: &MilestoneIndex, ledger_state: &LedgerState) -> Result<(), Self::Error> {
         let ms_index_to_ledger_state = self.inner.cf_handle(MILESTONE_INDEX_TO_LEDGER_STATE).unwrap();
         let mut index_buf = Vec::new();
-        milestone_index.encode_persistable::<Self>(&mut index_buf);
+        milestone_index.encode_persistable(&mut index_buf);
         let mut ledger_state_buf = Vec::new();
-        ledger_state.encode_persistable::<Self>(&mut ledger_state_buf);
+        ledger_state.encode_persistable(&mut ledger_state_buf);
         self.inner.put_cf(
             &ms_index_to_ledger_state,
             index_buf.as_slice(),
@@ -74,9 +77,9 @@ impl Insert<Hash, BundledTransaction> for Storage {
     async fn insert(&self, hash: &Hash, bundle_transaction: &BundledTransaction) -> Result<(), Self::Error> {
         let hash_to_tx = self.inner.cf_handle(TRANSACTION_HASH_TO_TRANSACTION).unwrap();
         let mut hash_buf = Vec::new();
-        hash.encode_persistable::<Self>(&mut hash_buf);
+        hash.encode_persistable(&mut hash_buf);
         let mut tx_buf = Vec::new();
-        bundle_transaction.encode_persistable::<Self>(&mut tx_buf);
+        bundle_transaction.encode_persistable(&mut tx_buf);
         self.inner.put_cf(&hash_to_tx, hash_buf.as_slice(), tx_buf.as_slice())?;
         Ok(())
     }
@@ -87,11 +90,55 @@ impl Insert<Hash, MilestoneIndex> for Storage {
     async fn insert(&self, hash: &Hash, milestone_index: &MilestoneIndex) -> Result<(), Self::Error> {
         let ms_hash_to_ms_index = self.inner.cf_handle(MILESTONE_HASH_TO_INDEX).unwrap();
         let mut hash_buf = Vec::new();
-        hash.encode_persistable::<Self>(&mut hash_buf);
+        hash.encode_persistable(&mut hash_buf);
         let mut index_buf = Vec::new();
-        milestone_index.encode_persistable::<Self>(&mut index_buf);
+        milestone_index.encode_persistable(&mut index_buf);
         self.inner
             .put_cf(&ms_hash_to_ms_index, hash_buf.as_slice(), index_buf.as_slice())?;
         Ok(())
     }
 }
+
+#[async_trait::async_trait]
+impl Insert<(), SolidEntryPoints> for Storage {
+    type Error = OpError;
+    async fn insert(&self, key: &(), solid_entry_points: &SolidEntryPoints) -> Result<(), Self::Error> {
+        let solid_entry_points_cf = self.inner.cf_handle(SOLID_ENTRY_POINTS).unwrap();
+        let mut key_buf = Vec::new();
+        key.encode_persistable(&mut key_buf);
+        let mut solid_entry_points_buf = Vec::new();
+        solid_entry_points.encode_persistable(&mut solid_entry_points_buf);
+        self.inner.put_cf(
+            &solid_entry_points_cf,
+            key_buf.as_slice(),
+            solid_entry_points_buf.as_slice(),
+        )?;
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl Insert<(), PersistedMetrics> for Storage {
+    type Error = OpError;
+    async fn insert(&self, _key: &(), metrics: &PersistedMetrics) -> Result<(), Self::Error> {
+        let system = self.inner.cf_handle(SYSTEM).unwrap();
+        let mut metrics_buf = Vec::new();
+        metrics.encode_persistable(&mut metrics_buf);
+        self.inner
+            .put_cf(&system, SYSTEM_PROTOCOL_METRICS_KEY, metrics_buf.as_slice())?;
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl Insert<(), RunningMarker> for Storage {
+    type Error = OpError;
+    async fn insert(&self, _key: &(), marker: &RunningMarker) -> Result<(), Self::Error> {
+        let system = self.inner.cf_handle(SYSTEM).unwrap();
+        let mut marker_buf = Vec::new();
+        marker.encode_persistable(&mut marker_buf);
+        self.inner
+            .put_cf(&system, SYSTEM_RUNNING_MARKER_KEY, marker_buf.as_slice())?;
+        Ok(())
+    }
+}
@@ -0,0 +1,67 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+use bee_crypto::ternary::Hash;
+use bee_ledger::{diff::LedgerDiff, state::LedgerState};
+use bee_protocol::{tangle::TransactionMetadata, MilestoneIndex};
+use bee_storage::{access::Iter, persistable::Persistable};
+use bee_transaction::bundled::BundledTransaction;
+
+use crate::{access::OpError, storage::*};
+
+type PairIterator<'a, K, V> = Box<dyn Iterator<Item = Result<(K, V), OpError>> + 'a>;
+
+fn iter_cf<'a, K, V>(storage: &'a Storage, cf: &str) -> Result<PairIterator<'a, K, V>, OpError>
+where
+    K: Persistable,
+    V: Persistable,
+{
+    let cf = storage.inner.cf_handle(cf).unwrap();
+    Ok(Box::new(storage.inner.full_iterator_cf(cf, IteratorMode::Start).map(
+        |(key, value)| -> Result<(K, V), OpError> {
+            Ok((K::decode_persistable(&key)?, V::decode_persistable(&value)?))
+        },
+    )))
+}
+
+impl Iter<Hash, TransactionMetadata> for Storage {
+    type Error = OpError;
+    fn iter(&self) -> Result<PairIterator<'_, Hash, TransactionMetadata>, Self::Error> {
+        iter_cf(self, TRANSACTION_HASH_TO_METADATA)
+    }
+}
+
+impl Iter<MilestoneIndex, LedgerDiff> for Storage {
+    type Error = OpError;
+    fn iter(&self) -> Result<PairIterator<'_, MilestoneIndex, LedgerDiff>, Self::Error> {
+        iter_cf(self, MILESTONE_INDEX_TO_LEDGER_DIFF)
+    }
+}
+
+impl Iter<MilestoneIndex, LedgerState> for Storage {
+    type Error = OpError;
+    fn iter(&self) -> Result<PairIterator<'_, MilestoneIndex, LedgerState>, Self::Error> {
+        iter_cf(self, MILESTONE_INDEX_TO_LEDGER_STATE)
+    }
+}
+
+impl Iter<Hash, BundledTransaction> for Storage {
+    type Error = OpError;
+    fn iter(&self) -> Result<PairIterator<'_, Hash, BundledTransaction>, Self::Error> {
+        iter_cf(self, TRANSACTION_HASH_TO_TRANSACTION)
+    }
+}
+
+impl Iter<Hash, MilestoneIndex> for Storage {
+    type Error = OpError;
+    fn iter(&self) -> Result<PairIterator<'_, Hash, MilestoneIndex>, Self::Error> {
+        iter_cf(self, MILESTONE_HASH_TO_INDEX)
+    }
+}
@@ -13,14 +13,17 @@ pub mod batch;
 pub mod delete;
 pub mod fetch;
 pub mod insert;
+pub mod iter;
 
-use bee_storage::access::Error;
+use bee_storage::{access::Error, persistable::DecodeError};
+
+use std::fmt;
 
 #[derive(Debug)]
 pub struct OpError {
     is_retryable: bool,
     is_still_valid: bool,
-    error_msg: Option<String>,
+    source: Option<Box<dyn std::error::Error + Send + Sync>>,
 }
 
 impl Error for OpError {
@@ -31,7 +34,21 @@ impl Error for OpError {
         self.is_still_valid
     }
     fn error_msg(&self) -> Option<String> {
-        self.error_msg.clone()
+        self.source.as_ref().map(|source| source.to_string())
+    }
+}
+
+impl fmt::Display for OpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "RocksDB operation failed")
+    }
+}
+
+impl std::error::Error for OpError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.source
+            .as_ref()
+            .map(|source| source.as_ref() as &(dyn std::error::Error + 'static))
     }
 }
 
@@ -40,7 +57,17 @@ impl From<::rocksdb::Error> for OpError {
         Self {
             is_retryable: false,
             is_still_valid: false,
-            error_msg: Some(err.into_string()),
+            source: Some(Box::new(err)),
+        }
+    }
+}
+
+impl From<DecodeError> for OpError {
+    fn from(err: DecodeError) -> Self {
+        Self {
+            is_retryable: false,
+            is_still_valid: false,
+            source: Some(Box::new(err)),
         }
     }
 }
@@ -13,6 +13,7 @@ use rocksdb::DBCompactionStyle;
 use serde::Deserialize;
 
 #[derive(Debug, Clone, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub enum CompactionStyle {
     Level,
     Universal,
@@ -13,6 +13,7 @@ use rocksdb::DBCompressionType;
 use serde::Deserialize;
 
 #[derive(Debug, Clone, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub enum CompressionType {
     None,
     Snappy,
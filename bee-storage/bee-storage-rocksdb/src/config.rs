@@ -12,7 +12,7 @@
 // TODO remove
 #![allow(dead_code)]
 
-use crate::{compaction::CompactionStyle, compression::CompressionType};
+use crate::{compaction::CompactionStyle, compression::CompressionType, recovery::WalRecoveryMode};
 
 use serde::Deserialize;
 
@@ -37,30 +37,56 @@ const DEFAULT_SET_MAX_BACKGROUND_COMPACTIONS: i32 = 0;
 const DEFAULT_SET_MAX_BACKGROUND_FLUSHES: i32 = 0;
 const DEFAULT_SET_DISABLE_AUTO_COMPACTIONS: bool = true;
 const DEFAULT_SET_COMPRESSION_TYPE: CompressionType = CompressionType::None;
+// Recovers a consistent database up to the last intact write, even when the log is corrupted before its tail.
+const DEFAULT_SET_WAL_RECOVERY_MODE: WalRecoveryMode = WalRecoveryMode::PointInTimeRecovery;
 
 #[derive(Default, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct RocksDBConfigBuilder {
+    /// Path of the database directory.
     path: Option<String>,
+    /// Whether the database is created if missing.
     create_if_missing: Option<bool>,
+    /// Whether missing column families are created.
     create_missing_column_families: Option<bool>,
+    /// Whether RocksDB collects statistics.
     enable_statistics: Option<bool>,
+    /// Number of background threads, see `Options::increase_parallelism`.
     increase_parallelism: Option<i32>,
+    /// Block cache size in MB, see `Options::optimize_for_point_lookup`.
     optimize_for_point_lookup: Option<u64>,
+    /// Memtable memory budget in bytes, see `Options::optimize_level_style_compaction`.
     optimize_level_style_compaction: Option<usize>,
+    /// Memtable memory budget in bytes, see `Options::optimize_universal_style_compaction`.
     optimize_universal_style_compaction: Option<usize>,
+    /// See `Options::set_advise_random_on_open`.
     set_advise_random_on_open: Option<bool>,
+    /// See `Options::set_allow_concurrent_memtable_write`.
     set_allow_concurrent_memtable_write: Option<bool>,
+    /// See `Options::set_allow_mmap_reads`.
     set_allow_mmap_reads: Option<bool>,
+    /// See `Options::set_allow_mmap_writes`.
     set_allow_mmap_writes: Option<bool>,
+    /// See `Options::set_atomic_flush`.
     set_atomic_flush: Option<bool>,
+    /// See `Options::set_bytes_per_sync`.
     set_bytes_per_sync: Option<u64>,
+    /// See `Options::set_compaction_readahead_size`.
     set_compaction_readahead_size: Option<usize>,
+    /// See `Options::set_compaction_style`.
     set_compaction_style: Option<CompactionStyle>,
+    /// See `Options::set_max_write_buffer_number`.
     set_max_write_buffer_number: Option<i32>,
+    /// See `Options::set_max_background_compactions`.
     set_max_background_compactions: Option<i32>,
+    /// See `Options::set_max_background_flushes`.
     set_max_background_flushes: Option<i32>,
+    /// See `Options::set_disable_auto_compactions`.
     set_disable_auto_compactions: Option<bool>,
+    /// See `Options::set_compression_type`.
     set_compression_type: Option<CompressionType>,
+    /// See `Options::set_wal_recovery_mode`.
+    set_wal_recovery_mode: Option<WalRecoveryMode>,
 }
 
 impl RocksDBConfigBuilder {
@@ -68,6 +94,16 @@ impl RocksDBConfigBuilder {
         Self::default()
     }
 
+    pub fn path(mut self, path: String) -> Self {
+        self.path.replace(path);
+        self
+    }
+
+    pub fn set_wal_recovery_mode(mut self, wal_recovery_mode: WalRecoveryMode) -> Self {
+        self.set_wal_recovery_mode.replace(wal_recovery_mode);
+        self
+    }
+
     pub fn finish(self) -> RocksDBConfig {
         RocksDBConfig::from(self)
     }
@@ -119,6 +155,7 @@ impl From<RocksDBConfigBuilder> for RocksDBConfig {
                 .set_disable_auto_compactions
                 .unwrap_or(DEFAULT_SET_DISABLE_AUTO_COMPACTIONS),
             set_compression_type: builder.set_compression_type.unwrap_or(DEFAULT_SET_COMPRESSION_TYPE),
+            set_wal_recovery_mode: builder.set_wal_recovery_mode.unwrap_or(DEFAULT_SET_WAL_RECOVERY_MODE),
         }
     }
 }
@@ -146,4 +183,5 @@ pub struct RocksDBConfig {
     pub(crate) set_max_background_flushes: i32,
     pub(crate) set_disable_auto_compactions: bool,
     pub(crate) set_compression_type: CompressionType,
+    pub(crate) set_wal_recovery_mode: WalRecoveryMode,
 }
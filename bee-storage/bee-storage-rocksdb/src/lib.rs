@@ -13,5 +13,6 @@ pub mod access;
 pub mod compaction;
 pub mod compression;
 pub mod config;
-pub mod persistable;
+pub mod migration;
+pub mod recovery;
 pub mod storage;
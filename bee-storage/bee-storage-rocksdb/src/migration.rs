@@ -0,0 +1,140 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+//! Migration of databases written with an older storage format.
+
+use crate::storage::*;
+
+use bee_protocol::{tangle::TransactionMetadata, MilestoneIndex, MILESTONE_INDEX_KEY_LENGTH};
+use bee_storage::persistable::Persistable;
+use rocksdb::Error;
+
+use std::fmt;
+
+/// Version of the storage format, persisted in the system column family under `SYSTEM_VERSION_KEY`.
+///
+/// Databases without a version persisted milestone indexes in little-endian, version 1 persists them in big-endian so
+/// that keys are ordered like the indexes they represent.
+pub const STORAGE_VERSION: u8 = 1;
+
+// Unversioned metadata: flags, milestone index, then the arrival, solidification and confirmation timestamps.
+const LEGACY_TRANSACTION_METADATA_LENGTH: usize = 29;
+// Version of the metadata encoding that has the layout of the unversioned one.
+const TRANSACTION_METADATA_VERSION_1: u8 = 1;
+
+#[derive(Debug)]
+pub enum MigrationError {
+    /// The database was written with a newer storage format.
+    UnsupportedVersion(Vec<u8>),
+    /// A value of the column family doesn't have the layout of the unversioned storage format.
+    InvalidLegacyValue(&'static str),
+    Storage(Error),
+}
+
+impl fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnsupportedVersion(version) => write!(
+                f,
+                "storage version {:?} is not supported, expected [{}]",
+                version, STORAGE_VERSION
+            ),
+            Self::InvalidLegacyValue(cf) => write!(f, "invalid unversioned entry in column family {}", cf),
+            Self::Storage(e) => write!(f, "migrating the storage failed: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for MigrationError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Storage(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<Error> for MigrationError {
+    fn from(e: Error) -> Self {
+        Self::Storage(e)
+    }
+}
+
+fn big_endian_index(little_endian: &[u8]) -> Option<[u8; MILESTONE_INDEX_KEY_LENGTH]> {
+    let mut bytes = [0u8; MILESTONE_INDEX_KEY_LENGTH];
+
+    if little_endian.len() != bytes.len() {
+        return None;
+    }
+    bytes.copy_from_slice(little_endian);
+
+    Some(MilestoneIndex::from(u32::from_le_bytes(bytes)).to_key_bytes())
+}
+
+fn migrate_transaction_metadata(legacy: &[u8]) -> Option<Vec<u8>> {
+    if legacy.len() != LEGACY_TRANSACTION_METADATA_LENGTH {
+        return None;
+    }
+
+    let mut version_1 = vec![TRANSACTION_METADATA_VERSION_1, legacy[0]];
+    version_1.extend_from_slice(&big_endian_index(&legacy[1..1 + MILESTONE_INDEX_KEY_LENGTH])?);
+    version_1.extend_from_slice(&legacy[1 + MILESTONE_INDEX_KEY_LENGTH..]);
+
+    let mut buffer = Vec::new();
+    TransactionMetadata::decode_persistable(&version_1)
+        .ok()?
+        .encode_persistable(&mut buffer);
+
+    Some(buffer)
+}
+
+/// Brings the database to `STORAGE_VERSION`, rewriting the entries of an unversioned database in a single batch.
+pub(crate) fn migrate(db: &DB) -> Result<(), MigrationError> {
+    let system = db.cf_handle(SYSTEM).unwrap();
+
+    match db.get_cf(system, SYSTEM_VERSION_KEY)? {
+        Some(version) if version == [STORAGE_VERSION] => return Ok(()),
+        Some(version) => return Err(MigrationError::UnsupportedVersion(version)),
+        None => (),
+    }
+
+    let mut batch = WriteBatch::default();
+
+    for &name in [MILESTONE_INDEX_TO_LEDGER_DIFF, MILESTONE_INDEX_TO_LEDGER_STATE].iter() {
+        let cf = db.cf_handle(name).unwrap();
+
+        // All the old keys are deleted before the new ones are written, a new key may be the old key of another entry.
+        for (key, _) in db.full_iterator_cf(cf, IteratorMode::Start) {
+            batch.delete_cf(cf, &key);
+        }
+        for (key, value) in db.full_iterator_cf(cf, IteratorMode::Start) {
+            let key = big_endian_index(&key).ok_or(MigrationError::InvalidLegacyValue(name))?;
+            batch.put_cf(cf, &key, &value);
+        }
+    }
+
+    let cf = db.cf_handle(MILESTONE_HASH_TO_INDEX).unwrap();
+    for (key, value) in db.full_iterator_cf(cf, IteratorMode::Start) {
+        let value = big_endian_index(&value).ok_or(MigrationError::InvalidLegacyValue(MILESTONE_HASH_TO_INDEX))?;
+        batch.put_cf(cf, &key, &value);
+    }
+
+    let cf = db.cf_handle(TRANSACTION_HASH_TO_METADATA).unwrap();
+    for (key, value) in db.full_iterator_cf(cf, IteratorMode::Start) {
+        let value = migrate_transaction_metadata(&value)
+            .ok_or(MigrationError::InvalidLegacyValue(TRANSACTION_HASH_TO_METADATA))?;
+        batch.put_cf(cf, &key, &value);
+    }
+
+    batch.put_cf(system, SYSTEM_VERSION_KEY, &[STORAGE_VERSION]);
+
+    Ok(db.write(batch)?)
+}
@@ -0,0 +1,38 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use rocksdb::DBRecoveryMode;
+use serde::Deserialize;
+
+/// How the write-ahead log is replayed when opening a database that was not shut down cleanly.
+#[derive(Debug, Clone, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum WalRecoveryMode {
+    /// Ignores incomplete records at the end of the log, as left by a crash during a write.
+    TolerateCorruptedTailRecords,
+    /// Fails to open the database on any corruption of the log, including an incomplete last record.
+    AbsoluteConsistency,
+    /// Replays the log up to the first corrupted record and discards everything after it.
+    PointInTimeRecovery,
+    /// Skips every corrupted record and replays all the others, possibly leaving the database inconsistent.
+    SkipAnyCorruptedRecords,
+}
+
+impl From<WalRecoveryMode> for DBRecoveryMode {
+    fn from(wal_recovery_mode: WalRecoveryMode) -> Self {
+        match wal_recovery_mode {
+            WalRecoveryMode::TolerateCorruptedTailRecords => DBRecoveryMode::TolerateCorruptedTailRecords,
+            WalRecoveryMode::AbsoluteConsistency => DBRecoveryMode::AbsoluteConsistency,
+            WalRecoveryMode::PointInTimeRecovery => DBRecoveryMode::PointInTime,
+            WalRecoveryMode::SkipAnyCorruptedRecords => DBRecoveryMode::SkipAnyCorruptedRecord,
+        }
+    }
+}
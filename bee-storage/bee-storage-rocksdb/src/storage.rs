@@ -11,7 +11,7 @@
 
 //! A crate that contains foundational building blocks for the IOTA Tangle.
 
-use super::config::*;
+use super::{config::*, migration::migrate};
 use async_trait::async_trait;
 pub use bee_storage::storage::Backend;
 pub use rocksdb::*;
@@ -22,6 +22,13 @@ pub const TRANSACTION_HASH_TO_METADATA: &str = "transaction_hash_to_metadata";
 pub const MILESTONE_HASH_TO_INDEX: &str = "milestone_hash_to_index";
 pub const MILESTONE_INDEX_TO_LEDGER_DIFF: &str = "milestone_hash_to_ledger_diff";
 pub const MILESTONE_INDEX_TO_LEDGER_STATE: &str = "milestone_hash_to_ledger_state";
+pub const SOLID_ENTRY_POINTS: &str = "solid_entry_points";
+pub const SYSTEM: &str = "system";
+
+// Keys of the values stored in the system column family.
+pub const SYSTEM_PROTOCOL_METRICS_KEY: &[u8] = b"protocol_metrics";
+pub const SYSTEM_RUNNING_MARKER_KEY: &[u8] = b"running_marker";
+pub const SYSTEM_VERSION_KEY: &[u8] = b"version";
 
 pub struct Storage {
     pub inner: ::rocksdb::DB,
@@ -38,6 +45,8 @@ impl Storage {
             ColumnFamilyDescriptor::new(MILESTONE_INDEX_TO_LEDGER_DIFF, Options::default());
         let milestone_index_to_ledger_state =
             ColumnFamilyDescriptor::new(MILESTONE_INDEX_TO_LEDGER_STATE, Options::default());
+        let solid_entry_points = ColumnFamilyDescriptor::new(SOLID_ENTRY_POINTS, Options::default());
+        let system = ColumnFamilyDescriptor::new(SYSTEM, Options::default());
 
         let mut opts = Options::default();
 
@@ -61,6 +70,7 @@ impl Storage {
         opts.set_max_write_buffer_number(config.set_max_write_buffer_number);
         opts.set_disable_auto_compactions(config.set_disable_auto_compactions);
         opts.set_compression_type(DBCompressionType::from(config.set_compression_type));
+        opts.set_wal_recovery_mode(DBRecoveryMode::from(config.set_wal_recovery_mode));
 
         let column_familes = vec![
             transaction_hash_to_transaction,
@@ -68,9 +78,13 @@ impl Storage {
             milestone_hash_to_index,
             milestone_index_to_ledger_diff,
             milestone_index_to_ledger_state,
+            solid_entry_points,
+            system,
         ];
         let db = DB::open_cf_descriptors(&opts, config.path, column_familes)?;
 
+        migrate(&db)?;
+
         Ok(db)
     }
 }
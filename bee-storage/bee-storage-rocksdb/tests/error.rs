@@ -0,0 +1,46 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use bee_storage::{access::Error as _, persistable::DecodeError};
+use bee_storage_rocksdb::{
+    access::OpError,
+    storage::{Error as RocksDBError, DB},
+};
+
+use std::{env, error::Error, fs, process};
+
+#[test]
+fn rocksdb_error_source() {
+    // A database can't be created below a regular file.
+    // Suffixed with the process id so that concurrent runs don't share it.
+    let file = env::temp_dir().join(format!("bee_storage_rocksdb_error_source_{}", process::id()));
+    fs::write(&file, b"").unwrap();
+    let rocksdb_error = DB::open_default(file.join("database")).err().unwrap();
+    let _ = fs::remove_file(&file);
+
+    let error = OpError::from(rocksdb_error.clone());
+
+    assert_eq!(
+        error.source().and_then(|source| source.downcast_ref::<RocksDBError>()),
+        Some(&rocksdb_error)
+    );
+    assert_eq!(error.error_msg(), Some(rocksdb_error.into_string()));
+}
+
+#[test]
+fn decode_error_source() {
+    let error = OpError::from(DecodeError::InvalidDiscriminant(42));
+
+    assert!(matches!(
+        error.source().and_then(|source| source.downcast_ref::<DecodeError>()),
+        Some(DecodeError::InvalidDiscriminant(42))
+    ));
+}
@@ -0,0 +1,48 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+use bee_ledger::diff::LedgerDiff;
+use bee_protocol::MilestoneIndex;
+use bee_storage::{
+    access::{Insert, Iter},
+    storage::Backend,
+};
+use bee_storage_rocksdb::{config::RocksDBConfigBuilder, storage::Storage};
+
+use std::collections::HashSet;
+
+#[tokio::test]
+async fn iter_yields_all_records() {
+    let path = std::env::temp_dir().join("bee_storage_rocksdb_iter");
+    let _ = std::fs::remove_dir_all(&path);
+
+    let config = RocksDBConfigBuilder::new()
+        .path(path.to_str().unwrap().to_string())
+        .finish();
+    let storage = Storage::start(config).await.unwrap();
+
+    for index in 0..50 {
+        storage
+            .insert(&MilestoneIndex(index), &LedgerDiff::new())
+            .await
+            .unwrap();
+    }
+
+    let mut indexes = HashSet::new();
+    for pair in Iter::<MilestoneIndex, LedgerDiff>::iter(&storage).unwrap() {
+        let (index, _) = pair.unwrap();
+        assert!(indexes.insert(index), "iteration yielded {:?} twice", index);
+    }
+
+    assert_eq!(indexes, (0..50).map(MilestoneIndex).collect::<HashSet<_>>());
+
+    storage.shutdown().await.unwrap();
+    std::fs::remove_dir_all(&path).unwrap();
+}
@@ -0,0 +1,130 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use bee_protocol::{
+    tangle::{flags::Flags, TransactionMetadata},
+    MilestoneIndex,
+};
+use bee_storage::persistable::Persistable;
+use bee_storage_rocksdb::{
+    config::RocksDBConfigBuilder,
+    migration::STORAGE_VERSION,
+    storage::{
+        Storage, DB, MILESTONE_HASH_TO_INDEX, MILESTONE_INDEX_TO_LEDGER_DIFF, SYSTEM, SYSTEM_VERSION_KEY,
+        TRANSACTION_HASH_TO_METADATA,
+    },
+};
+
+use std::{error::Error, fs, path::Path};
+
+const HASH: &[u8] = &[7; 49];
+
+fn open(path: &Path) -> Result<DB, Box<dyn Error>> {
+    Storage::try_new(
+        RocksDBConfigBuilder::new()
+            .path(path.to_str().unwrap().to_string())
+            .finish(),
+    )
+}
+
+// Creates a database with the entries an unversioned storage wrote, milestone indexes being little-endian.
+fn unversioned_database(path: &Path) {
+    let _ = fs::remove_dir_all(path);
+
+    let db = open(path).unwrap();
+
+    db.delete_cf(db.cf_handle(SYSTEM).unwrap(), SYSTEM_VERSION_KEY).unwrap();
+
+    let ledger_diff = db.cf_handle(MILESTONE_INDEX_TO_LEDGER_DIFF).unwrap();
+    // 0x0403_0201 and 0x0102_0304 swap their keys.
+    db.put_cf(ledger_diff, [1, 2, 3, 4], [1]).unwrap();
+    db.put_cf(ledger_diff, [4, 3, 2, 1], [2]).unwrap();
+    db.put_cf(ledger_diff, [0xa3, 0xce, 0x14, 0], [3]).unwrap();
+
+    db.put_cf(
+        db.cf_handle(MILESTONE_HASH_TO_INDEX).unwrap(),
+        HASH,
+        [0xa3, 0xce, 0x14, 0],
+    )
+    .unwrap();
+
+    db.put_cf(
+        db.cf_handle(TRANSACTION_HASH_TO_METADATA).unwrap(),
+        HASH,
+        [
+            3, 0xa3, 0xce, 0x14, 0, 1, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 3, 0, 0, 0, 0, 0, 0, 0,
+        ],
+    )
+    .unwrap();
+
+    db.flush().unwrap();
+}
+
+fn index(index: u32) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    MilestoneIndex(index).encode_persistable(&mut buffer);
+    buffer
+}
+
+#[test]
+fn unversioned_database_migrated() {
+    let path = std::env::temp_dir().join("bee_storage_rocksdb_unversioned");
+
+    unversioned_database(&path);
+
+    // Opening twice checks that a migrated database is left untouched.
+    for _ in 0..2 {
+        let db = open(&path).unwrap();
+
+        assert_eq!(
+            db.get_cf(db.cf_handle(SYSTEM).unwrap(), SYSTEM_VERSION_KEY).unwrap(),
+            Some(vec![STORAGE_VERSION])
+        );
+
+        let ledger_diff = db.cf_handle(MILESTONE_INDEX_TO_LEDGER_DIFF).unwrap();
+        assert_eq!(db.get_cf(ledger_diff, index(0x0403_0201)).unwrap(), Some(vec![1]));
+        assert_eq!(db.get_cf(ledger_diff, index(0x0102_0304)).unwrap(), Some(vec![2]));
+        assert_eq!(db.get_cf(ledger_diff, index(1_363_619)).unwrap(), Some(vec![3]));
+        assert_eq!(db.get_cf(ledger_diff, [0xa3, 0xce, 0x14, 0]).unwrap(), None);
+
+        let ms_index = db.get_cf(db.cf_handle(MILESTONE_HASH_TO_INDEX).unwrap(), HASH).unwrap();
+        assert_eq!(
+            MilestoneIndex::decode_persistable(&ms_index.unwrap()).unwrap(),
+            MilestoneIndex(1_363_619)
+        );
+
+        let metadata = db
+            .get_cf(db.cf_handle(TRANSACTION_HASH_TO_METADATA).unwrap(), HASH)
+            .unwrap();
+        let metadata = TransactionMetadata::decode_persistable(&metadata.unwrap()).unwrap();
+        assert_eq!(*metadata.flags(), Flags::SOLID | Flags::TAIL);
+        assert_eq!(metadata.milestone_index(), MilestoneIndex(1_363_619));
+        assert_eq!(metadata.arrival_timestamp(), 1);
+        assert_eq!(metadata.confirmation_timestamp(), 3);
+    }
+
+    let _ = fs::remove_dir_all(&path);
+}
+
+#[test]
+fn newer_version_rejected() {
+    let path = std::env::temp_dir().join("bee_storage_rocksdb_newer_version");
+    let _ = fs::remove_dir_all(&path);
+
+    let db = open(&path).unwrap();
+    db.put_cf(db.cf_handle(SYSTEM).unwrap(), SYSTEM_VERSION_KEY, [STORAGE_VERSION + 1])
+        .unwrap();
+    drop(db);
+
+    assert!(open(&path).is_err());
+
+    let _ = fs::remove_dir_all(&path);
+}
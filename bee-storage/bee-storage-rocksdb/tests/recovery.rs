@@ -0,0 +1,87 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use bee_storage_rocksdb::{
+    config::RocksDBConfigBuilder,
+    recovery::WalRecoveryMode,
+    storage::{Storage, DB, SYSTEM},
+};
+
+use std::{error::Error, fs, path::Path};
+
+const RECORDS: u8 = 10;
+const RECORD_SIZE: usize = 1000;
+
+fn open(path: &Path, wal_recovery_mode: WalRecoveryMode) -> Result<DB, Box<dyn Error>> {
+    Storage::try_new(
+        RocksDBConfigBuilder::new()
+            .path(path.to_str().unwrap().to_string())
+            .set_wal_recovery_mode(wal_recovery_mode)
+            .finish(),
+    )
+}
+
+// Creates a database whose records only live in the write-ahead log and cuts its last record short, as a crash in the
+// middle of a write would.
+fn torn_database(path: &Path) {
+    let _ = fs::remove_dir_all(path);
+
+    let db = open(path, WalRecoveryMode::TolerateCorruptedTailRecords).unwrap();
+    let cf = db.cf_handle(SYSTEM).unwrap();
+    for i in 0..RECORDS {
+        db.put_cf(cf, [i], [i; RECORD_SIZE]).unwrap();
+    }
+    // Dropping without flushing leaves the memtable unpersisted.
+    drop(db);
+
+    let wal = fs::read_dir(path)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().map_or(false, |extension| extension == "log"))
+        .max_by_key(|path| fs::metadata(path).unwrap().len())
+        .unwrap();
+    let file = fs::OpenOptions::new().write(true).open(&wal).unwrap();
+    let len = file.metadata().unwrap().len();
+    file.set_len(len - RECORD_SIZE as u64 / 10).unwrap();
+}
+
+// Only `AbsoluteConsistency` refuses to open a database whose log ends with a torn record; the other modes drop that
+// record and replay the previous ones.
+#[test]
+fn torn_wal_recovery() {
+    let path = std::env::temp_dir().join("bee_storage_rocksdb_torn_wal");
+
+    let modes = vec![
+        (WalRecoveryMode::TolerateCorruptedTailRecords, true),
+        (WalRecoveryMode::AbsoluteConsistency, false),
+        (WalRecoveryMode::PointInTimeRecovery, true),
+        (WalRecoveryMode::SkipAnyCorruptedRecords, true),
+    ];
+
+    for (mode, recovers) in modes {
+        torn_database(&path);
+
+        match open(&path, mode.clone()) {
+            Ok(db) => {
+                assert!(recovers, "{:?} opened a database with a torn log", mode);
+
+                let cf = db.cf_handle(SYSTEM).unwrap();
+                for i in 0..RECORDS - 1 {
+                    assert_eq!(db.get_cf(cf, [i]).unwrap(), Some(vec![i; RECORD_SIZE]));
+                }
+                assert_eq!(db.get_cf(cf, [RECORDS - 1]).unwrap(), None);
+            }
+            Err(e) => assert!(!recovers, "{:?} failed to open a database with a torn log: {}", mode, e),
+        }
+    }
+
+    fs::remove_dir_all(&path).unwrap();
+}
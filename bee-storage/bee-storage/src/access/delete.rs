@@ -11,7 +11,7 @@
 
 #[async_trait::async_trait]
 pub trait Delete<K, V> {
-    type Error;
+    type Error: std::fmt::Debug;
     async fn delete(&self, key: &K) -> Result<(), Self::Error>
     where
         Self: Sized;
@@ -11,7 +11,7 @@
 
 #[async_trait::async_trait]
 pub trait Fetch<K, V> {
-    type Error;
+    type Error: std::fmt::Debug;
     async fn fetch(&self, key: &K) -> Result<Option<V>, Self::Error>
     where
         Self: Sized;
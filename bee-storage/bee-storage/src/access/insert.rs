@@ -11,7 +11,7 @@
 
 #[async_trait::async_trait]
 pub trait Insert<K, V> {
-    type Error;
+    type Error: std::fmt::Debug;
     async fn insert(&self, key: &K, value: &V) -> Result<(), Self::Error>
     where
         Self: Sized;
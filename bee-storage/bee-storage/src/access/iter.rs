@@ -0,0 +1,18 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+/// Full scan of the key-value pairs stored for the `K` to `V` mapping, in no particular order.
+pub trait Iter<K, V> {
+    type Error: std::fmt::Debug;
+    #[allow(clippy::type_complexity)]
+    fn iter(&self) -> Result<Box<dyn Iterator<Item = Result<(K, V), Self::Error>> + '_>, Self::Error>
+    where
+        Self: Sized;
+}
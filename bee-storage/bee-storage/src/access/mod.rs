@@ -15,11 +15,13 @@ pub mod batch;
 pub mod delete;
 pub mod fetch;
 pub mod insert;
+pub mod iter;
 
 pub use batch::{ApplyBatch, Batch, BatchBuilder};
 pub use delete::Delete;
 pub use fetch::Fetch;
 pub use insert::Insert;
+pub use iter::Iter;
 
 pub trait Error: std::fmt::Debug {
     fn is_retryable(&self) -> bool;
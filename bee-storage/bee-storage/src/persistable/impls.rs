@@ -0,0 +1,136 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use crate::persistable::{take, DecodeError, Persistable};
+
+use bee_crypto::ternary::{Hash, HASH_LENGTH};
+use bee_ternary::{T1B1Buf, T5B1Buf, Trits, T5B1};
+
+use std::{collections::HashMap, convert::TryInto};
+
+const LE_0_BYTES_LEN: [u8; 4] = [0, 0, 0, 0];
+
+// Auto trait implementations;
+macro_rules! impl_persistable_le {
+    ($($ty:ty),*) => {
+        $(
+            impl Persistable for $ty {
+                fn encode_persistable(&self, buffer: &mut Vec<u8>) {
+                    buffer.extend(&<$ty>::to_le_bytes(*self));
+                }
+                fn decode_persistable_prefix(slice: &[u8]) -> Result<(Self, usize), DecodeError> {
+                    let length = std::mem::size_of::<$ty>();
+                    Ok((<$ty>::from_le_bytes(take(slice, length)?.try_into().unwrap()), length))
+                }
+            }
+        )*
+    };
+}
+
+impl_persistable_le!(u8, u16, u32, u64, i32, i64);
+
+// Key of the column families holding a single value.
+impl Persistable for () {
+    fn encode_persistable(&self, _buffer: &mut Vec<u8>) {}
+    fn decode_persistable_prefix(_slice: &[u8]) -> Result<(Self, usize), DecodeError> {
+        Ok(((), 0))
+    }
+}
+
+impl Persistable for Vec<u8> {
+    fn encode_persistable(&self, buffer: &mut Vec<u8>) {
+        // extend the length of the vector then its bytes
+        buffer.extend(&u32::to_le_bytes(self.len() as u32));
+        buffer.extend(self);
+    }
+    fn decode_persistable_prefix(slice: &[u8]) -> Result<(Self, usize), DecodeError> {
+        let (length, position) = u32::decode_persistable_prefix(slice)?;
+        let bytes = take(&slice[position..], length as usize)?;
+        Ok((bytes.to_vec(), position + bytes.len()))
+    }
+}
+
+// Decodes a length-prefixed key or value of a `HashMap`, returns it along with the position following it.
+fn decode_map_item<T: Persistable>(slice: &[u8], start: usize) -> Result<(T, usize), DecodeError> {
+    let length = i32::decode_persistable_prefix(&slice[start..])?.0 as usize;
+    let item_start = start + 4;
+    let item = T::decode_persistable(take(&slice[item_start..], length)?)?;
+    Ok((item, item_start + length))
+}
+
+impl<K, V, S: ::std::hash::BuildHasher + Default> Persistable for HashMap<K, V, S>
+where
+    K: Eq + std::hash::Hash + Persistable,
+    V: Persistable,
+{
+    fn encode_persistable(&self, buffer: &mut Vec<u8>) {
+        // extend key_value pairs count of the hashmap into the buffer
+        buffer.extend(&i32::to_le_bytes(self.len() as i32));
+        let mut current_k_or_v_position;
+        let mut k_or_v_byte_size;
+        // iter on hashmap pairs;
+        for (k, v) in self {
+            // extend k-0-length;
+            buffer.extend(&LE_0_BYTES_LEN);
+            current_k_or_v_position = buffer.len();
+            // encode key into the buffer
+            k.encode_persistable(buffer);
+            // calculate the actual byte_size of the key;
+            k_or_v_byte_size = buffer.len() - current_k_or_v_position;
+            // change the k-0-length to reflect the actual key length;
+            buffer[(current_k_or_v_position - 4)..current_k_or_v_position]
+                .copy_from_slice(&i32::to_le_bytes(k_or_v_byte_size as i32));
+            // extend v-0-length;
+            buffer.extend(&LE_0_BYTES_LEN);
+            current_k_or_v_position = buffer.len();
+            // encode value into the buffer
+            v.encode_persistable(buffer);
+            // calculate the actual byte_size of the value;
+            k_or_v_byte_size = buffer.len() - current_k_or_v_position;
+            // change the k-0-length to reflect the actual value length;
+            buffer[(current_k_or_v_position - 4)..current_k_or_v_position]
+                .copy_from_slice(&i32::to_le_bytes(k_or_v_byte_size as i32));
+        }
+    }
+
+    fn decode_persistable_prefix(slice: &[u8]) -> Result<(Self, usize), DecodeError> {
+        let map_len = i32::decode_persistable_prefix(slice)?.0 as usize;
+        let mut map: HashMap<K, V, S> = HashMap::default();
+        let mut pair_start = 4;
+        for _ in 0..map_len {
+            let (k, value_start) = decode_map_item(slice, pair_start)?;
+            let (v, next_pair_start) = decode_map_item(slice, value_start)?;
+            pair_start = next_pair_start;
+            // insert key,value
+            map.insert(k, v);
+        }
+        Ok((map, pair_start))
+    }
+}
+
+// `Hash` is a foreign type so it can't derive `Persistable`, it is encoded as its 49 T5B1 bytes.
+impl Persistable for Hash {
+    fn encode_persistable(&self, buffer: &mut Vec<u8>) {
+        buffer.extend(
+            self.as_trits()
+                .encode::<T5B1Buf>()
+                .as_i8_slice()
+                .iter()
+                .map(|b| *b as u8),
+        );
+    }
+    fn decode_persistable_prefix(slice: &[u8]) -> Result<(Self, usize), DecodeError> {
+        let bytes = take(slice, 49)?.iter().map(|b| *b as i8).collect::<Vec<i8>>();
+        let trits = Trits::<T5B1>::try_from_raw(&bytes, HASH_LENGTH).map_err(|_| DecodeError::InvalidValue)?;
+        let hash = Hash::try_from_inner(trits.encode::<T1B1Buf>()).map_err(|_| DecodeError::InvalidValue)?;
+        Ok((hash, 49))
+    }
+}
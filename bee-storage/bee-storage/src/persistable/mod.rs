@@ -9,11 +9,69 @@
 // an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and limitations under the License.
 
-use crate::storage::Backend;
+mod impls;
 
-pub trait Persistable<S: Backend>: Sized {
+pub use bee_storage_derive::Persistable;
+
+use std::fmt;
+
+/// Errors occurring when decoding a `Persistable` from bytes.
+#[derive(Debug)]
+pub enum DecodeError {
+    /// The slice ended before the value was fully decoded.
+    UnexpectedEnd { expected: usize, remaining: usize },
+    /// The slice contained bytes after the decoded value.
+    TrailingBytes(usize),
+    /// The discriminant byte doesn't match any variant of the enum.
+    InvalidDiscriminant(u8),
+    /// The bytes don't represent a valid value of the type.
+    InvalidValue,
+    /// The version byte doesn't match any known version of the encoding.
+    UnsupportedVersion(u8),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnexpectedEnd { expected, remaining } => write!(
+                f,
+                "unexpected end of slice: expected {} bytes, {} remaining",
+                expected, remaining
+            ),
+            Self::TrailingBytes(len) => write!(f, "{} trailing bytes after the decoded value", len),
+            Self::InvalidDiscriminant(discriminant) => write!(f, "invalid discriminant {}", discriminant),
+            Self::InvalidValue => write!(f, "invalid value"),
+            Self::UnsupportedVersion(version) => write!(f, "unsupported version {}", version),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// Returns the first `len` bytes of `slice` or an `UnexpectedEnd` error if there are not enough of them.
+pub fn take(slice: &[u8], len: usize) -> Result<&[u8], DecodeError> {
+    slice.get(..len).ok_or(DecodeError::UnexpectedEnd {
+        expected: len,
+        remaining: slice.len(),
+    })
+}
+
+/// A type with a canonical byte encoding, shared by all the storage backends.
+///
+/// The encoding of a type is part of the storage format: changing it requires a migration of the existing databases.
+pub trait Persistable: Sized {
     /// This encode method will extend the provided buffer and return ();
-    fn encode_persistable<B>(&self, buffer: &mut Vec<u8>);
-    /// Decode `slice` and return Self
-    fn decode_persistable<B>(slice: &[u8]) -> Self;
+    fn encode_persistable(&self, buffer: &mut Vec<u8>);
+    /// Decode the start of `slice` and return Self along with the number of bytes that were read
+    fn decode_persistable_prefix(slice: &[u8]) -> Result<(Self, usize), DecodeError>;
+    /// Decode the whole `slice` and return Self
+    fn decode_persistable(slice: &[u8]) -> Result<Self, DecodeError> {
+        let (value, length) = Self::decode_persistable_prefix(slice)?;
+
+        if length != slice.len() {
+            return Err(DecodeError::TrailingBytes(slice.len() - length));
+        }
+
+        Ok(value)
+    }
 }
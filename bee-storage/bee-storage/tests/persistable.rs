@@ -0,0 +1,89 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use bee_crypto::ternary::Hash;
+use bee_storage::persistable::{DecodeError, Persistable};
+use bee_ternary::{T1B1Buf, TryteBuf};
+
+use std::collections::HashMap;
+
+// These are the canonical encodings shared by all the backends, changing them breaks existing databases.
+
+fn encode<P: Persistable>(value: &P) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    value.encode_persistable(&mut buffer);
+    buffer
+}
+
+#[test]
+fn integers_golden_bytes() {
+    assert_eq!(encode(&0x01u8), [1]);
+    assert_eq!(encode(&0x0102u16), [2, 1]);
+    assert_eq!(encode(&0x0102_0304u32), [4, 3, 2, 1]);
+    assert_eq!(encode(&0x0102_0304_0506_0708u64), [8, 7, 6, 5, 4, 3, 2, 1]);
+    assert_eq!(encode(&-2i32), [254, 255, 255, 255]);
+    assert_eq!(encode(&-2i64), [254, 255, 255, 255, 255, 255, 255, 255]);
+
+    assert_eq!(u32::decode_persistable(&[4, 3, 2, 1]).unwrap(), 0x0102_0304);
+    assert_eq!(
+        i64::decode_persistable(&[254, 255, 255, 255, 255, 255, 255, 255]).unwrap(),
+        -2
+    );
+}
+
+#[test]
+fn unit_golden_bytes() {
+    assert!(encode(&()).is_empty());
+    assert!(<()>::decode_persistable(&[]).is_ok());
+}
+
+#[test]
+fn bytes_golden_bytes() {
+    const BYTES: [u8; 7] = [3, 0, 0, 0, 7, 8, 9];
+
+    assert_eq!(encode(&vec![7u8, 8, 9]), BYTES);
+    assert_eq!(Vec::<u8>::decode_persistable(&BYTES).unwrap(), vec![7, 8, 9]);
+}
+
+#[test]
+fn map_golden_bytes() {
+    // Entries count, then each key and value prefixed by its length.
+    const BYTES: [u8; 24] = [1, 0, 0, 0, 4, 0, 0, 0, 1, 0, 0, 0, 8, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0];
+    let mut map = HashMap::new();
+    map.insert(1u32, 2u64);
+
+    assert_eq!(encode(&map), BYTES);
+    assert_eq!(HashMap::<u32, u64>::decode_persistable(&BYTES).unwrap(), map);
+}
+
+#[test]
+fn hash_golden_bytes() {
+    let hash = Hash::from_inner_unchecked(
+        TryteBuf::try_from_str(&format!("{:9<81}", "AZ"))
+            .unwrap()
+            .as_trits()
+            .encode::<T1B1Buf>(),
+    );
+    let mut bytes = [0u8; 49];
+    // The T5B1 encoding of the trits 1, 0, 0, -1, 0 is 1 - 27.
+    bytes[0] = 230;
+
+    assert_eq!(encode(&hash), &bytes[..]);
+    assert_eq!(Hash::decode_persistable(&bytes).unwrap(), hash);
+}
+
+#[test]
+fn hash_truncated() {
+    match Hash::decode_persistable(&[0; 48]) {
+        Err(DecodeError::UnexpectedEnd { .. }) => (),
+        _ => panic!("decoding a truncated hash should fail"),
+    }
+}
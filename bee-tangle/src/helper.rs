@@ -16,16 +16,17 @@ use crate::{
 };
 
 use bee_crypto::ternary::Hash;
-use bee_transaction::bundled::IncomingBundleBuilder;
+use bee_transaction::bundled::{BundledTransaction, IncomingBundleBuilder};
 
-pub fn load_bundle_builder<Metadata, H: Hooks<Metadata>>(
+/// Loads the transactions of the bundle whose tail is `hash`, ordered from tail to head.
+pub fn load_bundle_transactions<Metadata, H: Hooks<Metadata>>(
     tangle: &Tangle<Metadata, H>,
     hash: &Hash,
-) -> Option<IncomingBundleBuilder>
+) -> Option<Vec<BundledTransaction>>
 where
     Metadata: Clone + Copy,
 {
-    let mut bundle_builder = IncomingBundleBuilder::default();
+    let mut transactions = Vec::new();
     let mut done = false;
 
     visit_parents_follow_trunk(
@@ -41,12 +42,28 @@ where
             true
         },
         |_, transaction, _| {
-            bundle_builder.push((*(*transaction)).clone());
+            transactions.push((*(*transaction)).clone());
         },
     );
 
-    match bundle_builder.len() {
+    match transactions.len() {
         0 => None,
-        _ => Some(bundle_builder),
+        _ => Some(transactions),
+    }
+}
+
+pub fn load_bundle_builder<Metadata, H: Hooks<Metadata>>(
+    tangle: &Tangle<Metadata, H>,
+    hash: &Hash,
+) -> Option<IncomingBundleBuilder>
+where
+    Metadata: Clone + Copy,
+{
+    let mut bundle_builder = IncomingBundleBuilder::default();
+
+    for transaction in load_bundle_transactions(tangle, hash)? {
+        bundle_builder.push(transaction);
     }
+
+    Some(bundle_builder)
 }
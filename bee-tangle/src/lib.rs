@@ -19,20 +19,58 @@ pub mod traversal;
 mod tangle;
 mod vertex;
 
-pub use tangle::{Hooks, Tangle};
+pub use tangle::{Hooks, InsertionOutcome, Tangle};
 
+use bee_crypto::ternary::Hash;
 use bee_transaction::bundled::BundledTransaction as Transaction;
 
-use std::{ops::Deref, sync::Arc};
+use std::{
+    hash::{Hash as StdHash, Hasher},
+    ops::Deref,
+    sync::Arc,
+};
+
+pub(crate) struct HashedTransaction {
+    hash: Hash,
+    transaction: Transaction,
+}
 
 /// A thread-safe reference to a `bee_transaction:BundledTransaction`.
+///
+/// Cloning a reference only clones an `Arc`. References are compared and hashed by the hash of the transaction they
+/// point to, so they can be kept in sets without carrying that hash alongside.
 #[derive(Clone)]
-pub struct TransactionRef(pub(crate) Arc<Transaction>);
+pub struct TransactionRef(pub(crate) Arc<HashedTransaction>);
+
+impl TransactionRef {
+    pub(crate) fn new(hash: Hash, transaction: Transaction) -> Self {
+        Self(Arc::new(HashedTransaction { hash, transaction }))
+    }
+
+    /// Returns the hash of the referenced transaction, as it was inserted in the tangle.
+    pub fn hash(&self) -> &Hash {
+        &self.0.hash
+    }
+}
 
 impl Deref for TransactionRef {
     type Target = Transaction;
 
     fn deref(&self) -> &Self::Target {
-        &*self.0
+        &self.0.transaction
+    }
+}
+
+impl PartialEq for TransactionRef {
+    fn eq(&self, other: &Self) -> bool {
+        self.hash() == other.hash()
+    }
+}
+
+impl Eq for TransactionRef {}
+
+impl StdHash for TransactionRef {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash.hash(state)
     }
 }
@@ -27,10 +27,18 @@ use std::{
         atomic::{AtomicU64, Ordering},
         RwLock,
     },
+    time::{SystemTime, UNIX_EPOCH},
 };
 
 const CACHE_LEN: usize = 1_000_000;
 
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Clock may have gone backwards")
+        .as_millis() as u64
+}
+
 /// A trait used to provide hooks for a tangle. The tangle acts as an in-memory cache and will use hooks to extend its
 /// effective volume. When an entry doesn't exist in the tangle cache and needs fetching, or when an entry gets
 /// inserted, the tangle will call out to the hooks in order to fulfil these actions.
@@ -43,6 +51,8 @@ pub trait Hooks<T> {
     async fn get(&self, hash: &Hash) -> Result<(Tx, T), Self::Error>;
     /// Insert a transaction into some external storage medium.
     async fn insert(&self, hash: Hash, tx: Tx, metadata: T) -> Result<(), Self::Error>;
+    /// Update the metadata of a transaction in some external storage medium.
+    async fn update(&self, hash: &Hash, metadata: T) -> Result<(), Self::Error>;
 }
 
 /// Phoney default hooks that do nothing.
@@ -65,6 +75,48 @@ impl<T: Send + Sync> Hooks<T> for NullHooks<T> {
     async fn insert(&self, _hash: Hash, _tx: Tx, _metadata: T) -> Result<(), Self::Error> {
         Ok(())
     }
+
+    async fn update(&self, _hash: &Hash, _metadata: T) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// The outcome of the insertion of a transaction in the Tangle.
+pub enum InsertionOutcome {
+    /// The transaction was new, with a thread-safe reference to it.
+    Inserted(TxRef),
+    /// The transaction was already present, its vertex was kept.
+    AlreadyPresent,
+}
+
+// Outcome of the insertion of a transaction in the memory of the Tangle, before it reaches the hooks.
+enum InnerOutcome<T> {
+    Inserted(TxRef),
+    // The transaction was already present and the update changed its metadata to this one.
+    Updated(T),
+    Unchanged,
+}
+
+// Applies `update` to the metadata of an existing vertex.
+fn update_existing<T: Clone, Update>(metadata: &mut T, update: Update) -> InnerOutcome<T>
+where
+    Update: FnOnce(&mut T) -> bool,
+{
+    if update(metadata) {
+        InnerOutcome::Updated(metadata.clone())
+    } else {
+        InnerOutcome::Unchanged
+    }
+}
+
+impl InsertionOutcome {
+    /// Returns the reference to the inserted transaction, `None` if it was already present.
+    pub fn inserted(self) -> Option<TxRef> {
+        match self {
+            Self::Inserted(tx) => Some(tx),
+            Self::AlreadyPresent => None,
+        }
+    }
 }
 
 /// A foundational, thread-safe graph datastructure to represent the IOTA Tangle.
@@ -75,9 +127,12 @@ where
     pub(crate) vertices: DashMap<Hash, Vertex<T>>,
     pub(crate) children: DashMap<Hash, HashSet<Hash>>,
     pub(crate) tips: DashSet<Hash>,
+    // Parents that are referenced by children but not known, with the time in milliseconds their first link was made.
+    pub(crate) orphan_links: DashMap<Hash, u64>,
 
     pub(crate) cache_counter: AtomicU64,
     pub(crate) cache_queue: RwLock<LruCache<Hash, u64>>,
+    pub(crate) cache_cap: usize,
 
     pub(crate) hooks: H,
 }
@@ -102,9 +157,11 @@ where
             vertices: DashMap::new(),
             children: DashMap::new(),
             tips: DashSet::new(),
+            orphan_links: DashMap::new(),
 
             cache_counter: AtomicU64::new(0),
             cache_queue: RwLock::new(LruCache::new(CACHE_LEN + 1)),
+            cache_cap: CACHE_LEN,
 
             hooks,
         }
@@ -114,17 +171,34 @@ where
     pub fn with_capacity(self, cap: usize) -> Self {
         Self {
             cache_queue: RwLock::new(LruCache::new(cap + 1)),
+            cache_cap: cap,
             ..self
         }
     }
 
-    fn insert_inner(&self, hash: Hash, transaction: Tx, metadata: T) -> Option<TxRef> {
+    fn insert_inner<Update>(&self, hash: Hash, transaction: Tx, metadata: T, update: Update) -> InnerOutcome<T>
+    where
+        Update: FnOnce(&mut T) -> bool,
+    {
+        // Checked before taking the entry since the parents may live in the same shard.
+        let unknown_parents = [*transaction.trunk(), *transaction.branch()]
+            .iter()
+            .filter(|parent| !self.vertices.contains_key(parent))
+            .copied()
+            .collect::<Vec<_>>();
+
         let r = match self.vertices.entry(hash) {
-            Entry::Occupied(_) => None,
+            Entry::Occupied(mut entry) => update_existing(entry.get_mut().metadata_mut(), update),
             Entry::Vacant(entry) => {
                 self.add_child(*transaction.trunk(), hash);
                 self.add_child(*transaction.branch(), hash);
 
+                for parent in unknown_parents {
+                    self.orphan_links.entry(parent).or_insert_with(now_millis);
+                }
+                // The links made by children that arrived first are now backed by this vertex.
+                self.orphan_links.remove(&hash);
+
                 self.tips.remove(transaction.trunk());
                 self.tips.remove(transaction.branch());
 
@@ -136,14 +210,14 @@ where
                     self.tips.remove(&hash);
                 }
 
-                let vtx = Vertex::new(transaction, metadata);
+                let vtx = Vertex::new(hash, transaction, metadata);
                 let tx = vtx.transaction().clone();
                 entry.insert(vtx);
 
                 // Insert cache queue entry to track eviction priority
                 self.cache_queue.write().unwrap().put(hash, self.generate_cache_index());
 
-                Some(tx)
+                InnerOutcome::Inserted(tx)
             }
         };
 
@@ -153,17 +227,50 @@ where
     }
 
     /// Inserts a transaction, and returns a thread-safe reference to it in case it didn't already exist.
-    pub async fn insert(&self, hash: Hash, transaction: Tx, metadata: T) -> Option<TxRef> {
-        if self.contains_inner(&hash) {
-            None
-        } else {
-            // Insert into backend using hooks
-            self.hooks
-                .insert(hash, transaction.clone(), metadata.clone())
-                .await
-                .unwrap_or_else(|e| info!("Failed to insert transaction {:?}", e));
+    pub async fn insert(&self, hash: Hash, transaction: Tx, metadata: T) -> InsertionOutcome {
+        self.insert_or_update(hash, transaction, metadata, |_| false).await
+    }
+
+    /// Inserts a transaction like `insert` does, but if it already exists, updates the metadata of its vertex with
+    /// `update` instead. The check and the update happen atomically, under the lock of the vertex. `update` returns
+    /// whether it changed the metadata, in which case the new metadata is also written through the hooks.
+    pub async fn insert_or_update<Update>(
+        &self,
+        hash: Hash,
+        transaction: Tx,
+        metadata: T,
+        update: Update,
+    ) -> InsertionOutcome
+    where
+        Update: FnOnce(&mut T) -> bool,
+    {
+        // Fast path for already known transactions, avoids cloning them.
+        // Otherwise the vertex entry is checked and filled atomically, so only one of several concurrent insertions of
+        // the same transaction gets past this point and reaches the backend.
+        let outcome = match self.vertices.get_mut(&hash) {
+            Some(mut vtx) => update_existing(vtx.value_mut().metadata_mut(), update),
+            None => self.insert_inner(hash, transaction.clone(), metadata.clone(), update),
+        };
+
+        match outcome {
+            InnerOutcome::Inserted(tx) => {
+                // Insert into backend using hooks
+                self.hooks
+                    .insert(hash, transaction, metadata)
+                    .await
+                    .unwrap_or_else(|e| info!("Failed to insert transaction {:?}", e));
 
-            self.insert_inner(hash, transaction, metadata)
+                InsertionOutcome::Inserted(tx)
+            }
+            InnerOutcome::Updated(metadata) => {
+                self.hooks
+                    .update(&hash, metadata)
+                    .await
+                    .unwrap_or_else(|e| info!("Failed to update transaction metadata {:?}", e));
+
+                InsertionOutcome::AlreadyPresent
+            }
+            InnerOutcome::Unchanged => InsertionOutcome::AlreadyPresent,
         }
     }
 
@@ -183,6 +290,18 @@ where
         }
     }
 
+    #[inline]
+    fn remove_child(&self, parent: &Hash, child: &Hash) {
+        if let Entry::Occupied(mut entry) = self.children.entry(*parent) {
+            let children = entry.get_mut();
+            children.remove(child);
+            if children.is_empty() {
+                entry.remove();
+                self.orphan_links.remove(parent);
+            }
+        }
+    }
+
     fn get_inner(&self, hash: &Hash) -> Option<TxRef> {
         self.vertices.get(hash).map(|vtx| {
             let mut cache_queue = self.cache_queue.write().unwrap();
@@ -267,11 +386,47 @@ where
         self.children.get(hash).map_or(0, |r| r.value().len())
     }
 
+    /// Returns the number of unknown parents that children are linked to.
+    pub fn orphan_links_count(&self) -> usize {
+        self.orphan_links.len()
+    }
+
+    /// Removes the links of children to unknown parents for which `is_unnecessary` returns true, given the parent
+    /// hash and the time in milliseconds the first link to it was made. Returns the number of parents cleaned.
+    pub fn clean_orphan_links<F>(&self, mut is_unnecessary: F) -> usize
+    where
+        F: FnMut(&Hash, u64) -> bool,
+    {
+        let mut unnecessary = Vec::new();
+
+        for entry in self.orphan_links.iter() {
+            if is_unnecessary(entry.key(), *entry.value()) {
+                unnecessary.push(*entry.key());
+            }
+        }
+
+        let mut cleaned = 0;
+
+        for parent in unnecessary {
+            // The parent may have been inserted in the meantime, in which case its links are not orphans anymore.
+            if self.vertices.contains_key(&parent) {
+                continue;
+            }
+            if self.orphan_links.remove(&parent).is_some() {
+                self.children.remove(&parent);
+                cleaned += 1;
+            }
+        }
+
+        cleaned
+    }
+
     #[cfg(test)]
     pub fn clear(&mut self) {
         self.vertices.clear();
         self.children.clear();
         self.tips.clear();
+        self.orphan_links.clear();
     }
 
     // Attempts to pull the transaction from the storage, returns true if successful.
@@ -280,7 +435,7 @@ where
         if self.vertices.contains_key(hash) {
             true
         } else if let Ok((tx, metadata)) = self.hooks.get(hash).await {
-            self.insert_inner(*hash, tx, metadata);
+            self.insert_inner(*hash, tx, metadata, |_| false);
             true
         } else {
             false
@@ -291,17 +446,51 @@ where
         self.cache_counter.fetch_add(1, Ordering::Relaxed)
     }
 
+    // Removes a vertex unless a `TxRef` to it is still held outside of the tangle, returns true if successful.
+    fn remove_vertex(&self, hash: &Hash) -> bool {
+        // The reference check happens under the shard lock so no `get` can slip in between check and removal.
+        match self.vertices.remove_if(hash, |_, vtx| !vtx.is_referenced()) {
+            Some((_, vtx)) => {
+                // Unlink the vertex from its parents so that walks over children don't run into ghosts. Parents that
+                // lose their last child are deliberately not turned back into tips.
+                self.remove_child(vtx.trunk(), hash);
+                self.remove_child(vtx.branch(), hash);
+                self.children.remove(hash);
+                self.tips.remove(hash);
+                true
+            }
+            None => false,
+        }
+    }
+
     fn perform_eviction(&self) {
         let mut cache = self.cache_queue.write().unwrap();
 
         assert_eq!(cache.len(), self.len());
 
-        if cache.len() == cache.cap() {
-            let (hash, _) = cache.pop_lru().expect("Cache capacity is zero");
+        let mut referenced = Vec::new();
+
+        while cache.len() + referenced.len() > self.cache_cap {
+            let (hash, index) = match cache.pop_lru() {
+                Some(entry) => entry,
+                None => break,
+            };
 
-            self.vertices.remove(&hash).expect("Expected vertex entry to exist");
-            self.children.remove(&hash);
-            self.tips.remove(&hash);
+            if !self.remove_vertex(&hash) {
+                referenced.push((hash, index));
+            }
+        }
+
+        for (hash, index) in referenced {
+            cache.put(hash, index);
+        }
+
+        // If every candidate is still referenced the cache has to grow, otherwise the next insertion would silently
+        // push an entry out of the queue while its vertex stays in the tangle. The surplus is evicted once the
+        // references are dropped.
+        if cache.len() == cache.cap() {
+            let cap = cache.cap();
+            cache.resize(cap + 1);
         }
     }
 }
@@ -309,9 +498,18 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
-    use bee_test::transaction::create_random_tx;
+    use crate::traversal;
+    use bee_test::transaction::{create_random_attached_tx, create_random_tx};
     use pollster::block_on;
 
+    use std::{
+        sync::{
+            atomic::{AtomicBool, AtomicUsize},
+            Arc, Barrier,
+        },
+        thread,
+    };
+
     #[test]
     fn new_tangle() {
         let _: Tangle<u8> = Tangle::default();
@@ -325,19 +523,116 @@ mod tests {
 
         let insert1 = block_on(tangle.insert(hash, tx.clone(), ()));
 
-        assert!(insert1.is_some());
+        assert!(matches!(insert1, InsertionOutcome::Inserted(_)));
         assert_eq!(1, tangle.len());
         assert!(block_on(tangle.contains(&hash)));
         assert_eq!(1, tangle.num_tips());
 
         let insert2 = block_on(tangle.insert(hash, tx, ()));
 
-        assert!(insert2.is_none());
+        assert!(matches!(insert2, InsertionOutcome::AlreadyPresent));
         assert_eq!(1, tangle.len());
         assert!(block_on(tangle.contains(&hash)));
         assert_eq!(1, tangle.num_tips());
     }
 
+    #[test]
+    fn insert_or_update_existing() {
+        let tangle = Tangle::<u8>::default();
+        let (hash, tx) = create_random_tx();
+
+        let insert1 = block_on(tangle.insert_or_update(hash, tx.clone(), 1, |metadata| {
+            *metadata += 10;
+            true
+        }));
+
+        assert!(matches!(insert1, InsertionOutcome::Inserted(_)));
+        // The update only applies to an existing vertex.
+        assert_eq!(tangle.get_metadata(&hash), Some(1));
+
+        let insert2 = block_on(tangle.insert_or_update(hash, tx, 2, |metadata| {
+            *metadata += 10;
+            true
+        }));
+
+        assert!(matches!(insert2, InsertionOutcome::AlreadyPresent));
+        // The metadata passed on a duplicate insertion is dropped in favour of the update.
+        assert_eq!(tangle.get_metadata(&hash), Some(11));
+        assert_eq!(1, tangle.len());
+    }
+
+    #[derive(Default)]
+    struct CountingHooks {
+        inserts: AtomicUsize,
+        updates: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl<T: Send + Sync> Hooks<T> for CountingHooks {
+        type Error = ();
+
+        async fn get(&self, _hash: &Hash) -> Result<(Tx, T), Self::Error> {
+            Err(())
+        }
+
+        async fn insert(&self, _hash: Hash, _tx: Tx, _metadata: T) -> Result<(), Self::Error> {
+            self.inserts.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+
+        async fn update(&self, _hash: &Hash, _metadata: T) -> Result<(), Self::Error> {
+            self.updates.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn changed_metadata_reaches_hooks() {
+        let tangle = Tangle::<u8, CountingHooks>::default();
+        let (hash, tx) = create_random_tx();
+
+        block_on(tangle.insert_or_update(hash, tx.clone(), 1, |_| unreachable!()));
+        block_on(tangle.insert_or_update(hash, tx.clone(), 1, |_| false));
+
+        assert_eq!(tangle.hooks.inserts.load(Ordering::SeqCst), 1);
+        assert_eq!(tangle.hooks.updates.load(Ordering::SeqCst), 0);
+
+        block_on(tangle.insert_or_update(hash, tx, 1, |metadata| {
+            *metadata = 2;
+            true
+        }));
+
+        assert_eq!(tangle.get_metadata(&hash), Some(2));
+        assert_eq!(tangle.hooks.inserts.load(Ordering::SeqCst), 1);
+        assert_eq!(tangle.hooks.updates.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn concurrent_insert_same_transaction() {
+        let tangle = Arc::new(Tangle::<(), CountingHooks>::default());
+        let barrier = Arc::new(Barrier::new(16));
+        let (hash, tx) = create_random_tx();
+
+        let handles = (0..16)
+            .map(|_| {
+                let tangle = tangle.clone();
+                let barrier = barrier.clone();
+                let tx = tx.clone();
+
+                thread::spawn(move || {
+                    barrier.wait();
+                    block_on(tangle.insert(hash, tx, ())).inserted().is_some()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let inserted = handles.into_iter().filter(|handle| handle.join().unwrap()).count();
+
+        assert_eq!(inserted, 1);
+        assert_eq!(tangle.len(), 1);
+        assert_eq!(tangle.hooks.inserts.load(Ordering::SeqCst), 1);
+    }
+
     #[test]
     fn eviction_cap() {
         let tangle = Tangle::<()>::default().with_capacity(5);
@@ -373,4 +668,190 @@ mod tests {
             assert!(entry.key() == &txs[0].0 || txs[4..].iter().any(|(h, _)| entry.key() == h));
         }
     }
+
+    #[test]
+    fn eviction_skips_referenced() {
+        let tangle = Tangle::<()>::default().with_capacity(5);
+
+        let txs = (0..10).map(|_| create_random_tx()).collect::<Vec<_>>();
+
+        let held = block_on(tangle.insert(txs[0].0, txs[0].1.clone(), ()))
+            .inserted()
+            .unwrap();
+
+        for (hash, tx) in txs.iter().skip(1) {
+            let _ = block_on(tangle.insert(*hash, tx.clone(), ()));
+        }
+
+        assert_eq!(tangle.len(), 5);
+        assert!(block_on(tangle.contains(&txs[0].0)));
+        assert_eq!(*held, txs[0].1);
+
+        drop(held);
+
+        for _ in 0..5 {
+            let (hash, tx) = create_random_tx();
+            let _ = block_on(tangle.insert(hash, tx, ()));
+        }
+
+        assert_eq!(tangle.len(), 5);
+        assert!(!tangle.vertices.contains_key(&txs[0].0));
+    }
+
+    #[test]
+    fn evicted_child_unlinked_from_parents() {
+        let tangle = Tangle::<()>::default().with_capacity(3);
+
+        let (a_hash, a) = create_random_tx();
+        let (b_hash, b) = create_random_tx();
+        let (c_hash, c) = create_random_attached_tx(a_hash, b_hash);
+
+        block_on(tangle.insert(a_hash, a, ()));
+        block_on(tangle.insert(b_hash, b, ()));
+        block_on(tangle.insert(c_hash, c, ()));
+
+        assert_eq!(tangle.num_children(&a_hash), 1);
+        assert_eq!(tangle.num_children(&b_hash), 1);
+
+        // Touch the parents so that `c` becomes the eviction candidate.
+        assert!(block_on(tangle.get(&a_hash)).is_some());
+        assert!(block_on(tangle.get(&b_hash)).is_some());
+
+        let (d_hash, d) = create_random_tx();
+        block_on(tangle.insert(d_hash, d, ()));
+
+        assert_eq!(tangle.len(), 3);
+        assert!(!tangle.vertices.contains_key(&c_hash));
+        assert_eq!(tangle.num_children(&a_hash), 0);
+        assert!(tangle.get_children(&b_hash).is_empty());
+
+        let mut visited = Vec::new();
+        traversal::visit_children_follow_trunk(
+            &tangle,
+            b_hash,
+            |_, _| true,
+            |hash, _, _| visited.push(*hash),
+            &AtomicBool::new(false),
+        )
+        .unwrap();
+
+        assert_eq!(visited, vec![b_hash]);
+    }
+
+    #[test]
+    fn traversal_across_evicted_parent() {
+        let tangle = Tangle::<()>::default().with_capacity(3);
+
+        let (a_hash, a) = create_random_tx();
+        let (b_hash, b) = create_random_tx();
+        let (c_hash, c) = create_random_attached_tx(a_hash, b_hash);
+
+        block_on(tangle.insert(a_hash, a, ()));
+        block_on(tangle.insert(b_hash, b, ()));
+        block_on(tangle.insert(c_hash, c, ()));
+
+        let (d_hash, d) = create_random_tx();
+        block_on(tangle.insert(d_hash, d, ()));
+
+        assert!(!tangle.vertices.contains_key(&a_hash));
+
+        let mut applied = Vec::new();
+        let mut not_matched = Vec::new();
+        let mut missing = Vec::new();
+
+        traversal::visit_parents_depth_first(
+            &tangle,
+            c_hash,
+            |hash, _, _| *hash == c_hash,
+            |hash, _, _| applied.push(*hash),
+            |hash, _, _| not_matched.push(*hash),
+            |hash| missing.push(*hash),
+            &AtomicBool::new(false),
+        )
+        .unwrap();
+
+        assert_eq!(applied, vec![c_hash]);
+        assert_eq!(not_matched, vec![b_hash]);
+        assert_eq!(missing, vec![a_hash]);
+    }
+
+    // The fixtures have random parents, which are orphan links of their own, so links are checked per parent.
+    fn is_orphan(tangle: &Tangle<()>, parent: &Hash) -> bool {
+        tangle.orphan_links.contains_key(parent)
+    }
+
+    #[test]
+    fn orphan_link_claimed_by_parent() {
+        let tangle = Tangle::<()>::default();
+
+        let (a_hash, a) = create_random_tx();
+        let (b_hash, b) = create_random_tx();
+        let (c_hash, c) = create_random_attached_tx(a_hash, b_hash);
+
+        block_on(tangle.insert(b_hash, b, ()));
+        block_on(tangle.insert(c_hash, c, ()));
+
+        assert!(is_orphan(&tangle, &a_hash));
+        assert!(!is_orphan(&tangle, &b_hash));
+        assert_eq!(tangle.num_children(&a_hash), 1);
+
+        block_on(tangle.insert(a_hash, a, ()));
+
+        assert!(!is_orphan(&tangle, &a_hash));
+        assert_eq!(tangle.num_children(&a_hash), 1);
+        assert_eq!(tangle.clean_orphan_links(|parent, _| *parent == a_hash), 0);
+        assert_eq!(tangle.num_children(&a_hash), 1);
+    }
+
+    #[test]
+    fn orphan_link_cleaned_past_horizon() {
+        let tangle = Tangle::<()>::default();
+
+        let (a_hash, _) = create_random_tx();
+        let (b_hash, b) = create_random_tx();
+        let (c_hash, c) = create_random_attached_tx(a_hash, b_hash);
+
+        block_on(tangle.insert(b_hash, b, ()));
+        block_on(tangle.insert(c_hash, c, ()));
+
+        let created = *tangle.orphan_links.get(&a_hash).unwrap();
+
+        assert_eq!(
+            tangle.clean_orphan_links(|parent, time| *parent == a_hash && time < created),
+            0
+        );
+        assert!(is_orphan(&tangle, &a_hash));
+
+        assert_eq!(
+            tangle.clean_orphan_links(|parent, time| *parent == a_hash && time <= created),
+            1
+        );
+        assert!(!is_orphan(&tangle, &a_hash));
+        assert_eq!(tangle.num_children(&a_hash), 0);
+        assert_eq!(tangle.num_children(&b_hash), 1);
+    }
+
+    #[test]
+    fn orphan_link_removed_with_evicted_child() {
+        let tangle = Tangle::<()>::default().with_capacity(2);
+
+        let (a_hash, _) = create_random_tx();
+        let (b_hash, b) = create_random_tx();
+        let (c_hash, c) = create_random_attached_tx(a_hash, b_hash);
+
+        block_on(tangle.insert(b_hash, b, ()));
+        block_on(tangle.insert(c_hash, c, ()));
+
+        assert!(is_orphan(&tangle, &a_hash));
+
+        // Touch the parent so that `c` becomes the eviction candidate.
+        assert!(block_on(tangle.get(&b_hash)).is_some());
+
+        let (d_hash, d) = create_random_tx();
+        block_on(tangle.insert(d_hash, d, ()));
+
+        assert!(!tangle.vertices.contains_key(&c_hash));
+        assert!(!is_orphan(&tangle, &a_hash));
+        assert_eq!(tangle.num_children(&a_hash), 0);
+    }
 }
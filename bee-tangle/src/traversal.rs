@@ -20,7 +20,26 @@ use crate::{
 
 use bee_crypto::ternary::Hash;
 
-use std::collections::HashSet;
+use std::{
+    collections::HashSet,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+/// Number of visited vertices between two checks of the cancellation flag of the interruptible walkers.
+pub const CANCELLATION_CHECK_INTERVAL: usize = 1000;
+
+/// Returned by the interruptible walkers when their cancellation flag was set before the end of the walk.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Cancelled;
+
+#[inline]
+fn check_cancellation(visited: usize, cancel: &AtomicBool) -> Result<(), Cancelled> {
+    if visited % CANCELLATION_CHECK_INTERVAL == 0 && cancel.load(Ordering::Relaxed) {
+        Err(Cancelled)
+    } else {
+        Ok(())
+    }
+}
 
 /// A Tangle walker that - given a starting vertex - visits all of its ancestors that are connected through
 /// the *trunk* edge. The walk continues as long as the visited vertices match a certain condition. For each
@@ -51,25 +70,31 @@ pub fn visit_parents_follow_trunk<Metadata, Match, Apply, H: Hooks<Metadata>>(
 /// A Tangle walker that - given a starting vertex - visits all of its children that are connected through
 /// the *trunk* edge. The walk continues as long as the visited vertices match a certain condition. For each
 /// visited vertex a customized logic can be applied. Each traversed vertex provides read access to its
-/// associated data and metadata.
+/// associated data and metadata. The walk is cut short with `Cancelled` if `cancel` is found set.
 pub fn visit_children_follow_trunk<Metadata, Match, Apply, H: Hooks<Metadata>>(
     tangle: &Tangle<Metadata, H>,
     root: Hash,
     mut matches: Match,
     mut apply: Apply,
-) where
+    cancel: &AtomicBool,
+) -> Result<(), Cancelled>
+where
     Metadata: Clone + Copy,
     Match: FnMut(&TxRef, &Metadata) -> bool,
     Apply: FnMut(&Hash, &TxRef, &Metadata),
 {
     // TODO could be simplified like visit_parents_follow_trunk ? Meaning no vector ?
     let mut children = vec![root];
+    let mut visited = 0;
 
     while let Some(ref parent_hash) = children.pop() {
         if let Some(parent) = tangle.vertices.get(parent_hash) {
             if matches(parent.value().transaction(), parent.value().metadata()) {
                 apply(parent_hash, parent.value().transaction(), parent.value().metadata());
 
+                visited += 1;
+                check_cancellation(visited, cancel)?;
+
                 if let Some(parent_children) = tangle.children.get(parent_hash) {
                     for child_hash in parent_children.value() {
                         if let Some(child) = tangle.vertices.get(child_hash) {
@@ -82,12 +107,15 @@ pub fn visit_children_follow_trunk<Metadata, Match, Apply, H: Hooks<Metadata>>(
             }
         }
     }
+
+    Ok(())
 }
 
 /// A Tangle walker that - given a starting vertex - visits all of its ancestors that are connected through
 /// either the *trunk* or the *branch* edge. The walk continues as long as the visited vertices match a certain
 /// condition. For each visited vertex customized logic can be applied depending on the availability of the
-/// vertex. Each traversed vertex provides read access to its associated data and metadata.
+/// vertex. Each traversed vertex provides read access to its associated data and metadata. The walk is cut short with
+/// `Cancelled` if `cancel` is found set.
 pub fn visit_parents_depth_first<Metadata, Match, Apply, ElseApply, MissingApply, H: Hooks<Metadata>>(
     tangle: &Tangle<Metadata, H>,
     root: Hash,
@@ -95,7 +123,9 @@ pub fn visit_parents_depth_first<Metadata, Match, Apply, ElseApply, MissingApply
     mut apply: Apply,
     mut else_apply: ElseApply,
     mut missing_apply: MissingApply,
-) where
+    cancel: &AtomicBool,
+) -> Result<(), Cancelled>
+where
     Metadata: Clone + Copy,
     Match: Fn(&Hash, &TxRef, &Metadata) -> bool,
     Apply: FnMut(&Hash, &TxRef, &Metadata),
@@ -127,8 +157,12 @@ pub fn visit_parents_depth_first<Metadata, Match, Apply, ElseApply, MissingApply
                 }
             }
             visited.insert(hash);
+
+            check_cancellation(visited.len(), cancel)?;
         }
     }
+
+    Ok(())
 }
 
 // TODO: test
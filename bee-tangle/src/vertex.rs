@@ -29,9 +29,9 @@ impl<T> Vertex<T>
 where
     T: Clone,
 {
-    pub fn new(transaction: Tx, metadata: T) -> Self {
+    pub fn new(hash: Hash, transaction: Tx, metadata: T) -> Self {
         Self {
-            transaction: TxRef(Arc::new(transaction)),
+            transaction: TxRef::new(hash, transaction),
             metadata,
         }
     }
@@ -55,6 +55,11 @@ where
     pub fn metadata_mut(&mut self) -> &mut T {
         &mut self.metadata
     }
+
+    /// Returns whether a `TxRef` to this vertex is held outside of the tangle.
+    pub fn is_referenced(&self) -> bool {
+        Arc::strong_count(&self.transaction.0) > 1
+    }
 }
 
 #[cfg(test)]
@@ -64,22 +69,23 @@ mod tests {
 
     #[test]
     fn create_new_vertex() {
-        let (_, tx) = create_random_tx();
+        let (hash, tx) = create_random_tx();
         let metadata = 0b0000_0001u8;
 
-        let vtx = Vertex::new(tx.clone(), metadata);
+        let vtx = Vertex::new(hash, tx.clone(), metadata);
 
         assert_eq!(tx.trunk(), vtx.trunk());
         assert_eq!(tx.branch(), vtx.branch());
         assert_eq!(tx, **vtx.transaction());
+        assert_eq!(hash, *vtx.transaction().hash());
         assert_eq!(metadata, *vtx.metadata());
     }
 
     #[test]
     fn update_vertex_meta() {
-        let (_, tx) = create_random_tx();
+        let (hash, tx) = create_random_tx();
 
-        let mut vtx = Vertex::new(tx, 0b0000_0001u8);
+        let mut vtx = Vertex::new(hash, tx, 0b0000_0001u8);
         *vtx.metadata_mut() = 0b1111_1110u8;
 
         assert_eq!(0b1111_1110u8, *vtx.metadata());
@@ -10,7 +10,7 @@
 // See the License for the specific language governing permissions and limitations under the License.
 
 use bee_crypto::ternary::Hash;
-use bee_tangle::Tangle;
+use bee_tangle::{Hooks, Tangle};
 use bee_test::transaction::{create_random_attached_tx, create_random_tx};
 use bee_transaction::{bundled::BundledTransaction as Tx, Vertex};
 
@@ -30,6 +30,50 @@ pub struct Hashes {
     pub e_hash: Hash,
 }
 
+/// Checks that `hashes`, all the vertices of `tangle`, are the children of their parents and that every child of a
+/// vertex has it as its trunk or branch.
+pub async fn verify_child_invariant<T: Clone, H: Hooks<T>>(tangle: &Tangle<T, H>, hashes: &[Hash]) {
+    assert_eq!(tangle.len(), hashes.len());
+
+    for hash in hashes {
+        let transaction = tangle.get(hash).await.unwrap();
+
+        for parent in [transaction.trunk(), transaction.branch()].iter() {
+            assert!(tangle.get_children(parent).contains(hash));
+        }
+
+        for child in tangle.get_children(hash) {
+            let child = tangle.get(&child).await.unwrap();
+
+            assert!(child.trunk() == hash || child.branch() == hash);
+        }
+    }
+}
+
+/// Creates a tangle made of a single chain of `len` transactions, each of them attached to the previous one through
+/// both its trunk and its branch. Returns the hashes of the chain, from its root to its head.
+// Only used by some of the test crates including this module.
+#[allow(dead_code)]
+pub fn create_test_chain(len: usize) -> (Tangle<()>, Vec<Hash>) {
+    pollster::block_on(async {
+        let tangle = Tangle::default();
+        let mut hashes = Vec::with_capacity(len);
+
+        let (root_hash, root) = create_random_tx();
+        tangle.insert(root_hash, root, ()).await;
+        hashes.push(root_hash);
+
+        while hashes.len() < len {
+            let parent_hash = *hashes.last().unwrap();
+            let (hash, transaction) = create_random_attached_tx(parent_hash, parent_hash);
+            tangle.insert(hash, transaction, ()).await;
+            hashes.push(hash);
+        }
+
+        (tangle, hashes)
+    })
+}
+
 #[allow(clippy::many_single_char_names)]
 pub fn create_test_tangle() -> (Tangle<()>, Transactions, Hashes) {
     // a   b
@@ -69,7 +113,7 @@ pub fn create_test_tangle() -> (Tangle<()>, Transactions, Hashes) {
         assert_eq!(*tangle.get(&e_hash).await.unwrap().trunk(), c_hash);
         assert_eq!(*tangle.get(&e_hash).await.unwrap().branch(), d_hash);
 
-        // TODO ensure children reference their parents correctly
+        verify_child_invariant(&tangle, &[a_hash, b_hash, c_hash, d_hash, e_hash]).await;
 
         assert_eq!(5, tangle.len());
         assert_eq!(2, tangle.num_children(&a_hash));
@@ -13,9 +13,61 @@ mod helper;
 
 use self::helper::*;
 
+use bee_tangle::Tangle;
+use bee_test::transaction::{create_random_attached_tx, create_random_tx};
+
+use std::collections::HashSet;
+
 #[test]
 fn count_tips() {
     let (tangle, _, _) = create_test_tangle();
 
     assert_eq!(1, tangle.num_tips());
 }
+
+#[test]
+fn transaction_ref_identity() {
+    let (tangle, _, Hashes { a_hash, b_hash, .. }) = create_test_tangle();
+
+    pollster::block_on(async {
+        let a = tangle.get(&a_hash).await.unwrap();
+        let a_again = tangle.get(&a_hash).await.unwrap();
+        let b = tangle.get(&b_hash).await.unwrap();
+
+        assert_eq!(*a.hash(), a_hash);
+        assert!(a == a.clone());
+        assert!(a == a_again);
+        assert!(a != b);
+
+        let refs = vec![a.clone(), a_again, b.clone(), b]
+            .into_iter()
+            .collect::<HashSet<_>>();
+
+        assert_eq!(refs.len(), 2);
+        assert!(refs.contains(&a));
+    });
+}
+
+#[test]
+fn children_linked_before_parents_arrive() {
+    pollster::block_on(async {
+        let tangle = Tangle::<()>::default();
+
+        let (a_hash, a) = create_random_tx();
+        let (b_hash, b) = create_random_tx();
+        let (c_hash, c) = create_random_attached_tx(a_hash, b_hash);
+
+        tangle.insert(c_hash, c, ()).await;
+
+        // Both parents are linked by the insertion of the child alone.
+        assert!(tangle.get_children(&a_hash).contains(&c_hash));
+        assert!(tangle.get_children(&b_hash).contains(&c_hash));
+
+        tangle.insert(a_hash, a, ()).await;
+        tangle.insert(b_hash, b, ()).await;
+
+        assert_eq!(1, tangle.num_children(&a_hash));
+        assert_eq!(1, tangle.num_children(&b_hash));
+        verify_child_invariant(&tangle, &[a_hash, b_hash, c_hash]).await;
+    });
+}
@@ -15,6 +15,23 @@ use self::helper::*;
 
 use bee_tangle::traversal::*;
 
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+};
+
+// Sets `cancel` from another thread and waits for it to be set.
+fn cancel_from_other_thread(cancel: &Arc<AtomicBool>) {
+    let cancel = cancel.clone();
+
+    thread::spawn(move || cancel.store(true, Ordering::Relaxed))
+        .join()
+        .unwrap();
+}
+
 #[test]
 fn visit_children_follow_trunk_in_simple_graph() {
     // a   b0
@@ -29,7 +46,16 @@ fn visit_children_follow_trunk_in_simple_graph() {
 
     let mut txs = vec![];
 
-    visit_children_follow_trunk(&tangle, b_hash, |_, _| true, |_, tx, _| txs.push(tx.clone()));
+    assert_eq!(
+        visit_children_follow_trunk(
+            &tangle,
+            b_hash,
+            |_, _| true,
+            |_, tx, _| txs.push(tx.clone()),
+            &AtomicBool::new(false)
+        ),
+        Ok(())
+    );
 
     assert_eq!(4, txs.len());
 
@@ -91,13 +117,17 @@ fn visit_parents_depth_first_in_simple_graph() {
 
     let mut addresses = vec![];
 
-    visit_parents_depth_first(
-        &tangle,
-        e_hash,
-        |_, _, _| true,
-        |_, data, _| addresses.push(data.address().clone()),
-        |_, _, _| {},
-        |_| (),
+    assert_eq!(
+        visit_parents_depth_first(
+            &tangle,
+            e_hash,
+            |_, _, _| true,
+            |_, data, _| addresses.push(data.address().clone()),
+            |_, _, _| {},
+            |_| (),
+            &AtomicBool::new(false),
+        ),
+        Ok(())
     );
 
     assert_eq!(5, addresses.len());
@@ -108,3 +138,77 @@ fn visit_parents_depth_first_in_simple_graph() {
     assert_eq!(*c.address(), addresses[3]);
     assert_eq!(*b.address(), addresses[4]);
 }
+
+#[test]
+fn visit_children_follow_trunk_cancelled() {
+    let (tangle, hashes) = create_test_chain(10 * CANCELLATION_CHECK_INTERVAL);
+    let cancel = Arc::new(AtomicBool::new(false));
+    let mut applied = Vec::new();
+
+    let result = visit_children_follow_trunk(
+        &tangle,
+        hashes[0],
+        |_, _| true,
+        |hash, _, _| {
+            applied.push(*hash);
+            if applied.len() == 10 {
+                cancel_from_other_thread(&cancel);
+            }
+        },
+        &cancel,
+    );
+
+    assert_eq!(result, Err(Cancelled));
+    assert!(applied.len() <= 10 + CANCELLATION_CHECK_INTERVAL);
+    // What was applied before the cancellation is an unbroken run of the chain from the root, so flags set along the
+    // way stay consistent with the ones of their neighbours, and the tangle itself is left untouched.
+    assert_eq!(applied[..], hashes[..applied.len()]);
+    pollster::block_on(verify_child_invariant(&tangle, &hashes));
+}
+
+#[test]
+fn visit_parents_depth_first_cancelled() {
+    let (tangle, hashes) = create_test_chain(10 * CANCELLATION_CHECK_INTERVAL);
+    let cancel = Arc::new(AtomicBool::new(false));
+    let mut applied = Vec::new();
+
+    let result = visit_parents_depth_first(
+        &tangle,
+        *hashes.last().unwrap(),
+        |_, _, _| true,
+        |hash, _, _| {
+            applied.push(*hash);
+            if applied.len() == 10 {
+                cancel_from_other_thread(&cancel);
+            }
+        },
+        |_, _, _| {},
+        |_| {},
+        &cancel,
+    );
+
+    assert_eq!(result, Err(Cancelled));
+    assert!(applied.len() <= 10 + CANCELLATION_CHECK_INTERVAL);
+    // Same as above, from the head of the chain down.
+    assert!(applied.iter().eq(hashes.iter().rev().take(applied.len())));
+    pollster::block_on(verify_child_invariant(&tangle, &hashes));
+}
+
+#[test]
+fn visit_parents_depth_first_not_cancelled() {
+    let (tangle, hashes) = create_test_chain(2 * CANCELLATION_CHECK_INTERVAL);
+    let mut visited = 0;
+
+    let result = visit_parents_depth_first(
+        &tangle,
+        *hashes.last().unwrap(),
+        |_, _, _| true,
+        |_, _, _| visited += 1,
+        |_, _, _| {},
+        |_| {},
+        &AtomicBool::new(false),
+    );
+
+    assert_eq!(result, Ok(()));
+    assert_eq!(visited, hashes.len());
+}
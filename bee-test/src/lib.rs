@@ -13,5 +13,6 @@
 
 pub mod field;
 pub mod milestone;
+pub mod network;
 pub mod storage;
 pub mod transaction;
@@ -0,0 +1,306 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+//! A deterministic, in-process transport to connect test nodes without sockets or sleeps.
+//!
+//! Time is virtual: messages are only delivered when the test advances the clock, and packet loss is drawn from a
+//! seeded generator so that a scenario always plays out the same way.
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, VecDeque},
+    time::Duration,
+};
+
+pub type NodeId = usize;
+
+/// Properties of the directed link between two nodes.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Link {
+    /// Delay before a message sent on the link is delivered.
+    pub latency: Duration,
+    /// Probability, between 0 and 1, of a message being dropped.
+    pub loss: f64,
+}
+
+/// A message delivered to a node.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Envelope {
+    pub from: NodeId,
+    pub bytes: Vec<u8>,
+}
+
+// Ordered by delivery time, then by sending order to keep simultaneous deliveries deterministic.
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+struct InFlight {
+    deliver_at: Duration,
+    sequence: u64,
+    to: NodeId,
+    from: NodeId,
+    bytes: Vec<u8>,
+}
+
+pub struct VirtualNetwork {
+    nodes: usize,
+    now: Duration,
+    rng: StdRng,
+    default_link: Link,
+    links: HashMap<(NodeId, NodeId), Link>,
+    // Nodes can only reach nodes of the same group; every node is in group 0 when the network is healed.
+    groups: Vec<usize>,
+    sequence: u64,
+    in_flight: BinaryHeap<Reverse<InFlight>>,
+    inboxes: Vec<VecDeque<Envelope>>,
+}
+
+impl VirtualNetwork {
+    /// Creates a fully connected network of `nodes` nodes, with lossless and instant links.
+    pub fn new(nodes: usize, seed: u64) -> Self {
+        Self {
+            nodes,
+            now: Duration::from_secs(0),
+            rng: StdRng::seed_from_u64(seed),
+            default_link: Link::default(),
+            links: HashMap::new(),
+            groups: vec![0; nodes],
+            sequence: 0,
+            in_flight: BinaryHeap::new(),
+            inboxes: vec![VecDeque::new(); nodes],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes == 0
+    }
+
+    /// Returns the virtual time elapsed since the creation of the network.
+    pub fn now(&self) -> Duration {
+        self.now
+    }
+
+    /// Sets the properties of every link that has not been configured with `set_link`.
+    pub fn set_default_link(&mut self, link: Link) {
+        self.default_link = link;
+    }
+
+    /// Sets the properties of the link from `from` to `to`.
+    pub fn set_link(&mut self, from: NodeId, to: NodeId, link: Link) {
+        self.links.insert((from, to), link);
+    }
+
+    /// Splits the network so that nodes only reach the nodes of their own group; unlisted nodes form a last group.
+    pub fn partition(&mut self, groups: &[&[NodeId]]) {
+        self.groups = vec![groups.len(); self.nodes];
+
+        for (group, nodes) in groups.iter().enumerate() {
+            for node in nodes.iter() {
+                self.groups[*node] = group;
+            }
+        }
+    }
+
+    /// Reconnects all the nodes. Messages dropped while the network was partitioned are not recovered.
+    pub fn heal(&mut self) {
+        self.groups = vec![0; self.nodes];
+    }
+
+    pub fn is_reachable(&self, from: NodeId, to: NodeId) -> bool {
+        self.groups[from] == self.groups[to]
+    }
+
+    /// Sends a message, returning whether it was accepted by the link.
+    pub fn send(&mut self, from: NodeId, to: NodeId, bytes: Vec<u8>) -> bool {
+        if !self.is_reachable(from, to) {
+            return false;
+        }
+
+        let link = self.links.get(&(from, to)).copied().unwrap_or(self.default_link);
+
+        if link.loss > 0.0 && self.rng.gen_bool(link.loss.min(1.0)) {
+            return false;
+        }
+
+        self.sequence += 1;
+        self.in_flight.push(Reverse(InFlight {
+            deliver_at: self.now + link.latency,
+            sequence: self.sequence,
+            to,
+            from,
+            bytes,
+        }));
+
+        true
+    }
+
+    /// Sends a message to every other node.
+    pub fn broadcast(&mut self, from: NodeId, bytes: &[u8]) {
+        for to in (0..self.nodes).filter(|to| *to != from) {
+            self.send(from, to, bytes.to_vec());
+        }
+    }
+
+    /// Advances the virtual time and moves the messages that are due to the inboxes of their recipients.
+    ///
+    /// A message in flight when its link gets partitioned is still delivered, like a packet already on the wire.
+    pub fn advance(&mut self, duration: Duration) {
+        self.now += duration;
+
+        while let Some(Reverse(message)) = self.in_flight.peek() {
+            if message.deliver_at > self.now {
+                break;
+            }
+            if let Some(Reverse(message)) = self.in_flight.pop() {
+                self.inboxes[message.to].push_back(Envelope {
+                    from: message.from,
+                    bytes: message.bytes,
+                });
+            }
+        }
+    }
+
+    /// Returns the next message delivered to `node`.
+    pub fn recv(&mut self, node: NodeId) -> Option<Envelope> {
+        self.inboxes[node].pop_front()
+    }
+
+    /// Returns whether no message is in flight or waiting in an inbox.
+    pub fn is_idle(&self) -> bool {
+        self.in_flight.is_empty() && self.inboxes.iter().all(VecDeque::is_empty)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn delivery_after_latency() {
+        let mut network = VirtualNetwork::new(2, 0);
+
+        network.set_link(
+            0,
+            1,
+            Link {
+                latency: Duration::from_millis(100),
+                loss: 0.0,
+            },
+        );
+
+        assert!(network.send(0, 1, vec![1]));
+
+        network.advance(Duration::from_millis(99));
+        assert_eq!(network.recv(1), None);
+
+        network.advance(Duration::from_millis(1));
+        assert_eq!(
+            network.recv(1),
+            Some(Envelope {
+                from: 0,
+                bytes: vec![1]
+            })
+        );
+        assert!(network.is_idle());
+    }
+
+    #[test]
+    fn simultaneous_deliveries_keep_sending_order() {
+        let mut network = VirtualNetwork::new(3, 0);
+
+        network.send(0, 2, vec![0]);
+        network.send(1, 2, vec![1]);
+        network.send(0, 2, vec![2]);
+        network.advance(Duration::from_secs(0));
+
+        for byte in 0..3 {
+            assert_eq!(network.recv(2).unwrap().bytes, vec![byte]);
+        }
+    }
+
+    #[test]
+    fn partition_then_heal() {
+        let mut network = VirtualNetwork::new(4, 0);
+
+        network.partition(&[&[0, 1], &[2, 3]]);
+
+        assert!(network.send(0, 1, vec![0]));
+        assert!(!network.send(0, 2, vec![0]));
+        assert!(network.send(3, 2, vec![0]));
+
+        network.heal();
+
+        assert!(network.send(0, 2, vec![1]));
+        network.advance(Duration::from_secs(0));
+
+        assert_eq!(
+            network.recv(2),
+            Some(Envelope {
+                from: 3,
+                bytes: vec![0]
+            })
+        );
+        assert_eq!(
+            network.recv(2),
+            Some(Envelope {
+                from: 0,
+                bytes: vec![1]
+            })
+        );
+    }
+
+    #[test]
+    fn unlisted_nodes_are_isolated_together() {
+        let mut network = VirtualNetwork::new(3, 0);
+
+        network.partition(&[&[0]]);
+
+        assert!(!network.is_reachable(0, 1));
+        assert!(network.is_reachable(1, 2));
+    }
+
+    #[test]
+    fn loss_is_deterministic() {
+        let run = |seed| {
+            let mut network = VirtualNetwork::new(2, seed);
+
+            network.set_default_link(Link {
+                latency: Duration::from_secs(0),
+                loss: 0.5,
+            });
+
+            (0..100).map(|_| network.send(0, 1, vec![])).collect::<Vec<_>>()
+        };
+
+        assert_eq!(run(42), run(42));
+        assert!(run(42).iter().any(|sent| *sent));
+        assert!(run(42).iter().any(|sent| !*sent));
+    }
+
+    #[test]
+    fn total_loss_drops_everything() {
+        let mut network = VirtualNetwork::new(2, 0);
+
+        network.set_default_link(Link {
+            latency: Duration::from_secs(0),
+            loss: 1.0,
+        });
+        network.broadcast(0, &[0]);
+        network.advance(Duration::from_secs(1));
+
+        assert!(network.is_idle());
+    }
+}
@@ -38,7 +38,7 @@ pub fn clone_tx(tx: &Transaction) -> Transaction {
         .with_attachment_ubts(tx.attachment_ubts().clone())
         .with_nonce(tx.nonce().clone());
 
-    builder.build().unwrap()
+    builder.build_unchecked().unwrap()
 }
 
 pub fn create_random_tx() -> (Hash, Transaction) {
@@ -59,7 +59,7 @@ pub fn create_random_tx() -> (Hash, Transaction) {
         .with_attachment_ubts(Timestamp::from_inner_unchecked(0))
         .with_nonce(rand_trits_field::<Nonce>());
 
-    (rand_trits_field::<Hash>(), builder.build().unwrap())
+    (rand_trits_field::<Hash>(), builder.build_unchecked().unwrap())
 }
 
 pub fn create_random_attached_tx(branch: Hash, trunk: Hash) -> (Hash, Transaction) {
@@ -80,5 +80,5 @@ pub fn create_random_attached_tx(branch: Hash, trunk: Hash) -> (Hash, Transactio
         .with_attachment_ubts(Timestamp::from_inner_unchecked(0))
         .with_nonce(rand_trits_field::<Nonce>());
 
-    (rand_trits_field::<Hash>(), builder.build().unwrap())
+    (rand_trits_field::<Hash>(), builder.build_unchecked().unwrap())
 }
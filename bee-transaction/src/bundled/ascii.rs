@@ -0,0 +1,102 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+//! Encoding of ASCII data into trytes, two trytes per byte, as used to carry messages in transaction payloads.
+
+use thiserror::Error;
+
+const TRYTE_ALPHABET: &[u8; 27] = b"9ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+#[derive(Debug, Error, PartialEq)]
+pub enum AsciiError {
+    #[error("byte {0:#04x} is not ASCII")]
+    NonAscii(u8),
+    #[error("character {0:?} is not a tryte")]
+    InvalidTryte(char),
+    #[error("odd number of trytes: {0}")]
+    OddLength(usize),
+}
+
+fn tryte_value(tryte: char) -> Result<u8, AsciiError> {
+    TRYTE_ALPHABET
+        .iter()
+        .position(|c| *c as char == tryte)
+        .map(|position| position as u8)
+        .ok_or(AsciiError::InvalidTryte(tryte))
+}
+
+/// Encodes ASCII bytes into trytes, the least significant tryte of each byte coming first.
+pub fn ascii_to_trytes(bytes: &[u8]) -> Result<String, AsciiError> {
+    let mut trytes = String::with_capacity(bytes.len() * 2);
+
+    for byte in bytes {
+        if !byte.is_ascii() {
+            return Err(AsciiError::NonAscii(*byte));
+        }
+        trytes.push(TRYTE_ALPHABET[(byte % 27) as usize] as char);
+        trytes.push(TRYTE_ALPHABET[(byte / 27) as usize] as char);
+    }
+
+    Ok(trytes)
+}
+
+/// Decodes trytes produced by `ascii_to_trytes` back into ASCII bytes.
+pub fn trytes_to_ascii(trytes: &str) -> Result<Vec<u8>, AsciiError> {
+    let trytes: Vec<char> = trytes.chars().collect();
+
+    if trytes.len() % 2 != 0 {
+        return Err(AsciiError::OddLength(trytes.len()));
+    }
+
+    trytes
+        .chunks(2)
+        .map(|pair| {
+            let value = tryte_value(pair[0])? as u16 + tryte_value(pair[1])? as u16 * 27;
+
+            if value > 127 {
+                return Err(AsciiError::NonAscii(value as u8));
+            }
+
+            Ok(value as u8)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let data = b"Hello, IOTA! 0123456789 ~\x00\x7f";
+
+        assert_eq!(trytes_to_ascii(&ascii_to_trytes(data).unwrap()).unwrap(), data.to_vec());
+    }
+
+    #[test]
+    fn known_encoding() {
+        assert_eq!(ascii_to_trytes(b"IOTA").unwrap(), "SBYBCCKB");
+        assert_eq!(trytes_to_ascii("SBYBCCKB").unwrap(), b"IOTA".to_vec());
+    }
+
+    #[test]
+    fn non_ascii_rejected() {
+        assert_eq!(ascii_to_trytes("é".as_bytes()), Err(AsciiError::NonAscii(0xc3)));
+        assert_eq!(trytes_to_ascii("ZZ"), Err(AsciiError::NonAscii((26 + 26 * 27) as u8)));
+    }
+
+    #[test]
+    fn invalid_trytes_rejected() {
+        assert_eq!(trytes_to_ascii("A"), Err(AsciiError::OddLength(1)));
+        assert_eq!(trytes_to_ascii("A1"), Err(AsciiError::InvalidTryte('1')));
+    }
+}
@@ -10,11 +10,15 @@
 // See the License for the specific language governing permissions and limitations under the License.
 
 use crate::{
-    bundled::{Address, BundledTransaction, BundledTransactionField, BundledTransactions},
+    bundled::{
+        bundle::outgoing_bundle_builder::PAYLOAD_DATA_LEN, trytes_to_ascii, Address, AsciiError, BundledTransaction,
+        BundledTransactionField, BundledTransactions,
+    },
     Vertex,
 };
 
 use bee_crypto::ternary::Hash;
+use bee_ternary::T3B1Buf;
 
 use std::collections::HashMap;
 
@@ -71,6 +75,47 @@ impl Bundle {
 
         (false, diff)
     }
+
+    /// Returns the transactions of the bundle targeting `address`, along with their index in the bundle.
+    pub fn transactions_to_address(&self, address: &Address) -> Vec<(usize, &BundledTransaction)> {
+        self.into_iter()
+            .enumerate()
+            .filter(|(_, transaction)| transaction.address() == address)
+            .collect()
+    }
+
+    /// Returns the sum of the values of the transactions of the bundle targeting `address`.
+    pub fn value_to_address(&self, address: &Address) -> i64 {
+        self.transactions_to_address(address)
+            .iter()
+            .map(|(_, transaction)| *transaction.value.to_inner())
+            .sum()
+    }
+
+    /// Decodes the ASCII data carried by the payloads of a zero value bundle, as built by
+    /// `OutgoingBundleBuilder::data`. Trailing null bytes are considered padding and are not returned.
+    pub fn extract_data(&self) -> Result<Vec<u8>, AsciiError> {
+        let mut data = Vec::new();
+
+        for transaction in self {
+            let trytes: String = transaction
+                .payload()
+                .to_inner()
+                .encode::<T3B1Buf>()
+                .as_trytes()
+                .iter()
+                .take(PAYLOAD_DATA_LEN * 2)
+                .map(|tryte| char::from(*tryte))
+                .collect();
+            data.extend(trytes_to_ascii(&trytes)?);
+        }
+
+        while data.last() == Some(&0) {
+            data.pop();
+        }
+
+        Ok(data)
+    }
 }
 
 impl Vertex for Bundle {
@@ -118,4 +163,63 @@ impl std::ops::Index<usize> for Bundle {
 }
 
 #[cfg(test)]
-mod tests {}
+mod tests {
+
+    use super::*;
+
+    use crate::bundled::{BundledTransactionBuilder, Index, Nonce, Payload, Tag, Timestamp, Value};
+
+    use bee_ternary::{T1B1Buf, TryteBuf};
+
+    fn address(trytes: &str) -> Address {
+        Address::from_inner_unchecked(TryteBuf::try_from_str(trytes).unwrap().as_trits().encode::<T1B1Buf>())
+    }
+
+    fn transaction(index: usize, address: Address, value: i64) -> BundledTransaction {
+        BundledTransactionBuilder::new()
+            .with_payload(Payload::zeros())
+            .with_address(address)
+            .with_value(Value::from_inner_unchecked(value))
+            .with_obsolete_tag(Tag::zeros())
+            .with_timestamp(Timestamp::from_inner_unchecked(0))
+            .with_index(Index::from_inner_unchecked(index))
+            .with_last_index(Index::from_inner_unchecked(2))
+            .with_tag(Tag::zeros())
+            .with_attachment_ts(Timestamp::from_inner_unchecked(0))
+            .with_bundle(Hash::zeros())
+            .with_trunk(Hash::zeros())
+            .with_branch(Hash::zeros())
+            .with_attachment_lbts(Timestamp::from_inner_unchecked(0))
+            .with_attachment_ubts(Timestamp::from_inner_unchecked(0))
+            .with_nonce(Nonce::zeros())
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn transactions_and_value_to_address() {
+        let receiver = address(&"A".repeat(81));
+        let sender = address(&"B".repeat(81));
+        let bundle = Bundle(BundledTransactions(vec![
+            transaction(0, receiver.clone(), 10),
+            transaction(1, sender.clone(), -15),
+            transaction(2, receiver.clone(), 5),
+        ]));
+
+        let transactions = bundle.transactions_to_address(&receiver);
+
+        assert_eq!(transactions.len(), 2);
+        assert_eq!(transactions[0].0, 0);
+        assert_eq!(transactions[1].0, 2);
+        assert!(transactions
+            .iter()
+            .all(|(_, transaction)| transaction.address() == &receiver));
+        assert_eq!(bundle.value_to_address(&receiver), 15);
+
+        assert_eq!(bundle.transactions_to_address(&sender).len(), 1);
+        assert_eq!(bundle.value_to_address(&sender), -15);
+
+        assert!(bundle.transactions_to_address(&Address::zeros()).is_empty());
+        assert_eq!(bundle.value_to_address(&Address::zeros()), 0);
+    }
+}
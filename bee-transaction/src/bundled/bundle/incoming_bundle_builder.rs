@@ -21,18 +21,31 @@ use bee_crypto::ternary::{
 use bee_signing::ternary::{wots::WotsPublicKey, PublicKey, Signature};
 use bee_ternary::{T1B1Buf, TritBuf};
 
+use thiserror::Error;
+
 use std::marker::PhantomData;
 
-#[derive(Debug)]
+#[derive(Debug, Error)]
+#[non_exhaustive]
 pub enum IncomingBundleBuilderError {
+    #[error("bundle is empty")]
     Empty,
+    #[error("invalid transaction index: {0}")]
     InvalidIndex(usize),
+    #[error("invalid transaction last index: {0}")]
     InvalidLastIndex(usize),
+    #[error("invalid bundle value sum: {0}")]
     InvalidValue(i64),
+    #[error("bundle signature is invalid")]
     InvalidSignature,
+    #[error("bundle hash is invalid")]
     InvalidBundleHash,
+    #[error("bundle transactions have an invalid branch")]
     InvalidBranch,
+    #[error("bundle transactions have an invalid trunk")]
     InvalidTrunk,
+    #[error("transaction {0} doesn't satisfy the minimum weight magnitude")]
+    InvalidWeight(usize),
 }
 
 pub trait IncomingBundleBuilderStage {}
@@ -228,12 +241,33 @@ where
     }
 }
 
+/// Validates the transactions of a bundle, ordered from tail to head, and builds the bundle out of them.
+///
+/// On top of the checks of `IncomingBundleBuilder::validate`, the hash of every transaction must have a weight of at
+/// least `mwm`; a failing transaction is reported by its index in the bundle. An `mwm` of 0 skips the hashing of the
+/// transactions, for callers that already checked their weight.
+pub fn validate_bundle(transactions: &[BundledTransaction], mwm: u8) -> Result<Bundle, IncomingBundleBuilderError> {
+    let mut builder = IncomingBundleBuilder::default();
+
+    for (index, transaction) in transactions.iter().enumerate() {
+        if mwm > 0 && transaction.hash().weight() < mwm {
+            return Err(IncomingBundleBuilderError::InvalidWeight(index));
+        }
+
+        builder.push(transaction.clone());
+    }
+
+    Ok(builder.validate()?.build())
+}
+
 #[cfg(test)]
 mod tests {
 
     use super::*;
 
-    use crate::bundled::{Address, BundledTransactionBuilder, Index, Nonce, Payload, Tag, Timestamp, Value};
+    use crate::bundled::{
+        Address, BundledTransactionBuilder, Index, Nonce, OutgoingBundleBuilder, Payload, Tag, Timestamp, Value,
+    };
 
     use bee_crypto::ternary::Hash;
 
@@ -272,4 +306,70 @@ mod tests {
     //
     //     Ok(())
     // }
+
+    fn attached_bundle(mwm: u8) -> Bundle {
+        OutgoingBundleBuilder::data(Address::zeros(), Tag::zeros(), &[42; 4000])
+            .unwrap()
+            .seal()
+            .unwrap()
+            .attach_local(Hash::zeros(), Hash::zeros(), mwm)
+            .unwrap()
+            .build()
+            .unwrap()
+    }
+
+    fn transactions(bundle: &Bundle) -> Vec<BundledTransaction> {
+        bundle.into_iter().cloned().collect()
+    }
+
+    #[test]
+    fn validate_bundle_valid() {
+        let bundle = attached_bundle(1);
+        let transactions = transactions(&bundle);
+
+        let validated = validate_bundle(&transactions, 1).unwrap();
+
+        assert_eq!(validated.len(), transactions.len());
+        assert_eq!(validated.hash(), bundle.hash());
+    }
+
+    #[test]
+    fn validate_bundle_empty() {
+        assert!(matches!(
+            validate_bundle(&[], 1),
+            Err(IncomingBundleBuilderError::Empty)
+        ));
+    }
+
+    #[test]
+    fn validate_bundle_insufficient_weight() {
+        let transactions = transactions(&attached_bundle(1));
+
+        assert!(matches!(
+            validate_bundle(&transactions, 243),
+            Err(IncomingBundleBuilderError::InvalidWeight(0))
+        ));
+    }
+
+    #[test]
+    fn validate_bundle_missing_transaction() {
+        let mut transactions = transactions(&attached_bundle(1));
+
+        transactions.pop();
+
+        assert!(validate_bundle(&transactions, 1).is_err());
+    }
+
+    #[test]
+    fn validate_bundle_tampered_value() {
+        let mut transactions = transactions(&attached_bundle(1));
+
+        transactions[0].value = Value::from_inner_unchecked(1);
+        transactions[1].value = Value::from_inner_unchecked(-1);
+
+        assert!(matches!(
+            validate_bundle(&transactions, 0),
+            Err(IncomingBundleBuilderError::InvalidBundleHash)
+        ));
+    }
 }
@@ -14,5 +14,5 @@ mod incoming_bundle_builder;
 mod outgoing_bundle_builder;
 
 pub use bundle::Bundle;
-pub use incoming_bundle_builder::{IncomingBundleBuilder, IncomingBundleBuilderError};
+pub use incoming_bundle_builder::{validate_bundle, IncomingBundleBuilder, IncomingBundleBuilderError};
 pub use outgoing_bundle_builder::{OutgoingBundleBuilder, OutgoingBundleBuilderError};
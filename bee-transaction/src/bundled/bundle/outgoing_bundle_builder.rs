@@ -10,32 +10,84 @@
 // See the License for the specific language governing permissions and limitations under the License.
 
 use crate::bundled::{
-    constants::{IOTA_SUPPLY, PAYLOAD_TRIT_LEN},
-    Address, Bundle, BundledTransactionBuilder, BundledTransactionBuilders, BundledTransactionError,
-    BundledTransactionField, BundledTransactions, Index, Payload, Tag,
+    ascii_to_trytes,
+    constants::{
+        ATTACHMENT_TS_LOWER_BOUND, ATTACHMENT_TS_UPPER_BOUND, IOTA_SUPPLY, NONCE, PAYLOAD_TRIT_LEN,
+        TRANSACTION_TRIT_LEN,
+    },
+    Address, AsciiError, Bundle, BundledTransactionBuilder, BundledTransactionBuilders, BundledTransactionError,
+    BundledTransactionField, BundledTransactions, Index, Nonce, Payload, SignToFragments, Tag, Timestamp, Value,
 };
 
 use bee_crypto::ternary::{
-    sponge::{Kerl, Sponge},
+    sponge::{CurlP81, Kerl, Sponge},
     Hash,
 };
+use bee_pow::{Error as PowError, LocalPoW, NonceProvider, RemotePoW};
 use bee_signing::ternary::{
     seed::Seed,
     wots::{normalize, WotsSecurityLevel, WotsSpongePrivateKeyGeneratorBuilder},
-    PrivateKey, PrivateKeyGenerator, Signature,
+    PrivateKeyGenerator,
 };
-use bee_ternary::Btrit;
+use bee_ternary::{Btrit, T1B1Buf, TritBuf, TryteBuf};
 
-use std::marker::PhantomData;
+use thiserror::Error;
 
-#[derive(Debug)]
+use std::{
+    marker::PhantomData,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Number of ASCII bytes a transaction payload can carry, each byte being encoded in two trytes.
+pub(crate) const PAYLOAD_DATA_LEN: usize = PAYLOAD_TRIT_LEN / 3 / 2;
+
+/// Maximum number of bundle hashes computed while looking for one without the M-bug.
+const MAX_BUNDLE_HASH_ATTEMPTS: usize = 10_000;
+
+#[derive(Debug, Error)]
+#[non_exhaustive]
 pub enum OutgoingBundleBuilderError {
+    #[error("bundle is empty")]
     Empty,
+    #[error("bundle has an unsigned input")]
     UnsignedInput,
+    #[error("invalid bundle value sum: {0}")]
     InvalidValue(i64),
+    #[error("missing transaction builder field {0}")]
     MissingTransactionBuilderField(&'static str),
-    TransactionError(BundledTransactionError),
+    #[error("building a transaction failed: {0}")]
+    TransactionError(#[from] BundledTransactionError),
+    #[error("signing the bundle failed")]
     FailedSigningOperation,
+    #[error("proof of work failed: {0}")]
+    FailedProofOfWork(#[from] PowError),
+    #[error("invalid data: {0}")]
+    InvalidData(#[from] AsciiError),
+    #[error("obsolete tag overflowed")]
+    ObsoleteTagOverflow,
+    #[error("no bundle hash without the M-bug found after {0} attempts")]
+    BundleHashAttemptsExhausted(usize),
+}
+
+/// Increments balanced trits by one, the least significant trit coming first.
+/// If all trits were `PlusOne`, they wrap around to `NegOne` and an overflow error is returned.
+fn increment_trits(trits: &mut TritBuf<T1B1Buf>) -> Result<(), OutgoingBundleBuilderError> {
+    for i in 0..trits.len() {
+        // Safe to unwrap since it's in the range of the buffer.
+        match trits.get(i).unwrap() {
+            Btrit::NegOne => {
+                trits.set(i, Btrit::Zero);
+                return Ok(());
+            }
+            Btrit::Zero => {
+                trits.set(i, Btrit::PlusOne);
+                return Ok(());
+            }
+            Btrit::PlusOne => trits.set(i, Btrit::NegOne),
+        };
+    }
+
+    Err(OutgoingBundleBuilderError::ObsoleteTagOverflow)
 }
 
 pub trait OutgoingBundleBuilderStage {}
@@ -80,7 +132,14 @@ where
             _ => return Err(OutgoingBundleBuilderError::Empty),
         };
 
+        let mut attempts = 0;
+
         let hash = loop {
+            if attempts == MAX_BUNDLE_HASH_ATTEMPTS {
+                return Err(OutgoingBundleBuilderError::BundleHashAttemptsExhausted(attempts));
+            }
+            attempts += 1;
+
             sponge.reset();
 
             for builder in &self.builders.0 {
@@ -113,22 +172,9 @@ where
             if !has_m_bug {
                 break Hash::from_inner_unchecked(hash);
             } else {
-                // obsolete_tag + 1
                 // TODO we may want to move this operation to the ternary crate
-                for i in 0..obsolete_tag.len() {
-                    // Safe to unwrap since it's in the range of tag
-                    match obsolete_tag.get(i).unwrap() {
-                        Btrit::NegOne => {
-                            obsolete_tag.set(i, Btrit::Zero);
-                            break;
-                        }
-                        Btrit::Zero => {
-                            obsolete_tag.set(i, Btrit::PlusOne);
-                            break;
-                        }
-                        Btrit::PlusOne => obsolete_tag.set(i, Btrit::NegOne),
-                    };
-                }
+                // Wrapping around would retry tags that were already tried.
+                increment_trits(&mut obsolete_tag)?;
                 // Safe to unwrap because we already check first tx exists.
                 self.builders
                     .0
@@ -158,6 +204,44 @@ impl<E: Sponge + Default> Default for StagedOutgoingBundleBuilder<E, OutgoingRaw
 }
 
 impl<E: Sponge + Default> StagedOutgoingBundleBuilder<E, OutgoingRaw> {
+    /// Creates a zero value bundle carrying ASCII `data` to `address`, split across as many transactions as needed.
+    pub fn data(address: Address, tag: Tag, data: &[u8]) -> Result<Self, OutgoingBundleBuilderError> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("Clock may have gone backwards")
+            .as_secs();
+        // An empty message still needs a transaction to carry the tag.
+        let chunks: Vec<&[u8]> = if data.is_empty() {
+            vec![&[]]
+        } else {
+            data.chunks(PAYLOAD_DATA_LEN).collect()
+        };
+        let mut bundle_builder = Self::default();
+
+        for chunk in chunks {
+            let mut trytes = ascii_to_trytes(chunk)?;
+            trytes.extend(std::iter::repeat('9').take(PAYLOAD_TRIT_LEN / 3 - trytes.len()));
+            // Safe to unwrap since the trytes come from the tryte alphabet and fill a payload.
+            let payload =
+                Payload::from_inner_unchecked(TryteBuf::try_from_str(&trytes).unwrap().as_trits().encode::<T1B1Buf>());
+
+            bundle_builder.push(
+                BundledTransactionBuilder::new()
+                    .with_payload(payload)
+                    .with_address(address.clone())
+                    .with_value(Value::from_inner_unchecked(0))
+                    .with_obsolete_tag(tag.clone())
+                    .with_timestamp(Timestamp::from_inner_unchecked(timestamp))
+                    .with_tag(tag.clone())
+                    .with_attachment_ts(Timestamp::from_inner_unchecked(0))
+                    .with_attachment_lbts(Timestamp::from_inner_unchecked(0))
+                    .with_attachment_ubts(Timestamp::from_inner_unchecked(0)),
+            );
+        }
+
+        Ok(bundle_builder)
+    }
+
     // TODO TEST
     pub fn push(&mut self, builder: BundledTransactionBuilder) {
         self.builders.push(builder);
@@ -166,7 +250,6 @@ impl<E: Sponge + Default> StagedOutgoingBundleBuilder<E, OutgoingRaw> {
     // TODO TEST
     pub fn seal(mut self) -> Result<StagedOutgoingBundleBuilder<E, OutgoingSealed>, OutgoingBundleBuilderError> {
         // TODO Impl
-        // TODO should call validate() on transaction builders ?
         let mut sum: i64 = 0;
         let last_index = self.builders.len() - 1;
 
@@ -183,10 +266,15 @@ impl<E: Sponge + Default> StagedOutgoingBundleBuilder<E, OutgoingRaw> {
                 return Err(OutgoingBundleBuilderError::MissingTransactionBuilderField("value"));
             } else if builder.tag.is_none() {
                 return Err(OutgoingBundleBuilderError::MissingTransactionBuilderField("tag"));
+            } else if builder.obsolete_tag.is_none() {
+                return Err(OutgoingBundleBuilderError::MissingTransactionBuilderField(
+                    "obsolete_tag",
+                ));
             }
 
             builder.index.replace(Index::from_inner_unchecked(index));
             builder.last_index.replace(Index::from_inner_unchecked(last_index));
+            builder.validate()?;
 
             // Safe to unwrap since we just checked it's not None
             sum += builder.value.as_ref().unwrap().to_inner();
@@ -221,11 +309,12 @@ impl<E: Sponge + Default> StagedOutgoingBundleBuilder<E, OutgoingSealed> {
         Ok(())
     }
 
-    // TODO TEST
-    pub fn attach_local(
+    pub fn attach<P: NonceProvider + ?Sized>(
         self,
         trunk: Hash,
         branch: Hash,
+        mwm: u8,
+        provider: &P,
     ) -> Result<StagedOutgoingBundleBuilder<E, OutgoingAttached>, OutgoingBundleBuilderError> {
         // Checking that no transaction actually needs to be signed (no inputs)
         self.has_no_input()?;
@@ -234,7 +323,16 @@ impl<E: Sponge + Default> StagedOutgoingBundleBuilder<E, OutgoingSealed> {
             builders: self.builders,
             marker: PhantomData,
         }
-        .attach_local(trunk, branch)
+        .attach(trunk, branch, mwm, provider)
+    }
+
+    pub fn attach_local(
+        self,
+        trunk: Hash,
+        branch: Hash,
+        mwm: u8,
+    ) -> Result<StagedOutgoingBundleBuilder<E, OutgoingAttached>, OutgoingBundleBuilderError> {
+        self.attach(trunk, branch, mwm, &LocalPoW::default())
     }
 
     // TODO TEST
@@ -242,15 +340,10 @@ impl<E: Sponge + Default> StagedOutgoingBundleBuilder<E, OutgoingSealed> {
         self,
         trunk: Hash,
         branch: Hash,
+        mwm: u8,
+        remote: &RemotePoW,
     ) -> Result<StagedOutgoingBundleBuilder<E, OutgoingAttached>, OutgoingBundleBuilderError> {
-        // Checking that no transaction actually needs to be signed (no inputs)
-        self.has_no_input()?;
-
-        StagedOutgoingBundleBuilder::<E, OutgoingSigned> {
-            builders: self.builders,
-            marker: PhantomData,
-        }
-        .attach_remote(trunk, branch)
+        self.attach(trunk, branch, mwm, remote)
     }
 
     // TODO TEST
@@ -274,16 +367,13 @@ impl<E: Sponge + Default> StagedOutgoingBundleBuilder<E, OutgoingSealed> {
                 // Safe to unwrap because security level is provided
                 .unwrap();
             // Create subseed and then sign the message
-            let signature = key_generator
+            let fragments = key_generator
                 .generate_from_seed(seed, *index)
                 .map_err(|_| OutgoingBundleBuilderError::FailedSigningOperation)?
-                .sign(&message)
+                .sign_to_fragments(&message)
                 .map_err(|_| OutgoingBundleBuilderError::FailedSigningOperation)?;
 
-            // Split signature into fragments
-            for fragment in signature.as_trits().chunks(PAYLOAD_TRIT_LEN) {
-                signature_fragments.push(Payload::from_inner_unchecked(fragment.to_owned()));
-            }
+            signature_fragments.extend(fragments.into_iter().map(Payload::from_inner_unchecked));
         }
 
         // Find the first input tx
@@ -310,30 +400,82 @@ impl<E: Sponge + Default> StagedOutgoingBundleBuilder<E, OutgoingSealed> {
 }
 
 impl<E: Sponge + Default> StagedOutgoingBundleBuilder<E, OutgoingSigned> {
-    // TODO TEST
-    pub fn attach_local(
-        self,
-        _trunk: Hash,
-        _branch: Hash,
+    /// Chains the transactions from the head to the tail, the head approving `trunk` and `branch` and every other
+    /// transaction approving the next one and `trunk`, and finds their nonces with `provider`.
+    ///
+    /// The attachment timestamp is set to the current time in milliseconds, within the protocol bounds.
+    pub fn attach<P: NonceProvider + ?Sized>(
+        mut self,
+        trunk: Hash,
+        branch: Hash,
+        mwm: u8,
+        provider: &P,
     ) -> Result<StagedOutgoingBundleBuilder<E, OutgoingAttached>, OutgoingBundleBuilderError> {
-        // TODO Impl
+        let mut next: Option<Hash> = None;
+        let attachment_ts = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("Clock may have gone backwards")
+            .as_millis() as u64;
+
+        for builder in self.builders.0.iter_mut().rev() {
+            match next {
+                Some(next) => {
+                    builder.trunk.replace(next);
+                    builder.branch.replace(trunk);
+                }
+                None => {
+                    builder.trunk.replace(trunk);
+                    builder.branch.replace(branch);
+                }
+            }
+            // Now that the parents are known, a zero timestamp can be told apart from the genesis one.
+            builder.validate()?;
+            builder
+                .attachment_ts
+                .replace(Timestamp::from_inner_unchecked(attachment_ts));
+            builder
+                .attachment_lbts
+                .replace(Timestamp::from_inner_unchecked(ATTACHMENT_TS_LOWER_BOUND));
+            builder
+                .attachment_ubts
+                .replace(Timestamp::from_inner_unchecked(ATTACHMENT_TS_UPPER_BOUND));
+            builder.nonce.replace(Nonce::zeros());
+
+            let mut trits = TritBuf::<T1B1Buf>::zeros(TRANSACTION_TRIT_LEN);
+            builder.clone().build()?.as_trits_allocated(&mut trits);
+
+            let nonce = provider.provide(&trits, mwm)?.to_trit_buf();
+            trits[NONCE.trit_offset.start..][..NONCE.trit_offset.length].copy_from(&nonce);
+
+            // Safe to unwrap since the input has a valid transaction length.
+            next.replace(Hash::from_inner_unchecked(CurlP81::default().digest(&trits).unwrap()));
+            builder.nonce.replace(Nonce::from_inner_unchecked(nonce));
+        }
+
         Ok(StagedOutgoingBundleBuilder::<E, OutgoingAttached> {
             builders: self.builders,
             marker: PhantomData,
         })
     }
 
+    pub fn attach_local(
+        self,
+        trunk: Hash,
+        branch: Hash,
+        mwm: u8,
+    ) -> Result<StagedOutgoingBundleBuilder<E, OutgoingAttached>, OutgoingBundleBuilderError> {
+        self.attach(trunk, branch, mwm, &LocalPoW::default())
+    }
+
     // TODO TEST
     pub fn attach_remote(
         self,
-        _trunk: Hash,
-        _branch: Hash,
+        trunk: Hash,
+        branch: Hash,
+        mwm: u8,
+        remote: &RemotePoW,
     ) -> Result<StagedOutgoingBundleBuilder<E, OutgoingAttached>, OutgoingBundleBuilderError> {
-        // TODO Impl
-        Ok(StagedOutgoingBundleBuilder::<E, OutgoingAttached> {
-            builders: self.builders,
-            marker: PhantomData,
-        })
+        self.attach(trunk, branch, mwm, remote)
     }
 }
 
@@ -343,11 +485,7 @@ impl<E: Sponge + Default> StagedOutgoingBundleBuilder<E, OutgoingAttached> {
         let mut transactions = BundledTransactions::new();
 
         for transaction_builder in self.builders.0 {
-            transactions.push(
-                transaction_builder
-                    .build()
-                    .map_err(OutgoingBundleBuilderError::TransactionError)?,
-            );
+            transactions.push(transaction_builder.build()?);
         }
 
         Ok(Bundle(transactions))
@@ -359,10 +497,15 @@ mod tests {
 
     use super::*;
 
-    use crate::bundled::{Address, Nonce, Payload, Tag, Timestamp, Value};
+    use crate::{
+        bundled::{Address, Nonce, Payload, Tag, Timestamp, Value},
+        Vertex,
+    };
 
-    use bee_signing::ternary::{seed::Seed, wots::WotsSignature, PublicKey, RecoverableSignature};
-    use bee_ternary::{T1B1Buf, TritBuf};
+    use bee_signing::ternary::{
+        seed::Seed, wots::WotsSignature, PrivateKey, PublicKey, RecoverableSignature, Signature,
+    };
+    use bee_ternary::{Trits, TryteBuf, T1B1};
 
     fn default_transaction_builder(index: usize, last_index: usize) -> BundledTransactionBuilder {
         BundledTransactionBuilder::new()
@@ -370,7 +513,7 @@ mod tests {
             .with_address(Address::zeros())
             .with_value(Value::from_inner_unchecked(0))
             .with_obsolete_tag(Tag::zeros())
-            .with_timestamp(Timestamp::from_inner_unchecked(0))
+            .with_timestamp(Timestamp::from_inner_unchecked(1))
             .with_index(Index::from_inner_unchecked(index))
             .with_last_index(Index::from_inner_unchecked(last_index))
             .with_tag(Tag::zeros())
@@ -411,7 +554,7 @@ mod tests {
         let bundle = bundle_builder
             .seal()?
             .sign(&seed, &[(0, address.clone(), security)])?
-            .attach_local(Hash::zeros(), Hash::zeros())?
+            .attach_local(Hash::zeros(), Hash::zeros(), 1)?
             .build()?;
         assert_eq!(bundle.len(), bundle_size);
 
@@ -493,7 +636,7 @@ mod tests {
                     (1, address_medium.clone(), WotsSecurityLevel::Medium),
                 ],
             )?
-            .attach_local(Hash::zeros(), Hash::zeros())?
+            .attach_local(Hash::zeros(), Hash::zeros(), 1)?
             .build()?;
         assert_eq!(bundle.len(), bundle_size);
 
@@ -531,6 +674,89 @@ mod tests {
         bundle_builder_different_security_check()
     }
 
+    #[test]
+    fn outgoing_bundle_builder_attach_test() -> Result<(), OutgoingBundleBuilderError> {
+        let bundle_size = 3;
+        let mwm = 3;
+        let mut bundle_builder = OutgoingBundleBuilder::default();
+        let trunk = Hash::from_inner_unchecked(
+            TryteBuf::try_from_str(&"A".repeat(81))
+                .unwrap()
+                .as_trits()
+                .encode::<T1B1Buf>(),
+        );
+        let branch = Hash::from_inner_unchecked(
+            TryteBuf::try_from_str(&"B".repeat(81))
+                .unwrap()
+                .as_trits()
+                .encode::<T1B1Buf>(),
+        );
+
+        for i in 0..bundle_size {
+            bundle_builder.push(default_transaction_builder(i, bundle_size - 1));
+        }
+
+        let bundle = bundle_builder.seal()?.attach_local(trunk, branch, mwm)?.build()?;
+
+        let transactions = (0..bundle_size)
+            .map(|index| bundle.0.get(index).unwrap())
+            .collect::<Vec<_>>();
+        let hashes = transactions
+            .iter()
+            .map(|transaction| {
+                let mut trits = TritBuf::<T1B1Buf>::zeros(TRANSACTION_TRIT_LEN);
+                transaction.as_trits_allocated(&mut trits);
+                Hash::from_inner_unchecked(CurlP81::default().digest(&trits).unwrap())
+            })
+            .collect::<Vec<Hash>>();
+
+        for (index, transaction) in transactions.iter().enumerate() {
+            assert!(hashes[index].weight() >= mwm);
+            assert_eq!(*transaction.attachment_lbts().to_inner(), ATTACHMENT_TS_LOWER_BOUND);
+            assert_eq!(*transaction.attachment_ubts().to_inner(), ATTACHMENT_TS_UPPER_BOUND);
+            assert!(*transaction.attachment_ts().to_inner() > ATTACHMENT_TS_LOWER_BOUND);
+            assert!(*transaction.attachment_ts().to_inner() <= ATTACHMENT_TS_UPPER_BOUND);
+            if index == bundle_size - 1 {
+                assert_eq!(*transaction.trunk(), trunk);
+                assert_eq!(*transaction.branch(), branch);
+            } else {
+                assert_eq!(*transaction.trunk(), hashes[index + 1]);
+                assert_eq!(*transaction.branch(), trunk);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn outgoing_bundle_builder_zero_timestamp() -> Result<(), OutgoingBundleBuilderError> {
+        let trunk = Hash::from_inner_unchecked(
+            TryteBuf::try_from_str(&"A".repeat(81))
+                .unwrap()
+                .as_trits()
+                .encode::<T1B1Buf>(),
+        );
+        let mut bundle_builder = OutgoingBundleBuilder::default();
+        bundle_builder.push(default_transaction_builder(0, 0).with_timestamp(Timestamp::from_inner_unchecked(0)));
+
+        match bundle_builder.seal()?.attach_local(trunk, Hash::zeros(), 1) {
+            Err(OutgoingBundleBuilderError::TransactionError(BundledTransactionError::ZeroTimestamp)) => (),
+            _ => unreachable!(),
+        }
+
+        // Only the genesis, approving the null hash twice, is allowed to have no timestamp.
+        let mut bundle_builder = OutgoingBundleBuilder::default();
+        bundle_builder.push(default_transaction_builder(0, 0).with_timestamp(Timestamp::from_inner_unchecked(0)));
+
+        assert!(bundle_builder
+            .seal()?
+            .attach_local(Hash::zeros(), Hash::zeros(), 1)?
+            .build()
+            .is_ok());
+
+        Ok(())
+    }
+
     // TODO Also check to sign if data ?
     #[test]
     fn outgoing_bundle_builder_data_test() -> Result<(), OutgoingBundleBuilderError> {
@@ -543,11 +769,127 @@ mod tests {
 
         let bundle = bundle_builder
             .seal()?
-            .attach_local(Hash::zeros(), Hash::zeros())?
+            .attach_local(Hash::zeros(), Hash::zeros(), 1)?
             .build()?;
 
         assert_eq!(bundle.len(), bundle_size);
 
         Ok(())
     }
+
+    fn data_round_trip(data: &[u8]) -> Result<usize, OutgoingBundleBuilderError> {
+        let bundle = OutgoingBundleBuilder::data(Address::zeros(), Tag::zeros(), data)?
+            .seal()?
+            .attach_local(Hash::zeros(), Hash::zeros(), 1)?
+            .build()?;
+
+        assert_eq!(bundle.extract_data().unwrap(), data.to_vec());
+
+        Ok(bundle.len())
+    }
+
+    #[test]
+    fn outgoing_bundle_builder_data_round_trip() -> Result<(), OutgoingBundleBuilderError> {
+        assert_eq!(data_round_trip(b"Hello, IOTA!")?, 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn outgoing_bundle_builder_data_round_trip_empty() -> Result<(), OutgoingBundleBuilderError> {
+        assert_eq!(data_round_trip(b"")?, 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn outgoing_bundle_builder_data_round_trip_multiple_transactions() -> Result<(), OutgoingBundleBuilderError> {
+        let data: Vec<u8> = (0..PAYLOAD_DATA_LEN * 2 + 10).map(|i| b'a' + (i % 26) as u8).collect();
+
+        assert_eq!(data_round_trip(&data)?, 3);
+        assert_eq!(data_round_trip(&data[..PAYLOAD_DATA_LEN * 2])?, 2);
+
+        Ok(())
+    }
+
+    fn trits(trits: &[i8]) -> TritBuf<T1B1Buf> {
+        Trits::<T1B1>::try_from_raw(trits, trits.len()).unwrap().to_buf()
+    }
+
+    fn trytes(trytes: &str) -> TritBuf<T1B1Buf> {
+        TryteBuf::try_from_str(trytes).unwrap().as_trits().encode()
+    }
+
+    #[test]
+    fn increment_trits_carry() -> Result<(), OutgoingBundleBuilderError> {
+        for (before, after) in &[
+            (vec![-1, 0, 0], vec![0, 0, 0]),
+            (vec![0, 0, 0], vec![1, 0, 0]),
+            (vec![1, 0, 0], vec![-1, 1, 0]),
+            (vec![1, 1, -1], vec![-1, -1, 0]),
+            (vec![1, 1, 0], vec![-1, -1, 1]),
+        ] {
+            let mut incremented = trits(before);
+            increment_trits(&mut incremented)?;
+            assert_eq!(incremented, trits(after));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn increment_trits_overflow() {
+        // `M` is made of three `PlusOne` trits and `N` of three `NegOne` trits.
+        let mut tag = trytes(&"M".repeat(Tag::trit_len() / 3));
+
+        match increment_trits(&mut tag) {
+            Err(OutgoingBundleBuilderError::ObsoleteTagOverflow) => (),
+            _ => unreachable!(),
+        }
+        assert_eq!(tag, trytes(&"N".repeat(Tag::trit_len() / 3)));
+    }
+
+    #[test]
+    fn outgoing_bundle_builder_missing_obsolete_tag() {
+        let mut bundle_builder = OutgoingBundleBuilder::default();
+
+        bundle_builder.push(default_transaction_builder(0, 1));
+        bundle_builder.push(
+            BundledTransactionBuilder::new()
+                .with_payload(Payload::zeros())
+                .with_address(Address::zeros())
+                .with_value(Value::from_inner_unchecked(0))
+                .with_timestamp(Timestamp::from_inner_unchecked(1))
+                .with_tag(Tag::zeros()),
+        );
+
+        match bundle_builder.seal() {
+            Err(OutgoingBundleBuilderError::MissingTransactionBuilderField("obsolete_tag")) => (),
+            _ => panic!("sealing without an obsolete tag should fail"),
+        }
+    }
+
+    #[test]
+    fn outgoing_bundle_builder_data_non_ascii() {
+        match OutgoingBundleBuilder::data(Address::zeros(), Tag::zeros(), "Grüße".as_bytes()) {
+            Err(OutgoingBundleBuilderError::InvalidData(AsciiError::NonAscii(0xc3))) => (),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn outgoing_bundle_builder_error_display_and_source() {
+        let error = OutgoingBundleBuilderError::from(AsciiError::NonAscii(0xc3));
+
+        assert_eq!(error.to_string(), "invalid data: byte 0xc3 is not ASCII");
+        assert_eq!(
+            OutgoingBundleBuilderError::MissingTransactionBuilderField("obsolete_tag").to_string(),
+            "missing transaction builder field obsolete_tag"
+        );
+        assert_eq!(
+            std::error::Error::source(&error).and_then(|source| source.downcast_ref::<AsciiError>()),
+            Some(&AsciiError::NonAscii(0xc3))
+        );
+        assert!(std::error::Error::source(&OutgoingBundleBuilderError::Empty).is_none());
+    }
 }
@@ -51,11 +51,20 @@ macro_rules! offsets_from_previous_field {
     };
 }
 
+/// Returns the number of bytes needed to pack `trit_len` trits 5 per byte (T5B1), the last byte being possibly
+/// incomplete.
+pub const fn t5b1_byte_len(trit_len: usize) -> usize {
+    (trit_len + 4) / 5
+}
+
 pub const IOTA_SUPPLY: i64 = 2_779_530_283_277_761;
 
+/// Number of trits of a transaction, the sum of the lengths of its fields.
 pub const TRANSACTION_TRIT_LEN: usize = 8019;
-pub const TRANSACTION_TRYT_LEN: usize = TRANSACTION_TRIT_LEN / 3; // 2673
-pub const TRANSACTION_BYTE_LEN: usize = TRANSACTION_TRIT_LEN / 5 + 1; // 1604
+/// Number of trytes of a transaction, 3 trits each: 2673.
+pub const TRANSACTION_TRYT_LEN: usize = TRANSACTION_TRIT_LEN / 3;
+/// Number of T5B1 bytes of a transaction, the maximum size of a transaction on the wire: 1604.
+pub const TRANSACTION_BYTE_LEN: usize = t5b1_byte_len(TRANSACTION_TRIT_LEN);
 
 pub const PAYLOAD_TRIT_LEN: usize = 6561;
 pub const ADDRESS_TRIT_LEN: usize = 243;
@@ -65,10 +74,30 @@ pub const TIMESTAMP_TRIT_LEN: usize = 27;
 pub const INDEX_TRIT_LEN: usize = 27;
 pub const HASH_TRIT_LEN: usize = 243;
 pub const NONCE_TRIT_LEN: usize = 81;
+/// Number of trits of an address checksum, the last 9 trytes of the Kerl hash of the address.
+pub const ADDRESS_CHECKSUM_TRIT_LEN: usize = 27;
+
+/// Lower bound of the attachment timestamp, in milliseconds, set when attaching a transaction.
+pub const ATTACHMENT_TS_LOWER_BOUND: u64 = 0;
+/// Upper bound of the attachment timestamp, in milliseconds, set when attaching a transaction: the largest value a
+/// timestamp field can hold, (3^27 - 1) / 2.
+pub const ATTACHMENT_TS_UPPER_BOUND: u64 = 3_812_798_742_493;
 
 pub const ESSENCE_TRIT_LEN: usize =
     ADDRESS_TRIT_LEN + VALUE_TRIT_LEN + TAG_TRIT_LEN + TIMESTAMP_TRIT_LEN + INDEX_TRIT_LEN + INDEX_TRIT_LEN;
 
+/// Number of T5B1 bytes of a hash, its size on the wire: 49.
+pub const HASH_BYTE_LEN: usize = t5b1_byte_len(HASH_TRIT_LEN);
+/// Number of T5B1 bytes of an address: 49.
+pub const ADDRESS_BYTE_LEN: usize = t5b1_byte_len(ADDRESS_TRIT_LEN);
+/// Number of leading T5B1 bytes of a transaction that only hold payload trits: 1312.
+///
+/// The payload comes first, so these are the bytes that may be trimmed when they are zeros.
+pub const PAYLOAD_BYTE_LEN: usize = PAYLOAD_TRIT_LEN / 5;
+/// Number of trailing T5B1 bytes of a transaction that hold trits other than payload ones, the minimum size of a
+/// transaction on the wire: 292.
+pub const NON_PAYLOAD_BYTE_LEN: usize = TRANSACTION_BYTE_LEN - PAYLOAD_BYTE_LEN;
+
 pub(crate) const PAYLOAD: Field = offsets_from_trits!(0, PAYLOAD_TRIT_LEN);
 pub(crate) const ADDRESS: Field = offsets_from_previous_field!(PAYLOAD, ADDRESS_TRIT_LEN);
 pub(crate) const VALUE: Field = offsets_from_previous_field!(ADDRESS, VALUE_TRIT_LEN);
@@ -85,6 +114,12 @@ pub(crate) const ATTACHMENT_LBTS: Field = offsets_from_previous_field!(ATTACHMEN
 pub(crate) const ATTACHMENT_UBTS: Field = offsets_from_previous_field!(ATTACHMENT_LBTS, TIMESTAMP_TRIT_LEN);
 pub(crate) const NONCE: Field = offsets_from_previous_field!(ATTACHMENT_UBTS, NONCE_TRIT_LEN);
 
+const _: () = assert!(NONCE.trit_offset.start + NONCE.trit_offset.length == TRANSACTION_TRIT_LEN);
+const _: () = assert!(TRANSACTION_TRYT_LEN * 3 == TRANSACTION_TRIT_LEN);
+const _: () = assert!(HASH_BYTE_LEN == ADDRESS_BYTE_LEN);
+const _: () = assert!(PAYLOAD_BYTE_LEN * 5 <= PAYLOAD_TRIT_LEN);
+const _: () = assert!(NON_PAYLOAD_BYTE_LEN * 5 >= TRANSACTION_TRIT_LEN - PAYLOAD_TRIT_LEN);
+
 #[cfg(test)]
 mod tests {
 
@@ -131,4 +166,18 @@ mod tests {
 
         assert_eq!(total_tryte_length, TRANSACTION_TRYT_LEN);
     }
+
+    #[test]
+    fn byte_lengths() {
+        assert_eq!(t5b1_byte_len(0), 0);
+        assert_eq!(t5b1_byte_len(5), 1);
+        assert_eq!(t5b1_byte_len(6), 2);
+
+        assert_eq!(HASH_BYTE_LEN, 49);
+        assert_eq!(ADDRESS_BYTE_LEN, 49);
+        assert_eq!(TRANSACTION_BYTE_LEN, 1604);
+        assert_eq!(PAYLOAD_BYTE_LEN, 1312);
+        assert_eq!(NON_PAYLOAD_BYTE_LEN, 292);
+        assert_eq!(PAYLOAD_BYTE_LEN + NON_PAYLOAD_BYTE_LEN, TRANSACTION_BYTE_LEN);
+    }
 }
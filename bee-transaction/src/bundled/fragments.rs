@@ -0,0 +1,141 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+//! Signatures split into the fragments carried by the payloads of the input transactions of a bundle.
+
+use crate::bundled::constants::PAYLOAD_TRIT_LEN;
+
+use bee_signing::ternary::{PrivateKey, PublicKey, Signature};
+use bee_ternary::{T1B1Buf, TritBuf, Trits};
+
+/// Signing into fragments of `PAYLOAD_TRIT_LEN` trits, one per security level of the key.
+pub trait SignToFragments: PrivateKey {
+    /// Signs `message` and splits the signature into its fragments, in the order of the input transactions.
+    fn sign_to_fragments(&mut self, message: &Trits) -> Result<Vec<TritBuf>, Self::Error>;
+}
+
+impl<K: PrivateKey> SignToFragments for K {
+    fn sign_to_fragments(&mut self, message: &Trits) -> Result<Vec<TritBuf>, Self::Error> {
+        Ok(self
+            .sign(message)?
+            .as_trits()
+            .chunks(PAYLOAD_TRIT_LEN)
+            .map(|fragment| fragment.to_owned())
+            .collect())
+    }
+}
+
+/// Verification of a signature given as the fragments produced by `SignToFragments`.
+pub trait VerifyFromFragments: PublicKey {
+    /// Reassembles the signature from `fragments` and verifies it against `message`.
+    ///
+    /// Fails if the reassembled signature doesn't have the length of a signature.
+    fn verify_from_fragments(&self, fragments: &[TritBuf], message: &Trits) -> Result<bool, Self::Error>;
+}
+
+impl<P> VerifyFromFragments for P
+where
+    P: PublicKey,
+    P::Signature: Signature<Error = P::Error>,
+{
+    fn verify_from_fragments(&self, fragments: &[TritBuf], message: &Trits) -> Result<bool, Self::Error> {
+        let mut signature = TritBuf::<T1B1Buf>::zeros(fragments.iter().map(|fragment| fragment.len()).sum());
+        let mut offset = 0;
+
+        for fragment in fragments {
+            signature[offset..][..fragment.len()].copy_from(fragment);
+            offset += fragment.len();
+        }
+
+        self.verify(message, &P::Signature::from_trits(signature)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use crate::bundled::constants::HASH_TRIT_LEN;
+
+    use bee_crypto::ternary::sponge::Kerl;
+    use bee_signing::ternary::{
+        seed::Seed,
+        wots::{normalize, WotsPrivateKey, WotsSecurityLevel, WotsSpongePrivateKeyGeneratorBuilder},
+        PrivateKeyGenerator,
+    };
+
+    fn private_key(security: WotsSecurityLevel) -> WotsPrivateKey<Kerl> {
+        WotsSpongePrivateKeyGeneratorBuilder::<Kerl>::default()
+            .with_security_level(security)
+            .build()
+            .unwrap()
+            .generate_from_seed(&Seed::rand(), 0)
+            .unwrap()
+    }
+
+    fn message() -> TritBuf {
+        normalize(&TritBuf::<T1B1Buf>::zeros(HASH_TRIT_LEN)).unwrap()
+    }
+
+    #[test]
+    fn fragments_verified() {
+        for (security, count) in [
+            (WotsSecurityLevel::Low, 1),
+            (WotsSecurityLevel::Medium, 2),
+            (WotsSecurityLevel::High, 3),
+        ]
+        .iter()
+        {
+            let mut private_key = private_key(*security);
+            let public_key = private_key.generate_public_key().unwrap();
+            let fragments = private_key.sign_to_fragments(&message()).unwrap();
+
+            assert_eq!(fragments.len(), *count);
+            assert!(fragments.iter().all(|fragment| fragment.len() == PAYLOAD_TRIT_LEN));
+            assert!(public_key.verify_from_fragments(&fragments, &message()).unwrap());
+        }
+    }
+
+    #[test]
+    fn fragments_match_signature() {
+        let mut private_key = private_key(WotsSecurityLevel::Medium);
+        let signature = private_key.sign(&message()).unwrap();
+        let fragments = private_key.sign_to_fragments(&message()).unwrap();
+
+        assert_eq!(&fragments[0], &signature.as_trits()[..PAYLOAD_TRIT_LEN]);
+        assert_eq!(&fragments[1], &signature.as_trits()[PAYLOAD_TRIT_LEN..]);
+    }
+
+    #[test]
+    fn swapped_fragments_not_verified() {
+        let mut private_key = private_key(WotsSecurityLevel::Medium);
+        let public_key = private_key.generate_public_key().unwrap();
+        let mut fragments = private_key.sign_to_fragments(&message()).unwrap();
+
+        fragments.swap(0, 1);
+
+        assert!(!public_key.verify_from_fragments(&fragments, &message()).unwrap());
+    }
+
+    #[test]
+    fn missing_fragment_not_verified() {
+        let mut private_key = private_key(WotsSecurityLevel::Medium);
+        let public_key = private_key.generate_public_key().unwrap();
+        let mut fragments = private_key.sign_to_fragments(&message()).unwrap();
+
+        fragments.pop();
+
+        assert!(!public_key
+            .verify_from_fragments(&fragments, &message())
+            .unwrap_or(false));
+    }
+}
@@ -9,18 +9,24 @@
 // an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and limitations under the License.
 
+mod ascii;
 mod bundle;
-mod constants;
+pub mod constants;
+mod fragments;
 mod transaction;
 
+pub use ascii::{ascii_to_trytes, trytes_to_ascii, AsciiError};
 pub use bundle::{
-    Bundle, IncomingBundleBuilder, IncomingBundleBuilderError, OutgoingBundleBuilder, OutgoingBundleBuilderError,
+    validate_bundle, Bundle, IncomingBundleBuilder, IncomingBundleBuilderError, OutgoingBundleBuilder,
+    OutgoingBundleBuilderError,
 };
 pub use constants::{
-    ADDRESS_TRIT_LEN, HASH_TRIT_LEN, NONCE_TRIT_LEN, PAYLOAD_TRIT_LEN, TAG_TRIT_LEN, TRANSACTION_BYTE_LEN,
-    TRANSACTION_TRIT_LEN, TRANSACTION_TRYT_LEN,
+    ADDRESS_CHECKSUM_TRIT_LEN, ADDRESS_TRIT_LEN, HASH_TRIT_LEN, NONCE_TRIT_LEN, PAYLOAD_TRIT_LEN, TAG_TRIT_LEN,
+    TRANSACTION_BYTE_LEN, TRANSACTION_TRIT_LEN, TRANSACTION_TRYT_LEN,
 };
+pub use fragments::{SignToFragments, VerifyFromFragments};
 pub use transaction::{
-    Address, BundledTransaction, BundledTransactionBuilder, BundledTransactionBuilders, BundledTransactionError,
-    BundledTransactionField, BundledTransactions, Index, Nonce, Payload, Tag, Timestamp, Value,
+    gossip_bytes_to_trits, Address, BundledTransaction, BundledTransactionBuilder, BundledTransactionBuilders,
+    BundledTransactionError, BundledTransactionField, BundledTransactions, GossipError, Index, Nonce, Payload, Tag,
+    Timestamp, Value,
 };
@@ -18,7 +18,7 @@ use crate::bundled::{
 use bee_crypto::ternary::Hash;
 use bee_ternary::{Btrit, T1B1Buf, TritBuf};
 
-#[derive(Default)]
+#[derive(Clone, Default)]
 pub struct BundledTransactionBuilder {
     pub(crate) payload: Option<Payload>,
     pub(crate) address: Option<Address>,
@@ -154,26 +154,59 @@ impl BundledTransactionBuilder {
         self
     }
 
-    pub fn build(self) -> Result<BundledTransaction, BundledTransactionError> {
-        let value = self
-            .value
-            .as_ref()
-            .ok_or(BundledTransactionError::MissingField("value"))?
-            .0;
-        let address = self.address.ok_or(BundledTransactionError::MissingField("address"))?;
-
-        if value.abs() > IOTA_SUPPLY {
-            return Err(BundledTransactionError::InvalidValue(value));
+    // Rules any transaction satisfies, including historical ones being decoded.
+    fn check_fields(&self) -> Result<(), BundledTransactionError> {
+        if let Some(value) = self.value.as_ref().map(|value| value.0) {
+            if value.abs() > IOTA_SUPPLY {
+                return Err(BundledTransactionError::InvalidValue(value));
+            }
+
+            if let Some(address) = self.address.as_ref() {
+                if value != 0 && address.to_inner().get(ADDRESS.trit_offset.length - 1).unwrap() != Btrit::Zero {
+                    return Err(BundledTransactionError::InvalidAddress);
+                }
+            }
         }
 
-        if value != 0 && address.to_inner().get(ADDRESS.trit_offset.length - 1).unwrap() != Btrit::Zero {
-            return Err(BundledTransactionError::InvalidAddress);
+        if let (Some(index), Some(last_index)) = (self.index.as_ref(), self.last_index.as_ref()) {
+            if index.0 > last_index.0 {
+                return Err(BundledTransactionError::InvalidIndex(index.0, last_index.0));
+            }
         }
 
+        Ok(())
+    }
+
+    /// Checks the network rules on the fields that are set, the missing ones being reported by `build`.
+    pub fn validate(&self) -> Result<(), BundledTransactionError> {
+        self.check_fields()?;
+
+        // Only the genesis, approving the null hash twice, is allowed to have no timestamp.
+        if let (Some(Timestamp(0)), Some(trunk), Some(branch)) =
+            (self.timestamp.as_ref(), self.trunk.as_ref(), self.branch.as_ref())
+        {
+            if *trunk != Hash::zeros() || *branch != Hash::zeros() {
+                return Err(BundledTransactionError::ZeroTimestamp);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Builds the transaction, checking the value, address and indexes. The timestamp is left to `validate` so that
+    /// historical transactions can still be decoded.
+    pub fn build(self) -> Result<BundledTransaction, BundledTransactionError> {
+        self.check_fields()?;
+        self.build_unchecked()
+    }
+
+    /// Builds the transaction without checking the network rules, which is only meant for tests needing malformed
+    /// transactions. Missing fields are still reported.
+    pub fn build_unchecked(self) -> Result<BundledTransaction, BundledTransactionError> {
         Ok(BundledTransaction {
             payload: self.payload.ok_or(BundledTransactionError::MissingField("payload"))?,
-            address,
-            value: Value(value),
+            address: self.address.ok_or(BundledTransactionError::MissingField("address"))?,
+            value: self.value.ok_or(BundledTransactionError::MissingField("value"))?,
             obsolete_tag: self
                 .obsolete_tag
                 .ok_or(BundledTransactionError::MissingField("obsolete_tag"))?,
@@ -292,4 +325,120 @@ mod tests {
         assert_eq!(tx.attachment_ubts, tx2.attachment_ubts);
         assert_eq!(tx.nonce, tx2.nonce);
     }
+
+    fn valid_builder() -> BundledTransactionBuilder {
+        BundledTransactionBuilder::new()
+            .with_payload(Payload::zeros())
+            .with_address(Address::zeros())
+            .with_value(Value(0))
+            .with_obsolete_tag(Tag::zeros())
+            .with_timestamp(Timestamp(1))
+            .with_index(Index(0))
+            .with_last_index(Index(0))
+            .with_tag(Tag::zeros())
+            .with_attachment_ts(Timestamp(0))
+            .with_bundle(Hash::zeros())
+            .with_trunk(Hash::zeros())
+            .with_branch(Hash::zeros())
+            .with_attachment_lbts(Timestamp(0))
+            .with_attachment_ubts(Timestamp(0))
+            .with_nonce(Nonce::zeros())
+    }
+
+    fn non_zero_hash() -> Hash {
+        let mut trits = TritBuf::<T1B1Buf>::zeros(Hash::trit_len());
+        trits.set(0, Btrit::PlusOne);
+        Hash::from_inner_unchecked(trits)
+    }
+
+    #[test]
+    fn value_above_supply() {
+        match valid_builder().with_value(Value(IOTA_SUPPLY + 1)).build() {
+            Err(BundledTransactionError::InvalidValue(value)) => assert_eq!(value, IOTA_SUPPLY + 1),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn value_below_negative_supply() {
+        match valid_builder().with_value(Value(-IOTA_SUPPLY - 1)).build() {
+            Err(BundledTransactionError::InvalidValue(value)) => assert_eq!(value, -IOTA_SUPPLY - 1),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn value_on_address_with_non_zero_last_trit() {
+        let mut address = TritBuf::<T1B1Buf>::zeros(ADDRESS.trit_offset.length);
+        address.set(ADDRESS.trit_offset.length - 1, Btrit::PlusOne);
+
+        match valid_builder()
+            .with_address(Address(address))
+            .with_value(Value(1))
+            .build()
+        {
+            Err(BundledTransactionError::InvalidAddress) => (),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn index_above_last_index() {
+        match valid_builder().with_index(Index(2)).with_last_index(Index(1)).build() {
+            Err(BundledTransactionError::InvalidIndex(2, 1)) => (),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn zero_timestamp_on_non_genesis() {
+        match valid_builder()
+            .with_timestamp(Timestamp(0))
+            .with_trunk(non_zero_hash())
+            .validate()
+        {
+            Err(BundledTransactionError::ZeroTimestamp) => (),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn zero_timestamp_on_genesis() {
+        assert!(valid_builder().with_timestamp(Timestamp(0)).validate().is_ok());
+    }
+
+    #[test]
+    fn zero_timestamp_built() {
+        assert!(valid_builder()
+            .with_timestamp(Timestamp(0))
+            .with_trunk(non_zero_hash())
+            .build()
+            .is_ok());
+    }
+
+    #[test]
+    fn missing_field() {
+        let mut builder = valid_builder();
+        builder.nonce = None;
+
+        match builder.build() {
+            Err(BundledTransactionError::MissingField("nonce")) => (),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn unchecked_bypasses_rules() {
+        let transaction = valid_builder()
+            .with_value(Value(IOTA_SUPPLY + 1))
+            .with_index(Index(2))
+            .with_last_index(Index(1))
+            .with_timestamp(Timestamp(0))
+            .with_trunk(non_zero_hash())
+            .build_unchecked()
+            .unwrap();
+
+        assert_eq!(transaction.value, Value(IOTA_SUPPLY + 1));
+        assert_eq!(transaction.index, Index(2));
+    }
 }
@@ -10,16 +10,23 @@
 // See the License for the specific language governing permissions and limitations under the License.
 
 use crate::bundled::constants::{
-    ADDRESS, ADDRESS_TRIT_LEN, NONCE, NONCE_TRIT_LEN, PAYLOAD, PAYLOAD_TRIT_LEN, TAG, TAG_TRIT_LEN,
+    ADDRESS, ADDRESS_CHECKSUM_TRIT_LEN, ADDRESS_TRIT_LEN, NONCE, NONCE_TRIT_LEN, PAYLOAD, PAYLOAD_TRIT_LEN, TAG,
+    TAG_TRIT_LEN,
 };
 
-use bee_crypto::ternary::Hash;
+use bee_crypto::ternary::{
+    sponge::{Kerl, Sponge},
+    Hash,
+};
 use bee_ternary::{T1B1Buf, TritBuf, Trits, T1B1};
 
+use thiserror::Error;
+
 use std::cmp::PartialEq;
 
-#[derive(Debug)]
+#[derive(Debug, Error)]
 pub enum BundledTransactionFieldError {
+    #[error("field has a wrong length")]
     FieldWrongLength,
 }
 
@@ -91,6 +98,14 @@ impl Address {
     pub fn trit_len() -> usize {
         ADDRESS_TRIT_LEN
     }
+
+    /// Computes the checksum of the address, made of the last 9 trytes of its Kerl hash.
+    pub fn checksum(&self) -> TritBuf<T1B1Buf> {
+        // Safe to unwrap since the input has a valid address length.
+        let hash = Kerl::default().digest(&self.0).unwrap();
+
+        hash[ADDRESS_TRIT_LEN - ADDRESS_CHECKSUM_TRIT_LEN..].to_buf()
+    }
 }
 
 impl Eq for Address {}
@@ -0,0 +1,261 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+//! Conversions between bundled transactions and the compressed T5B1 byte format used for gossip.
+//!
+//! A transaction is encoded as `TRANSACTION_BYTE_LEN` T5B1 bytes. Since the payload comes first and is often mostly
+//! empty, its trailing zero bytes are stripped before the transaction is sent, leaving between `NON_PAYLOAD_BYTE_LEN`
+//! and `TRANSACTION_BYTE_LEN` bytes on the wire.
+
+use crate::bundled::{
+    constants::{NON_PAYLOAD_BYTE_LEN, PAYLOAD_BYTE_LEN, TRANSACTION_BYTE_LEN, TRANSACTION_TRIT_LEN},
+    BundledTransaction, BundledTransactionError,
+};
+
+use bee_crypto::ternary::{
+    sponge::{CurlP81, Sponge},
+    Hash,
+};
+use bee_ternary::{T1B1Buf, T5B1Buf, TritBuf, Trits, T5B1};
+
+use bytemuck::cast_slice;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum GossipError {
+    #[error("invalid gossip length: {0} bytes")]
+    InvalidLength(usize),
+    #[error("bytes don't encode valid trits")]
+    InvalidTrits,
+    #[error("invalid transaction: {0}")]
+    InvalidTransaction(#[from] BundledTransactionError),
+}
+
+fn compress(bytes: &[u8]) -> Vec<u8> {
+    let payload_len = bytes[..PAYLOAD_BYTE_LEN]
+        .iter()
+        .rposition(|byte| *byte != 0)
+        .map_or(0, |position| position + 1);
+    let mut compressed = Vec::with_capacity(payload_len + NON_PAYLOAD_BYTE_LEN);
+
+    compressed.extend_from_slice(&bytes[..payload_len]);
+    compressed.extend_from_slice(&bytes[PAYLOAD_BYTE_LEN..]);
+
+    compressed
+}
+
+fn uncompress(bytes: &[u8]) -> Result<[u8; TRANSACTION_BYTE_LEN], GossipError> {
+    if bytes.len() < NON_PAYLOAD_BYTE_LEN || bytes.len() > TRANSACTION_BYTE_LEN {
+        return Err(GossipError::InvalidLength(bytes.len()));
+    }
+
+    let mut uncompressed = [0u8; TRANSACTION_BYTE_LEN];
+    let payload_len = bytes.len() - NON_PAYLOAD_BYTE_LEN;
+
+    uncompressed[..payload_len].copy_from_slice(&bytes[..payload_len]);
+    uncompressed[PAYLOAD_BYTE_LEN..].copy_from_slice(&bytes[payload_len..]);
+
+    Ok(uncompressed)
+}
+
+/// Uncompresses gossip bytes into the T5B1 trits of a transaction, without parsing its fields.
+pub fn gossip_bytes_to_trits(bytes: &[u8]) -> Result<TritBuf<T5B1Buf>, GossipError> {
+    let uncompressed = uncompress(bytes)?;
+
+    Trits::<T5B1>::try_from_raw(cast_slice(&uncompressed), TRANSACTION_TRIT_LEN)
+        .map(|trits| trits.to_buf::<T5B1Buf>())
+        .map_err(|_| GossipError::InvalidTrits)
+}
+
+impl BundledTransaction {
+    /// Encodes the transaction into compressed gossip bytes.
+    pub fn to_gossip_bytes(&self) -> Vec<u8> {
+        let mut trits = TritBuf::<T1B1Buf>::zeros(TRANSACTION_TRIT_LEN);
+
+        self.as_trits_allocated(&mut trits);
+
+        compress(cast_slice(trits.encode::<T5B1Buf>().as_i8_slice()))
+    }
+
+    /// Decodes a transaction from compressed gossip bytes.
+    pub fn from_gossip_bytes(bytes: &[u8]) -> Result<Self, GossipError> {
+        let trits = gossip_bytes_to_trits(bytes)?.encode::<T1B1Buf>();
+
+        Ok(Self::from_trits(&trits)?)
+    }
+
+    /// Decodes a transaction from compressed gossip bytes and computes its hash.
+    pub fn from_gossip_bytes_with_hash(bytes: &[u8]) -> Result<(Self, Hash), GossipError> {
+        let trits = gossip_bytes_to_trits(bytes)?.encode::<T1B1Buf>();
+        let transaction = Self::from_trits(&trits)?;
+        // Safe to unwrap since the input has a valid transaction length.
+        let hash = Hash::from_inner_unchecked(CurlP81::default().digest(&trits).unwrap());
+
+        Ok((transaction, hash))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::bundled::{
+        constants::{ADDRESS, HASH_TRIT_LEN, NONCE, PAYLOAD, TAG},
+        Address, BundledTransactionBuilder, BundledTransactionField, Index, Nonce, Payload, Tag, Timestamp, Value,
+    };
+
+    use bee_ternary::Btrit;
+
+    use rand::Rng;
+
+    const TRANSACTION_BYTES: [u8; TRANSACTION_BYTE_LEN] = [
+        65, 96, 90, 85, 12, 26, 72, 235, 46, 55, 188, 26, 78, 102, 37, 58, 8, 38, 115, 187, 46, 82, 85, 36, 70, 253,
+        194, 151, 4, 26, 111, 105, 158, 151, 179, 24, 78, 87, 158, 178, 94, 40, 39, 96, 184, 28, 83, 6, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 117, 167, 169, 239, 254, 234, 173, 147, 183, 30, 140, 14, 143, 141, 104, 237, 150, 224, 112, 4, 200, 217,
+        87, 9, 233, 144, 12, 106, 219, 114, 37, 76, 242, 209, 107, 83, 19, 221, 202, 239, 175, 41, 63, 117, 63, 222,
+        111, 240, 238, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 25, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        181, 225, 207, 89, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 139, 47, 84, 189, 223, 199, 11, 22, 223, 65, 86, 76,
+        203, 211, 19, 70, 202, 211, 161, 177, 146, 90, 159, 90, 35, 42, 85, 135, 81, 22, 203, 79, 138, 171, 157, 109,
+        29, 12, 223, 137, 17, 254, 51, 24, 9, 218, 39, 78, 29, 67, 31, 255, 246, 114, 65, 114, 191, 50, 14, 171, 210,
+        192, 214, 174, 147, 195, 50, 207, 185, 16, 139, 110, 160, 144, 38, 66, 241, 10, 86, 32, 164, 163, 253, 239, 92,
+        140, 221, 49, 95, 152, 136, 62, 29, 69, 239, 0, 0, 148, 120, 241, 193, 227, 165, 47, 252, 252, 239, 174, 41,
+        188, 214, 142, 9, 50, 48, 240, 229, 236, 165, 121, 222, 80, 197, 159, 88, 39, 15, 217, 209, 52, 70, 81, 244,
+        187, 153, 213, 59, 37, 150, 214, 5, 24, 168, 4, 0, 0, 172, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 117,
+        144, 115, 65, 221, 17, 0, 0, 0, 0, 0, 120, 121, 121, 121, 121, 242, 253, 0, 0, 0, 157, 0, 0, 0, 0, 117, 213,
+        141, 197, 121, 121, 40,
+    ];
+
+    fn random_trits(len: usize) -> TritBuf<T1B1Buf> {
+        let mut rng = rand::thread_rng();
+        let mut trits = TritBuf::<T1B1Buf>::zeros(len);
+
+        for i in 0..len {
+            trits.set(i, [Btrit::NegOne, Btrit::Zero, Btrit::PlusOne][rng.gen_range(0, 3)]);
+        }
+
+        trits
+    }
+
+    fn random_transaction() -> BundledTransaction {
+        let mut rng = rand::thread_rng();
+
+        // Only fill a prefix of the payload so that compression has trailing zeros to strip.
+        let mut payload = TritBuf::<T1B1Buf>::zeros(PAYLOAD.trit_offset.length);
+        let payload_len = rng.gen_range(0, PAYLOAD.trit_offset.length + 1);
+        payload[..payload_len].copy_from(&random_trits(payload_len));
+
+        // A value transaction requires the last trit of the address to be zero.
+        let mut address = random_trits(ADDRESS.trit_offset.length);
+        address.set(ADDRESS.trit_offset.length - 1, Btrit::Zero);
+
+        let last_index = rng.gen_range(0, 10);
+
+        BundledTransactionBuilder::new()
+            .with_payload(Payload::from_inner_unchecked(payload))
+            .with_address(Address::from_inner_unchecked(address))
+            .with_value(Value::from_inner_unchecked(rng.gen_range(-1_000_000, 1_000_000)))
+            .with_obsolete_tag(Tag::from_inner_unchecked(random_trits(TAG.trit_offset.length)))
+            .with_timestamp(Timestamp::from_inner_unchecked(rng.gen_range(1, u32::MAX as u64)))
+            .with_index(Index::from_inner_unchecked(rng.gen_range(0, last_index + 1)))
+            .with_last_index(Index::from_inner_unchecked(last_index))
+            .with_tag(Tag::from_inner_unchecked(random_trits(TAG.trit_offset.length)))
+            .with_attachment_ts(Timestamp::from_inner_unchecked(rng.gen_range(1, u32::MAX as u64)))
+            .with_bundle(Hash::from_inner_unchecked(random_trits(HASH_TRIT_LEN)))
+            .with_trunk(Hash::from_inner_unchecked(random_trits(HASH_TRIT_LEN)))
+            .with_branch(Hash::from_inner_unchecked(random_trits(HASH_TRIT_LEN)))
+            .with_attachment_lbts(Timestamp::from_inner_unchecked(rng.gen_range(1, u32::MAX as u64)))
+            .with_attachment_ubts(Timestamp::from_inner_unchecked(rng.gen_range(1, u32::MAX as u64)))
+            .with_nonce(Nonce::from_inner_unchecked(random_trits(NONCE.trit_offset.length)))
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn fixture_roundtrip() {
+        let compressed = compress(&TRANSACTION_BYTES);
+
+        assert_eq!(compressed.len(), 340);
+        assert!(TRANSACTION_BYTES.eq(&uncompress(&compressed).unwrap()));
+
+        let (transaction, hash) = BundledTransaction::from_gossip_bytes_with_hash(&compressed).unwrap();
+
+        assert_eq!(transaction.hash(), hash);
+        assert_eq!(transaction.to_gossip_bytes(), compressed);
+    }
+
+    #[test]
+    fn random_roundtrip() {
+        for _ in 0..100 {
+            let transaction = random_transaction();
+            let bytes = transaction.to_gossip_bytes();
+            let (decoded, hash) = BundledTransaction::from_gossip_bytes_with_hash(&bytes).unwrap();
+
+            assert_eq!(decoded, transaction);
+            assert_eq!(hash, transaction.hash());
+            assert_eq!(decoded.to_gossip_bytes(), bytes);
+        }
+    }
+
+    #[test]
+    fn invalid_length() {
+        match BundledTransaction::from_gossip_bytes(&[0u8; NON_PAYLOAD_BYTE_LEN - 1]) {
+            Err(GossipError::InvalidLength(len)) => assert_eq!(len, NON_PAYLOAD_BYTE_LEN - 1),
+            _ => panic!("should fail"),
+        }
+        match BundledTransaction::from_gossip_bytes(&[0u8; TRANSACTION_BYTE_LEN + 1]) {
+            Err(GossipError::InvalidLength(len)) => assert_eq!(len, TRANSACTION_BYTE_LEN + 1),
+            _ => panic!("should fail"),
+        }
+    }
+
+    #[test]
+    fn invalid_trits() {
+        // 127 is outside of the range of values a T5B1 byte can take.
+        match BundledTransaction::from_gossip_bytes(&[127u8; NON_PAYLOAD_BYTE_LEN]) {
+            Err(GossipError::InvalidTrits) => (),
+            _ => panic!("should fail"),
+        }
+    }
+}
@@ -11,8 +11,11 @@
 
 mod builder;
 mod fields;
+mod gossip;
+mod persistable;
 mod transaction;
 
 pub use builder::{BundledTransactionBuilder, BundledTransactionBuilders};
 pub use fields::{Address, BundledTransactionField, Index, Nonce, Payload, Tag, Timestamp, Value};
+pub use gossip::{gossip_bytes_to_trits, GossipError};
 pub use transaction::{BundledTransaction, BundledTransactionError, BundledTransactions};
@@ -0,0 +1,93 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use crate::bundled::{
+    constants::{ADDRESS_BYTE_LEN, ADDRESS_TRIT_LEN, TRANSACTION_BYTE_LEN, TRANSACTION_TRIT_LEN},
+    Address, BundledTransaction, BundledTransactionField,
+};
+
+use bee_storage::persistable::{take, DecodeError, Persistable};
+use bee_ternary::{T1B1Buf, T5B1Buf, TritBuf, Trits, T5B1};
+
+use bytemuck::cast_slice;
+
+// An address is encoded as its 49 T5B1 bytes, like a `Hash`.
+impl Persistable for Address {
+    fn encode_persistable(&self, buffer: &mut Vec<u8>) {
+        buffer.extend(cast_slice(self.to_inner().encode::<T5B1Buf>().as_i8_slice()));
+    }
+    fn decode_persistable_prefix(slice: &[u8]) -> Result<(Self, usize), DecodeError> {
+        let trits = Trits::<T5B1>::try_from_raw(cast_slice(take(slice, ADDRESS_BYTE_LEN)?), ADDRESS_TRIT_LEN)
+            .map_err(|_| DecodeError::InvalidValue)?;
+        let address = Address::try_from_inner(trits.encode::<T1B1Buf>()).map_err(|_| DecodeError::InvalidValue)?;
+        Ok((address, ADDRESS_BYTE_LEN))
+    }
+}
+
+// A transaction is encoded as the 1604 T5B1 bytes of its trits.
+impl Persistable for BundledTransaction {
+    fn encode_persistable(&self, buffer: &mut Vec<u8>) {
+        let mut trits = TritBuf::<T1B1Buf>::zeros(TRANSACTION_TRIT_LEN);
+        self.as_trits_allocated(&mut trits);
+        buffer.extend(cast_slice(trits.encode::<T5B1Buf>().as_i8_slice()));
+    }
+    fn decode_persistable_prefix(slice: &[u8]) -> Result<(Self, usize), DecodeError> {
+        let trits = Trits::<T5B1>::try_from_raw(cast_slice(take(slice, TRANSACTION_BYTE_LEN)?), TRANSACTION_TRIT_LEN)
+            .map_err(|_| DecodeError::InvalidValue)?;
+        let transaction = BundledTransaction::from_trits(trits).map_err(|_| DecodeError::InvalidValue)?;
+        Ok((transaction, TRANSACTION_BYTE_LEN))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use bee_ternary::TryteBuf;
+
+    #[test]
+    fn address_golden_bytes() {
+        let address = Address::from_inner_unchecked(
+            TryteBuf::try_from_str(&format!("{:9<81}", "A"))
+                .unwrap()
+                .as_trits()
+                .encode::<T1B1Buf>(),
+        );
+        let mut bytes = [0u8; ADDRESS_BYTE_LEN];
+        bytes[0] = 1;
+
+        let mut buffer = Vec::new();
+        address.encode_persistable(&mut buffer);
+
+        assert_eq!(buffer, bytes);
+        assert_eq!(Address::decode_persistable(&bytes).unwrap(), address);
+    }
+
+    #[test]
+    fn transaction_round_trip() {
+        let transaction = BundledTransaction::from_trits(&TritBuf::<T1B1Buf>::zeros(TRANSACTION_TRIT_LEN)).unwrap();
+
+        let mut buffer = Vec::new();
+        transaction.encode_persistable(&mut buffer);
+
+        assert_eq!(buffer.len(), TRANSACTION_BYTE_LEN);
+        assert_eq!(BundledTransaction::decode_persistable(&buffer).unwrap(), transaction);
+    }
+
+    #[test]
+    fn transaction_truncated() {
+        match BundledTransaction::decode_persistable(&[0; TRANSACTION_BYTE_LEN - 1]) {
+            Err(DecodeError::UnexpectedEnd { .. }) => (),
+            _ => panic!("decoding a truncated transaction should fail"),
+        }
+    }
+}
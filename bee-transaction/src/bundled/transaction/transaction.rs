@@ -20,19 +20,37 @@ use crate::{
     Vertex,
 };
 
-use bee_crypto::ternary::Hash;
+use bee_crypto::ternary::{
+    sponge::{CurlP81, Sponge},
+    Hash,
+};
 use bee_ternary::{convert::Error as ConvertError, raw::RawEncoding, Btrit, T1B1Buf, TritBuf, Trits, T1B1};
 
+use thiserror::Error;
+
 use core::convert::TryFrom;
 
-#[derive(Debug)]
+#[derive(Debug, Error)]
 pub enum BundledTransactionError {
+    #[error("invalid numeric field {0}: {1:?}")]
     InvalidNumericField(&'static str, ConvertError),
+    #[error("missing field {0}")]
     MissingField(&'static str),
+    #[error("value {0} is out of the supply range")]
     InvalidValue(i64),
+    #[error("address of a value transaction has a non-zero last trit")]
     InvalidAddress,
+    #[error("index {0} is above the last index {1}")]
+    InvalidIndex(usize, usize),
+    #[error("timestamp of a non-genesis transaction is zero")]
+    ZeroTimestamp,
 }
 
+/// A transaction that belongs to a bundle.
+///
+/// Equality is derived and compares every field. Since the hash of a transaction covers all of its trits, this is
+/// equivalent to comparing hashes but avoids running the sponge; use `eq_by_hash` when hash semantics are explicitly
+/// wanted.
 #[derive(PartialEq, Clone, Debug)]
 pub struct BundledTransaction {
     pub(crate) payload: Payload,
@@ -243,6 +261,21 @@ impl BundledTransaction {
         TRANSACTION_TRIT_LEN
     }
 
+    /// Computes the CurlP81 hash of the transaction.
+    pub fn hash(&self) -> Hash {
+        let mut trits = TritBuf::<T1B1Buf>::zeros(TRANSACTION_TRIT_LEN);
+
+        self.as_trits_allocated(&mut trits);
+
+        // Safe to unwrap since the input has a valid transaction length.
+        Hash::from_inner_unchecked(CurlP81::default().digest(&trits).unwrap())
+    }
+
+    /// Compares two transactions by their hashes.
+    pub fn eq_by_hash(&self, other: &Self) -> bool {
+        self.hash() == other.hash()
+    }
+
     pub fn essence(&self) -> TritBuf {
         let mut essence = TritBuf::<T1B1Buf>::zeros(ESSENCE_TRIT_LEN);
 
@@ -318,3 +351,56 @@ impl BundledTransactions {
         self.0.push(transaction);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transaction(nonce: Nonce) -> BundledTransaction {
+        BundledTransactionBuilder::new()
+            .with_payload(Payload::zeros())
+            .with_address(Address::zeros())
+            .with_value(Value(0))
+            .with_obsolete_tag(Tag::zeros())
+            .with_timestamp(Timestamp(0))
+            .with_index(Index(0))
+            .with_last_index(Index(0))
+            .with_tag(Tag::zeros())
+            .with_attachment_ts(Timestamp(0))
+            .with_bundle(Hash::zeros())
+            .with_trunk(Hash::zeros())
+            .with_branch(Hash::zeros())
+            .with_attachment_lbts(Timestamp(0))
+            .with_attachment_ubts(Timestamp(0))
+            .with_nonce(nonce)
+            .build()
+            .unwrap()
+    }
+
+    fn non_zero_nonce() -> Nonce {
+        let mut trits = TritBuf::<T1B1Buf>::zeros(NONCE.trit_offset.length);
+        trits.set(0, Btrit::PlusOne);
+
+        Nonce::from_inner_unchecked(trits)
+    }
+
+    #[test]
+    fn identical_transactions_eq_by_hash() {
+        let a = transaction(Nonce::zeros());
+        let b = transaction(Nonce::zeros());
+
+        assert_eq!(a.hash(), b.hash());
+        assert!(a.eq_by_hash(&b));
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn different_transactions_not_eq_by_hash() {
+        let a = transaction(Nonce::zeros());
+        let b = transaction(non_zero_nonce());
+
+        assert_ne!(a.hash(), b.hash());
+        assert!(!a.eq_by_hash(&b));
+        assert_ne!(a, b);
+    }
+}